@@ -0,0 +1,75 @@
+//! Image dithering, bit packing and raster encoding.
+//!
+//! ```bash
+//! cargo bench --bench image --features image
+//! ```
+
+use std::hint::black_box;
+
+use bixolon::command::image::PrintRasterImage;
+use bixolon::command::image::raster::{self, Dither, FilterType, Threshold};
+use bixolon::command::{Command, Dialect};
+use bixolon::profile::PrinterProfile;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use image::{DynamicImage, GrayImage, Luma};
+
+/// A diagonal gradient, so dithering has gray levels to work on.
+fn gradient(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+        Luma([((x + y) * 255 / (width + height)) as u8])
+    }))
+}
+
+fn dithering(c: &mut Criterion) {
+    let image = gradient(512, 256);
+    let mut group = c.benchmark_group("dither");
+    group.throughput(Throughput::Elements(u64::from(image.width() * image.height())));
+    for (name, dither) in
+        [("threshold", Dither::Threshold(Threshold::default())), ("bayer", Dither::Bayer)]
+    {
+        group.bench_function(BenchmarkId::new("pack", name), |b| {
+            b.iter(|| black_box(raster::from_image(&image, 512, dither).unwrap()));
+        });
+    }
+    group.bench_function("fit_to_profile", |b| {
+        let profile = PrinterProfile::default();
+        b.iter(|| {
+            black_box(
+                raster::fit_to_profile(&image, &profile, FilterType::Triangle, Dither::Bayer)
+                    .unwrap(),
+            )
+        });
+    });
+    group.finish();
+}
+
+fn encoding(c: &mut Criterion) {
+    let image = PrintRasterImage::new(64, 512, (0..64 * 512).map(|i| i as u8).collect());
+    let mut group = c.benchmark_group("raster");
+    group.throughput(Throughput::Bytes(image.data.len() as u64));
+    for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+        group.bench_function(BenchmarkId::new("encode_into_for", format!("{dialect:?}")), |b| {
+            let mut output = Vec::new();
+            b.iter(|| {
+                output.clear();
+                image.encode_into_for(dialect, &mut output);
+                black_box(&output);
+            });
+        });
+    }
+    group.bench_function("write_bands_to", |b| {
+        let mut output = Vec::new();
+        b.iter(|| {
+            output.clear();
+            image.write_bands_to(image.band_height(), Dialect::Bixolon, &mut output).unwrap();
+            black_box(&output);
+        });
+    });
+    group.bench_function("rotate_90", |b| {
+        b.iter(|| black_box(image.rotate_90(image.width_bytes * 8)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dithering, encoding);
+criterion_main!(benches);
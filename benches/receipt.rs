@@ -0,0 +1,66 @@
+//! Full receipt encoding, from JSON and from a compiled template.
+//!
+//! ```bash
+//! cargo bench --bench receipt --features json
+//! ```
+
+use std::hint::black_box;
+
+use bixolon::profile::PrinterProfile;
+use bixolon::receipt::Receipt;
+use bixolon::receipt::template::CompiledTemplate;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// A typical café receipt with a dozen items, totals and a barcode.
+fn receipt_json() -> String {
+    let items: Vec<String> = (0..12)
+        .map(|i| {
+            format!(
+                r#"{{"type": "Item", "qty": {}, "description": ["Menu item number {i}"], "unit_price": {}}}"#,
+                i % 3 + 1,
+                350 + i * 25
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"sections": [
+            {{"type": "Text", "align": "Center", "spans": [
+                {{"text": "ACME Coffee", "bold": true, "width_scale": "X2"}}
+            ]}},
+            {{"type": "Text", "align": "Center", "spans": ["Order {{order}} for {{guest}}"]}},
+            {{"type": "Rule"}},
+            {items},
+            {{"type": "Rule", "style": "Double"}},
+            {{"type": "Totals", "subtotal": 6150, "lines": [
+                {{"label": ["Tax"], "amount": 507}}
+            ], "double_width": true}},
+            {{"type": "Barcode", "system": "Code39", "data": "1042", "hri": "Below"}},
+            {{"type": "Cut", "partial": true}}
+        ]}}"#,
+        items = items.join(",\n")
+    )
+}
+
+fn receipts(c: &mut Criterion) {
+    let json = receipt_json();
+    let profile = PrinterProfile::default();
+    let receipt = Receipt::from_json(&json).unwrap();
+    let template = CompiledTemplate::new(&receipt, &profile).unwrap();
+    let values = [("order", "1042"), ("guest", "Ada")];
+
+    let mut group = c.benchmark_group("receipt");
+    group.bench_function("from_json", |b| b.iter(|| black_box(Receipt::from_json(&json).unwrap())));
+    group.bench_function("render", |b| b.iter(|| black_box(receipt.render(&profile).unwrap())));
+    group.bench_function("template_render_into", |b| {
+        let mut output = Vec::new();
+        b.iter(|| {
+            output.clear();
+            template.render_into(&values, &mut output).unwrap();
+            black_box(&output);
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, receipts);
+criterion_main!(benches);
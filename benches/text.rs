@@ -0,0 +1,104 @@
+//! Styled text rendering and column layout.
+//!
+//! ```bash
+//! cargo bench --bench text
+//! ```
+
+use std::hint::black_box;
+
+use bixolon::money::{Currency, Locale, Money};
+use bixolon::profile::PrinterProfile;
+use bixolon::style::layout::{ItemLine, LineBuilder, TotalsBlock};
+use bixolon::style::text::{StyleRenderer, Styleable, StyledNode};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// A receipt body mixing plain and styled runs.
+fn styled_lines() -> Vec<StyledNode> {
+    (0..40)
+        .map(|i| {
+            "Item "
+                .into_node()
+                .append(format!("{i:02}").bold())
+                .append(StyledNode::text(" with some plain text "))
+                .append("and underlined".underlined())
+        })
+        .collect()
+}
+
+fn rendering(c: &mut Criterion) {
+    let lines = styled_lines();
+    let mut group = c.benchmark_group("render");
+    group.bench_function("render_line", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(line.render_line());
+            }
+        });
+    });
+    group.bench_function("render_line_into", |b| {
+        let mut output = Vec::new();
+        b.iter(|| {
+            output.clear();
+            for line in &lines {
+                line.render_line_into(&mut output);
+            }
+            black_box(&output);
+        });
+    });
+    group.bench_function("style_renderer", |b| {
+        let mut output = Vec::new();
+        b.iter(|| {
+            let mut renderer = StyleRenderer::new();
+            output.clear();
+            for line in &lines {
+                renderer.render_line_into(line, &mut output);
+            }
+            black_box(&output);
+        });
+    });
+    group.finish();
+}
+
+fn layout(c: &mut Criterion) {
+    let profile = PrinterProfile::default();
+    let locale = Locale::default();
+    let money = |minor| Money::new(minor, Currency::default());
+    let mut group = c.benchmark_group("layout");
+    group.bench_function("line_builder", |b| {
+        b.iter(|| {
+            for i in 0..40 {
+                let line = LineBuilder::new()
+                    .left(format!("Flat white {i}"))
+                    .amount(&money(450 + i), &locale)
+                    .fill('.')
+                    .build(&profile);
+                black_box(line);
+            }
+        });
+    });
+    group.bench_function("item_line", |b| {
+        b.iter(|| {
+            for i in 0..40 {
+                let item = ItemLine::new(
+                    2,
+                    "Sourdough loaf with a description long enough to wrap",
+                    money(625 + i),
+                );
+                black_box(item.build(&profile, &locale));
+            }
+        });
+    });
+    group.bench_function("totals_block", |b| {
+        b.iter(|| {
+            let totals = TotalsBlock::new(money(12_345))
+                .tax("Tax 8.25%", money(1_018))
+                .discount("Loyalty", money(-500))
+                .double_width_total(true);
+            black_box(totals.build(&profile, &locale));
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, rendering, layout);
+criterion_main!(benches);
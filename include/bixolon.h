@@ -0,0 +1,71 @@
+/*
+ * C interface to the bixolon crate, built with the `ffi` feature:
+ *
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * Every function taking a printer accepts NULL and reports
+ * BIXOLON_NULL_POINTER. After a failure, bixolon_last_error() describes it
+ * until the next failing call on the same thread.
+ */
+
+#ifndef BIXOLON_H
+#define BIXOLON_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum BixolonResult {
+    BIXOLON_OK = 0,
+    BIXOLON_NULL_POINTER = 1,
+    BIXOLON_INVALID_UTF8 = 2,
+    BIXOLON_IO = 3,
+    BIXOLON_INVALID = 4,
+    BIXOLON_NO_RESPONSE = 5,
+    BIXOLON_PANIC = 6,
+} BixolonResult;
+
+typedef struct BixolonStatus {
+    bool online;
+    bool drawer_open;
+    bool cover_open;
+    bool paper_near_end;
+    bool paper_out;
+    bool cutter_error;
+    bool recoverable_error;
+    bool unrecoverable_error;
+} BixolonStatus;
+
+typedef struct BixolonPrinter BixolonPrinter;
+
+/* Open a device path such as "/dev/usb/lp0" or "COM3". NULL on failure. */
+BixolonPrinter *bixolon_printer_open(const char *path);
+
+#ifndef _WIN32
+/* Take ownership of an open descriptor. NULL on failure, leaving fd open. */
+BixolonPrinter *bixolon_printer_from_fd(int fd);
+#endif
+
+/* Close the printer. NULL is ignored. */
+void bixolon_printer_free(BixolonPrinter *printer);
+
+/* Print UTF-8 text; '\n' ends lines. */
+BixolonResult bixolon_print_text(BixolonPrinter *printer, const char *text);
+
+/* Print a receipt in the crate's JSON receipt format. */
+BixolonResult bixolon_print_receipt_json(BixolonPrinter *printer, const char *json);
+
+/* Query the printer's state into *status. Returns BIXOLON_NO_RESPONSE if
+ * the printer doesn't answer in time. */
+BixolonResult bixolon_query_status(BixolonPrinter *printer, BixolonStatus *status);
+
+/* Description of the last failure on this thread, or NULL. */
+const char *bixolon_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BIXOLON_H */
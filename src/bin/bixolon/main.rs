@@ -0,0 +1,312 @@
+//! Command line tool for commissioning and testing receipt printers.
+
+mod markdown;
+mod target;
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::ExitCode;
+
+use bixolon::command::printer_control::{DrawerPin, GeneratePulse, PrintTestPage, TestPattern};
+use bixolon::command::status::{StatusResponse, StatusType, TransmitStatus};
+use bixolon::encoding::Fallback;
+use bixolon::profile::PrinterProfile;
+use bixolon::receipt::{Receipt, Section, Span};
+use miette::{IntoDiagnostic, miette};
+
+use crate::target::{Connection, DEFAULT_PORT, Target};
+
+const HELP: &str = "\
+Print to and inspect Bixolon receipt printers.
+
+Usage: bixolon [--printer TARGET] [--profile FILE] COMMAND
+
+Commands:
+  discover [--scan CIDR] [--port PORT]
+      List printers on USB and local device files, and with --scan those
+      answering on PORT (default 9100) in an IPv4 network such as
+      192.168.1.0/24
+  print [--format FORMAT] [--no-cut] FILE
+      Print FILE, or standard input for -. FORMAT is text, markdown, json
+      or raw, guessed from the file extension if not given. Text and
+      markdown end with a partial cut unless --no-cut is given
+  test-page [--pattern status|hex|rolling]
+      Print the printer's self-test page
+  drawer [--pin 2|5]
+      Open the cash drawer
+  status
+      Show whether the printer is online, its cover and paper state
+
+Targets:
+  usb, usb:VID:PID      USB printer (needs the rusb feature)
+  tcp://HOST[:PORT]     Network printer; an IP address or HOST:PORT also works
+  PATH                  Device file such as /dev/usb/lp0 or /dev/ttyUSB0
+
+Without --printer, $BIXOLON_PRINTER is used, then the first printer found
+by discover. Configure serial ports (e.g. with stty) before printing.
+
+--profile loads the printer's capabilities from a TOML or JSON file (by
+extension) instead of assuming an SRP-350plus.
+";
+
+fn main() -> ExitCode {
+    match run(pico_args::Arguments::from_env()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: pico_args::Arguments) -> miette::Result<()> {
+    if args.contains(["-h", "--help"]) {
+        print!("{HELP}");
+        return Ok(());
+    }
+    let printer: Option<Target> = args.opt_value_from_str(["-p", "--printer"]).into_diagnostic()?;
+    let profile: Option<String> = args.opt_value_from_str("--profile").into_diagnostic()?;
+    let profile = profile.as_deref().map(load_profile).transpose()?.unwrap_or_default();
+    let Some(command) = args.subcommand().into_diagnostic()? else {
+        print!("{HELP}");
+        return Ok(());
+    };
+
+    match command.as_str() {
+        "discover" => {
+            let scan: Option<String> = args.opt_value_from_str("--scan").into_diagnostic()?;
+            let port = args.opt_value_from_str("--port").into_diagnostic()?.unwrap_or(DEFAULT_PORT);
+            finish(args)?;
+            let mut targets = target::discover_local()?;
+            if let Some(cidr) = scan {
+                targets.extend(target::discover_network(&cidr, port)?);
+            }
+            for target in targets {
+                println!("{target}");
+            }
+            Ok(())
+        }
+        "print" => {
+            let format: Option<Format> = args.opt_value_from_str("--format").into_diagnostic()?;
+            let cut = !args.contains("--no-cut");
+            let file: String = args.free_from_str().into_diagnostic()?;
+            finish(args)?;
+            let format = format.unwrap_or_else(|| Format::guess(&file));
+            let data = if file == "-" {
+                let mut data = Vec::new();
+                io::stdin().read_to_end(&mut data).into_diagnostic()?;
+                data
+            } else {
+                fs::read(&file).into_diagnostic()?
+            };
+            let mut connection = connect(printer, &profile)?;
+            print(&mut connection, format, &data, cut)?;
+            connection.flush().into_diagnostic()?;
+            Ok(())
+        }
+        "test-page" => {
+            let pattern =
+                match args.opt_value_from_str::<_, String>("--pattern").into_diagnostic()? {
+                    None => TestPattern::StatusSheet,
+                    Some(pattern) => match pattern.as_str() {
+                        "status" => TestPattern::StatusSheet,
+                        "hex" => TestPattern::HexDump,
+                        "rolling" => TestPattern::RollingPattern,
+                        _ => return Err(miette!("unknown test pattern {pattern:?}")),
+                    },
+                };
+            finish(args)?;
+            let mut connection = connect(printer, &profile)?;
+            connection.send(PrintTestPage(pattern)).into_diagnostic()?;
+            connection.flush().into_diagnostic()?;
+            Ok(())
+        }
+        "drawer" => {
+            let pin = match args.opt_value_from_str::<_, u8>("--pin").into_diagnostic()? {
+                None | Some(2) => DrawerPin::Pin2,
+                Some(5) => DrawerPin::Pin5,
+                Some(pin) => return Err(miette!("drawer pin must be 2 or 5, not {pin}")),
+            };
+            finish(args)?;
+            let mut connection = connect(printer, &profile)?;
+            connection.send(GeneratePulse::new(pin, 200, 200)).into_diagnostic()?;
+            connection.flush().into_diagnostic()?;
+            Ok(())
+        }
+        "status" => {
+            finish(args)?;
+            status(&mut connect(printer, &profile)?)
+        }
+        _ => Err(miette!("unknown command {command:?}; see --help")),
+    }
+}
+
+/// Reject arguments no command used.
+fn finish(args: pico_args::Arguments) -> miette::Result<()> {
+    let rest: Vec<OsString> = args.finish();
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(miette!("unexpected arguments: {rest:?}"))
+    }
+}
+
+fn load_profile(file: &str) -> miette::Result<PrinterProfile> {
+    let text = fs::read_to_string(file).into_diagnostic()?;
+    let profile = match Format::guess(file) {
+        Format::Json => PrinterProfile::from_json(&text),
+        _ => PrinterProfile::from_toml(&text),
+    };
+    profile.map_err(|err| miette!("{file}: {err}"))
+}
+
+fn connect(printer: Option<Target>, profile: &PrinterProfile) -> miette::Result<Connection> {
+    let target = match printer {
+        Some(target) => target,
+        None => match std::env::var("BIXOLON_PRINTER") {
+            Ok(target) => target.parse().map_err(|err| miette!("BIXOLON_PRINTER: {err}"))?,
+            Err(_) => target::discover_local()?
+                .into_iter()
+                .next()
+                .ok_or_else(|| miette!("no printer found; pass --printer"))?,
+        },
+    };
+    Ok(target::connect(&target)?
+        .with_profile(profile.clone())
+        .with_encoding_fallback(Fallback::Transliterate))
+}
+
+/// Input file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Markdown,
+    Json,
+    Raw,
+}
+
+impl Format {
+    fn guess(file: &str) -> Self {
+        let extension = Path::new(file).extension().and_then(|ext| ext.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("md" | "markdown") => Format::Markdown,
+            Some("json") => Format::Json,
+            Some("bin" | "prn" | "escpos") => Format::Raw,
+            _ => Format::Text,
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "markdown" | "md" => Ok(Format::Markdown),
+            "json" => Ok(Format::Json),
+            "raw" => Ok(Format::Raw),
+            _ => Err(format!("unknown format {s:?}")),
+        }
+    }
+}
+
+fn print(
+    connection: &mut Connection,
+    format: Format,
+    data: &[u8],
+    cut: bool,
+) -> miette::Result<()> {
+    let mut receipt = match format {
+        Format::Raw => {
+            connection.send_raw(data).into_diagnostic()?;
+            return Ok(());
+        }
+        Format::Json => {
+            let json = std::str::from_utf8(data).into_diagnostic()?;
+            return print_receipt(connection, &Receipt::from_json(json).into_diagnostic()?);
+        }
+        Format::Text => {
+            let text = String::from_utf8_lossy(data);
+            let sections = text.lines().map(|line| Section::Text {
+                spans: vec![Span::Plain(line.to_string())],
+                align: None,
+            });
+            Receipt {
+                sections: sections.collect(),
+                ..Default::default()
+            }
+        }
+        Format::Markdown => markdown::parse(&String::from_utf8_lossy(data)),
+    };
+    if cut {
+        receipt.sections.push(Section::Cut {
+            partial: true,
+            feed: 3,
+        });
+    }
+    print_receipt(connection, &receipt)
+}
+
+/// Print `receipt`, loading images from files.
+fn print_receipt(connection: &mut Connection, receipt: &Receipt) -> miette::Result<()> {
+    let load = |url: &str| match url.strip_prefix("file://") {
+        Some(path) => fs::read(path),
+        None if !url.contains("://") => fs::read(url),
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "only local files are loaded; download the image first",
+        )),
+    };
+
+    receipt.print_with_loader(connection, load).into_diagnostic()
+}
+
+fn status(connection: &mut Connection) -> miette::Result<()> {
+    let yes_no = |value: bool| {
+        if value {
+            "yes"
+        } else {
+            "no"
+        }
+    };
+    for kind in [StatusType::Printer, StatusType::Offline, StatusType::Error, StatusType::PaperRoll]
+    {
+        match connection.query(TransmitStatus(kind)).into_diagnostic()? {
+            StatusResponse::Printer(status) => {
+                println!("online:              {}", yes_no(status.online));
+                println!("drawer open:         {}", yes_no(status.drawer_open));
+            }
+            StatusResponse::Offline(status) => {
+                println!("cover open:          {}", yes_no(status.cover_open));
+                println!("feeding paper:       {}", yes_no(status.paper_feeding));
+            }
+            StatusResponse::Error(status) => {
+                println!("recoverable error:   {}", yes_no(status.recoverable_error));
+                println!("cutter error:        {}", yes_no(status.cutter_error));
+                println!("unrecoverable error: {}", yes_no(status.unrecoverable_error));
+            }
+            StatusResponse::PaperRoll(status) => {
+                println!("paper near end:      {}", yes_no(status.paper_near_end));
+                println!("paper out:           {}", yes_no(status.paper_end));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_formats_from_extensions() {
+        assert_eq!(Format::guess("menu.MD"), Format::Markdown);
+        assert_eq!(Format::guess("order.json"), Format::Json);
+        assert_eq!(Format::guess("capture.bin"), Format::Raw);
+        assert_eq!(Format::guess("notes.txt"), Format::Text);
+        assert_eq!(Format::guess("-"), Format::Text);
+    }
+}
@@ -0,0 +1,246 @@
+//! Markdown subset to receipt conversion.
+//!
+//! Headings, paragraphs, `-`/`*` list items and thematic breaks are
+//! understood, with `**bold**` and `*underlined*` inline text (the printer
+//! has no italics). Underscores inside words, as in `snake_case`, print as
+//! written.
+
+use bixolon::command::character::{Justification, ScaleFactor};
+use bixolon::receipt::{Receipt, Section, Span};
+use bixolon::style::StyleSet;
+
+/// Convert `markdown` to a receipt.
+pub fn parse(markdown: &str) -> Receipt {
+    let mut sections = Vec::new();
+    let mut paragraph = String::new();
+    for line in markdown.lines() {
+        let line = line.trim();
+        let ends_paragraph = line.is_empty()
+            || is_rule(line)
+            || heading(line).is_some()
+            || list_item(line).is_some();
+        if ends_paragraph && !paragraph.is_empty() {
+            sections.push(text(spans(&paragraph), None));
+            paragraph.clear();
+        }
+
+        if line.is_empty() {
+            // One blank line between blocks, however many separate them
+            if sections.last().is_some_and(|last| *last != blank()) {
+                sections.push(blank());
+            }
+        } else if is_rule(line) {
+            sections.push(Section::Rule {
+                style: Default::default(),
+            });
+        } else if let Some((level, title)) = heading(line) {
+            let style = match level {
+                1 => StyleSet::new()
+                    .with_bold(true)
+                    .with_width_scale(ScaleFactor::X2)
+                    .with_height_scale(ScaleFactor::X2),
+                2 => StyleSet::new().with_bold(true).with_height_scale(ScaleFactor::X2),
+                _ => StyleSet::new().with_bold(true),
+            };
+            let align = (level == 1).then_some(Justification::Center);
+            let spans = spans(title).into_iter().map(|span| restyle(span, &style)).collect();
+            sections.push(text(spans, align));
+        } else if let Some(item) = list_item(line) {
+            let mut spans = spans(item);
+            spans.insert(0, Span::Plain("- ".into()));
+            sections.push(text(spans, None));
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line);
+        }
+    }
+    if !paragraph.is_empty() {
+        sections.push(text(spans(&paragraph), None));
+    }
+    if sections.last() == Some(&blank()) {
+        sections.pop();
+    }
+    Receipt {
+        sections,
+        ..Default::default()
+    }
+}
+
+fn text(spans: Vec<Span>, align: Option<Justification>) -> Section {
+    Section::Text {
+        spans,
+        align,
+    }
+}
+
+fn blank() -> Section {
+    text(Vec::new(), None)
+}
+
+/// A line of three or more `-`, `*` or `_`, optionally spaced.
+fn is_rule(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    let Some(first) = marks.next() else {
+        return false;
+    };
+    matches!(first, '-' | '*' | '_') && marks.clone().all(|c| c == first) && marks.count() >= 2
+}
+
+/// Level and text of an ATX heading.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level, title.trim_end_matches(['#', ' '])))
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).or_else(|| line.strip_prefix("+ "))
+}
+
+/// Inline text, with `**`/`__` toggling bold and `*`/`_` toggling underline.
+fn spans(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let (mut bold, mut underline) = (false, false);
+    let mut previous = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let mut len = match c {
+            '*' | '_' if rest[1..].starts_with(c) => 2,
+            '*' | '_' => 1,
+            _ => 0,
+        };
+        let next = rest[len..].chars().next();
+        let intraword = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+        if c == '_' && intraword(previous) && intraword(next) {
+            len = 0;
+        }
+        if len == 0 {
+            current.push(c);
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        push_span(&mut spans, &mut current, bold, underline);
+        if len == 2 {
+            bold = !bold;
+        } else {
+            underline = !underline;
+        }
+        previous = Some(c);
+        rest = &rest[len..];
+    }
+    push_span(&mut spans, &mut current, bold, underline);
+    spans
+}
+
+fn push_span(spans: &mut Vec<Span>, text: &mut String, bold: bool, underline: bool) {
+    if text.is_empty() {
+        return;
+    }
+    let text = std::mem::take(text);
+    if !bold && !underline {
+        spans.push(Span::Plain(text));
+        return;
+    }
+    let mut style = StyleSet::new();
+    if bold {
+        style = style.with_bold(true);
+    }
+    if underline {
+        style = style.with_underline(true);
+    }
+    spans.push(Span::Styled {
+        text,
+        style,
+    });
+}
+
+/// `span` with `style` underneath its own.
+fn restyle(span: Span, style: &StyleSet) -> Span {
+    match span {
+        Span::Plain(text) => Span::Styled {
+            text,
+            style: style.clone(),
+        },
+        Span::Styled {
+            text,
+            style: own,
+        } => Span::Styled {
+            text,
+            style: StyleSet::from_stack(&[style.clone(), own]),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &str) -> Span {
+        Span::Plain(text.into())
+    }
+
+    fn styled(text: &str, style: StyleSet) -> Span {
+        Span::Styled {
+            text: text.into(),
+            style,
+        }
+    }
+
+    #[test]
+    fn parses_inline_emphasis() {
+        let bold = StyleSet::new().with_bold(true);
+        let underline = StyleSet::new().with_underline(true);
+        assert_eq!(
+            spans("a **b** *c* __d__ snake_case"),
+            [
+                plain("a "),
+                styled("b", bold.clone()),
+                plain(" "),
+                styled("c", underline),
+                plain(" "),
+                styled("d", bold),
+                plain(" snake_case"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_blocks() {
+        let receipt = parse("# Café\n\n\nThank you\nfor visiting\n\n---\n- one\n## Total ##\n");
+        let h1 = StyleSet::new()
+            .with_bold(true)
+            .with_width_scale(ScaleFactor::X2)
+            .with_height_scale(ScaleFactor::X2);
+        let h2 = StyleSet::new().with_bold(true).with_height_scale(ScaleFactor::X2);
+        assert_eq!(
+            receipt.sections,
+            [
+                text(vec![styled("Café", h1)], Some(Justification::Center)),
+                blank(),
+                text(vec![plain("Thank you for visiting")], None),
+                blank(),
+                Section::Rule {
+                    style: Default::default()
+                },
+                text(vec![plain("- "), plain("one")], None),
+                text(vec![styled("Total", h2)], None),
+            ]
+        );
+    }
+
+    #[test]
+    fn recognises_rules_and_headings() {
+        assert!(is_rule("***"));
+        assert!(is_rule("- - -"));
+        assert!(!is_rule("--"));
+        assert!(!is_rule("-*-"));
+        assert_eq!(heading("### Hi"), Some((3, "Hi")));
+        assert_eq!(heading("#hashtag"), None);
+        assert_eq!(heading("####### deep"), None);
+    }
+}
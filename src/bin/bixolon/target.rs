@@ -0,0 +1,235 @@
+//! Printer addresses, connections and discovery.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use bixolon::printer::Printer;
+use miette::{Context, IntoDiagnostic, miette};
+
+/// Raw TCP port used by network receipt printers.
+pub const DEFAULT_PORT: u16 = 9100;
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A printer reached over any transport.
+pub type Connection = Printer<Box<dyn Write>, Box<dyn Read>>;
+
+/// Where to find a printer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// `usb`, or `usb:VID:PID` in hex.
+    Usb(Option<(u16, u16)>),
+    /// `tcp://host[:port]`, `host:port` or an IP address.
+    Tcp(String),
+    /// A device file, such as `/dev/usb/lp0` or a serial port.
+    Device(PathBuf),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "usb" {
+            return Ok(Target::Usb(None));
+        }
+        if let Some(ids) = s.strip_prefix("usb:") {
+            let (vendor, product) = ids.split_once(':').ok_or("expected usb:VID:PID")?;
+            let hex =
+                |id| u16::from_str_radix(id, 16).map_err(|_| format!("invalid USB ID {id:?}"));
+            return Ok(Target::Usb(Some((hex(vendor)?, hex(product)?))));
+        }
+        if let Some(address) = s.strip_prefix("tcp://") {
+            return Ok(Target::Tcp(with_port(address)));
+        }
+        if s.parse::<IpAddr>().is_ok() || (!s.contains(['/', '\\']) && s.contains(':')) {
+            return Ok(Target::Tcp(with_port(s)));
+        }
+        Ok(Target::Device(s.into()))
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Usb(None) => write!(f, "usb"),
+            Target::Usb(Some((vendor, product))) => write!(f, "usb:{vendor:04x}:{product:04x}"),
+            Target::Tcp(address) => write!(f, "tcp://{address}"),
+            Target::Device(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// `address` with the default port added if it has none.
+fn with_port(address: &str) -> String {
+    match address.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, DEFAULT_PORT).to_string(),
+        Err(_) if address.contains(':') => address.to_string(),
+        Err(_) => format!("{address}:{DEFAULT_PORT}"),
+    }
+}
+
+/// Open a connection to `target`.
+pub fn connect(target: &Target) -> miette::Result<Connection> {
+    match target {
+        Target::Usb(ids) => connect_usb(*ids),
+        Target::Tcp(address) => {
+            let addr = address
+                .to_socket_addrs()
+                .into_diagnostic()
+                .wrap_err_with(|| format!("cannot resolve {address}"))?
+                .next()
+                .ok_or_else(|| miette!("{address} has no addresses"))?;
+            let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("cannot connect to {address}"))?;
+            stream.set_read_timeout(Some(TIMEOUT)).into_diagnostic()?;
+            let reader = stream.try_clone().into_diagnostic()?;
+            Ok(Printer::with_reader(Box::new(stream), Box::new(reader)))
+        }
+        Target::Device(path) => {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("cannot open {}", path.display()))?;
+            let reader = file.try_clone().into_diagnostic()?;
+            Ok(Printer::with_reader(Box::new(file), Box::new(reader)))
+        }
+    }
+}
+
+#[cfg(feature = "rusb")]
+fn connect_usb(ids: Option<(u16, u16)>) -> miette::Result<Connection> {
+    use bixolon::transport::usb::UsbPrinter;
+
+    let device = match ids {
+        Some((vendor, product)) => UsbPrinter::find_by_ids(vendor, product),
+        None => UsbPrinter::find_bixolon(),
+    }
+    .into_diagnostic()?;
+    let (reader, writer) = device.open().and_then(UsbPrinter::split).into_diagnostic()?;
+    Ok(Printer::with_reader(Box::new(writer), Box::new(reader)))
+}
+
+#[cfg(not(feature = "rusb"))]
+fn connect_usb(_ids: Option<(u16, u16)>) -> miette::Result<Connection> {
+    Err(miette!("USB printers need the `rusb` feature; use the device file instead"))
+}
+
+/// Printers found on USB and as local device files.
+pub fn discover_local() -> miette::Result<Vec<Target>> {
+    let mut targets = Vec::new();
+    #[cfg(feature = "rusb")]
+    {
+        use bixolon::transport::usb::UsbPrinter;
+
+        for device in UsbPrinter::list_printers().into_diagnostic()? {
+            targets.push(Target::Usb(Some((device.vendor_id(), device.product_id()))));
+        }
+    }
+
+    let candidates = [("/dev/usb", "lp"), ("/dev", "ttyUSB"), ("/dev", "ttyACM")];
+    for (dir, prefix) in candidates {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+            .map(|entry| entry.path())
+            .collect();
+        paths.sort();
+        targets.extend(paths.into_iter().map(Target::Device));
+    }
+    Ok(targets)
+}
+
+/// Hosts in the IPv4 network `cidr` accepting connections on `port`.
+pub fn discover_network(cidr: &str, port: u16) -> miette::Result<Vec<Target>> {
+    let hosts = hosts(cidr).map_err(|err| miette!("invalid network {cidr:?}: {err}"))?;
+    let mut found = Vec::new();
+    // Probe a batch at a time so a /16 doesn't open 65k sockets at once
+    for batch in hosts.chunks(256) {
+        let open: Vec<Ipv4Addr> = thread::scope(|scope| {
+            let probes: Vec<_> = batch
+                .iter()
+                .map(|&ip| {
+                    scope.spawn(move || {
+                        let addr = SocketAddr::new(ip.into(), port);
+                        TcpStream::connect_timeout(&addr, Duration::from_millis(300))
+                            .is_ok()
+                            .then_some(ip)
+                    })
+                })
+                .collect();
+            probes.into_iter().filter_map(|probe| probe.join().ok().flatten()).collect()
+        });
+        found.extend(
+            open.into_iter().map(|ip| Target::Tcp(SocketAddr::new(ip.into(), port).to_string())),
+        );
+    }
+    Ok(found)
+}
+
+/// Host addresses of an IPv4 network of at least a /16.
+fn hosts(cidr: &str) -> Result<Vec<Ipv4Addr>, String> {
+    let (network, prefix) = cidr.split_once('/').ok_or("expected ADDRESS/PREFIX")?;
+    let network: Ipv4Addr = network.parse().map_err(|_| "invalid address")?;
+    let prefix: u32 = prefix.parse().map_err(|_| "invalid prefix")?;
+    if !(16..=32).contains(&prefix) {
+        return Err("prefix must be 16-32".into());
+    }
+    let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+    let first = u32::from(network) & mask;
+    let last = first | !mask;
+    // Skip the network and broadcast addresses where there are any
+    let range = if prefix >= 31 {
+        first..=last
+    } else {
+        first + 1..=last - 1
+    };
+    Ok(range.map(Ipv4Addr::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_targets() {
+        let parse = |s: &str| s.parse::<Target>().unwrap();
+        assert_eq!(parse("usb"), Target::Usb(None));
+        assert_eq!(parse("usb:1504:0006"), Target::Usb(Some((0x1504, 0x0006))));
+        assert_eq!(parse("tcp://printer"), Target::Tcp("printer:9100".into()));
+        assert_eq!(parse("10.0.0.7"), Target::Tcp("10.0.0.7:9100".into()));
+        assert_eq!(parse("printer.local:9101"), Target::Tcp("printer.local:9101".into()));
+        assert_eq!(parse("/dev/usb/lp0"), Target::Device("/dev/usb/lp0".into()));
+        assert_eq!(parse("COM3"), Target::Device("COM3".into()));
+        assert!("usb:zz:1".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn targets_display_as_parsed() {
+        for s in ["usb", "usb:1504:0006", "tcp://10.0.0.7:9100", "/dev/ttyUSB0"] {
+            assert_eq!(s.parse::<Target>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn lists_network_hosts() {
+        let network = hosts("192.168.1.77/24").unwrap();
+        assert_eq!(network.len(), 254);
+        assert_eq!(network[0], Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(network[253], Ipv4Addr::new(192, 168, 1, 254));
+        assert_eq!(hosts("10.0.0.5/32").unwrap(), [Ipv4Addr::new(10, 0, 0, 5)]);
+        assert!(hosts("10.0.0.0/8").is_err());
+        assert!(hosts("10.0.0.0").is_err());
+    }
+}
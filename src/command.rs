@@ -1,19 +1,51 @@
 //! ESC/POS command definitions.
 //!
 //! All commands implement the [`Command`] trait for encoding to bytes.
+//!
+//! Commands encode as Bixolon ESC/POS by default. A few, such as cuts,
+//! drawer pulses and raster images, encode differently for other
+//! manufacturers' command sets; see [`Dialect`].
+
+use alloc::vec::Vec;
+
+// Commands with fixed bytes encode from a static slice. Defined before the
+// submodules so they can use it.
+macro_rules! impl_static_command {
+    ($name:ty => $($byte:expr),+) => {
+        impl $crate::command::StaticCommand for $name {
+            const BYTES: &'static [u8] = &[$($byte),+];
+        }
+
+        impl $crate::command::Command for $name {
+            fn encode(&self) -> alloc::vec::Vec<u8> {
+                <Self as $crate::command::StaticCommand>::BYTES.to_vec()
+            }
+
+            fn encode_into(&self, output: &mut alloc::vec::Vec<u8>) {
+                output.extend_from_slice(<Self as $crate::command::StaticCommand>::BYTES);
+            }
+        }
+    };
+}
 
 pub mod barcode;
 pub mod basic;
 pub mod character;
 pub mod codepage;
+pub mod display;
+pub mod graphics;
+pub mod gs1;
 pub mod image;
+pub mod kanji;
 pub mod macro_cmd;
 pub mod page_mode;
 pub mod paper;
 pub mod printer_control;
+pub mod rule;
 pub mod spacing;
 pub mod status;
 pub mod symbol;
+pub mod user_defined;
 
 /// Escape character - starts most ESC/POS commands.
 pub const ESC: u8 = 0x1B;
@@ -45,14 +77,143 @@ pub const HT: u8 = 0x09;
 /// Cancel.
 pub const CAN: u8 = 0x18;
 
+/// Unit Separator - starts customer display commands.
+pub const US: u8 = 0x1F;
+
+/// Command set spoken by a printer.
+///
+/// Most commands are identical across ESC/POS printers and ignore the
+/// dialect. Those that differ are translated by
+/// [`Command::encode_for`], which the printer calls with its profile's
+/// dialect, so one application can drive a mixed fleet:
+///
+/// | Command | Bixolon | Epson | Star |
+/// |---------|---------|-------|------|
+/// | [`CutPaper`](paper::CutPaper) | `GS V` | `GS V` | `ESC d` |
+/// | [`GeneratePulse`](printer_control::GeneratePulse) | `ESC p` | `ESC p` | `ESC BEL` + `BEL`, or `SUB` |
+/// | [`PrintRasterImage`](image::PrintRasterImage) | `GS v 0` | `GS ( L` graphics | Star raster mode |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dialect {
+    /// Bixolon ESC/POS, as documented for the SRP series.
+    #[default]
+    Bixolon,
+    /// Epson TM series ESC/POS, which prefers `GS ( L` graphics over the
+    /// obsolete `GS v 0` raster command.
+    Epson,
+    /// Star Line Mode, for the commands in the table above only.
+    ///
+    /// Everything else, including text styles and barcodes, is still sent
+    /// as ESC/POS, which Star Line Mode printers don't all understand. For
+    /// full support, switch the printer to its ESC/POS emulation and use
+    /// [`Epson`](Self::Epson).
+    Star,
+}
+
 /// A command that can be sent to the printer.
 ///
 /// Commands serialize to byte sequences in ESC/POS format.
 pub trait Command {
     /// Encode this command to bytes.
     fn encode(&self) -> Vec<u8>;
+
+    /// Encode this command for a printer speaking `dialect`.
+    ///
+    /// Defaults to [`encode`](Self::encode), for commands every dialect
+    /// shares.
+    fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+        let _ = dialect;
+        self.encode()
+    }
+
+    /// Append this command's bytes to `output`.
+    ///
+    /// Lets callers reuse one buffer across many commands. Defaults to
+    /// extending `output` with [`encode`](Self::encode); commands sent
+    /// often override it to write without allocating.
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend(self.encode());
+    }
+
+    /// Append this command's bytes for `dialect` to `output`.
+    ///
+    /// Defaults to extending `output` with [`encode_for`](Self::encode_for),
+    /// so commands that only override `encode_for` still encode correctly
+    /// here. Commands that override `encode_into` to avoid allocating
+    /// should override this too.
+    fn encode_into_for(&self, dialect: Dialect, output: &mut Vec<u8>) {
+        output.extend(self.encode_for(dialect));
+    }
 }
 
+/// A command whose bytes never change, such as
+/// [`LineFeed`](basic::LineFeed) or [`Initialize`](printer_control::Initialize).
+///
+/// Hot loops can write [`BYTES`](Self::BYTES) directly instead of
+/// allocating with [`encode`](Command::encode):
+///
+/// ```
+/// use bixolon::command::StaticCommand;
+/// use bixolon::command::basic::LineFeed;
+///
+/// let mut output = Vec::new();
+/// for row in ["a", "b"] {
+///     output.extend_from_slice(row.as_bytes());
+///     output.extend_from_slice(LineFeed::BYTES);
+/// }
+/// assert_eq!(output, b"a\nb\n");
+/// ```
+pub trait StaticCommand: Command {
+    /// The command's bytes, identical in every dialect.
+    const BYTES: &'static [u8];
+
+    /// The command's bytes, for use through a value.
+    fn encoded(&self) -> &'static [u8] {
+        Self::BYTES
+    }
+}
+
+// Tuples of commands encode as each command in turn.
+macro_rules! impl_command_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Command),+> Command for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self) -> Vec<u8> {
+                let ($($name,)+) = self;
+                let mut bytes = Vec::new();
+                $(bytes.extend($name.encode());)+
+                bytes
+            }
+
+            #[allow(non_snake_case)]
+            fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+                let ($($name,)+) = self;
+                let mut bytes = Vec::new();
+                $(bytes.extend($name.encode_for(dialect));)+
+                bytes
+            }
+
+            #[allow(non_snake_case)]
+            fn encode_into(&self, output: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode_into(output);)+
+            }
+
+            #[allow(non_snake_case)]
+            fn encode_into_for(&self, dialect: Dialect, output: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode_into_for(dialect, output);)+
+            }
+        }
+    };
+}
+
+impl_command_for_tuple!(A, B);
+impl_command_for_tuple!(A, B, C);
+impl_command_for_tuple!(A, B, C, D);
+impl_command_for_tuple!(A, B, C, D, E);
+impl_command_for_tuple!(A, B, C, D, E, F);
+
 /// A command that expects a response from the printer.
 pub trait QueryCommand: Command {
     /// The type of response expected.
@@ -2,17 +2,63 @@
 //!
 //! All barcode types supported by the printer with validation.
 
+#[cfg(feature = "std")]
+mod render;
+
+#[cfg(feature = "std")]
+pub(crate) use render::barcode_fallback;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::gs1;
 use super::{Command, GS};
-use crate::error::BarcodeError;
-use miette::SourceSpan;
+use crate::error::SourceSpan;
+use crate::error::{BarcodeError, ValidationError};
+use crate::profile::PrinterProfile;
 
 /// Set barcode height in dots.
 ///
 /// ESC/POS: `GS h n` (0x1D 0x68 n)
 /// Default: 162 dots
+///
+/// The tuple field is sent as-is apart from clamping 0 to 1; use
+/// [`SetBarcodeHeight::new`] or [`SetBarcodeHeight::for_profile`] to reject
+/// out-of-range values instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetBarcodeHeight(pub u8);
 
+impl SetBarcodeHeight {
+    /// Create with validation (1-255 dots).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if `dots` is 0.
+    pub fn new(dots: u8) -> Result<Self, ValidationError> {
+        Self::for_profile(dots, &PrinterProfile::default())
+    }
+
+    /// Create with validation against the limits of `profile`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if `dots` is 0 or exceeds
+    /// the model's maximum barcode height.
+    pub fn for_profile(dots: u8, profile: &PrinterProfile) -> Result<Self, ValidationError> {
+        if dots == 0 || dots > profile.max_barcode_height {
+            return Err(ValidationError::OutOfRange {
+                name: "barcode height",
+                value: u16::from(dots),
+                min: 1,
+                max: u16::from(profile.max_barcode_height),
+            });
+        }
+        Ok(Self(dots))
+    }
+}
+
 impl Default for SetBarcodeHeight {
     fn default() -> Self {
         Self(162)
@@ -28,6 +74,7 @@ impl Command for SetBarcodeHeight {
 /// Barcode module width.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarcodeWidth {
     /// Thinnest module (0.282mm).
     Thin = 2,
@@ -42,12 +89,57 @@ pub enum BarcodeWidth {
     ExtraWide = 6,
 }
 
+impl BarcodeWidth {
+    /// Convert a module width in dots (2-6).
+    pub const fn from_dots(dots: u8) -> Option<Self> {
+        match dots {
+            2 => Some(BarcodeWidth::Thin),
+            3 => Some(BarcodeWidth::Normal),
+            4 => Some(BarcodeWidth::Medium),
+            5 => Some(BarcodeWidth::Wide),
+            6 => Some(BarcodeWidth::ExtraWide),
+            _ => None,
+        }
+    }
+}
+
 /// Set barcode module width.
 ///
 /// ESC/POS: `GS w n` (0x1D 0x77 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetBarcodeWidth(pub BarcodeWidth);
 
+impl SetBarcodeWidth {
+    /// Create from a module width in dots with validation (2-6).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if `dots` is not 2-6.
+    pub fn from_dots(dots: u8) -> Result<Self, ValidationError> {
+        Self::for_profile(dots, &PrinterProfile::default())
+    }
+
+    /// Create from a module width in dots, validated against `profile`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if `dots` is outside the
+    /// model's supported module widths.
+    pub fn for_profile(dots: u8, profile: &PrinterProfile) -> Result<Self, ValidationError> {
+        let min = profile.min_barcode_module_width;
+        let max = profile.max_barcode_module_width;
+        BarcodeWidth::from_dots(dots).filter(|_| (min..=max).contains(&dots)).map(Self).ok_or(
+            ValidationError::OutOfRange {
+                name: "barcode module width",
+                value: u16::from(dots),
+                min: u16::from(min.max(2)),
+                max: u16::from(max.min(6)),
+            },
+        )
+    }
+}
+
 impl Command for SetBarcodeWidth {
     fn encode(&self) -> Vec<u8> {
         vec![GS, b'w', self.0 as u8]
@@ -57,6 +149,7 @@ impl Command for SetBarcodeWidth {
 /// HRI (Human Readable Interpretation) character position.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HriPosition {
     /// HRI not printed.
     #[default]
@@ -73,6 +166,7 @@ pub enum HriPosition {
 ///
 /// ESC/POS: `GS H n` (0x1D 0x48 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetHriPosition(pub HriPosition);
 
 impl Command for SetHriPosition {
@@ -84,6 +178,7 @@ impl Command for SetHriPosition {
 /// HRI character font.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HriFont {
     /// Font A (12×24).
     #[default]
@@ -96,6 +191,7 @@ pub enum HriFont {
 ///
 /// ESC/POS: `GS f n` (0x1D 0x66 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetHriFont(pub HriFont);
 
 impl Command for SetHriFont {
@@ -107,6 +203,7 @@ impl Command for SetHriFont {
 /// Barcode symbology.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarcodeSystem {
     /// UPC-A - 11-12 digits, North American retail.
     UpcA = 65,
@@ -128,17 +225,53 @@ pub enum BarcodeSystem {
     Code128 = 73,
 }
 
+impl BarcodeSystem {
+    /// Human-readable symbology name used in errors.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            BarcodeSystem::UpcA => "UPC-A",
+            BarcodeSystem::UpcE => "UPC-E",
+            BarcodeSystem::Jan13 => "JAN-13",
+            BarcodeSystem::Jan8 => "JAN-8",
+            BarcodeSystem::Code39 => "CODE39",
+            BarcodeSystem::Itf => "ITF",
+            BarcodeSystem::Codabar => "CODABAR",
+            BarcodeSystem::Code93 => "CODE93",
+            BarcodeSystem::Code128 => "CODE128",
+        }
+    }
+
+    /// Number of data digits before the check digit, for symbologies
+    /// with a GS1 mod-10 check digit. ITF is treated as ITF-14.
+    const fn check_digit_position(self) -> Option<usize> {
+        match self {
+            BarcodeSystem::UpcA | BarcodeSystem::UpcE => Some(11),
+            BarcodeSystem::Jan13 => Some(12),
+            BarcodeSystem::Jan8 => Some(7),
+            BarcodeSystem::Itf => Some(13),
+            _ => None,
+        }
+    }
+}
+
 /// Print a barcode.
 ///
 /// ESC/POS: `GS k m n d1...dn` (0x1D 0x6B m n d1...dn)
 ///
 /// **Note:** Barcode configuration commands (height, width, HRI) must be
 /// sent BEFORE this command.
+///
+/// [`new`](Self::new) validates the data; call [`validate`](Self::validate)
+/// after changing the fields directly. The length is sent in a single
+/// byte, so data over 255 bytes is cut short when encoded rather than
+/// spilling into the following commands.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PrintBarcodeFields"))]
 pub struct PrintBarcode {
     /// Barcode symbology.
     pub system: BarcodeSystem,
-    /// Barcode data.
+    /// Barcode data, as sent to the printer.
     pub data: Vec<u8>,
 }
 
@@ -150,24 +283,58 @@ impl PrintBarcode {
     /// Returns [`BarcodeError`] if the data is invalid for the barcode system.
     pub fn new(system: BarcodeSystem, data: impl Into<Vec<u8>>) -> Result<Self, BarcodeError> {
         let data = data.into();
-        Self::validate(system, &data)?;
+        Self::check(system, &data)?;
         Ok(Self {
             system,
             data,
         })
     }
 
-    fn validate(system: BarcodeSystem, data: &[u8]) -> Result<(), BarcodeError> {
-        let (min_len, max_len, name): (usize, usize, &'static str) = match system {
-            BarcodeSystem::UpcA => (11, 12, "UPC-A"),
-            BarcodeSystem::UpcE => (11, 12, "UPC-E"),
-            BarcodeSystem::Jan13 => (12, 13, "JAN-13"),
-            BarcodeSystem::Jan8 => (7, 8, "JAN-8"),
-            BarcodeSystem::Code39 => (1, 255, "CODE39"),
-            BarcodeSystem::Itf => (2, 255, "ITF"),
-            BarcodeSystem::Codabar => (1, 255, "CODABAR"),
-            BarcodeSystem::Code93 => (1, 255, "CODE93"),
-            BarcodeSystem::Code128 => (2, 255, "CODE128"),
+    /// Barcode symbology.
+    pub fn system(&self) -> BarcodeSystem {
+        self.system
+    }
+
+    /// Barcode data, as sent to the printer.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Borrow the barcode as a [`PrintBarcodeRef`].
+    pub fn as_borrowed(&self) -> PrintBarcodeRef<'_> {
+        PrintBarcodeRef {
+            system: self.system,
+            data: &self.data,
+        }
+    }
+
+    /// Check the data against the barcode system, as [`new`](Self::new)
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError`] if the data is invalid for the barcode system.
+    pub fn validate(&self) -> Result<(), BarcodeError> {
+        Self::check(self.system, &self.data)
+    }
+
+    fn check(system: BarcodeSystem, data: &[u8]) -> Result<(), BarcodeError> {
+        let name = system.name();
+
+        // `GS k m n` carries the length in a single byte
+        if data.len() > usize::from(u8::MAX) {
+            return Err(BarcodeError::DataTooLong {
+                system: name,
+                actual: data.len(),
+                max: usize::from(u8::MAX),
+            });
+        }
+        let (min_len, max_len) = match system {
+            BarcodeSystem::UpcA | BarcodeSystem::UpcE => (11, 12),
+            BarcodeSystem::Jan13 => (12, 13),
+            BarcodeSystem::Jan8 => (7, 8),
+            BarcodeSystem::Itf | BarcodeSystem::Code128 => (2, 255),
+            BarcodeSystem::Code39 | BarcodeSystem::Codabar | BarcodeSystem::Code93 => (1, 255),
         };
 
         if data.len() < min_len || data.len() > max_len {
@@ -219,14 +386,426 @@ impl PrintBarcode {
 
         Ok(())
     }
+
+    /// Create a barcode, appending or verifying the mod-10 check digit.
+    ///
+    /// Supported for UPC-A, UPC-E (in its 11-digit UPC-A form), JAN-13,
+    /// JAN-8 and ITF-14. Data without a check digit has it computed and
+    /// appended; data that already includes one is verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError::CheckDigitMismatch`] if the supplied check
+    /// digit is wrong, [`BarcodeError::CheckDigitUnsupported`] for other
+    /// symbologies, or any error from [`PrintBarcode::new`].
+    pub fn with_check_digit(
+        system: BarcodeSystem,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<Self, BarcodeError> {
+        let mut data = data.into();
+        let name = system.name();
+        let body_len =
+            system.check_digit_position().ok_or(BarcodeError::CheckDigitUnsupported(name))?;
+
+        if data.len() != body_len && data.len() != body_len + 1 {
+            return Err(BarcodeError::InvalidLength {
+                system: name,
+                actual: data.len(),
+                min: body_len,
+                max: body_len + 1,
+            });
+        }
+
+        if let Some(position) = data.iter().position(|b| !b.is_ascii_digit()) {
+            return Err(BarcodeError::InvalidCharacter {
+                data: String::from_utf8_lossy(&data).into_owned(),
+                span: SourceSpan::from((position, 1)),
+                system: name,
+            });
+        }
+
+        let expected = gs1::check_digit(&data[..body_len]).unwrap_or(0);
+        match data.get(body_len) {
+            Some(&digit) if digit - b'0' != expected => {
+                return Err(BarcodeError::CheckDigitMismatch {
+                    system: name,
+                    expected,
+                    actual: digit - b'0',
+                });
+            }
+            Some(_) => {}
+            None => data.push(b'0' + expected),
+        }
+
+        Self::new(system, data)
+    }
+}
+
+/// Deserialized [`PrintBarcode`] fields, validated before use.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PrintBarcodeFields {
+    system: BarcodeSystem,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PrintBarcodeFields> for PrintBarcode {
+    type Error = BarcodeError;
+
+    fn try_from(fields: PrintBarcodeFields) -> Result<Self, Self::Error> {
+        Self::new(fields.system, fields.data)
+    }
 }
 
 impl Command for PrintBarcode {
     fn encode(&self) -> Vec<u8> {
-        let mut bytes = vec![GS, b'k', self.system as u8, self.data.len() as u8];
-        bytes.extend_from_slice(&self.data);
+        self.as_borrowed().encode()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        self.as_borrowed().encode_into(output);
+    }
+}
+
+/// Print a barcode whose data is borrowed.
+///
+/// Encodes like [`PrintBarcode`], but borrows the data instead of copying
+/// it into the command, for printing barcodes straight from a caller's
+/// buffer. The data is validated the same way on construction.
+///
+/// ```
+/// use bixolon::command::Command;
+/// use bixolon::command::barcode::{BarcodeSystem, PrintBarcode, PrintBarcodeRef};
+///
+/// let sku = b"4901234567894";
+/// let barcode = PrintBarcodeRef::new(BarcodeSystem::Jan13, sku).unwrap();
+/// assert_eq!(barcode.encode(), PrintBarcode::new(BarcodeSystem::Jan13, *sku).unwrap().encode());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintBarcodeRef<'a> {
+    system: BarcodeSystem,
+    data: &'a [u8],
+}
+
+impl<'a> PrintBarcodeRef<'a> {
+    /// Create a new barcode borrowing `data`, with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError`] if the data is invalid for the barcode system.
+    pub fn new(system: BarcodeSystem, data: &'a [u8]) -> Result<Self, BarcodeError> {
+        PrintBarcode::check(system, data)?;
+        Ok(Self {
+            system,
+            data,
+        })
+    }
+
+    /// Barcode symbology.
+    pub fn system(&self) -> BarcodeSystem {
+        self.system
+    }
+
+    /// Barcode data, as sent to the printer.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Copy the data into an owned [`PrintBarcode`].
+    pub fn into_owned(self) -> PrintBarcode {
+        PrintBarcode {
+            system: self.system,
+            data: self.data.to_vec(),
+        }
+    }
+}
+
+impl Command for PrintBarcodeRef<'_> {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + self.data.len());
+        self.encode_into(&mut bytes);
         bytes
     }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        // Only reachable through a `PrintBarcode` whose fields were changed
+        let data = &self.data[..self.data.len().min(usize::from(u8::MAX))];
+        output.extend_from_slice(&[GS, b'k', self.system as u8, data.len() as u8]);
+        output.extend_from_slice(data);
+    }
+}
+
+/// Code 128 code set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Code128Set {
+    /// Code A: uppercase, digits, punctuation and control characters.
+    A,
+    /// Code B: uppercase, lowercase, digits and punctuation.
+    B,
+    /// Code C: digit pairs.
+    C,
+}
+
+impl Code128Set {
+    /// All sets, in `index()` order.
+    const ALL: [Code128Set; 3] = [Code128Set::A, Code128Set::B, Code128Set::C];
+
+    /// Tie-break order: prefer readable Code B, then Code C.
+    const PREFERENCE: [Code128Set; 3] = [Code128Set::B, Code128Set::C, Code128Set::A];
+
+    const fn select_byte(self) -> u8 {
+        match self {
+            Code128Set::A => b'A',
+            Code128Set::B => b'B',
+            Code128Set::C => b'C',
+        }
+    }
+
+    const fn index(self) -> usize {
+        match self {
+            Code128Set::A => 0,
+            Code128Set::B => 1,
+            Code128Set::C => 2,
+        }
+    }
+
+    fn encodes(self, byte: u8) -> bool {
+        match self {
+            Code128Set::A => byte < 96,
+            Code128Set::B => (32..=127).contains(&byte),
+            Code128Set::C => false,
+        }
+    }
+}
+
+/// One logical Code 128 symbol character before code set selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Code128Item {
+    Char(u8),
+    Fnc1,
+}
+
+/// How a symbol character is emitted at one step of the encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Code128Step {
+    /// Emit one item in the current set.
+    Single,
+    /// Emit a digit pair in Code C.
+    Pair,
+    /// Emit one item via the `{S` shift.
+    Shift,
+}
+
+/// Builder for CODE128 barcode data.
+///
+/// Takes plain text and produces the `{A`/`{B`/`{C` prefixed data expected
+/// by [`PrintBarcode`], choosing code sets to minimize the symbol length.
+/// Digit runs are compressed with Code C, literal `{` is escaped as `{{`,
+/// and shifts are used for isolated characters from the other set.
+///
+/// In Code C each data byte is the binary value of a digit pair (0-99).
+///
+/// ```
+/// use bixolon::command::barcode::Code128Builder;
+///
+/// let barcode = Code128Builder::new().text("ABC123456").build().unwrap();
+/// assert_eq!(barcode.data(), b"{BABC{C\x0c\x22\x38");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Code128Builder {
+    items: Vec<Code128Item>,
+}
+
+impl Code128Builder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append plain text.
+    pub fn text(mut self, text: &str) -> Self {
+        self.items.extend(text.bytes().map(Code128Item::Char));
+        self
+    }
+
+    /// Append an FNC1 function character.
+    pub fn fnc1(mut self) -> Self {
+        self.items.push(Code128Item::Fnc1);
+        self
+    }
+
+    /// Encode into `GS k` CODE128 data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError::InvalidCharacter`] for non-ASCII input, or
+    /// [`BarcodeError::InvalidLength`] if the builder is empty.
+    pub fn encode_data(&self) -> Result<Vec<u8>, BarcodeError> {
+        if self.items.is_empty() {
+            return Err(BarcodeError::InvalidLength {
+                system: "CODE128",
+                actual: 0,
+                min: 1,
+                max: 253,
+            });
+        }
+        self.validate_chars()?;
+
+        let plan = self.plan();
+        let mut bytes = vec![b'{', plan.start.select_byte()];
+        let mut i = 0;
+
+        for (switch, step) in plan.steps {
+            if let Some(next) = switch {
+                bytes.extend_from_slice(&[b'{', next.select_byte()]);
+            }
+            match step {
+                Code128Step::Pair => {
+                    let (Code128Item::Char(hi), Code128Item::Char(lo)) =
+                        (self.items[i], self.items[i + 1])
+                    else {
+                        unreachable!("pair steps only cover digits");
+                    };
+                    bytes.push((hi - b'0') * 10 + (lo - b'0'));
+                    i += 2;
+                }
+                Code128Step::Shift | Code128Step::Single => {
+                    if step == Code128Step::Shift {
+                        bytes.extend_from_slice(b"{S");
+                    }
+                    match self.items[i] {
+                        Code128Item::Fnc1 => bytes.extend_from_slice(b"{1"),
+                        Code128Item::Char(b'{') => bytes.extend_from_slice(b"{{"),
+                        Code128Item::Char(byte) => bytes.push(byte),
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Encode and validate into a [`PrintBarcode`] command.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError`] if the input is invalid or the encoded data
+    /// does not fit in a single `GS k` command.
+    pub fn build(&self) -> Result<PrintBarcode, BarcodeError> {
+        PrintBarcode::new(BarcodeSystem::Code128, self.encode_data()?)
+    }
+
+    fn validate_chars(&self) -> Result<(), BarcodeError> {
+        let position =
+            self.items.iter().position(|item| matches!(item, Code128Item::Char(b) if *b > 127));
+        match position {
+            Some(index) => Err(BarcodeError::InvalidCharacter {
+                data: self.display(),
+                span: SourceSpan::from((index, 1)),
+                system: "CODE128",
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Render items for error reporting, one character per item.
+    fn display(&self) -> String {
+        self.items
+            .iter()
+            .map(|item| match item {
+                Code128Item::Char(b) if b.is_ascii_graphic() || *b == b' ' => *b as char,
+                _ => '?',
+            })
+            .collect()
+    }
+
+    fn is_digit_pair(&self, i: usize) -> bool {
+        matches!(
+            self.items.get(i..i + 2),
+            Some([Code128Item::Char(a), Code128Item::Char(b)]) if a.is_ascii_digit() && b.is_ascii_digit()
+        )
+    }
+
+    /// Cost of emitting the item at `i` without switching, and the step used.
+    fn stay(&self, i: usize, set: Code128Set, cost: &[[usize; 3]]) -> Option<(usize, Code128Step)> {
+        match (set, self.items[i]) {
+            (_, Code128Item::Fnc1) => Some((1 + cost[i + 1][set.index()], Code128Step::Single)),
+            (Code128Set::C, _) => {
+                self.is_digit_pair(i).then(|| (1 + cost[i + 2][set.index()], Code128Step::Pair))
+            }
+            (_, Code128Item::Char(byte)) if set.encodes(byte) => {
+                Some((1 + cost[i + 1][set.index()], Code128Step::Single))
+            }
+            (_, Code128Item::Char(_)) => Some((2 + cost[i + 1][set.index()], Code128Step::Shift)),
+        }
+    }
+
+    /// Find the code set sequence with the fewest symbol characters.
+    ///
+    /// Dynamic programming over (position, current set), working backwards
+    /// from the end of the data.
+    fn plan(&self) -> Code128Plan {
+        let n = self.items.len();
+        let mut cost = vec![[0usize; 3]; n + 2];
+        let mut choice = vec![[(None, Code128Step::Single); 3]; n];
+
+        for i in (0..n).rev() {
+            let stay = Code128Set::ALL.map(|set| self.stay(i, set, &cost));
+            for set in Code128Set::ALL {
+                let mut best = stay[set.index()].map(|(c, step)| (c, None, step));
+                for other in Code128Set::PREFERENCE {
+                    if other == set {
+                        continue;
+                    }
+                    if let Some((c, step)) = stay[other.index()]
+                        && best.is_none_or(|(b, ..)| c + 1 < b)
+                    {
+                        best = Some((c + 1, Some(other), step));
+                    }
+                }
+                let (c, switch, step) = best.expect("Code A and B can encode any ASCII character");
+                cost[i][set.index()] = c;
+                choice[i][set.index()] = (switch, step);
+            }
+        }
+
+        let start = Code128Set::PREFERENCE
+            .into_iter()
+            .filter_map(|set| self.stay(0, set, &cost).map(|(c, _)| (c, set)))
+            .min_by_key(|(c, _)| *c)
+            .map_or(Code128Set::B, |(_, set)| set);
+
+        let mut steps = Vec::new();
+        let mut set = start;
+        let mut i = 0;
+        while i < n {
+            let (switch, step) = choice[i][set.index()];
+            if let Some(next) = switch {
+                set = next;
+            }
+            steps.push((switch, step));
+            i += if step == Code128Step::Pair {
+                2
+            } else {
+                1
+            };
+        }
+
+        Code128Plan {
+            start,
+            steps,
+        }
+    }
+}
+
+/// Code set selection for a [`Code128Builder`].
+#[derive(Debug)]
+struct Code128Plan {
+    start: Code128Set,
+    steps: Vec<(Option<Code128Set>, Code128Step)>,
 }
 
 #[cfg(test)]
@@ -244,6 +823,41 @@ mod tests {
         assert_eq!(cmd.encode(), vec![0x1D, b'h', 100]);
     }
 
+    #[test]
+    fn barcode_height_validated() {
+        assert_eq!(SetBarcodeHeight::new(80).unwrap(), SetBarcodeHeight(80));
+        assert!(matches!(
+            SetBarcodeHeight::new(0),
+            Err(ValidationError::OutOfRange {
+                value: 0,
+                ..
+            })
+        ));
+
+        let mut profile = PrinterProfile::SRP_350PLUS;
+        profile.max_barcode_height = 200;
+        assert!(SetBarcodeHeight::for_profile(200, &profile).is_ok());
+        assert!(SetBarcodeHeight::for_profile(201, &profile).is_err());
+    }
+
+    #[test]
+    fn barcode_width_from_dots_validated() {
+        assert_eq!(SetBarcodeWidth::from_dots(4).unwrap(), SetBarcodeWidth(BarcodeWidth::Medium));
+        assert!(matches!(
+            SetBarcodeWidth::from_dots(1),
+            Err(ValidationError::OutOfRange {
+                min: 2,
+                max: 6,
+                ..
+            })
+        ));
+        assert!(SetBarcodeWidth::from_dots(7).is_err());
+
+        let mut profile = PrinterProfile::SRP_350PLUS;
+        profile.max_barcode_module_width = 4;
+        assert!(SetBarcodeWidth::for_profile(5, &profile).is_err());
+    }
+
     #[test]
     fn barcode_width_values() {
         assert_eq!(BarcodeWidth::Thin as u8, 2);
@@ -281,10 +895,182 @@ mod tests {
         assert!(matches!(result, Err(BarcodeError::ItfRequiresEvenLength(_))));
     }
 
+    #[test]
+    fn print_barcode_rejects_data_over_255_bytes() {
+        let result = PrintBarcode::new(BarcodeSystem::Code128, vec![b'A'; 300]);
+        assert!(matches!(
+            result,
+            Err(BarcodeError::DataTooLong {
+                actual: 300,
+                max: 255,
+                ..
+            })
+        ));
+
+        let result = Code128Builder::new().text(&"x{".repeat(100)).build();
+        assert!(matches!(result, Err(BarcodeError::DataTooLong { .. })));
+    }
+
+    #[test]
+    fn changed_fields_are_revalidated_and_cut_short() {
+        let mut barcode = PrintBarcode::new(BarcodeSystem::Code128, b"{A123".to_vec()).unwrap();
+        barcode.data = vec![b'A'; 300];
+        assert!(matches!(barcode.validate(), Err(BarcodeError::DataTooLong { .. })));
+
+        let bytes = barcode.encode();
+        assert_eq!(bytes[3], 255);
+        assert_eq!(bytes.len(), 4 + 255);
+    }
+
     #[test]
     fn print_barcode_encodes() {
         let cmd = PrintBarcode::new(BarcodeSystem::Code128, b"{A123".to_vec()).unwrap();
         let encoded = cmd.encode();
         assert_eq!(encoded[0..4], [0x1D, b'k', 73, 5]);
     }
+
+    #[test]
+    fn borrowed_barcode_matches_owned() {
+        let data = b"{A123";
+        let owned = PrintBarcode::new(BarcodeSystem::Code128, data.to_vec()).unwrap();
+        let borrowed = PrintBarcodeRef::new(BarcodeSystem::Code128, data).unwrap();
+        assert_eq!(borrowed.encode(), owned.encode());
+        assert_eq!(owned.as_borrowed(), borrowed);
+        assert_eq!(borrowed.into_owned(), owned);
+        assert!(matches!(
+            PrintBarcodeRef::new(BarcodeSystem::Itf, b"123"),
+            Err(BarcodeError::ItfRequiresEvenLength(3))
+        ));
+    }
+
+    fn code128(text: &str) -> Vec<u8> {
+        Code128Builder::new().text(text).encode_data().unwrap()
+    }
+
+    #[test]
+    fn code128_plain_text_uses_code_b() {
+        assert_eq!(code128("Hello"), b"{BHello");
+    }
+
+    #[test]
+    fn code128_even_digits_use_code_c() {
+        assert_eq!(code128("123456"), vec![b'{', b'C', 12, 34, 56]);
+    }
+
+    #[test]
+    fn code128_odd_digits_lead_with_single_digit() {
+        assert_eq!(code128("12345"), vec![b'{', b'B', b'1', b'{', b'C', 23, 45]);
+    }
+
+    #[test]
+    fn code128_short_digit_runs_stay_in_b() {
+        assert_eq!(code128("AB12CD"), b"{BAB12CD");
+    }
+
+    #[test]
+    fn code128_long_digit_run_switches_to_c() {
+        assert_eq!(code128("AB123456"), vec![b'{', b'B', b'A', b'B', b'{', b'C', 12, 34, 56]);
+    }
+
+    #[test]
+    fn code128_escapes_brace() {
+        assert_eq!(code128("a{b"), b"{Ba{{b");
+    }
+
+    #[test]
+    fn code128_control_chars_use_code_a() {
+        assert_eq!(code128("AB\tCD"), b"{AAB\tCD");
+    }
+
+    #[test]
+    fn code128_shift_for_isolated_character() {
+        assert_eq!(code128("\ta\t\t"), b"{A\t{Sa\t\t");
+    }
+
+    #[test]
+    fn code128_fnc1() {
+        let data = Code128Builder::new().fnc1().text("0112").encode_data().unwrap();
+        assert_eq!(data, vec![b'{', b'C', b'{', b'1', 1, 12]);
+    }
+
+    #[test]
+    fn code128_rejects_non_ascii() {
+        let result = Code128Builder::new().text("caf\u{e9}").build();
+        assert!(matches!(result, Err(BarcodeError::InvalidCharacter { .. })));
+    }
+
+    #[test]
+    fn code128_rejects_empty() {
+        assert!(matches!(Code128Builder::new().build(), Err(BarcodeError::InvalidLength { .. })));
+    }
+
+    #[test]
+    fn code128_build_validates() {
+        let barcode = Code128Builder::new().text("ABC").build().unwrap();
+        assert_eq!(barcode.system(), BarcodeSystem::Code128);
+        assert_eq!(barcode.data(), b"{BABC");
+    }
+
+    #[test]
+    fn check_digit_appended_for_upc_a() {
+        let barcode = PrintBarcode::with_check_digit(BarcodeSystem::UpcA, b"03600029145").unwrap();
+        assert_eq!(barcode.data(), b"036000291452");
+    }
+
+    #[test]
+    fn check_digit_appended_for_ean13_and_ean8() {
+        let ean13 = PrintBarcode::with_check_digit(BarcodeSystem::Jan13, b"400638133393").unwrap();
+        assert_eq!(ean13.data(), b"4006381333931");
+
+        let ean8 = PrintBarcode::with_check_digit(BarcodeSystem::Jan8, b"9638507").unwrap();
+        assert_eq!(ean8.data(), b"96385074");
+    }
+
+    #[test]
+    fn check_digit_verified_when_present() {
+        assert!(PrintBarcode::with_check_digit(BarcodeSystem::Jan13, b"4006381333931").is_ok());
+
+        let bad = PrintBarcode::with_check_digit(BarcodeSystem::Jan13, b"4006381333932");
+        assert!(matches!(
+            bad,
+            Err(BarcodeError::CheckDigitMismatch {
+                expected: 1,
+                actual: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn check_digit_itf14() {
+        let barcode = PrintBarcode::with_check_digit(BarcodeSystem::Itf, b"1540014128876").unwrap();
+        assert_eq!(barcode.data(), b"15400141288763");
+
+        let wrong_len = PrintBarcode::with_check_digit(BarcodeSystem::Itf, b"1234");
+        assert!(matches!(wrong_len, Err(BarcodeError::InvalidLength { .. })));
+    }
+
+    #[test]
+    fn check_digit_rejects_non_digits() {
+        let result = PrintBarcode::with_check_digit(BarcodeSystem::UpcA, b"0360002914A");
+        assert!(matches!(result, Err(BarcodeError::InvalidCharacter { .. })));
+    }
+
+    #[test]
+    fn check_digit_unsupported_for_code39() {
+        let result = PrintBarcode::with_check_digit(BarcodeSystem::Code39, b"ABC");
+        assert!(matches!(result, Err(BarcodeError::CheckDigitUnsupported("CODE39"))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializing_validates_data() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "ABC").unwrap();
+        let json = serde_json::to_string(&barcode).unwrap();
+        assert_eq!(json, r#"{"system":"Code39","data":[65,66,67]}"#);
+        assert_eq!(serde_json::from_str::<PrintBarcode>(&json).unwrap(), barcode);
+
+        let lowercase = r#"{"system":"Code39","data":[97]}"#;
+        assert!(serde_json::from_str::<PrintBarcode>(lowercase).is_err());
+    }
 }
@@ -0,0 +1,437 @@
+//! Host-side barcode rendering.
+//!
+//! Draws CODE128 and EAN/UPC symbols as raster images, for symbologies or
+//! data lengths a printer's `GS k` implementation rejects. Bars are
+//! [`BarcodeWidth`] dots per module and the full [`SetBarcodeHeight`] tall,
+//! with no quiet zone or HRI text.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{BarcodeSystem, BarcodeWidth, Code128Builder, PrintBarcode, SetBarcodeHeight};
+use crate::command::gs1;
+use crate::command::image::PrintRasterImage;
+use crate::error::{BarcodeError, PrinterError};
+use crate::profile::PrinterProfile;
+
+/// Raster replacement for `barcode` if `profile` can't print it natively.
+///
+/// Returns `None` when the data fits [`PrinterProfile::max_barcode_data`].
+/// Longer barcodes are drawn at the default height and the default module
+/// width, or the thinnest width the profile allows if that doesn't fit the
+/// paper.
+///
+/// Fails with [`BarcodeError::TooWide`] if no module width fits.
+pub(crate) fn barcode_fallback(
+    barcode: &PrintBarcode,
+    profile: &PrinterProfile,
+) -> Result<Option<PrintRasterImage>, PrinterError> {
+    if barcode.data().len() <= usize::from(profile.max_barcode_data) {
+        return Ok(None);
+    }
+    let thinnest = match profile.min_barcode_module_width {
+        ..=2 => BarcodeWidth::Thin,
+        _ => BarcodeWidth::Normal,
+    };
+    let mut image = barcode.to_raster(BarcodeWidth::default(), SetBarcodeHeight::default())?;
+    if dots_wide(&image) > u32::from(profile.paper_width_dots)
+        && thinnest != BarcodeWidth::default()
+    {
+        image = barcode.to_raster(thinnest, SetBarcodeHeight::default())?;
+    }
+    if dots_wide(&image) > u32::from(profile.paper_width_dots) {
+        return Err(BarcodeError::TooWide {
+            system: barcode.system().name(),
+            width: dots_wide(&image),
+            paper: profile.paper_width_dots,
+        }
+        .into());
+    }
+    Ok(Some(image))
+}
+
+/// Width of a raster image, in dots.
+fn dots_wide(image: &PrintRasterImage) -> u32 {
+    u32::from(image.width_bytes) * 8
+}
+
+impl PrintBarcode {
+    /// Render the barcode host-side as a raster image.
+    ///
+    /// Supports CODE128, JAN-13, JAN-8 and UPC-A. A missing EAN/UPC check
+    /// digit is computed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError::RenderUnsupported`] for other symbologies,
+    /// or [`BarcodeError::InvalidCharacter`] if CODE128 data has an invalid
+    /// `{` sequence or a character outside its code set.
+    pub fn to_raster(
+        &self,
+        width: BarcodeWidth,
+        height: SetBarcodeHeight,
+    ) -> Result<PrintRasterImage, BarcodeError> {
+        let modules = match self.system() {
+            BarcodeSystem::Code128 => code128_modules(&code128_values(self.data())?),
+            BarcodeSystem::Jan13 => ean13_modules(&with_check_digit(self.data(), 12)),
+            BarcodeSystem::UpcA => {
+                ean13_modules(&[b"0".as_slice(), &with_check_digit(self.data(), 11)].concat())
+            }
+            BarcodeSystem::Jan8 => ean8_modules(&with_check_digit(self.data(), 7)),
+            system => return Err(BarcodeError::RenderUnsupported(system.name())),
+        };
+        Ok(draw(&modules, width, height))
+    }
+}
+
+impl Code128Builder {
+    /// Render the symbol host-side as a raster image.
+    ///
+    /// Unlike [`build`](Self::build), the data isn't limited to what fits
+    /// in one `GS k` command, so long symbols can still be printed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError`] if the input is invalid, as for
+    /// [`encode_data`](Self::encode_data).
+    pub fn to_raster(
+        &self,
+        width: BarcodeWidth,
+        height: SetBarcodeHeight,
+    ) -> Result<PrintRasterImage, BarcodeError> {
+        let values = code128_values(&self.encode_data()?)?;
+        Ok(draw(&code128_modules(&values), width, height))
+    }
+}
+
+/// Draw modules (true for a bar) as a raster image.
+fn draw(modules: &[bool], width: BarcodeWidth, height: SetBarcodeHeight) -> PrintRasterImage {
+    let scale = width as usize;
+    let width_bytes = (modules.len() * scale).div_ceil(8);
+    let mut row = vec![0u8; width_bytes];
+    for (i, _) in modules.iter().enumerate().filter(|(_, bar)| **bar) {
+        for x in i * scale..(i + 1) * scale {
+            row[x / 8] |= 0x80 >> (x % 8);
+        }
+    }
+    let height = height.0.max(1);
+    PrintRasterImage::new(width_bytes as u16, u16::from(height), row.repeat(usize::from(height)))
+}
+
+/// Append the check digit if `data` has only the `body_len` data digits.
+fn with_check_digit(data: &[u8], body_len: usize) -> Vec<u8> {
+    let mut digits = data.to_vec();
+    if digits.len() == body_len {
+        digits.push(b'0' + gs1::check_digit(&digits).unwrap_or(0));
+    }
+    digits
+}
+
+/// Bar and space widths of CODE128 symbol values 0-105, in modules.
+const CODE128_PATTERNS: [&[u8; 6]; 106] = [
+    b"212222", b"222122", b"222221", b"121223", b"121322", b"131222", b"122213", b"122312",
+    b"132212", b"221213", b"221312", b"231212", b"112232", b"122132", b"122231", b"113222",
+    b"123122", b"123221", b"223211", b"221132", b"221231", b"213212", b"223112", b"312131",
+    b"311222", b"321122", b"321221", b"312212", b"322112", b"322211", b"212123", b"212321",
+    b"232121", b"111323", b"131123", b"131321", b"112313", b"132113", b"132311", b"211313",
+    b"231113", b"231311", b"112133", b"112331", b"132131", b"113123", b"113321", b"133121",
+    b"313121", b"211331", b"231131", b"213113", b"213311", b"213131", b"311123", b"311321",
+    b"331121", b"312113", b"312311", b"332111", b"314111", b"221411", b"431111", b"111224",
+    b"111422", b"121124", b"121421", b"141122", b"141221", b"112214", b"112412", b"122114",
+    b"122411", b"142112", b"142211", b"241211", b"221114", b"413111", b"241112", b"134111",
+    b"111242", b"121142", b"121241", b"114212", b"124112", b"124211", b"411212", b"421112",
+    b"421211", b"212141", b"214121", b"412121", b"111143", b"111341", b"131141", b"114113",
+    b"114311", b"411113", b"411311", b"113141", b"114131", b"311141", b"411131", b"211412",
+    b"211214", b"211232",
+];
+
+/// The CODE128 stop pattern, including the final bar.
+const CODE128_STOP: &[u8; 7] = b"2331112";
+
+/// Translate `GS k` CODE128 data into symbol values, start code first.
+fn code128_values(data: &[u8]) -> Result<Vec<u8>, BarcodeError> {
+    let malformed = |position: usize| BarcodeError::InvalidCharacter {
+        data: String::from_utf8_lossy(data).into_owned(),
+        span: (position, 2.min(data.len() - position)).into(),
+        system: "CODE128",
+    };
+    let switch_value = |target: u8| match target {
+        b'A' => 101,
+        b'B' => 100,
+        _ => 99,
+    };
+
+    let mut values = Vec::with_capacity(data.len() + 1);
+    let mut set = match data {
+        [b'{', set @ (b'A' | b'B' | b'C'), ..] => *set,
+        _ => return Err(malformed(0)),
+    };
+    values.push(103 + set - b'A');
+    let mut i = 2;
+    let mut shifted = false;
+    while i < data.len() {
+        let current = if shifted {
+            if set == b'A' {
+                b'B'
+            } else {
+                b'A'
+            }
+        } else {
+            set
+        };
+        shifted = false;
+        let byte = data[i];
+        if byte == b'{' {
+            let Some(&code) = data.get(i + 1) else {
+                return Err(malformed(i));
+            };
+            match code {
+                b'A' | b'B' | b'C' if code != set => {
+                    values.push(switch_value(code));
+                    set = code;
+                }
+                b'S' if set != b'C' => {
+                    values.push(98);
+                    shifted = true;
+                }
+                b'1' => values.push(102),
+                b'2' if current != b'C' => values.push(97),
+                b'3' if current != b'C' => values.push(96),
+                b'4' if current == b'A' => values.push(101),
+                b'4' if current == b'B' => values.push(100),
+                b'{' if current == b'B' => values.push(b'{' - 32),
+                _ => return Err(malformed(i)),
+            }
+            i += 2;
+            continue;
+        }
+        let value = match current {
+            b'C' if byte <= 99 => byte,
+            b'A' if byte < 32 => byte + 64,
+            b'A' if byte < 96 => byte - 32,
+            b'B' if (32..=127).contains(&byte) => byte - 32,
+            _ => return Err(malformed(i)),
+        };
+        values.push(value);
+        i += 1;
+    }
+
+    let checksum =
+        values.iter().enumerate().map(|(i, &value)| i.max(1) * usize::from(value)).sum::<usize>()
+            % 103;
+    values.push(checksum as u8);
+    Ok(values)
+}
+
+fn code128_modules(values: &[u8]) -> Vec<bool> {
+    let mut modules = Vec::with_capacity(values.len() * 11 + 13);
+    for &value in values {
+        push_widths(&mut modules, CODE128_PATTERNS[usize::from(value)]);
+    }
+    push_widths(&mut modules, CODE128_STOP);
+    modules
+}
+
+/// Append alternating bars and spaces, starting with a bar.
+fn push_widths(modules: &mut Vec<bool>, widths: &[u8]) {
+    for (i, width) in widths.iter().enumerate() {
+        modules.extend(core::iter::repeat_n(i % 2 == 0, usize::from(width - b'0')));
+    }
+}
+
+/// EAN left-hand odd parity (L) codes; R codes are their complement and
+/// even parity (G) codes the reversed R codes.
+const EAN_L: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+/// EAN-13 parity of the left-hand digits by first digit, 1 for even (G).
+const EAN13_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+#[derive(Clone, Copy)]
+enum EanCode {
+    L,
+    G,
+    R,
+}
+
+fn push_ean_digit(modules: &mut Vec<bool>, digit: u8, code: EanCode) {
+    let l = EAN_L[usize::from(digit - b'0')];
+    let bits = match code {
+        EanCode::L => l,
+        EanCode::R => !l & 0x7F,
+        EanCode::G => (!l & 0x7F).reverse_bits() >> 1,
+    };
+    modules.extend((0..7).rev().map(|bit| bits & (1 << bit) != 0));
+}
+
+fn push_guard(modules: &mut Vec<bool>, guard: &[u8]) {
+    modules.extend(guard.iter().map(|&m| m == 1));
+}
+
+fn ean13_modules(digits: &[u8]) -> Vec<bool> {
+    let parity = EAN13_PARITY[usize::from(digits[0] - b'0')];
+    let mut modules = Vec::with_capacity(95);
+    push_guard(&mut modules, &[1, 0, 1]);
+    for (i, &digit) in digits[1..7].iter().enumerate() {
+        let even = parity & (0b100000 >> i) != 0;
+        push_ean_digit(
+            &mut modules,
+            digit,
+            if even {
+                EanCode::G
+            } else {
+                EanCode::L
+            },
+        );
+    }
+    push_guard(&mut modules, &[0, 1, 0, 1, 0]);
+    for &digit in &digits[7..13] {
+        push_ean_digit(&mut modules, digit, EanCode::R);
+    }
+    push_guard(&mut modules, &[1, 0, 1]);
+    modules
+}
+
+fn ean8_modules(digits: &[u8]) -> Vec<bool> {
+    let mut modules = Vec::with_capacity(67);
+    push_guard(&mut modules, &[1, 0, 1]);
+    for &digit in &digits[..4] {
+        push_ean_digit(&mut modules, digit, EanCode::L);
+    }
+    push_guard(&mut modules, &[0, 1, 0, 1, 0]);
+    for &digit in &digits[4..8] {
+        push_ean_digit(&mut modules, digit, EanCode::R);
+    }
+    push_guard(&mut modules, &[1, 0, 1]);
+    modules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read the first row back as '1'/'0' per module of `scale` dots.
+    fn bars(image: &PrintRasterImage, modules: usize, scale: usize) -> String {
+        (0..modules)
+            .map(|m| m * scale)
+            .map(|x| {
+                if image.data[x / 8] & (0x80 >> (x % 8)) != 0 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn code128_patterns_are_11_distinct_modules() {
+        let mut seen = std::collections::HashSet::new();
+        for pattern in CODE128_PATTERNS {
+            assert_eq!(pattern.iter().map(|w| w - b'0').sum::<u8>(), 11);
+            assert!(seen.insert(pattern));
+        }
+        assert_eq!(CODE128_STOP.iter().map(|w| w - b'0').sum::<u8>(), 13);
+    }
+
+    #[test]
+    fn code128_values_and_checksum() {
+        let values = code128_values(b"{BWikipedia").unwrap();
+        assert_eq!(values[0], 104);
+        assert_eq!(values[1], b'W' - 32);
+        assert_eq!(*values.last().unwrap(), 88);
+
+        let values = code128_values(b"{C\x0c\x22{BA").unwrap();
+        assert_eq!(values[..5], [105, 12, 34, 100, 33]);
+        assert!(code128_values(b"{C\x64").is_err());
+        assert!(code128_values(b"ABC").is_err());
+    }
+
+    #[test]
+    fn code128_renders_start_and_stop() {
+        let barcode = Code128Builder::new().text("Hi").build().unwrap();
+        let image = barcode.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(10)).unwrap();
+        // Start B, H, i, checksum and stop: 4 * 11 + 13 modules, 2 dots each
+        assert_eq!(image.width_bytes, (57 * 2_u16).div_ceil(8));
+        assert_eq!(image.height_dots, 10);
+        assert!(image.data.chunks(image.width_bytes.into()).all(|row| row == &image.data[..15]));
+
+        // Start B is 211214: bar 2, space 1, bar 1, space 2, bar 1, space 4
+        assert!(bars(&image, 57, 2).starts_with("11010010000"));
+        assert!(bars(&image, 57, 2).ends_with("1100011101011"));
+    }
+
+    #[test]
+    fn long_code128_renders_beyond_gs_k_limit() {
+        let text = "X".repeat(300);
+        let builder = Code128Builder::new().text(&text);
+        assert!(builder.build().is_err());
+        let image = builder.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(1)).unwrap();
+        assert_eq!(usize::from(image.width_bytes), ((302 * 11 + 13) * 2_usize).div_ceil(8));
+    }
+
+    #[test]
+    fn ean13_matches_reference() {
+        // 5901234123457, a common EAN-13 example
+        let barcode = PrintBarcode::new(BarcodeSystem::Jan13, "590123412345").unwrap();
+        let image = barcode.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(1)).unwrap();
+        let expected = concat!(
+            "101", "0001011", "0100111", "0110011", "0010011", "0111101", "0011101", "01010",
+            "1100110", "1101100", "1000010", "1011100", "1001110", "1000100", "101"
+        );
+        assert_eq!(bars(&image, 95, 2), expected);
+    }
+
+    #[test]
+    fn fallback_follows_profile_limit() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Jan8, "9638507").unwrap();
+        let profile = PrinterProfile::SRP_350PLUS;
+        assert!(barcode_fallback(&barcode, &profile).unwrap().is_none());
+
+        let image = barcode_fallback(&barcode, &profile.with_max_barcode_data(6)).unwrap();
+        let image = image.unwrap();
+        assert_eq!(image.width_bytes, (67 * 3_u16).div_ceil(8));
+        assert_eq!(image.height_dots, u16::from(SetBarcodeHeight::default().0));
+    }
+
+    #[test]
+    fn fallback_narrows_or_rejects_wide_barcodes() {
+        let profile = PrinterProfile::SRP_350PLUS.with_max_barcode_data(6);
+        // 22 symbols and the stop: 255 modules, too wide at 3 dots each
+        let barcode = Code128Builder::new().text(&"X".repeat(20)).build().unwrap();
+        let image = barcode_fallback(&barcode, &profile).unwrap().unwrap();
+        assert_eq!(image.width_bytes, (255 * 2_u16).div_ceil(8));
+
+        let barcode = Code128Builder::new().text(&"X".repeat(30)).build().unwrap();
+        assert!(matches!(
+            barcode_fallback(&barcode, &profile),
+            Err(PrinterError::Barcode(BarcodeError::TooWide {
+                paper: 512,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn ean8_and_upc_a_lengths() {
+        let ean8 = PrintBarcode::new(BarcodeSystem::Jan8, "9638507").unwrap();
+        let image = ean8.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(1)).unwrap();
+        assert_eq!(image.width_bytes, (67 * 2_u16).div_ceil(8));
+
+        let upc = PrintBarcode::new(BarcodeSystem::UpcA, "03600029145").unwrap();
+        let image = upc.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(1)).unwrap();
+        assert_eq!(image.width_bytes, (95 * 2_u16).div_ceil(8));
+
+        let itf = PrintBarcode::new(BarcodeSystem::Itf, "1234").unwrap();
+        assert!(matches!(
+            itf.to_raster(BarcodeWidth::Thin, SetBarcodeHeight(1)),
+            Err(BarcodeError::RenderUnsupported("ITF"))
+        ));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! These are single-byte commands for fundamental printer operations.
 
-use super::{CAN, CR, Command, FF, HT, LF};
+use super::{CAN, CR, FF, HT, LF};
 
 /// Print buffer and feed one line.
 ///
@@ -11,13 +11,10 @@ use super::{CAN, CR, Command, FF, HT, LF};
 ///
 /// ESC/POS: `LF` (0x0A)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineFeed;
 
-impl Command for LineFeed {
-    fn encode(&self) -> Vec<u8> {
-        vec![LF]
-    }
-}
+impl_static_command!(LineFeed => LF);
 
 /// Print and return to standard mode (page mode only).
 ///
@@ -26,13 +23,10 @@ impl Command for LineFeed {
 ///
 /// ESC/POS: `FF` (0x0C)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FormFeed;
 
-impl Command for FormFeed {
-    fn encode(&self) -> Vec<u8> {
-        vec![FF]
-    }
-}
+impl_static_command!(FormFeed => FF);
 
 /// Print and carriage return.
 ///
@@ -41,13 +35,10 @@ impl Command for FormFeed {
 ///
 /// ESC/POS: `CR` (0x0D)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CarriageReturn;
 
-impl Command for CarriageReturn {
-    fn encode(&self) -> Vec<u8> {
-        vec![CR]
-    }
-}
+impl_static_command!(CarriageReturn => CR);
 
 /// Move to next horizontal tab position.
 ///
@@ -56,13 +47,10 @@ impl Command for CarriageReturn {
 ///
 /// ESC/POS: `HT` (0x09)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HorizontalTab;
 
-impl Command for HorizontalTab {
-    fn encode(&self) -> Vec<u8> {
-        vec![HT]
-    }
-}
+impl_static_command!(HorizontalTab => HT);
 
 /// Cancel print data in page mode.
 ///
@@ -71,17 +59,17 @@ impl Command for HorizontalTab {
 ///
 /// ESC/POS: `CAN` (0x18)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cancel;
 
-impl Command for Cancel {
-    fn encode(&self) -> Vec<u8> {
-        vec![CAN]
-    }
-}
+impl_static_command!(Cancel => CAN);
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use super::*;
+    use crate::command::{Command, StaticCommand};
 
     #[test]
     fn line_feed_encodes_to_0x0a() {
@@ -128,4 +116,13 @@ mod tests {
         let lf2 = lf;
         assert_eq!(lf.encode(), lf2.encode());
     }
+
+    #[test]
+    fn static_bytes_match_encode() {
+        assert_eq!(LineFeed::BYTES, [0x0A]);
+        assert_eq!(FormFeed.encoded(), FormFeed.encode());
+        let mut output = vec![b'x'];
+        Cancel.encode_into(&mut output);
+        assert_eq!(output, [b'x', 0x18]);
+    }
 }
@@ -2,6 +2,9 @@
 //!
 //! Commands for text styling: emphasis, underline, size, font, rotation, etc.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Command, ESC, GS};
 
 /// Turn emphasized (bold) mode on or off.
@@ -11,17 +14,30 @@ use super::{Command, ESC, GS};
 ///
 /// ESC/POS: `ESC E n` (0x1B 0x45 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetEmphasized(pub bool);
 
+impl SetEmphasized {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'E', self.0 as u8]
+    }
+}
+
 impl Command for SetEmphasized {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'E', u8::from(self.0)]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
 /// Underline thickness options.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnderlineThickness {
     /// Underline disabled.
     #[default]
@@ -39,11 +55,23 @@ pub enum UnderlineThickness {
 ///
 /// ESC/POS: `ESC - n` (0x1B 0x2D n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetUnderline(pub UnderlineThickness);
 
+impl SetUnderline {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'-', self.0 as u8]
+    }
+}
+
 impl Command for SetUnderline {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'-', self.0 as u8]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -54,17 +82,30 @@ impl Command for SetUnderline {
 ///
 /// ESC/POS: `ESC G n` (0x1B 0x47 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetDoubleStrike(pub bool);
 
+impl SetDoubleStrike {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'G', self.0 as u8]
+    }
+}
+
 impl Command for SetDoubleStrike {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'G', u8::from(self.0)]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
 /// Character font selection.
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Font {
     /// Font A: 12×24 dots.
     #[default]
@@ -77,11 +118,23 @@ pub enum Font {
 ///
 /// ESC/POS: `ESC M n` (0x1B 0x4D n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectFont(pub Font);
 
+impl SelectFont {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'M', self.0 as u8]
+    }
+}
+
 impl Command for SelectFont {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'M', self.0 as u8]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -90,6 +143,7 @@ impl Command for SelectFont {
 /// Valid values are 1-8x, encoded as 0-7 in the command byte.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScaleFactor {
     /// 1x (normal size).
     #[default]
@@ -110,8 +164,16 @@ pub enum ScaleFactor {
     X8 = 7,
 }
 
+impl ScaleFactor {
+    /// The scale as a multiplier (1-8).
+    pub const fn multiplier(self) -> u8 {
+        self as u8 + 1
+    }
+}
+
 /// Character size with independent width and height scaling.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacterSize {
     /// Horizontal scaling factor.
     pub width: ScaleFactor,
@@ -174,20 +236,32 @@ impl CharacterSize {
 /// - Bits 0-2: height scale (0=1x, 1=2x, ..., 7=8x)
 /// - Bits 4-6: width scale (0=1x, 1=2x, ..., 7=8x)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetCharacterSize(pub CharacterSize);
 
-impl Command for SetCharacterSize {
-    fn encode(&self) -> Vec<u8> {
+impl SetCharacterSize {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
         let w = self.0.width as u8;
         let h = self.0.height as u8;
-        let n = (w << 4) | h;
-        vec![GS, b'!', n]
+        [GS, b'!', (w << 4) | h]
+    }
+}
+
+impl Command for SetCharacterSize {
+    fn encode(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
 /// Text justification options.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Justification {
     /// Left justification (default).
     #[default]
@@ -205,11 +279,23 @@ pub enum Justification {
 ///
 /// ESC/POS: `ESC a n` (0x1B 0x61 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetJustification(pub Justification);
 
+impl SetJustification {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'a', self.0 as u8]
+    }
+}
+
 impl Command for SetJustification {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'a', self.0 as u8]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -220,17 +306,30 @@ impl Command for SetJustification {
 ///
 /// ESC/POS: `ESC { n` (0x1B 0x7B n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetUpsideDown(pub bool);
 
+impl SetUpsideDown {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'{', self.0 as u8]
+    }
+}
+
 impl Command for SetUpsideDown {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'{', u8::from(self.0)]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
 /// Rotation mode options.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RotationMode {
     /// No rotation (default).
     #[default]
@@ -246,11 +345,23 @@ pub enum RotationMode {
 ///
 /// ESC/POS: `ESC V n` (0x1B 0x56 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetRotation(pub RotationMode);
 
+impl SetRotation {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'V', self.0 as u8]
+    }
+}
+
 impl Command for SetRotation {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'V', self.0 as u8]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -261,11 +372,23 @@ impl Command for SetRotation {
 ///
 /// ESC/POS: `GS B n` (0x1D 0x42 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetReverse(pub bool);
 
+impl SetReverse {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [GS, b'B', self.0 as u8]
+    }
+}
+
 impl Command for SetReverse {
     fn encode(&self) -> Vec<u8> {
-        vec![GS, b'B', u8::from(self.0)]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -276,6 +399,7 @@ impl Command for SetReverse {
 ///
 /// ESC/POS: `GS b n` (0x1D 0x62 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetSmoothing(pub bool);
 
 impl Command for SetSmoothing {
@@ -284,6 +408,45 @@ impl Command for SetSmoothing {
     }
 }
 
+/// Print color for two-color models.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrintColor {
+    /// First color (black).
+    #[default]
+    Black = 0,
+    /// Second color (red).
+    Red = 1,
+}
+
+/// Select print color.
+///
+/// Only effective on two-color models; the SRP-350plus prints a single
+/// color and ignores this command.
+///
+/// ESC/POS: `ESC r n` (0x1B 0x72 n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectPrintColor(pub PrintColor);
+
+impl SelectPrintColor {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b'r', self.0 as u8]
+    }
+}
+
+impl Command for SelectPrintColor {
+    fn encode(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -400,4 +563,10 @@ mod tests {
         let cmd = SetSmoothing(true);
         assert_eq!(cmd.encode(), vec![0x1D, b'b', 1]);
     }
+
+    #[test]
+    fn select_print_color() {
+        assert_eq!(SelectPrintColor(PrintColor::Black).encode(), vec![0x1B, b'r', 0]);
+        assert_eq!(SelectPrintColor(PrintColor::Red).encode(), vec![0x1B, b'r', 1]);
+    }
 }
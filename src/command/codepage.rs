@@ -2,7 +2,12 @@
 //!
 //! The printer supports 40+ code pages for international character support.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Command, ESC};
+use crate::encoding;
+use crate::error::ValidationError;
 
 /// Character code page selection.
 ///
@@ -10,6 +15,7 @@ use super::{Command, ESC};
 /// Use `ESC t n` to select a code page.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CodePage {
     /// CP437 - USA, Standard Europe. Default code page.
     #[default]
@@ -32,6 +38,8 @@ pub enum CodePage {
     Cp852Latin2 = 18,
     /// CP858 - Multilingual with Euro symbol.
     Cp858Euro = 19,
+    /// Thai - the power-on code page of models with Thai support.
+    ThaiDefault = 20,
     /// CP862 - Hebrew (DOS).
     Cp862HebrewDos = 21,
     /// CP864 - Arabic.
@@ -79,6 +87,90 @@ impl CodePage {
     pub const fn as_byte(self) -> u8 {
         self as u8
     }
+
+    /// Code page for an `ESC t` value, if the printer defines one.
+    pub(crate) const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(CodePage::Cp437UsaStandardEurope),
+            1 => Some(CodePage::Katakana),
+            2 => Some(CodePage::Cp850Multilingual),
+            3 => Some(CodePage::Cp860Portuguese),
+            4 => Some(CodePage::Cp863CanadianFrench),
+            5 => Some(CodePage::Cp865Nordic),
+            16 => Some(CodePage::Windows1252LatinI),
+            17 => Some(CodePage::Cp866Cyrillic2),
+            18 => Some(CodePage::Cp852Latin2),
+            19 => Some(CodePage::Cp858Euro),
+            20 => Some(CodePage::ThaiDefault),
+            21 => Some(CodePage::Cp862HebrewDos),
+            22 => Some(CodePage::Cp864Arabic),
+            23 => Some(CodePage::Thai42),
+            24 => Some(CodePage::Windows1253Greek),
+            25 => Some(CodePage::Windows1254Turkish),
+            26 => Some(CodePage::Windows1257Baltic),
+            27 => Some(CodePage::Farsi),
+            28 => Some(CodePage::Windows1251Cyrillic),
+            29 => Some(CodePage::Cp737Greek),
+            30 => Some(CodePage::Cp775Baltic),
+            31 => Some(CodePage::Thai14),
+            32 => Some(CodePage::HebrewOld),
+            33 => Some(CodePage::Windows1255HebrewNew),
+            34 => Some(CodePage::Thai11),
+            35 => Some(CodePage::Thai18),
+            36 => Some(CodePage::Cp855Cyrillic),
+            37 => Some(CodePage::Cp857Turkish),
+            38 => Some(CodePage::Cp928Greek),
+            39 => Some(CodePage::Thai16),
+            40 => Some(CodePage::Windows1256Arabic),
+            _ => None,
+        }
+    }
+
+    /// Byte for `c` in this code page.
+    ///
+    /// ASCII maps to itself. Other characters are looked up in the code
+    /// page's table; code pages without a built-in table only encode ASCII.
+    /// See [`encoding::encode`] for whole strings.
+    pub fn encode_char(self, c: char) -> Option<u8> {
+        if c.is_ascii() {
+            return Some(c as u8);
+        }
+        let index = encoding::table(self)?.iter().position(|&mapped| mapped == c)?;
+        Some(0x80 + index as u8)
+    }
+
+    /// Character for `byte` in this code page.
+    ///
+    /// Returns `None` for upper-half bytes of code pages without a built-in
+    /// table and for unassigned positions.
+    pub fn decode_byte(self, byte: u8) -> Option<char> {
+        if byte.is_ascii() {
+            return Some(char::from(byte));
+        }
+        let c = encoding::table(self)?[usize::from(byte - 0x80)];
+        (c != char::REPLACEMENT_CHARACTER).then_some(c)
+    }
+
+    /// Encode `text` in this code page, replacing unmappable characters
+    /// with `?`.
+    pub fn encode_lossy(self, text: &str) -> Vec<u8> {
+        encoding::encode_lossy(text, self)
+    }
+}
+
+impl TryFrom<u8> for CodePage {
+    type Error = ValidationError;
+
+    /// Code page for an `ESC t` value, e.g. from a configuration file.
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        Self::from_byte(byte).ok_or(ValidationError::InvalidCodePage(byte))
+    }
+}
+
+impl From<CodePage> for u8 {
+    fn from(code_page: CodePage) -> Self {
+        code_page.as_byte()
+    }
 }
 
 /// Select character code page.
@@ -87,11 +179,23 @@ impl CodePage {
 ///
 /// ESC/POS: `ESC t n` (0x1B 0x74 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectCodePage(pub CodePage);
 
+impl SelectCodePage {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [ESC, b't', self.0.as_byte()]
+    }
+}
+
 impl Command for SelectCodePage {
     fn encode(&self) -> Vec<u8> {
-        vec![ESC, b't', self.0.as_byte()]
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -101,6 +205,7 @@ impl Command for SelectCodePage {
 /// that vary between countries.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InternationalCharacterSet {
     /// U.S.A. - Standard ASCII.
     #[default]
@@ -138,6 +243,47 @@ impl InternationalCharacterSet {
     pub const fn as_byte(self) -> u8 {
         self as u8
     }
+
+    /// Character set for an `ESC R` value, if the printer defines one.
+    pub(crate) const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(InternationalCharacterSet::Usa),
+            1 => Some(InternationalCharacterSet::France),
+            2 => Some(InternationalCharacterSet::Germany),
+            3 => Some(InternationalCharacterSet::Uk),
+            4 => Some(InternationalCharacterSet::DenmarkI),
+            5 => Some(InternationalCharacterSet::Sweden),
+            6 => Some(InternationalCharacterSet::Italy),
+            7 => Some(InternationalCharacterSet::SpainI),
+            8 => Some(InternationalCharacterSet::Japan),
+            9 => Some(InternationalCharacterSet::Norway),
+            10 => Some(InternationalCharacterSet::DenmarkII),
+            11 => Some(InternationalCharacterSet::SpainII),
+            12 => Some(InternationalCharacterSet::LatinAmerica),
+            13 => Some(InternationalCharacterSet::Korea),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for InternationalCharacterSet {
+    type Error = ValidationError;
+
+    /// Character set for an `ESC R` value, e.g. from a configuration file.
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        Self::from_byte(byte).ok_or(ValidationError::OutOfRange {
+            name: "international character set",
+            value: u16::from(byte),
+            min: 0,
+            max: 13,
+        })
+    }
+}
+
+impl From<InternationalCharacterSet> for u8 {
+    fn from(charset: InternationalCharacterSet) -> Self {
+        charset.as_byte()
+    }
 }
 
 /// Select international character set.
@@ -146,6 +292,7 @@ impl InternationalCharacterSet {
 ///
 /// ESC/POS: `ESC R n` (0x1B 0x52 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectCharacterSet(pub InternationalCharacterSet);
 
 impl Command for SelectCharacterSet {
@@ -201,6 +348,57 @@ mod tests {
         assert_eq!(cmd.encode(), vec![0x1B, b't', 28]);
     }
 
+    #[test]
+    fn encode_char_ascii_passthrough() {
+        assert_eq!(CodePage::Katakana.encode_char('A'), Some(b'A'));
+    }
+
+    #[test]
+    fn encode_char_uses_page_table() {
+        assert_eq!(CodePage::Cp437UsaStandardEurope.encode_char('é'), Some(0x82));
+        assert_eq!(CodePage::Windows1252LatinI.encode_char('é'), Some(0xE9));
+        assert_eq!(CodePage::Cp858Euro.encode_char('€'), Some(0xD5));
+        assert_eq!(CodePage::Windows1251Cyrillic.encode_char('Ж'), Some(0xC6));
+    }
+
+    #[test]
+    fn decode_byte_round_trips() {
+        let page = CodePage::Cp866Cyrillic2;
+        assert_eq!(page.decode_byte(0x80), Some('А'));
+        assert_eq!(page.encode_char('А'), Some(0x80));
+        assert_eq!(CodePage::Windows1252LatinI.decode_byte(0x81), None);
+        assert_eq!(CodePage::Thai42.decode_byte(0xA0), None);
+    }
+
+    #[test]
+    fn encode_lossy_replaces_unmappable() {
+        assert_eq!(CodePage::Cp437UsaStandardEurope.encode_lossy("Жé"), vec![b'?', 0x82]);
+    }
+
+    #[test]
+    fn codepage_try_from_round_trips() {
+        let pages: Vec<CodePage> = (0..=255).filter_map(|n| CodePage::try_from(n).ok()).collect();
+        assert_eq!(pages.len(), 31);
+        assert!(pages.iter().all(|&page| CodePage::try_from(u8::from(page)).ok() == Some(page)));
+        assert_eq!(CodePage::try_from(20).ok(), Some(CodePage::ThaiDefault));
+        assert!(matches!(CodePage::try_from(6), Err(ValidationError::InvalidCodePage(6))));
+    }
+
+    #[test]
+    fn international_charset_try_from_round_trips() {
+        for n in 0..=13 {
+            let charset = InternationalCharacterSet::try_from(n).unwrap();
+            assert_eq!(u8::from(charset), n);
+        }
+        assert!(matches!(
+            InternationalCharacterSet::try_from(14),
+            Err(ValidationError::OutOfRange {
+                value: 14,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn international_charset_usa_value() {
         assert_eq!(InternationalCharacterSet::Usa as u8, 0);
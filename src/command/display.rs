@@ -0,0 +1,310 @@
+//! Customer display commands.
+//!
+//! Pole displays such as the Bixolon BCD-1100 plug into the printer's
+//! display port and show two lines of 20 characters to the customer. The
+//! printer passes data through to the display once it is selected with
+//! `ESC =`; wrap display commands in [`ToDisplay`] to select the display,
+//! send them, and return to the printer in one go.
+//!
+//! # Example
+//!
+//! ```
+//! use bixolon::command::Command;
+//! use bixolon::command::display::{ClearDisplay, DisplayLine, DisplayRow, ToDisplay};
+//!
+//! let bytes = ToDisplay((
+//!     ClearDisplay,
+//!     DisplayLine::new(DisplayRow::Upper, "Coffee        $3.50"),
+//!     DisplayLine::new(DisplayRow::Lower, "Total         $3.50"),
+//! ))
+//! .encode();
+//! assert_eq!(&bytes[..3], b"\x1b=\x02");
+//! ```
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::codepage::CodePage;
+use super::printer_control::{PeripheralDevice, SelectPeripheral};
+use super::{CAN, Command, ESC, FF, US};
+use crate::encoding::encode_lossy;
+use crate::error::ValidationError;
+
+/// Characters per line on a customer display.
+pub const DISPLAY_COLUMNS: u8 = 20;
+
+/// Lines on a customer display.
+pub const DISPLAY_ROWS: u8 = 2;
+
+/// Home: moves the cursor to the upper left corner.
+const HOM: u8 = 0x0B;
+
+/// Send commands to the customer display instead of the printer.
+///
+/// Encodes as `ESC = 2`, the wrapped commands, then `ESC = 1` so that
+/// later data prints again. Wrap a tuple to send several commands with one
+/// pair of selections.
+///
+/// ESC/POS: `ESC = 2` ... `ESC = 1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToDisplay<C>(pub C);
+
+impl<C: Command> Command for ToDisplay<C> {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.encode_into(&mut bytes);
+        bytes
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        SelectPeripheral(PeripheralDevice::DisplayOnly).encode_into(output);
+        self.0.encode_into(output);
+        SelectPeripheral(PeripheralDevice::PrinterOnly).encode_into(output);
+    }
+}
+
+/// Initialize the customer display.
+///
+/// Clears the screen and returns brightness, cursor and scroll mode to
+/// their power-on defaults.
+///
+/// ESC/POS: `ESC @` (0x1B 0x40)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializeDisplay;
+
+impl_static_command!(InitializeDisplay => ESC, b'@');
+
+/// Clear the whole display and move the cursor home.
+///
+/// ESC/POS: `CLR` (0x0C)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClearDisplay;
+
+impl_static_command!(ClearDisplay => FF);
+
+/// Clear the line the cursor is on and move the cursor to its start.
+///
+/// ESC/POS: `CAN` (0x18)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClearLine;
+
+impl_static_command!(ClearLine => CAN);
+
+/// Move the cursor to the upper left corner.
+///
+/// ESC/POS: `HOM` (0x0B)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CursorHome;
+
+impl_static_command!(CursorHome => HOM);
+
+/// Move the cursor to a column and row.
+///
+/// Columns run 1-20 and rows 1-2, counted from the upper left.
+///
+/// ESC/POS: `US $ n m` (0x1F 0x24 n m)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MoveCursor {
+    column: u8,
+    row: u8,
+}
+
+impl MoveCursor {
+    /// Create a cursor move with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if `column` is not 1-20 or
+    /// `row` is not 1-2.
+    pub fn new(column: u8, row: u8) -> Result<Self, ValidationError> {
+        for (name, value, max) in [("column", column, DISPLAY_COLUMNS), ("row", row, DISPLAY_ROWS)]
+        {
+            if !(1..=max).contains(&value) {
+                return Err(ValidationError::OutOfRange {
+                    name,
+                    value: value.into(),
+                    min: 1,
+                    max: max.into(),
+                });
+            }
+        }
+        Ok(Self {
+            column,
+            row,
+        })
+    }
+
+    /// The target column, 1-20.
+    pub fn column(&self) -> u8 {
+        self.column
+    }
+
+    /// The target row, 1-2.
+    pub fn row(&self) -> u8 {
+        self.row
+    }
+}
+
+impl Command for MoveCursor {
+    fn encode(&self) -> Vec<u8> {
+        vec![US, b'$', self.column, self.row]
+    }
+}
+
+/// Display brightness.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Brightness {
+    /// 20% brightness.
+    Dim = 1,
+    /// 40% brightness.
+    Low = 2,
+    /// 60% brightness.
+    Medium = 3,
+    /// 100% brightness.
+    #[default]
+    Full = 4,
+}
+
+/// Set the display brightness.
+///
+/// ESC/POS: `US X n` (0x1F 0x58 n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetBrightness(pub Brightness);
+
+impl Command for SetBrightness {
+    fn encode(&self) -> Vec<u8> {
+        vec![US, b'X', self.0 as u8]
+    }
+}
+
+/// Show or hide the cursor.
+///
+/// ESC/POS: `US C n` (0x1F 0x43 n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShowCursor(pub bool);
+
+impl Command for ShowCursor {
+    fn encode(&self) -> Vec<u8> {
+        vec![US, b'C', u8::from(self.0)]
+    }
+}
+
+/// A display line.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayRow {
+    /// The top line.
+    #[default]
+    Upper = 1,
+    /// The bottom line.
+    Lower = 2,
+}
+
+/// Replace one line of the display with text.
+///
+/// The text is encoded in CP437 and padded with spaces to 20 characters,
+/// so it overwrites whatever the line showed before. Text longer than 20
+/// characters is cut off without an error. Characters CP437 lacks, and
+/// control characters, which would move the cursor, display as `?`.
+///
+/// ESC/POS: `US $ 1 m` followed by 20 characters
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayLine {
+    /// The line to write.
+    pub row: DisplayRow,
+    /// The text to show.
+    pub text: String,
+}
+
+impl DisplayLine {
+    /// Show `text` on `row`.
+    pub fn new(row: DisplayRow, text: impl Into<String>) -> Self {
+        Self {
+            row,
+            text: text.into(),
+        }
+    }
+}
+
+impl Command for DisplayLine {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![US, b'$', 1, self.row as u8];
+        let mut text = encode_lossy(&self.text, CodePage::default());
+        text.resize(DISPLAY_COLUMNS.into(), b' ');
+        for byte in &mut text {
+            if byte.is_ascii_control() {
+                *byte = b'?';
+            }
+        }
+        bytes.extend(text);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_display_wraps_in_peripheral_selection() {
+        assert_eq!(ToDisplay(ClearDisplay).encode(), [ESC, b'=', 2, FF, ESC, b'=', 1]);
+        assert_eq!(
+            ToDisplay((CursorHome, SetBrightness(Brightness::Dim))).encode(),
+            [ESC, b'=', 2, HOM, US, b'X', 1, ESC, b'=', 1]
+        );
+    }
+
+    #[test]
+    fn move_cursor_validates_position() {
+        assert_eq!(MoveCursor::new(20, 2).unwrap().encode(), [US, b'$', 20, 2]);
+        assert!(matches!(
+            MoveCursor::new(0, 1),
+            Err(ValidationError::OutOfRange {
+                name: "column",
+                ..
+            })
+        ));
+        assert!(matches!(
+            MoveCursor::new(1, 3),
+            Err(ValidationError::OutOfRange {
+                name: "row",
+                value: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn display_line_fills_the_row() {
+        let bytes = DisplayLine::new(DisplayRow::Lower, "Total €5").encode();
+        assert_eq!(&bytes[..4], [US, b'$', 1, 2]);
+        assert_eq!(&bytes[4..], b"Total ?5            ");
+
+        let long = DisplayLine::new(DisplayRow::Upper, "A".repeat(30)).encode();
+        assert_eq!(long.len(), 4 + usize::from(DISPLAY_COLUMNS));
+
+        let bytes = DisplayLine::new(DisplayRow::Upper, "A\x0cB\x1b@").encode();
+        assert_eq!(&bytes[4..], b"A?B?@               ");
+    }
+
+    #[test]
+    fn simple_display_commands() {
+        assert_eq!(InitializeDisplay.encode(), [ESC, b'@']);
+        assert_eq!(ClearLine.encode(), [CAN]);
+        assert_eq!(ShowCursor(false).encode(), [US, b'C', 0]);
+        assert_eq!(SetBrightness::default().encode(), [US, b'X', 4]);
+    }
+}
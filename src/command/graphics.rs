@@ -0,0 +1,382 @@
+//! NV graphics commands.
+//!
+//! Raster graphics stored in the printer's non-volatile graphics memory
+//! under two-byte key codes, using the `GS ( L` / `GS 8 L` functions. See
+//! [`NvGraphics`](crate::printer::NvGraphics) for a manager that drives
+//! these from a [`Printer`](crate::printer::Printer).
+//!
+//! Writing NV memory wears it out; the manual recommends no more than 10
+//! writes a day. Define logos once at setup, not per receipt.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::image::PrintRasterImage;
+use super::{Command, GS, QueryCommand};
+use crate::error::{StatusParseError, ValidationError};
+
+/// Key code identifying an NV graphic.
+///
+/// Both bytes are printable ASCII (32-126), e.g. `KeyCode::new(b'L', b'1')`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "[u8; 2]", into = "[u8; 2]"))]
+pub struct KeyCode([u8; 2]);
+
+impl KeyCode {
+    /// Create a key code from its two bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if either byte is outside
+    /// 32-126.
+    pub fn new(kc1: u8, kc2: u8) -> Result<Self, ValidationError> {
+        for (name, value) in [("kc1", kc1), ("kc2", kc2)] {
+            if !(32..=126).contains(&value) {
+                return Err(ValidationError::OutOfRange {
+                    name,
+                    value: u16::from(value),
+                    min: 32,
+                    max: 126,
+                });
+            }
+        }
+        Ok(Self([kc1, kc2]))
+    }
+
+    /// The two key code bytes.
+    pub fn bytes(self) -> [u8; 2] {
+        self.0
+    }
+}
+
+impl TryFrom<[u8; 2]> for KeyCode {
+    type Error = ValidationError;
+
+    fn try_from([kc1, kc2]: [u8; 2]) -> Result<Self, Self::Error> {
+        Self::new(kc1, kc2)
+    }
+}
+
+impl From<KeyCode> for [u8; 2] {
+    fn from(key: KeyCode) -> Self {
+        key.0
+    }
+}
+
+/// Encode `GS ( L`, or `GS 8 L` when the parameter is too long.
+fn encode_graphics(function: u8, parameter: &[u8]) -> Vec<u8> {
+    let len = parameter.len() + 2;
+    let mut bytes = Vec::with_capacity(len + 7);
+    if let Ok(len) = u16::try_from(len) {
+        bytes.extend_from_slice(&[GS, b'(', b'L']);
+        bytes.extend_from_slice(&len.to_le_bytes());
+    } else {
+        bytes.extend_from_slice(&[GS, b'8', b'L']);
+        bytes.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+    bytes.extend_from_slice(&[48, function]);
+    bytes.extend_from_slice(parameter);
+    bytes
+}
+
+/// Parse a `0x37 flag digits... NUL` capacity response.
+fn parse_capacity(bytes: &[u8], flag: u8) -> Result<u32, StatusParseError> {
+    let body = match bytes {
+        [] => return Err(StatusParseError::EmptyResponse),
+        [0x37, f, rest @ ..] if *f == flag => rest,
+        [0x37, f, ..] => return Err(StatusParseError::InvalidStatus(*f)),
+        [first, ..] => return Err(StatusParseError::InvalidStatus(*first)),
+    };
+    let digits = body.strip_suffix(&[0x00]).ok_or(StatusParseError::Incomplete)?;
+    core::str::from_utf8(digits)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(StatusParseError::Truncated)
+}
+
+/// Query the total NV graphics memory capacity in bytes.
+///
+/// ESC/POS: `GS ( L 2 0 48 48` (function 48)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryNvCapacity;
+
+impl Command for QueryNvCapacity {
+    fn encode(&self) -> Vec<u8> {
+        encode_graphics(48, &[])
+    }
+}
+
+impl QueryCommand for QueryNvCapacity {
+    type Response = u32;
+
+    fn parse_response(&self, bytes: &[u8]) -> Result<Self::Response, StatusParseError> {
+        parse_capacity(bytes, 0x30)
+    }
+}
+
+/// Query the unused NV graphics memory in bytes.
+///
+/// ESC/POS: `GS ( L 2 0 48 51` (function 51)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryNvRemaining;
+
+impl Command for QueryNvRemaining {
+    fn encode(&self) -> Vec<u8> {
+        encode_graphics(51, &[])
+    }
+}
+
+impl QueryCommand for QueryNvRemaining {
+    type Response = u32;
+
+    fn parse_response(&self, bytes: &[u8]) -> Result<Self::Response, StatusParseError> {
+        parse_capacity(bytes, 0x31)
+    }
+}
+
+/// One block of the defined key code list.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyCodeBlock {
+    /// Key codes in this block, at most 40.
+    pub keys: Vec<KeyCode>,
+    /// Whether another block follows.
+    ///
+    /// The printer waits for ACK before sending it.
+    pub more: bool,
+}
+
+/// Query the key codes of the defined NV graphics.
+///
+/// ESC/POS: `GS ( L 4 0 48 64 75 67` (function 64)
+///
+/// The printer sends up to 40 key codes per block and waits for the host
+/// to answer each block with ACK (next block or finish), NAK (resend) or
+/// CAN (stop).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryNvKeyCodes;
+
+impl Command for QueryNvKeyCodes {
+    fn encode(&self) -> Vec<u8> {
+        encode_graphics(64, b"KC")
+    }
+}
+
+impl QueryCommand for QueryNvKeyCodes {
+    type Response = KeyCodeBlock;
+
+    fn parse_response(&self, bytes: &[u8]) -> Result<Self::Response, StatusParseError> {
+        let (more, body) = match bytes {
+            [] => return Err(StatusParseError::EmptyResponse),
+            [0x37, 0x72, 0x40, rest @ ..] => (false, rest),
+            [0x37, 0x72, 0x41, rest @ ..] => (true, rest),
+            [0x37, 0x72, status, ..] => return Err(StatusParseError::InvalidStatus(*status)),
+            [first, ..] => return Err(StatusParseError::InvalidStatus(*first)),
+        };
+        let body = body.strip_suffix(&[0x00]).ok_or(StatusParseError::Incomplete)?;
+        if body.len() % 2 != 0 {
+            return Err(StatusParseError::Truncated);
+        }
+        let keys = body.chunks(2).map(|pair| KeyCode([pair[0], pair[1]])).collect();
+        Ok(KeyCodeBlock {
+            keys,
+            more,
+        })
+    }
+}
+
+/// Delete every NV graphic.
+///
+/// ESC/POS: `GS ( L 5 0 48 65 67 76 82` (function 65)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeleteAllNvGraphics;
+
+impl Command for DeleteAllNvGraphics {
+    fn encode(&self) -> Vec<u8> {
+        encode_graphics(65, b"CLR")
+    }
+}
+
+/// Delete the NV graphic stored under a key code.
+///
+/// ESC/POS: `GS ( L 4 0 48 66 kc1 kc2` (function 66)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeleteNvGraphics(pub KeyCode);
+
+impl Command for DeleteNvGraphics {
+    fn encode(&self) -> Vec<u8> {
+        encode_graphics(66, &self.0.bytes())
+    }
+}
+
+/// Store a raster image in NV graphics memory.
+///
+/// ESC/POS: `GS ( L pL pH 48 67 48 kc1 kc2 1 xL xH yL yH 49 d1...dk`
+/// (function 67)
+///
+/// Uses `GS 8 L` for images over 64 KB. Any graphic already stored under
+/// the key is replaced. The raster mode is ignored; scale when printing with
+/// [`PrintNvGraphics`] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefineNvGraphics {
+    /// Key code to store the image under.
+    pub key: KeyCode,
+    /// The image.
+    pub image: PrintRasterImage,
+}
+
+impl Command for DefineNvGraphics {
+    fn encode(&self) -> Vec<u8> {
+        let [kc1, kc2] = self.key.bytes();
+        let width_dots = self.image.width_bytes.saturating_mul(8);
+        let mut parameter = vec![48, kc1, kc2, 1];
+        parameter.extend_from_slice(&width_dots.to_le_bytes());
+        parameter.extend_from_slice(&self.image.height_dots.to_le_bytes());
+        parameter.push(49);
+        parameter.extend_from_slice(&self.image.data);
+        encode_graphics(67, &parameter)
+    }
+}
+
+/// Print the NV graphic stored under a key code.
+///
+/// ESC/POS: `GS ( L 6 0 48 69 kc1 kc2 x y` (function 69)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintNvGraphics {
+    /// Key code of the graphic.
+    pub key: KeyCode,
+    /// Print at double width.
+    pub double_width: bool,
+    /// Print at double height.
+    pub double_height: bool,
+}
+
+impl PrintNvGraphics {
+    /// Print the graphic at normal size.
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            double_width: false,
+            double_height: false,
+        }
+    }
+}
+
+impl Command for PrintNvGraphics {
+    fn encode(&self) -> Vec<u8> {
+        let [kc1, kc2] = self.key.bytes();
+        let scale = |double| {
+            if double {
+                2
+            } else {
+                1
+            }
+        };
+        encode_graphics(69, &[kc1, kc2, scale(self.double_width), scale(self.double_height)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> KeyCode {
+        KeyCode::new(b'L', b'1').unwrap()
+    }
+
+    #[test]
+    fn key_codes_are_printable_ascii() {
+        assert_eq!(key().bytes(), *b"L1");
+        assert!(matches!(
+            KeyCode::new(b'A', 0x1F),
+            Err(ValidationError::OutOfRange {
+                name: "kc2",
+                value: 0x1F,
+                ..
+            })
+        ));
+        assert!(KeyCode::new(127, b'A').is_err());
+    }
+
+    #[test]
+    fn management_commands_encode() {
+        assert_eq!(QueryNvCapacity.encode(), [GS, b'(', b'L', 2, 0, 48, 48]);
+        assert_eq!(QueryNvRemaining.encode(), [GS, b'(', b'L', 2, 0, 48, 51]);
+        assert_eq!(QueryNvKeyCodes.encode(), [GS, b'(', b'L', 4, 0, 48, 64, 75, 67]);
+        assert_eq!(DeleteAllNvGraphics.encode(), [GS, b'(', b'L', 5, 0, 48, 65, 67, 76, 82]);
+        assert_eq!(DeleteNvGraphics(key()).encode(), [GS, b'(', b'L', 4, 0, 48, 66, b'L', b'1']);
+        let print = PrintNvGraphics {
+            double_height: true,
+            ..PrintNvGraphics::new(key())
+        };
+        assert_eq!(print.encode(), [GS, b'(', b'L', 6, 0, 48, 69, b'L', b'1', 1, 2]);
+    }
+
+    #[test]
+    fn define_encodes_dimensions_and_data() {
+        let image = PrintRasterImage::new(2, 3, vec![0xAA; 6]);
+        let bytes = DefineNvGraphics {
+            key: key(),
+            image,
+        }
+        .encode();
+        assert_eq!(
+            bytes[..17],
+            [GS, b'(', b'L', 17, 0, 48, 67, 48, b'L', b'1', 1, 16, 0, 3, 0, 49, 0xAA]
+        );
+        assert_eq!(bytes.len(), 7 + 15);
+    }
+
+    #[test]
+    fn large_define_uses_gs_8_l() {
+        let image = PrintRasterImage::new(72, 1000, vec![0; 72_000]);
+        let bytes = DefineNvGraphics {
+            key: key(),
+            image,
+        }
+        .encode();
+        assert_eq!(bytes[..9], [GS, b'8', b'L', 0x4B, 0x19, 0x01, 0x00, 48, 67]);
+        assert_eq!(bytes.len(), 9 + 72_009);
+    }
+
+    #[test]
+    fn parses_capacity_responses() {
+        assert_eq!(QueryNvCapacity.parse_response(b"\x370262144\x00").unwrap(), 262_144);
+        assert_eq!(QueryNvRemaining.parse_response(b"\x371512\x00").unwrap(), 512);
+        assert!(matches!(
+            QueryNvRemaining.parse_response(b"\x370512\x00"),
+            Err(StatusParseError::InvalidStatus(0x30))
+        ));
+        assert!(matches!(
+            QueryNvCapacity.parse_response(b"\x3701"),
+            Err(StatusParseError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn parses_key_code_blocks() {
+        let block = QueryNvKeyCodes.parse_response(b"\x37\x72\x41L1L2\x00").unwrap();
+        assert_eq!(block.keys, [key(), KeyCode::new(b'L', b'2').unwrap()]);
+        assert!(block.more);
+
+        let empty = QueryNvKeyCodes.parse_response(b"\x37\x72\x40\x00").unwrap();
+        assert_eq!(empty, KeyCodeBlock::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn key_codes_deserialize_validated() {
+        let key = KeyCode::new(b'L', b'1').unwrap();
+        assert_eq!(serde_json::to_string(&key).unwrap(), "[76,49]");
+        assert_eq!(serde_json::from_str::<KeyCode>("[76,49]").unwrap(), key);
+        assert!(serde_json::from_str::<KeyCode>("[76,10]").is_err());
+    }
+}
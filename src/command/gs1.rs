@@ -0,0 +1,621 @@
+//! GS1 Application Identifier support.
+//!
+//! Parses and validates GS1 element strings written in the human-readable
+//! form `(01)09501101530003(17)250101(10)ABC123`, shared by the GS1 DataBar
+//! and GS1-128 encoders.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::SourceSpan;
+
+use super::barcode::{Code128Builder, PrintBarcode};
+use crate::error::{BarcodeError, Gs1Error};
+
+/// Character class allowed in an AI value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    /// Digits only.
+    Numeric,
+    /// GS1 AI encodable character set 82 (printable ASCII subset).
+    Alphanumeric,
+    /// Six-digit YYMMDD date.
+    Date,
+}
+
+/// Format specification for one Application Identifier.
+#[derive(Debug, Clone, Copy)]
+struct AiSpec {
+    /// AI digits, or a prefix when `ai_len` is longer.
+    prefix: &'static str,
+    /// Total number of AI digits.
+    ai_len: usize,
+    /// Allowed characters.
+    charset: Charset,
+    /// Minimum value length.
+    min: usize,
+    /// Maximum value length.
+    max: usize,
+}
+
+const fn spec(
+    prefix: &'static str,
+    ai_len: usize,
+    charset: Charset,
+    min: usize,
+    max: usize,
+) -> AiSpec {
+    AiSpec {
+        prefix,
+        ai_len,
+        charset,
+        min,
+        max,
+    }
+}
+
+/// Supported Application Identifiers.
+///
+/// Covers the AIs commonly found on retail and logistics labels. Entries
+/// with `ai_len` longer than the prefix match any trailing digits (e.g.
+/// `310n` net weight with `n` decimal places).
+const AI_TABLE: &[AiSpec] = &[
+    spec("00", 2, Charset::Numeric, 18, 18),
+    spec("01", 2, Charset::Numeric, 14, 14),
+    spec("02", 2, Charset::Numeric, 14, 14),
+    spec("10", 2, Charset::Alphanumeric, 1, 20),
+    spec("11", 2, Charset::Date, 6, 6),
+    spec("12", 2, Charset::Date, 6, 6),
+    spec("13", 2, Charset::Date, 6, 6),
+    spec("15", 2, Charset::Date, 6, 6),
+    spec("16", 2, Charset::Date, 6, 6),
+    spec("17", 2, Charset::Date, 6, 6),
+    spec("20", 2, Charset::Numeric, 2, 2),
+    spec("21", 2, Charset::Alphanumeric, 1, 20),
+    spec("22", 2, Charset::Alphanumeric, 1, 20),
+    spec("30", 2, Charset::Numeric, 1, 8),
+    spec("37", 2, Charset::Numeric, 1, 8),
+    spec("240", 3, Charset::Alphanumeric, 1, 30),
+    spec("241", 3, Charset::Alphanumeric, 1, 30),
+    spec("250", 3, Charset::Alphanumeric, 1, 30),
+    spec("400", 3, Charset::Alphanumeric, 1, 30),
+    spec("401", 3, Charset::Alphanumeric, 1, 30),
+    spec("402", 3, Charset::Numeric, 17, 17),
+    spec("403", 3, Charset::Alphanumeric, 1, 30),
+    spec("410", 3, Charset::Numeric, 13, 13),
+    spec("411", 3, Charset::Numeric, 13, 13),
+    spec("412", 3, Charset::Numeric, 13, 13),
+    spec("413", 3, Charset::Numeric, 13, 13),
+    spec("414", 3, Charset::Numeric, 13, 13),
+    spec("415", 3, Charset::Numeric, 13, 13),
+    spec("420", 3, Charset::Alphanumeric, 1, 20),
+    spec("421", 3, Charset::Alphanumeric, 4, 12),
+    spec("422", 3, Charset::Numeric, 3, 3),
+    spec("31", 4, Charset::Numeric, 6, 6),
+    spec("32", 4, Charset::Numeric, 6, 6),
+    spec("33", 4, Charset::Numeric, 6, 6),
+    spec("34", 4, Charset::Numeric, 6, 6),
+    spec("35", 4, Charset::Numeric, 6, 6),
+    spec("36", 4, Charset::Numeric, 6, 6),
+    spec("390", 4, Charset::Numeric, 1, 15),
+    spec("392", 4, Charset::Numeric, 1, 15),
+    spec("393", 4, Charset::Numeric, 4, 18),
+    spec("8005", 4, Charset::Numeric, 6, 6),
+    spec("8020", 4, Charset::Alphanumeric, 1, 25),
+    spec("90", 2, Charset::Alphanumeric, 1, 30),
+    spec("91", 2, Charset::Alphanumeric, 1, 90),
+    spec("92", 2, Charset::Alphanumeric, 1, 90),
+    spec("93", 2, Charset::Alphanumeric, 1, 90),
+    spec("94", 2, Charset::Alphanumeric, 1, 90),
+    spec("95", 2, Charset::Alphanumeric, 1, 90),
+    spec("96", 2, Charset::Alphanumeric, 1, 90),
+    spec("97", 2, Charset::Alphanumeric, 1, 90),
+    spec("98", 2, Charset::Alphanumeric, 1, 90),
+    spec("99", 2, Charset::Alphanumeric, 1, 90),
+];
+
+/// AI prefixes with a predefined length that never need an FNC1 separator.
+const PREDEFINED_LENGTH: &[&str] = &[
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+fn lookup(ai: &str) -> Option<&'static AiSpec> {
+    AI_TABLE.iter().find(|spec| {
+        spec.ai_len == ai.len()
+            && ai.starts_with(spec.prefix)
+            && ai.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+/// A single validated GS1 element: an Application Identifier and its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gs1Element {
+    ai: String,
+    value: String,
+    variable_length: bool,
+}
+
+impl Gs1Element {
+    /// Create a validated element.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the AI is unknown or the value does not match
+    /// its format (length, character set, date validity, or check digit).
+    pub fn new(ai: impl Into<String>, value: impl Into<String>) -> Result<Self, Gs1Error> {
+        let ai = ai.into();
+        let value = value.into();
+        let spec = lookup(&ai).ok_or_else(|| Gs1Error::UnknownApplicationIdentifier(ai.clone()))?;
+
+        let len = value.len();
+        if len < spec.min || len > spec.max {
+            return Err(Gs1Error::InvalidLength {
+                ai,
+                actual: len,
+                min: spec.min,
+                max: spec.max,
+            });
+        }
+
+        let valid_chars = match spec.charset {
+            Charset::Numeric | Charset::Date => value.bytes().all(|b| b.is_ascii_digit()),
+            Charset::Alphanumeric => value.bytes().all(is_cset82),
+        };
+        if !valid_chars {
+            return Err(Gs1Error::InvalidValue {
+                ai,
+                value,
+                reason: match spec.charset {
+                    Charset::Alphanumeric => "contains characters outside GS1 character set 82",
+                    _ => "must contain digits only",
+                },
+            });
+        }
+
+        if spec.charset == Charset::Date && !is_valid_date(value.as_bytes()) {
+            return Err(Gs1Error::InvalidValue {
+                ai,
+                value,
+                reason: "is not a valid YYMMDD date",
+            });
+        }
+
+        // GTIN-bearing and SSCC AIs carry a mod-10 check digit
+        if matches!(ai.as_str(), "00" | "01" | "02" | "410" | "411" | "412" | "413" | "414" | "415")
+        {
+            verify_check_digit(value.as_bytes())?;
+        }
+
+        let variable_length = !PREDEFINED_LENGTH.contains(&&ai[..2]);
+        Ok(Self {
+            ai,
+            value,
+            variable_length,
+        })
+    }
+
+    /// The Application Identifier digits.
+    pub fn ai(&self) -> &str {
+        &self.ai
+    }
+
+    /// The element value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Whether the AI is outside the GS1 predefined-length table and needs
+    /// an FNC1 separator when followed by another element.
+    pub fn is_variable_length(&self) -> bool {
+        self.variable_length
+    }
+}
+
+/// Parse a human-readable element string such as `(01)09501101530003(10)AB1`.
+///
+/// Each AI is two to four digits in parentheses. A value runs up to the
+/// next such bracket, so values may contain other parentheses, as in
+/// `(10)LOT(A)`.
+///
+/// # Errors
+///
+/// Returns [`Gs1Error::Malformed`] if the string is not a sequence of
+/// `(AI)value` pairs, or any error from [`Gs1Element::new`].
+pub fn parse_element_string(input: &str) -> Result<Vec<Gs1Element>, Gs1Error> {
+    let malformed = |offset: usize, len: usize| Gs1Error::Malformed {
+        data: input.to_string(),
+        span: SourceSpan::from((offset, len.max(1))),
+    };
+
+    let mut elements = Vec::new();
+    let mut rest = input;
+    let mut offset = 0;
+
+    if rest.is_empty() {
+        return Err(malformed(0, 0));
+    }
+
+    while !rest.is_empty() {
+        let bracket = ai_bracket_len(rest).ok_or_else(|| {
+            malformed(offset, rest.find(')').map_or(rest.len(), |close| close + 1))
+        })?;
+        let ai = &rest[1..bracket - 1];
+        let value_end = rest[bracket..]
+            .match_indices('(')
+            .map(|(i, _)| bracket + i)
+            .find(|&i| ai_bracket_len(&rest[i..]).is_some())
+            .unwrap_or(rest.len());
+        let value = &rest[bracket..value_end];
+        if value.is_empty() {
+            return Err(malformed(offset, value_end));
+        }
+
+        elements.push(Gs1Element::new(ai, value)?);
+        offset += value_end;
+        rest = &rest[value_end..];
+    }
+
+    Ok(elements)
+}
+
+/// Length of the `(AI)` bracket starting `input`: two to four digits in
+/// parentheses.
+fn ai_bracket_len(input: &str) -> Option<usize> {
+    let digits = input.strip_prefix('(')?.bytes().take_while(u8::is_ascii_digit).count();
+    let closed = input.as_bytes().get(digits + 1) == Some(&b')');
+    ((2..=4).contains(&digits) && closed).then_some(digits + 2)
+}
+
+/// Builder for GS1-128 (EAN-128) barcodes.
+///
+/// Elements are validated as they are added. The encoded data starts with
+/// FNC1 and inserts an FNC1 separator after each element whose AI is not
+/// of predefined length, unless it is the last one.
+///
+/// ```
+/// use bixolon::command::gs1::Gs1128Builder;
+///
+/// let barcode = Gs1128Builder::new()
+///     .gtin("09501101530003")?
+///     .expiry("251231")?
+///     .batch("AB12")?
+///     .build()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gs1128Builder {
+    elements: Vec<Gs1Element>,
+}
+
+impl Gs1128Builder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an arbitrary element.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is invalid for the AI.
+    pub fn element(mut self, ai: &str, value: &str) -> Result<Self, Gs1Error> {
+        self.elements.push(Gs1Element::new(ai, value)?);
+        Ok(self)
+    }
+
+    /// Add a Serial Shipping Container Code, AI (00).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not 18 digits with a valid
+    /// check digit.
+    pub fn sscc(self, sscc: &str) -> Result<Self, Gs1Error> {
+        self.element("00", sscc)
+    }
+
+    /// Add a Global Trade Item Number, AI (01).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not 14 digits with a valid
+    /// check digit.
+    pub fn gtin(self, gtin: &str) -> Result<Self, Gs1Error> {
+        self.element("01", gtin)
+    }
+
+    /// Add a batch or lot number, AI (10).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is empty, longer than 20
+    /// characters, or outside GS1 character set 82.
+    pub fn batch(self, batch: &str) -> Result<Self, Gs1Error> {
+        self.element("10", batch)
+    }
+
+    /// Add a production date (YYMMDD), AI (11).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not a valid YYMMDD date.
+    pub fn production_date(self, date: &str) -> Result<Self, Gs1Error> {
+        self.element("11", date)
+    }
+
+    /// Add a best-before date (YYMMDD), AI (15).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not a valid YYMMDD date.
+    pub fn best_before(self, date: &str) -> Result<Self, Gs1Error> {
+        self.element("15", date)
+    }
+
+    /// Add an expiration date (YYMMDD), AI (17).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not a valid YYMMDD date.
+    pub fn expiry(self, date: &str) -> Result<Self, Gs1Error> {
+        self.element("17", date)
+    }
+
+    /// Add a serial number, AI (21).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is empty, longer than 20
+    /// characters, or outside GS1 character set 82.
+    pub fn serial(self, serial: &str) -> Result<Self, Gs1Error> {
+        self.element("21", serial)
+    }
+
+    /// Add a count of items, AI (30).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the value is not 1-8 digits.
+    pub fn count(self, count: &str) -> Result<Self, Gs1Error> {
+        self.element("30", count)
+    }
+
+    /// The validated elements.
+    pub fn elements(&self) -> &[Gs1Element] {
+        &self.elements
+    }
+
+    /// Human-readable form, e.g. `(01)09501101530003(10)AB12`.
+    pub fn human_readable(&self) -> String {
+        self.elements.iter().map(|e| format!("({}){}", e.ai(), e.value())).collect()
+    }
+
+    /// Assemble the CODE128 data with FNC1 characters.
+    pub fn code128(&self) -> Code128Builder {
+        let mut builder = Code128Builder::new().fnc1();
+        for (i, element) in self.elements.iter().enumerate() {
+            builder = builder.text(element.ai()).text(element.value());
+            if element.is_variable_length() && i + 1 < self.elements.len() {
+                builder = builder.fnc1();
+            }
+        }
+        builder
+    }
+
+    /// Build the barcode command.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BarcodeError::InvalidLength`] if no elements were added or
+    /// the encoded data is too long for a single barcode.
+    pub fn build(&self) -> Result<PrintBarcode, BarcodeError> {
+        if self.elements.is_empty() {
+            return Err(BarcodeError::InvalidLength {
+                system: "GS1-128",
+                actual: 0,
+                min: 1,
+                max: 48,
+            });
+        }
+        self.code128().build()
+    }
+}
+
+/// Compute the GS1 mod-10 check digit for a string of digits.
+///
+/// The digits are weighted 3, 1, 3, ... from the right. Returns `None`
+/// if `digits` contains non-digit characters.
+pub fn check_digit(digits: &[u8]) -> Option<u8> {
+    let mut sum = 0u32;
+    for (i, &byte) in digits.iter().rev().enumerate() {
+        if !byte.is_ascii_digit() {
+            return None;
+        }
+        let weight = if i % 2 == 0 {
+            3
+        } else {
+            1
+        };
+        sum += u32::from(byte - b'0') * weight;
+    }
+    Some(((10 - sum % 10) % 10) as u8)
+}
+
+/// Verify that the last digit of `digits` is the correct check digit.
+///
+/// # Errors
+///
+/// Returns [`Gs1Error::CheckDigitMismatch`] if the check digit is wrong.
+pub fn verify_check_digit(digits: &[u8]) -> Result<(), Gs1Error> {
+    let Some((&last, body)) = digits.split_last() else {
+        return Ok(());
+    };
+    let expected = check_digit(body).unwrap_or(0);
+    let actual = last.wrapping_sub(b'0');
+    if expected != actual {
+        return Err(Gs1Error::CheckDigitMismatch {
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Whether a byte belongs to GS1 AI encodable character set 82.
+fn is_cset82(b: u8) -> bool {
+    b.is_ascii_alphanumeric()
+        || matches!(
+            b,
+            b'!' | b'"'
+                | b'%'
+                | b'&'
+                | b'\''
+                | b'('
+                | b')'
+                | b'*'
+                | b'+'
+                | b','
+                | b'-'
+                | b'.'
+                | b'/'
+                | b':'
+                | b';'
+                | b'<'
+                | b'='
+                | b'>'
+                | b'?'
+                | b'_'
+        )
+}
+
+fn is_valid_date(digits: &[u8]) -> bool {
+    let two = |i: usize| (digits[i] - b'0') * 10 + (digits[i + 1] - b'0');
+    let month = two(2);
+    let day = two(4);
+    // Day 00 means "end of month" in GS1 dates
+    (1..=12).contains(&month) && day <= 31
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_digit_computes_gtin() {
+        // GTIN-13 5901234123457
+        assert_eq!(check_digit(b"590123412345"), Some(7));
+        // UPC-A 036000291452
+        assert_eq!(check_digit(b"03600029145"), Some(2));
+        assert_eq!(check_digit(b"12A"), None);
+    }
+
+    #[test]
+    fn verify_check_digit_detects_mismatch() {
+        assert!(verify_check_digit(b"5901234123457").is_ok());
+        assert!(matches!(
+            verify_check_digit(b"5901234123458"),
+            Err(Gs1Error::CheckDigitMismatch {
+                expected: 7,
+                actual: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn element_validates_fixed_length() {
+        assert!(Gs1Element::new("01", "09501101530003").is_ok());
+        assert!(matches!(
+            Gs1Element::new("01", "0950110153000"),
+            Err(Gs1Error::InvalidLength { .. })
+        ));
+    }
+
+    #[test]
+    fn element_validates_date() {
+        assert!(Gs1Element::new("17", "250131").is_ok());
+        assert!(Gs1Element::new("17", "251300").is_err());
+    }
+
+    #[test]
+    fn element_rejects_unknown_ai() {
+        assert!(matches!(
+            Gs1Element::new("05", "123"),
+            Err(Gs1Error::UnknownApplicationIdentifier(_))
+        ));
+    }
+
+    #[test]
+    fn element_matches_four_digit_ai() {
+        let element = Gs1Element::new("3103", "001250").unwrap();
+        assert!(!element.is_variable_length());
+    }
+
+    #[test]
+    fn parse_element_string_splits_elements() {
+        let elements = parse_element_string("(01)09501101530003(17)250101(10)AB-12").unwrap();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[0].ai(), "01");
+        assert_eq!(elements[2].value(), "AB-12");
+        assert!(elements[2].is_variable_length());
+    }
+
+    #[test]
+    fn parse_element_string_rejects_malformed() {
+        assert!(matches!(parse_element_string("01095011"), Err(Gs1Error::Malformed { .. })));
+        assert!(matches!(parse_element_string("(01"), Err(Gs1Error::Malformed { .. })));
+        assert!(matches!(parse_element_string(""), Err(Gs1Error::Malformed { .. })));
+        assert!(matches!(parse_element_string("(1)A"), Err(Gs1Error::Malformed { .. })));
+        assert!(matches!(parse_element_string("(AB)1"), Err(Gs1Error::Malformed { .. })));
+        assert!(matches!(parse_element_string("(10)(17)250101"), Err(Gs1Error::Malformed { .. })));
+    }
+
+    #[test]
+    fn parse_element_string_keeps_parentheses_in_values() {
+        let elements = parse_element_string("(10)LOT(A)(B1(17)250101(21)(9)").unwrap();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[0].value(), "LOT(A)(B1");
+        assert_eq!(elements[1].ai(), "17");
+        assert_eq!(elements[2].value(), "(9)");
+    }
+
+    #[test]
+    fn predefined_length_ais_need_no_separator() {
+        assert!(!Gs1Element::new("17", "250101").unwrap().is_variable_length());
+        // Fixed length, but not in the predefined-length table
+        assert!(Gs1Element::new("8005", "000123").unwrap().is_variable_length());
+    }
+
+    #[test]
+    fn gs1_128_inserts_fnc1_after_variable_fields() {
+        let builder = Gs1128Builder::new()
+            .gtin("09501101530003")
+            .unwrap()
+            .batch("AB1")
+            .unwrap()
+            .expiry("251231")
+            .unwrap();
+        let data = builder.code128().encode_data().unwrap();
+
+        // FNC1, (01) + GTIN in Code C, batch in Code B, FNC1, (17) + date
+        let mut expected = vec![b'{', b'C', b'{', b'1', 1, 9, 50, 11, 1, 53, 0, 3, 10];
+        expected.extend_from_slice(b"{BAB1{1{C");
+        expected.extend_from_slice(&[17, 25, 12, 31]);
+        assert_eq!(data, expected);
+        assert_eq!(builder.human_readable(), "(01)09501101530003(10)AB1(17)251231");
+    }
+
+    #[test]
+    fn gs1_128_omits_trailing_fnc1() {
+        let builder = Gs1128Builder::new().batch("X").unwrap();
+        let data = builder.code128().encode_data().unwrap();
+        assert!(!data.ends_with(b"{1"));
+    }
+
+    #[test]
+    fn gs1_128_validates_fields() {
+        assert!(Gs1128Builder::new().gtin("09501101530004").is_err());
+        assert!(Gs1128Builder::new().expiry("251301").is_err());
+        assert!(Gs1128Builder::new().build().is_err());
+    }
+}
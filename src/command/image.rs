@@ -1,10 +1,24 @@
 //! Image commands.
 
-use super::{Command, ESC, GS};
+#[cfg(feature = "image")]
+pub mod raster;
+#[cfg(feature = "svg")]
+pub mod svg;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use super::basic::LineFeed;
+use super::paper::FeedPaper;
+use super::spacing::{SetDefaultLineSpacing, SetLineSpacing};
+use super::{Command, Dialect, ESC, GS, StaticCommand};
+use crate::error::ValidationError;
 
 /// Bit image mode.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BitImageMode {
     /// 8-dot single density (60 dpi V, 90 dpi H).
     SingleDensity8 = 0,
@@ -21,6 +35,7 @@ pub enum BitImageMode {
 ///
 /// ESC/POS: `ESC * m nL nH d1...dk`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectBitImageMode {
     /// Image mode.
     pub mode: BitImageMode,
@@ -43,6 +58,7 @@ impl Command for SelectBitImageMode {
 /// Raster image mode.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RasterImageMode {
     /// Normal (180 dpi).
     #[default]
@@ -55,10 +71,29 @@ pub enum RasterImageMode {
     Quadruple = 3,
 }
 
+impl RasterImageMode {
+    /// The mode with width and height scaling swapped.
+    fn transposed(self) -> Self {
+        match self {
+            Self::DoubleWidth => Self::DoubleHeight,
+            Self::DoubleHeight => Self::DoubleWidth,
+            other => other,
+        }
+    }
+}
+
 /// Print raster bit image.
 ///
 /// ESC/POS: `GS v 0 m xL xH yL yH d1...dk`
+///
+/// Epson: `GS ( L` (or `GS 8 L` for large images) storing the image in the
+/// print buffer, then `GS ( L` function 50 to print it.
+///
+/// Star: `ESC * r A`, one `b n1 n2 d1...dk` per row, then `ESC * r B`.
+/// Star raster mode has no scaling, so double width and height modes print
+/// at normal size.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrintRasterImage {
     /// Image mode/density.
     pub mode: RasterImageMode,
@@ -81,34 +116,542 @@ impl PrintRasterImage {
         }
     }
 
+    /// Create a raster image, checking its width and data length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::ImageTooLarge`] if `width_bytes` is over
+    /// [`MAX_WIDTH_BYTES`](Self::MAX_WIDTH_BYTES), or
+    /// [`ValidationError::ImageDataLength`] unless `data` holds exactly
+    /// `width_bytes * height_dots` bytes.
+    pub fn try_new(
+        width_bytes: u16,
+        height_dots: u16,
+        data: Vec<u8>,
+    ) -> Result<Self, ValidationError> {
+        let image = Self::new(width_bytes, height_dots, data);
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Widest image, in bytes, whose width in dots fits the `GS ( L`
+    /// parameters.
+    pub const MAX_WIDTH_BYTES: u16 = u16::MAX / 8;
+
+    /// Check the width and that the data length matches the dimensions.
+    ///
+    /// A mismatch shifts every following row and leaves the printer
+    /// consuming the next commands as image data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::ImageTooLarge`] if `width_bytes` is over
+    /// [`MAX_WIDTH_BYTES`](Self::MAX_WIDTH_BYTES), or
+    /// [`ValidationError::ImageDataLength`] with the expected and actual
+    /// sizes.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_dimensions(self.width_bytes, self.height_dots, &self.data)
+    }
+
     /// Set the raster image mode.
     pub fn with_mode(mut self, mode: RasterImageMode) -> Self {
         self.mode = mode;
         self
     }
+
+    /// Most image data sent in one band by [`Printer::print_image`].
+    ///
+    /// Keeps each command well inside the printer's receive buffer.
+    ///
+    /// [`Printer::print_image`]: crate::printer::Printer::print_image
+    pub const BAND_BYTES: usize = 4096;
+
+    /// Tallest band that stays within [`BAND_BYTES`](Self::BAND_BYTES).
+    pub fn band_height(&self) -> u16 {
+        let rows = Self::BAND_BYTES / usize::from(self.width_bytes.max(1));
+        rows.clamp(1, usize::from(u16::MAX)) as u16
+    }
+
+    /// Split into images at most `max_height` dots tall, top to bottom.
+    ///
+    /// Printed one after another in standard mode, the bands join up without
+    /// gaps. A `max_height` of 0 is treated as 1.
+    pub fn bands(&self, max_height: u16) -> impl Iterator<Item = PrintRasterImage> + '_ {
+        self.band_slices(max_height).map(PrintRasterImageRef::into_owned)
+    }
+
+    /// The bands of [`bands`](Self::bands), borrowing the image data.
+    pub fn band_slices(&self, max_height: u16) -> impl Iterator<Item = PrintRasterImageRef<'_>> {
+        let width = usize::from(self.width_bytes.max(1));
+        let rows = usize::from(max_height.max(1));
+        self.data.chunks(width * rows).map(move |chunk| PrintRasterImageRef {
+            mode: self.mode,
+            width_bytes: self.width_bytes,
+            height_dots: chunk.len().div_ceil(width) as u16,
+            data: chunk,
+        })
+    }
+
+    /// Borrow the image as a [`PrintRasterImageRef`].
+    pub fn as_borrowed(&self) -> PrintRasterImageRef<'_> {
+        PrintRasterImageRef {
+            mode: self.mode,
+            width_bytes: self.width_bytes,
+            height_dots: self.height_dots,
+            data: &self.data,
+        }
+    }
+
+    /// Write the command to `writer` without copying the image data.
+    ///
+    /// Produces the bytes of [`encode`](Command::encode), for streaming a
+    /// large logo straight to a file or socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first I/O error from `writer`.
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        self.write_to_for(Dialect::Bixolon, writer)
+    }
+
+    /// Write the command for `dialect` to `writer` without copying the
+    /// image data.
+    ///
+    /// Produces the bytes of [`encode_for`](Command::encode_for).
+    ///
+    /// # Errors
+    ///
+    /// Returns the first I/O error from `writer`.
+    #[cfg(feature = "std")]
+    pub fn write_to_for(
+        &self,
+        dialect: Dialect,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        self.as_borrowed().emit(dialect, &mut |bytes| writer.write_all(bytes))
+    }
+
+    /// Write the image as [`bands`](Self::bands) for `dialect` to `writer`,
+    /// without copying the image data.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first I/O error from `writer`.
+    #[cfg(feature = "std")]
+    pub fn write_bands_to(
+        &self,
+        max_height: u16,
+        dialect: Dialect,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        for band in self.band_slices(max_height) {
+            band.emit(dialect, &mut |bytes| writer.write_all(bytes))?;
+        }
+        Ok(())
+    }
+
+    /// Rotate a quarter turn clockwise.
+    ///
+    /// `width_dots` is the image's width without the padding that fills
+    /// out the last byte of each row; it is capped at `width_bytes * 8`.
+    /// Padding dots are treated as white, and stay white in the result so
+    /// they never print. Double width and double height modes swap to keep
+    /// the printed shape.
+    pub fn rotate_90(&self, width_dots: u16) -> Self {
+        let (width, height) = self.dimensions(width_dots);
+        Self::from_fn(height.div_ceil(8), height, width, self.mode.transposed(), |x, y| {
+            self.dot(y, height - 1 - x)
+        })
+    }
+
+    /// Rotate a half turn, e.g. for a printer mounted upside down.
+    ///
+    /// See [`rotate_90`](Self::rotate_90) for `width_dots`.
+    pub fn rotate_180(&self, width_dots: u16) -> Self {
+        let (width, height) = self.dimensions(width_dots);
+        Self::from_fn(self.width_bytes.into(), width, height, self.mode, |x, y| {
+            self.dot(width - 1 - x, height - 1 - y)
+        })
+    }
+
+    /// Rotate a quarter turn counterclockwise.
+    ///
+    /// See [`rotate_90`](Self::rotate_90) for `width_dots`.
+    pub fn rotate_270(&self, width_dots: u16) -> Self {
+        let (width, height) = self.dimensions(width_dots);
+        Self::from_fn(height.div_ceil(8), height, width, self.mode.transposed(), |x, y| {
+            self.dot(width - 1 - y, x)
+        })
+    }
+
+    /// Mirror left to right.
+    ///
+    /// See [`rotate_90`](Self::rotate_90) for `width_dots`.
+    pub fn mirror(&self, width_dots: u16) -> Self {
+        let (width, height) = self.dimensions(width_dots);
+        Self::from_fn(self.width_bytes.into(), width, height, self.mode, |x, y| {
+            self.dot(width - 1 - x, y)
+        })
+    }
+
+    /// Swap black and white dots, e.g. to print a white-on-black logo.
+    ///
+    /// See [`rotate_90`](Self::rotate_90) for `width_dots`.
+    pub fn invert(&self, width_dots: u16) -> Self {
+        let (width, height) = self.dimensions(width_dots);
+        Self::from_fn(self.width_bytes.into(), width, height, self.mode, |x, y| !self.dot(x, y))
+    }
+
+    /// Width in dots, capped at the row width, and height in dots.
+    fn dimensions(&self, width_dots: u16) -> (usize, usize) {
+        let width_bytes = usize::from(self.width_bytes);
+        let height = self.data.len().checked_div(width_bytes).unwrap_or(0);
+        (usize::from(width_dots).min(width_bytes * 8), height)
+    }
+
+    fn dot(&self, x: usize, y: usize) -> bool {
+        let width_bytes = usize::from(self.width_bytes);
+        self.data[y * width_bytes + x / 8] & (0x80 >> (x % 8)) != 0
+    }
+
+    /// Build an image from a function giving each of `width` by `height`
+    /// dots, leaving the rest of each row white.
+    fn from_fn(
+        width_bytes: usize,
+        width: usize,
+        height: usize,
+        mode: RasterImageMode,
+        dot: impl Fn(usize, usize) -> bool,
+    ) -> Self {
+        let mut data = vec![0u8; width_bytes * height];
+        for y in 0..height {
+            for x in 0..width {
+                if dot(x, y) {
+                    data[y * width_bytes + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        Self::new(width_bytes as u16, height as u16, data).with_mode(mode)
+    }
 }
 
 impl Command for PrintRasterImage {
     fn encode(&self) -> Vec<u8> {
-        let mut bytes = vec![
-            GS,
-            b'v',
-            b'0',
-            self.mode as u8,
-            (self.width_bytes & 0xFF) as u8,
-            ((self.width_bytes >> 8) & 0xFF) as u8,
-            (self.height_dots & 0xFF) as u8,
-            ((self.height_dots >> 8) & 0xFF) as u8,
-        ];
-        bytes.extend_from_slice(&self.data);
+        let mut bytes = Vec::with_capacity(8 + self.data.len());
+        self.encode_into(&mut bytes);
+        bytes
+    }
+
+    fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.encode_into_for(dialect, &mut bytes);
+        bytes
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        self.encode_into_for(Dialect::Bixolon, output);
+    }
+
+    fn encode_into_for(&self, dialect: Dialect, output: &mut Vec<u8>) {
+        self.as_borrowed().encode_into_for(dialect, output);
+    }
+}
+
+/// Print a raster bit image whose data is borrowed.
+///
+/// Encodes like [`PrintRasterImage`], but borrows the image data instead
+/// of owning it, so an image kept in a buffer or a static can be printed
+/// without copying it into a command first. Bands of a
+/// [`PrintRasterImage`] are printed the same way.
+///
+/// ```
+/// use bixolon::command::Command;
+/// use bixolon::command::image::{PrintRasterImage, PrintRasterImageRef};
+///
+/// static LOGO: [u8; 4] = [0xFF, 0x00, 0x0F, 0xF0];
+///
+/// let image = PrintRasterImageRef::try_new(2, 2, &LOGO).unwrap();
+/// assert_eq!(image.encode(), PrintRasterImage::new(2, 2, LOGO.to_vec()).encode());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintRasterImageRef<'a> {
+    /// Image mode/density.
+    pub mode: RasterImageMode,
+    /// Width in bytes (8 pixels per byte).
+    pub width_bytes: u16,
+    /// Height in dots.
+    pub height_dots: u16,
+    /// Image data (1 bit per pixel, MSB first).
+    pub data: &'a [u8],
+}
+
+impl<'a> PrintRasterImageRef<'a> {
+    /// Create a new raster image borrowing `data`.
+    pub fn new(width_bytes: u16, height_dots: u16, data: &'a [u8]) -> Self {
+        Self {
+            mode: RasterImageMode::Normal,
+            width_bytes,
+            height_dots,
+            data,
+        }
+    }
+
+    /// Create a raster image borrowing `data`, checking its size.
+    ///
+    /// # Errors
+    ///
+    /// See [`PrintRasterImage::try_new`].
+    pub fn try_new(
+        width_bytes: u16,
+        height_dots: u16,
+        data: &'a [u8],
+    ) -> Result<Self, ValidationError> {
+        let image = Self::new(width_bytes, height_dots, data);
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Check the width and that the data length matches the dimensions.
+    ///
+    /// # Errors
+    ///
+    /// See [`PrintRasterImage::validate`].
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        check_dimensions(self.width_bytes, self.height_dots, self.data)
+    }
+
+    /// Set the raster image mode.
+    pub fn with_mode(mut self, mode: RasterImageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Copy the data into an owned [`PrintRasterImage`].
+    pub fn into_owned(self) -> PrintRasterImage {
+        PrintRasterImage::new(self.width_bytes, self.height_dots, self.data.to_vec())
+            .with_mode(self.mode)
+    }
+
+    /// Pass the command for `dialect` to `emit` in pieces, the image data
+    /// as borrowed slices.
+    pub(crate) fn emit<E>(
+        &self,
+        dialect: Dialect,
+        emit: &mut impl FnMut(&[u8]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let [wl, wh] = self.width_bytes.to_le_bytes();
+        let [hl, hh] = self.height_dots.to_le_bytes();
+        match dialect {
+            Dialect::Bixolon => {
+                emit(&[GS, b'v', b'0', self.mode as u8, wl, wh, hl, hh])?;
+                emit(self.data)
+            }
+            Dialect::Epson => {
+                let (bx, by) = match self.mode {
+                    RasterImageMode::Normal => (1, 1),
+                    RasterImageMode::DoubleWidth => (2, 1),
+                    RasterImageMode::DoubleHeight => (1, 2),
+                    RasterImageMode::Quadruple => (2, 2),
+                };
+                // Wider images fail `validate`; clamp rather than overflow.
+                let width_dots = u16::try_from(u32::from(self.width_bytes) * 8);
+                let [dl, dh] = width_dots.unwrap_or(u16::MAX).to_le_bytes();
+                let params = [b'0', b'p', b'0', bx, by, b'1', dl, dh, hl, hh];
+                let length = params.len() + self.data.len();
+                match u16::try_from(length) {
+                    Ok(length) => {
+                        let [l0, l1] = length.to_le_bytes();
+                        emit(&[GS, b'(', b'L', l0, l1])?;
+                    }
+                    Err(_) => {
+                        let [l0, l1, l2, l3] = (length as u32).to_le_bytes();
+                        emit(&[GS, b'8', b'L', l0, l1, l2, l3])?;
+                    }
+                }
+                emit(&params)?;
+                emit(self.data)?;
+                emit(&[GS, b'(', b'L', 2, 0, b'0', b'2'])
+            }
+            Dialect::Star => {
+                emit(&[ESC, b'*', b'r', b'A'])?;
+                for row in self.data.chunks(usize::from(self.width_bytes.max(1))) {
+                    let [nl, nh] = (row.len() as u16).to_le_bytes();
+                    emit(&[b'b', nl, nh])?;
+                    emit(row)?;
+                }
+                emit(&[ESC, b'*', b'r', b'B'])
+            }
+        }
+    }
+}
+
+impl Command for PrintRasterImageRef<'_> {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.data.len());
+        self.encode_into(&mut bytes);
         bytes
     }
+
+    fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.encode_into_for(dialect, &mut bytes);
+        bytes
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        self.encode_into_for(Dialect::Bixolon, output);
+    }
+
+    fn encode_into_for(&self, dialect: Dialect, output: &mut Vec<u8>) {
+        let Ok(()) = self.emit(dialect, &mut |bytes| {
+            output.extend_from_slice(bytes);
+            Ok::<_, Infallible>(())
+        });
+    }
+}
+
+/// A raster image printed as 24-dot bit image columns.
+///
+/// Some firmware prints `GS v 0` raster images slowly or misaligns them.
+/// This converts a [`PrintRasterImage`] into `ESC *` 24-dot bands instead,
+/// each followed by a line feed. Line spacing is set to 24 dots for the
+/// bands so they join up, then restored to the default.
+///
+/// The line spacing assumes the default vertical motion unit of 1/360 inch.
+/// Double height raster modes have no column equivalent and print at
+/// normal height.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnImage {
+    bands: Vec<SelectBitImageMode>,
+    darkness: Darkness,
+}
+
+impl ColumnImage {
+    /// The `ESC *` bands, top to bottom.
+    pub fn bands(&self) -> &[SelectBitImageMode] {
+        &self.bands
+    }
+
+    /// Strike each band more than once, for faded print heads.
+    pub fn with_darkness(mut self, darkness: Darkness) -> Self {
+        self.darkness = darkness;
+        self
+    }
+}
+
+/// Number of times each band of an image is printed.
+///
+/// Worn thermal heads print pale images. Printing a band again without
+/// feeding the paper heats the same dots a second time, which darkens them
+/// at the cost of print speed. Double-strike mode (`ESC G`) only affects
+/// text, so images are struck by sending each band repeatedly.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Darkness {
+    /// Print once.
+    #[default]
+    Normal = 1,
+    /// Print each band twice.
+    Double = 2,
+    /// Print each band three times.
+    Triple = 3,
+}
+
+impl Darkness {
+    /// Number of passes per band.
+    pub const fn passes(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<&PrintRasterImage> for ColumnImage {
+    type Error = ValidationError;
+
+    /// Split an image into 24-dot bands.
+    ///
+    /// Fails with [`ValidationError::ImageTooLarge`] if the image is wider
+    /// than the 65535 columns `ESC *` can address.
+    fn try_from(image: &PrintRasterImage) -> Result<Self, Self::Error> {
+        let mode = match image.mode {
+            RasterImageMode::DoubleWidth | RasterImageMode::Quadruple => {
+                BitImageMode::SingleDensity24
+            }
+            RasterImageMode::Normal | RasterImageMode::DoubleHeight => {
+                BitImageMode::DoubleDensity24
+            }
+        };
+        let width_bytes = usize::from(image.width_bytes);
+        let columns = width_bytes * 8;
+        let rows = image.data.len() / width_bytes.max(1);
+        let width = u16::try_from(columns).map_err(|_| ValidationError::ImageTooLarge {
+            width: u32::try_from(columns).unwrap_or(u32::MAX),
+            height: u32::try_from(rows).unwrap_or(u32::MAX),
+        })?;
+        let bands = (0..rows.div_ceil(24))
+            .map(|band| {
+                let mut data = vec![0u8; columns * 3];
+                for row in band * 24..(band * 24 + 24).min(rows) {
+                    let offset = row - band * 24;
+                    for column in 0..columns {
+                        let byte = image.data[row * width_bytes + column / 8];
+                        if byte & (0x80 >> (column % 8)) != 0 {
+                            data[column * 3 + offset / 8] |= 0x80 >> (offset % 8);
+                        }
+                    }
+                }
+                SelectBitImageMode {
+                    mode,
+                    width,
+                    data,
+                }
+            })
+            .collect();
+        Ok(Self {
+            bands,
+            darkness: Darkness::default(),
+        })
+    }
+}
+
+impl Command for ColumnImage {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = SetLineSpacing(48).encode();
+        for band in &self.bands {
+            // Extra passes print without moving the paper
+            for _ in 1..self.darkness.passes() {
+                bytes.extend(band.encode());
+                bytes.extend(FeedPaper(0).encode());
+            }
+            bytes.extend(band.encode());
+            bytes.extend_from_slice(LineFeed::BYTES);
+        }
+        bytes.extend_from_slice(SetDefaultLineSpacing::BYTES);
+        bytes
+    }
+}
+
+#[cfg(feature = "image")]
+impl TryFrom<&::image::DynamicImage> for PrintRasterImage {
+    type Error = ValidationError;
+
+    /// Convert at the image's own size, printing pixels darker than 50%
+    /// luma. Transparent pixels are treated as white paper.
+    ///
+    /// Fails with [`ValidationError::ImageTooLarge`] for images over 65535
+    /// dots tall or [`MAX_WIDTH_BYTES`](Self::MAX_WIDTH_BYTES) bytes wide.
+    fn try_from(image: &::image::DynamicImage) -> Result<Self, Self::Error> {
+        raster::pack(image, raster::Dither::default())
+    }
 }
 
 /// Define downloaded bit image.
 ///
 /// ESC/POS: `GS * x y d1...dk`
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DefineDownloadedImage {
     /// Width in bytes (1-255).
     pub width_bytes: u8,
@@ -118,6 +661,57 @@ pub struct DefineDownloadedImage {
     pub data: Vec<u8>,
 }
 
+impl DefineDownloadedImage {
+    /// Most downloaded image memory, as `width_bytes * height_bytes`.
+    pub const MAX_SIZE: u16 = 1536;
+
+    /// Create a downloaded image, checking its size and data length.
+    ///
+    /// `data` holds `width_bytes * 8` columns of `height_bytes` bytes each,
+    /// top byte first, for `width_bytes * height_bytes * 8` bytes in all.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if either dimension is 0,
+    /// the height is over 48 bytes or the image is over
+    /// [`MAX_SIZE`](Self::MAX_SIZE), or
+    /// [`ValidationError::ImageDataLength`] if the data length is wrong.
+    pub fn new(width_bytes: u8, height_bytes: u8, data: Vec<u8>) -> Result<Self, ValidationError> {
+        let image = Self {
+            width_bytes,
+            height_bytes,
+            data,
+        };
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Check the dimensions, size limit and data length.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new).
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let out_of_range = |name, value: u16, min, max| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(ValidationError::OutOfRange {
+                    name,
+                    value,
+                    min,
+                    max,
+                })
+            }
+        };
+        out_of_range("width_bytes", u16::from(self.width_bytes), 1, 255)?;
+        out_of_range("height_bytes", u16::from(self.height_bytes), 1, 48)?;
+        let size = u16::from(self.width_bytes) * u16::from(self.height_bytes);
+        out_of_range("downloaded image size", size, 1, Self::MAX_SIZE)?;
+        check_data_length(usize::from(size) * 8, &self.data)
+    }
+}
+
 impl Command for DefineDownloadedImage {
     fn encode(&self) -> Vec<u8> {
         let mut bytes = vec![GS, b'*', self.width_bytes, self.height_bytes];
@@ -126,9 +720,35 @@ impl Command for DefineDownloadedImage {
     }
 }
 
+fn check_dimensions(
+    width_bytes: u16,
+    height_dots: u16,
+    data: &[u8],
+) -> Result<(), ValidationError> {
+    if width_bytes > PrintRasterImage::MAX_WIDTH_BYTES {
+        return Err(ValidationError::ImageTooLarge {
+            width: u32::from(width_bytes) * 8,
+            height: u32::from(height_dots),
+        });
+    }
+    check_data_length(usize::from(width_bytes) * usize::from(height_dots), data)
+}
+
+fn check_data_length(expected: usize, data: &[u8]) -> Result<(), ValidationError> {
+    if data.len() == expected {
+        Ok(())
+    } else {
+        Err(ValidationError::ImageDataLength {
+            expected,
+            actual: data.len(),
+        })
+    }
+}
+
 /// Print downloaded image mode.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DownloadedImageMode {
     /// Normal.
     #[default]
@@ -145,6 +765,7 @@ pub enum DownloadedImageMode {
 ///
 /// ESC/POS: `GS / m`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrintDownloadedImage(pub DownloadedImageMode);
 
 impl Command for PrintDownloadedImage {
@@ -157,6 +778,164 @@ impl Command for PrintDownloadedImage {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "image")]
+    #[test]
+    fn raster_from_dynamic_image_packs_msb_first() {
+        use ::image::{DynamicImage, GrayImage, Luma};
+
+        // 10x2: row 0 has dark pixels at x = 0 and 9, row 1 at x = 7.
+        let mut pixels = GrayImage::from_pixel(10, 2, Luma([255]));
+        pixels.put_pixel(0, 0, Luma([0]));
+        pixels.put_pixel(9, 0, Luma([0]));
+        pixels.put_pixel(7, 1, Luma([100]));
+        let raster = PrintRasterImage::try_from(&DynamicImage::ImageLuma8(pixels)).unwrap();
+        assert_eq!(raster.width_bytes, 2);
+        assert_eq!(raster.height_dots, 2);
+        assert_eq!(raster.data, vec![0x80, 0x40, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn raster_bands_split_rows() {
+        let image =
+            PrintRasterImage::new(2, 5, (0..10).collect()).with_mode(RasterImageMode::DoubleWidth);
+        let bands: Vec<_> = image.bands(2).collect();
+        assert_eq!(bands.len(), 3);
+        assert_eq!(bands[0].data, [0, 1, 2, 3]);
+        assert_eq!(bands[2].height_dots, 1);
+        assert_eq!(bands[2].data, [8, 9]);
+        assert!(bands.iter().all(|band| band.mode == RasterImageMode::DoubleWidth));
+
+        assert_eq!(image.band_height(), 2048);
+        assert_eq!(PrintRasterImage::new(64, 1, vec![0; 64]).band_height(), 64);
+    }
+
+    #[test]
+    fn column_image_transposes_24_row_bands() {
+        // 8x25: the top-left dot, the last dot of row 23, and row 24 in full
+        let mut data = vec![0u8; 25];
+        data[0] = 0x80;
+        data[23] = 0x01;
+        data[24] = 0xFF;
+        let columns = ColumnImage::try_from(&PrintRasterImage::new(1, 25, data)).unwrap();
+        let bands = columns.bands();
+        assert_eq!(bands.len(), 2);
+        assert_eq!(bands[0].mode, BitImageMode::DoubleDensity24);
+        assert_eq!(bands[0].width, 8);
+        assert_eq!(&bands[0].data[..3], [0x80, 0x00, 0x00]);
+        assert_eq!(&bands[0].data[21..], [0x00, 0x00, 0x01]);
+        assert!(bands[1].data.chunks(3).all(|column| column == [0x80, 0x00, 0x00]));
+
+        let encoded = columns.encode();
+        assert!(encoded.starts_with(&[ESC, b'3', 48, ESC, b'*', 33, 8, 0]));
+        assert_eq!(encoded.iter().filter(|&&b| b == b'\n').count(), 2);
+        assert!(encoded.ends_with(&[b'\n', ESC, b'2']));
+    }
+
+    #[test]
+    fn column_image_rejects_widths_over_u16() {
+        let image = PrintRasterImage::new(8192, 1, vec![0; 8192]);
+        assert!(matches!(
+            ColumnImage::try_from(&image),
+            Err(ValidationError::ImageTooLarge {
+                width: 65536,
+                height: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn darkness_repeats_each_band_without_feeding() {
+        let image = PrintRasterImage::new(1, 30, vec![0xFF; 30]);
+        let columns = ColumnImage::try_from(&image).unwrap().with_darkness(Darkness::Triple);
+        let encoded = columns.encode();
+        let band = columns.bands()[0].encode();
+
+        let mut expected = SetLineSpacing(48).encode();
+        expected.extend([band.as_slice(), &[ESC, b'J', 0]].concat().repeat(2));
+        expected.extend(&band);
+        expected.push(b'\n');
+        assert!(encoded.starts_with(&expected));
+        assert_eq!(encoded.windows(3).filter(|w| *w == [ESC, b'J', 0]).count(), 4);
+        assert_eq!(encoded.iter().filter(|&&b| b == b'\n').count(), 2);
+    }
+
+    #[test]
+    fn raster_data_length_is_validated() {
+        assert!(PrintRasterImage::try_new(2, 3, vec![0; 6]).is_ok());
+        assert!(matches!(
+            PrintRasterImage::try_new(2, 3, vec![0; 5]),
+            Err(ValidationError::ImageDataLength {
+                expected: 6,
+                actual: 5
+            })
+        ));
+        assert!(PrintRasterImage::new(1, 1, vec![0; 2]).validate().is_err());
+    }
+
+    #[test]
+    fn downloaded_image_limits_are_validated() {
+        assert!(DefineDownloadedImage::new(32, 48, vec![0; 32 * 48 * 8]).is_ok());
+        assert!(matches!(
+            DefineDownloadedImage::new(33, 48, vec![0; 33 * 48 * 8]),
+            Err(ValidationError::OutOfRange {
+                value: 1584,
+                max: 1536,
+                ..
+            })
+        ));
+        assert!(matches!(
+            DefineDownloadedImage::new(1, 49, vec![0; 49 * 8]),
+            Err(ValidationError::OutOfRange {
+                name: "height_bytes",
+                ..
+            })
+        ));
+        assert!(matches!(
+            DefineDownloadedImage::new(2, 2, vec![0; 31]),
+            Err(ValidationError::ImageDataLength {
+                expected: 32,
+                actual: 31
+            })
+        ));
+    }
+
+    #[test]
+    fn raster_transforms() {
+        // 8x2 with an L shape: row 0 = X......., row 1 = XX......
+        let image =
+            PrintRasterImage::new(1, 2, vec![0x80, 0xC0]).with_mode(RasterImageMode::DoubleWidth);
+
+        let rotated = image.rotate_90(8);
+        assert_eq!((rotated.width_bytes, rotated.height_dots), (1, 8));
+        assert_eq!(rotated.data, [0xC0, 0x80, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(rotated.mode, RasterImageMode::DoubleHeight);
+
+        let rotated = image.rotate_270(8);
+        assert_eq!(rotated.data, [0, 0, 0, 0, 0, 0, 0x40, 0xC0]);
+        assert_eq!(rotated.rotate_90(2).data[..2], image.data);
+
+        assert_eq!(image.rotate_180(8).data, [0x03, 0x01]);
+        assert_eq!(image.rotate_180(8).rotate_180(8), image);
+        assert_eq!(image.mirror(8).data, [0x01, 0x03]);
+        assert_eq!(image.invert(8).data, [0x7F, 0x3F]);
+    }
+
+    #[test]
+    fn raster_transforms_keep_padding_white() {
+        // 3 dots wide, padded to a byte: row 0 = X.., row 1 = XX.
+        let image = PrintRasterImage::new(1, 2, vec![0x80, 0xC0]);
+
+        assert_eq!(image.invert(3).data, [0x60, 0x20]);
+        assert_eq!(image.mirror(3).data, [0x20, 0x60]);
+        assert_eq!(image.rotate_180(3).data, [0x60, 0x20]);
+        assert_eq!(image.rotate_180(3).rotate_180(3), image);
+
+        let rotated = image.rotate_90(3);
+        assert_eq!((rotated.width_bytes, rotated.height_dots), (1, 3));
+        assert_eq!(rotated.data, [0xC0, 0x80, 0x00]);
+        assert_eq!(rotated.rotate_270(2), image);
+    }
+
     #[test]
     fn bit_image_mode_values() {
         assert_eq!(BitImageMode::SingleDensity8 as u8, 0);
@@ -208,4 +987,101 @@ mod tests {
         let cmd = PrintDownloadedImage(DownloadedImageMode::DoubleWidth);
         assert_eq!(cmd.encode(), vec![0x1D, b'/', 1]);
     }
+
+    #[test]
+    fn raster_encodes_for_epson_graphics() {
+        let image =
+            PrintRasterImage::new(1, 2, vec![0xF0, 0x0F]).with_mode(RasterImageMode::DoubleWidth);
+        assert_eq!(
+            image.encode_for(Dialect::Epson),
+            [
+                &[0x1D, b'(', b'L', 12, 0, 48, 112, 48, 2, 1, 49, 8, 0, 2, 0, 0xF0, 0x0F][..],
+                &[0x1D, b'(', b'L', 2, 0, 48, 50],
+            ]
+            .concat()
+        );
+
+        let large = PrintRasterImage::new(72, 1000, vec![0; 72_000]);
+        let bytes = large.encode_for(Dialect::Epson);
+        assert_eq!(bytes[..7], [0x1D, b'8', b'L', 0x4A, 0x19, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn raster_wider_than_epson_width_is_rejected() {
+        let wide = PrintRasterImage::new(8192, 1, vec![0; 8192]);
+        assert!(matches!(
+            wide.validate(),
+            Err(ValidationError::ImageTooLarge {
+                width: 65536,
+                height: 1
+            })
+        ));
+        assert!(PrintRasterImageRef::try_new(8192, 1, &wide.data).is_err());
+
+        // Encoding without validating clamps instead of overflowing.
+        let bytes = wide.encode_for(Dialect::Epson);
+        assert_eq!(bytes[10..14], [b'1', 0xFF, 0xFF, 1]);
+        assert!(PrintRasterImage::new(8191, 1, vec![0; 8191]).validate().is_ok());
+    }
+
+    #[test]
+    fn raster_encodes_for_star_raster_mode() {
+        let image = PrintRasterImage::new(2, 2, vec![1, 2, 3, 4]);
+        assert_eq!(
+            image.encode_for(Dialect::Star),
+            [0x1B, b'*', b'r', b'A', b'b', 2, 0, 1, 2, b'b', 2, 0, 3, 4, 0x1B, b'*', b'r', b'B']
+        );
+    }
+
+    #[test]
+    fn encode_into_appends_the_same_bytes() {
+        use crate::command::character::{Justification, SetJustification};
+        use crate::command::paper::CutPaper;
+
+        let job = (
+            SetJustification(Justification::Center),
+            PrintRasterImage::new(2, 2, vec![1, 2, 3, 4]),
+            CutPaper::partial(),
+        );
+        for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+            let mut output = b"head".to_vec();
+            job.encode_into_for(dialect, &mut output);
+            assert_eq!(output[4..], job.encode_for(dialect));
+        }
+        let mut output = Vec::new();
+        job.encode_into(&mut output);
+        assert_eq!(output, job.encode());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_to_streams_the_encoded_bytes() {
+        let image = PrintRasterImage::new(2, 5, (0..10).collect());
+        for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+            let mut output = Vec::new();
+            image.write_to_for(dialect, &mut output).unwrap();
+            assert_eq!(output, image.encode_for(dialect));
+
+            let mut output = Vec::new();
+            image.write_bands_to(2, dialect, &mut output).unwrap();
+            let bands: Vec<u8> = image.bands(2).flat_map(|band| band.encode_for(dialect)).collect();
+            assert_eq!(output, bands);
+        }
+    }
+
+    #[test]
+    fn borrowed_image_matches_owned() {
+        let data: Vec<u8> = (0..10).collect();
+        let owned = PrintRasterImage::new(2, 5, data.clone()).with_mode(RasterImageMode::Quadruple);
+        let borrowed = PrintRasterImageRef::new(2, 5, &data).with_mode(RasterImageMode::Quadruple);
+        for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+            assert_eq!(borrowed.encode_for(dialect), owned.encode_for(dialect));
+        }
+        assert_eq!(owned.as_borrowed(), borrowed);
+        assert_eq!(borrowed.into_owned(), owned);
+        assert!(matches!(
+            PrintRasterImageRef::try_new(2, 6, &data),
+            Err(ValidationError::ImageDataLength { .. })
+        ));
+    }
 }
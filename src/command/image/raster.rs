@@ -0,0 +1,280 @@
+//! Raster images from the `image` crate.
+//!
+//! Converts any [`DynamicImage`] into a [`PrintRasterImage`] scaled to a
+//! width in printer dots, handling the MSB-first bit packing and row padding
+//! that `GS v 0` expects.
+//!
+//! Photos and gradients print better with [`Dither::Bayer`], which spreads
+//! gray levels into a fixed dot pattern.
+//!
+//! ```
+//! use bixolon::command::image::raster::{Threshold, from_image};
+//! use image::{DynamicImage, GrayImage, Luma};
+//!
+//! let logo = DynamicImage::ImageLuma8(GrayImage::from_pixel(100, 50, Luma([0])));
+//! let raster = from_image(&logo, 200, Threshold::default())?;
+//! assert_eq!((raster.width_bytes, raster.height_dots), (25, 100));
+//! # Ok::<(), bixolon::error::ValidationError>(())
+//! ```
+
+use std::borrow::Cow;
+
+use ::image::DynamicImage;
+pub use ::image::imageops::FilterType;
+
+use super::PrintRasterImage;
+use crate::error::ValidationError;
+use crate::profile::PrinterProfile;
+
+/// Luma below which a pixel prints.
+///
+/// Pixels darker than the threshold become black dots; the rest stay white.
+/// Pixels that are more than half transparent never print, whatever their
+/// color. The default of 128 prints anything darker than mid-gray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Threshold(pub u8);
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Self(128)
+    }
+}
+
+/// How gray levels are reduced to black and white dots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dither {
+    /// Print every pixel darker than a fixed threshold.
+    ///
+    /// Best for logos, text and line art.
+    Threshold(Threshold),
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    ///
+    /// Each pixel is compared against a threshold that depends only on its
+    /// position, so gray areas become a regular dot pattern. Faster than
+    /// error diffusion, and an unchanged region always produces the same
+    /// dots, which keeps repeated prints and animations stable.
+    Bayer,
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::Threshold(Threshold::default())
+    }
+}
+
+impl From<Threshold> for Dither {
+    fn from(threshold: Threshold) -> Self {
+        Self::Threshold(threshold)
+    }
+}
+
+/// Convert `image` to a raster image `width_dots` wide.
+///
+/// The image is resized to `width_dots` (at least 1) keeping its aspect
+/// ratio, then reduced to dots with `dither`, which may be a plain
+/// [`Threshold`]. Rows are padded to whole bytes with white dots.
+///
+/// # Errors
+///
+/// Returns [`ValidationError::ImageTooLarge`] if the resized image is over
+/// 65535 dots tall, or `width_dots` is over
+/// [`PrintRasterImage::MAX_WIDTH_BYTES`] bytes.
+pub fn from_image(
+    image: &DynamicImage,
+    width_dots: u16,
+    dither: impl Into<Dither>,
+) -> Result<PrintRasterImage, ValidationError> {
+    let width = u32::from(width_dots.max(1));
+    pack(&resize(image, width, FilterType::Triangle), dither.into())
+}
+
+/// Convert `image`, scaling it down to fit the printable width of `profile`.
+///
+/// Images wider than [`PrinterProfile::paper_width_dots`] are shrunk with
+/// `filter`, keeping their aspect ratio, instead of having their right edge
+/// clipped by the printer. Narrower images print at their own size.
+/// [`FilterType::Nearest`] keeps hard edges in pixel art and barcodes;
+/// [`FilterType::Lanczos3`] gives the smoothest photos.
+///
+/// # Errors
+///
+/// As for [`from_image`].
+pub fn fit_to_profile(
+    image: &DynamicImage,
+    profile: &PrinterProfile,
+    filter: FilterType,
+    dither: impl Into<Dither>,
+) -> Result<PrintRasterImage, ValidationError> {
+    let width = image.width().min(u32::from(profile.paper_width_dots)).max(1);
+    pack(&resize(image, width, filter), dither.into())
+}
+
+/// Scale `image` to `width`, keeping its aspect ratio.
+fn resize(image: &DynamicImage, width: u32, filter: FilterType) -> Cow<'_, DynamicImage> {
+    if width == image.width() {
+        return Cow::Borrowed(image);
+    }
+    let height = (u64::from(image.height()) * u64::from(width))
+        .div_ceil(u64::from(image.width().max(1)))
+        .max(1);
+    let height = u32::try_from(height).unwrap_or(u32::MAX);
+    Cow::Owned(image.resize_exact(width, height, filter))
+}
+
+/// Pack `image` at its own size.
+///
+/// # Errors
+///
+/// Returns [`ValidationError::ImageTooLarge`] unless the width fits in
+/// [`PrintRasterImage::MAX_WIDTH_BYTES`] and the height in 65535 dots.
+pub(super) fn pack(
+    image: &DynamicImage,
+    dither: Dither,
+) -> Result<PrintRasterImage, ValidationError> {
+    let (width, height) = (image.width(), image.height());
+    let too_large = ValidationError::ImageTooLarge {
+        width,
+        height,
+    };
+    let row_bytes = u16::try_from(width.div_ceil(8))
+        .ok()
+        .filter(|&bytes| bytes <= PrintRasterImage::MAX_WIDTH_BYTES)
+        .ok_or_else(|| too_large.clone())?;
+    let height_dots = u16::try_from(height).map_err(|_| too_large)?;
+
+    let pixels = image.to_luma_alpha8();
+    let width_bytes = usize::from(row_bytes);
+    let mut data = vec![0u8; width_bytes * usize::from(height_dots)];
+    for (x, y, pixel) in pixels.enumerate_pixels() {
+        let [luma, alpha] = pixel.0;
+        let threshold = match dither {
+            Dither::Threshold(Threshold(threshold)) => u16::from(threshold),
+            Dither::Bayer => bayer_threshold(x, y),
+        };
+        if alpha >= 128 && u16::from(luma) < threshold {
+            data[y as usize * width_bytes + x as usize / 8] |= 0x80 >> (x % 8);
+        }
+    }
+    Ok(PrintRasterImage::new(row_bytes, height_dots, data))
+}
+
+/// Threshold for the pixel at (`x`, `y`), from 2 to 254.
+fn bayer_threshold(x: u32, y: u32) -> u16 {
+    // Bit-interleaved index into the recursive 8x8 Bayer matrix
+    let (x, y) = (x % 8, y % 8);
+    let mixed = x ^ y;
+    let mut index = 0;
+    for bit in 0..3 {
+        index = (index << 2) | (((mixed >> bit) & 1) << 1) | ((y >> bit) & 1);
+    }
+    (index * 4 + 2) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::image::{GrayImage, Luma, LumaA};
+
+    #[test]
+    fn threshold_selects_printed_pixels() {
+        let mut pixels = GrayImage::from_pixel(3, 1, Luma([255]));
+        pixels.put_pixel(0, 0, Luma([50]));
+        pixels.put_pixel(1, 0, Luma([150]));
+        let image = DynamicImage::ImageLuma8(pixels);
+
+        assert_eq!(from_image(&image, 3, Threshold::default()).unwrap().data, [0x80]);
+        assert_eq!(from_image(&image, 3, Threshold(200)).unwrap().data, [0xC0]);
+        assert_eq!(from_image(&image, 3, Threshold(0)).unwrap().data, [0x00]);
+    }
+
+    #[test]
+    fn rows_are_padded_msb_first() {
+        // 12 wide: row 0 dark at x = 0 and 11, row 1 dark at x = 8.
+        let mut pixels = GrayImage::from_pixel(12, 2, Luma([255]));
+        pixels.put_pixel(0, 0, Luma([0]));
+        pixels.put_pixel(11, 0, Luma([0]));
+        pixels.put_pixel(8, 1, Luma([0]));
+        let raster =
+            from_image(&DynamicImage::ImageLuma8(pixels), 12, Threshold::default()).unwrap();
+        assert_eq!(raster.width_bytes, 2);
+        assert_eq!(raster.data, [0x80, 0x10, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn resizes_keeping_aspect_ratio() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 32, Luma([0])));
+        let raster = from_image(&image, 16, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (2, 8));
+        assert!(raster.data.iter().all(|&b| b == 0xFF));
+
+        let raster = from_image(&image, 0, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (1, 1));
+    }
+
+    #[test]
+    fn rejects_images_too_tall_to_print() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 70_000, Luma([0])));
+        assert!(matches!(
+            from_image(&image, 1, Threshold::default()),
+            Err(ValidationError::ImageTooLarge {
+                width: 1,
+                height: 70_000
+            })
+        ));
+    }
+
+    #[test]
+    fn bayer_matrix_is_a_permutation() {
+        let mut thresholds: Vec<u16> = (0..64).map(|i| bayer_threshold(i % 8, i / 8)).collect();
+        assert_eq!(bayer_threshold(0, 0), 2);
+        assert_eq!(bayer_threshold(1, 1), 2 + 4 * 16);
+        assert_eq!(bayer_threshold(9, 8), bayer_threshold(1, 0));
+        thresholds.sort_unstable();
+        assert_eq!(thresholds, (0..64).map(|i| i * 4 + 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bayer_dithers_gray_to_matching_coverage() {
+        let gray = |luma| DynamicImage::ImageLuma8(GrayImage::from_pixel(16, 16, Luma([luma])));
+        let coverage = |luma| {
+            from_image(&gray(luma), 16, Dither::Bayer)
+                .unwrap()
+                .data
+                .iter()
+                .map(|b| b.count_ones())
+                .sum::<u32>()
+        };
+        assert_eq!(coverage(0), 256);
+        assert_eq!(coverage(128), 128);
+        assert_eq!(coverage(192), 64);
+        assert_eq!(coverage(255), 0);
+
+        // The pattern repeats every 8 dots
+        let raster = from_image(&gray(100), 16, Dither::Bayer).unwrap();
+        assert_eq!(raster.data[..16], raster.data[16..]);
+        assert!(raster.data.chunks(2).all(|row| row[0] == row[1]));
+    }
+
+    #[test]
+    fn fit_to_profile_only_shrinks() {
+        let profile = PrinterProfile::default();
+        let wide = DynamicImage::ImageLuma8(GrayImage::from_pixel(1024, 100, Luma([0])));
+        let raster =
+            fit_to_profile(&wide, &profile, FilterType::Nearest, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (64, 50));
+
+        let narrow = DynamicImage::ImageLuma8(GrayImage::from_pixel(100, 30, Luma([0])));
+        let raster =
+            fit_to_profile(&narrow, &profile, FilterType::Lanczos3, Dither::Bayer).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (13, 30));
+    }
+
+    #[test]
+    fn transparent_pixels_do_not_print() {
+        let pixels = ::image::GrayAlphaImage::from_pixel(8, 1, LumaA([0, 0]));
+        let raster = from_image(&DynamicImage::ImageLumaA8(pixels), 8, Threshold(255)).unwrap();
+        assert_eq!(raster.data, [0x00]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Raster images from SVG documents.
+//!
+//! Vector logos are rendered straight at the printer's resolution, so edges
+//! stay sharp instead of being resampled from a bitmap. SVG user units are
+//! CSS pixels (1/96 inch), which [`from_svg_natural`] maps onto 180 dpi
+//! printer dots.
+//!
+//! ```
+//! use bixolon::command::image::raster::Threshold;
+//! use bixolon::command::image::svg::from_svg;
+//!
+//! let logo = br#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20">
+//!     <rect width="40" height="20"/>
+//! </svg>"#;
+//! let raster = from_svg(logo, 160, Threshold::default())?;
+//! assert_eq!((raster.width_bytes, raster.height_dots), (20, 80));
+//! # Ok::<(), bixolon::error::SvgError>(())
+//! ```
+
+use ::image::{DynamicImage, RgbaImage};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+
+use super::PrintRasterImage;
+use super::raster::{Dither, pack};
+use crate::error::SvgError;
+use crate::profile::PrinterProfile;
+
+/// Printer dots per SVG user unit.
+const DOTS_PER_PX: f32 = 180.0 / 96.0;
+
+/// Render an SVG document `width_dots` wide.
+///
+/// The drawing is scaled to `width_dots` keeping its aspect ratio, then
+/// reduced to dots with `dither`. Transparent areas don't print.
+///
+/// # Errors
+///
+/// Returns [`SvgError::Parse`] if `svg` isn't a valid document, or
+/// [`SvgError::Size`] if it renders to an empty image.
+pub fn from_svg(
+    svg: &[u8],
+    width_dots: u16,
+    dither: impl Into<Dither>,
+) -> Result<PrintRasterImage, SvgError> {
+    let tree = Tree::from_data(svg, &Options::default())?;
+    let scale = f32::from(width_dots.max(1)) / tree.size().width();
+    render(&tree, scale, dither.into())
+}
+
+/// Render an SVG document at its own physical size.
+///
+/// Each SVG user unit becomes 180/96 printer dots, so a logo drawn 2 inches
+/// wide prints 2 inches wide. Drawings wider than
+/// [`PrinterProfile::paper_width_dots`] are scaled down to fit.
+///
+/// # Errors
+///
+/// As for [`from_svg`].
+pub fn from_svg_natural(
+    svg: &[u8],
+    profile: &PrinterProfile,
+    dither: impl Into<Dither>,
+) -> Result<PrintRasterImage, SvgError> {
+    let tree = Tree::from_data(svg, &Options::default())?;
+    let fit = f32::from(profile.paper_width_dots) / tree.size().width();
+    render(&tree, DOTS_PER_PX.min(fit), dither.into())
+}
+
+fn render(tree: &Tree, scale: f32, dither: Dither) -> Result<PrintRasterImage, SvgError> {
+    let width = (tree.size().width() * scale).round() as u32;
+    let height = (tree.size().height() * scale).round() as u32;
+    let size_error = SvgError::Size {
+        width,
+        height,
+    };
+    if height > u32::from(u16::MAX) {
+        return Err(size_error);
+    }
+    let mut pixmap = Pixmap::new(width, height).ok_or(size_error)?;
+    resvg::render(tree, Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    let pixels = RgbaImage::from_raw(width, height, pixmap.take_demultiplied())
+        .expect("pixmap holds width * height RGBA pixels");
+    pack(&DynamicImage::ImageRgba8(pixels), dither).map_err(|_| SvgError::Size {
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::image::raster::Threshold;
+
+    const HALF_BLACK: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" width="96" height="48">
+        <rect width="48" height="48" fill="black"/>
+        <rect x="48" width="48" height="48" fill="white"/>
+    </svg>"#;
+
+    #[test]
+    fn renders_at_requested_width() {
+        let raster = from_svg(HALF_BLACK, 64, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (8, 32));
+        assert!(raster.data.chunks(8).all(|row| row == [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn natural_size_uses_printer_resolution() {
+        let profile = PrinterProfile::default();
+        let raster = from_svg_natural(HALF_BLACK, &profile, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (23, 90));
+
+        let wide = br#"<svg xmlns="http://www.w3.org/2000/svg" width="960" height="96"/>"#;
+        let raster = from_svg_natural(wide, &profile, Threshold::default()).unwrap();
+        assert_eq!((raster.width_bytes, raster.height_dots), (64, 51));
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert!(matches!(from_svg(b"not svg", 64, Threshold::default()), Err(SvgError::Parse(_))));
+    }
+}
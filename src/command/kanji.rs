@@ -0,0 +1,234 @@
+//! Kanji (multi-byte character) mode commands.
+//!
+//! These FS commands control the two-byte character mode available on
+//! CJK-capable models. They are ignored by single-byte-only firmware.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Command, FS};
+
+/// Select Kanji character mode.
+///
+/// Subsequent text is interpreted as two-byte characters in the code
+/// system selected by [`SelectKanjiCodeSystem`].
+///
+/// ESC/POS: `FS &` (0x1C 0x26)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectKanjiMode;
+
+impl_static_command!(SelectKanjiMode => FS, b'&');
+
+/// Cancel Kanji character mode.
+///
+/// Returns to single-byte character interpretation.
+///
+/// ESC/POS: `FS .` (0x1C 0x2E)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CancelKanjiMode;
+
+impl_static_command!(CancelKanjiMode => FS, b'.');
+
+/// Print mode flags for Kanji characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KanjiPrintMode {
+    /// Double-width Kanji characters.
+    pub double_width: bool,
+    /// Double-height Kanji characters.
+    pub double_height: bool,
+    /// Underlined Kanji characters.
+    pub underline: bool,
+}
+
+impl KanjiPrintMode {
+    /// Normal Kanji printing (all flags off).
+    pub const fn normal() -> Self {
+        Self {
+            double_width: false,
+            double_height: false,
+            underline: false,
+        }
+    }
+
+    /// Double width and height.
+    pub const fn quadruple() -> Self {
+        Self {
+            double_width: true,
+            double_height: true,
+            underline: false,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        let mut n = 0u8;
+        if self.double_width {
+            n |= 0x04;
+        }
+        if self.double_height {
+            n |= 0x08;
+        }
+        if self.underline {
+            n |= 0x80;
+        }
+        n
+    }
+}
+
+/// Set print mode for Kanji characters.
+///
+/// ESC/POS: `FS ! n` (0x1C 0x21 n)
+///
+/// # Encoding
+///
+/// - Bit 2: double width
+/// - Bit 3: double height
+/// - Bit 7: underline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetKanjiPrintMode(pub KanjiPrintMode);
+
+impl Command for SetKanjiPrintMode {
+    fn encode(&self) -> Vec<u8> {
+        vec![FS, b'!', self.0.to_byte()]
+    }
+}
+
+/// Kanji underline thickness options.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KanjiUnderline {
+    /// Underline disabled.
+    #[default]
+    Off = 0,
+    /// 1-dot thick underline.
+    OneDot = 1,
+    /// 2-dot thick underline.
+    TwoDot = 2,
+}
+
+/// Turn Kanji underline mode on or off.
+///
+/// ESC/POS: `FS - n` (0x1C 0x2D n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetKanjiUnderline(pub KanjiUnderline);
+
+impl Command for SetKanjiUnderline {
+    fn encode(&self) -> Vec<u8> {
+        vec![FS, b'-', self.0 as u8]
+    }
+}
+
+/// Set left- and right-side Kanji character spacing.
+///
+/// Spacing is in horizontal motion units.
+///
+/// ESC/POS: `FS S n1 n2` (0x1C 0x53 n1 n2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetKanjiSpacing {
+    /// Left-side spacing.
+    pub left: u8,
+    /// Right-side spacing.
+    pub right: u8,
+}
+
+impl SetKanjiSpacing {
+    /// Create with the given left and right spacing.
+    pub const fn new(left: u8, right: u8) -> Self {
+        Self {
+            left,
+            right,
+        }
+    }
+}
+
+impl Command for SetKanjiSpacing {
+    fn encode(&self) -> Vec<u8> {
+        vec![FS, b'S', self.left, self.right]
+    }
+}
+
+/// Kanji character code system.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KanjiCodeSystem {
+    /// JIS code system.
+    #[default]
+    Jis = 0,
+    /// Shift JIS code system.
+    ShiftJis = 1,
+}
+
+/// Select Kanji character code system.
+///
+/// ESC/POS: `FS C n` (0x1C 0x43 n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectKanjiCodeSystem(pub KanjiCodeSystem);
+
+impl Command for SelectKanjiCodeSystem {
+    fn encode(&self) -> Vec<u8> {
+        vec![FS, b'C', self.0 as u8]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_kanji_mode_encodes() {
+        assert_eq!(SelectKanjiMode.encode(), vec![0x1C, b'&']);
+    }
+
+    #[test]
+    fn cancel_kanji_mode_encodes() {
+        assert_eq!(CancelKanjiMode.encode(), vec![0x1C, b'.']);
+    }
+
+    #[test]
+    fn kanji_print_mode_normal() {
+        let cmd = SetKanjiPrintMode(KanjiPrintMode::normal());
+        assert_eq!(cmd.encode(), vec![0x1C, b'!', 0x00]);
+    }
+
+    #[test]
+    fn kanji_print_mode_flags() {
+        let cmd = SetKanjiPrintMode(KanjiPrintMode {
+            double_width: true,
+            double_height: false,
+            underline: true,
+        });
+        assert_eq!(cmd.encode(), vec![0x1C, b'!', 0x84]);
+    }
+
+    #[test]
+    fn kanji_print_mode_quadruple() {
+        let cmd = SetKanjiPrintMode(KanjiPrintMode::quadruple());
+        assert_eq!(cmd.encode(), vec![0x1C, b'!', 0x0C]);
+    }
+
+    #[test]
+    fn kanji_underline_encodes() {
+        assert_eq!(SetKanjiUnderline(KanjiUnderline::Off).encode(), vec![0x1C, b'-', 0]);
+        assert_eq!(SetKanjiUnderline(KanjiUnderline::TwoDot).encode(), vec![0x1C, b'-', 2]);
+    }
+
+    #[test]
+    fn kanji_spacing_encodes() {
+        let cmd = SetKanjiSpacing::new(2, 4);
+        assert_eq!(cmd.encode(), vec![0x1C, b'S', 2, 4]);
+    }
+
+    #[test]
+    fn kanji_code_system_encodes() {
+        assert_eq!(SelectKanjiCodeSystem(KanjiCodeSystem::Jis).encode(), vec![0x1C, b'C', 0]);
+        assert_eq!(SelectKanjiCodeSystem(KanjiCodeSystem::ShiftJis).encode(), vec![0x1C, b'C', 1]);
+    }
+}
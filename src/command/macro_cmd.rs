@@ -3,6 +3,9 @@
 //! Macros allow storing a sequence of commands and replaying them.
 //! Maximum macro size is 2048 bytes.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Command, GS};
 
 /// Toggle macro definition mode.
@@ -22,17 +25,15 @@ use super::{Command, GS};
 /// printer.send(ToggleMacroDefinition)?;  // End recording
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ToggleMacroDefinition;
 
-impl Command for ToggleMacroDefinition {
-    fn encode(&self) -> Vec<u8> {
-        vec![GS, b':']
-    }
-}
+impl_static_command!(ToggleMacroDefinition => GS, b':');
 
 /// Macro execution mode.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MacroExecutionMode {
     /// Execute macro continuously.
     #[default]
@@ -51,6 +52,7 @@ pub enum MacroExecutionMode {
 /// - `wait_ms`: Wait time between executions in 100ms units (0-255)
 /// - `mode`: Execution mode (continuous or wait for button)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecuteMacro {
     /// Number of times to execute (1-255).
     pub times: u8,
@@ -2,7 +2,11 @@
 //!
 //! Page mode buffers all output and prints when FormFeed is sent.
 
-use super::{Command, ESC, GS};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::spacing::{Length, SetMotionUnits};
+use super::{Command, ESC, FF, GS};
 
 /// Enter page mode.
 ///
@@ -11,13 +15,10 @@ use super::{Command, ESC, GS};
 ///
 /// ESC/POS: `ESC L` (0x1B 0x4C)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnterPageMode;
 
-impl Command for EnterPageMode {
-    fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'L']
-    }
-}
+impl_static_command!(EnterPageMode => ESC, b'L');
 
 /// Exit page mode and return to standard mode.
 ///
@@ -25,17 +26,28 @@ impl Command for EnterPageMode {
 ///
 /// ESC/POS: `ESC S` (0x1B 0x53)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExitPageMode;
 
-impl Command for ExitPageMode {
-    fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'S']
-    }
-}
+impl_static_command!(ExitPageMode => ESC, b'S');
+
+/// Print data in page mode without clearing the buffer.
+///
+/// Prints all buffered data in the printing area but, unlike FormFeed,
+/// keeps the data and stays in page mode. Use this to print the same page
+/// several times before clearing it with FormFeed or [`ExitPageMode`].
+///
+/// ESC/POS: `ESC FF` (0x1B 0x0C)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintPageModeData;
+
+impl_static_command!(PrintPageModeData => ESC, FF);
 
 /// Print direction in page mode.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrintDirection {
     /// Left to right, starting upper-left.
     #[default]
@@ -52,6 +64,7 @@ pub enum PrintDirection {
 ///
 /// ESC/POS: `ESC T n` (0x1B 0x54 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetPrintDirection(pub PrintDirection);
 
 impl Command for SetPrintDirection {
@@ -62,6 +75,7 @@ impl Command for SetPrintDirection {
 
 /// Print area definition for page mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrintArea {
     /// Horizontal start position.
     pub x: u16,
@@ -93,6 +107,36 @@ impl PrintArea {
             height: 1662,
         }
     }
+
+    /// Create a print area from millimeter dimensions using the given motion units.
+    ///
+    /// `x` and `width` use horizontal units; `y` and `height` use vertical units.
+    pub fn from_mm(x: f32, y: f32, width: f32, height: f32, units: &SetMotionUnits) -> Self {
+        Self {
+            x: units.horizontal_from_mm(x),
+            y: units.vertical_from_mm(y),
+            width: units.horizontal_from_mm(width),
+            height: units.vertical_from_mm(height),
+        }
+    }
+
+    /// Create a print area from [`Length`]s using the given motion units.
+    ///
+    /// `x` and `width` use horizontal units; `y` and `height` use vertical units.
+    pub fn from_lengths(
+        x: impl Length,
+        y: impl Length,
+        width: impl Length,
+        height: impl Length,
+        units: &SetMotionUnits,
+    ) -> Self {
+        Self {
+            x: x.horizontal_units(units),
+            y: y.vertical_units(units),
+            width: width.horizontal_units(units),
+            height: height.vertical_units(units),
+        }
+    }
 }
 
 /// Set printing area in page mode.
@@ -101,6 +145,7 @@ impl PrintArea {
 ///
 /// ESC/POS: `ESC W xL xH yL yH dxL dxH dyL dyH`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetPrintArea(pub PrintArea);
 
 impl Command for SetPrintArea {
@@ -128,8 +173,20 @@ impl Command for SetPrintArea {
 ///
 /// ESC/POS: `ESC $ nL nH` (0x1B 0x24 nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetHorizontalPosition(pub u16);
 
+impl SetHorizontalPosition {
+    /// Create from a distance in millimeters using the given motion units.
+    pub fn from_mm(mm: f32, units: &SetMotionUnits) -> Self {
+        Self(units.horizontal_from_mm(mm))
+    }
+    /// Create from any [`Length`] using the given motion units.
+    pub fn from_length(length: impl Length, units: &SetMotionUnits) -> Self {
+        Self(length.horizontal_units(units))
+    }
+}
+
 impl Command for SetHorizontalPosition {
     fn encode(&self) -> Vec<u8> {
         let nl = (self.0 & 0xFF) as u8;
@@ -142,8 +199,20 @@ impl Command for SetHorizontalPosition {
 ///
 /// ESC/POS: `GS $ nL nH` (0x1D 0x24 nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetVerticalPosition(pub u16);
 
+impl SetVerticalPosition {
+    /// Create from a distance in millimeters using the given motion units.
+    pub fn from_mm(mm: f32, units: &SetMotionUnits) -> Self {
+        Self(units.vertical_from_mm(mm))
+    }
+    /// Create from any [`Length`] using the given motion units.
+    pub fn from_length(length: impl Length, units: &SetMotionUnits) -> Self {
+        Self(length.vertical_units(units))
+    }
+}
+
 impl Command for SetVerticalPosition {
     fn encode(&self) -> Vec<u8> {
         let nl = (self.0 & 0xFF) as u8;
@@ -166,6 +235,11 @@ mod tests {
         assert_eq!(ExitPageMode.encode(), vec![0x1B, b'S']);
     }
 
+    #[test]
+    fn print_page_mode_data_encodes() {
+        assert_eq!(PrintPageModeData.encode(), vec![0x1B, 0x0C]);
+    }
+
     #[test]
     fn print_direction_values() {
         assert_eq!(PrintDirection::LeftToRight as u8, 0);
@@ -200,6 +274,44 @@ mod tests {
         assert_eq!(cmd.encode(), vec![0x1D, b'$', 0, 1]);
     }
 
+    #[test]
+    fn print_area_from_mm() {
+        let units = SetMotionUnits::new(180, 90);
+        let area = PrintArea::from_mm(0.0, 0.0, 25.4, 50.8, &units);
+        assert_eq!(area.width, 180);
+        assert_eq!(area.height, 180);
+    }
+
+    #[test]
+    fn positions_from_mm() {
+        let units = SetMotionUnits::default();
+        assert_eq!(SetHorizontalPosition::from_mm(25.4, &units), SetHorizontalPosition(180));
+        assert_eq!(SetVerticalPosition::from_mm(25.4, &units), SetVerticalPosition(360));
+    }
+
+    #[test]
+    fn positions_and_area_from_lengths() {
+        use crate::command::spacing::{Dots, Inch, Mm};
+
+        let units = SetMotionUnits::default();
+        assert_eq!(
+            SetHorizontalPosition::from_length(Inch(0.5), &units),
+            SetHorizontalPosition(90)
+        );
+        assert_eq!(SetVerticalPosition::from_length(Dots(100), &units), SetVerticalPosition(200));
+
+        let area = PrintArea::from_lengths(Mm(0.0), Dots(10), Dots(512), Inch(2.0), &units);
+        assert_eq!(
+            area,
+            PrintArea {
+                x: 0,
+                y: 20,
+                width: 512,
+                height: 720,
+            }
+        );
+    }
+
     #[test]
     fn set_horizontal_position_encodes() {
         let cmd = SetHorizontalPosition(256);
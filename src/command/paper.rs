@@ -1,11 +1,15 @@
 //! Paper feed and cutting commands.
 
-use super::{Command, ESC, GS};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Command, Dialect, ESC, GS};
 
 /// Print buffer and feed paper by specified dots.
 ///
 /// ESC/POS: `ESC J n` (0x1B 0x4A n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeedPaper(pub u8);
 
 impl Command for FeedPaper {
@@ -18,6 +22,7 @@ impl Command for FeedPaper {
 ///
 /// ESC/POS: `ESC d n` (0x1B 0x64 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeedLines(pub u8);
 
 impl Command for FeedLines {
@@ -29,6 +34,7 @@ impl Command for FeedLines {
 /// Cut mode selection.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CutMode {
     /// Full cut (or partial, depending on hardware setting).
     #[default]
@@ -44,7 +50,11 @@ pub enum CutMode {
 /// Cut paper.
 ///
 /// ESC/POS: `GS V m [n]` (0x1D 0x56 m [n])
+///
+/// Star: `ESC d n` (0x1B 0x64 n). Star printers choose the feed before a
+/// feed-and-cut themselves, so `feed_lines` is ignored.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CutPaper {
     /// Cut mode.
     pub mode: CutMode,
@@ -99,6 +109,21 @@ impl Command for CutPaper {
             None => vec![GS, b'V', self.mode as u8],
         }
     }
+
+    fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+        match dialect {
+            Dialect::Bixolon | Dialect::Epson => self.encode(),
+            Dialect::Star => {
+                let n = match self.mode {
+                    CutMode::Full => 0,
+                    CutMode::Partial => 1,
+                    CutMode::FeedAndFull => 2,
+                    CutMode::FeedAndPartial => 3,
+                };
+                vec![ESC, b'd', n]
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +165,11 @@ mod tests {
         let cmd = CutPaper::feed_and_partial(5);
         assert_eq!(cmd.encode(), vec![0x1D, b'V', 66, 5]);
     }
+
+    #[test]
+    fn cuts_encode_for_star() {
+        assert_eq!(CutPaper::full().encode_for(Dialect::Star), [0x1B, b'd', 0]);
+        assert_eq!(CutPaper::feed_and_partial(3).encode_for(Dialect::Star), [0x1B, b'd', 3]);
+        assert_eq!(CutPaper::partial().encode_for(Dialect::Epson), CutPaper::partial().encode());
+    }
 }
@@ -2,7 +2,10 @@
 //!
 //! Commands for initializing the printer, selecting peripherals, and generating pulses.
 
-use super::{Command, ESC};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Command, Dialect, ESC, GS};
 
 /// Initialize the printer.
 ///
@@ -11,17 +14,15 @@ use super::{Command, ESC};
 ///
 /// ESC/POS: `ESC @` (0x1B 0x40)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Initialize;
 
-impl Command for Initialize {
-    fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'@']
-    }
-}
+impl_static_command!(Initialize => ESC, b'@');
 
 /// Peripheral device selection.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PeripheralDevice {
     /// Send data to printer only.
     #[default]
@@ -38,6 +39,7 @@ pub enum PeripheralDevice {
 ///
 /// ESC/POS: `ESC = n` (0x1B 0x3D n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectPeripheral(pub PeripheralDevice);
 
 impl Default for SelectPeripheral {
@@ -55,6 +57,7 @@ impl Command for SelectPeripheral {
 /// Drawer kick-out connector pin selection.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawerPin {
     /// Drawer kick-out connector pin 2.
     #[default]
@@ -70,12 +73,17 @@ pub enum DrawerPin {
 ///
 /// ESC/POS: `ESC p m t1 t2` (0x1B 0x70 m t1 t2)
 ///
+/// Star: `ESC BEL t1 t2 BEL` (0x1B 0x07 t1 t2 0x07) for pin 2, with times
+/// in 10ms units, and `SUB` (0x1A) for pin 5, whose pulse width Star
+/// printers fix.
+///
 /// # Parameters
 ///
 /// - `pin`: Which connector pin to pulse
 /// - `on_time_ms`: Pulse ON time in milliseconds (will be rounded to nearest 2ms)
 /// - `off_time_ms`: Pulse OFF time in milliseconds (will be rounded to nearest 2ms)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneratePulse {
     /// Which pin to generate pulse on.
     pub pin: DrawerPin,
@@ -116,6 +124,56 @@ impl Command for GeneratePulse {
         let t2 = (self.off_time_ms / 2).min(255) as u8;
         vec![ESC, b'p', self.pin as u8, t1, t2]
     }
+
+    fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+        const BEL: u8 = 0x07;
+        const SUB: u8 = 0x1A;
+        match (dialect, self.pin) {
+            (Dialect::Bixolon | Dialect::Epson, _) => self.encode(),
+            (Dialect::Star, DrawerPin::Pin2) => {
+                let t1 = (self.on_time_ms / 10) as u8;
+                let t2 = (self.off_time_ms / 10) as u8;
+                vec![ESC, BEL, t1, t2, BEL]
+            }
+            (Dialect::Star, DrawerPin::Pin5) => vec![SUB],
+        }
+    }
+
+    fn encode_into_for(&self, dialect: Dialect, output: &mut Vec<u8>) {
+        output.extend(self.encode_for(dialect));
+    }
+}
+
+/// Test print pattern selection.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TestPattern {
+    /// Hexadecimal dump mode.
+    HexDump = 1,
+    /// Printer status sheet (settings and firmware information).
+    #[default]
+    StatusSheet = 2,
+    /// Rolling character pattern.
+    RollingPattern = 3,
+}
+
+/// Execute a test print.
+///
+/// Triggers the same diagnostics as the power-on button combination,
+/// without physical access to the printer. Only valid in standard mode
+/// at the beginning of a line.
+///
+/// ESC/POS: `GS ( A pL pH n m` (0x1D 0x28 0x41 0x02 0x00 0x00 m)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintTestPage(pub TestPattern);
+
+impl Command for PrintTestPage {
+    fn encode(&self) -> Vec<u8> {
+        // n = 0: print on the paper roll
+        vec![GS, b'(', b'A', 2, 0, 0, self.0 as u8]
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +242,18 @@ mod tests {
         assert_eq!(cmd.off_time_ms, 200);
     }
 
+    #[test]
+    fn print_test_page_status_sheet() {
+        let cmd = PrintTestPage(TestPattern::StatusSheet);
+        assert_eq!(cmd.encode(), vec![0x1D, b'(', b'A', 2, 0, 0, 2]);
+    }
+
+    #[test]
+    fn print_test_page_rolling_pattern() {
+        let cmd = PrintTestPage(TestPattern::RollingPattern);
+        assert_eq!(cmd.encode(), vec![0x1D, b'(', b'A', 2, 0, 0, 3]);
+    }
+
     #[test]
     fn peripheral_device_default_is_printer() {
         assert_eq!(PeripheralDevice::default(), PeripheralDevice::PrinterOnly);
@@ -193,4 +263,13 @@ mod tests {
     fn drawer_pin_default_is_pin2() {
         assert_eq!(DrawerPin::default(), DrawerPin::Pin2);
     }
+
+    #[test]
+    fn pulses_encode_for_star() {
+        let pulse = GeneratePulse::new(DrawerPin::Pin2, 200, 250);
+        assert_eq!(pulse.encode_for(Dialect::Star), [0x1B, 0x07, 20, 25, 0x07]);
+        assert_eq!(pulse.encode_for(Dialect::Epson), pulse.encode());
+        let pulse = GeneratePulse::new(DrawerPin::Pin5, 200, 200);
+        assert_eq!(pulse.encode_for(Dialect::Star), [0x1A]);
+    }
 }
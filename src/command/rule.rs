@@ -0,0 +1,190 @@
+//! Horizontal rule helpers.
+//!
+//! Full-width separator lines, either as a row of CP437 box-drawing
+//! characters or as a thin raster strip. Both can be sized from a
+//! [`PrinterProfile`] so receipts don't rely on hand-counted `"-----"`
+//! strings.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::image::PrintRasterImage;
+use super::{Command, LF};
+use crate::profile::PrinterProfile;
+
+/// Horizontal rule weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleStyle {
+    /// Single thin line.
+    #[default]
+    Light,
+    /// Single thick line.
+    Heavy,
+    /// Two thin parallel lines.
+    Double,
+}
+
+impl RuleStyle {
+    /// CP437 character used for text rules.
+    pub const fn cp437_char(self) -> u8 {
+        match self {
+            // ─
+            RuleStyle::Light => 0xC4,
+            // ▄
+            RuleStyle::Heavy => 0xDC,
+            // ═
+            RuleStyle::Double => 0xCD,
+        }
+    }
+
+    /// Raster rows for raster rules, top to bottom (`true` = printed).
+    const fn raster_rows(self) -> &'static [bool] {
+        match self {
+            RuleStyle::Light => &[true, true],
+            RuleStyle::Heavy => &[true, true, true, true, true, true],
+            RuleStyle::Double => &[true, true, false, false, true, true],
+        }
+    }
+}
+
+/// Print a horizontal rule of box-drawing characters followed by a line feed.
+///
+/// Requires code page PC437 (the power-on default). With other code pages
+/// the bytes map to different glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextRule {
+    /// Line weight.
+    pub style: RuleStyle,
+    /// Number of characters.
+    pub columns: u8,
+}
+
+impl TextRule {
+    /// Create a rule of `columns` characters.
+    pub const fn new(style: RuleStyle, columns: u8) -> Self {
+        Self {
+            style,
+            columns,
+        }
+    }
+
+    /// Create a rule spanning the Font A line width of `profile`.
+    pub const fn full_width(style: RuleStyle, profile: &PrinterProfile) -> Self {
+        Self::new(style, profile.font_a_columns)
+    }
+}
+
+impl Command for TextRule {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![self.style.cp437_char(); self.columns as usize];
+        bytes.push(LF);
+        bytes
+    }
+}
+
+/// Print a horizontal rule as a raster image strip.
+///
+/// Independent of the selected code page and font. Widths that are not a
+/// multiple of 8 are padded with unprinted dots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RasterRule {
+    /// Line weight.
+    pub style: RuleStyle,
+    /// Width in dots.
+    pub width_dots: u16,
+}
+
+impl RasterRule {
+    /// Create a rule `width_dots` wide.
+    pub const fn new(style: RuleStyle, width_dots: u16) -> Self {
+        Self {
+            style,
+            width_dots,
+        }
+    }
+
+    /// Create a rule spanning the printable width of `profile`.
+    pub const fn full_width(style: RuleStyle, profile: &PrinterProfile) -> Self {
+        Self::new(style, profile.paper_width_dots)
+    }
+
+    /// Build the equivalent raster image command.
+    pub fn to_raster(&self) -> PrintRasterImage {
+        let width_bytes = self.width_dots.div_ceil(8);
+        let rows = self.style.raster_rows();
+
+        let mut line = vec![0xFF; width_bytes as usize];
+        let remainder = self.width_dots % 8;
+        if let Some(last) = line.last_mut()
+            && remainder != 0
+        {
+            *last = 0xFF << (8 - remainder);
+        }
+        let blank = vec![0x00; width_bytes as usize];
+
+        let mut data = Vec::with_capacity(line.len() * rows.len());
+        for &printed in rows {
+            data.extend_from_slice(if printed {
+                &line
+            } else {
+                &blank
+            });
+        }
+
+        PrintRasterImage::new(width_bytes, rows.len() as u16, data)
+    }
+}
+
+impl Command for RasterRule {
+    fn encode(&self) -> Vec<u8> {
+        self.to_raster().encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_rule_encodes_box_characters() {
+        let rule = TextRule::new(RuleStyle::Double, 4);
+        assert_eq!(rule.encode(), vec![0xCD, 0xCD, 0xCD, 0xCD, LF]);
+    }
+
+    #[test]
+    fn text_rule_full_width_uses_profile_columns() {
+        let rule = TextRule::full_width(RuleStyle::Light, &PrinterProfile::SRP_350PLUS);
+        assert_eq!(rule.encode().len(), 42 + 1);
+    }
+
+    #[test]
+    fn raster_rule_light() {
+        let raster = RasterRule::new(RuleStyle::Light, 16).to_raster();
+        assert_eq!(raster.width_bytes, 2);
+        assert_eq!(raster.height_dots, 2);
+        assert_eq!(raster.data, vec![0xFF; 4]);
+    }
+
+    #[test]
+    fn raster_rule_double_has_gap() {
+        let raster = RasterRule::new(RuleStyle::Double, 8).to_raster();
+        assert_eq!(raster.data, vec![0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn raster_rule_masks_partial_byte() {
+        let raster = RasterRule::new(RuleStyle::Light, 12).to_raster();
+        assert_eq!(raster.data, vec![0xFF, 0xF0, 0xFF, 0xF0]);
+    }
+
+    #[test]
+    fn raster_rule_full_width() {
+        let raster =
+            RasterRule::full_width(RuleStyle::Heavy, &PrinterProfile::SRP_350PLUS).to_raster();
+        assert_eq!(raster.width_bytes, 64);
+        assert_eq!(raster.height_dots, 6);
+    }
+}
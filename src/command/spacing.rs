@@ -1,23 +1,26 @@
 //! Spacing and positioning commands.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Command, ESC, GS};
+use crate::error::ValidationError;
+use crate::profile::PrinterProfile;
 
 /// Set default line spacing (~4.23mm / 1/6 inch).
 ///
 /// ESC/POS: `ESC 2` (0x1B 0x32)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetDefaultLineSpacing;
 
-impl Command for SetDefaultLineSpacing {
-    fn encode(&self) -> Vec<u8> {
-        vec![ESC, b'2']
-    }
-}
+impl_static_command!(SetDefaultLineSpacing => ESC, b'2');
 
 /// Set line spacing in vertical motion units.
 ///
 /// ESC/POS: `ESC 3 n` (0x1B 0x33 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetLineSpacing(pub u8);
 
 impl Command for SetLineSpacing {
@@ -30,6 +33,7 @@ impl Command for SetLineSpacing {
 ///
 /// ESC/POS: `ESC SP n` (0x1B 0x20 n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetRightSpacing(pub u8);
 
 impl Command for SetRightSpacing {
@@ -42,12 +46,90 @@ impl Command for SetRightSpacing {
 ///
 /// ESC/POS: `ESC D n1...nk NUL` (0x1B 0x44 n1...nk 0x00)
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetHorizontalTabs {
     /// Tab positions (must be in ascending order, max 32 positions).
     pub positions: Vec<u8>,
 }
 
 impl SetHorizontalTabs {
+    /// Maximum number of tab stops.
+    pub const MAX_STOPS: usize = 32;
+
+    /// Create tab positions with validation.
+    ///
+    /// Positions are column numbers (1-255) and must be strictly ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::InvalidTabPosition`] for a zero or
+    /// out-of-order position, or [`ValidationError::OutOfRange`] if there
+    /// are more than 32 stops.
+    pub fn new(positions: impl Into<Vec<u8>>) -> Result<Self, ValidationError> {
+        let positions = positions.into();
+        if positions.len() > Self::MAX_STOPS {
+            return Err(ValidationError::OutOfRange {
+                name: "tab stop count",
+                value: positions.len() as u16,
+                min: 0,
+                max: Self::MAX_STOPS as u16,
+            });
+        }
+
+        let mut previous = 0;
+        for &position in &positions {
+            if position <= previous {
+                return Err(ValidationError::InvalidTabPosition(position));
+            }
+            previous = position;
+        }
+
+        Ok(Self {
+            positions,
+        })
+    }
+
+    /// Create tab positions validated against the Font A line width of
+    /// `profile`.
+    ///
+    /// # Errors
+    ///
+    /// As [`SetHorizontalTabs::new`], and also returns
+    /// [`ValidationError::InvalidTabPosition`] for a position at or beyond
+    /// the end of the line.
+    pub fn for_profile(
+        positions: impl Into<Vec<u8>>,
+        profile: &PrinterProfile,
+    ) -> Result<Self, ValidationError> {
+        let tabs = Self::new(positions)?;
+        if let Some(&position) = tabs.positions.iter().find(|&&p| p >= profile.font_a_columns) {
+            return Err(ValidationError::InvalidTabPosition(position));
+        }
+        Ok(tabs)
+    }
+
+    /// Derive tab stops from column widths.
+    ///
+    /// Each column starts where the previous one ends, so `[20, 10, 12]`
+    /// sets stops at 20 and 30.
+    ///
+    /// # Errors
+    ///
+    /// As [`SetHorizontalTabs::for_profile`]; a zero-width column produces
+    /// a repeated position and is rejected.
+    pub fn from_column_widths(
+        widths: &[u8],
+        profile: &PrinterProfile,
+    ) -> Result<Self, ValidationError> {
+        let mut position = 0u16;
+        let mut positions = Vec::with_capacity(widths.len().saturating_sub(1));
+        for &width in widths.iter().take(widths.len().saturating_sub(1)) {
+            position += u16::from(width);
+            positions.push(u8::try_from(position).unwrap_or(u8::MAX));
+        }
+        Self::for_profile(positions, profile)
+    }
+
     /// Clear all tab positions.
     pub fn clear() -> Self {
         Self {
@@ -65,12 +147,186 @@ impl Command for SetHorizontalTabs {
     }
 }
 
+/// Set horizontal and vertical motion units.
+///
+/// Sets the horizontal unit to 1/`x` inch and the vertical unit to
+/// 1/`y` inch. A value of 0 restores the printer default for that axis.
+/// Positioning, spacing, and print area commands are measured in these units.
+///
+/// ESC/POS: `GS P x y` (0x1D 0x50 x y)
+/// Default: x = 180, y = 360. [`SetMotionUnits::default()`] sends 0 for both,
+/// restoring these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetMotionUnits {
+    /// Horizontal motion units per inch.
+    pub x: u8,
+    /// Vertical motion units per inch.
+    pub y: u8,
+}
+
+impl SetMotionUnits {
+    /// Default horizontal motion units per inch.
+    pub const DEFAULT_X: u16 = 180;
+
+    /// Default vertical motion units per inch.
+    pub const DEFAULT_Y: u16 = 360;
+
+    /// Create with the given horizontal and vertical units per inch.
+    pub const fn new(x: u8, y: u8) -> Self {
+        Self {
+            x,
+            y,
+        }
+    }
+
+    /// Horizontal units per inch, resolving 0 to the printer default.
+    pub const fn horizontal_per_inch(&self) -> u16 {
+        if self.x == 0 {
+            Self::DEFAULT_X
+        } else {
+            self.x as u16
+        }
+    }
+
+    /// Vertical units per inch, resolving 0 to the printer default.
+    pub const fn vertical_per_inch(&self) -> u16 {
+        if self.y == 0 {
+            Self::DEFAULT_Y
+        } else {
+            self.y as u16
+        }
+    }
+
+    /// Convert millimeters to horizontal motion units.
+    ///
+    /// Rounds to the nearest unit and saturates at `u16::MAX`.
+    pub fn horizontal_from_mm(&self, mm: f32) -> u16 {
+        mm_to_units(mm, self.horizontal_per_inch())
+    }
+
+    /// Convert millimeters to vertical motion units.
+    ///
+    /// Rounds to the nearest unit and saturates at `u16::MAX`.
+    pub fn vertical_from_mm(&self, mm: f32) -> u16 {
+        mm_to_units(mm, self.vertical_per_inch())
+    }
+}
+
+impl Command for SetMotionUnits {
+    fn encode(&self) -> Vec<u8> {
+        vec![GS, b'P', self.x, self.y]
+    }
+}
+
+fn mm_to_units(mm: f32, per_inch: u16) -> u16 {
+    // `as` truncates and saturates, clamping negatives to 0, so adding a
+    // half rounds without `f32::round`, which needs std
+    (mm / 25.4 * f32::from(per_inch) + 0.5) as u16
+}
+
+/// A distance that converts to motion units.
+///
+/// Implemented by [`Mm`], [`Inch`] and [`Dots`] for physical dimensions,
+/// and by `u16` for values already in motion units, which pass through
+/// unchanged.
+pub trait Length {
+    /// The distance in horizontal motion units.
+    fn horizontal_units(&self, units: &SetMotionUnits) -> u16;
+
+    /// The distance in vertical motion units.
+    fn vertical_units(&self, units: &SetMotionUnits) -> u16;
+}
+
+impl Length for u16 {
+    fn horizontal_units(&self, _units: &SetMotionUnits) -> u16 {
+        *self
+    }
+
+    fn vertical_units(&self, _units: &SetMotionUnits) -> u16 {
+        *self
+    }
+}
+
+/// A distance in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mm(pub f32);
+
+impl Length for Mm {
+    fn horizontal_units(&self, units: &SetMotionUnits) -> u16 {
+        units.horizontal_from_mm(self.0)
+    }
+
+    fn vertical_units(&self, units: &SetMotionUnits) -> u16 {
+        units.vertical_from_mm(self.0)
+    }
+}
+
+/// A distance in inches.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inch(pub f32);
+
+impl Length for Inch {
+    fn horizontal_units(&self, units: &SetMotionUnits) -> u16 {
+        Mm(self.0 * 25.4).horizontal_units(units)
+    }
+
+    fn vertical_units(&self, units: &SetMotionUnits) -> u16 {
+        Mm(self.0 * 25.4).vertical_units(units)
+    }
+}
+
+/// A distance in print head dots (180 per inch).
+///
+/// Unlike a bare `u16`, this is converted when the motion units differ
+/// from the dot pitch, e.g. 10 dots are 20 units at the default vertical
+/// unit of 1/360 inch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dots(pub u16);
+
+impl Dots {
+    /// Print head dots per inch.
+    pub const PER_INCH: u16 = 180;
+}
+
+impl Length for Dots {
+    fn horizontal_units(&self, units: &SetMotionUnits) -> u16 {
+        dots_to_units(self.0, units.horizontal_per_inch())
+    }
+
+    fn vertical_units(&self, units: &SetMotionUnits) -> u16 {
+        dots_to_units(self.0, units.vertical_per_inch())
+    }
+}
+
+fn dots_to_units(dots: u16, per_inch: u16) -> u16 {
+    let units = (u32::from(dots) * u32::from(per_inch) + u32::from(Dots::PER_INCH) / 2)
+        / u32::from(Dots::PER_INCH);
+    units.min(u32::from(u16::MAX)) as u16
+}
+
 /// Set absolute print position.
 ///
 /// ESC/POS: `ESC $ nL nH` (0x1B 0x24 nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetAbsolutePosition(pub u16);
 
+impl SetAbsolutePosition {
+    /// Create from a distance in millimeters using the given motion units.
+    pub fn from_mm(mm: f32, units: &SetMotionUnits) -> Self {
+        Self(units.horizontal_from_mm(mm))
+    }
+
+    /// Create from any [`Length`] using the given motion units.
+    pub fn from_length(length: impl Length, units: &SetMotionUnits) -> Self {
+        Self(length.horizontal_units(units))
+    }
+}
+
 impl Command for SetAbsolutePosition {
     fn encode(&self) -> Vec<u8> {
         let nl = (self.0 & 0xFF) as u8;
@@ -83,14 +339,40 @@ impl Command for SetAbsolutePosition {
 ///
 /// ESC/POS: `ESC \ nL nH` (0x1B 0x5C nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetRelativePosition(pub i16);
 
-impl Command for SetRelativePosition {
-    fn encode(&self) -> Vec<u8> {
+impl SetRelativePosition {
+    /// Create from a signed distance in millimeters using the given motion units.
+    ///
+    /// Saturates at the `i16` range.
+    pub fn from_mm(mm: f32, units: &SetMotionUnits) -> Self {
+        let magnitude = units.horizontal_from_mm(mm.abs()).min(i16::MAX as u16) as i16;
+        Self(if mm < 0.0 {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+}
+
+impl SetRelativePosition {
+    /// Encoded bytes, without allocating.
+    pub const fn to_bytes(&self) -> [u8; 4] {
         let value = self.0 as u16;
         let nl = (value & 0xFF) as u8;
         let nh = ((value >> 8) & 0xFF) as u8;
-        vec![ESC, b'\\', nl, nh]
+        [ESC, b'\\', nl, nh]
+    }
+}
+
+impl Command for SetRelativePosition {
+    fn encode(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_bytes());
     }
 }
 
@@ -98,6 +380,7 @@ impl Command for SetRelativePosition {
 ///
 /// ESC/POS: `GS L nL nH` (0x1D 0x4C nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetLeftMargin(pub u16);
 
 impl Command for SetLeftMargin {
@@ -112,6 +395,7 @@ impl Command for SetLeftMargin {
 ///
 /// ESC/POS: `GS W nL nH` (0x1D 0x57 nL nH)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetPrintingWidth(pub u16);
 
 impl Command for SetPrintingWidth {
@@ -158,6 +442,102 @@ mod tests {
         assert_eq!(cmd.encode(), vec![0x1B, b'D', 0]);
     }
 
+    #[test]
+    fn set_horizontal_tabs_validates_order() {
+        assert!(SetHorizontalTabs::new(vec![8, 16, 24]).is_ok());
+        assert!(matches!(
+            SetHorizontalTabs::new(vec![8, 8]),
+            Err(ValidationError::InvalidTabPosition(8))
+        ));
+        assert!(matches!(
+            SetHorizontalTabs::new(vec![16, 8]),
+            Err(ValidationError::InvalidTabPosition(8))
+        ));
+        assert!(matches!(
+            SetHorizontalTabs::new(vec![0]),
+            Err(ValidationError::InvalidTabPosition(0))
+        ));
+    }
+
+    #[test]
+    fn set_horizontal_tabs_limits_count() {
+        let positions: Vec<u8> = (1..=33).collect();
+        assert!(matches!(
+            SetHorizontalTabs::new(positions),
+            Err(ValidationError::OutOfRange {
+                value: 33,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn set_horizontal_tabs_for_profile_limits_columns() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        assert!(SetHorizontalTabs::for_profile(vec![10, 41], &profile).is_ok());
+        assert!(matches!(
+            SetHorizontalTabs::for_profile(vec![10, 42], &profile),
+            Err(ValidationError::InvalidTabPosition(42))
+        ));
+    }
+
+    #[test]
+    fn set_horizontal_tabs_from_column_widths() {
+        let tabs =
+            SetHorizontalTabs::from_column_widths(&[20, 10, 12], &PrinterProfile::SRP_350PLUS)
+                .unwrap();
+        assert_eq!(tabs.positions, vec![20, 30]);
+    }
+
+    #[test]
+    fn set_motion_units_encodes() {
+        let cmd = SetMotionUnits::new(180, 180);
+        assert_eq!(cmd.encode(), vec![0x1D, b'P', 180, 180]);
+    }
+
+    #[test]
+    fn motion_units_zero_uses_default() {
+        let units = SetMotionUnits::default();
+        assert_eq!(units.horizontal_per_inch(), SetMotionUnits::DEFAULT_X);
+        assert_eq!(units.vertical_per_inch(), SetMotionUnits::DEFAULT_Y);
+    }
+
+    #[test]
+    fn motion_units_convert_mm() {
+        let units = SetMotionUnits::new(180, 180);
+        // 25.4mm = 1 inch = 180 units
+        assert_eq!(units.horizontal_from_mm(25.4), 180);
+        assert_eq!(units.vertical_from_mm(12.7), 90);
+        assert_eq!(units.horizontal_from_mm(-5.0), 0);
+    }
+
+    #[test]
+    fn absolute_position_from_mm() {
+        let units = SetMotionUnits::new(180, 180);
+        assert_eq!(SetAbsolutePosition::from_mm(25.4, &units), SetAbsolutePosition(180));
+    }
+
+    #[test]
+    fn relative_position_from_mm() {
+        let units = SetMotionUnits::new(180, 180);
+        assert_eq!(SetRelativePosition::from_mm(-25.4, &units), SetRelativePosition(-180));
+        assert_eq!(SetRelativePosition::from_mm(12.7, &units), SetRelativePosition(90));
+    }
+
+    #[test]
+    fn lengths_convert_through_motion_units() {
+        let units = SetMotionUnits::default();
+        assert_eq!(Mm(25.4).horizontal_units(&units), 180);
+        assert_eq!(Inch(1.0).vertical_units(&units), 360);
+        assert_eq!(Dots(10).horizontal_units(&units), 10);
+        assert_eq!(Dots(10).vertical_units(&units), 20);
+        assert_eq!(42u16.vertical_units(&units), 42);
+
+        let coarse = SetMotionUnits::new(90, 90);
+        assert_eq!(Dots(9).horizontal_units(&coarse), 5);
+        assert_eq!(Inch(0.5).vertical_units(&coarse), 45);
+    }
+
     #[test]
     fn set_absolute_position_encodes() {
         let cmd = SetAbsolutePosition(256);
@@ -2,12 +2,16 @@
 //!
 //! These commands query printer state and require reading a response.
 
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::{Command, DLE, GS, QueryCommand};
 use crate::error::StatusParseError;
 
 /// Real-time status type for DLE EOT.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatusType {
     /// Printer status (online, drawer, paper).
     Printer = 1,
@@ -25,6 +29,7 @@ pub enum StatusType {
 ///
 /// This is a real-time command that can be sent even during printing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransmitStatus(pub StatusType);
 
 impl Command for TransmitStatus {
@@ -35,6 +40,7 @@ impl Command for TransmitStatus {
 
 /// Printer status response.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrinterStatus {
     /// Drawer kick-out connector pin 3 status.
     pub drawer_open: bool,
@@ -59,6 +65,7 @@ impl PrinterStatus {
 
 /// Offline status response.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OfflineStatus {
     /// Cover is open.
     pub cover_open: bool,
@@ -83,6 +90,7 @@ impl OfflineStatus {
 
 /// Error status response.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ErrorStatus {
     /// Recoverable error occurred.
     pub recoverable_error: bool,
@@ -104,6 +112,7 @@ impl ErrorStatus {
 
 /// Paper roll sensor status response.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PaperRollStatus {
     /// Paper near end detected.
     pub paper_near_end: bool,
@@ -122,6 +131,7 @@ impl PaperRollStatus {
 
 /// Combined status response from TransmitStatus.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatusResponse {
     /// Printer status.
     Printer(PrinterStatus),
@@ -153,6 +163,7 @@ impl QueryCommand for TransmitStatus {
 
 /// ASB (Automatic Status Back) enable flags.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsbFlags {
     /// Enable drawer kick-out connector status.
     pub drawer: bool,
@@ -208,8 +219,12 @@ impl AsbFlags {
 /// ESC/POS: `GS a n` (0x1D 0x61 n)
 ///
 /// When enabled, the printer automatically transmits status when
-/// status changes occur.
+/// status changes occur, as [`ASB_PACKET_LEN`]-byte packets that
+/// [`PrinterStatus::from_asb`] parses. A
+/// [`Printer`](crate::printer::Printer) query that reads one first records
+/// it in the status monitor and keeps reading for its own response.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnableAsb(pub AsbFlags);
 
 impl Command for EnableAsb {
@@ -218,6 +233,39 @@ impl Command for EnableAsb {
     }
 }
 
+/// Length of an Automatic Status Back packet.
+pub const ASB_PACKET_LEN: usize = 4;
+
+/// Whether `byte` can start an Automatic Status Back packet.
+///
+/// An ASB packet's first byte has bits 0, 1 and 7 clear and bit 4 set.
+/// Real-time status responses always have bit 1 set, and the `GS (`
+/// responses start with `0x37`, so neither is mistaken for one.
+pub const fn is_asb_header(byte: u8) -> bool {
+    byte & 0x93 == 0x10
+}
+
+impl PrinterStatus {
+    /// Parse the first [`ASB_PACKET_LEN`] bytes of `packet` as an
+    /// Automatic Status Back packet.
+    ///
+    /// Returns `None` if `packet` is too short or its fixed bits don't
+    /// match: a header for which [`is_asb_header`] holds, followed by three
+    /// bytes with bits 4 and 7 clear.
+    pub fn from_asb(packet: &[u8]) -> Option<Self> {
+        let [first, _, paper, _] = *packet.first_chunk::<ASB_PACKET_LEN>()?;
+        if !is_asb_header(first) || packet[1..ASB_PACKET_LEN].iter().any(|b| b & 0x90 != 0) {
+            return None;
+        }
+        Some(Self {
+            drawer_open: first & 0x04 != 0,
+            online: first & 0x08 == 0,
+            feed_button_pressed: first & 0x40 != 0,
+            paper_present: paper & 0x0C == 0,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,6 +309,25 @@ mod tests {
         assert_eq!(flags.to_byte(), 0x05);
     }
 
+    #[test]
+    fn asb_packet_parses() {
+        let status = PrinterStatus::from_asb(&[0x14, 0x00, 0x0C, 0x00]).unwrap();
+        assert!(status.drawer_open);
+        assert!(status.online);
+        assert!(!status.paper_present);
+
+        let status = PrinterStatus::from_asb(&[0x58, 0x00, 0x00, 0x00, 0x12]).unwrap();
+        assert!(!status.online);
+        assert!(status.feed_button_pressed);
+        assert!(status.paper_present);
+
+        // Real-time status responses and broken packets aren't ASB
+        assert!(!is_asb_header(0x12));
+        assert_eq!(PrinterStatus::from_asb(&[0x12, 0x00, 0x00, 0x00]), None);
+        assert_eq!(PrinterStatus::from_asb(&[0x10, 0x10, 0x00, 0x00]), None);
+        assert_eq!(PrinterStatus::from_asb(&[0x10, 0x00, 0x00]), None);
+    }
+
     #[test]
     fn enable_asb_encodes() {
         let cmd = EnableAsb(AsbFlags::all());
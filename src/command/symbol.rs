@@ -1,11 +1,21 @@
-//! 2D symbol commands (QR Code, PDF417).
+//! 2D symbol commands (QR Code, PDF417, DataMatrix, GS1 DataBar).
 
-use super::{Command, GS};
-use crate::error::{Pdf417Error, QrCodeError};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::gs1::{self, Gs1Element};
+use super::image::PrintRasterImage;
+use super::{Command, GS, QueryCommand};
+use crate::error::{
+    DataMatrixError, Gs1Error, Pdf417Error, PrinterError, QrCodeError, StatusParseError,
+};
+use crate::profile::PrinterProfile;
 
 /// QR Code model.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QrModel {
     /// Model 1 - Original QR Code.
     Model1 = 49,
@@ -17,6 +27,7 @@ pub enum QrModel {
 /// QR Code error correction level.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QrErrorCorrection {
     /// Level L - approximately 7% recovery capacity.
     #[default]
@@ -32,6 +43,7 @@ pub enum QrErrorCorrection {
 /// QR Code module size (1-8 dots per module).
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QrModuleSize {
     /// 1 dot per module (smallest).
     Size1 = 1,
@@ -60,7 +72,12 @@ pub enum QrModuleSize {
 /// 3. Set error correction
 /// 4. Store data
 /// 5. Print symbol
+///
+/// Each step is also available as a separate command ([`SetQrModel`],
+/// [`SetQrModuleSize`], [`SetQrErrorCorrection`], [`StoreQrData`],
+/// [`PrintStoredQr`]) for storing a symbol once and printing it repeatedly.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrintQrCode {
     /// QR Code model.
     pub model: QrModel,
@@ -76,12 +93,330 @@ impl PrintQrCode {
     /// Create a QR code with default settings.
     pub fn new(data: impl Into<Vec<u8>>) -> Result<Self, QrCodeError> {
         let data = data.into();
-        if data.is_empty() {
-            return Err(QrCodeError::EmptyData);
+        validate_qr_data(&data)?;
+        Ok(Self {
+            model: QrModel::default(),
+            module_size: QrModuleSize::default(),
+            error_correction: QrErrorCorrection::default(),
+            data,
+        })
+    }
+
+    /// Set the QR code model.
+    pub fn with_model(mut self, model: QrModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the module size.
+    pub fn with_module_size(mut self, size: QrModuleSize) -> Self {
+        self.module_size = size;
+        self
+    }
+
+    /// Set the error correction level.
+    pub fn with_error_correction(mut self, level: QrErrorCorrection) -> Self {
+        self.error_correction = level;
+        self
+    }
+
+    /// Create a QR code from binary data encoded in byte mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::ExceedsCapacity`] if the data does not fit in
+    /// a version 40 symbol at the given error correction level.
+    pub fn bytes(data: impl Into<Vec<u8>>, level: QrErrorCorrection) -> Result<Self, QrCodeError> {
+        let qr = Self::new(data)?.with_error_correction(level);
+        qr.check_byte_capacity()?;
+        Ok(qr)
+    }
+
+    /// Create a QR code from Shift-JIS encoded Kanji characters.
+    ///
+    /// Every character must be a two-byte Shift-JIS code in the Kanji mode
+    /// ranges (0x8140-0x9FFC, 0xE040-0xEBBF), so the printer encodes the
+    /// payload in Kanji mode at 13 bits per character.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::InvalidKanji`] if the data contains other
+    /// bytes, or [`QrCodeError::ExceedsCapacity`] if it does not fit at the
+    /// given error correction level.
+    pub fn kanji(sjis: impl Into<Vec<u8>>, level: QrErrorCorrection) -> Result<Self, QrCodeError> {
+        let data = sjis.into();
+        let chars = count_kanji(&data)?;
+        let max = qr_capacity(level).kanji;
+        if chars > max {
+            return Err(QrCodeError::ExceedsCapacity {
+                mode: "Kanji",
+                length: chars,
+                max,
+            });
         }
-        if data.len() > 7089 {
-            return Err(QrCodeError::DataTooLong(data.len()));
+        Ok(Self::new(data)?.with_error_correction(level))
+    }
+
+    /// Check the data length against the byte mode capacity for the
+    /// current error correction level.
+    ///
+    /// Useful after changing the level with [`with_error_correction`](Self::with_error_correction).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::ExceedsCapacity`] if the data is too long.
+    pub fn check_byte_capacity(&self) -> Result<(), QrCodeError> {
+        let max = qr_capacity(self.error_correction).bytes;
+        if self.data.len() > max {
+            return Err(QrCodeError::ExceedsCapacity {
+                mode: "byte",
+                length: self.data.len(),
+                max,
+            });
         }
+        Ok(())
+    }
+    /// The smallest Model 2 version whose byte mode capacity holds the
+    /// data at the current error correction level, or `None` if the data is
+    /// too long for version 40.
+    ///
+    /// Printers may pick a smaller version for data that suits numeric or
+    /// alphanumeric mode, so this is an upper bound.
+    pub fn version(&self) -> Option<u8> {
+        let level = self.error_correction as usize - QrErrorCorrection::L as usize;
+        let position = QR_BYTE_CAPACITY
+            .iter()
+            .position(|capacities| usize::from(capacities[level]) >= self.data.len())?;
+        Some(position as u8 + 1)
+    }
+
+    /// The symbol's width in modules, excluding the quiet zone: 21 for
+    /// version 1 up to 177 for version 40.
+    ///
+    /// Returns `None` if the data is too long for version 40.
+    pub fn width_modules(&self) -> Option<u16> {
+        self.version().map(|version| 17 + 4 * u16::from(version))
+    }
+
+    /// Borrow the QR code as a [`PrintQrCodeRef`].
+    pub fn as_borrowed(&self) -> PrintQrCodeRef<'_> {
+        PrintQrCodeRef {
+            model: self.model,
+            module_size: self.module_size,
+            error_correction: self.error_correction,
+            data: &self.data,
+        }
+    }
+}
+
+/// Byte mode capacity of each Model 2 version, at levels L, M, Q and H.
+const QR_BYTE_CAPACITY: [[u16; 4]; 40] = [
+    [17, 14, 11, 7],
+    [32, 26, 20, 14],
+    [53, 42, 32, 24],
+    [78, 62, 46, 34],
+    [106, 84, 60, 44],
+    [134, 106, 74, 58],
+    [154, 122, 86, 64],
+    [192, 152, 108, 84],
+    [230, 180, 130, 98],
+    [271, 213, 151, 119],
+    [321, 251, 177, 137],
+    [367, 287, 203, 155],
+    [425, 331, 241, 177],
+    [458, 362, 258, 194],
+    [520, 412, 292, 220],
+    [586, 450, 322, 250],
+    [644, 504, 364, 280],
+    [718, 560, 394, 310],
+    [792, 624, 442, 338],
+    [858, 666, 482, 382],
+    [929, 711, 509, 403],
+    [1003, 779, 565, 439],
+    [1091, 857, 611, 461],
+    [1171, 911, 661, 511],
+    [1273, 997, 715, 535],
+    [1367, 1059, 751, 593],
+    [1465, 1125, 805, 625],
+    [1528, 1190, 868, 658],
+    [1628, 1264, 908, 698],
+    [1732, 1370, 982, 742],
+    [1840, 1452, 1030, 790],
+    [1952, 1538, 1112, 842],
+    [2068, 1628, 1168, 898],
+    [2188, 1722, 1228, 958],
+    [2303, 1809, 1283, 983],
+    [2431, 1911, 1351, 1051],
+    [2563, 1989, 1423, 1093],
+    [2699, 2099, 1499, 1139],
+    [2809, 2213, 1579, 1219],
+    [2953, 2331, 1663, 1273],
+];
+
+/// Maximum data length of a version 40 Model 2 symbol.
+struct QrCapacity {
+    bytes: usize,
+    kanji: usize,
+}
+
+const fn qr_capacity(level: QrErrorCorrection) -> QrCapacity {
+    match level {
+        QrErrorCorrection::L => QrCapacity {
+            bytes: 2953,
+            kanji: 1817,
+        },
+        QrErrorCorrection::M => QrCapacity {
+            bytes: 2331,
+            kanji: 1435,
+        },
+        QrErrorCorrection::Q => QrCapacity {
+            bytes: 1663,
+            kanji: 1024,
+        },
+        QrErrorCorrection::H => QrCapacity {
+            bytes: 1273,
+            kanji: 784,
+        },
+    }
+}
+
+/// Count Shift-JIS Kanji mode characters, rejecting anything else.
+fn count_kanji(data: &[u8]) -> Result<usize, QrCodeError> {
+    if !data.len().is_multiple_of(2) {
+        return Err(QrCodeError::InvalidKanji(data.len() - 1));
+    }
+    for (i, pair) in data.chunks_exact(2).enumerate() {
+        let code = u16::from_be_bytes([pair[0], pair[1]]);
+        if !matches!(code, 0x8140..=0x9FFC | 0xE040..=0xEBBF) {
+            return Err(QrCodeError::InvalidKanji(i * 2));
+        }
+    }
+    Ok(data.len() / 2)
+}
+
+#[cfg(feature = "qrcode")]
+impl PrintQrCode {
+    /// Render the symbol host-side as a raster image.
+    ///
+    /// For models without `GS ( k` QR support. Each module is
+    /// [`module_size`](Self::module_size) dots square, with no quiet zone,
+    /// and the smallest Model 2 version that fits the data is used; the
+    /// [`model`](Self::model) setting is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::ExceedsCapacity`] if the data doesn't fit in
+    /// a version 40 symbol at the error correction level.
+    pub fn to_raster(&self) -> Result<PrintRasterImage, QrCodeError> {
+        use qrcode::{Color, EcLevel, QrCode};
+
+        let level = match self.error_correction {
+            QrErrorCorrection::L => EcLevel::L,
+            QrErrorCorrection::M => EcLevel::M,
+            QrErrorCorrection::Q => EcLevel::Q,
+            QrErrorCorrection::H => EcLevel::H,
+        };
+        let code = QrCode::with_error_correction_level(&self.data, level).map_err(|_| {
+            QrCodeError::ExceedsCapacity {
+                mode: "byte",
+                length: self.data.len(),
+                max: qr_capacity(self.error_correction).bytes,
+            }
+        })?;
+        let modules = code.width();
+        let colors = code.to_colors();
+        let scale = self.module_size as usize;
+        let width = modules * scale;
+        let width_bytes = width.div_ceil(8);
+        let mut data = vec![0u8; width_bytes * width];
+        for y in 0..width {
+            for x in 0..width {
+                if colors[(y / scale) * modules + x / scale] == Color::Dark {
+                    data[y * width_bytes + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        Ok(PrintRasterImage::new(width_bytes as u16, width as u16, data))
+    }
+}
+
+/// The raster image to print in place of `qr` on `profile`, if any.
+///
+/// `None` means the printer handles the symbol natively.
+#[cfg(feature = "qrcode")]
+pub(crate) fn qr_fallback(
+    qr: &PrintQrCode,
+    profile: &PrinterProfile,
+) -> Result<Option<PrintRasterImage>, PrinterError> {
+    if profile.qr_code {
+        return Ok(None);
+    }
+    Ok(Some(qr.to_raster()?))
+}
+
+/// The raster image to print in place of `qr` on `profile`, if any.
+///
+/// Without the `qrcode` feature there is no fallback, so profiles without
+/// native QR support are rejected.
+#[cfg(not(feature = "qrcode"))]
+pub(crate) fn qr_fallback(
+    _qr: &PrintQrCode,
+    profile: &PrinterProfile,
+) -> Result<Option<PrintRasterImage>, PrinterError> {
+    if profile.qr_code {
+        return Ok(None);
+    }
+    Err(crate::error::ValidationError::Unsupported {
+        feature: "QR codes",
+        profile: profile.name.to_string(),
+    }
+    .into())
+}
+
+impl Command for PrintQrCode {
+    fn encode(&self) -> Vec<u8> {
+        self.as_borrowed().encode()
+    }
+
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        self.as_borrowed().encode_into(output);
+    }
+}
+
+/// Print a QR Code whose data is borrowed.
+///
+/// Encodes like [`PrintQrCode`], but borrows the data instead of copying
+/// it into the command, for printing large payloads straight from a
+/// caller's buffer.
+///
+/// ```
+/// use bixolon::command::Command;
+/// use bixolon::command::symbol::{PrintQrCode, PrintQrCodeRef};
+///
+/// let url = "https://example.com/receipt/1234";
+/// let qr = PrintQrCodeRef::new(url.as_bytes()).unwrap();
+/// assert_eq!(qr.encode(), PrintQrCode::new(url).unwrap().encode());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintQrCodeRef<'a> {
+    /// QR Code model.
+    pub model: QrModel,
+    /// Module size.
+    pub module_size: QrModuleSize,
+    /// Error correction level.
+    pub error_correction: QrErrorCorrection,
+    /// Data to encode.
+    pub data: &'a [u8],
+}
+
+impl<'a> PrintQrCodeRef<'a> {
+    /// Create a QR code borrowing `data`, with default settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError`] if the data is empty or longer than 7089 bytes.
+    pub fn new(data: &'a [u8]) -> Result<Self, QrCodeError> {
+        validate_qr_data(data)?;
         Ok(Self {
             model: QrModel::default(),
             module_size: QrModuleSize::default(),
@@ -107,40 +442,227 @@ impl PrintQrCode {
         self.error_correction = level;
         self
     }
+
+    /// Copy the data into an owned [`PrintQrCode`].
+    pub fn into_owned(self) -> PrintQrCode {
+        PrintQrCode {
+            model: self.model,
+            module_size: self.module_size,
+            error_correction: self.error_correction,
+            data: self.data.to_vec(),
+        }
+    }
 }
 
-impl Command for PrintQrCode {
+impl Command for PrintQrCodeRef<'_> {
     fn encode(&self) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(64 + self.data.len());
+        self.encode_into(&mut bytes);
+        bytes
+    }
 
-        // Function 165: Select model
-        bytes.extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, self.model as u8, 0]);
+    fn encode_into(&self, output: &mut Vec<u8>) {
+        SetQrModel(self.model).encode_into(output);
+        SetQrModuleSize(self.module_size).encode_into(output);
+        SetQrErrorCorrection(self.error_correction).encode_into(output);
+        encode_qr_store_into(self.data, output);
+        PrintStoredQr.encode_into(output);
+    }
+}
 
-        // Function 167: Set module size
-        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, self.module_size as u8]);
+/// Select the QR Code model.
+///
+/// ESC/POS: `GS ( k 4 0 49 65 n1 n2` (function 165)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetQrModel(pub QrModel);
 
-        // Function 169: Set error correction
-        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, self.error_correction as u8]);
+impl Command for SetQrModel {
+    fn encode(&self) -> Vec<u8> {
+        vec![GS, b'(', b'k', 4, 0, 49, 65, self.0 as u8, 0]
+    }
+}
 
-        // Function 180: Store data
-        let data_len = self.data.len() + 3;
-        let pl = (data_len & 0xFF) as u8;
-        let ph = ((data_len >> 8) & 0xFF) as u8;
-        bytes.extend_from_slice(&[GS, b'(', b'k', pl, ph, 49, 80, 48]);
-        bytes.extend_from_slice(&self.data);
+/// Set the QR Code module size.
+///
+/// ESC/POS: `GS ( k 3 0 49 67 n` (function 167)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetQrModuleSize(pub QrModuleSize);
 
-        // Function 181: Print symbol
-        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]);
+impl Command for SetQrModuleSize {
+    fn encode(&self) -> Vec<u8> {
+        vec![GS, b'(', b'k', 3, 0, 49, 67, self.0 as u8]
+    }
+}
 
-        bytes
+/// Set the QR Code error correction level.
+///
+/// ESC/POS: `GS ( k 3 0 49 69 n` (function 169)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetQrErrorCorrection(pub QrErrorCorrection);
+
+impl Command for SetQrErrorCorrection {
+    fn encode(&self) -> Vec<u8> {
+        vec![GS, b'(', b'k', 3, 0, 49, 69, self.0 as u8]
+    }
+}
+
+/// Store QR Code data in the symbol storage area.
+///
+/// The stored symbol can be printed any number of times with
+/// [`PrintStoredQr`] until new data is stored.
+///
+/// ESC/POS: `GS ( k pL pH 49 80 48 d1...dk` (function 180)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "StoreQrDataFields"))]
+pub struct StoreQrData {
+    data: Vec<u8>,
+}
+
+impl StoreQrData {
+    /// Create a store command with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError`] if the data is empty or longer than 7089 bytes.
+    pub fn new(data: impl Into<Vec<u8>>) -> Result<Self, QrCodeError> {
+        let data = data.into();
+        validate_qr_data(&data)?;
+        Ok(Self {
+            data,
+        })
+    }
+
+    /// The data to be stored.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Deserialized [`StoreQrData`] fields, validated before use.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct StoreQrDataFields {
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<StoreQrDataFields> for StoreQrData {
+    type Error = QrCodeError;
+
+    fn try_from(fields: StoreQrDataFields) -> Result<Self, Self::Error> {
+        Self::new(fields.data)
+    }
+}
+
+impl Command for StoreQrData {
+    fn encode(&self) -> Vec<u8> {
+        encode_qr_store(&self.data)
+    }
+}
+
+/// Print the QR Code symbol in the symbol storage area.
+///
+/// ESC/POS: `GS ( k 3 0 49 81 48` (function 181)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintStoredQr;
+
+impl_static_command!(PrintStoredQr => GS, b'(', b'k', 3, 0, 49, 81, 48);
+
+/// Size information for the symbol in the symbol storage area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SymbolStorageInfo {
+    /// Horizontal size of the symbol in dots.
+    pub width_dots: u16,
+    /// Vertical size of the symbol in dots.
+    pub height_dots: u16,
+    /// Whether the stored data can be printed as a symbol.
+    pub printable: bool,
+}
+
+/// Query the size of the QR Code symbol in the symbol storage area.
+///
+/// ESC/POS: `GS ( k 3 0 49 82 48` (function 182)
+///
+/// The printer responds with `0x37 0x76`, the width and height as ASCII
+/// decimal fields separated by `0x1F`, a printable flag, and a NUL terminator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuerySymbolStorage;
+
+impl_static_command!(QuerySymbolStorage => GS, b'(', b'k', 3, 0, 49, 82, 48);
+
+impl QueryCommand for QuerySymbolStorage {
+    type Response = SymbolStorageInfo;
+
+    fn parse_response(&self, bytes: &[u8]) -> Result<Self::Response, StatusParseError> {
+        let body = match bytes {
+            [] => return Err(StatusParseError::EmptyResponse),
+            [0x37, 0x76, rest @ ..] => rest,
+            [first, ..] => return Err(StatusParseError::InvalidStatus(*first)),
+        };
+        let body = body.strip_suffix(&[0x00]).ok_or(StatusParseError::Incomplete)?;
+
+        let mut fields = body.split(|&b| b == 0x1F);
+        let mut next_number = || -> Result<u16, StatusParseError> {
+            let field = fields.next().ok_or(StatusParseError::Truncated)?;
+            core::str::from_utf8(field)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or(StatusParseError::Truncated)
+        };
+        let width_dots = next_number()?;
+        let height_dots = next_number()?;
+        let printable = match fields.next() {
+            Some([0x30]) => true,
+            Some([0x31]) => false,
+            Some([other, ..]) => return Err(StatusParseError::InvalidStatus(*other)),
+            _ => return Err(StatusParseError::Truncated),
+        };
+
+        Ok(SymbolStorageInfo {
+            width_dots,
+            height_dots,
+            printable,
+        })
     }
 }
 
+fn validate_qr_data(data: &[u8]) -> Result<(), QrCodeError> {
+    if data.is_empty() {
+        return Err(QrCodeError::EmptyData);
+    }
+    if data.len() > 7089 {
+        return Err(QrCodeError::DataTooLong(data.len()));
+    }
+    Ok(())
+}
+
+fn encode_qr_store(data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8 + data.len());
+    encode_qr_store_into(data, &mut bytes);
+    bytes
+}
+
+fn encode_qr_store_into(data: &[u8], output: &mut Vec<u8>) {
+    let data_len = data.len() + 3;
+    let pl = (data_len & 0xFF) as u8;
+    let ph = ((data_len >> 8) & 0xFF) as u8;
+    output.extend_from_slice(&[GS, b'(', b'k', pl, ph, 49, 80, 48]);
+    output.extend_from_slice(data);
+}
+
 /// PDF417 module size (2-8 dots per module).
 ///
 /// Used for both width and height of PDF417 modules.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pdf417ModuleSize {
     /// 2 dots per module (smallest).
     Size2 = 2,
@@ -161,6 +683,7 @@ pub enum Pdf417ModuleSize {
 
 /// PDF417 column count configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pdf417Columns {
     /// Automatically determine column count (default).
     #[default]
@@ -193,6 +716,7 @@ impl Pdf417Columns {
 
 /// PDF417 row count configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pdf417Rows {
     /// Automatically determine row count (default).
     #[default]
@@ -226,6 +750,7 @@ impl Pdf417Rows {
 /// PDF417 error correction level (0-8).
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pdf417ErrorCorrection {
     /// Level 0 - minimal error correction.
     Level0 = 48,
@@ -250,6 +775,7 @@ pub enum Pdf417ErrorCorrection {
 
 /// Print a PDF417 barcode.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrintPdf417 {
     /// Number of columns.
     pub columns: Pdf417Columns,
@@ -342,10 +868,387 @@ impl Command for PrintPdf417 {
     }
 }
 
+/// DataMatrix symbol shape.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataMatrixShape {
+    /// Square symbol (10×10 to 144×144 modules).
+    #[default]
+    Square = 0,
+    /// Rectangular symbol (8×18 to 16×48 modules).
+    Rectangle = 1,
+}
+
+/// Print a DataMatrix symbol.
+///
+/// Like [`PrintQrCode`], this is a compound command that sends:
+/// 1. Set symbol type (shape, columns, rows)
+/// 2. Set module size
+/// 3. Store data
+/// 4. Print symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintDataMatrix {
+    /// Symbol shape.
+    pub shape: DataMatrixShape,
+    /// Number of columns (0 = automatic).
+    pub columns: u8,
+    /// Number of rows (0 = automatic).
+    pub rows: u8,
+    /// Module size in dots (2-16).
+    pub module_size: u8,
+    /// Data to encode.
+    pub data: Vec<u8>,
+}
+
+impl PrintDataMatrix {
+    /// Create a square DataMatrix symbol with automatic sizing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataMatrixError`] if the data is empty or longer than 3116 bytes.
+    pub fn new(data: impl Into<Vec<u8>>) -> Result<Self, DataMatrixError> {
+        let data = data.into();
+        if data.is_empty() {
+            return Err(DataMatrixError::EmptyData);
+        }
+        if data.len() > 3116 {
+            return Err(DataMatrixError::DataTooLong(data.len()));
+        }
+        Ok(Self {
+            shape: DataMatrixShape::default(),
+            columns: 0,
+            rows: 0,
+            module_size: 3,
+            data,
+        })
+    }
+
+    /// Set the symbol shape.
+    pub fn with_shape(mut self, shape: DataMatrixShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Set a fixed symbol size in modules (0 for automatic).
+    pub fn with_size(mut self, columns: u8, rows: u8) -> Self {
+        self.columns = columns;
+        self.rows = rows;
+        self
+    }
+
+    /// Set the module size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataMatrixError::InvalidModuleSize`] if `dots` is not in range 2-16.
+    pub fn with_module_size(mut self, dots: u8) -> Result<Self, DataMatrixError> {
+        if !(2..=16).contains(&dots) {
+            return Err(DataMatrixError::InvalidModuleSize(dots));
+        }
+        self.module_size = dots;
+        Ok(self)
+    }
+}
+
+impl Command for PrintDataMatrix {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.data.len());
+
+        // Function 265: Set symbol type, columns, rows
+        bytes.extend_from_slice(&[
+            GS,
+            b'(',
+            b'k',
+            5,
+            0,
+            54,
+            66,
+            self.shape as u8,
+            self.columns,
+            self.rows,
+        ]);
+
+        // Function 267: Set module size
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 54, 67, self.module_size]);
+
+        // Function 280: Store data
+        let data_len = self.data.len() + 3;
+        let pl = (data_len & 0xFF) as u8;
+        let ph = ((data_len >> 8) & 0xFF) as u8;
+        bytes.extend_from_slice(&[GS, b'(', b'k', pl, ph, 54, 80, 48]);
+        bytes.extend_from_slice(&self.data);
+
+        // Function 281: Print symbol
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 54, 81, 48]);
+
+        bytes
+    }
+}
+
+/// GS1 DataBar symbol type.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gs1DataBarType {
+    /// GS1 DataBar Omnidirectional.
+    Omnidirectional = 72,
+    /// GS1 DataBar Truncated.
+    Truncated = 73,
+    /// GS1 DataBar Stacked.
+    Stacked = 74,
+    /// GS1 DataBar Stacked Omnidirectional.
+    StackedOmnidirectional = 75,
+    /// GS1 DataBar Limited.
+    Limited = 76,
+    /// GS1 DataBar Expanded.
+    Expanded = 77,
+    /// GS1 DataBar Expanded Stacked.
+    ExpandedStacked = 78,
+}
+
+impl Gs1DataBarType {
+    /// Whether this type encodes arbitrary AI element strings.
+    pub const fn is_expanded(self) -> bool {
+        matches!(self, Gs1DataBarType::Expanded | Gs1DataBarType::ExpandedStacked)
+    }
+}
+
+/// Print a GS1 DataBar symbol.
+///
+/// Supported by newer firmware only; the SRP-350plus does not document
+/// symbol type 51.
+///
+/// Non-expanded types encode a GTIN: pass 13 digits (the printer adds the
+/// check digit) or 14 digits with a valid check digit. Expanded types take
+/// a human-readable element string such as `(01)09501101530003(17)250101`,
+/// which is validated against the known Application Identifier formats.
+///
+/// This is a compound command that sends:
+/// 1. Set module width
+/// 2. Set maximum width (Expanded Stacked only)
+/// 3. Store data
+/// 4. Print symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PrintGs1DataBarFields"))]
+pub struct PrintGs1DataBar {
+    /// Symbol type.
+    pub symbol_type: Gs1DataBarType,
+    /// Module width in dots (2-8).
+    pub module_width: u8,
+    /// Maximum symbol width in dots for Expanded Stacked (0 = automatic).
+    pub max_width: u16,
+    /// Data to encode, already validated.
+    data: Vec<u8>,
+}
+
+impl PrintGs1DataBar {
+    /// Create a GS1 DataBar symbol with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Gs1Error`] if the data is not valid for the symbol type.
+    pub fn new(symbol_type: Gs1DataBarType, data: &str) -> Result<Self, Gs1Error> {
+        let data = if symbol_type.is_expanded() {
+            gs1::parse_element_string(data)?;
+            data.as_bytes().to_vec()
+        } else {
+            Self::validate_gtin(symbol_type, data)?
+        };
+
+        Ok(Self {
+            symbol_type,
+            module_width: 2,
+            max_width: 0,
+            data,
+        })
+    }
+
+    /// Create an expanded symbol from pre-validated elements.
+    pub fn expanded(elements: &[Gs1Element], stacked: bool) -> Self {
+        let mut data = Vec::new();
+        for element in elements {
+            data.push(b'(');
+            data.extend_from_slice(element.ai().as_bytes());
+            data.push(b')');
+            data.extend_from_slice(element.value().as_bytes());
+        }
+        Self {
+            symbol_type: if stacked {
+                Gs1DataBarType::ExpandedStacked
+            } else {
+                Gs1DataBarType::Expanded
+            },
+            module_width: 2,
+            max_width: 0,
+            data,
+        }
+    }
+
+    /// Set the module width.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`](crate::error::ValidationError::OutOfRange)
+    /// if `dots` is not in range 2-8.
+    pub fn with_module_width(mut self, dots: u8) -> Result<Self, crate::error::ValidationError> {
+        if !(2..=8).contains(&dots) {
+            return Err(crate::error::ValidationError::OutOfRange {
+                name: "GS1 DataBar module width",
+                value: u16::from(dots),
+                min: 2,
+                max: 8,
+            });
+        }
+        self.module_width = dots;
+        Ok(self)
+    }
+
+    /// Set the maximum symbol width for Expanded Stacked (0 = automatic).
+    pub fn with_max_width(mut self, dots: u16) -> Self {
+        self.max_width = dots;
+        self
+    }
+
+    /// The validated data sent to the printer.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn validate_gtin(symbol_type: Gs1DataBarType, data: &str) -> Result<Vec<u8>, Gs1Error> {
+        let digits = data.as_bytes();
+        if !(13..=14).contains(&digits.len()) {
+            return Err(Gs1Error::InvalidLength {
+                ai: "01".to_string(),
+                actual: digits.len(),
+                min: 13,
+                max: 14,
+            });
+        }
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return Err(Gs1Error::InvalidValue {
+                ai: "01".to_string(),
+                value: data.to_string(),
+                reason: "must contain digits only",
+            });
+        }
+        if symbol_type == Gs1DataBarType::Limited && digits[0] > b'1' {
+            return Err(Gs1Error::InvalidValue {
+                ai: "01".to_string(),
+                value: data.to_string(),
+                reason: "must start with 0 or 1 for GS1 DataBar Limited",
+            });
+        }
+        if digits.len() == 14 {
+            gs1::verify_check_digit(digits)?;
+        }
+        Ok(digits[..13].to_vec())
+    }
+}
+
+/// Deserialized [`PrintGs1DataBar`] fields, validated before use.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PrintGs1DataBarFields {
+    symbol_type: Gs1DataBarType,
+    module_width: u8,
+    max_width: u16,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PrintGs1DataBarFields> for PrintGs1DataBar {
+    type Error = Gs1Error;
+
+    fn try_from(fields: PrintGs1DataBarFields) -> Result<Self, Self::Error> {
+        // Non-UTF-8 data becomes replacement characters, which no GS1
+        // format accepts
+        let data = alloc::string::String::from_utf8_lossy(&fields.data);
+        Ok(Self {
+            module_width: fields.module_width,
+            max_width: fields.max_width,
+            ..Self::new(fields.symbol_type, &data)?
+        })
+    }
+}
+
+impl Command for PrintGs1DataBar {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(40 + self.data.len());
+
+        // Function 367: Set module width
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 51, 67, self.module_width]);
+
+        // Function 371: Set maximum width for Expanded Stacked
+        if self.symbol_type == Gs1DataBarType::ExpandedStacked {
+            let [nl, nh] = self.max_width.to_le_bytes();
+            bytes.extend_from_slice(&[GS, b'(', b'k', 4, 0, 51, 71, nl, nh]);
+        }
+
+        // Function 380: Store data
+        let data_len = self.data.len() + 4;
+        let pl = (data_len & 0xFF) as u8;
+        let ph = ((data_len >> 8) & 0xFF) as u8;
+        bytes.extend_from_slice(&[GS, b'(', b'k', pl, ph, 51, 80, 48, self.symbol_type as u8]);
+        bytes.extend_from_slice(&self.data);
+
+        // Function 381: Print symbol
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 51, 81, 48]);
+
+        bytes
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn qr_renders_to_raster() {
+        let qr = PrintQrCode::new(b"HELLO".to_vec()).unwrap();
+        let raster = qr.to_raster().unwrap();
+        // Version 1 is 21 modules; 3 dots each
+        assert_eq!((raster.width_bytes, raster.height_dots), (8, 63));
+        // The top-left finder pattern's outer ring is 7 modules wide
+        assert_eq!(raster.data[..3], [0xFF, 0xFF, 0xF8]);
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn version_matches_rendered_width() {
+        use QrErrorCorrection::*;
+        for (index, level) in [L, M, Q, H].into_iter().enumerate() {
+            let boundaries = QR_BYTE_CAPACITY[..10].iter().map(|capacities| capacities[index]);
+            for length in boundaries.flat_map(|capacity| [capacity, capacity + 1]) {
+                let qr = PrintQrCode::new(vec![b'x'; length.into()])
+                    .unwrap()
+                    .with_module_size(QrModuleSize::Size1)
+                    .with_error_correction(level);
+                let raster = qr.to_raster().unwrap();
+                assert_eq!(qr.width_modules(), Some(raster.height_dots), "{level:?} {length}");
+            }
+        }
+    }
+
+    #[test]
+    fn qr_fallback_follows_profile() {
+        let qr = PrintQrCode::new(b"HELLO".to_vec()).unwrap();
+        assert!(matches!(qr_fallback(&qr, &PrinterProfile::default()), Ok(None)));
+
+        let profile = PrinterProfile::default().with_qr_code(false);
+        let fallback = qr_fallback(&qr, &profile);
+        #[cfg(feature = "qrcode")]
+        assert_eq!(fallback.unwrap(), Some(qr.to_raster().unwrap()));
+        #[cfg(not(feature = "qrcode"))]
+        assert!(matches!(
+            fallback,
+            Err(PrinterError::Validation(crate::error::ValidationError::Unsupported { .. }))
+        ));
+    }
+
     #[test]
     fn qr_model_values() {
         assert_eq!(QrModel::Model1 as u8, 49);
@@ -390,6 +1293,76 @@ mod tests {
         assert!(encoded.windows(5).any(|w| w == *b"Hello"));
     }
 
+    #[test]
+    fn qr_code_matches_individual_commands() {
+        let qr = PrintQrCode::new(b"Hello".to_vec())
+            .unwrap()
+            .with_module_size(QrModuleSize::Size5)
+            .with_error_correction(QrErrorCorrection::Q);
+
+        let mut expected = SetQrModel(QrModel::Model2).encode();
+        expected.extend(SetQrModuleSize(QrModuleSize::Size5).encode());
+        expected.extend(SetQrErrorCorrection(QrErrorCorrection::Q).encode());
+        expected.extend(StoreQrData::new(b"Hello".to_vec()).unwrap().encode());
+        expected.extend(PrintStoredQr.encode());
+
+        assert_eq!(qr.encode(), expected);
+    }
+
+    #[test]
+    fn borrowed_qr_code_matches_owned() {
+        let data = b"Hello";
+        let owned =
+            PrintQrCode::new(data.to_vec()).unwrap().with_error_correction(QrErrorCorrection::H);
+        let borrowed =
+            PrintQrCodeRef::new(data).unwrap().with_error_correction(QrErrorCorrection::H);
+        assert_eq!(borrowed.encode(), owned.encode());
+        assert_eq!(owned.as_borrowed(), borrowed);
+        assert_eq!(borrowed.into_owned(), owned);
+        assert!(matches!(PrintQrCodeRef::new(&[]), Err(QrCodeError::EmptyData)));
+    }
+
+    #[test]
+    fn store_qr_data_rejects_empty() {
+        assert!(matches!(StoreQrData::new(vec![]), Err(QrCodeError::EmptyData)));
+    }
+
+    #[test]
+    fn store_qr_data_encodes_length() {
+        let cmd = StoreQrData::new(b"AB".to_vec()).unwrap();
+        assert_eq!(cmd.encode(), vec![GS, b'(', b'k', 5, 0, 49, 80, 48, b'A', b'B']);
+    }
+
+    #[test]
+    fn query_symbol_storage_encodes() {
+        assert_eq!(QuerySymbolStorage.encode(), vec![GS, b'(', b'k', 3, 0, 49, 82, 48]);
+    }
+
+    #[test]
+    fn query_symbol_storage_parses_response() {
+        let response = b"\x37\x76120\x1F120\x1F0\x00";
+        let info = QuerySymbolStorage.parse_response(response).unwrap();
+        assert_eq!(info.width_dots, 120);
+        assert_eq!(info.height_dots, 120);
+        assert!(info.printable);
+    }
+
+    #[test]
+    fn query_symbol_storage_rejects_bad_header() {
+        let result = QuerySymbolStorage.parse_response(&[0x10, 0x00]);
+        assert!(matches!(result, Err(StatusParseError::InvalidStatus(0x10))));
+        let result = QuerySymbolStorage.parse_response(b"\x37\x36120\x1F120\x1F0\x00");
+        assert!(matches!(result, Err(StatusParseError::InvalidStatus(0x37))));
+    }
+
+    #[test]
+    fn query_symbol_storage_rejects_truncated() {
+        let result = QuerySymbolStorage.parse_response(b"\x37\x76120");
+        assert!(matches!(result, Err(StatusParseError::Incomplete)));
+        let result = QuerySymbolStorage.parse_response(b"\x37\x76120\x1F0\x00");
+        assert!(matches!(result, Err(StatusParseError::Truncated)));
+    }
+
     #[test]
     fn pdf417_module_size_values() {
         assert_eq!(Pdf417ModuleSize::Size2 as u8, 2);
@@ -444,4 +1417,168 @@ mod tests {
         // Should contain the data
         assert!(encoded.windows(5).any(|w| w == *b"Hello"));
     }
+
+    #[test]
+    fn data_matrix_creates_with_defaults() {
+        let dm = PrintDataMatrix::new(b"test".to_vec()).unwrap();
+        assert_eq!(dm.shape, DataMatrixShape::Square);
+        assert_eq!(dm.columns, 0);
+        assert_eq!(dm.rows, 0);
+        assert_eq!(dm.module_size, 3);
+    }
+
+    #[test]
+    fn data_matrix_rejects_empty() {
+        assert!(matches!(PrintDataMatrix::new(vec![]), Err(DataMatrixError::EmptyData)));
+    }
+
+    #[test]
+    fn data_matrix_rejects_too_long() {
+        let result = PrintDataMatrix::new(vec![b'A'; 3117]);
+        assert!(matches!(result, Err(DataMatrixError::DataTooLong(3117))));
+    }
+
+    #[test]
+    fn data_matrix_module_size_range() {
+        let dm = PrintDataMatrix::new(b"x".to_vec()).unwrap();
+        assert!(dm.clone().with_module_size(16).is_ok());
+        assert!(matches!(dm.with_module_size(1), Err(DataMatrixError::InvalidModuleSize(1))));
+    }
+
+    #[test]
+    fn data_matrix_encodes_commands() {
+        let dm = PrintDataMatrix::new(b"AB".to_vec())
+            .unwrap()
+            .with_shape(DataMatrixShape::Rectangle)
+            .with_size(18, 8);
+        let expected = vec![
+            GS, b'(', b'k', 5, 0, 54, 66, 1, 18, 8, // symbol type
+            GS, b'(', b'k', 3, 0, 54, 67, 3, // module size
+            GS, b'(', b'k', 5, 0, 54, 80, 48, b'A', b'B', // store
+            GS, b'(', b'k', 3, 0, 54, 81, 48, // print
+        ];
+        assert_eq!(dm.encode(), expected);
+    }
+
+    #[test]
+    fn gs1_databar_accepts_13_digits() {
+        let db = PrintGs1DataBar::new(Gs1DataBarType::Omnidirectional, "0950110153000").unwrap();
+        assert_eq!(db.data(), b"0950110153000");
+    }
+
+    #[test]
+    fn gs1_databar_strips_valid_check_digit() {
+        let db = PrintGs1DataBar::new(Gs1DataBarType::Truncated, "09501101530003").unwrap();
+        assert_eq!(db.data(), b"0950110153000");
+    }
+
+    #[test]
+    fn gs1_databar_rejects_bad_check_digit() {
+        let result = PrintGs1DataBar::new(Gs1DataBarType::Stacked, "09501101530004");
+        assert!(matches!(result, Err(Gs1Error::CheckDigitMismatch { .. })));
+    }
+
+    #[test]
+    fn gs1_databar_limited_requires_leading_0_or_1() {
+        assert!(PrintGs1DataBar::new(Gs1DataBarType::Limited, "1950110153000").is_ok());
+        assert!(matches!(
+            PrintGs1DataBar::new(Gs1DataBarType::Limited, "2950110153000"),
+            Err(Gs1Error::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn gs1_databar_expanded_validates_ais() {
+        assert!(PrintGs1DataBar::new(Gs1DataBarType::Expanded, "(01)09501101530003(10)A1").is_ok());
+        assert!(matches!(
+            PrintGs1DataBar::new(Gs1DataBarType::Expanded, "(17)251340"),
+            Err(Gs1Error::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn gs1_databar_encodes_commands() {
+        let db = PrintGs1DataBar::new(Gs1DataBarType::Omnidirectional, "0950110153000").unwrap();
+        let encoded = db.encode();
+
+        assert_eq!(&encoded[0..8], &[GS, b'(', b'k', 3, 0, 51, 67, 2]);
+        assert_eq!(&encoded[8..17], &[GS, b'(', b'k', 17, 0, 51, 80, 48, 72]);
+        assert!(encoded.ends_with(&[GS, b'(', b'k', 3, 0, 51, 81, 48]));
+    }
+
+    #[test]
+    fn gs1_databar_expanded_stacked_sets_max_width() {
+        let elements = gs1::parse_element_string("(01)09501101530003").unwrap();
+        let db = PrintGs1DataBar::expanded(&elements, true).with_max_width(300);
+        let encoded = db.encode();
+        assert!(encoded.windows(9).any(|w| w == [GS, b'(', b'k', 4, 0, 51, 71, 0x2C, 0x01]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn gs1_databar_deserializing_revalidates_data() {
+        let db = PrintGs1DataBar::new(Gs1DataBarType::Truncated, "09501101530003")
+            .unwrap()
+            .with_module_width(4)
+            .unwrap();
+        let json = serde_json::to_string(&db).unwrap();
+        assert_eq!(serde_json::from_str::<PrintGs1DataBar>(&json).unwrap(), db);
+
+        let bad = json.replace("48,48,48]", "48,48,65]");
+        assert!(serde_json::from_str::<PrintGs1DataBar>(&bad).is_err());
+        assert!(serde_json::from_str::<StoreQrData>(r#"{"data":[]}"#).is_err());
+    }
+
+    #[test]
+    fn qr_bytes_validates_capacity_per_level() {
+        let data = vec![0xA5; 2000];
+        assert!(PrintQrCode::bytes(data.clone(), QrErrorCorrection::L).is_ok());
+        assert!(matches!(
+            PrintQrCode::bytes(data, QrErrorCorrection::H),
+            Err(QrCodeError::ExceedsCapacity {
+                max: 1273,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn qr_check_byte_capacity_after_level_change() {
+        let qr = PrintQrCode::bytes(vec![0; 1500], QrErrorCorrection::M).unwrap();
+        assert!(qr.check_byte_capacity().is_ok());
+        assert!(qr.with_error_correction(QrErrorCorrection::H).check_byte_capacity().is_err());
+    }
+
+    #[test]
+    fn qr_kanji_accepts_shift_jis() {
+        // "漢字" in Shift-JIS
+        let qr = PrintQrCode::kanji(vec![0x8A, 0xBF, 0x8E, 0x9A], QrErrorCorrection::M).unwrap();
+        assert_eq!(qr.error_correction, QrErrorCorrection::M);
+        assert_eq!(qr.data.len(), 4);
+    }
+
+    #[test]
+    fn qr_kanji_rejects_non_kanji() {
+        assert!(matches!(
+            PrintQrCode::kanji(b"AB".to_vec(), QrErrorCorrection::L),
+            Err(QrCodeError::InvalidKanji(0))
+        ));
+        assert!(matches!(
+            PrintQrCode::kanji(vec![0x8A, 0xBF, 0x8E], QrErrorCorrection::L),
+            Err(QrCodeError::InvalidKanji(2))
+        ));
+    }
+
+    #[test]
+    fn qr_kanji_validates_capacity() {
+        let data: Vec<u8> = std::iter::repeat_n([0x8A, 0xBF], 785).flatten().collect();
+        assert!(matches!(
+            PrintQrCode::kanji(data, QrErrorCorrection::H),
+            Err(QrCodeError::ExceedsCapacity {
+                mode: "Kanji",
+                length: 785,
+                max: 784
+            })
+        ));
+    }
 }
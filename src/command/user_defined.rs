@@ -0,0 +1,199 @@
+//! User-defined character commands.
+//!
+//! The printer can replace characters 0x20-0x7E with downloaded glyphs.
+//! Definitions take effect while the user-defined character set is selected
+//! with [`SelectUserDefinedCharacters`]; unselected, the built-in glyphs
+//! print as usual.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{Command, ESC};
+use crate::error::ValidationError;
+
+/// First character code that can be redefined.
+pub const FIRST_CODE: u8 = 0x20;
+
+/// Last character code that can be redefined.
+pub const LAST_CODE: u8 = 0x7E;
+
+/// Bytes per glyph column, giving 24 dots of height.
+pub const HEIGHT_BYTES: u8 = 3;
+
+/// Widest glyph, in dots (a Font A cell).
+pub const MAX_WIDTH: u8 = 12;
+
+/// Glyph for one user-defined character.
+///
+/// `data` holds `width` columns of [`HEIGHT_BYTES`] bytes each, left to
+/// right, top byte first with the topmost dot in the high bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserDefinedCharacter {
+    /// Width in dots (0-12; Font B glyphs are at most 9).
+    pub width: u8,
+    /// Column data.
+    pub data: Vec<u8>,
+}
+
+/// Define user-defined characters.
+///
+/// ESC/POS: `ESC & y c1 c2 [x d1...d(y*x)]...`
+///
+/// Glyphs are assigned to consecutive codes starting at `first`, for the
+/// currently selected font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefineUserDefinedCharacters {
+    /// Code of the first glyph.
+    pub first: u8,
+    /// Glyphs, in code order.
+    pub characters: Vec<UserDefinedCharacter>,
+}
+
+impl DefineUserDefinedCharacters {
+    /// Create a definition, checking the codes and glyph data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] if there are no glyphs, the
+    /// codes fall outside [`FIRST_CODE`]-[`LAST_CODE`] or a glyph is wider
+    /// than [`MAX_WIDTH`], or [`ValidationError::ImageDataLength`] if a
+    /// glyph's data doesn't match its width.
+    pub fn new(first: u8, characters: Vec<UserDefinedCharacter>) -> Result<Self, ValidationError> {
+        let definition = Self {
+            first,
+            characters,
+        };
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Check the codes and glyph data.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new).
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let out_of_range = |name, value: u16, min: u8, max: u8| {
+            if (u16::from(min)..=u16::from(max)).contains(&value) {
+                Ok(())
+            } else {
+                Err(ValidationError::OutOfRange {
+                    name,
+                    value,
+                    min: u16::from(min),
+                    max: u16::from(max),
+                })
+            }
+        };
+        let count = self.characters.len().min(usize::from(u16::MAX)) as u16;
+        out_of_range("user-defined character count", count, 1, LAST_CODE - FIRST_CODE + 1)?;
+        out_of_range("first user-defined character", u16::from(self.first), FIRST_CODE, LAST_CODE)?;
+        let last = u16::from(self.first) + count - 1;
+        out_of_range("last user-defined character", last, FIRST_CODE, LAST_CODE)?;
+        for character in &self.characters {
+            out_of_range("user-defined character width", u16::from(character.width), 0, MAX_WIDTH)?;
+            let expected = usize::from(character.width) * usize::from(HEIGHT_BYTES);
+            if character.data.len() != expected {
+                return Err(ValidationError::ImageDataLength {
+                    expected,
+                    actual: character.data.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Code of the last glyph.
+    pub fn last(&self) -> u8 {
+        self.first.saturating_add(self.characters.len().saturating_sub(1) as u8)
+    }
+}
+
+impl Command for DefineUserDefinedCharacters {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![ESC, b'&', HEIGHT_BYTES, self.first, self.last()];
+        for character in &self.characters {
+            bytes.push(character.width);
+            bytes.extend_from_slice(&character.data);
+        }
+        bytes
+    }
+}
+
+/// Select or cancel the user-defined character set.
+///
+/// ESC/POS: `ESC % n` (0x1B 0x25 n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectUserDefinedCharacters(pub bool);
+
+impl Command for SelectUserDefinedCharacters {
+    fn encode(&self) -> Vec<u8> {
+        vec![ESC, b'%', u8::from(self.0)]
+    }
+}
+
+/// Delete the user-defined character for a code.
+///
+/// The built-in glyph prints for the code afterwards, even with the
+/// user-defined character set selected.
+///
+/// ESC/POS: `ESC ? n` (0x1B 0x3F n)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CancelUserDefinedCharacter(pub u8);
+
+impl Command for CancelUserDefinedCharacter {
+    fn encode(&self) -> Vec<u8> {
+        vec![ESC, b'?', self.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph(width: u8) -> UserDefinedCharacter {
+        UserDefinedCharacter {
+            width,
+            data: vec![0xFF; usize::from(width) * 3],
+        }
+    }
+
+    #[test]
+    fn define_encodes_code_range() {
+        let define = DefineUserDefinedCharacters::new(b'A', vec![glyph(1), glyph(2)]).unwrap();
+        assert_eq!(
+            define.encode(),
+            [&[ESC, b'&', 3, b'A', b'B', 1][..], &[0xFF; 3], &[2], &[0xFF; 6]].concat()
+        );
+    }
+
+    #[test]
+    fn define_validates_codes_and_data() {
+        assert!(DefineUserDefinedCharacters::new(0x1F, vec![glyph(1)]).is_err());
+        assert!(DefineUserDefinedCharacters::new(LAST_CODE, vec![glyph(1), glyph(1)]).is_err());
+        assert!(DefineUserDefinedCharacters::new(FIRST_CODE, Vec::new()).is_err());
+        assert!(DefineUserDefinedCharacters::new(FIRST_CODE, vec![glyph(13)]).is_err());
+        let short = UserDefinedCharacter {
+            width: 2,
+            data: vec![0; 5],
+        };
+        assert!(matches!(
+            DefineUserDefinedCharacters::new(FIRST_CODE, vec![short]),
+            Err(ValidationError::ImageDataLength {
+                expected: 6,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn select_and_cancel_encode() {
+        assert_eq!(SelectUserDefinedCharacters(true).encode(), [ESC, b'%', 1]);
+        assert_eq!(SelectUserDefinedCharacters(false).encode(), [ESC, b'%', 0]);
+        assert_eq!(CancelUserDefinedCharacter(b' ').encode(), [ESC, b'?', b' ']);
+    }
+}
@@ -0,0 +1,800 @@
+//! ESC/POS stream decoding.
+//!
+//! Parses raw printer bytes back into the command types of the [`command`]
+//! module, for inspecting captured traffic, porting output from other
+//! SDKs, or asserting on what a test sent:
+//!
+//! ```
+//! use bixolon::command::character::SetEmphasized;
+//! use bixolon::command::paper::CutPaper;
+//! use bixolon::decode::{Decoded, decode};
+//!
+//! let items = decode(b"\x1bE\x01Total\n\x1dV\x42\x03");
+//! assert_eq!(items[0], Decoded::SetEmphasized(SetEmphasized(true)));
+//! assert_eq!(items[1], Decoded::Text(b"Total".to_vec()));
+//! assert_eq!(items[3], Decoded::CutPaper(CutPaper::feed_and_partial(3)));
+//! ```
+//!
+//! Every byte ends up in exactly one [`Decoded`] item. Commands this crate
+//! doesn't model, and parameters outside a type's range, come out as
+//! [`Decoded::Unknown`] and re-encode unchanged. Modeled commands re-encode
+//! in the form this crate sends, so re-encoding reproduces streams it
+//! produced, but not every stream: parameters the printer also accepts as
+//! ASCII digits (`'1'` for `1`), or of which it only reads some bits
+//! (`ESC E 0x38` for `ESC E 0`, `GS a 0x4A` for `GS a 0x0A`), decode to the
+//! same value and re-encode in the canonical form.
+//!
+//! [`command`]: crate::command
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::command::barcode::{
+    BarcodeSystem, BarcodeWidth, HriFont, HriPosition, PrintBarcode, SetBarcodeHeight,
+    SetBarcodeWidth, SetHriFont, SetHriPosition,
+};
+use crate::command::basic::{Cancel, CarriageReturn, FormFeed, HorizontalTab, LineFeed};
+use crate::command::character::{
+    CharacterSize, Font, Justification, PrintColor, RotationMode, ScaleFactor, SelectFont,
+    SelectPrintColor, SetCharacterSize, SetDoubleStrike, SetEmphasized, SetJustification,
+    SetReverse, SetRotation, SetSmoothing, SetUnderline, SetUpsideDown, UnderlineThickness,
+};
+use crate::command::codepage::{
+    CodePage, InternationalCharacterSet, SelectCharacterSet, SelectCodePage,
+};
+use crate::command::graphics::{
+    DefineNvGraphics, DeleteAllNvGraphics, DeleteNvGraphics, KeyCode, PrintNvGraphics,
+    QueryNvCapacity, QueryNvKeyCodes, QueryNvRemaining,
+};
+use crate::command::image::{
+    BitImageMode, DefineDownloadedImage, DownloadedImageMode, PrintDownloadedImage,
+    PrintRasterImage, RasterImageMode, SelectBitImageMode,
+};
+use crate::command::kanji::{
+    CancelKanjiMode, KanjiCodeSystem, KanjiPrintMode, KanjiUnderline, SelectKanjiCodeSystem,
+    SelectKanjiMode, SetKanjiPrintMode, SetKanjiSpacing, SetKanjiUnderline,
+};
+use crate::command::macro_cmd::{ExecuteMacro, MacroExecutionMode, ToggleMacroDefinition};
+use crate::command::page_mode::{
+    EnterPageMode, ExitPageMode, PrintArea, PrintDirection, PrintPageModeData, SetPrintArea,
+    SetPrintDirection, SetVerticalPosition,
+};
+use crate::command::paper::{CutMode, CutPaper, FeedLines, FeedPaper};
+use crate::command::printer_control::{
+    DrawerPin, GeneratePulse, Initialize, PeripheralDevice, PrintTestPage, SelectPeripheral,
+    TestPattern,
+};
+use crate::command::spacing::{
+    SetAbsolutePosition, SetDefaultLineSpacing, SetHorizontalTabs, SetLeftMargin, SetLineSpacing,
+    SetMotionUnits, SetPrintingWidth, SetRelativePosition, SetRightSpacing,
+};
+use crate::command::status::{AsbFlags, EnableAsb, StatusType, TransmitStatus};
+use crate::command::symbol::{
+    PrintStoredQr, QrErrorCorrection, QrModel, QrModuleSize, QuerySymbolStorage,
+    SetQrErrorCorrection, SetQrModel, SetQrModuleSize, StoreQrData,
+};
+use crate::command::user_defined::{
+    CancelUserDefinedCharacter, DefineUserDefinedCharacters, HEIGHT_BYTES,
+    SelectUserDefinedCharacters, UserDefinedCharacter,
+};
+use crate::command::{CAN, CR, Command, DLE, EOT, ESC, FF, FS, GS, HT, LF};
+
+macro_rules! decoded {
+    ($($command:ident),* $(,)?) => {
+        /// One item of a decoded ESC/POS stream.
+        ///
+        /// Each command variant holds the command type of the same name.
+        /// Its [`Debug`](fmt::Debug) output is the inner value's, so a
+        /// listing reads `SetEmphasized(true)` rather than repeating the
+        /// name.
+        #[derive(Clone, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Decoded {
+            /// A run of printable bytes, still in the printer's code page.
+            Text(Vec<u8>),
+            /// Bytes the decoder doesn't recognise, or a command cut short
+            /// by the end of the stream.
+            Unknown(Vec<u8>),
+            $(
+                #[allow(missing_docs)]
+                $command($command),
+            )*
+        }
+
+        impl Command for Decoded {
+            fn encode(&self) -> Vec<u8> {
+                match self {
+                    Decoded::Text(bytes) | Decoded::Unknown(bytes) => bytes.clone(),
+                    $(Decoded::$command(command) => command.encode(),)*
+                }
+            }
+        }
+
+        impl fmt::Debug for Decoded {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Decoded::Text(bytes) => write!(f, "Text(\"{}\")", bytes.escape_ascii()),
+                    Decoded::Unknown(bytes) => write!(f, "Unknown(\"{}\")", bytes.escape_ascii()),
+                    $(Decoded::$command(command) => fmt::Debug::fmt(command, f),)*
+                }
+            }
+        }
+    };
+}
+
+decoded!(
+    LineFeed,
+    FormFeed,
+    CarriageReturn,
+    HorizontalTab,
+    Cancel,
+    Initialize,
+    SelectPeripheral,
+    GeneratePulse,
+    PrintTestPage,
+    FeedPaper,
+    FeedLines,
+    CutPaper,
+    SetEmphasized,
+    SetUnderline,
+    SetDoubleStrike,
+    SelectFont,
+    SetCharacterSize,
+    SetJustification,
+    SetUpsideDown,
+    SetRotation,
+    SetReverse,
+    SetSmoothing,
+    SelectPrintColor,
+    SelectCodePage,
+    SelectCharacterSet,
+    SetDefaultLineSpacing,
+    SetLineSpacing,
+    SetRightSpacing,
+    SetHorizontalTabs,
+    SetMotionUnits,
+    SetAbsolutePosition,
+    SetRelativePosition,
+    SetLeftMargin,
+    SetPrintingWidth,
+    EnterPageMode,
+    ExitPageMode,
+    PrintPageModeData,
+    SetPrintDirection,
+    SetPrintArea,
+    SetVerticalPosition,
+    SelectBitImageMode,
+    PrintRasterImage,
+    DefineDownloadedImage,
+    PrintDownloadedImage,
+    SetBarcodeHeight,
+    SetBarcodeWidth,
+    SetHriPosition,
+    SetHriFont,
+    PrintBarcode,
+    SetQrModel,
+    SetQrModuleSize,
+    SetQrErrorCorrection,
+    StoreQrData,
+    PrintStoredQr,
+    QuerySymbolStorage,
+    QueryNvCapacity,
+    QueryNvRemaining,
+    QueryNvKeyCodes,
+    DeleteAllNvGraphics,
+    DeleteNvGraphics,
+    DefineNvGraphics,
+    PrintNvGraphics,
+    SelectKanjiMode,
+    CancelKanjiMode,
+    SetKanjiPrintMode,
+    SetKanjiUnderline,
+    SetKanjiSpacing,
+    SelectKanjiCodeSystem,
+    DefineUserDefinedCharacters,
+    SelectUserDefinedCharacters,
+    CancelUserDefinedCharacter,
+    TransmitStatus,
+    EnableAsb,
+    ToggleMacroDefinition,
+    ExecuteMacro,
+);
+
+/// Iterator over the items of an ESC/POS byte stream.
+///
+/// `ESC $` decodes as [`SetAbsolutePosition`]; in page mode the same bytes
+/// mean [`SetHorizontalPosition`](crate::command::page_mode::SetHorizontalPosition).
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Decode `bytes` from the start.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+        }
+    }
+
+    /// Offset of the next item in the stream.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for Decoder<'_> {
+    type Item = Decoded;
+
+    fn next(&mut self) -> Option<Decoded> {
+        let rest = &self.bytes[self.position..];
+        if rest.is_empty() {
+            return None;
+        }
+        let len = length(rest);
+        let item = if len > rest.len() {
+            Decoded::Unknown(rest.to_vec())
+        } else {
+            let command = &rest[..len];
+            parse(command).unwrap_or_else(|| Decoded::Unknown(command.to_vec()))
+        };
+        self.position += len.min(rest.len());
+        Some(item)
+    }
+}
+
+/// Decode a whole byte stream.
+pub fn decode(bytes: &[u8]) -> Vec<Decoded> {
+    Decoder::new(bytes).collect()
+}
+
+/// List the items of a byte stream, one per line with its offset.
+///
+/// ```
+/// use bixolon::decode::disassemble;
+///
+/// assert_eq!(
+///     disassemble(b"\x1b@Hi\n"),
+///     "0000  Initialize\n0002  Text(\"Hi\")\n0004  LineFeed\n"
+/// );
+/// ```
+pub fn disassemble(bytes: &[u8]) -> String {
+    let mut decoder = Decoder::new(bytes);
+    let mut listing = String::new();
+    loop {
+        let offset = decoder.position();
+        let Some(item) = decoder.next() else {
+            return listing;
+        };
+        listing.push_str(&format!("{offset:04X}  {item:?}\n"));
+    }
+}
+
+fn is_text(byte: u8) -> bool {
+    byte >= 0x20
+}
+
+/// Length of the item starting `rest`, which may exceed `rest` if the
+/// stream ends mid-command.
+fn length(rest: &[u8]) -> usize {
+    let arg = |i: usize| usize::from(rest.get(i).copied().unwrap_or(0));
+    let u16_at = |i: usize| arg(i) + (arg(i + 1) << 8);
+    let terminated = |start: usize| {
+        let data = rest.get(start..).unwrap_or_default();
+        data.iter().position(|&b| b == 0).map_or(rest.len() + 1, |n| start + n + 1)
+    };
+    match rest[0] {
+        ESC => match rest.get(1) {
+            Some(b'@' | b'2' | b'L' | b'S' | b'<' | b'i' | b'm') | Some(&FF) => 2,
+            Some(b'$' | b'\\' | b'c') => 4,
+            Some(b'p') => 5,
+            Some(b'W') => 10,
+            Some(b'D') => terminated(2),
+            Some(b'*') => {
+                let bytes_per_column = if arg(2) >= 32 {
+                    3
+                } else {
+                    1
+                };
+                5 + u16_at(3) * bytes_per_column
+            }
+            Some(b'&') => {
+                let mut len = 5;
+                for _ in arg(3)..=arg(4) {
+                    len += 1 + arg(2) * arg(len);
+                    if len > rest.len() {
+                        break;
+                    }
+                }
+                len
+            }
+            _ => 3,
+        },
+        GS => match rest.get(1) {
+            Some(b':') => 2,
+            Some(b'V') if matches!(arg(2), 65 | 66) => 4,
+            Some(b'L' | b'W' | b'P' | b'$' | b'\\') => 4,
+            Some(b'^') => 5,
+            Some(b'k') if arg(2) <= 6 => terminated(3),
+            Some(b'k') => 4 + arg(3),
+            Some(b'v') => 8 + u16_at(4) * u16_at(6),
+            Some(b'*') => 4 + arg(2) * arg(3) * 8,
+            Some(b'(') => 5 + u16_at(3),
+            Some(b'8') => 7 + u16_at(3) + (u16_at(5) << 16),
+            _ => 3,
+        },
+        FS => match rest.get(1) {
+            Some(b'&' | b'.') => 2,
+            Some(b'S' | b'p') => 4,
+            _ => 3,
+        },
+        DLE => match rest.get(1) {
+            Some(&EOT | 0x05) => 3,
+            Some(0x14) => 5,
+            _ => 2,
+        },
+        byte if is_text(byte) => rest.iter().position(|&b| !is_text(b)).unwrap_or(rest.len()),
+        _ => 1,
+    }
+}
+
+/// Typed value of the complete item `command`, if it has one.
+fn parse(command: &[u8]) -> Option<Decoded> {
+    let u16_at = |i: usize| u16::from_le_bytes([command[i], command[i + 1]]);
+    Some(match *command {
+        [LF] => Decoded::LineFeed(LineFeed),
+        [FF] => Decoded::FormFeed(FormFeed),
+        [CR] => Decoded::CarriageReturn(CarriageReturn),
+        [HT] => Decoded::HorizontalTab(HorizontalTab),
+        [CAN] => Decoded::Cancel(Cancel),
+        [first, ..] if is_text(first) => Decoded::Text(command.to_vec()),
+
+        [ESC, b'@'] => Decoded::Initialize(Initialize),
+        [ESC, b'E', n] => Decoded::SetEmphasized(SetEmphasized(n & 1 == 1)),
+        [ESC, b'-', n] => Decoded::SetUnderline(SetUnderline(find(
+            digit(n),
+            &[UnderlineThickness::Off, UnderlineThickness::OneDot, UnderlineThickness::TwoDot],
+            |v| v as u8,
+        )?)),
+        [ESC, b'G', n] => Decoded::SetDoubleStrike(SetDoubleStrike(n & 1 == 1)),
+        [ESC, b'M', n] => {
+            Decoded::SelectFont(SelectFont(find(digit(n), &[Font::A, Font::B], |v| v as u8)?))
+        }
+        [ESC, b'a', n] => Decoded::SetJustification(SetJustification(find(
+            digit(n),
+            &[Justification::Left, Justification::Center, Justification::Right],
+            |v| v as u8,
+        )?)),
+        [ESC, b'{', n] => Decoded::SetUpsideDown(SetUpsideDown(n & 1 == 1)),
+        [ESC, b'V', n] => Decoded::SetRotation(SetRotation(find(
+            digit(n),
+            &[RotationMode::Off, RotationMode::Clockwise90],
+            |v| v as u8,
+        )?)),
+        [ESC, b'r', n] => Decoded::SelectPrintColor(SelectPrintColor(find(
+            digit(n),
+            &[PrintColor::Black, PrintColor::Red],
+            |v| v as u8,
+        )?)),
+        [ESC, b't', n] => Decoded::SelectCodePage(SelectCodePage(CodePage::from_byte(n)?)),
+        [ESC, b'R', n] => Decoded::SelectCharacterSet(SelectCharacterSet(
+            InternationalCharacterSet::from_byte(n)?,
+        )),
+        [ESC, b'J', n] => Decoded::FeedPaper(FeedPaper(n)),
+        [ESC, b'd', n] => Decoded::FeedLines(FeedLines(n)),
+        [ESC, b'2'] => Decoded::SetDefaultLineSpacing(SetDefaultLineSpacing),
+        [ESC, b'3', n] => Decoded::SetLineSpacing(SetLineSpacing(n)),
+        [ESC, b' ', n] => Decoded::SetRightSpacing(SetRightSpacing(n)),
+        [ESC, b'D', ref positions @ .., 0] => Decoded::SetHorizontalTabs(SetHorizontalTabs {
+            positions: positions.to_vec(),
+        }),
+        [ESC, b'$', ..] => Decoded::SetAbsolutePosition(SetAbsolutePosition(u16_at(2))),
+        [ESC, b'\\', ..] => Decoded::SetRelativePosition(SetRelativePosition(u16_at(2) as i16)),
+        [ESC, b'=', n] => Decoded::SelectPeripheral(SelectPeripheral(find(
+            n,
+            &[PeripheralDevice::PrinterOnly, PeripheralDevice::DisplayOnly, PeripheralDevice::Both],
+            |v| v as u8,
+        )?)),
+        [ESC, b'p', pin, t1, t2] => Decoded::GeneratePulse(GeneratePulse {
+            pin: find(digit(pin), &[DrawerPin::Pin2, DrawerPin::Pin5], |v| v as u8)?,
+            on_time_ms: u16::from(t1) * 2,
+            off_time_ms: u16::from(t2) * 2,
+        }),
+        [ESC, b'*', m, _, _, ref data @ ..] => Decoded::SelectBitImageMode(SelectBitImageMode {
+            mode: find(
+                m,
+                &[
+                    BitImageMode::SingleDensity8,
+                    BitImageMode::DoubleDensity8,
+                    BitImageMode::SingleDensity24,
+                    BitImageMode::DoubleDensity24,
+                ],
+                |v| v as u8,
+            )?,
+            width: u16_at(3),
+            data: data.to_vec(),
+        }),
+        [ESC, b'L'] => Decoded::EnterPageMode(EnterPageMode),
+        [ESC, b'S'] => Decoded::ExitPageMode(ExitPageMode),
+        [ESC, FF] => Decoded::PrintPageModeData(PrintPageModeData),
+        [ESC, b'T', n] => Decoded::SetPrintDirection(SetPrintDirection(find(
+            digit(n),
+            &[
+                PrintDirection::LeftToRight,
+                PrintDirection::BottomToTop,
+                PrintDirection::RightToLeft,
+                PrintDirection::TopToBottom,
+            ],
+            |v| v as u8,
+        )?)),
+        [ESC, b'W', ..] => Decoded::SetPrintArea(SetPrintArea(PrintArea {
+            x: u16_at(2),
+            y: u16_at(4),
+            width: u16_at(6),
+            height: u16_at(8),
+        })),
+        [ESC, b'&', HEIGHT_BYTES, first, _, ref glyphs @ ..] => {
+            let mut characters = Vec::new();
+            let mut rest = glyphs;
+            while let [width, ref tail @ ..] = *rest {
+                let (data, tail) = tail.split_at(usize::from(width) * usize::from(HEIGHT_BYTES));
+                characters.push(UserDefinedCharacter {
+                    width,
+                    data: data.to_vec(),
+                });
+                rest = tail;
+            }
+            Decoded::DefineUserDefinedCharacters(
+                DefineUserDefinedCharacters::new(first, characters).ok()?,
+            )
+        }
+        [ESC, b'%', n] => {
+            Decoded::SelectUserDefinedCharacters(SelectUserDefinedCharacters(n & 1 == 1))
+        }
+        [ESC, b'?', n] => Decoded::CancelUserDefinedCharacter(CancelUserDefinedCharacter(n)),
+
+        [GS, b'!', n] => Decoded::SetCharacterSize(SetCharacterSize(CharacterSize {
+            width: *SCALES.get(usize::from(n >> 4))?,
+            height: *SCALES.get(usize::from(n & 0x0F))?,
+        })),
+        [GS, b'B', n] => Decoded::SetReverse(SetReverse(n & 1 == 1)),
+        [GS, b'b', n] => Decoded::SetSmoothing(SetSmoothing(n & 1 == 1)),
+        [GS, b'V', m, ref feed @ ..] => Decoded::CutPaper(CutPaper {
+            mode: find(
+                digit(m),
+                &[CutMode::Full, CutMode::Partial, CutMode::FeedAndFull, CutMode::FeedAndPartial],
+                |v| v as u8,
+            )?,
+            feed_lines: feed.first().copied(),
+        }),
+        [GS, b'L', ..] => Decoded::SetLeftMargin(SetLeftMargin(u16_at(2))),
+        [GS, b'W', ..] => Decoded::SetPrintingWidth(SetPrintingWidth(u16_at(2))),
+        [GS, b'P', x, y] => Decoded::SetMotionUnits(SetMotionUnits {
+            x,
+            y,
+        }),
+        [GS, b'$', ..] => Decoded::SetVerticalPosition(SetVerticalPosition(u16_at(2))),
+        [GS, b'v', b'0' | 0, m, _, _, _, _, ref data @ ..] => {
+            Decoded::PrintRasterImage(PrintRasterImage {
+                mode: find(digit(m), &RASTER_MODES, |v| v as u8)?,
+                width_bytes: u16_at(4),
+                height_dots: u16_at(6),
+                data: data.to_vec(),
+            })
+        }
+        [GS, b'*', x, y, ref data @ ..] => {
+            Decoded::DefineDownloadedImage(DefineDownloadedImage::new(x, y, data.to_vec()).ok()?)
+        }
+        [GS, b'/', m] => Decoded::PrintDownloadedImage(PrintDownloadedImage(find(
+            digit(m),
+            &DOWNLOADED_MODES,
+            |v| v as u8,
+        )?)),
+        [GS, b'h', n] => Decoded::SetBarcodeHeight(SetBarcodeHeight(n)),
+        [GS, b'w', n] => Decoded::SetBarcodeWidth(SetBarcodeWidth(BarcodeWidth::from_dots(n)?)),
+        [GS, b'H', n] => Decoded::SetHriPosition(SetHriPosition(find(
+            digit(n),
+            &[HriPosition::None, HriPosition::Above, HriPosition::Below, HriPosition::Both],
+            |v| v as u8,
+        )?)),
+        [GS, b'f', n] => {
+            Decoded::SetHriFont(SetHriFont(find(digit(n), &[HriFont::A, HriFont::B], |v| v as u8)?))
+        }
+        [GS, b'k', m, _, ref data @ ..] if m > 6 => Decoded::PrintBarcode(
+            PrintBarcode::new(find(m, &BARCODE_SYSTEMS, |v| v as u8)?, data).ok()?,
+        ),
+        [GS, b'(', b'A', 2, 0, 0 | b'0', n] => {
+            Decoded::PrintTestPage(PrintTestPage(find(digit(n), &TEST_PATTERNS, |v| v as u8)?))
+        }
+        [GS, b'(', b'k', _, _, ref body @ ..] => symbol(body)?,
+        [GS, b'(', b'L', _, _, ref body @ ..] | [GS, b'8', b'L', _, _, _, _, ref body @ ..] => {
+            graphics(body)?
+        }
+        [GS, b'a', n] => Decoded::EnableAsb(EnableAsb(AsbFlags {
+            drawer: n & 0x01 != 0,
+            online_offline: n & 0x02 != 0,
+            error: n & 0x04 != 0,
+            paper_roll: n & 0x08 != 0,
+        })),
+        [GS, b':'] => Decoded::ToggleMacroDefinition(ToggleMacroDefinition),
+        [GS, b'^', times, wait_100ms, mode] => Decoded::ExecuteMacro(ExecuteMacro {
+            times,
+            wait_100ms,
+            mode: find(
+                mode,
+                &[MacroExecutionMode::Continuous, MacroExecutionMode::WaitForButton],
+                |v| v as u8,
+            )?,
+        }),
+
+        [FS, b'&'] => Decoded::SelectKanjiMode(SelectKanjiMode),
+        [FS, b'.'] => Decoded::CancelKanjiMode(CancelKanjiMode),
+        [FS, b'!', n] => Decoded::SetKanjiPrintMode(SetKanjiPrintMode(KanjiPrintMode {
+            double_width: n & 0x04 != 0,
+            double_height: n & 0x08 != 0,
+            underline: n & 0x80 != 0,
+        })),
+        [FS, b'-', n] => Decoded::SetKanjiUnderline(SetKanjiUnderline(find(
+            digit(n),
+            &[KanjiUnderline::Off, KanjiUnderline::OneDot, KanjiUnderline::TwoDot],
+            |v| v as u8,
+        )?)),
+        [FS, b'S', left, right] => Decoded::SetKanjiSpacing(SetKanjiSpacing::new(left, right)),
+        [FS, b'C', n] => Decoded::SelectKanjiCodeSystem(SelectKanjiCodeSystem(find(
+            digit(n),
+            &[KanjiCodeSystem::Jis, KanjiCodeSystem::ShiftJis],
+            |v| v as u8,
+        )?)),
+
+        [DLE, EOT, n] => Decoded::TransmitStatus(TransmitStatus(find(
+            n,
+            &[StatusType::Printer, StatusType::Offline, StatusType::Error, StatusType::PaperRoll],
+            |v| v as u8,
+        )?)),
+        _ => return None,
+    })
+}
+
+/// `GS ( k` functions, from the symbol type byte on.
+fn symbol(body: &[u8]) -> Option<Decoded> {
+    Some(match *body {
+        [49, 65, model, 0] => Decoded::SetQrModel(SetQrModel(find(
+            model,
+            &[QrModel::Model1, QrModel::Model2],
+            |v| v as u8,
+        )?)),
+        [49, 67, size] => {
+            Decoded::SetQrModuleSize(SetQrModuleSize(find(size, &QR_MODULE_SIZES, |v| v as u8)?))
+        }
+        [49, 69, level] => Decoded::SetQrErrorCorrection(SetQrErrorCorrection(find(
+            level,
+            &[
+                QrErrorCorrection::L,
+                QrErrorCorrection::M,
+                QrErrorCorrection::Q,
+                QrErrorCorrection::H,
+            ],
+            |v| v as u8,
+        )?)),
+        [49, 80, 48, ref data @ ..] => Decoded::StoreQrData(StoreQrData::new(data).ok()?),
+        [49, 81, 48] => Decoded::PrintStoredQr(PrintStoredQr),
+        [49, 82, 48] => Decoded::QuerySymbolStorage(QuerySymbolStorage),
+        _ => return None,
+    })
+}
+
+/// `GS ( L` and `GS 8 L` functions, from the `48` byte on.
+fn graphics(body: &[u8]) -> Option<Decoded> {
+    Some(match *body {
+        [48, 48] => Decoded::QueryNvCapacity(QueryNvCapacity),
+        [48, 51] => Decoded::QueryNvRemaining(QueryNvRemaining),
+        [48, 64, b'K', b'C'] => Decoded::QueryNvKeyCodes(QueryNvKeyCodes),
+        [48, 65, b'C', b'L', b'R'] => Decoded::DeleteAllNvGraphics(DeleteAllNvGraphics),
+        [48, 66, kc1, kc2] => {
+            Decoded::DeleteNvGraphics(DeleteNvGraphics(KeyCode::new(kc1, kc2).ok()?))
+        }
+        [48, 67, 48, kc1, kc2, 1, x_low, x_high, y_low, y_high, 49, ref data @ ..] => {
+            let width_dots = u16::from_le_bytes([x_low, x_high]);
+            let height_dots = u16::from_le_bytes([y_low, y_high]);
+            if !width_dots.is_multiple_of(8)
+                || data.len() != usize::from(width_dots / 8) * usize::from(height_dots)
+            {
+                return None;
+            }
+            Decoded::DefineNvGraphics(DefineNvGraphics {
+                key: KeyCode::new(kc1, kc2).ok()?,
+                image: PrintRasterImage::new(width_dots / 8, height_dots, data.to_vec()),
+            })
+        }
+        [48, 69, kc1, kc2, x @ (1 | 2), y @ (1 | 2)] => Decoded::PrintNvGraphics(PrintNvGraphics {
+            key: KeyCode::new(kc1, kc2).ok()?,
+            double_width: x == 2,
+            double_height: y == 2,
+        }),
+        _ => return None,
+    })
+}
+
+const SCALES: [ScaleFactor; 8] = [
+    ScaleFactor::X1,
+    ScaleFactor::X2,
+    ScaleFactor::X3,
+    ScaleFactor::X4,
+    ScaleFactor::X5,
+    ScaleFactor::X6,
+    ScaleFactor::X7,
+    ScaleFactor::X8,
+];
+
+const RASTER_MODES: [RasterImageMode; 4] = [
+    RasterImageMode::Normal,
+    RasterImageMode::DoubleWidth,
+    RasterImageMode::DoubleHeight,
+    RasterImageMode::Quadruple,
+];
+
+const DOWNLOADED_MODES: [DownloadedImageMode; 4] = [
+    DownloadedImageMode::Normal,
+    DownloadedImageMode::DoubleWidth,
+    DownloadedImageMode::DoubleHeight,
+    DownloadedImageMode::Quadruple,
+];
+
+const BARCODE_SYSTEMS: [BarcodeSystem; 9] = [
+    BarcodeSystem::UpcA,
+    BarcodeSystem::UpcE,
+    BarcodeSystem::Jan13,
+    BarcodeSystem::Jan8,
+    BarcodeSystem::Code39,
+    BarcodeSystem::Itf,
+    BarcodeSystem::Codabar,
+    BarcodeSystem::Code93,
+    BarcodeSystem::Code128,
+];
+
+const QR_MODULE_SIZES: [QrModuleSize; 8] = [
+    QrModuleSize::Size1,
+    QrModuleSize::Size2,
+    QrModuleSize::Size3,
+    QrModuleSize::Size4,
+    QrModuleSize::Size5,
+    QrModuleSize::Size6,
+    QrModuleSize::Size7,
+    QrModuleSize::Size8,
+];
+
+const TEST_PATTERNS: [TestPattern; 3] =
+    [TestPattern::HexDump, TestPattern::StatusSheet, TestPattern::RollingPattern];
+
+/// The value among `values` whose command byte is `n`.
+fn find<T: Copy>(n: u8, values: &[T], byte: impl Fn(T) -> u8) -> Option<T> {
+    values.iter().copied().find(|&value| byte(value) == n)
+}
+
+/// `n`, reading ASCII digits as their value.
+fn digit(n: u8) -> u8 {
+    if n.is_ascii_digit() {
+        n - b'0'
+    } else {
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::symbol::PrintQrCode;
+    use crate::style::text::Styleable;
+
+    fn round_trip(bytes: &[u8]) -> Vec<Decoded> {
+        let items = decode(bytes);
+        let encoded: Vec<u8> = items.iter().flat_map(Decoded::encode).collect();
+        assert_eq!(encoded, bytes);
+        items
+    }
+
+    #[test]
+    fn decodes_styled_text() {
+        let items = round_trip(&"Total".bold().underlined().render_line_merged());
+        assert_eq!(
+            items,
+            [
+                Decoded::SetEmphasized(SetEmphasized(true)),
+                Decoded::SetUnderline(SetUnderline(UnderlineThickness::OneDot)),
+                Decoded::Text(b"Total".to_vec()),
+                Decoded::SetEmphasized(SetEmphasized(false)),
+                Decoded::SetUnderline(SetUnderline(UnderlineThickness::Off)),
+                Decoded::LineFeed(LineFeed),
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_variable_length_commands() {
+        let raster = PrintRasterImage::new(2, 3, vec![0xAA; 6]);
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "ABC").unwrap();
+        let tabs = SetHorizontalTabs::new(vec![8, 16]).unwrap();
+        let bytes = [raster.encode(), barcode.encode(), tabs.encode()].concat();
+        assert_eq!(
+            round_trip(&bytes),
+            [
+                Decoded::PrintRasterImage(raster),
+                Decoded::PrintBarcode(barcode),
+                Decoded::SetHorizontalTabs(tabs),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_qr_code_into_functions() {
+        let qr = PrintQrCode::new("https://example.com").unwrap();
+        let items = round_trip(&qr.encode());
+        assert_eq!(items.len(), 5);
+        assert_eq!(items[0], Decoded::SetQrModel(SetQrModel(QrModel::Model2)));
+        assert_eq!(
+            items[3],
+            Decoded::StoreQrData(StoreQrData::new("https://example.com").unwrap())
+        );
+        assert_eq!(items[4], Decoded::PrintStoredQr(PrintStoredQr));
+    }
+
+    #[test]
+    fn accepts_ascii_digit_parameters() {
+        assert_eq!(
+            decode(b"\x1ba1"),
+            [Decoded::SetJustification(SetJustification(Justification::Center))]
+        );
+        assert_eq!(decode(b"\x1dV1"), [Decoded::CutPaper(CutPaper::partial())]);
+    }
+
+    #[test]
+    fn reencodes_ignored_bits_in_canonical_form() {
+        let items = decode(b"\x1bE\x38");
+        assert_eq!(items, [Decoded::SetEmphasized(SetEmphasized(false))]);
+        assert_eq!(items[0].encode(), b"\x1bE\x00");
+    }
+
+    #[test]
+    fn passes_unknown_and_truncated_bytes_through() {
+        let items = round_trip(b"\x1b!\x08A\x1b-\x07\x01\x1dv0\x00\x10\x00");
+        assert_eq!(
+            items,
+            [
+                Decoded::Unknown(b"\x1b!\x08".to_vec()),
+                Decoded::Text(b"A".to_vec()),
+                Decoded::Unknown(b"\x1b-\x07".to_vec()),
+                Decoded::Unknown(b"\x01".to_vec()),
+                Decoded::Unknown(b"\x1dv0\x00\x10\x00".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_items_with_offsets() {
+        let listing = disassemble(b"\x1d!\x11x\x1b!\x00");
+        assert_eq!(
+            listing,
+            "0000  SetCharacterSize(CharacterSize { width: X2, height: X2 })\n\
+             0003  Text(\"x\")\n\
+             0004  Unknown(\"\\x1b!\\x00\")\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn jobs_round_trip_through_json() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "ABC").unwrap();
+        let bytes =
+            [&"Total".bold().render_line()[..], &barcode.encode(), b"\x1dV\x42\x03"].concat();
+        let json = serde_json::to_string(&decode(&bytes)).unwrap();
+        assert!(json.starts_with(r#"[{"SetEmphasized":true},{"Text":[84,111,116,97,108]}"#));
+
+        let items: Vec<Decoded> = serde_json::from_str(&json).unwrap();
+        let encoded: Vec<u8> = items.iter().flat_map(Decoded::encode).collect();
+        assert_eq!(encoded, bytes);
+    }
+}
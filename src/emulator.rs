@@ -0,0 +1,40 @@
+//! Printer emulators for testing receipt output.
+//!
+//! Both emulators run a print job through the [`decode`](crate::decode)
+//! module and keep the printer's standard mode state, so they show what
+//! would come out of the printer rather than what was asked for:
+//!
+//! - [`text`] writes annotated plain text, for readable golden files of
+//!   receipt layout
+//! - `image` draws the receipt dot for dot, for visual snapshot tests
+//!   (requires the `emulator` feature)
+
+#[cfg(feature = "emulator")]
+pub mod image;
+pub mod text;
+
+use crate::command::barcode::{BarcodeSystem, PrintBarcode};
+use crate::command::codepage::CodePage;
+
+/// Human readable text for `barcode`, without CODE128 code set selectors.
+fn hri_text(barcode: &PrintBarcode) -> String {
+    let data = barcode.data();
+    if barcode.system() != BarcodeSystem::Code128 {
+        return String::from_utf8_lossy(data).into_owned();
+    }
+    let mut text = String::new();
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte != b'{' {
+            text.push(char::from(byte));
+        } else if bytes.next() == Some(&b'{') {
+            text.push('{');
+        }
+    }
+    text
+}
+
+/// `byte` in `page`, or CP437 if no page was selected.
+fn decode_byte(page: Option<CodePage>, byte: u8) -> char {
+    page.unwrap_or_default().decode_byte(byte).unwrap_or('?')
+}
@@ -0,0 +1,948 @@
+//! Receipt images.
+//!
+//! Runs a print job through the [`decode`](crate::decode) module and draws
+//! what a standard mode receipt would look like, one pixel per dot, so
+//! receipt code can be covered by visual snapshot tests in CI. Character
+//! fonts, sizes, emphasis, underline, reverse, rotated and upside-down
+//! printing, justification, margins, tabs and line spacing are laid out as
+//! the printer does; bit, raster, downloaded and NV images, barcodes with
+//! their HRI text, QR codes and user-defined characters are drawn dot for
+//! dot. Cuts are drawn as a dashed line across the paper.
+//!
+//! ```
+//! use bixolon::command::Command;
+//! use bixolon::command::paper::CutPaper;
+//! use bixolon::emulator::image;
+//! use bixolon::style::text::Styleable;
+//!
+//! let mut job = "Total 12.50".bold().centered().render_line();
+//! job.extend(CutPaper::feed_and_partial(3).encode());
+//!
+//! let receipt = image::render(&job);
+//! assert_eq!(receipt.cuts().len(), 1);
+//! assert!(receipt.image().to_png()?.starts_with(b"\x89PNG"));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Glyphs come from the same bitmap fonts as the
+//! [page mode preview](crate::page::preview), which also draws any page mode
+//! sections of the job. Barcode symbologies without a host-side renderer
+//! (see [`PrintBarcode::to_raster`]) are drawn as outlined boxes of roughly
+//! their printed size, and macros aren't executed.
+
+use std::collections::HashMap;
+use std::mem;
+
+use super::{decode_byte, hri_text};
+use crate::command::Command;
+use crate::command::barcode::{BarcodeWidth, HriFont, HriPosition, PrintBarcode, SetBarcodeHeight};
+use crate::command::character::{Font, Justification, RotationMode};
+use crate::command::codepage::CodePage;
+use crate::command::graphics::KeyCode;
+use crate::command::image::{
+    BitImageMode, DefineDownloadedImage, DownloadedImageMode, PrintRasterImage, RasterImageMode,
+};
+use crate::command::paper::CutMode;
+use crate::command::printer_control::PeripheralDevice;
+use crate::command::spacing::SetMotionUnits;
+use crate::command::symbol::{PrintQrCode, QrErrorCorrection, QrModel, QrModuleSize};
+use crate::command::user_defined::HEIGHT_BYTES;
+use crate::decode::{Decoded, Decoder};
+use crate::page::preview::{Canvas, LINE_SPACING, PagePreview, draw_glyph, font, units_to_dots};
+use crate::profile::PrinterProfile;
+
+/// Default barcode height, in dots.
+const BARCODE_HEIGHT: u8 = 162;
+
+/// A receipt printed by the [`Emulator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    image: PagePreview,
+    cuts: Vec<u32>,
+}
+
+impl Receipt {
+    /// The paper, from the first printed line to the last feed.
+    pub fn image(&self) -> &PagePreview {
+        &self.image
+    }
+
+    /// Rows at which the paper was cut, in dots from the top.
+    pub fn cuts(&self) -> &[u32] {
+        &self.cuts
+    }
+}
+
+/// Render the receipt printed by a raw ESC/POS job.
+pub fn render(job: &[u8]) -> Receipt {
+    render_with_profile(job, &PrinterProfile::default())
+}
+
+/// Render the receipt printed by a raw ESC/POS job on a specific printer.
+pub fn render_with_profile(job: &[u8], profile: &PrinterProfile) -> Receipt {
+    let mut emulator = Emulator::new(profile.clone());
+    emulator.feed(job);
+    emulator.receipt()
+}
+
+/// Standard mode printer state, as set by commands and cleared by
+/// `ESC @`.
+#[derive(Debug, Clone)]
+struct State {
+    font: Font,
+    bold: bool,
+    underline: u32,
+    reverse: bool,
+    upside_down: bool,
+    rotated: bool,
+    width: u32,
+    height: u32,
+    justification: Justification,
+    units: SetMotionUnits,
+    left_margin: u16,
+    printing_width: Option<u16>,
+    line_spacing: u32,
+    right_spacing: u8,
+    tabs: Vec<u8>,
+    code_page: Option<CodePage>,
+    user_defined: bool,
+    barcode_height: u8,
+    barcode_width: BarcodeWidth,
+    hri_position: HriPosition,
+    hri_font: HriFont,
+    qr: PrintQrCode,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            font: Font::A,
+            bold: false,
+            underline: 0,
+            reverse: false,
+            upside_down: false,
+            rotated: false,
+            width: 1,
+            height: 1,
+            justification: Justification::Left,
+            units: SetMotionUnits::default(),
+            left_margin: 0,
+            printing_width: None,
+            line_spacing: LINE_SPACING,
+            right_spacing: 0,
+            tabs: (1..32).map(|n| n * 8).collect(),
+            code_page: None,
+            user_defined: false,
+            barcode_height: BARCODE_HEIGHT,
+            barcode_width: BarcodeWidth::default(),
+            hri_position: HriPosition::None,
+            hri_font: HriFont::A,
+            qr: PrintQrCode {
+                model: QrModel::Model2,
+                module_size: QrModuleSize::Size3,
+                error_correction: QrErrorCorrection::L,
+                data: Vec::new(),
+            },
+        }
+    }
+}
+
+/// The line being collected in the print buffer.
+#[derive(Debug, Default)]
+struct Line {
+    /// Bitmaps and their offsets from the start of the line, in dots.
+    items: Vec<(u32, Bitmap)>,
+    x: u32,
+    justification: Justification,
+    upside_down: bool,
+}
+
+/// Printer simulation for standard mode jobs.
+///
+/// Feed it the bytes sent to a printer, in as many pieces as they were
+/// written, then take the [`Receipt`]. Like the printer, a line isn't
+/// printed until a line feed, feed, cut or image ends it.
+pub struct Emulator {
+    profile: PrinterProfile,
+    paper: Canvas,
+    /// Top of the next printed line, in dots.
+    y: u32,
+    cuts: Vec<u32>,
+    state: State,
+    line: Line,
+    /// Encoded commands since `ESC L`, while in page mode.
+    page: Option<Vec<u8>>,
+    /// Device receiving data, selected with `ESC =`.
+    peripheral: PeripheralDevice,
+    downloaded: Option<Bitmap>,
+    nv_graphics: HashMap<KeyCode, Bitmap>,
+    user_defined: HashMap<u8, Bitmap>,
+    glyphs: HashMap<(char, Font), Bitmap>,
+}
+
+impl Emulator {
+    /// An emulator for `profile`'s paper width and fonts.
+    pub fn new(profile: PrinterProfile) -> Self {
+        Self {
+            profile,
+            paper: Canvas::default(),
+            y: 0,
+            cuts: Vec::new(),
+            state: State::default(),
+            line: Line::default(),
+            page: None,
+            peripheral: PeripheralDevice::PrinterOnly,
+            downloaded: None,
+            nv_graphics: HashMap::new(),
+            user_defined: HashMap::new(),
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Process the next part of a job.
+    ///
+    /// Commands are decoded per call, so split jobs at command boundaries.
+    pub fn feed(&mut self, bytes: &[u8]) -> &mut Self {
+        for item in Decoder::new(bytes) {
+            self.execute(item);
+        }
+        self
+    }
+
+    /// The paper printed so far.
+    pub fn receipt(&self) -> Receipt {
+        Receipt {
+            image: self.paper.to_preview(),
+            cuts: self.cuts.clone(),
+        }
+    }
+
+    fn execute(&mut self, item: Decoded) {
+        // Data for the customer display passes through without printing
+        if let Decoded::SelectPeripheral(select) = item {
+            self.peripheral = select.0;
+            return;
+        }
+        if self.peripheral == PeripheralDevice::DisplayOnly {
+            return;
+        }
+
+        if let Some(page) = &mut self.page {
+            page.extend(item.encode());
+            match item {
+                Decoded::PrintPageModeData(_) => self.print_page(),
+                Decoded::FormFeed(_) => {
+                    self.print_page();
+                    self.page = None;
+                }
+                Decoded::ExitPageMode(_) => self.page = None,
+                Decoded::Initialize(_) => {
+                    self.page = None;
+                    self.execute(item);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let state = &mut self.state;
+        match item {
+            Decoded::Text(bytes) => self.text(&bytes),
+            Decoded::LineFeed(_) => self.print_line(None),
+            Decoded::HorizontalTab(_) => {
+                let column = self.column_width();
+                if let Some(&stop) =
+                    self.state.tabs.iter().find(|&&stop| u32::from(stop) * column > self.line.x)
+                {
+                    self.line.x = u32::from(stop) * column;
+                }
+            }
+            Decoded::Initialize(_) => {
+                self.line = Line::default();
+                self.state = State::default();
+                self.downloaded = None;
+                self.user_defined.clear();
+            }
+            Decoded::FeedPaper(feed) => {
+                let dots = units_to_dots(u16::from(feed.0), state.units.vertical_per_inch());
+                self.print_line(Some(dots));
+            }
+            Decoded::FeedLines(feed) => {
+                if feed.0 == 0 {
+                    self.print_line(Some(0));
+                }
+                for _ in 0..feed.0 {
+                    self.print_line(None);
+                }
+            }
+            Decoded::CutPaper(cut) => {
+                self.end_line();
+                if matches!(cut.mode, CutMode::FeedAndFull | CutMode::FeedAndPartial) {
+                    let feed = u16::from(cut.feed_lines.unwrap_or(0));
+                    self.advance(units_to_dots(feed, self.state.units.vertical_per_inch()));
+                }
+                self.cut();
+            }
+            Decoded::SetEmphasized(on) => state.bold = on.0,
+            Decoded::SetDoubleStrike(on) => state.bold = on.0,
+            Decoded::SetUnderline(thickness) => state.underline = thickness.0 as u32,
+            Decoded::SelectFont(font) => state.font = font.0,
+            Decoded::SetCharacterSize(size) => {
+                state.width = u32::from(size.0.width.multiplier());
+                state.height = u32::from(size.0.height.multiplier());
+            }
+            Decoded::SetJustification(justification) => state.justification = justification.0,
+            Decoded::SetUpsideDown(on) => state.upside_down = on.0,
+            Decoded::SetRotation(mode) => state.rotated = mode.0 == RotationMode::Clockwise90,
+            Decoded::SetReverse(on) => state.reverse = on.0,
+            Decoded::SelectCodePage(page) => state.code_page = Some(page.0),
+            Decoded::SetDefaultLineSpacing(_) => state.line_spacing = LINE_SPACING,
+            Decoded::SetLineSpacing(spacing) => {
+                state.line_spacing =
+                    units_to_dots(u16::from(spacing.0), state.units.vertical_per_inch());
+            }
+            Decoded::SetRightSpacing(spacing) => state.right_spacing = spacing.0,
+            Decoded::SetHorizontalTabs(tabs) => state.tabs = tabs.positions,
+            Decoded::SetMotionUnits(units) => state.units = units,
+            Decoded::SetAbsolutePosition(position) => {
+                self.line.x = units_to_dots(position.0, state.units.horizontal_per_inch());
+            }
+            Decoded::SetRelativePosition(offset) => {
+                let dots =
+                    units_to_dots(offset.0.unsigned_abs(), state.units.horizontal_per_inch());
+                self.line.x = if offset.0 < 0 {
+                    self.line.x.saturating_sub(dots)
+                } else {
+                    self.line.x + dots
+                };
+            }
+            Decoded::SetLeftMargin(margin) => state.left_margin = margin.0,
+            Decoded::SetPrintingWidth(width) => state.printing_width = Some(width.0),
+            Decoded::EnterPageMode(item) => {
+                self.end_line();
+                self.page = Some(item.encode());
+            }
+            Decoded::SelectBitImageMode(image) => {
+                let (height_bytes, scale_x, scale_y) = match image.mode {
+                    BitImageMode::SingleDensity8 => (1, 2, 3),
+                    BitImageMode::DoubleDensity8 => (1, 1, 3),
+                    BitImageMode::SingleDensity24 => (3, 2, 1),
+                    BitImageMode::DoubleDensity24 => (3, 1, 1),
+                };
+                let bitmap = Bitmap::from_columns(&image.data, height_bytes);
+                self.push(bitmap.scale(scale_x, scale_y));
+            }
+            Decoded::PrintRasterImage(image) => {
+                let bitmap = Bitmap::from_raster(&image);
+                self.print_block(bitmap);
+            }
+            Decoded::DefineDownloadedImage(image) => {
+                self.downloaded = Some(Bitmap::from_downloaded(&image));
+            }
+            Decoded::PrintDownloadedImage(mode) => {
+                if let Some(image) = &self.downloaded {
+                    let bitmap = match mode.0 {
+                        DownloadedImageMode::Normal => image.clone(),
+                        DownloadedImageMode::DoubleWidth => image.scale(2, 1),
+                        DownloadedImageMode::DoubleHeight => image.scale(1, 2),
+                        DownloadedImageMode::Quadruple => image.scale(2, 2),
+                    };
+                    self.push(bitmap);
+                }
+            }
+            Decoded::DefineNvGraphics(graphics) => {
+                self.nv_graphics.insert(graphics.key, Bitmap::from_raster(&graphics.image));
+            }
+            Decoded::DeleteNvGraphics(delete) => {
+                self.nv_graphics.remove(&delete.0);
+            }
+            Decoded::DeleteAllNvGraphics(_) => self.nv_graphics.clear(),
+            Decoded::PrintNvGraphics(print) => {
+                if let Some(image) = self.nv_graphics.get(&print.key) {
+                    let bitmap = image.scale(
+                        1 + u32::from(print.double_width),
+                        1 + u32::from(print.double_height),
+                    );
+                    self.print_block(bitmap);
+                }
+            }
+            Decoded::SetBarcodeHeight(height) => state.barcode_height = height.0,
+            Decoded::SetBarcodeWidth(width) => state.barcode_width = width.0,
+            Decoded::SetHriPosition(position) => state.hri_position = position.0,
+            Decoded::SetHriFont(font) => state.hri_font = font.0,
+            Decoded::PrintBarcode(barcode) => {
+                let bitmap = self.barcode(&barcode);
+                self.print_block(bitmap);
+            }
+            Decoded::SetQrModel(model) => state.qr.model = model.0,
+            Decoded::SetQrModuleSize(size) => state.qr.module_size = size.0,
+            Decoded::SetQrErrorCorrection(level) => state.qr.error_correction = level.0,
+            Decoded::StoreQrData(store) => state.qr.data = store.data().to_vec(),
+            Decoded::PrintStoredQr(_) => {
+                if let Ok(image) = state.qr.to_raster() {
+                    self.print_block(Bitmap::from_raster(&image));
+                }
+            }
+            Decoded::DefineUserDefinedCharacters(define) => {
+                for (code, character) in (define.first..).zip(&define.characters) {
+                    let glyph = Bitmap::from_columns(&character.data, usize::from(HEIGHT_BYTES));
+                    self.user_defined.insert(code, glyph);
+                }
+            }
+            Decoded::SelectUserDefinedCharacters(on) => state.user_defined = on.0,
+            Decoded::CancelUserDefinedCharacter(cancel) => {
+                self.user_defined.remove(&cancel.0);
+            }
+            _ => {}
+        }
+    }
+
+    fn paper_width(&self) -> u32 {
+        u32::from(self.profile.paper_width_dots)
+    }
+
+    /// Left edge and width of the printable area, in dots.
+    fn print_area(&self) -> (u32, u32) {
+        let per_inch = self.state.units.horizontal_per_inch();
+        let left = units_to_dots(self.state.left_margin, per_inch).min(self.paper_width());
+        let available = self.paper_width() - left;
+        let width = self
+            .state
+            .printing_width
+            .map_or(available, |width| units_to_dots(width, per_inch).min(available));
+        (left, width)
+    }
+
+    /// Width of one tab column, in dots.
+    fn column_width(&self) -> u32 {
+        let font_width = u32::from(self.profile.char_width_dots(self.state.font));
+        let spacing = units_to_dots(
+            u16::from(self.state.right_spacing),
+            self.state.units.horizontal_per_inch(),
+        );
+        (font_width + spacing) * self.state.width
+    }
+
+    fn text(&mut self, bytes: &[u8]) {
+        if self.state.user_defined {
+            for &byte in bytes {
+                match self.user_defined.get(&byte) {
+                    Some(glyph) => {
+                        let glyph = glyph.clone();
+                        self.character(glyph);
+                    }
+                    None => self.text_char(decode_byte(self.state.code_page, byte)),
+                }
+            }
+            return;
+        }
+        match (self.state.code_page, std::str::from_utf8(bytes)) {
+            (None, Ok(text)) => text.chars().for_each(|c| self.text_char(c)),
+            (page, _) => bytes.iter().for_each(|&b| self.text_char(decode_byte(page, b))),
+        }
+    }
+
+    fn text_char(&mut self, c: char) {
+        let font = self.state.font;
+        let glyph = match self.glyphs.get(&(c, font)) {
+            Some(glyph) => glyph.clone(),
+            None => {
+                let glyph = self.font_glyph(c, font);
+                self.glyphs.insert((c, font), glyph.clone());
+                glyph
+            }
+        };
+        self.character(glyph);
+    }
+
+    /// `c` centred in `font`'s character cell.
+    fn font_glyph(&self, c: char, font_choice: Font) -> Bitmap {
+        let size = font(font_choice).character_size;
+        let source = Bitmap {
+            width: size.width,
+            height: size.height,
+            dots: draw_glyph(c, font_choice),
+        };
+        let width = u32::from(self.profile.char_width_dots(font_choice));
+        let height = u32::from(self.profile.char_height_dots(font_choice));
+        let mut cell = Bitmap::new(width, height);
+        cell.draw(
+            &source,
+            width.saturating_sub(size.width) / 2,
+            height.saturating_sub(size.height) / 2,
+        );
+        cell
+    }
+
+    /// Add a glyph to the line in the current character style, wrapping
+    /// first if it doesn't fit.
+    fn character(&mut self, glyph: Bitmap) {
+        let state = &self.state;
+        let spacing =
+            units_to_dots(u16::from(state.right_spacing), state.units.horizontal_per_inch());
+        let mut cell = Bitmap::new(glyph.width + spacing, glyph.height);
+        cell.draw(&glyph, 0, 0);
+        if state.bold {
+            cell.draw(&glyph, 1, 0);
+        }
+        if state.rotated {
+            cell = cell.rotate_90();
+        }
+        for y in cell.height.saturating_sub(state.underline)..cell.height {
+            for x in 0..cell.width {
+                cell.set(x, y);
+            }
+        }
+        if state.reverse {
+            cell.invert();
+        }
+        let cell = cell.scale(state.width, state.height);
+
+        let (_, area_width) = self.print_area();
+        if self.line.x > 0 && self.line.x + cell.width > area_width {
+            self.print_line(None);
+        }
+        self.push(cell);
+    }
+
+    /// Add a bitmap to the line at the current position.
+    fn push(&mut self, bitmap: Bitmap) {
+        if self.line.items.is_empty() {
+            self.line.justification = self.state.justification;
+            self.line.upside_down = self.state.upside_down;
+        }
+        let x = self.line.x;
+        self.line.x += bitmap.width;
+        self.line.items.push((x, bitmap));
+    }
+
+    /// Print the buffered line and feed `advance` dots, or the line
+    /// spacing (at least the line's height) if `None`.
+    fn print_line(&mut self, advance: Option<u32>) {
+        let line = mem::take(&mut self.line);
+        let height = line.items.iter().map(|(_, item)| item.height).max().unwrap_or(0);
+        let advance = advance.unwrap_or(self.state.line_spacing.max(height));
+        if height > 0 {
+            let (_, area_width) = self.print_area();
+            let width = line
+                .items
+                .iter()
+                .map(|(x, item)| x + item.width)
+                .max()
+                .unwrap_or(0)
+                .min(area_width);
+            let mut bitmap = Bitmap::new(width, height);
+            for (x, item) in &line.items {
+                bitmap.draw(item, *x, height - item.height);
+            }
+            if line.upside_down {
+                bitmap = bitmap.rotate_180();
+            }
+            self.place(&bitmap, line.justification);
+        }
+        self.advance(advance);
+    }
+
+    /// Feed the paper `dots` past the current row.
+    fn advance(&mut self, dots: u32) {
+        self.y += dots;
+        self.paper.grow(self.paper_width(), self.y);
+    }
+
+    /// Print any buffered line, as the printer does before images and cuts.
+    fn end_line(&mut self) {
+        if !self.line.items.is_empty() {
+            self.print_line(None);
+        }
+    }
+
+    /// Print a bitmap on lines of its own.
+    fn print_block(&mut self, bitmap: Bitmap) {
+        self.end_line();
+        self.place(&bitmap, self.state.justification);
+        self.advance(bitmap.height);
+    }
+
+    /// Draw `bitmap` at the current row, justified within the print area.
+    fn place(&mut self, bitmap: &Bitmap, justification: Justification) {
+        let (left, area_width) = self.print_area();
+        let slack = area_width.saturating_sub(bitmap.width);
+        let x = left
+            + match justification {
+                Justification::Left => 0,
+                Justification::Center => slack / 2,
+                Justification::Right => slack,
+            };
+        self.paper.grow(self.paper_width(), self.y + bitmap.height);
+        for row in 0..bitmap.height {
+            for column in 0..bitmap.width.min(area_width) {
+                if bitmap.get(column, row) {
+                    self.paper.set(x + column, self.y + row, true);
+                }
+            }
+        }
+    }
+
+    fn cut(&mut self) {
+        self.paper.grow(self.paper_width(), self.y + 1);
+        for x in (0..self.paper_width()).filter(|x| x % 8 < 4) {
+            self.paper.set(x, self.y, true);
+        }
+        self.cuts.push(self.y);
+    }
+
+    /// Render the page mode commands collected so far and paste the newest
+    /// page.
+    fn print_page(&mut self) {
+        let Some(job) = &self.page else {
+            return;
+        };
+        let pages = crate::page::preview::render_with_profile(job, &self.profile);
+        if let Some(page) = pages.last() {
+            self.paper.grow(self.paper_width(), self.y + page.height());
+            let mut paper = mem::take(&mut self.paper);
+            let y = self.y;
+            for row in 0..page.height() {
+                for column in 0..page.width() {
+                    if page.is_black(column, row) {
+                        paper.set(column, y + row, true);
+                    }
+                }
+            }
+            self.paper = paper;
+            self.advance(page.height());
+        }
+    }
+
+    /// Bars and HRI text for `barcode`.
+    fn barcode(&self, barcode: &PrintBarcode) -> Bitmap {
+        let height = SetBarcodeHeight(self.state.barcode_height);
+        let bars = match barcode.to_raster(self.state.barcode_width, height) {
+            Ok(image) => Bitmap::from_raster(&image),
+            Err(_) => {
+                // About 11 modules per character plus quiet zones and guards
+                let modules = barcode.data().len() as u32 * 11 + 35;
+                let module = self.state.barcode_width as u32;
+                Bitmap::outline(modules * module, u32::from(self.state.barcode_height))
+            }
+        };
+        let (above, below) = match self.state.hri_position {
+            HriPosition::None => (false, false),
+            HriPosition::Above => (true, false),
+            HriPosition::Below => (false, true),
+            HriPosition::Both => (true, true),
+        };
+        if !above && !below {
+            return bars;
+        }
+
+        let font = match self.state.hri_font {
+            HriFont::A => Font::A,
+            HriFont::B => Font::B,
+        };
+        let glyphs: Vec<Bitmap> =
+            hri_text(barcode).chars().map(|c| self.font_glyph(c, font)).collect();
+        let text_width = glyphs.iter().map(|glyph| glyph.width).sum();
+        let text_height = u32::from(self.profile.char_height_dots(font));
+        let mut text = Bitmap::new(text_width, text_height);
+        let mut x = 0;
+        for glyph in &glyphs {
+            text.draw(glyph, x, 0);
+            x += glyph.width;
+        }
+
+        let width = bars.width.max(text.width);
+        let height = bars.height + text.height * (u32::from(above) + u32::from(below));
+        let mut block = Bitmap::new(width, height);
+        let mut y = 0;
+        if above {
+            block.draw(&text, (width - text.width) / 2, y);
+            y += text.height;
+        }
+        block.draw(&bars, (width - bars.width) / 2, y);
+        y += bars.height;
+        if below {
+            block.draw(&text, (width - text.width) / 2, y);
+        }
+        block
+    }
+}
+
+/// A monochrome image, one `bool` per dot, row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Bitmap {
+    width: u32,
+    height: u32,
+    dots: Vec<bool>,
+}
+
+impl Bitmap {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// A box outline, for symbols that can't be drawn.
+    fn outline(width: u32, height: u32) -> Self {
+        let mut bitmap = Self::new(width, height);
+        for x in 0..width {
+            bitmap.set(x, 0);
+            bitmap.set(x, height.saturating_sub(1));
+        }
+        for y in 0..height {
+            bitmap.set(0, y);
+            bitmap.set(width.saturating_sub(1), y);
+        }
+        bitmap
+    }
+
+    /// Rows of `width_bytes` bytes, leftmost dot in the high bit, scaled
+    /// by the image's mode.
+    fn from_raster(image: &PrintRasterImage) -> Self {
+        let width_bytes = usize::from(image.width_bytes);
+        let mut bitmap = Self::new(u32::from(image.width_bytes) * 8, u32::from(image.height_dots));
+        for y in 0..bitmap.height {
+            for x in 0..bitmap.width {
+                let index = y as usize * width_bytes + x as usize / 8;
+                if image.data.get(index).is_some_and(|&byte| byte & (0x80 >> (x % 8)) != 0) {
+                    bitmap.set(x, y);
+                }
+            }
+        }
+        match image.mode {
+            RasterImageMode::Normal => bitmap,
+            RasterImageMode::DoubleWidth => bitmap.scale(2, 1),
+            RasterImageMode::DoubleHeight => bitmap.scale(1, 2),
+            RasterImageMode::Quadruple => bitmap.scale(2, 2),
+        }
+    }
+
+    /// Columns of `height_bytes` bytes, top dot in the high bit.
+    fn from_columns(data: &[u8], height_bytes: usize) -> Self {
+        let columns = data.len() / height_bytes.max(1);
+        let mut bitmap = Self::new(columns as u32, height_bytes as u32 * 8);
+        for (x, column) in data.chunks_exact(height_bytes.max(1)).enumerate() {
+            for y in 0..height_bytes * 8 {
+                if column[y / 8] & (0x80 >> (y % 8)) != 0 {
+                    bitmap.set(x as u32, y as u32);
+                }
+            }
+        }
+        bitmap
+    }
+
+    fn from_downloaded(image: &DefineDownloadedImage) -> Self {
+        Self::from_columns(&image.data, usize::from(image.height_bytes))
+    }
+
+    fn get(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.dots[(y * self.width + x) as usize]
+    }
+
+    fn set(&mut self, x: u32, y: u32) {
+        if x < self.width && y < self.height {
+            self.dots[(y * self.width + x) as usize] = true;
+        }
+    }
+
+    /// Overlay `other`'s black dots with its corner at (`x`, `y`).
+    fn draw(&mut self, other: &Bitmap, x: u32, y: u32) {
+        for row in 0..other.height {
+            for column in 0..other.width {
+                if other.get(column, row) {
+                    self.set(x + column, y + row);
+                }
+            }
+        }
+    }
+
+    fn invert(&mut self) {
+        self.dots.iter_mut().for_each(|dot| *dot = !*dot);
+    }
+
+    fn scale(&self, scale_x: u32, scale_y: u32) -> Self {
+        let mut scaled = Self::new(self.width * scale_x, self.height * scale_y);
+        for y in 0..scaled.height {
+            for x in 0..scaled.width {
+                if self.get(x / scale_x, y / scale_y) {
+                    scaled.set(x, y);
+                }
+            }
+        }
+        scaled
+    }
+
+    fn rotate_90(&self) -> Self {
+        let mut rotated = Self::new(self.height, self.width);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y) {
+                    rotated.set(self.height - 1 - y, x);
+                }
+            }
+        }
+        rotated
+    }
+
+    fn rotate_180(&self) -> Self {
+        let mut dots = self.dots.clone();
+        dots.reverse();
+        Self {
+            dots,
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::barcode::{BarcodeSystem, SetBarcodeWidth, SetHriPosition};
+    use crate::command::character::SetJustification;
+    use crate::command::paper::CutPaper;
+    use crate::command::spacing::SetLineSpacing;
+    use crate::style::text::{Styleable, StyledNode};
+
+    /// Bounding box of printed dots as (left, top, right, bottom), inclusive.
+    fn bounds(image: &PagePreview, rows: std::ops::Range<u32>) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for y in rows.start..rows.end.min(image.height()) {
+            for x in 0..image.width() {
+                if image.is_black(x, y) {
+                    let (l, t, r, b) = bounds.unwrap_or((x, y, x, y));
+                    bounds = Some((l.min(x), t.min(y), r.max(x), b.max(y)));
+                }
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn text_lines_advance_by_line_spacing() {
+        let receipt = render(b"H\nH\n");
+        let image = receipt.image();
+        assert_eq!((image.width(), image.height()), (512, 60));
+        let (left, top, right, bottom) = bounds(image, 0..30).unwrap();
+        assert!(left < 12 && right < 12 && top < 24 && bottom < 24);
+        assert!(bounds(image, 30..60).is_some());
+    }
+
+    #[test]
+    fn unterminated_line_is_not_printed() {
+        assert_eq!(bounds(render(b"Total").image(), 0..u32::MAX), None);
+    }
+
+    #[test]
+    fn justification_and_size_place_text() {
+        let receipt = render(&"M".reversed().double_size().centered().render_line());
+        // A reversed 24x48 cell in the middle of the 512 dot line
+        assert_eq!(bounds(receipt.image(), 0..48), Some((244, 0, 267, 47)));
+        assert_eq!(receipt.image().height(), 48);
+    }
+
+    #[test]
+    fn long_text_wraps_at_print_area() {
+        let receipt = render(&StyledNode::text("x".repeat(43)).render_line());
+        assert_eq!(receipt.image().height(), 60);
+    }
+
+    #[test]
+    fn raster_image_prints_dot_for_dot() {
+        let image = PrintRasterImage::new(1, 2, vec![0x80, 0x01]);
+        let receipt = render(&image.encode());
+        assert!(receipt.image().is_black(0, 0));
+        assert!(receipt.image().is_black(7, 1));
+        assert_eq!(bounds(receipt.image(), 0..2), Some((0, 0, 7, 1)));
+        assert_eq!(receipt.image().height(), 2);
+    }
+
+    #[test]
+    fn barcode_has_bars_and_hri_text() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Jan13, "4901234567894").unwrap();
+        let bytes = [
+            SetHriPosition(HriPosition::Below).encode(),
+            SetBarcodeWidth(BarcodeWidth::Thin).encode(),
+            barcode.encode(),
+        ]
+        .concat();
+        let receipt = render(&bytes);
+        // 95 modules of 2 dots, then a Font A line of digits
+        assert_eq!(receipt.image().height(), u32::from(BARCODE_HEIGHT) + 24);
+        let (left, _, right, _) = bounds(receipt.image(), 0..1).unwrap();
+        assert_eq!((left, right), (0, 189));
+    }
+
+    #[test]
+    fn unrendered_symbology_is_outlined() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "A").unwrap();
+        let receipt = render(&barcode.encode());
+        let module = BarcodeWidth::default() as u32;
+        assert_eq!(bounds(receipt.image(), 0..1), Some((0, 0, 46 * module - 1, 0)));
+    }
+
+    #[test]
+    fn qr_code_is_drawn_centered() {
+        let qr = PrintQrCode::new("https://example.com").unwrap();
+        let bytes = [SetJustification(Justification::Center).encode(), qr.encode()].concat();
+        let receipt = render(&bytes);
+        let raster = qr.to_raster().unwrap();
+        let side = u32::from(raster.height_dots);
+        assert_eq!(receipt.image().height(), side);
+        let left = (512 - u32::from(raster.width_bytes) * 8) / 2;
+        assert_eq!(bounds(receipt.image(), 0..side), Some((left, 0, left + side - 1, side - 1)));
+    }
+
+    #[test]
+    fn cuts_are_recorded_after_feed() {
+        let bytes = [
+            b"Hi\n".to_vec(),
+            SetLineSpacing(60).encode(),
+            CutPaper::feed_and_partial(20).encode(),
+        ]
+        .concat();
+        let receipt = render(&bytes);
+        assert_eq!(receipt.cuts(), [40]);
+        assert!(receipt.image().is_black(0, 40));
+        assert!(!receipt.image().is_black(4, 40));
+    }
+
+    #[test]
+    fn page_mode_pages_are_pasted_into_the_receipt() {
+        let page = crate::page::PageBuilder::new().position(100, 60).text("X").build().unwrap();
+        let receipt = render(&page);
+        let pages = crate::page::preview::render(&page);
+        assert_eq!(receipt.image().height(), pages[0].height());
+        assert_eq!(bounds(receipt.image(), 0..u32::MAX), bounds(&pages[0], 0..u32::MAX));
+    }
+
+    #[test]
+    fn oversized_page_mode_area_is_clamped() {
+        let job = b"\x1bL\x1dP\x01\x01\x1bW\x00\x00\x00\x00\xff\xff\xff\xffHi\x1b\x0c";
+        let receipt = render(job);
+        assert_eq!(receipt.image().width(), u32::from(PrinterProfile::default().paper_width_dots));
+        assert!(bounds(receipt.image(), 0..u32::MAX).is_some());
+    }
+
+    #[test]
+    fn user_defined_characters_replace_codes() {
+        use crate::command::user_defined::{
+            DefineUserDefinedCharacters, SelectUserDefinedCharacters, UserDefinedCharacter,
+        };
+
+        let block = UserDefinedCharacter {
+            width: 2,
+            data: vec![0xFF; 6],
+        };
+        let bytes = [
+            DefineUserDefinedCharacters::new(b'A', vec![block]).unwrap().encode(),
+            SelectUserDefinedCharacters(true).encode(),
+            b"A\n".to_vec(),
+        ]
+        .concat();
+        let receipt = render(&bytes);
+        assert_eq!(bounds(receipt.image(), 0..30), Some((0, 0, 1, 23)));
+    }
+}
@@ -0,0 +1,546 @@
+//! Annotated plain text of printed receipts.
+//!
+//! Prints each receipt line as text, justified within the paper width and
+//! wrapped where the printer would wrap it, with character styles marked by
+//! tags around the text they apply to. Receipt layout tests can then compare
+//! against readable golden files instead of bytes or pixels:
+//!
+//! ```
+//! use bixolon::command::Command;
+//! use bixolon::command::paper::CutPaper;
+//! use bixolon::emulator::text;
+//! use bixolon::style::text::Styleable;
+//!
+//! let mut job = ("Total".bold() + "   $25.00").render_line();
+//! job.extend(CutPaper::partial().encode());
+//! assert_eq!(text::render(&job), "[BOLD]Total[/BOLD]   $25.00\n--PARTIAL CUT--\n");
+//! ```
+//!
+//! The tags are `[FONT B]`, `[SIZE wxh]`, `[BOLD]`, `[UNDERLINE]`,
+//! `[REVERSE]`, `[ROTATED]`, `[UPSIDE-DOWN]`, `[RED]` and `[STRIKE]`, each
+//! closed by `[/NAME]`. Images, barcodes, QR codes and page mode pages print
+//! as bracketed lines such as `[IMAGE 64x32]` or `[QR https://example.com]`,
+//! cuts as `--CUT--` or `--PARTIAL CUT--`, and drawer pulses as
+//! `[DRAWER KICK]`. As on the printer, text without a closing line feed
+//! isn't printed.
+
+use super::{decode_byte, hri_text};
+use crate::command::character::{
+    Font, Justification, PrintColor, RotationMode, UnderlineThickness,
+};
+use crate::command::codepage::CodePage;
+use crate::command::image::{BitImageMode, DownloadedImageMode, PrintRasterImage, RasterImageMode};
+use crate::command::paper::CutMode;
+use crate::command::printer_control::PeripheralDevice;
+use crate::command::spacing::SetMotionUnits;
+use crate::decode::{Decoded, Decoder};
+use crate::profile::PrinterProfile;
+
+/// Render the annotated text of a raw ESC/POS job.
+pub fn render(job: &[u8]) -> String {
+    render_with_profile(job, &PrinterProfile::default())
+}
+
+/// Render the annotated text of a raw ESC/POS job on a specific printer.
+pub fn render_with_profile(job: &[u8], profile: &PrinterProfile) -> String {
+    let mut emulator = Emulator::new(profile.clone());
+    emulator.feed(job);
+    emulator.output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Attributes {
+    font: Font,
+    width: u8,
+    height: u8,
+    bold: bool,
+    underline: bool,
+    reverse: bool,
+    rotated: bool,
+    upside_down: bool,
+    red: bool,
+    struck: bool,
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self {
+            font: Font::A,
+            width: 1,
+            height: 1,
+            bold: false,
+            underline: false,
+            reverse: false,
+            rotated: false,
+            upside_down: false,
+            red: false,
+            struck: false,
+        }
+    }
+}
+
+impl Attributes {
+    /// Opening tags, outermost first.
+    fn tags(&self) -> Vec<String> {
+        let mut tags = Vec::new();
+        if self.font == Font::B {
+            tags.push("FONT B".to_string());
+        }
+        if (self.width, self.height) != (1, 1) {
+            tags.push(format!("SIZE {}x{}", self.width, self.height));
+        }
+        let flags = [
+            (self.bold, "BOLD"),
+            (self.underline, "UNDERLINE"),
+            (self.reverse, "REVERSE"),
+            (self.rotated, "ROTATED"),
+            (self.upside_down, "UPSIDE-DOWN"),
+            (self.red, "RED"),
+            (self.struck, "STRIKE"),
+        ];
+        tags.extend(flags.iter().filter(|(on, _)| *on).map(|(_, tag)| tag.to_string()));
+        tags
+    }
+}
+
+/// Text printer simulation for standard mode jobs.
+///
+/// Feed it the bytes sent to a printer, in as many pieces as they were
+/// written, then read the [`output`](Self::output).
+pub struct Emulator {
+    profile: PrinterProfile,
+    attributes: Attributes,
+    justification: Justification,
+    code_page: Option<CodePage>,
+    units: SetMotionUnits,
+    tabs: Vec<u8>,
+    downloaded: Option<(u32, u32)>,
+    qr: Option<String>,
+    in_page_mode: bool,
+    /// Device receiving data, selected with `ESC =`.
+    peripheral: PeripheralDevice,
+    line: Vec<(Attributes, char)>,
+    /// Width of `line`, in dots.
+    line_width: u32,
+    line_justification: Justification,
+    /// Index into `line` being overprinted after a backwards `ESC \`.
+    overprint: Option<usize>,
+    output: String,
+}
+
+impl Emulator {
+    /// An emulator for `profile`'s paper width and fonts.
+    pub fn new(profile: PrinterProfile) -> Self {
+        Self {
+            profile,
+            attributes: Attributes::default(),
+            justification: Justification::Left,
+            code_page: None,
+            units: SetMotionUnits::default(),
+            tabs: (1..32).map(|n| n * 8).collect(),
+            downloaded: None,
+            qr: None,
+            in_page_mode: false,
+            peripheral: PeripheralDevice::PrinterOnly,
+            line: Vec::new(),
+            line_width: 0,
+            line_justification: Justification::Left,
+            overprint: None,
+            output: String::new(),
+        }
+    }
+
+    /// Process the next part of a job.
+    ///
+    /// Commands are decoded per call, so split jobs at command boundaries.
+    pub fn feed(&mut self, bytes: &[u8]) -> &mut Self {
+        for item in Decoder::new(bytes) {
+            self.execute(item);
+        }
+        self
+    }
+
+    /// The text printed so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    fn execute(&mut self, item: Decoded) {
+        // Data for the customer display passes through without printing
+        if let Decoded::SelectPeripheral(select) = item {
+            self.peripheral = select.0;
+            return;
+        }
+        if self.peripheral == PeripheralDevice::DisplayOnly {
+            return;
+        }
+
+        if self.in_page_mode {
+            match item {
+                Decoded::PrintPageModeData(_) => self.block("[PAGE]".to_string()),
+                Decoded::FormFeed(_) => {
+                    self.block("[PAGE]".to_string());
+                    self.in_page_mode = false;
+                }
+                Decoded::ExitPageMode(_) => self.in_page_mode = false,
+                Decoded::Initialize(_) => {
+                    self.in_page_mode = false;
+                    self.execute(item);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let attributes = &mut self.attributes;
+        match item {
+            Decoded::Text(bytes) => self.text(&bytes),
+            Decoded::LineFeed(_) => self.flush_line(),
+            Decoded::HorizontalTab(_) => {
+                let column = self.cell_width();
+                let next = self
+                    .tabs
+                    .iter()
+                    .map(|&stop| u32::from(stop) * column)
+                    .find(|&stop| stop > self.line_width);
+                if let Some(stop) = next {
+                    self.pad_to(stop);
+                }
+            }
+            Decoded::Initialize(_) => {
+                self.attributes = Attributes::default();
+                self.justification = Justification::Left;
+                self.code_page = None;
+                self.units = SetMotionUnits::default();
+                self.tabs = (1..32).map(|n| n * 8).collect();
+                self.downloaded = None;
+            }
+            Decoded::FeedPaper(_) => {
+                if !self.line.is_empty() {
+                    self.flush_line();
+                }
+            }
+            Decoded::FeedLines(feed) => {
+                for _ in 0..feed.0 {
+                    self.flush_line();
+                }
+            }
+            Decoded::CutPaper(cut) => {
+                let label = match cut.mode {
+                    CutMode::Full | CutMode::FeedAndFull => "--CUT--",
+                    CutMode::Partial | CutMode::FeedAndPartial => "--PARTIAL CUT--",
+                };
+                self.block(label.to_string());
+            }
+            Decoded::GeneratePulse(_) => self.block("[DRAWER KICK]".to_string()),
+            Decoded::SetEmphasized(on) => attributes.bold = on.0,
+            Decoded::SetDoubleStrike(on) => attributes.bold = on.0,
+            Decoded::SetUnderline(thickness) => {
+                attributes.underline = thickness.0 != UnderlineThickness::Off;
+            }
+            Decoded::SelectFont(font) => attributes.font = font.0,
+            Decoded::SetCharacterSize(size) => {
+                attributes.width = size.0.width.multiplier();
+                attributes.height = size.0.height.multiplier();
+            }
+            Decoded::SetJustification(justification) => self.justification = justification.0,
+            Decoded::SetUpsideDown(on) => attributes.upside_down = on.0,
+            Decoded::SetRotation(mode) => {
+                attributes.rotated = mode.0 == RotationMode::Clockwise90;
+            }
+            Decoded::SetReverse(on) => attributes.reverse = on.0,
+            Decoded::SelectPrintColor(color) => attributes.red = color.0 == PrintColor::Red,
+            Decoded::SelectCodePage(page) => self.code_page = Some(page.0),
+            Decoded::SetHorizontalTabs(tabs) => self.tabs = tabs.positions,
+            Decoded::SetMotionUnits(units) => self.units = units,
+            Decoded::SetAbsolutePosition(position) => {
+                self.pad_to(self.dots(position.0));
+            }
+            Decoded::SetRelativePosition(offset) => {
+                let dots = self.dots(offset.0.unsigned_abs());
+                if offset.0 < 0 {
+                    self.move_back(dots);
+                } else {
+                    self.pad_to(self.line_width + dots);
+                }
+            }
+            Decoded::EnterPageMode(_) => {
+                if !self.line.is_empty() {
+                    self.flush_line();
+                }
+                self.in_page_mode = true;
+            }
+            Decoded::SelectBitImageMode(image) => {
+                // Both densities print 24 dots tall, single density columns
+                // two dots wide
+                let scale_x = match image.mode {
+                    BitImageMode::SingleDensity8 | BitImageMode::SingleDensity24 => 2,
+                    BitImageMode::DoubleDensity8 | BitImageMode::DoubleDensity24 => 1,
+                };
+                self.block(format!("[IMAGE {}x24]", u32::from(image.width) * scale_x));
+            }
+            Decoded::PrintRasterImage(image) => self.block(raster_label(&image)),
+            Decoded::DefineDownloadedImage(image) => {
+                let size = (u32::from(image.width_bytes) * 8, u32::from(image.height_bytes) * 8);
+                self.downloaded = Some(size);
+            }
+            Decoded::PrintDownloadedImage(mode) => {
+                if let Some((width, height)) = self.downloaded {
+                    let (width, height) = match mode.0 {
+                        DownloadedImageMode::Normal => (width, height),
+                        DownloadedImageMode::DoubleWidth => (width * 2, height),
+                        DownloadedImageMode::DoubleHeight => (width, height * 2),
+                        DownloadedImageMode::Quadruple => (width * 2, height * 2),
+                    };
+                    self.block(format!("[IMAGE {width}x{height}]"));
+                }
+            }
+            Decoded::PrintNvGraphics(print) => {
+                let key = String::from_utf8_lossy(&print.key.bytes()).into_owned();
+                self.block(format!("[NV IMAGE {key}]"));
+            }
+            Decoded::PrintBarcode(barcode) => {
+                let label = format!("[BARCODE {} {}]", barcode.system().name(), hri_text(&barcode));
+                self.block(label);
+            }
+            Decoded::StoreQrData(store) => {
+                self.qr = Some(String::from_utf8_lossy(store.data()).into_owned());
+            }
+            Decoded::PrintStoredQr(_) => {
+                if let Some(data) = self.qr.clone() {
+                    self.block(format!("[QR {data}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Motion units to dots along the line.
+    fn dots(&self, units: u16) -> u32 {
+        u32::from(units) * 180 / u32::from(self.units.horizontal_per_inch())
+    }
+
+    fn cell_width(&self) -> u32 {
+        u32::from(self.profile.char_width_dots(self.attributes.font))
+            * u32::from(self.attributes.width)
+    }
+
+    fn text(&mut self, bytes: &[u8]) {
+        match (self.code_page, std::str::from_utf8(bytes)) {
+            (None, Ok(text)) => text.chars().for_each(|c| self.push_char(c)),
+            (page, _) => bytes.iter().for_each(|&b| self.push_char(decode_byte(page, b))),
+        }
+    }
+
+    fn move_back(&mut self, dots: u32) {
+        let mut remaining = dots;
+        let mut index = self.overprint.unwrap_or(self.line.len());
+        while remaining > 0 && index > 0 {
+            index -= 1;
+            let attributes = self.line[index].0;
+            let width = u32::from(self.profile.char_width_dots(attributes.font))
+                * u32::from(attributes.width);
+            remaining = remaining.saturating_sub(width);
+        }
+        self.overprint = Some(index);
+    }
+
+    /// Pad the line with spaces up to `target` dots.
+    ///
+    /// Like the printer, ignores positions beyond the paper width, and
+    /// stops rather than wrap onto another line.
+    fn pad_to(&mut self, target: u32) {
+        if target > u32::from(self.profile.paper_width_dots) {
+            return;
+        }
+        while self.line_width < target {
+            let before = (self.line_width, self.overprint);
+            self.push_char(' ');
+            if self.line_width <= before.0 && self.overprint == before.1 {
+                break;
+            }
+        }
+    }
+
+    fn push_char(&mut self, c: char) {
+        if let Some(index) = self.overprint {
+            if index < self.line.len() {
+                let (attributes, existing) = &mut self.line[index];
+                if c == '-' {
+                    attributes.struck = true;
+                } else if c != ' ' {
+                    *existing = c;
+                }
+                self.overprint = Some(index + 1);
+                return;
+            }
+            self.overprint = None;
+        }
+        let width = self.cell_width();
+        if self.line_width > 0 && self.line_width + width > u32::from(self.profile.paper_width_dots)
+        {
+            self.flush_line();
+        }
+        if self.line.is_empty() {
+            self.line_justification = self.justification;
+        }
+        self.line.push((self.attributes, c));
+        self.line_width += width;
+    }
+
+    /// Print a bracketed line for content that isn't text.
+    fn block(&mut self, label: String) {
+        if !self.line.is_empty() {
+            self.flush_line();
+        }
+        self.output.push_str(&label);
+        self.output.push('\n');
+    }
+
+    fn flush_line(&mut self) {
+        self.overprint = None;
+        let slack = u32::from(self.profile.paper_width_dots).saturating_sub(self.line_width);
+        let padding = match self.line_justification {
+            Justification::Left => 0,
+            Justification::Center => slack / 2,
+            Justification::Right => slack,
+        } / u32::from(self.profile.char_width_dots(Font::A));
+        self.output.extend(std::iter::repeat_n(' ', padding as usize));
+
+        let mut open: Vec<String> = Vec::new();
+        for (attributes, c) in self.line.drain(..) {
+            let tags = attributes.tags();
+            let common = open.iter().zip(&tags).take_while(|(a, b)| a == b).count();
+            close_tags(&mut self.output, &open[common..]);
+            for tag in &tags[common..] {
+                self.output.push_str(&format!("[{tag}]"));
+            }
+            open = tags;
+            self.output.push(c);
+        }
+        close_tags(&mut self.output, &open);
+        self.output.push('\n');
+        self.line_width = 0;
+    }
+}
+
+/// Close `tags`, innermost first.
+fn close_tags(output: &mut String, tags: &[String]) {
+    for tag in tags.iter().rev() {
+        let name = tag.split(' ').next().unwrap_or(tag);
+        output.push_str(&format!("[/{name}]"));
+    }
+}
+
+fn raster_label(image: &PrintRasterImage) -> String {
+    let (scale_x, scale_y) = match image.mode {
+        RasterImageMode::Normal => (1, 1),
+        RasterImageMode::DoubleWidth => (2, 1),
+        RasterImageMode::DoubleHeight => (1, 2),
+        RasterImageMode::Quadruple => (2, 2),
+    };
+    let width = u32::from(image.width_bytes) * 8 * scale_x;
+    let height = u32::from(image.height_dots) * scale_y;
+    format!("[IMAGE {width}x{height}]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::command::barcode::{BarcodeSystem, PrintBarcode};
+    use crate::command::display::{DisplayLine, DisplayRow, ToDisplay};
+    use crate::command::paper::CutPaper;
+    use crate::command::symbol::PrintQrCode;
+    use crate::style::text::{Styleable, StyledNode};
+
+    fn line(node: &StyledNode) -> String {
+        render(&node.render_line())
+    }
+
+    #[test]
+    fn plain_text_has_no_tags() {
+        assert_eq!(render(b"Hello\nWorld\n"), "Hello\nWorld\n");
+        assert_eq!(render(b"unterminated"), "");
+    }
+
+    #[test]
+    fn nested_styles_close_innermost_first() {
+        let node = "a".bold() + "b".bold().underlined() + "c".underlined();
+        assert_eq!(line(&node), "[BOLD]a[UNDERLINE]b[/UNDERLINE][/BOLD][UNDERLINE]c[/UNDERLINE]\n");
+    }
+
+    #[test]
+    fn size_and_font_tags() {
+        assert_eq!(line(&"Hi".double_size()), "[SIZE 2x2]Hi[/SIZE]\n");
+        assert_eq!(line(&"b".font(Font::B)), "[FONT B]b[/FONT]\n");
+    }
+
+    #[test]
+    fn justification_pads_in_font_a_columns() {
+        assert_eq!(line(&"Hi".centered()), format!("{}Hi\n", " ".repeat(20)));
+        assert_eq!(
+            line(&"Hi".double_size().right_aligned()),
+            format!("{}[SIZE 2x2]Hi[/SIZE]\n", " ".repeat(38))
+        );
+    }
+
+    #[test]
+    fn long_lines_wrap_at_paper_width() {
+        let text = "x".repeat(50);
+        assert_eq!(
+            line(&StyledNode::text(text)),
+            format!("{}\n{}\n", "x".repeat(42), "x".repeat(8))
+        );
+    }
+
+    #[test]
+    fn positions_past_the_paper_width_are_ignored() {
+        assert_eq!(render(b"\x1b$\xf2\x56"), "");
+        assert_eq!(render(b"\x1b$\xf2\x56A\n"), "A\n");
+        assert_eq!(render(b"\x1b\\\xff\x7fA\n"), "A\n");
+        assert_eq!(render(b"\x1bD\xc8\x00\tA\n"), "A\n");
+
+        // In range, positions still pad
+        assert_eq!(render(b"\x1b$\x24\x00A\n"), "   A\n");
+    }
+
+    #[test]
+    fn display_data_is_not_printed() {
+        let mut job = b"Paid".to_vec();
+        job.extend(ToDisplay(DisplayLine::new(DisplayRow::Upper, "Thank you")).encode());
+        job.push(b'\n');
+        assert_eq!(render(&job), "Paid\n");
+    }
+
+    #[test]
+    fn struck_text_is_tagged() {
+        assert_eq!(line(&("Fries".struck() + " 2.50")), "[STRIKE]Fries[/STRIKE] 2.50\n");
+    }
+
+    #[test]
+    fn blocks_and_cuts() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "ABC").unwrap();
+        let qr = PrintQrCode::new("https://example.com").unwrap();
+        let image =
+            PrintRasterImage::new(8, 32, vec![0; 256]).with_mode(RasterImageMode::Quadruple);
+        let job = [
+            b"Total".to_vec(),
+            barcode.encode(),
+            qr.encode(),
+            image.encode(),
+            CutPaper::feed_and_full(3).encode(),
+        ]
+        .concat();
+        assert_eq!(
+            render(&job),
+            "Total\n[BARCODE CODE39 ABC]\n[QR https://example.com]\n[IMAGE 128x64]\n--CUT--\n"
+        );
+    }
+
+    #[test]
+    fn page_mode_prints_one_line_per_page() {
+        let page = crate::page::PageBuilder::new().text("hidden").build().unwrap();
+        assert_eq!(render(&page), "[PAGE]\n");
+    }
+}
@@ -0,0 +1,319 @@
+//! Unicode text encoding for the printer's code pages.
+//!
+//! The printer interprets text bytes through the code page selected with
+//! `ESC t`. Bytes 0x00-0x7F are ASCII everywhere; the upper half differs per
+//! code page. [`encode`] converts a string for one code page and reports the
+//! first character it can't represent:
+//!
+//! ```
+//! use bixolon::command::codepage::CodePage;
+//! use bixolon::encoding::encode;
+//!
+//! assert_eq!(encode("Café", CodePage::Cp437UsaStandardEurope)?, b"Caf\x82");
+//! assert!(encode("Чай", CodePage::Cp437UsaStandardEurope).is_err());
+//! # Ok::<(), bixolon::error::EncodingError>(())
+//! ```
+//!
+//! The Thai pages, Farsi, old Hebrew and CP928 have no built-in table and only encode
+//! ASCII.
+//!
+//! Characters a code page lacks can also be replaced instead of rejected;
+//! see [`Fallback`]. The [`currency`] module prints currency symbols by
+//! switching code pages around them.
+//!
+//! With the `cjk` feature, the [`cjk`] module encodes Japanese and Chinese
+//! text for the printer's two-byte Kanji mode. With the `glyphs` feature,
+//! the [`glyph`] module prints characters no code page has as downloaded
+//! user-defined characters.
+
+#[cfg(feature = "cjk")]
+pub mod cjk;
+mod compose;
+pub mod currency;
+#[cfg(feature = "glyphs")]
+pub mod glyph;
+mod tables;
+mod translit;
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::command::codepage::CodePage;
+use crate::error::EncodingError;
+
+/// Two-byte character encoding used by a CJK model's Kanji mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CjkEncoding {
+    /// Shift-JIS, for Japanese models.
+    ShiftJis,
+    /// GB18030 two-byte characters (the GB2312/GBK range), for Chinese
+    /// models.
+    Gb18030,
+}
+
+/// Upper-half mapping table for `code_page`, if one is built in.
+pub(crate) const fn table(code_page: CodePage) -> Option<&'static [char; 128]> {
+    match code_page {
+        CodePage::Cp437UsaStandardEurope => Some(&tables::CP437),
+        CodePage::Katakana => Some(&tables::KATAKANA),
+        CodePage::Cp850Multilingual => Some(&tables::CP850),
+        CodePage::Cp860Portuguese => Some(&tables::CP860),
+        CodePage::Cp863CanadianFrench => Some(&tables::CP863),
+        CodePage::Cp865Nordic => Some(&tables::CP865),
+        CodePage::Windows1252LatinI => Some(&tables::WINDOWS_1252),
+        CodePage::Cp866Cyrillic2 => Some(&tables::CP866),
+        CodePage::Cp852Latin2 => Some(&tables::CP852),
+        CodePage::Cp858Euro => Some(&tables::CP858),
+        CodePage::Cp862HebrewDos => Some(&tables::CP862),
+        CodePage::Cp864Arabic => Some(&tables::CP864),
+        CodePage::Windows1253Greek => Some(&tables::WINDOWS_1253),
+        CodePage::Windows1254Turkish => Some(&tables::WINDOWS_1254),
+        CodePage::Windows1257Baltic => Some(&tables::WINDOWS_1257),
+        CodePage::Windows1251Cyrillic => Some(&tables::WINDOWS_1251),
+        CodePage::Cp737Greek => Some(&tables::CP737),
+        CodePage::Cp775Baltic => Some(&tables::CP775),
+        CodePage::Windows1255HebrewNew => Some(&tables::WINDOWS_1255),
+        CodePage::Cp855Cyrillic => Some(&tables::CP855),
+        CodePage::Cp857Turkish => Some(&tables::CP857),
+        CodePage::Windows1256Arabic => Some(&tables::WINDOWS_1256),
+        CodePage::ThaiDefault
+        | CodePage::Thai42
+        | CodePage::Farsi
+        | CodePage::Thai14
+        | CodePage::HebrewOld
+        | CodePage::Thai11
+        | CodePage::Thai18
+        | CodePage::Cp928Greek
+        | CodePage::Thai16 => None,
+    }
+}
+
+/// What to do with characters a code page can't represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Fallback {
+    /// Fail with an [`EncodingError`].
+    #[default]
+    Error,
+    /// Print `?` instead.
+    Replace,
+    /// Print an ASCII lookalike, such as `e` for `é`, `TM` for `™` or
+    /// straight quotes for curly ones, falling back to `?`.
+    Transliterate,
+}
+
+/// ASCII transliteration of `c`, if the built-in table has one.
+///
+/// ```
+/// use bixolon::encoding::transliterate;
+///
+/// assert_eq!(transliterate('ñ'), Some("n"));
+/// assert_eq!(transliterate('“'), Some("\""));
+/// assert_eq!(transliterate('Ж'), None);
+/// ```
+pub fn transliterate(c: char) -> Option<&'static str> {
+    let table = translit::TRANSLITERATIONS;
+    table.binary_search_by_key(&c, |&(from, _)| from).ok().map(|index| table[index].1)
+}
+
+/// `text` with letters followed by combining marks replaced by the
+/// precomposed letters, such as `e` and U+0301 by `é`.
+///
+/// Code pages only hold precomposed letters, so decomposed text would
+/// print its marks as `?`. Every encoding function composes first; this
+/// covers the Latin, Greek and Cyrillic letters of the built-in code pages
+/// and leaves other marks alone.
+///
+/// ```
+/// use bixolon::encoding::compose;
+///
+/// assert_eq!(compose("Cafe\u{301}"), "Café");
+/// ```
+pub fn compose(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| matches!(c, '\u{0300}'..='\u{036f}')) {
+        return Cow::Borrowed(text);
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut pending: Option<char> = None;
+    for c in text.chars() {
+        if let Some(base) = pending {
+            let table = compose::COMPOSITIONS;
+            if let Ok(index) =
+                table.binary_search_by_key(&(base, c), |&(base, mark, _)| (base, mark))
+            {
+                pending = Some(table[index].2);
+                continue;
+            }
+            output.push(base);
+        }
+        pending = Some(c);
+    }
+    output.extend(pending);
+    Cow::Owned(output)
+}
+
+/// Encode `text` in `code_page`.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] pointing at the first character the code page
+/// can't represent.
+pub fn encode(text: &str, code_page: CodePage) -> Result<Vec<u8>, EncodingError> {
+    encode_with(text, code_page, Fallback::Error)
+}
+
+/// Encode `text` in `code_page`, handling unencodable characters with
+/// `fallback`.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] only for [`Fallback::Error`].
+pub fn encode_with(
+    text: &str,
+    code_page: CodePage,
+    fallback: Fallback,
+) -> Result<Vec<u8>, EncodingError> {
+    let text = apply_fallback(text, code_page, fallback)?;
+    Ok(text.chars().map(|c| code_page.encode_char(c).unwrap_or(b'?')).collect())
+}
+
+/// Encode `text` in `code_page`, replacing unmappable characters with `?`.
+pub fn encode_lossy(text: &str, code_page: CodePage) -> Vec<u8> {
+    compose(text).chars().map(|c| code_page.encode_char(c).unwrap_or(b'?')).collect()
+}
+
+/// Rewrite the characters of `text` that `code_page` lacks.
+///
+/// Returns `text` unchanged, apart from [composing](compose) it, when
+/// every character is encodable, so the result can be encoded later, e.g.
+/// by the style renderer.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] only for [`Fallback::Error`].
+pub fn apply_fallback<'a>(
+    text: &'a str,
+    code_page: CodePage,
+    fallback: Fallback,
+) -> Result<Cow<'a, str>, EncodingError> {
+    let text = compose(text);
+    let Some(offset) = text.find(|c| code_page.encode_char(c).is_none()) else {
+        return Ok(text);
+    };
+    if fallback == Fallback::Error {
+        return Err(unencodable(&text, offset, code_page));
+    }
+
+    let mut output = String::with_capacity(text.len());
+    output.push_str(&text[..offset]);
+    for c in text[offset..].chars() {
+        if code_page.encode_char(c).is_some() {
+            output.push(c);
+            continue;
+        }
+        match transliterate(c).filter(|_| fallback == Fallback::Transliterate) {
+            Some(ascii) => output.push_str(ascii),
+            None => output.push('?'),
+        }
+    }
+    Ok(Cow::Owned(output))
+}
+
+/// Error for the character at byte `offset` of `text`.
+fn unencodable(text: &str, offset: usize, code_page: CodePage) -> EncodingError {
+    let c = text[offset..].chars().next().unwrap_or_default();
+    EncodingError {
+        src: text.to_string(),
+        span: (offset, c.len_utf8()).into(),
+        code_page: format!("{code_page:?}"),
+        help: Some(match table(code_page) {
+            Some(_) => format!("select a code page containing {c:?}"),
+            None => format!("{code_page:?} has no built-in table; only ASCII is encoded"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_table_round_trips() {
+        for byte in 0..=255u8 {
+            let Some(code_page) = CodePage::from_byte(byte) else {
+                continue;
+            };
+            let Some(table) = table(code_page) else {
+                continue;
+            };
+            for (index, &c) in table.iter().enumerate() {
+                if c != char::REPLACEMENT_CHARACTER {
+                    // Some tables repeat a character; the first byte wins
+                    let encoded = code_page.encode_char(c).unwrap();
+                    assert_eq!(table[usize::from(encoded - 0x80)], c);
+                    assert!(usize::from(encoded - 0x80) <= index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encodes_per_code_page() {
+        assert_eq!(encode("Ωμέγα", CodePage::Windows1253Greek).unwrap(), b"\xd9\xec\xdd\xe3\xe1");
+        assert_eq!(encode("şık", CodePage::Cp857Turkish).unwrap(), b"\x9f\x8dk");
+        assert_eq!(encode("ｱｲｳ", CodePage::Katakana).unwrap(), b"\xb1\xb2\xb3");
+        assert_eq!(encode("שלום", CodePage::Cp862HebrewDos).unwrap(), b"\x99\x8c\x85\x8d");
+    }
+
+    #[test]
+    fn reports_first_unencodable_character() {
+        let err = encode("ab€c€", CodePage::Cp437UsaStandardEurope).unwrap_err();
+        assert_eq!(err.span, (2, 3).into());
+        assert_eq!(err.code_page, "Cp437UsaStandardEurope");
+
+        let err = encode("ก", CodePage::Thai42).unwrap_err();
+        assert!(err.help.unwrap().contains("no built-in table"));
+    }
+
+    #[test]
+    fn transliteration_table_is_sorted_ascii() {
+        let table = translit::TRANSLITERATIONS;
+        assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(table.iter().all(|(_, ascii)| ascii.is_ascii()));
+    }
+
+    #[test]
+    fn fallback_policies() {
+        let text = "“Ærø™” €5";
+        let page = CodePage::Cp437UsaStandardEurope;
+        assert!(encode_with(text, page, Fallback::Error).is_err());
+        assert_eq!(encode_with(text, page, Fallback::Replace).unwrap(), b"?\x92r??? ?5");
+        assert_eq!(encode_with(text, page, Fallback::Transliterate).unwrap(), b"\"\x92roTM\" EUR5");
+        // Characters the page has are kept, even with a transliteration
+        assert_eq!(apply_fallback("é", page, Fallback::Transliterate).unwrap(), "é");
+        assert_eq!(apply_fallback("Жé", page, Fallback::Transliterate).unwrap(), "?é");
+    }
+
+    #[test]
+    fn composition_table_is_sorted() {
+        let table = compose::COMPOSITIONS;
+        assert!(table.windows(2).all(|pair| (pair[0].0, pair[0].1) < (pair[1].0, pair[1].1)));
+    }
+
+    #[test]
+    fn decomposed_text_is_composed() {
+        let page = CodePage::Cp437UsaStandardEurope;
+        assert_eq!(encode("Cafe\u{301}", page).unwrap(), b"Caf\x82");
+        assert_eq!(encode_lossy("A\u{30a}ngstro\u{308}m", page), b"\x8fngstr\x94m");
+        assert_eq!(compose("x\u{301}"), "x\u{301}");
+        assert!(matches!(compose("Café"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn lossy_replaces_unmappable() {
+        assert_eq!(encode_lossy("€1", CodePage::Cp850Multilingual), b"?1");
+        assert_eq!(encode_lossy("€1", CodePage::Cp858Euro), b"\xd51");
+    }
+}
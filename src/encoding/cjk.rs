@@ -0,0 +1,125 @@
+//! Japanese and Chinese text in Kanji mode.
+//!
+//! CJK models print two-byte characters only while Kanji mode (`FS &`) is
+//! on, and single-byte characters only while it is off. [`encode_mixed`]
+//! splits text into runs of each kind and switches modes between them.
+//!
+//! ```
+//! use bixolon::command::codepage::CodePage;
+//! use bixolon::encoding::CjkEncoding;
+//! use bixolon::encoding::cjk::encode_mixed;
+//!
+//! let bytes = encode_mixed("No.1 寿司", CjkEncoding::ShiftJis, CodePage::default())?;
+//! assert_eq!(bytes, b"\x1cC\x01No.1 \x1c&\x8e\xf5\x8e\x69\x1c.");
+//! # Ok::<(), bixolon::error::EncodingError>(())
+//! ```
+
+use encoding_rs::{Encoding, GB18030, SHIFT_JIS};
+
+use super::CjkEncoding;
+use crate::command::Command;
+use crate::command::codepage::CodePage;
+use crate::command::kanji::{
+    CancelKanjiMode, KanjiCodeSystem, SelectKanjiCodeSystem, SelectKanjiMode,
+};
+use crate::error::EncodingError;
+
+impl CjkEncoding {
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            CjkEncoding::ShiftJis => SHIFT_JIS,
+            CjkEncoding::Gb18030 => GB18030,
+        }
+    }
+
+    /// Two-byte code for `c`, if it has one.
+    pub fn encode_char(self, c: char) -> Option<[u8; 2]> {
+        if c.is_ascii() {
+            return None;
+        }
+        let mut buf = [0u8; 4];
+        let (bytes, _, unmappable) = self.encoding().encode(c.encode_utf8(&mut buf));
+        match *bytes {
+            [first, second] if !unmappable => Some([first, second]),
+            _ => None,
+        }
+    }
+}
+
+/// Encode `text` for a printer whose Kanji mode uses `encoding`.
+///
+/// Characters with a two-byte code are printed in Kanji mode; everything
+/// else is encoded in `code_page` with Kanji mode off. Shift-JIS output
+/// starts by selecting the Shift-JIS code system (`FS C 1`), and Kanji mode
+/// is always off again at the end.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] for the first character that fits neither
+/// encoding, such as GB18030 characters outside the two-byte range.
+pub fn encode_mixed(
+    text: &str,
+    encoding: CjkEncoding,
+    code_page: CodePage,
+) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = match encoding {
+        CjkEncoding::ShiftJis => SelectKanjiCodeSystem(KanjiCodeSystem::ShiftJis).encode(),
+        CjkEncoding::Gb18030 => Vec::new(),
+    };
+    let mut kanji = false;
+    for (offset, c) in text.char_indices() {
+        if let Some(pair) = encoding.encode_char(c) {
+            if !kanji {
+                bytes.extend(SelectKanjiMode.encode());
+                kanji = true;
+            }
+            bytes.extend(pair);
+        } else if let Some(byte) = code_page.encode_char(c) {
+            if kanji {
+                bytes.extend(CancelKanjiMode.encode());
+                kanji = false;
+            }
+            bytes.push(byte);
+        } else {
+            return Err(EncodingError {
+                src: text.to_string(),
+                span: (offset, c.len_utf8()).into(),
+                code_page: format!("{encoding:?} or {code_page:?}"),
+                help: Some("the printer's Kanji fonts only cover two-byte characters".into()),
+            });
+        }
+    }
+    if kanji {
+        bytes.extend(CancelKanjiMode.encode());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_jis_runs_switch_modes() {
+        let bytes = encode_mixed("日本 OK 円", CjkEncoding::ShiftJis, CodePage::default()).unwrap();
+        assert_eq!(bytes, b"\x1cC\x01\x1c&\x93\xfa\x96\x7b\x1c. OK \x1c&\x89\x7e\x1c.");
+    }
+
+    #[test]
+    fn gb18030_uses_two_byte_codes() {
+        let bytes = encode_mixed("中文a", CjkEncoding::Gb18030, CodePage::default()).unwrap();
+        assert_eq!(bytes, b"\x1c&\xd6\xd0\xce\xc4\x1c.a");
+        // Four-byte GB18030 sequences have no Kanji mode equivalent
+        assert_eq!(CjkEncoding::Gb18030.encode_char('€'), Some([0xa2, 0xe3]));
+        assert_eq!(CjkEncoding::Gb18030.encode_char('\u{1f600}'), None);
+    }
+
+    #[test]
+    fn falls_back_to_code_page_for_single_byte_characters() {
+        let bytes = encode_mixed("½中", CjkEncoding::Gb18030, CodePage::Cp437UsaStandardEurope);
+        assert_eq!(bytes.unwrap(), b"\xab\x1c&\xd6\xd0\x1c.");
+
+        let err = encode_mixed("a\u{1f600}", CjkEncoding::ShiftJis, CodePage::default());
+        assert_eq!(err.unwrap_err().span, (1, 4).into());
+    }
+}
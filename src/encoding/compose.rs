@@ -0,0 +1,335 @@
+//! Canonical compositions of a letter and a combining mark.
+//!
+//! Covers the precomposed Latin, Greek and Cyrillic letters the built-in
+//! code pages can hold, generated from the Unicode Character Database.
+//! Sorted by base character, then mark, for binary search.
+
+pub(super) const COMPOSITIONS: &[(char, char, char)] = &[
+    ('\u{0041}', '\u{0300}', '\u{00c0}'),
+    ('\u{0041}', '\u{0301}', '\u{00c1}'),
+    ('\u{0041}', '\u{0302}', '\u{00c2}'),
+    ('\u{0041}', '\u{0303}', '\u{00c3}'),
+    ('\u{0041}', '\u{0304}', '\u{0100}'),
+    ('\u{0041}', '\u{0306}', '\u{0102}'),
+    ('\u{0041}', '\u{0307}', '\u{0226}'),
+    ('\u{0041}', '\u{0308}', '\u{00c4}'),
+    ('\u{0041}', '\u{030a}', '\u{00c5}'),
+    ('\u{0041}', '\u{030c}', '\u{01cd}'),
+    ('\u{0041}', '\u{030f}', '\u{0200}'),
+    ('\u{0041}', '\u{0311}', '\u{0202}'),
+    ('\u{0041}', '\u{0328}', '\u{0104}'),
+    ('\u{0043}', '\u{0301}', '\u{0106}'),
+    ('\u{0043}', '\u{0302}', '\u{0108}'),
+    ('\u{0043}', '\u{0307}', '\u{010a}'),
+    ('\u{0043}', '\u{030c}', '\u{010c}'),
+    ('\u{0043}', '\u{0327}', '\u{00c7}'),
+    ('\u{0044}', '\u{030c}', '\u{010e}'),
+    ('\u{0045}', '\u{0300}', '\u{00c8}'),
+    ('\u{0045}', '\u{0301}', '\u{00c9}'),
+    ('\u{0045}', '\u{0302}', '\u{00ca}'),
+    ('\u{0045}', '\u{0304}', '\u{0112}'),
+    ('\u{0045}', '\u{0306}', '\u{0114}'),
+    ('\u{0045}', '\u{0307}', '\u{0116}'),
+    ('\u{0045}', '\u{0308}', '\u{00cb}'),
+    ('\u{0045}', '\u{030c}', '\u{011a}'),
+    ('\u{0045}', '\u{030f}', '\u{0204}'),
+    ('\u{0045}', '\u{0311}', '\u{0206}'),
+    ('\u{0045}', '\u{0327}', '\u{0228}'),
+    ('\u{0045}', '\u{0328}', '\u{0118}'),
+    ('\u{0047}', '\u{0301}', '\u{01f4}'),
+    ('\u{0047}', '\u{0302}', '\u{011c}'),
+    ('\u{0047}', '\u{0306}', '\u{011e}'),
+    ('\u{0047}', '\u{0307}', '\u{0120}'),
+    ('\u{0047}', '\u{030c}', '\u{01e6}'),
+    ('\u{0047}', '\u{0327}', '\u{0122}'),
+    ('\u{0048}', '\u{0302}', '\u{0124}'),
+    ('\u{0048}', '\u{030c}', '\u{021e}'),
+    ('\u{0049}', '\u{0300}', '\u{00cc}'),
+    ('\u{0049}', '\u{0301}', '\u{00cd}'),
+    ('\u{0049}', '\u{0302}', '\u{00ce}'),
+    ('\u{0049}', '\u{0303}', '\u{0128}'),
+    ('\u{0049}', '\u{0304}', '\u{012a}'),
+    ('\u{0049}', '\u{0306}', '\u{012c}'),
+    ('\u{0049}', '\u{0307}', '\u{0130}'),
+    ('\u{0049}', '\u{0308}', '\u{00cf}'),
+    ('\u{0049}', '\u{030c}', '\u{01cf}'),
+    ('\u{0049}', '\u{030f}', '\u{0208}'),
+    ('\u{0049}', '\u{0311}', '\u{020a}'),
+    ('\u{0049}', '\u{0328}', '\u{012e}'),
+    ('\u{004a}', '\u{0302}', '\u{0134}'),
+    ('\u{004b}', '\u{030c}', '\u{01e8}'),
+    ('\u{004b}', '\u{0327}', '\u{0136}'),
+    ('\u{004c}', '\u{0301}', '\u{0139}'),
+    ('\u{004c}', '\u{030c}', '\u{013d}'),
+    ('\u{004c}', '\u{0327}', '\u{013b}'),
+    ('\u{004e}', '\u{0300}', '\u{01f8}'),
+    ('\u{004e}', '\u{0301}', '\u{0143}'),
+    ('\u{004e}', '\u{0303}', '\u{00d1}'),
+    ('\u{004e}', '\u{030c}', '\u{0147}'),
+    ('\u{004e}', '\u{0327}', '\u{0145}'),
+    ('\u{004f}', '\u{0300}', '\u{00d2}'),
+    ('\u{004f}', '\u{0301}', '\u{00d3}'),
+    ('\u{004f}', '\u{0302}', '\u{00d4}'),
+    ('\u{004f}', '\u{0303}', '\u{00d5}'),
+    ('\u{004f}', '\u{0304}', '\u{014c}'),
+    ('\u{004f}', '\u{0306}', '\u{014e}'),
+    ('\u{004f}', '\u{0307}', '\u{022e}'),
+    ('\u{004f}', '\u{0308}', '\u{00d6}'),
+    ('\u{004f}', '\u{030b}', '\u{0150}'),
+    ('\u{004f}', '\u{030c}', '\u{01d1}'),
+    ('\u{004f}', '\u{030f}', '\u{020c}'),
+    ('\u{004f}', '\u{0311}', '\u{020e}'),
+    ('\u{004f}', '\u{031b}', '\u{01a0}'),
+    ('\u{004f}', '\u{0328}', '\u{01ea}'),
+    ('\u{0052}', '\u{0301}', '\u{0154}'),
+    ('\u{0052}', '\u{030c}', '\u{0158}'),
+    ('\u{0052}', '\u{030f}', '\u{0210}'),
+    ('\u{0052}', '\u{0311}', '\u{0212}'),
+    ('\u{0052}', '\u{0327}', '\u{0156}'),
+    ('\u{0053}', '\u{0301}', '\u{015a}'),
+    ('\u{0053}', '\u{0302}', '\u{015c}'),
+    ('\u{0053}', '\u{030c}', '\u{0160}'),
+    ('\u{0053}', '\u{0326}', '\u{0218}'),
+    ('\u{0053}', '\u{0327}', '\u{015e}'),
+    ('\u{0054}', '\u{030c}', '\u{0164}'),
+    ('\u{0054}', '\u{0326}', '\u{021a}'),
+    ('\u{0054}', '\u{0327}', '\u{0162}'),
+    ('\u{0055}', '\u{0300}', '\u{00d9}'),
+    ('\u{0055}', '\u{0301}', '\u{00da}'),
+    ('\u{0055}', '\u{0302}', '\u{00db}'),
+    ('\u{0055}', '\u{0303}', '\u{0168}'),
+    ('\u{0055}', '\u{0304}', '\u{016a}'),
+    ('\u{0055}', '\u{0306}', '\u{016c}'),
+    ('\u{0055}', '\u{0308}', '\u{00dc}'),
+    ('\u{0055}', '\u{030a}', '\u{016e}'),
+    ('\u{0055}', '\u{030b}', '\u{0170}'),
+    ('\u{0055}', '\u{030c}', '\u{01d3}'),
+    ('\u{0055}', '\u{030f}', '\u{0214}'),
+    ('\u{0055}', '\u{0311}', '\u{0216}'),
+    ('\u{0055}', '\u{031b}', '\u{01af}'),
+    ('\u{0055}', '\u{0328}', '\u{0172}'),
+    ('\u{0057}', '\u{0302}', '\u{0174}'),
+    ('\u{0059}', '\u{0301}', '\u{00dd}'),
+    ('\u{0059}', '\u{0302}', '\u{0176}'),
+    ('\u{0059}', '\u{0304}', '\u{0232}'),
+    ('\u{0059}', '\u{0308}', '\u{0178}'),
+    ('\u{005a}', '\u{0301}', '\u{0179}'),
+    ('\u{005a}', '\u{0307}', '\u{017b}'),
+    ('\u{005a}', '\u{030c}', '\u{017d}'),
+    ('\u{0061}', '\u{0300}', '\u{00e0}'),
+    ('\u{0061}', '\u{0301}', '\u{00e1}'),
+    ('\u{0061}', '\u{0302}', '\u{00e2}'),
+    ('\u{0061}', '\u{0303}', '\u{00e3}'),
+    ('\u{0061}', '\u{0304}', '\u{0101}'),
+    ('\u{0061}', '\u{0306}', '\u{0103}'),
+    ('\u{0061}', '\u{0307}', '\u{0227}'),
+    ('\u{0061}', '\u{0308}', '\u{00e4}'),
+    ('\u{0061}', '\u{030a}', '\u{00e5}'),
+    ('\u{0061}', '\u{030c}', '\u{01ce}'),
+    ('\u{0061}', '\u{030f}', '\u{0201}'),
+    ('\u{0061}', '\u{0311}', '\u{0203}'),
+    ('\u{0061}', '\u{0328}', '\u{0105}'),
+    ('\u{0063}', '\u{0301}', '\u{0107}'),
+    ('\u{0063}', '\u{0302}', '\u{0109}'),
+    ('\u{0063}', '\u{0307}', '\u{010b}'),
+    ('\u{0063}', '\u{030c}', '\u{010d}'),
+    ('\u{0063}', '\u{0327}', '\u{00e7}'),
+    ('\u{0064}', '\u{030c}', '\u{010f}'),
+    ('\u{0065}', '\u{0300}', '\u{00e8}'),
+    ('\u{0065}', '\u{0301}', '\u{00e9}'),
+    ('\u{0065}', '\u{0302}', '\u{00ea}'),
+    ('\u{0065}', '\u{0304}', '\u{0113}'),
+    ('\u{0065}', '\u{0306}', '\u{0115}'),
+    ('\u{0065}', '\u{0307}', '\u{0117}'),
+    ('\u{0065}', '\u{0308}', '\u{00eb}'),
+    ('\u{0065}', '\u{030c}', '\u{011b}'),
+    ('\u{0065}', '\u{030f}', '\u{0205}'),
+    ('\u{0065}', '\u{0311}', '\u{0207}'),
+    ('\u{0065}', '\u{0327}', '\u{0229}'),
+    ('\u{0065}', '\u{0328}', '\u{0119}'),
+    ('\u{0067}', '\u{0301}', '\u{01f5}'),
+    ('\u{0067}', '\u{0302}', '\u{011d}'),
+    ('\u{0067}', '\u{0306}', '\u{011f}'),
+    ('\u{0067}', '\u{0307}', '\u{0121}'),
+    ('\u{0067}', '\u{030c}', '\u{01e7}'),
+    ('\u{0067}', '\u{0327}', '\u{0123}'),
+    ('\u{0068}', '\u{0302}', '\u{0125}'),
+    ('\u{0068}', '\u{030c}', '\u{021f}'),
+    ('\u{0069}', '\u{0300}', '\u{00ec}'),
+    ('\u{0069}', '\u{0301}', '\u{00ed}'),
+    ('\u{0069}', '\u{0302}', '\u{00ee}'),
+    ('\u{0069}', '\u{0303}', '\u{0129}'),
+    ('\u{0069}', '\u{0304}', '\u{012b}'),
+    ('\u{0069}', '\u{0306}', '\u{012d}'),
+    ('\u{0069}', '\u{0308}', '\u{00ef}'),
+    ('\u{0069}', '\u{030c}', '\u{01d0}'),
+    ('\u{0069}', '\u{030f}', '\u{0209}'),
+    ('\u{0069}', '\u{0311}', '\u{020b}'),
+    ('\u{0069}', '\u{0328}', '\u{012f}'),
+    ('\u{006a}', '\u{0302}', '\u{0135}'),
+    ('\u{006a}', '\u{030c}', '\u{01f0}'),
+    ('\u{006b}', '\u{030c}', '\u{01e9}'),
+    ('\u{006b}', '\u{0327}', '\u{0137}'),
+    ('\u{006c}', '\u{0301}', '\u{013a}'),
+    ('\u{006c}', '\u{030c}', '\u{013e}'),
+    ('\u{006c}', '\u{0327}', '\u{013c}'),
+    ('\u{006e}', '\u{0300}', '\u{01f9}'),
+    ('\u{006e}', '\u{0301}', '\u{0144}'),
+    ('\u{006e}', '\u{0303}', '\u{00f1}'),
+    ('\u{006e}', '\u{030c}', '\u{0148}'),
+    ('\u{006e}', '\u{0327}', '\u{0146}'),
+    ('\u{006f}', '\u{0300}', '\u{00f2}'),
+    ('\u{006f}', '\u{0301}', '\u{00f3}'),
+    ('\u{006f}', '\u{0302}', '\u{00f4}'),
+    ('\u{006f}', '\u{0303}', '\u{00f5}'),
+    ('\u{006f}', '\u{0304}', '\u{014d}'),
+    ('\u{006f}', '\u{0306}', '\u{014f}'),
+    ('\u{006f}', '\u{0307}', '\u{022f}'),
+    ('\u{006f}', '\u{0308}', '\u{00f6}'),
+    ('\u{006f}', '\u{030b}', '\u{0151}'),
+    ('\u{006f}', '\u{030c}', '\u{01d2}'),
+    ('\u{006f}', '\u{030f}', '\u{020d}'),
+    ('\u{006f}', '\u{0311}', '\u{020f}'),
+    ('\u{006f}', '\u{031b}', '\u{01a1}'),
+    ('\u{006f}', '\u{0328}', '\u{01eb}'),
+    ('\u{0072}', '\u{0301}', '\u{0155}'),
+    ('\u{0072}', '\u{030c}', '\u{0159}'),
+    ('\u{0072}', '\u{030f}', '\u{0211}'),
+    ('\u{0072}', '\u{0311}', '\u{0213}'),
+    ('\u{0072}', '\u{0327}', '\u{0157}'),
+    ('\u{0073}', '\u{0301}', '\u{015b}'),
+    ('\u{0073}', '\u{0302}', '\u{015d}'),
+    ('\u{0073}', '\u{030c}', '\u{0161}'),
+    ('\u{0073}', '\u{0326}', '\u{0219}'),
+    ('\u{0073}', '\u{0327}', '\u{015f}'),
+    ('\u{0074}', '\u{030c}', '\u{0165}'),
+    ('\u{0074}', '\u{0326}', '\u{021b}'),
+    ('\u{0074}', '\u{0327}', '\u{0163}'),
+    ('\u{0075}', '\u{0300}', '\u{00f9}'),
+    ('\u{0075}', '\u{0301}', '\u{00fa}'),
+    ('\u{0075}', '\u{0302}', '\u{00fb}'),
+    ('\u{0075}', '\u{0303}', '\u{0169}'),
+    ('\u{0075}', '\u{0304}', '\u{016b}'),
+    ('\u{0075}', '\u{0306}', '\u{016d}'),
+    ('\u{0075}', '\u{0308}', '\u{00fc}'),
+    ('\u{0075}', '\u{030a}', '\u{016f}'),
+    ('\u{0075}', '\u{030b}', '\u{0171}'),
+    ('\u{0075}', '\u{030c}', '\u{01d4}'),
+    ('\u{0075}', '\u{030f}', '\u{0215}'),
+    ('\u{0075}', '\u{0311}', '\u{0217}'),
+    ('\u{0075}', '\u{031b}', '\u{01b0}'),
+    ('\u{0075}', '\u{0328}', '\u{0173}'),
+    ('\u{0077}', '\u{0302}', '\u{0175}'),
+    ('\u{0079}', '\u{0301}', '\u{00fd}'),
+    ('\u{0079}', '\u{0302}', '\u{0177}'),
+    ('\u{0079}', '\u{0304}', '\u{0233}'),
+    ('\u{0079}', '\u{0308}', '\u{00ff}'),
+    ('\u{007a}', '\u{0301}', '\u{017a}'),
+    ('\u{007a}', '\u{0307}', '\u{017c}'),
+    ('\u{007a}', '\u{030c}', '\u{017e}'),
+    ('\u{00a8}', '\u{0301}', '\u{0385}'),
+    ('\u{00c4}', '\u{0304}', '\u{01de}'),
+    ('\u{00c5}', '\u{0301}', '\u{01fa}'),
+    ('\u{00c6}', '\u{0301}', '\u{01fc}'),
+    ('\u{00c6}', '\u{0304}', '\u{01e2}'),
+    ('\u{00d5}', '\u{0304}', '\u{022c}'),
+    ('\u{00d6}', '\u{0304}', '\u{022a}'),
+    ('\u{00d8}', '\u{0301}', '\u{01fe}'),
+    ('\u{00dc}', '\u{0300}', '\u{01db}'),
+    ('\u{00dc}', '\u{0301}', '\u{01d7}'),
+    ('\u{00dc}', '\u{0304}', '\u{01d5}'),
+    ('\u{00dc}', '\u{030c}', '\u{01d9}'),
+    ('\u{00e4}', '\u{0304}', '\u{01df}'),
+    ('\u{00e5}', '\u{0301}', '\u{01fb}'),
+    ('\u{00e6}', '\u{0301}', '\u{01fd}'),
+    ('\u{00e6}', '\u{0304}', '\u{01e3}'),
+    ('\u{00f5}', '\u{0304}', '\u{022d}'),
+    ('\u{00f6}', '\u{0304}', '\u{022b}'),
+    ('\u{00f8}', '\u{0301}', '\u{01ff}'),
+    ('\u{00fc}', '\u{0300}', '\u{01dc}'),
+    ('\u{00fc}', '\u{0301}', '\u{01d8}'),
+    ('\u{00fc}', '\u{0304}', '\u{01d6}'),
+    ('\u{00fc}', '\u{030c}', '\u{01da}'),
+    ('\u{01b7}', '\u{030c}', '\u{01ee}'),
+    ('\u{01ea}', '\u{0304}', '\u{01ec}'),
+    ('\u{01eb}', '\u{0304}', '\u{01ed}'),
+    ('\u{0226}', '\u{0304}', '\u{01e0}'),
+    ('\u{0227}', '\u{0304}', '\u{01e1}'),
+    ('\u{022e}', '\u{0304}', '\u{0230}'),
+    ('\u{022f}', '\u{0304}', '\u{0231}'),
+    ('\u{0292}', '\u{030c}', '\u{01ef}'),
+    ('\u{0391}', '\u{0301}', '\u{0386}'),
+    ('\u{0395}', '\u{0301}', '\u{0388}'),
+    ('\u{0397}', '\u{0301}', '\u{0389}'),
+    ('\u{0399}', '\u{0301}', '\u{038a}'),
+    ('\u{0399}', '\u{0308}', '\u{03aa}'),
+    ('\u{039f}', '\u{0301}', '\u{038c}'),
+    ('\u{03a5}', '\u{0301}', '\u{038e}'),
+    ('\u{03a5}', '\u{0308}', '\u{03ab}'),
+    ('\u{03a9}', '\u{0301}', '\u{038f}'),
+    ('\u{03b1}', '\u{0301}', '\u{03ac}'),
+    ('\u{03b5}', '\u{0301}', '\u{03ad}'),
+    ('\u{03b7}', '\u{0301}', '\u{03ae}'),
+    ('\u{03b9}', '\u{0301}', '\u{03af}'),
+    ('\u{03b9}', '\u{0308}', '\u{03ca}'),
+    ('\u{03bf}', '\u{0301}', '\u{03cc}'),
+    ('\u{03c5}', '\u{0301}', '\u{03cd}'),
+    ('\u{03c5}', '\u{0308}', '\u{03cb}'),
+    ('\u{03c9}', '\u{0301}', '\u{03ce}'),
+    ('\u{03ca}', '\u{0301}', '\u{0390}'),
+    ('\u{03cb}', '\u{0301}', '\u{03b0}'),
+    ('\u{03d2}', '\u{0301}', '\u{03d3}'),
+    ('\u{03d2}', '\u{0308}', '\u{03d4}'),
+    ('\u{0406}', '\u{0308}', '\u{0407}'),
+    ('\u{0410}', '\u{0306}', '\u{04d0}'),
+    ('\u{0410}', '\u{0308}', '\u{04d2}'),
+    ('\u{0413}', '\u{0301}', '\u{0403}'),
+    ('\u{0415}', '\u{0300}', '\u{0400}'),
+    ('\u{0415}', '\u{0306}', '\u{04d6}'),
+    ('\u{0415}', '\u{0308}', '\u{0401}'),
+    ('\u{0416}', '\u{0306}', '\u{04c1}'),
+    ('\u{0416}', '\u{0308}', '\u{04dc}'),
+    ('\u{0417}', '\u{0308}', '\u{04de}'),
+    ('\u{0418}', '\u{0300}', '\u{040d}'),
+    ('\u{0418}', '\u{0304}', '\u{04e2}'),
+    ('\u{0418}', '\u{0306}', '\u{0419}'),
+    ('\u{0418}', '\u{0308}', '\u{04e4}'),
+    ('\u{041a}', '\u{0301}', '\u{040c}'),
+    ('\u{041e}', '\u{0308}', '\u{04e6}'),
+    ('\u{0423}', '\u{0304}', '\u{04ee}'),
+    ('\u{0423}', '\u{0306}', '\u{040e}'),
+    ('\u{0423}', '\u{0308}', '\u{04f0}'),
+    ('\u{0423}', '\u{030b}', '\u{04f2}'),
+    ('\u{0427}', '\u{0308}', '\u{04f4}'),
+    ('\u{042b}', '\u{0308}', '\u{04f8}'),
+    ('\u{042d}', '\u{0308}', '\u{04ec}'),
+    ('\u{0430}', '\u{0306}', '\u{04d1}'),
+    ('\u{0430}', '\u{0308}', '\u{04d3}'),
+    ('\u{0433}', '\u{0301}', '\u{0453}'),
+    ('\u{0435}', '\u{0300}', '\u{0450}'),
+    ('\u{0435}', '\u{0306}', '\u{04d7}'),
+    ('\u{0435}', '\u{0308}', '\u{0451}'),
+    ('\u{0436}', '\u{0306}', '\u{04c2}'),
+    ('\u{0436}', '\u{0308}', '\u{04dd}'),
+    ('\u{0437}', '\u{0308}', '\u{04df}'),
+    ('\u{0438}', '\u{0300}', '\u{045d}'),
+    ('\u{0438}', '\u{0304}', '\u{04e3}'),
+    ('\u{0438}', '\u{0306}', '\u{0439}'),
+    ('\u{0438}', '\u{0308}', '\u{04e5}'),
+    ('\u{043a}', '\u{0301}', '\u{045c}'),
+    ('\u{043e}', '\u{0308}', '\u{04e7}'),
+    ('\u{0443}', '\u{0304}', '\u{04ef}'),
+    ('\u{0443}', '\u{0306}', '\u{045e}'),
+    ('\u{0443}', '\u{0308}', '\u{04f1}'),
+    ('\u{0443}', '\u{030b}', '\u{04f3}'),
+    ('\u{0447}', '\u{0308}', '\u{04f5}'),
+    ('\u{044b}', '\u{0308}', '\u{04f9}'),
+    ('\u{044d}', '\u{0308}', '\u{04ed}'),
+    ('\u{0456}', '\u{0308}', '\u{0457}'),
+    ('\u{0474}', '\u{030f}', '\u{0476}'),
+    ('\u{0475}', '\u{030f}', '\u{0477}'),
+    ('\u{04d8}', '\u{0308}', '\u{04da}'),
+    ('\u{04d9}', '\u{0308}', '\u{04db}'),
+    ('\u{04e8}', '\u{0308}', '\u{04ea}'),
+    ('\u{04e9}', '\u{0308}', '\u{04eb}'),
+];
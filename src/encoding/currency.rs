@@ -0,0 +1,152 @@
+//! Currency symbols missing from the active code page.
+//!
+//! Receipts often need a symbol the selected code page lacks, such as `€`
+//! in CP437. [`encode_with_currency`] prints those symbols by briefly
+//! switching to an international character set or code page that has them,
+//! then switching back:
+//!
+//! ```
+//! use bixolon::command::codepage::{CodePage, InternationalCharacterSet};
+//! use bixolon::encoding::currency::encode_with_currency;
+//!
+//! let bytes = encode_with_currency(
+//!     "€5",
+//!     CodePage::Cp437UsaStandardEurope,
+//!     InternationalCharacterSet::Usa,
+//! )?;
+//! assert_eq!(bytes, b"\x1bt\x13\xd5\x1bt\x005");
+//! # Ok::<(), bixolon::error::EncodingError>(())
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::unencodable;
+use crate::command::Command;
+use crate::command::codepage::{
+    CodePage, InternationalCharacterSet, SelectCharacterSet, SelectCodePage,
+};
+use crate::error::EncodingError;
+
+/// Symbols printed by an international character set in place of an ASCII
+/// byte.
+const CHARSET_SYMBOLS: [(char, InternationalCharacterSet, u8); 4] = [
+    ('£', InternationalCharacterSet::Uk, b'#'),
+    ('¥', InternationalCharacterSet::Japan, b'\\'),
+    ('₩', InternationalCharacterSet::Korea, b'\\'),
+    ('₧', InternationalCharacterSet::SpainI, b'#'),
+];
+
+/// Code pages tried, in order, for symbols no character set provides.
+const CURRENCY_PAGES: [CodePage; 3] =
+    [CodePage::Cp858Euro, CodePage::Windows1252LatinI, CodePage::Cp437UsaStandardEurope];
+
+/// Whether `c` is a currency symbol: `$`, `¢`, `£`, `¤`, `¥`, `ƒ` or one
+/// of the Currency Symbols block (U+20A0-U+20CF).
+pub fn is_currency_symbol(c: char) -> bool {
+    matches!(c, '$' | '¢' | '£' | '¤' | '¥' | 'ƒ' | '\u{20a0}'..='\u{20cf}')
+}
+
+/// Bytes printing the currency symbol `c` and restoring `code_page` and
+/// `charset`.
+///
+/// Returns just the symbol's byte when `code_page` already has it, and
+/// `None` for characters that aren't currency symbols or that no supported
+/// code page or character set contains. An international character set is
+/// preferred, since `ESC R` only swaps a few ASCII positions.
+pub fn currency_bytes(
+    c: char,
+    code_page: CodePage,
+    charset: InternationalCharacterSet,
+) -> Option<Vec<u8>> {
+    if !is_currency_symbol(c) {
+        return None;
+    }
+    if let Some(byte) = code_page.encode_char(c) {
+        return Some(vec![byte]);
+    }
+    if let Some(&(_, symbol_charset, byte)) =
+        CHARSET_SYMBOLS.iter().find(|&&(symbol, ..)| symbol == c)
+    {
+        let mut bytes = SelectCharacterSet(symbol_charset).encode();
+        bytes.push(byte);
+        bytes.extend(SelectCharacterSet(charset).encode());
+        return Some(bytes);
+    }
+    CURRENCY_PAGES.iter().find_map(|&page| {
+        let byte = page.encode_char(c)?;
+        let mut bytes = SelectCodePage(page).encode();
+        bytes.push(byte);
+        bytes.extend(SelectCodePage(code_page).encode());
+        Some(bytes)
+    })
+}
+
+/// Encode `text` in `code_page`, switching pages for currency symbols it
+/// lacks.
+///
+/// Currency symbols `code_page` can't represent are printed with
+/// [`currency_bytes`]; the printer is left in `code_page` and `charset`
+/// afterwards.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] for any other character the code page lacks,
+/// or a currency symbol no code page has.
+pub fn encode_with_currency(
+    text: &str,
+    code_page: CodePage,
+    charset: InternationalCharacterSet,
+) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        match code_page.encode_char(c) {
+            Some(byte) => bytes.push(byte),
+            None => match currency_bytes(c, code_page, charset) {
+                Some(switched) => bytes.extend(switched),
+                None => return Err(unencodable(text, offset, code_page)),
+            },
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CP437: CodePage = CodePage::Cp437UsaStandardEurope;
+    const USA: InternationalCharacterSet = InternationalCharacterSet::Usa;
+
+    #[test]
+    fn symbols_in_the_code_page_need_no_switch() {
+        assert_eq!(currency_bytes('£', CP437, USA), Some(vec![0x9C]));
+        assert_eq!(currency_bytes('€', CodePage::Windows1252LatinI, USA), Some(vec![0x80]));
+    }
+
+    #[test]
+    fn pound_uses_uk_charset_and_restores() {
+        let cyrillic = CodePage::Windows1251Cyrillic;
+        let bytes = currency_bytes('£', cyrillic, InternationalCharacterSet::Germany).unwrap();
+        assert_eq!(bytes, b"\x1bR\x03#\x1bR\x02");
+    }
+
+    #[test]
+    fn euro_switches_to_cp858_and_back() {
+        let bytes = currency_bytes('€', CodePage::Cp866Cyrillic2, USA).unwrap();
+        assert_eq!(bytes, b"\x1bt\x13\xd5\x1bt\x11");
+        assert_eq!(currency_bytes('₿', CP437, USA), None);
+        assert_eq!(currency_bytes('$', CP437, USA), Some(vec![b'$']));
+        assert_eq!(currency_bytes('é', CodePage::Cp866Cyrillic2, USA), None);
+    }
+
+    #[test]
+    fn text_with_currency_symbols() {
+        let bytes = encode_with_currency("£3 = €3.50", CP437, USA).unwrap();
+        assert_eq!(bytes, b"\x9c3 = \x1bt\x13\xd5\x1bt\x003.50");
+
+        let err = encode_with_currency("€ Ж", CP437, USA).unwrap_err();
+        assert_eq!(err.span, (4, 2).into());
+        assert_eq!(err.src, "€ Ж");
+    }
+}
@@ -0,0 +1,274 @@
+//! Downloaded glyphs for characters missing from the code page.
+//!
+//! Characters the selected code page lacks can still print as user-defined
+//! characters: [`encode_with_glyphs`] draws each one from a built-in bitmap
+//! font, defines it with `ESC &`, prints it with the user-defined character
+//! set selected, and deletes the definitions again at the end.
+//!
+//! ```
+//! use bixolon::command::character::Font;
+//! use bixolon::command::codepage::CodePage;
+//! use bixolon::encoding::glyph::encode_with_glyphs;
+//!
+//! // Romanian s-comma is in no built-in code page
+//! let bytes = encode_with_glyphs("Brașov", CodePage::Windows1252LatinI, Font::A, 95)?;
+//! assert!(bytes.starts_with(b"\x1b&\x03\x20\x20\x0c"));
+//! assert!(bytes.ends_with(b"\x1b%\x01\x20\x1b%\x00ov\x1b?\x20"));
+//! # Ok::<(), bixolon::error::EncodingError>(())
+//! ```
+//!
+//! The glyphs cover the ISO 8859 Latin, Cyrillic and Greek repertoires and
+//! JIS X 0201 Katakana. The printer holds at most 95 definitions at once,
+//! or fewer on some models; see [`PrinterProfile::user_defined_characters`].
+//!
+//! [`PrinterProfile::user_defined_characters`]: crate::profile::PrinterProfile::user_defined_characters
+
+use std::convert::Infallible;
+
+use embedded_graphics::Drawable;
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::mono_font::{
+    iso_8859_1, iso_8859_2, iso_8859_3, iso_8859_4, iso_8859_5, iso_8859_7, iso_8859_9,
+    iso_8859_10, iso_8859_13, iso_8859_14, iso_8859_15, iso_8859_16, jis_x0201,
+};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Pixel, Point, Size};
+use embedded_graphics::text::{Baseline, Text};
+
+use crate::command::Command;
+use crate::command::character::Font;
+use crate::command::codepage::CodePage;
+use crate::command::user_defined::{
+    CancelUserDefinedCharacter, DefineUserDefinedCharacters, FIRST_CODE, HEIGHT_BYTES, LAST_CODE,
+    SelectUserDefinedCharacters, UserDefinedCharacter,
+};
+use crate::error::EncodingError;
+
+/// Fonts drawn in Font A's 12x24 cell.
+const FONT_A: [&MonoFont<'static>; 13] = [
+    &iso_8859_1::FONT_10X20,
+    &iso_8859_2::FONT_10X20,
+    &iso_8859_3::FONT_10X20,
+    &iso_8859_4::FONT_10X20,
+    &iso_8859_5::FONT_10X20,
+    &iso_8859_7::FONT_10X20,
+    &iso_8859_9::FONT_10X20,
+    &iso_8859_10::FONT_10X20,
+    &iso_8859_13::FONT_10X20,
+    &iso_8859_14::FONT_10X20,
+    &iso_8859_15::FONT_10X20,
+    &iso_8859_16::FONT_10X20,
+    &jis_x0201::FONT_10X20,
+];
+
+/// Fonts drawn in Font B's 9x17 cell.
+const FONT_B: [&MonoFont<'static>; 13] = [
+    &iso_8859_1::FONT_9X15,
+    &iso_8859_2::FONT_9X15,
+    &iso_8859_3::FONT_9X15,
+    &iso_8859_4::FONT_9X15,
+    &iso_8859_5::FONT_9X15,
+    &iso_8859_7::FONT_9X15,
+    &iso_8859_9::FONT_9X15,
+    &iso_8859_10::FONT_9X15,
+    &iso_8859_13::FONT_9X15,
+    &iso_8859_14::FONT_9X15,
+    &iso_8859_15::FONT_9X15,
+    &iso_8859_16::FONT_9X15,
+    &jis_x0201::FONT_9X15,
+];
+
+/// User-defined character drawing `c` in `font`'s cell, if a built-in
+/// bitmap font has it.
+pub fn glyph(c: char, font: Font) -> Option<UserDefinedCharacter> {
+    let (fonts, cell) = match font {
+        Font::A => (&FONT_A, Size::new(12, 24)),
+        Font::B => (&FONT_B, Size::new(9, 17)),
+    };
+    // Unknown characters map to the font's replacement glyph
+    let source = fonts
+        .iter()
+        .find(|source| source.glyph_mapping.index(c) != source.glyph_mapping.index('\u{ffff}'))?;
+
+    let mut canvas = Canvas {
+        width: cell.width,
+        data: vec![0; cell.width as usize * usize::from(HEIGHT_BYTES)],
+    };
+    let offset = (cell - source.character_size) / 2;
+    let style = MonoTextStyle::new(source, BinaryColor::On);
+    let mut buf = [0u8; 4];
+    let position = Point::new(offset.width as i32, offset.height as i32);
+    let Ok(_) = Text::with_baseline(c.encode_utf8(&mut buf), position, style, Baseline::Top)
+        .draw(&mut canvas);
+    Some(UserDefinedCharacter {
+        width: cell.width as u8,
+        data: canvas.data,
+    })
+}
+
+/// Encode `text` in `code_page`, downloading glyphs for characters it lacks.
+///
+/// Each distinct missing character takes one of `slots` user-defined
+/// character codes, drawn for `font`. The output defines the glyphs, selects
+/// the user-defined character set only around them, and deletes the
+/// definitions at the end, leaving the printer as it was.
+///
+/// # Errors
+///
+/// Returns [`EncodingError`] for the first character no built-in glyph
+/// covers, or the first new character once all `slots` are used.
+pub fn encode_with_glyphs(
+    text: &str,
+    code_page: CodePage,
+    font: Font,
+    slots: u8,
+) -> Result<Vec<u8>, EncodingError> {
+    let slots = usize::from(slots).min(usize::from(LAST_CODE - FIRST_CODE) + 1);
+    let mut defined: Vec<char> = Vec::new();
+    let mut characters = Vec::new();
+    let mut body = Vec::with_capacity(text.len());
+    let mut selected = false;
+    for (offset, c) in text.char_indices() {
+        if let Some(byte) = code_page.encode_char(c) {
+            if selected {
+                body.extend(SelectUserDefinedCharacters(false).encode());
+                selected = false;
+            }
+            body.push(byte);
+            continue;
+        }
+
+        let index = match defined.iter().position(|&d| d == c) {
+            Some(index) => index,
+            None => {
+                let error = |help| EncodingError {
+                    src: text.to_string(),
+                    span: (offset, c.len_utf8()).into(),
+                    code_page: format!("{code_page:?}"),
+                    help: Some(help),
+                };
+                let Some(glyph) = glyph(c, font) else {
+                    return Err(error(format!("no built-in glyph draws {c:?}")));
+                };
+                if defined.len() == slots {
+                    return Err(error(format!(
+                        "the printer holds {slots} user-defined characters at once"
+                    )));
+                }
+                defined.push(c);
+                characters.push(glyph);
+                defined.len() - 1
+            }
+        };
+        if !selected {
+            body.extend(SelectUserDefinedCharacters(true).encode());
+            selected = true;
+        }
+        body.push(FIRST_CODE + index as u8);
+    }
+    if selected {
+        body.extend(SelectUserDefinedCharacters(false).encode());
+    }
+    if characters.is_empty() {
+        return Ok(body);
+    }
+
+    let define = DefineUserDefinedCharacters {
+        first: FIRST_CODE,
+        characters,
+    };
+    let mut bytes = define.encode();
+    bytes.extend(body);
+    for code in FIRST_CODE..=define.last() {
+        bytes.extend(CancelUserDefinedCharacter(code).encode());
+    }
+    Ok(bytes)
+}
+
+/// Glyph columns in `ESC &` layout.
+struct Canvas {
+    width: u32,
+    data: Vec<u8>,
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.width, u32::from(HEIGHT_BYTES) * 8)
+    }
+}
+
+impl DrawTarget for Canvas {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y))
+                && x < self.width
+                && y < u32::from(HEIGHT_BYTES) * 8
+                && color.is_on()
+            {
+                let index = (x * u32::from(HEIGHT_BYTES) + y / 8) as usize;
+                self.data[index] |= 0x80 >> (y % 8);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CP437: CodePage = CodePage::Cp437UsaStandardEurope;
+
+    #[test]
+    fn draws_glyphs_inside_the_cell() {
+        let a = glyph('ș', Font::A).unwrap();
+        assert_eq!((a.width, a.data.len()), (12, 36));
+        // One dot of margin each side, two above and below
+        assert_eq!(a.data[..3], [0; 3]);
+        assert_eq!(a.data[33..], [0; 3]);
+        assert!(a.data.chunks(3).all(|column| column[0] & 0xC0 == 0 && column[2] & 0x03 == 0));
+        assert!(a.data.iter().any(|&byte| byte != 0));
+
+        let b = glyph('Ж', Font::B).unwrap();
+        assert_eq!((b.width, b.data.len()), (9, 27));
+        assert_eq!(glyph('寿', Font::A), None);
+    }
+
+    #[test]
+    fn reuses_codes_and_selects_only_around_glyphs() {
+        let s = glyph('ș', Font::A).unwrap();
+        let t = glyph('ț', Font::A).unwrap();
+        let bytes = encode_with_glyphs("ș1țș", CP437, Font::A, 95).unwrap();
+        let expected = [
+            &b"\x1b&\x03\x20\x21\x0c"[..],
+            &s.data,
+            b"\x0c",
+            &t.data,
+            b"\x1b%\x01\x20\x1b%\x001\x1b%\x01\x21\x20\x1b%\x00",
+            b"\x1b?\x20\x1b?\x21",
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn encodable_text_needs_no_definitions() {
+        assert_eq!(encode_with_glyphs("Café", CP437, Font::A, 95).unwrap(), b"Caf\x82");
+    }
+
+    #[test]
+    fn reports_missing_glyphs_and_full_slots() {
+        let err = encode_with_glyphs("a寿", CP437, Font::A, 95).unwrap_err();
+        assert_eq!(err.span, (1, 3).into());
+
+        let err = encode_with_glyphs("șșț", CP437, Font::A, 1).unwrap_err();
+        assert_eq!(err.span, (4, 2).into());
+        assert!(err.help.unwrap().contains("1 user-defined"));
+    }
+}
@@ -4,18 +4,66 @@
 // but clippy's static analysis doesn't see this usage.
 #![allow(unused_assignments)]
 
-use miette::{Diagnostic, SourceSpan};
+use alloc::string::String;
+
+#[cfg(feature = "std")]
+use miette::Diagnostic;
+#[cfg(feature = "std")]
+pub use miette::SourceSpan;
 use thiserror::Error;
 
 /// Result type alias using PrinterError.
-pub type Result<T> = std::result::Result<T, PrinterError>;
+pub type Result<T> = core::result::Result<T, PrinterError>;
+
+/// Location of the offending part of an input, as a byte offset and length.
+///
+/// Without the `std` feature this stands in for `miette::SourceSpan`, which
+/// it is with `std`.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceSpan {
+    offset: usize,
+    len: usize,
+}
+
+#[cfg(not(feature = "std"))]
+impl SourceSpan {
+    /// Byte offset of the span's start.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Length of the span in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the span covers no bytes.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<(usize, usize)> for SourceSpan {
+    fn from((offset, len): (usize, usize)) -> Self {
+        Self {
+            offset,
+            len,
+        }
+    }
+}
 
 /// Top-level error type for all printer operations.
 #[derive(Debug, Error)]
 pub enum PrinterError {
     /// IO error during communication with the printer.
+    ///
+    /// I/O errors carrying a [`TimeoutError`] convert to
+    /// [`Timeout`](Self::Timeout) instead.
+    #[cfg(feature = "std")]
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[source] std::io::Error),
 
     /// Text encoding error - character not representable in code page.
     #[error("encoding error")]
@@ -29,6 +77,10 @@ pub enum PrinterError {
     #[error("barcode error")]
     Barcode(#[from] BarcodeError),
 
+    /// QR code error.
+    #[error("QR code error: {0}")]
+    QrCode(#[from] QrCodeError),
+
     /// Printer reported an error status.
     #[error("printer status error: {0}")]
     Status(#[from] StatusError),
@@ -45,53 +97,135 @@ pub enum PrinterError {
     #[error("no response received from printer")]
     NoResponse,
 
+    /// The printer didn't answer in time.
+    ///
+    /// Reported by query deadlines, [`Printer::wait_until_ready`] and
+    /// transports whose transfers time out, so callers can tell a printer
+    /// that is off or unplugged from other I/O failures.
+    ///
+    /// [`Printer::wait_until_ready`]: crate::printer::Printer::wait_until_ready
+    #[error("printer not responding: {operation} timed out after {elapsed:?}")]
+    Timeout {
+        /// What was waiting, such as `"query"` or `"USB bulk read"`.
+        operation: &'static str,
+        /// How long it waited.
+        elapsed: core::time::Duration,
+    },
+
     /// Failed to parse status response.
     #[error("failed to parse status: {0}")]
     StatusParse(#[source] StatusParseError),
+
+    /// No retained job to reprint.
+    #[error("no print job to reprint")]
+    NoJobToReprint,
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for PrinterError {
+    fn from(error: std::io::Error) -> Self {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<TimeoutError>()) {
+            Some(&TimeoutError {
+                operation,
+                elapsed,
+            }) => PrinterError::Timeout {
+                operation,
+                elapsed,
+            },
+            None => PrinterError::Io(error),
+        }
+    }
+}
+
+/// A transfer that timed out, carried inside a [`std::io::Error`].
+///
+/// Transports implement `Read` and `Write`, so they can only fail with an
+/// I/O error. Wrapping this in one, with kind
+/// [`TimedOut`](std::io::ErrorKind::TimedOut), lets the printer report
+/// [`PrinterError::Timeout`] instead of a generic I/O error.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{operation} timed out after {elapsed:?}")]
+pub struct TimeoutError {
+    /// The operation that timed out, such as `"USB bulk write"`.
+    pub operation: &'static str,
+    /// How long it waited.
+    pub elapsed: core::time::Duration,
+}
+
+#[cfg(feature = "std")]
+impl From<TimeoutError> for std::io::Error {
+    fn from(error: TimeoutError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, error)
+    }
 }
 
 /// Encoding error with source span for miette diagnostics.
-#[derive(Debug, Error, Diagnostic)]
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
 #[error("character not representable in {code_page}")]
-#[diagnostic(code(bixolon::encoding))]
+#[cfg_attr(feature = "std", diagnostic(code(bixolon::encoding)))]
 pub struct EncodingError {
     /// The source text being encoded.
-    #[source_code]
+    #[cfg_attr(feature = "std", source_code)]
     pub src: String,
 
     /// Span pointing to the problematic character.
-    #[label("this character cannot be encoded")]
+    #[cfg_attr(feature = "std", label("this character cannot be encoded"))]
     pub span: SourceSpan,
 
     /// The code page being used.
     pub code_page: String,
 
     /// Optional help message.
-    #[help]
+    #[cfg_attr(feature = "std", help)]
     pub help: Option<String>,
 }
 
+/// Right-to-left text needing contextual shaping.
+#[cfg(feature = "bidi")]
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
+#[error("text needs contextual shaping, which the printer doesn't do")]
+#[cfg_attr(
+    feature = "std",
+    diagnostic(
+        code(bixolon::bidi::shaping),
+        help("use presentation forms (U+FB50-U+FEFF) with CP864, or print the text as an image")
+    )
+)]
+pub struct ShapingError {
+    /// The text being checked.
+    #[cfg_attr(feature = "std", source_code)]
+    pub src: String,
+
+    /// Span pointing to the first joining character.
+    #[cfg_attr(feature = "std", label("this character changes shape with its neighbours"))]
+    pub span: SourceSpan,
+}
+
 /// Validation error for command parameters.
-#[derive(Debug, Error, Diagnostic)]
+#[derive(Debug, Clone, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
 pub enum ValidationError {
     /// Line spacing value out of range.
     #[error("line spacing value {0} out of range")]
-    #[diagnostic(code(bixolon::validation::line_spacing))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::line_spacing)))]
     InvalidLineSpacing(u8),
 
     /// Tab position out of range.
     #[error("tab position {0} out of range")]
-    #[diagnostic(code(bixolon::validation::tab))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::tab)))]
     InvalidTabPosition(u8),
 
     /// Invalid code page value.
     #[error("invalid code page value: {0}")]
-    #[diagnostic(code(bixolon::validation::codepage))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::codepage)))]
     InvalidCodePage(u8),
 
     /// Generic parameter out of range.
     #[error("{name} value {value} out of range ({min}-{max})")]
-    #[diagnostic(code(bixolon::validation::range))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::range)))]
     OutOfRange {
         /// The name of the parameter.
         name: &'static str,
@@ -102,14 +236,101 @@ pub enum ValidationError {
         /// The maximum allowed value.
         max: u16,
     },
+
+    /// Feature not supported by the printer profile.
+    #[error("{feature} is not supported by {profile}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::unsupported)))]
+    Unsupported {
+        /// The unsupported feature.
+        feature: &'static str,
+        /// The printer profile name.
+        profile: String,
+    },
+
+    /// Page mode content extends past the bottom of the print area.
+    #[error("page content reaches {needed} dots but the print area is {available} dots deep")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::validation::page_overflow),
+            help("enlarge the print area or move content onto another page")
+        )
+    )]
+    PageOverflow {
+        /// Depth reached by the content, in dots.
+        needed: u32,
+        /// Depth of the print area, in dots.
+        available: u32,
+    },
+
+    /// Image data doesn't match the image's dimensions.
+    #[error("image data is {actual} bytes but its dimensions need {expected}")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::validation::image_data),
+            help(
+                "each row holds width_bytes bytes, 8 dots per byte with the leftmost dot in the high bit"
+            )
+        )
+    )]
+    ImageDataLength {
+        /// Bytes required by the width and height.
+        expected: usize,
+        /// Bytes supplied.
+        actual: usize,
+    },
+
+    /// An image is too large for the command that prints it.
+    #[error("image of {width}x{height} dots is too large to print")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::validation::image_size),
+            help("images are at most 65535 dots wide and tall; scale the image down")
+        )
+    )]
+    ImageTooLarge {
+        /// Width in dots.
+        width: u32,
+        /// Height in dots.
+        height: u32,
+    },
+
+    /// Amounts being added up are in different currencies.
+    #[error("cannot add {found} amounts to {expected} amounts")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::currency)))]
+    MixedCurrencies {
+        /// Currency code of the amounts so far.
+        expected: String,
+        /// Currency code of the amount that differs.
+        found: String,
+    },
+
+    /// A sum of amounts is past the range of `i64` minor units.
+    #[error("sum of amounts out of range")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::amount)))]
+    AmountOverflow,
+
+    /// A template placeholder was not given a value.
+    #[error("no value for template placeholder {{{0}}}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::placeholder)))]
+    MissingPlaceholder(String),
+
+    /// A QR code couldn't be drawn as an image for a printer without
+    /// native QR support.
+    #[error("QR code can't be printed as an image: {0}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::validation::qr_code)))]
+    QrCode(QrCodeError),
 }
 
 /// Barcode-specific errors with source spans.
-#[derive(Debug, Error, Diagnostic)]
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
 pub enum BarcodeError {
     /// Invalid barcode data length.
     #[error("invalid barcode length for {system}: got {actual}, expected {min}-{max}")]
-    #[diagnostic(code(bixolon::barcode::length))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::length)))]
     InvalidLength {
         /// The barcode system name.
         system: &'static str,
@@ -121,26 +342,237 @@ pub enum BarcodeError {
         max: usize,
     },
 
+    /// Encoded data does not fit in a single `GS k` command.
+    #[error("{system} barcode data too long: {actual} bytes (max {max})")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::too_long)))]
+    DataTooLong {
+        /// The barcode system name.
+        system: &'static str,
+        /// The encoded data length.
+        actual: usize,
+        /// The maximum encodable length.
+        max: usize,
+    },
+
     /// ITF barcode requires even number of digits.
     #[error("ITF barcode requires even number of digits, got {0}")]
-    #[diagnostic(code(bixolon::barcode::itf_length))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::itf_length)))]
     ItfRequiresEvenLength(usize),
 
     /// Invalid character in barcode data.
     #[error("invalid character in {system} barcode")]
-    #[diagnostic(code(bixolon::barcode::character))]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::character)))]
     InvalidCharacter {
         /// The barcode data.
-        #[source_code]
+        #[cfg_attr(feature = "std", source_code)]
         data: String,
 
         /// Span pointing to the invalid character.
-        #[label("invalid character")]
+        #[cfg_attr(feature = "std", label("invalid character"))]
         span: SourceSpan,
 
         /// The barcode system.
         system: &'static str,
     },
+
+    /// Check digit does not match the computed value.
+    #[error("check digit mismatch for {system}: expected {expected}, got {actual}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::check_digit)))]
+    CheckDigitMismatch {
+        /// The barcode system name.
+        system: &'static str,
+        /// The computed check digit.
+        expected: u8,
+        /// The check digit found in the data.
+        actual: u8,
+    },
+
+    /// Barcode system has no mod-10 check digit.
+    #[error("{0} barcodes do not use a mod-10 check digit")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::barcode::check_digit_unsupported)))]
+    CheckDigitUnsupported(&'static str),
+
+    /// Barcode system cannot be rendered host-side.
+    #[error("{0} barcodes cannot be rendered as raster images")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::barcode::render_unsupported),
+            help("only CODE128, JAN-13, JAN-8 and UPC-A can be rendered")
+        )
+    )]
+    RenderUnsupported(&'static str),
+
+    /// Barcode is wider than the paper even at the thinnest module width.
+    #[error("{system} barcode is {width} dots wide but the paper is {paper} dots")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(code(bixolon::barcode::too_wide), help("shorten the barcode data"))
+    )]
+    TooWide {
+        /// The barcode system name.
+        system: &'static str,
+        /// Width of the barcode at the thinnest module width, in dots.
+        width: u32,
+        /// Printable width of the paper, in dots.
+        paper: u16,
+    },
+}
+
+/// GS1 Application Identifier errors.
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
+pub enum Gs1Error {
+    /// Element string is not a sequence of `(AI)value` pairs.
+    #[error("malformed GS1 element string")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::gs1::malformed),
+            help("write elements as (AI)value, e.g. (01)09501101530003")
+        )
+    )]
+    Malformed {
+        /// The element string.
+        #[cfg_attr(feature = "std", source_code)]
+        data: String,
+
+        /// Span pointing to the malformed part.
+        #[cfg_attr(feature = "std", label("expected `(AI)value`"))]
+        span: SourceSpan,
+    },
+
+    /// Application Identifier is not recognized.
+    #[error("unknown GS1 application identifier ({0})")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::gs1::unknown_ai)))]
+    UnknownApplicationIdentifier(String),
+
+    /// Value length does not match the AI format.
+    #[error("invalid length for AI ({ai}): got {actual}, expected {min}-{max}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::gs1::length)))]
+    InvalidLength {
+        /// The Application Identifier.
+        ai: String,
+        /// The actual value length.
+        actual: usize,
+        /// The minimum allowed length.
+        min: usize,
+        /// The maximum allowed length.
+        max: usize,
+    },
+
+    /// Value content does not match the AI format.
+    #[error("invalid value {value:?} for AI ({ai}): {reason}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::gs1::value)))]
+    InvalidValue {
+        /// The Application Identifier.
+        ai: String,
+        /// The rejected value.
+        value: String,
+        /// Why the value was rejected.
+        reason: &'static str,
+    },
+
+    /// Check digit does not match the computed value.
+    #[error("check digit mismatch: expected {expected}, got {actual}")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::gs1::check_digit)))]
+    CheckDigitMismatch {
+        /// The computed check digit.
+        expected: u8,
+        /// The check digit found in the data.
+        actual: u8,
+    },
+}
+
+/// HTML fragment conversion errors.
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "std", derive(Diagnostic))]
+pub enum HtmlError {
+    /// Markup that is not a well-formed tag or entity.
+    #[error("malformed HTML")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::html::malformed)))]
+    Malformed {
+        /// The HTML fragment.
+        #[cfg_attr(feature = "std", source_code)]
+        src: String,
+
+        /// Span pointing to the malformed markup.
+        #[cfg_attr(feature = "std", label("not a valid tag or entity"))]
+        span: SourceSpan,
+    },
+
+    /// Tag outside the supported subset.
+    #[error("unsupported HTML tag <{tag}>")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(
+            code(bixolon::html::unsupported_tag),
+            help("supported tags are <b>, <strong>, <u>, <center>, <br> and <font size>")
+        )
+    )]
+    UnsupportedTag {
+        /// The HTML fragment.
+        #[cfg_attr(feature = "std", source_code)]
+        src: String,
+
+        /// Span pointing to the tag.
+        #[cfg_attr(feature = "std", label("unsupported tag"))]
+        span: SourceSpan,
+
+        /// The tag name.
+        tag: String,
+    },
+
+    /// Closing tag that does not match the innermost open tag.
+    #[error("unexpected closing tag </{tag}>")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::html::unexpected_close)))]
+    UnexpectedClosingTag {
+        /// The HTML fragment.
+        #[cfg_attr(feature = "std", source_code)]
+        src: String,
+
+        /// Span pointing to the closing tag.
+        #[cfg_attr(feature = "std", label("no matching open tag"))]
+        span: SourceSpan,
+
+        /// The tag name.
+        tag: String,
+    },
+
+    /// Tag left open at the end of the fragment.
+    #[error("unclosed HTML tag <{tag}>")]
+    #[cfg_attr(feature = "std", diagnostic(code(bixolon::html::unclosed)))]
+    UnclosedTag {
+        /// The HTML fragment.
+        #[cfg_attr(feature = "std", source_code)]
+        src: String,
+
+        /// Span pointing to the opening tag.
+        #[cfg_attr(feature = "std", label("opened here"))]
+        span: SourceSpan,
+
+        /// The tag name.
+        tag: String,
+    },
+
+    /// `<font size>` value outside 1-7 or +/-6.
+    #[error("invalid font size {size:?}")]
+    #[cfg_attr(
+        feature = "std",
+        diagnostic(code(bixolon::html::font_size), help("use 1-7, or a relative size like +1"))
+    )]
+    InvalidFontSize {
+        /// The HTML fragment.
+        #[cfg_attr(feature = "std", source_code)]
+        src: String,
+
+        /// Span pointing to the tag.
+        #[cfg_attr(feature = "std", label("invalid size"))]
+        span: SourceSpan,
+
+        /// The rejected size attribute.
+        size: String,
+    },
 }
 
 /// Printer status errors.
@@ -206,10 +638,18 @@ pub enum StatusParseError {
     /// Invalid status byte.
     #[error("invalid status byte: {0:#04x}")]
     InvalidStatus(u8),
+
+    /// Response ended before all expected fields were read.
+    #[error("truncated or malformed response from printer")]
+    Truncated,
+
+    /// Response is missing its terminator, so more bytes are still to come.
+    #[error("incomplete response from printer")]
+    Incomplete,
 }
 
 /// QR Code errors.
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum QrCodeError {
     /// Data is empty.
     #[error("QR code data cannot be empty")]
@@ -218,6 +658,21 @@ pub enum QrCodeError {
     /// Data exceeds maximum length.
     #[error("QR code data too long: {0} bytes (max 7089)")]
     DataTooLong(usize),
+
+    /// Data exceeds the capacity for the mode and error correction level.
+    #[error("QR code {mode} data too long: {length} (max {max} at this error correction level)")]
+    ExceedsCapacity {
+        /// The QR encoding mode.
+        mode: &'static str,
+        /// The data length in mode units (bytes or Kanji characters).
+        length: usize,
+        /// The maximum length for the error correction level.
+        max: usize,
+    },
+
+    /// Data is not a sequence of Shift-JIS Kanji characters.
+    #[error("invalid Shift-JIS Kanji character at byte offset {0}")]
+    InvalidKanji(usize),
 }
 
 /// PDF417 errors.
@@ -232,6 +687,95 @@ pub enum Pdf417Error {
     InvalidRowCount(u8),
 }
 
+/// DataMatrix errors.
+#[derive(Debug, Error)]
+pub enum DataMatrixError {
+    /// Data is empty.
+    #[error("DataMatrix data cannot be empty")]
+    EmptyData,
+
+    /// Data exceeds maximum length.
+    #[error("DataMatrix data too long: {0} bytes (max 3116)")]
+    DataTooLong(usize),
+
+    /// Invalid module size.
+    #[error("invalid DataMatrix module size {0} (must be 2-16)")]
+    InvalidModuleSize(u8),
+}
+
+/// SVG rasterization errors.
+#[cfg(feature = "svg")]
+#[derive(Debug, Error)]
+pub enum SvgError {
+    /// The document could not be parsed.
+    #[error("invalid SVG: {0}")]
+    Parse(#[from] resvg::usvg::Error),
+
+    /// The document renders to an empty or oversized image.
+    #[error("SVG cannot be rendered at {width}x{height} dots")]
+    Size {
+        /// Rendered width in dots.
+        width: u32,
+        /// Rendered height in dots.
+        height: u32,
+    },
+}
+
+/// JSON receipt errors.
+#[cfg(feature = "json")]
+#[derive(Debug, Error)]
+pub enum ReceiptError {
+    /// The document is not valid receipt JSON.
+    #[error("invalid receipt JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// An image URL could not be loaded.
+    #[error("cannot load image from {url}")]
+    Load {
+        /// The image URL.
+        url: String,
+        /// Why loading failed.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Image data is not valid base64.
+    #[error("invalid base64 image data")]
+    Base64,
+
+    /// Image data could not be decoded.
+    #[error("cannot decode image: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// A section could not be printed.
+    #[error("cannot print receipt: {0}")]
+    Printer(#[from] PrinterError),
+
+    /// An amount is too large to work out, such as a line total past the
+    /// range of `i64` minor units.
+    #[error("receipt amount out of range")]
+    AmountOverflow,
+}
+
+/// Capability profile loading errors.
+#[cfg(any(feature = "json", feature = "toml"))]
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    /// The document is not valid profile JSON.
+    #[cfg(feature = "json")]
+    #[error("invalid profile JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The document is not valid profile TOML.
+    #[cfg(feature = "toml")]
+    #[error("invalid profile TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// The profile's values are inconsistent.
+    #[error("invalid profile: {0}")]
+    Invalid(#[from] ValidationError),
+}
+
 /// USB transport errors.
 #[cfg(feature = "rusb")]
 #[derive(Debug, Error)]
@@ -253,6 +797,44 @@ pub enum UsbError {
     NoReadEndpoint,
 }
 
+/// WebUSB transport errors.
+#[cfg(feature = "web")]
+#[derive(Debug, Error)]
+pub enum WebUsbError {
+    /// The browser has no `navigator.usb`, or the page isn't served securely.
+    #[error("WebUSB is not available in this browser")]
+    Unsupported,
+
+    /// A WebUSB call was rejected, e.g. because the user chose no device.
+    #[error("WebUSB error: {0}")]
+    Js(String),
+
+    /// A transfer completed with a status other than `ok`.
+    #[error("USB transfer failed: {0}")]
+    Transfer(String),
+
+    /// No write endpoint found.
+    #[error("no bulk OUT endpoint found")]
+    NoWriteEndpoint,
+
+    /// No read endpoint found.
+    #[error("no bulk IN endpoint found")]
+    NoReadEndpoint,
+}
+
+#[cfg(feature = "web")]
+impl From<wasm_bindgen::JsValue> for WebUsbError {
+    fn from(value: wasm_bindgen::JsValue) -> Self {
+        use wasm_bindgen::JsCast;
+
+        let message = match value.dyn_ref::<js_sys::Error>() {
+            Some(error) => String::from(error.message()),
+            None => value.as_string().unwrap_or_else(|| alloc::format!("{value:?}")),
+        };
+        WebUsbError::Js(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +846,25 @@ mod tests {
         assert!(err.to_string().contains("IO error"));
     }
 
+    #[test]
+    fn io_timeout_converts_to_timeout() {
+        let elapsed = core::time::Duration::from_secs(5);
+        let io_err = std::io::Error::from(TimeoutError {
+            operation: "USB bulk read",
+            elapsed,
+        });
+        assert_eq!(io_err.kind(), std::io::ErrorKind::TimedOut);
+        let err: PrinterError = io_err.into();
+        assert!(matches!(
+            err,
+            PrinterError::Timeout { operation: "USB bulk read", elapsed: e } if e == elapsed
+        ));
+        assert!(err.to_string().starts_with("printer not responding"));
+
+        let plain = std::io::Error::from(std::io::ErrorKind::TimedOut);
+        assert!(matches!(PrinterError::from(plain), PrinterError::Io(_)));
+    }
+
     #[test]
     fn encoding_error_has_span() {
         let err = EncodingError {
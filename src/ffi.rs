@@ -0,0 +1,486 @@
+//! C ABI for embedding the crate in C, C++ and Python applications.
+//!
+//! Build a shared or static library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`) and include `include/bixolon.h`. Every function returns a
+//! [`BixolonResult`]; on failure [`bixolon_last_error`] describes what went
+//! wrong on the calling thread.
+//!
+//! ```c
+//! BixolonPrinter *printer = bixolon_printer_open("/dev/usb/lp0");
+//! if (printer == NULL) {
+//!     fprintf(stderr, "%s\n", bixolon_last_error());
+//!     return 1;
+//! }
+//! bixolon_print_text(printer, "Grüße aus Köln\n");
+//! bixolon_printer_free(printer);
+//! ```
+//!
+//! Text the code page can't represent is transliterated rather than
+//! rejected. On Unix, status queries give up after the
+//! [default query timeout](crate::printer::DEFAULT_QUERY_TIMEOUT) when the
+//! printer doesn't answer. Panics are caught at the boundary and reported as
+//! [`BixolonResult::Panic`].
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::command::codepage::CodePage;
+use crate::command::status::{StatusResponse, StatusType, TransmitStatus};
+use crate::encoding::Fallback;
+use crate::error::{PrinterError, ReceiptError};
+use crate::printer::Printer;
+use crate::receipt::Receipt;
+use crate::style::text::Styleable;
+
+/// Outcome of an FFI call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BixolonResult {
+    /// The call succeeded.
+    Ok = 0,
+    /// A pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// Reading from or writing to the printer failed.
+    Io = 3,
+    /// The input was rejected, e.g. malformed receipt JSON.
+    Invalid = 4,
+    /// The printer didn't answer a status query.
+    NoResponse = 5,
+    /// The library panicked; the printer handle should be freed.
+    Panic = 6,
+}
+
+/// Printer state reported by [`bixolon_query_status`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BixolonStatus {
+    /// The printer is online.
+    pub online: bool,
+    /// The cash drawer is open.
+    pub drawer_open: bool,
+    /// The cover is open.
+    pub cover_open: bool,
+    /// The paper roll is nearly used up.
+    pub paper_near_end: bool,
+    /// The paper has run out.
+    pub paper_out: bool,
+    /// The auto-cutter failed.
+    pub cutter_error: bool,
+    /// An error that clears once its cause is fixed occurred.
+    pub recoverable_error: bool,
+    /// An error that needs a power cycle occurred.
+    pub unrecoverable_error: bool,
+}
+
+/// A printer connection owned by C code.
+pub struct BixolonPrinter {
+    printer: Printer<File, PolledReader>,
+}
+
+/// How long a read waits for data before reporting a timeout.
+#[cfg(unix)]
+const READ_POLL_MS: libc::c_int = 50;
+
+/// The read side of a printer connection.
+///
+/// A device node or socket blocks until the printer answers, which it may
+/// never do. On Unix each read waits at most [`READ_POLL_MS`] and then
+/// fails with [`io::ErrorKind::TimedOut`], so [`Printer::query`] can check
+/// its deadline.
+struct PolledReader(File);
+
+impl Read for PolledReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+
+            let mut poll = libc::pollfd {
+                fd: self.0.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: a single pollfd borrowed for the duration of the call
+            match unsafe { libc::poll(&mut poll, 1, READ_POLL_MS) } {
+                -1 => return Err(io::Error::last_os_error()),
+                0 => return Err(io::ErrorKind::TimedOut.into()),
+                _ => {}
+            }
+        }
+        self.0.read(buf)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Record `error` for [`bixolon_last_error`] and classify it.
+fn fail(result: BixolonResult, error: impl ToString) -> BixolonResult {
+    set_last_error(error);
+    result
+}
+
+fn printer_error(error: PrinterError) -> BixolonResult {
+    let result = match error {
+        PrinterError::Io(_) => BixolonResult::Io,
+        PrinterError::NoReader
+        | PrinterError::NoResponse
+        | PrinterError::Timeout {
+            ..
+        }
+        | PrinterError::StatusParse(_) => BixolonResult::NoResponse,
+        _ => BixolonResult::Invalid,
+    };
+    fail(result, error)
+}
+
+/// Run `f`, turning a panic into [`BixolonResult::Panic`].
+fn guard(f: impl FnOnce() -> BixolonResult) -> BixolonResult {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| fail(BixolonResult::Panic, "panic inside bixolon"))
+}
+
+/// Borrow a C string argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Result<&'a str, BixolonResult> {
+    if ptr.is_null() {
+        return Err(fail(BixolonResult::NullPointer, "string argument is null"));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract
+    let bytes = unsafe { CStr::from_ptr(ptr) };
+    bytes.to_str().map_err(|err| fail(BixolonResult::InvalidUtf8, err))
+}
+
+/// Borrow the printer behind a handle.
+///
+/// # Safety
+///
+/// `printer` must be null or a live handle from this library.
+unsafe fn printer_arg<'a>(
+    printer: *mut BixolonPrinter,
+) -> Result<&'a mut BixolonPrinter, BixolonResult> {
+    // SAFETY: null or a live, unaliased handle per the caller's contract
+    unsafe { printer.as_mut() }.ok_or_else(|| fail(BixolonResult::NullPointer, "printer is null"))
+}
+
+fn handle(writer: File, reader: File) -> *mut BixolonPrinter {
+    let printer = Printer::with_reader(writer, PolledReader(reader))
+        .with_encoding_fallback(Fallback::Transliterate);
+    Box::into_raw(Box::new(BixolonPrinter {
+        printer,
+    }))
+}
+
+/// Open the printer at a device path, such as `/dev/usb/lp0` or `COM3`.
+///
+/// Returns null on failure.
+///
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_printer_open(path: *const c_char) -> *mut BixolonPrinter {
+    let mut printer = std::ptr::null_mut();
+    guard(|| {
+        // SAFETY: forwarded from the caller
+        let path = match unsafe { str_arg(path) } {
+            Ok(path) => path,
+            Err(result) => return result,
+        };
+        let opened = OpenOptions::new().read(true).write(true).open(path);
+        match opened.and_then(|file| Ok((file.try_clone()?, file))) {
+            Ok((reader, writer)) => {
+                printer = handle(writer, reader);
+                BixolonResult::Ok
+            }
+            Err(err) => fail(BixolonResult::Io, format!("cannot open {path}: {err}")),
+        }
+    });
+    printer
+}
+
+/// Wrap an open file descriptor, such as a serial port or socket.
+///
+/// The printer takes ownership of `fd` and closes it when freed. Returns
+/// null on failure, leaving `fd` open.
+///
+/// # Safety
+///
+/// `fd` must be an open file descriptor that nothing else closes.
+#[cfg(unix)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_printer_from_fd(fd: std::ffi::c_int) -> *mut BixolonPrinter {
+    use std::mem::ManuallyDrop;
+    use std::os::fd::FromRawFd;
+
+    if fd < 0 {
+        set_last_error("invalid file descriptor");
+        return std::ptr::null_mut();
+    }
+    // Not dropped on failure, so the caller still owns `fd`
+    // SAFETY: the caller hands over ownership of an open descriptor
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    match file.try_clone() {
+        Ok(reader) => handle(ManuallyDrop::into_inner(file), reader),
+        Err(err) => {
+            set_last_error(err);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Close a printer and free its handle. Null is ignored.
+///
+/// # Safety
+///
+/// `printer` must be null or a handle from this library that isn't used
+/// afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_printer_free(printer: *mut BixolonPrinter) {
+    if !printer.is_null() {
+        // SAFETY: a handle from Box::into_raw, freed once per the contract
+        drop(unsafe { Box::from_raw(printer) });
+    }
+}
+
+/// Print UTF-8 text and send it to the printer.
+///
+/// The text is encoded in the printer's default code page. Line feeds in
+/// `text` end lines; nothing is appended.
+///
+/// # Safety
+///
+/// `printer` must be null or a live handle, and `text` null or a
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_print_text(
+    printer: *mut BixolonPrinter,
+    text: *const c_char,
+) -> BixolonResult {
+    guard(|| {
+        // SAFETY: forwarded from the caller
+        let (printer, text) = match unsafe { (printer_arg(printer), str_arg(text)) } {
+            (Ok(printer), Ok(text)) => (printer, text),
+            (Err(result), _) | (_, Err(result)) => return result,
+        };
+        let text = text.code_page(CodePage::default());
+        match printer.printer.print(text).and_then(Printer::flush) {
+            Ok(_) => BixolonResult::Ok,
+            Err(err) => printer_error(err),
+        }
+    })
+}
+
+/// Print a receipt in the crate's JSON receipt format.
+///
+/// Images may be `data:` URLs or local file paths.
+///
+/// # Safety
+///
+/// `printer` must be null or a live handle, and `json` null or a
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_print_receipt_json(
+    printer: *mut BixolonPrinter,
+    json: *const c_char,
+) -> BixolonResult {
+    guard(|| {
+        // SAFETY: forwarded from the caller
+        let (printer, json) = match unsafe { (printer_arg(printer), str_arg(json)) } {
+            (Ok(printer), Ok(json)) => (printer, json),
+            (Err(result), _) | (_, Err(result)) => return result,
+        };
+        let load = |url: &str| match url.strip_prefix("file://") {
+            Some(path) => fs::read(path),
+            None if !url.contains("://") => fs::read(url),
+            None => Err(io::Error::new(io::ErrorKind::Unsupported, "only local files are loaded")),
+        };
+        let printed = Receipt::from_json(json)
+            .and_then(|receipt| receipt.print_with_loader(&mut printer.printer, load))
+            .and_then(|()| Ok(printer.printer.flush()?));
+        match printed {
+            Ok(_) => BixolonResult::Ok,
+            Err(ReceiptError::Printer(err)) => printer_error(err),
+            Err(err) => fail(BixolonResult::Invalid, err),
+        }
+    })
+}
+
+/// Query the printer's state into `status`.
+///
+/// Returns [`BixolonResult::NoResponse`] if the printer doesn't answer
+/// within the query timeout.
+///
+/// # Safety
+///
+/// `printer` must be null or a live handle, and `status` null or valid for
+/// writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bixolon_query_status(
+    printer: *mut BixolonPrinter,
+    status: *mut BixolonStatus,
+) -> BixolonResult {
+    guard(|| {
+        // SAFETY: forwarded from the caller
+        let printer = match unsafe { printer_arg(printer) } {
+            Ok(printer) => printer,
+            Err(result) => return result,
+        };
+        if status.is_null() {
+            return fail(BixolonResult::NullPointer, "status is null");
+        }
+        let mut result = BixolonStatus::default();
+        for kind in
+            [StatusType::Printer, StatusType::Offline, StatusType::Error, StatusType::PaperRoll]
+        {
+            match printer.printer.query(TransmitStatus(kind)) {
+                Ok(StatusResponse::Printer(status)) => {
+                    result.online = status.online;
+                    result.drawer_open = status.drawer_open;
+                }
+                Ok(StatusResponse::Offline(status)) => {
+                    result.cover_open = status.cover_open;
+                }
+                Ok(StatusResponse::Error(status)) => {
+                    result.cutter_error = status.cutter_error;
+                    result.recoverable_error = status.recoverable_error;
+                    result.unrecoverable_error = status.unrecoverable_error;
+                }
+                Ok(StatusResponse::PaperRoll(status)) => {
+                    result.paper_near_end = status.paper_near_end;
+                    result.paper_out = status.paper_end;
+                }
+                Err(err) => return printer_error(err),
+            }
+        }
+        // SAFETY: non-null and writable per the caller's contract
+        unsafe { status.write(result) };
+        BixolonResult::Ok
+    })
+}
+
+/// Message describing the last failure on this thread, or null.
+///
+/// The string stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn bixolon_last_error() -> *const c_char {
+    LAST_ERROR
+        .with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn last_error() -> String {
+        // SAFETY: bixolon_last_error returns null or a live C string
+        unsafe { CStr::from_ptr(bixolon_last_error()) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn prints_text_to_device_path() {
+        let path = std::env::temp_dir().join(format!("bixolon-ffi-{}", std::process::id()));
+        fs::write(&path, b"").unwrap();
+
+        let path_arg = c(path.to_str().unwrap());
+        // SAFETY: valid C strings and a handle freed once
+        unsafe {
+            let printer = bixolon_printer_open(path_arg.as_ptr());
+            assert!(!printer.is_null());
+            assert_eq!(bixolon_print_text(printer, c("Grüße\n").as_ptr()), BixolonResult::Ok);
+            bixolon_printer_free(printer);
+        }
+
+        let written = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(written.ends_with(b"Gr\x81\xE1e\n"), "{written:?}");
+    }
+
+    #[test]
+    fn reports_errors_through_last_error() {
+        // SAFETY: null and valid C string arguments
+        unsafe {
+            assert!(bixolon_printer_open(c("/nonexistent/lp0").as_ptr()).is_null());
+            assert!(last_error().starts_with("cannot open /nonexistent/lp0"));
+
+            let result = bixolon_print_text(std::ptr::null_mut(), c("hi").as_ptr());
+            assert_eq!(result, BixolonResult::NullPointer);
+            assert_eq!(last_error(), "printer is null");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn status_query_times_out_without_a_response() {
+        use std::os::fd::IntoRawFd;
+        use std::os::unix::net::UnixStream;
+
+        // Keep the far end open so the read blocks rather than seeing EOF
+        let (ours, _theirs) = UnixStream::pair().unwrap();
+        let mut status = BixolonStatus::default();
+        // SAFETY: an owned descriptor, a writable status and a handle freed once
+        unsafe {
+            let printer = bixolon_printer_from_fd(ours.into_raw_fd());
+            assert!(!printer.is_null());
+            let result = bixolon_query_status(printer, &mut status);
+            assert_eq!(result, BixolonResult::NoResponse);
+            assert!(last_error().contains("timed out"), "{}", last_error());
+            bixolon_printer_free(printer);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn queries_status_over_fd() {
+        use std::io::{Read, Write};
+        use std::os::fd::IntoRawFd;
+        use std::os::unix::net::UnixStream;
+
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        let printer_side = std::thread::spawn(move || {
+            let mut theirs = theirs;
+            // Online, cover closed, no errors, paper near end
+            for response in [0x12, 0x12, 0x12, 0x0C] {
+                let mut query = [0; 3];
+                theirs.read_exact(&mut query).unwrap();
+                theirs.write_all(&[response]).unwrap();
+            }
+        });
+
+        let mut status = BixolonStatus::default();
+        // SAFETY: an owned descriptor, a writable status and a handle freed once
+        unsafe {
+            let printer = bixolon_printer_from_fd(ours.into_raw_fd());
+            assert!(!printer.is_null());
+            assert_eq!(bixolon_query_status(printer, &mut status), BixolonResult::Ok);
+            let json = c(r#"{"sections": [{"type": "Bogus"}]}"#);
+            assert_eq!(bixolon_print_receipt_json(printer, json.as_ptr()), BixolonResult::Invalid);
+            bixolon_printer_free(printer);
+        }
+        printer_side.join().unwrap();
+
+        assert!(status.online);
+        assert!(status.paper_near_end);
+        assert!(!status.paper_out);
+        assert!(!status.cover_open);
+    }
+}
@@ -0,0 +1,199 @@
+//! QR code footers for digital receipts.
+//!
+//! A [`QrFooter`] prints a centered QR code, such as a survey link or
+//! e-receipt URL, with caption lines under it. The module size is picked
+//! from the data length so the symbol, quiet zone included, fits the paper:
+//!
+//! ```
+//! use bixolon::footer::QrFooter;
+//! use bixolon::profile::PrinterProfile;
+//!
+//! let footer = QrFooter::new("https://example.com/r/8f3a2c")?
+//!     .caption("Scan for your e-receipt")
+//!     .caption("Invoice INV-2024-0042");
+//! let bytes = footer.build(&PrinterProfile::SRP_350PLUS);
+//! assert!(bytes.windows(7).any(|w| w == b"Invoice"));
+//! # Ok::<(), bixolon::error::QrCodeError>(())
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::command::Command;
+use crate::command::character::{Justification, SetJustification};
+use crate::command::symbol::{PrintQrCode, QrErrorCorrection, QrModuleSize};
+use crate::error::QrCodeError;
+use crate::profile::PrinterProfile;
+use crate::style::StyleSet;
+use crate::style::text::StyledNode;
+
+/// Modules of blank space scanners need on each side of a QR code.
+const QUIET_ZONE_MODULES: u16 = 4;
+
+/// Module sizes from largest to smallest.
+const MODULE_SIZES: [QrModuleSize; 8] = [
+    QrModuleSize::Size8,
+    QrModuleSize::Size7,
+    QrModuleSize::Size6,
+    QrModuleSize::Size5,
+    QrModuleSize::Size4,
+    QrModuleSize::Size3,
+    QrModuleSize::Size2,
+    QrModuleSize::Size1,
+];
+
+/// Builder for a centered QR code with captions.
+///
+/// The symbol uses error correction level M by default, which survives the
+/// smudges and creases receipts pick up. Its module size is the largest,
+/// up to [`max_module_size`](Self::max_module_size), at which the symbol
+/// and its quiet zone fit the available width: the profile's paper width
+/// unless [`available_width`](Self::available_width) says otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrFooter {
+    qr: PrintQrCode,
+    captions: Vec<StyledNode>,
+    max_module_size: QrModuleSize,
+    available_width: Option<u16>,
+}
+
+impl QrFooter {
+    /// A footer encoding `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::EmptyData`] if `data` is empty, or
+    /// [`QrCodeError::ExceedsCapacity`] if it does not fit in a QR code at
+    /// level M.
+    pub fn new(data: impl Into<Vec<u8>>) -> Result<Self, QrCodeError> {
+        Ok(Self {
+            qr: PrintQrCode::bytes(data, QrErrorCorrection::M)?,
+            captions: Vec::new(),
+            max_module_size: QrModuleSize::Size6,
+            available_width: None,
+        })
+    }
+
+    /// Use error correction `level` instead of M.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QrCodeError::ExceedsCapacity`] if the data does not fit at
+    /// `level`.
+    pub fn error_correction(mut self, level: QrErrorCorrection) -> Result<Self, QrCodeError> {
+        self.qr = self.qr.with_error_correction(level);
+        self.qr.check_byte_capacity()?;
+        Ok(self)
+    }
+
+    /// Add a caption line, printed centered under the symbol.
+    pub fn caption(mut self, line: impl Into<StyledNode>) -> Self {
+        self.captions.push(line.into());
+        self
+    }
+
+    /// Never use modules larger than `size` (the default is 6 dots).
+    pub fn max_module_size(mut self, size: QrModuleSize) -> Self {
+        self.max_module_size = size;
+        self
+    }
+
+    /// Fit the symbol in `dots` instead of the profile's paper width, e.g.
+    /// when a left margin or print area width is set.
+    pub fn available_width(mut self, dots: u16) -> Self {
+        self.available_width = Some(dots);
+        self
+    }
+
+    /// The caption lines.
+    pub fn captions(&self) -> &[StyledNode] {
+        &self.captions
+    }
+
+    /// The module size used on `profile`.
+    ///
+    /// Falls back to 1 dot when even that does not fit.
+    pub fn module_size(&self, profile: &PrinterProfile) -> QrModuleSize {
+        let width = self.available_width.unwrap_or(profile.paper_width_dots);
+        let modules = self.qr.width_modules().unwrap_or(177) + 2 * QUIET_ZONE_MODULES;
+        MODULE_SIZES
+            .into_iter()
+            .filter(|&size| size as u8 <= self.max_module_size as u8)
+            .find(|&size| modules * u16::from(size as u8) <= width)
+            .unwrap_or(QrModuleSize::Size1)
+    }
+
+    /// The QR code to print on `profile`, with its module size chosen.
+    pub fn qr_code(&self, profile: &PrinterProfile) -> PrintQrCode {
+        self.qr.clone().with_module_size(self.module_size(profile))
+    }
+
+    /// Build the footer for `profile`.
+    ///
+    /// The symbol is always sent as `GS ( k`; on models without native QR
+    /// support, print with [`Printer::print_qr_footer`] instead, which
+    /// falls back to a raster image. Justification is reset to left after
+    /// the symbol.
+    ///
+    /// [`Printer::print_qr_footer`]: crate::printer::Printer::print_qr_footer
+    pub fn build(&self, profile: &PrinterProfile) -> Vec<u8> {
+        let mut output = SetJustification(Justification::Center).encode();
+        output.extend(self.qr_code(profile).encode());
+        output.extend(SetJustification(Justification::Left).encode());
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        for line in &self.captions {
+            line.clone().with_style(centered.clone()).render_line_into_for(profile, &mut output);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_data_gets_the_largest_modules() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let footer = QrFooter::new("https://example.com").unwrap();
+        assert_eq!(footer.module_size(&profile), QrModuleSize::Size6);
+        assert_eq!(
+            footer.clone().max_module_size(QrModuleSize::Size8).module_size(&profile),
+            QrModuleSize::Size8
+        );
+    }
+
+    #[test]
+    fn long_data_shrinks_to_fit() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        // Version 10 at level M: 57 modules plus the quiet zone
+        let footer = QrFooter::new(vec![b'x'; 200]).unwrap();
+        let footer = footer.max_module_size(QrModuleSize::Size8);
+        assert_eq!(footer.module_size(&profile), QrModuleSize::Size7);
+        assert_eq!(footer.clone().available_width(300).module_size(&profile), QrModuleSize::Size4);
+        assert_eq!(footer.available_width(40).module_size(&profile), QrModuleSize::Size1);
+    }
+
+    #[test]
+    fn captions_print_centered_below_the_symbol() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let footer = QrFooter::new("INV-42")
+            .unwrap()
+            .error_correction(QrErrorCorrection::H)
+            .unwrap()
+            .caption("Invoice INV-42");
+        let bytes = footer.build(&profile);
+        let qr = footer.qr_code(&profile).encode();
+        let caption = StyledNode::from("Invoice INV-42")
+            .with_style(StyleSet::new().with_justification(Justification::Center))
+            .render_line_for(&profile);
+        assert_eq!(&bytes[..3], SetJustification(Justification::Center).encode());
+        assert_eq!(&bytes[3..3 + qr.len()], qr);
+        assert!(bytes.ends_with(&caption));
+        assert!(
+            QrFooter::new(vec![b'x'; 1500])
+                .unwrap()
+                .error_correction(QrErrorCorrection::H)
+                .is_err()
+        );
+    }
+}
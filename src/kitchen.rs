@@ -0,0 +1,273 @@
+//! Kitchen order tickets.
+//!
+//! Kitchen printers are read from across a busy pass, so a
+//! [`KitchenTicket`] defaults to what cooks need rather than what customers
+//! expect: a huge order number, items in double-width, double-height text,
+//! bold modifiers, and optionally a buzzer to call attention to the new
+//! order.
+//!
+//! ```
+//! use bixolon::kitchen::{KitchenTicket, TicketItem};
+//! use bixolon::profile::PrinterProfile;
+//!
+//! let ticket = KitchenTicket::new("42")
+//!     .header("Table 7 - Dine in")
+//!     .item(TicketItem::new(2, "Burger").modifier("no onions").modifier("well done"))
+//!     .item(TicketItem::new(1, "Fries"))
+//!     .footer("Server: Ana")
+//!     .with_buzzer();
+//! let bytes = ticket.build(&PrinterProfile::SRP_350PLUS);
+//! assert!(bytes.windows(10).any(|w| w == b"2 x Burger"));
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::command::Command;
+use crate::command::character::{Justification, ScaleFactor};
+use crate::command::codepage::CodePage;
+use crate::command::paper::CutPaper;
+use crate::command::printer_control::{GeneratePulse, Initialize};
+use crate::command::rule::{RuleStyle, TextRule};
+use crate::profile::PrinterProfile;
+use crate::style::StyleSet;
+use crate::style::text::StyledNode;
+
+/// One dish on a [`KitchenTicket`], with its modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TicketItem {
+    /// Portions to prepare.
+    pub qty: u32,
+    /// The dish.
+    pub name: String,
+    /// Changes to the dish, such as "no onions".
+    pub modifiers: Vec<String>,
+}
+
+impl TicketItem {
+    /// `qty` portions of `name`.
+    pub fn new(qty: u32, name: impl Into<String>) -> Self {
+        Self {
+            qty,
+            name: name.into(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Add a modifier, printed in bold under the dish.
+    pub fn modifier(mut self, modifier: impl Into<String>) -> Self {
+        self.modifiers.push(modifier.into());
+        self
+    }
+}
+
+/// Builder for a kitchen order ticket.
+///
+/// Prints, top to bottom: the header lines, centered; the order number,
+/// centered in bold at 3x size; a rule; the items; a rule; the footer
+/// lines; then sounds the buzzer, if enabled, and cuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KitchenTicket {
+    order_number: String,
+    header: Vec<StyledNode>,
+    items: Vec<TicketItem>,
+    footer: Vec<StyledNode>,
+    item_style: StyleSet,
+    order_number_scale: ScaleFactor,
+    buzzer: Option<GeneratePulse>,
+    cut: bool,
+    code_page: CodePage,
+}
+
+impl KitchenTicket {
+    /// A ticket for order `order_number`.
+    pub fn new(order_number: impl Into<String>) -> Self {
+        Self {
+            order_number: order_number.into(),
+            header: Vec::new(),
+            items: Vec::new(),
+            footer: Vec::new(),
+            item_style: StyleSet::new()
+                .with_width_scale(ScaleFactor::X2)
+                .with_height_scale(ScaleFactor::X2),
+            order_number_scale: ScaleFactor::X3,
+            buzzer: None,
+            cut: true,
+            code_page: CodePage::default(),
+        }
+    }
+
+    /// Add a line above the order number, such as the table or channel.
+    pub fn header(mut self, line: impl Into<StyledNode>) -> Self {
+        self.header.push(line.into());
+        self
+    }
+
+    /// Add an item.
+    pub fn item(mut self, item: TicketItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Add a line below the items, such as the server or order time.
+    pub fn footer(mut self, line: impl Into<StyledNode>) -> Self {
+        self.footer.push(line.into());
+        self
+    }
+
+    /// Style items with `style` instead of double width and height.
+    ///
+    /// Modifiers use the same style in bold.
+    pub fn item_style(mut self, style: StyleSet) -> Self {
+        self.item_style = style;
+        self
+    }
+
+    /// Print the order number at `scale` instead of 3x.
+    pub fn order_number_scale(mut self, scale: ScaleFactor) -> Self {
+        self.order_number_scale = scale;
+        self
+    }
+
+    /// Sound a buzzer on the drawer kick-out connector after printing.
+    ///
+    /// Kitchen buzzers plug into the drawer port and sound while the pin is
+    /// pulsed. Uses pin 2 for 200ms; see [`with_buzzer_pulse`] for others.
+    ///
+    /// [`with_buzzer_pulse`]: Self::with_buzzer_pulse
+    pub fn with_buzzer(self) -> Self {
+        self.with_buzzer_pulse(GeneratePulse::open_drawer())
+    }
+
+    /// Sound a buzzer with a custom pulse after printing.
+    pub fn with_buzzer_pulse(mut self, pulse: GeneratePulse) -> Self {
+        self.buzzer = Some(pulse);
+        self
+    }
+
+    /// Whether to cut the paper at the end (the default).
+    pub fn cut(mut self, cut: bool) -> Self {
+        self.cut = cut;
+        self
+    }
+
+    /// Encode text in `code_page` instead of CP437.
+    ///
+    /// Characters the code page lacks print as `?`.
+    pub fn code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Build the ticket as a complete print job for `profile`.
+    ///
+    /// The job starts with `ESC @`, and commands whose encoding varies use
+    /// the profile's dialect.
+    pub fn build(&self, profile: &PrinterProfile) -> Vec<u8> {
+        let mut output = Initialize.encode();
+        let encoded = StyleSet::new().with_code_page(self.code_page);
+        let emit = |node: StyledNode, output: &mut Vec<u8>| {
+            node.with_style(encoded.clone()).render_line_into_for(profile, output);
+        };
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        for line in &self.header {
+            emit(line.clone().with_style(centered.clone()), &mut output);
+        }
+
+        let big = centered
+            .with_bold(true)
+            .with_width_scale(self.order_number_scale)
+            .with_height_scale(self.order_number_scale);
+        emit(StyledNode::styled(big, format!("#{}", self.order_number)), &mut output);
+
+        let rule = TextRule::full_width(RuleStyle::Heavy, profile).encode();
+        output.extend(&rule);
+        for item in &self.items {
+            let line = format!("{} x {}", item.qty, item.name);
+            emit(StyledNode::styled(self.item_style.clone(), line), &mut output);
+            let modifier_style = self.item_style.clone().with_bold(true);
+            for modifier in &item.modifiers {
+                let line = format!("  > {modifier}");
+                emit(StyledNode::styled(modifier_style.clone(), line), &mut output);
+            }
+        }
+        output.extend(&rule);
+
+        for line in &self.footer {
+            emit(line.clone(), &mut output);
+        }
+        if let Some(pulse) = &self.buzzer {
+            output.extend(pulse.encode_for(profile.dialect));
+        }
+        if self.cut {
+            output.extend(CutPaper::feed_and_partial(3).encode_for(profile.dialect));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Dialect;
+
+    fn text(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    #[test]
+    fn items_are_large_and_modifiers_bold() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let bytes = KitchenTicket::new("7")
+            .item(TicketItem::new(1, "Soup").modifier("no salt"))
+            .build(&profile);
+
+        let large =
+            StyleSet::new().with_width_scale(ScaleFactor::X2).with_height_scale(ScaleFactor::X2);
+        let item = StyledNode::styled(large.clone(), "1 x Soup").render_line_for(&profile);
+        let modifier =
+            StyledNode::styled(large.with_bold(true), "  > no salt").render_line_for(&profile);
+        let job = text(&bytes);
+        assert!(job.contains("#7"));
+        assert!(job.contains(&format!("{}{}", text(&item), text(&modifier))));
+        assert!(bytes.ends_with(&CutPaper::feed_and_partial(3).encode()));
+    }
+
+    #[test]
+    fn order_number_is_centered_and_scaled() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let bytes = KitchenTicket::new("123").order_number_scale(ScaleFactor::X4).build(&profile);
+        let big = StyleSet::new()
+            .with_justification(Justification::Center)
+            .with_bold(true)
+            .with_width_scale(ScaleFactor::X4)
+            .with_height_scale(ScaleFactor::X4);
+        let expected = StyledNode::styled(big, "#123").render_line_for(&profile);
+        assert!(bytes.windows(expected.len()).any(|w| w == expected));
+    }
+
+    #[test]
+    fn text_is_encoded_in_the_code_page() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let bytes =
+            KitchenTicket::new("8").item(TicketItem::new(1, "Crème brûlée")).build(&profile);
+        assert!(bytes.windows(12).any(|w| w == b"Cr\x8ame br\x96l\x82e"));
+
+        let bytes = KitchenTicket::new("9")
+            .footer("Чай")
+            .code_page(CodePage::Windows1251Cyrillic)
+            .build(&profile);
+        assert!(bytes.windows(3).any(|w| w == [0xD7, 0xE0, 0xE9]));
+    }
+
+    #[test]
+    fn buzzer_sounds_before_the_cut() {
+        let profile = PrinterProfile::SRP_350PLUS.with_dialect(Dialect::Star);
+        let bytes = KitchenTicket::new("1").with_buzzer().build(&profile);
+        let pulse = GeneratePulse::open_drawer().encode_for(Dialect::Star);
+        let cut = CutPaper::feed_and_partial(3).encode_for(Dialect::Star);
+        assert!(bytes.ends_with(&[pulse, cut].concat()));
+    }
+}
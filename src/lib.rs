@@ -35,15 +35,44 @@
 //! The library is organized into layers:
 //!
 //! - **Command layer** (`command` module): Raw ESC/POS command structs
+//! - **Encoding** (`encoding` module): Unicode to code page conversion
+//! - **Decoding** (`decode` module): Raw ESC/POS bytes back to command structs
+//! - **Emulation** (`emulator` module): Annotated text and images of printed receipts
 //! - **Style layer** (`style` module): Ergonomic styled text API
+//! - **Money** (`money` module): Currency amounts and locale-aware number formatting
 //! - **Page layer** (`page` module): Page mode command builder
+//! - **Receipts** (`receipt` module): Declarative JSON receipt format (feature-gated)
+//! - **Kitchen tickets** (`kitchen` module): Order tickets for kitchen printers
+//! - **QR footers** (`footer` module): Centered QR codes with captions for digital receipts
+//! - **Reports** (`report` module): End-of-day and shift summary reports
 //! - **Printer layer** (`printer` module): High-level printer interface
-//! - **Transport layer** (`transport` module): USB/serial helpers (feature-gated)
+//! - **Profile** (`profile` module): Per-model capability descriptions
+//! - **FFI** (`ffi` module): C ABI for embedding the crate (feature-gated)
+//! - **Transport layer** (`transport` module): USB, serial and WebUSB helpers (feature-gated)
 //!
 //! # Features
 //!
+//! - `std` (default) - Printer I/O, transports and miette diagnostics. Without
+//!   it the crate is `no_std` + `alloc`: the command, style, encoding, page and
+//!   profile layers still encode bytes for writing over your own UART driver.
+//!   Every other feature except `serde` implies `std`
 //! - `async` - Enable async printer interface using tokio
 //! - `rusb` - Enable USB transport using rusb
+//! - `image` - Convert `image` crate images to raster commands
+//! - `preview` - Render page mode output to PNG previews
+//! - `qrcode` - Render QR codes as raster images on models without native QR support
+//! - `cjk` - Print Japanese and Chinese text in Kanji mode on CJK-capable models
+//! - `bidi` - Reorder Hebrew and Arabic text for printing left to right
+//! - `svg` - Rasterize SVG logos at the printer's resolution (implies `image`)
+//! - `glyphs` - Print characters missing from the code page as downloaded glyphs
+//! - `emulator` - Render print jobs to receipt images for visual snapshot tests (implies `preview` and `qrcode`)
+//! - `serde` - Serialize and deserialize command structs, e.g. to describe jobs in JSON
+//! - `json` - Print receipts described in a declarative JSON format (implies `serde` and `image`)
+//! - `toml` - Load capability profiles from TOML (implies `serde`)
+//! - `cli` - Build the `bixolon` command line tool for discovering, testing and printing (implies `json` and `toml`; add `rusb` for USB printers)
+//! - `tracing` - Emit `tracing` events for bytes written, commands sent, status queries and USB errors
+//! - `ffi` - C ABI for linking from C, C++ and Python (implies `json`; see `include/bixolon.h`)
+//! - `web` - WebUSB transport for browser apps compiled to `wasm32-unknown-unknown`
 //! - `hardware-tests` - Enable tests requiring physical printer
 //!
 //! # Styled Text
@@ -77,7 +106,7 @@
 //!     .text_line("Header".bold())
 //!     .vertical_position(200)
 //!     .text_line("Body text")
-//!     .build();
+//!     .build()?;
 //!
 //! printer.send_raw(&page)?;
 //! ```
@@ -119,17 +148,40 @@
 //! }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
 pub mod command;
+pub mod decode;
+#[cfg(feature = "std")]
+pub mod emulator;
+pub mod encoding;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod footer;
+pub mod kitchen;
+pub mod money;
 pub mod page;
+#[cfg(feature = "std")]
 pub mod printer;
+pub mod profile;
+#[cfg(feature = "json")]
+pub mod receipt;
+pub mod report;
 pub mod style;
 
-#[cfg(feature = "rusb")]
+#[cfg(any(feature = "rusb", feature = "web"))]
 pub mod transport;
 
+// Paths used by exported macros, which can't name `alloc` directly
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{format, vec};
+}
+
 /// Prelude module for convenient imports.
 ///
 /// ```ignore
@@ -143,6 +195,7 @@ pub mod prelude {
 
     #[cfg(feature = "async")]
     pub use crate::printer::AsyncPrinter;
+    #[cfg(feature = "std")]
     pub use crate::printer::Printer;
 }
 
@@ -150,6 +203,7 @@ pub mod prelude {
 pub use command::Command;
 pub use error::{BarcodeError, PrinterError, QrCodeError};
 pub use page::PageBuilder;
+#[cfg(feature = "std")]
 pub use printer::Printer;
 pub use style::StyleSet;
 pub use style::text::StyledNode;
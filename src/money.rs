@@ -0,0 +1,403 @@
+//! Money amounts and locale-aware number formatting.
+//!
+//! [`Money`] holds an amount as an integer count of minor units (cents,
+//! pence, yen), so totals add up exactly. [`Money::format`] renders it with
+//! a [`Locale`]'s decimal and thousands separators and currency symbol
+//! placement:
+//!
+//! ```
+//! use bixolon::money::{Currency, Locale, Money};
+//!
+//! let total = Money::new(123_456, Currency::EUR);
+//! assert_eq!(total.format(&Locale::EN_US), "€1,234.56");
+//! assert_eq!(total.format(&Locale::DE_DE), "1.234,56 €");
+//! assert_eq!((-total).format(&Locale::FR_FR), "-1 234,56 €");
+//! ```
+//!
+//! To line amounts up on the decimal point, pass them to
+//! [`LineBuilder::amount`](crate::style::layout::LineBuilder::amount).
+//! Symbols must exist in the code page the receipt is printed in; `€`, for
+//! instance, needs [`CodePage::Cp858Euro`](crate::command::codepage::CodePage::Cp858Euro)
+//! or a transliterating [`Fallback`](crate::encoding::Fallback).
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A currency's symbol and number of minor-unit digits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Currency {
+    /// ISO 4217 code, such as `"USD"`.
+    pub code: Cow<'static, str>,
+    /// Symbol printed with amounts, such as `"$"`.
+    pub symbol: Cow<'static, str>,
+    /// Digits after the decimal point: 2 for cents, 0 for yen.
+    pub decimals: u8,
+}
+
+impl Currency {
+    /// US dollar.
+    pub const USD: Self = Self::new("USD", "$", 2);
+    /// Euro.
+    pub const EUR: Self = Self::new("EUR", "€", 2);
+    /// Pound sterling.
+    pub const GBP: Self = Self::new("GBP", "£", 2);
+    /// Swiss franc.
+    pub const CHF: Self = Self::new("CHF", "CHF", 2);
+    /// Japanese yen.
+    pub const JPY: Self = Self::new("JPY", "¥", 0);
+
+    /// A currency with the given code, symbol and minor-unit digits.
+    pub const fn new(code: &'static str, symbol: &'static str, decimals: u8) -> Self {
+        Self {
+            code: Cow::Borrowed(code),
+            symbol: Cow::Borrowed(symbol),
+            decimals,
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::USD
+    }
+}
+
+/// How a locale writes numbers and currency amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Locale {
+    /// Decimal separator.
+    pub decimal: char,
+    /// Thousands separator, or `None` to write digits ungrouped.
+    pub group: Option<char>,
+    /// Write the currency symbol after the amount instead of before it.
+    pub symbol_after: bool,
+    /// Separate the currency symbol from the amount with a space.
+    pub symbol_space: bool,
+}
+
+impl Locale {
+    /// English (United States): `$1,234.56`.
+    pub const EN_US: Self = Self {
+        decimal: '.',
+        group: Some(','),
+        symbol_after: false,
+        symbol_space: false,
+    };
+    /// German (Germany): `1.234,56 €`.
+    pub const DE_DE: Self = Self {
+        decimal: ',',
+        group: Some('.'),
+        symbol_after: true,
+        symbol_space: true,
+    };
+    /// French (France): `1 234,56 €`.
+    pub const FR_FR: Self = Self {
+        decimal: ',',
+        group: Some(' '),
+        symbol_after: true,
+        symbol_space: true,
+    };
+    /// German (Switzerland): `CHF 1'234.56`.
+    pub const DE_CH: Self = Self {
+        decimal: '.',
+        group: Some('\''),
+        symbol_after: false,
+        symbol_space: true,
+    };
+
+    /// Format an integer with this locale's thousands separator.
+    pub fn format_integer(&self, value: i64) -> String {
+        let mut output = String::new();
+        if value < 0 {
+            output.push('-');
+        }
+        self.push_digits(&mut output, value.unsigned_abs());
+        output
+    }
+
+    fn push_digits(&self, output: &mut String, value: u64) {
+        let digits = value.to_string();
+        for (index, digit) in digits.chars().enumerate() {
+            let remaining = digits.len() - index;
+            if index > 0
+                && remaining.is_multiple_of(3)
+                && let Some(group) = self.group
+            {
+                output.push(group);
+            }
+            output.push(digit);
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::EN_US
+    }
+}
+
+/// An amount of money in a currency's minor units.
+///
+/// Amounts of the same currency can be added and subtracted, and any
+/// amount multiplied by an integer quantity. For amounts from untrusted
+/// input, the `checked_` methods return `None` instead of overflowing or
+/// mixing currencies.
+///
+/// # Panics
+///
+/// Adding or subtracting amounts in different currencies panics, as does
+/// arithmetic overflowing `i64` minor units.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Money {
+    /// The amount in minor units, e.g. cents.
+    pub minor: i64,
+    /// The amount's currency.
+    pub currency: Currency,
+}
+
+impl Money {
+    /// `minor` units of `currency`, e.g. `Money::new(450, Currency::USD)`
+    /// for $4.50.
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Self {
+            minor,
+            currency,
+        }
+    }
+
+    /// Zero in `currency`.
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// `self + other`, or `None` on overflow or if the currencies differ.
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Self::new(self.minor.checked_add(other.minor)?, self.currency.clone()))
+    }
+
+    /// `self - other`, or `None` on overflow or if the currencies differ.
+    pub fn checked_sub(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Self::new(self.minor.checked_sub(other.minor)?, self.currency.clone()))
+    }
+
+    /// `self * quantity`, or `None` on overflow.
+    pub fn checked_mul(&self, quantity: i64) -> Option<Money> {
+        Some(Self::new(self.minor.checked_mul(quantity)?, self.currency.clone()))
+    }
+
+    /// `-self`, or `None` for the most negative amount.
+    pub fn checked_neg(&self) -> Option<Money> {
+        Some(Self::new(self.minor.checked_neg()?, self.currency.clone()))
+    }
+
+    /// Whether the amount is below zero.
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+
+    /// The amount without a currency symbol, e.g. `1,234.56`.
+    pub fn format_amount(&self, locale: &Locale) -> String {
+        let mut output = String::new();
+        if self.is_negative() {
+            output.push('-');
+        }
+        self.push_amount(&mut output, locale);
+        output
+    }
+
+    /// The amount with its currency symbol, e.g. `$1,234.56`.
+    ///
+    /// A minus sign goes in front of both the symbol and the digits.
+    pub fn format(&self, locale: &Locale) -> String {
+        let mut output = String::new();
+        if self.is_negative() {
+            output.push('-');
+        }
+        let symbol = &self.currency.symbol;
+        let space = if locale.symbol_space {
+            " "
+        } else {
+            ""
+        };
+        if !locale.symbol_after {
+            output.push_str(symbol);
+            output.push_str(space);
+        }
+        self.push_amount(&mut output, locale);
+        if locale.symbol_after {
+            output.push_str(space);
+            output.push_str(symbol);
+        }
+        output
+    }
+
+    fn push_amount(&self, output: &mut String, locale: &Locale) {
+        let minor = self.minor.unsigned_abs();
+        // Past 19 decimals every amount is a fraction of one unit
+        let (whole, fraction) = match 10u64.checked_pow(self.currency.decimals.into()) {
+            Some(scale) => (minor / scale, minor % scale),
+            None => (0, minor),
+        };
+        locale.push_digits(output, whole);
+        if self.currency.decimals > 0 {
+            output.push(locale.decimal);
+            let fraction = fraction.to_string();
+            for _ in fraction.len()..usize::from(self.currency.decimals) {
+                output.push('0');
+            }
+            output.push_str(&fraction);
+        }
+    }
+}
+
+/// Formats with [`Locale::EN_US`] conventions.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(&Locale::EN_US))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(mut self, other: Money) -> Money {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        assert_eq!(self.currency, other.currency, "cannot add amounts in different currencies");
+        *self = self.checked_add(&other).expect("amount overflowed");
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(mut self, other: Money) -> Money {
+        self -= other;
+        self
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        assert_eq!(
+            self.currency, other.currency,
+            "cannot subtract amounts in different currencies"
+        );
+        *self = self.checked_sub(&other).expect("amount overflowed");
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        self.checked_neg().expect("amount overflowed")
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, quantity: i64) -> Money {
+        self.checked_mul(quantity).expect("amount overflowed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_currencies_with_many_decimals() {
+        let currency = Currency::new("XTS", "T", 21);
+        let amount = Money::new(-123, currency);
+        assert_eq!(amount.format_amount(&Locale::EN_US), "-0.000000000000000000123");
+
+        let currency = Currency::new("XTS", "T", u8::MAX);
+        let amount = Money::new(i64::MIN, currency);
+        assert!(amount.format_amount(&Locale::EN_US).ends_with("9223372036854775808"));
+    }
+
+    #[test]
+    fn formats_per_locale() {
+        let amount = Money::new(123_456_789, Currency::CHF);
+        assert_eq!(amount.format(&Locale::DE_CH), "CHF 1'234'567.89");
+        assert_eq!(amount.format_amount(&Locale::DE_DE), "1.234.567,89");
+        let plain = Locale {
+            group: None,
+            ..Locale::EN_US
+        };
+        assert_eq!(amount.format_amount(&plain), "1234567.89");
+    }
+
+    #[test]
+    fn pads_fractions_and_handles_signs() {
+        assert_eq!(Money::new(5, Currency::USD).to_string(), "$0.05");
+        assert_eq!(Money::new(-1_000, Currency::USD).to_string(), "-$10.00");
+        assert_eq!(Money::new(-100, Currency::GBP).format(&Locale::DE_DE), "-1,00 £");
+        assert_eq!(Money::new(1_500, Currency::JPY).to_string(), "¥1,500");
+        assert_eq!(Money::new(i64::MIN, Currency::JPY).format_amount(&Locale::EN_US).len(), 26);
+    }
+
+    #[test]
+    fn groups_integers() {
+        assert_eq!(Locale::FR_FR.format_integer(1_000_000), "1 000 000");
+        assert_eq!(Locale::EN_US.format_integer(-999), "-999");
+        assert_eq!(Locale::EN_US.format_integer(-1000), "-1,000");
+    }
+
+    #[test]
+    fn arithmetic_keeps_the_currency() {
+        let price = Money::new(350, Currency::EUR);
+        let total = price.clone() * 3 - Money::new(50, Currency::EUR);
+        assert_eq!(total, Money::new(1_000, Currency::EUR));
+        assert_eq!(-price.clone() + price, Money::zero(Currency::EUR));
+    }
+
+    #[test]
+    fn checked_arithmetic_catches_overflow() {
+        let max = Money::new(i64::MAX, Currency::USD);
+        let one = Money::new(1, Currency::USD);
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(max.checked_sub(&one), Some(Money::new(i64::MAX - 1, Currency::USD)));
+        assert_eq!(one.checked_mul(i64::MAX), Some(max.clone()));
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(Money::new(i64::MIN, Currency::USD).checked_neg(), None);
+    }
+
+    #[test]
+    fn checked_arithmetic_rejects_mixed_currencies() {
+        let dollar = Money::new(100, Currency::USD);
+        let euro = Money::new(100, Currency::EUR);
+        assert_eq!(dollar.checked_add(&euro), None);
+        assert_eq!(dollar.checked_sub(&euro), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "amount overflowed")]
+    fn overflowing_operators_panic() {
+        let _ = Money::new(9_000_000_000_000, Currency::USD) * 4_000_000_000;
+    }
+
+    #[test]
+    #[should_panic(expected = "different currencies")]
+    fn mixing_currencies_panics() {
+        let _ = Money::new(1, Currency::USD) + Money::new(1, Currency::EUR);
+    }
+}
@@ -3,12 +3,33 @@
 //! [`PageBuilder`] accumulates commands for page mode printing.
 //! In page mode, all output is buffered until FormFeed is sent.
 
-use crate::command::Command;
+#[cfg(feature = "preview")]
+pub mod preview;
+pub mod template;
+
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+use crate::command::barcode::{
+    BarcodeWidth, HriFont, HriPosition, PrintBarcode, SetBarcodeHeight, SetBarcodeWidth,
+    SetHriFont, SetHriPosition,
+};
 use crate::command::basic::FormFeed;
+use crate::command::image::PrintRasterImage;
 use crate::command::page_mode::{
-    EnterPageMode, ExitPageMode, PrintArea, PrintDirection, SetHorizontalPosition, SetPrintArea,
-    SetPrintDirection, SetVerticalPosition,
+    EnterPageMode, ExitPageMode, PrintArea, PrintDirection, PrintPageModeData,
+    SetHorizontalPosition, SetPrintArea, SetPrintDirection, SetVerticalPosition,
 };
+use crate::command::spacing::{Dots, Length, SetMotionUnits};
+use crate::command::symbol::{PrintPdf417, PrintQrCode, qr_fallback};
+use crate::command::{Command, StaticCommand};
+use crate::error::PrinterError;
+use crate::error::ValidationError;
+#[cfg(feature = "std")]
+use crate::printer::Printer;
+use crate::profile::PrinterProfile;
 use crate::style::text::StyledNode;
 
 /// Builder for page mode printing.
@@ -27,7 +48,7 @@ use crate::style::text::StyledNode;
 ///     .text("Header".bold())
 ///     .vertical_position(100)
 ///     .text("Body text")
-///     .build();
+///     .build()?;
 ///
 /// printer.send_all(page)?;
 /// ```
@@ -37,8 +58,48 @@ pub struct PageBuilder {
     area: Option<PrintArea>,
     /// Print direction configuration.
     direction: Option<PrintDirection>,
+    /// Motion units for the page, if not the printer defaults.
+    units: Option<SetMotionUnits>,
+    /// Printer capabilities, for measuring text.
+    profile: PrinterProfile,
+    /// Baseline of the current line, in dots from the top of the area.
+    cursor: Option<u32>,
+    /// Deepest baseline reached by content, in dots.
+    extent: u32,
+    /// Depth reached and available in the first region that overflowed.
+    region_overflow: Option<(u32, u32)>,
     /// Queued commands.
     commands: Vec<QueuedCommand>,
+    /// Barcode settings were changed and must be restored after the page.
+    barcode_settings: bool,
+    /// Pages ended by [`page_break`](Self::page_break), in order.
+    previous: Vec<PageBuilder>,
+    /// First content on the page that can't be printed, reported by
+    /// [`build`](Self::build).
+    error: Option<ValidationError>,
+}
+
+/// Default line spacing of 1/6 inch, in dots.
+const LINE_SPACING: u32 = Dots::PER_INCH as u32 / 6;
+
+/// Convert motion units at `per_inch` to print head dots, rounding down.
+fn units_to_dots(value: u16, per_inch: u16) -> u32 {
+    u32::from(value) * u32::from(Dots::PER_INCH) / u32::from(per_inch)
+}
+
+/// Whether the last text in `node` is a newline.
+fn ends_with_newline(node: &StyledNode) -> bool {
+    match node {
+        StyledNode::Text(text) => text.ends_with('\n'),
+        StyledNode::Styled {
+            children,
+            ..
+        } => children
+            .iter()
+            .rev()
+            .find(|child| !matches!(child, StyledNode::Text(text) if text.is_empty()))
+            .is_some_and(ends_with_newline),
+    }
 }
 
 /// A command in the page builder queue.
@@ -48,6 +109,8 @@ enum QueuedCommand {
     Raw(Vec<u8>),
     /// Styled text node.
     StyledText(StyledNode),
+    /// Styled text node followed by a line feed.
+    StyledLine(StyledNode),
 }
 
 impl PageBuilder {
@@ -68,23 +131,299 @@ impl PageBuilder {
         self
     }
 
+    /// Set the motion units for the page.
+    ///
+    /// Sent before the print area, so an area built with
+    /// [`PrintArea::from_lengths`] and the same units lines up. Positions
+    /// given as [`Mm`](crate::command::spacing::Mm),
+    /// [`Inch`](crate::command::spacing::Inch) or [`Dots`] are converted
+    /// through these units. The printer defaults are restored after the page.
+    pub fn motion_units(mut self, units: SetMotionUnits) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Set the printer profile used to measure text for
+    /// [`text_box`](Self::text_box).
+    ///
+    /// Defaults to [`PrinterProfile::default`].
+    pub fn profile(mut self, profile: PrinterProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Set absolute vertical position.
-    pub fn vertical_position(mut self, position: u16) -> Self {
+    ///
+    /// A bare `u16` is taken as motion units. Other lengths use the
+    /// vertical motion unit, or the horizontal one when the print direction
+    /// is rotated, as the printer does.
+    pub fn vertical_position(mut self, position: impl Length) -> Self {
+        let units = self.units.unwrap_or_default();
+        let position = if self.is_rotated() {
+            position.horizontal_units(&units)
+        } else {
+            position.vertical_units(&units)
+        };
+        self.cursor = Some(units_to_dots(position, self.across_per_inch()));
         self.commands.push(QueuedCommand::Raw(SetVerticalPosition(position).encode()));
         self
     }
 
     /// Set absolute horizontal position.
-    pub fn horizontal_position(mut self, position: u16) -> Self {
+    ///
+    /// A bare `u16` is taken as motion units. Other lengths use the
+    /// horizontal motion unit, or the vertical one when the print direction
+    /// is rotated, as the printer does.
+    pub fn horizontal_position(mut self, position: impl Length) -> Self {
+        let units = self.units.unwrap_or_default();
+        let position = if self.is_rotated() {
+            position.vertical_units(&units)
+        } else {
+            position.horizontal_units(&units)
+        };
         self.commands.push(QueuedCommand::Raw(SetHorizontalPosition(position).encode()));
         self
     }
 
+    /// Motion units per inch across the print direction.
+    fn across_per_inch(&self) -> u16 {
+        let units = self.units.unwrap_or_default();
+        if self.is_rotated() {
+            units.horizontal_per_inch()
+        } else {
+            units.vertical_per_inch()
+        }
+    }
+
+    /// Whether lines run along the paper feed rather than across it.
+    fn is_rotated(&self) -> bool {
+        matches!(self.direction, Some(PrintDirection::BottomToTop | PrintDirection::TopToBottom))
+    }
+
     /// Set both horizontal and vertical position.
-    pub fn position(self, x: u16, y: u16) -> Self {
+    pub fn position(self, x: impl Length, y: impl Length) -> Self {
         self.horizontal_position(x).vertical_position(y)
     }
 
+    /// Add a region with its own print area and direction.
+    ///
+    /// `f` receives an empty builder for the region; commands queued on it
+    /// print inside `area` using `direction`. Afterwards the parent's area
+    /// and direction are restored, falling back to the printer defaults
+    /// (the full 80mm area, left to right) if the parent set none. Regions
+    /// can be nested.
+    ///
+    /// The page buffer is not cleared between regions, so several regions
+    /// form panels of a single printed page. A [`page_break`](Self::page_break)
+    /// inside `f` ends the parent's page too, and the region continues in
+    /// the same area on the next page.
+    ///
+    /// ```
+    /// use bixolon::command::page_mode::{PrintArea, PrintDirection};
+    /// use bixolon::page::PageBuilder;
+    ///
+    /// let stub = PrintArea { x: 400, y: 0, width: 112, height: 400 };
+    /// let page = PageBuilder::new()
+    ///     .text_line("Admit one")
+    ///     .region(stub, PrintDirection::BottomToTop, |r| r.text_line("No. 0042"))
+    ///     .build()?;
+    /// # Ok::<(), bixolon::error::ValidationError>(())
+    /// ```
+    pub fn region(
+        mut self,
+        area: PrintArea,
+        direction: PrintDirection,
+        f: impl FnOnce(PageBuilder) -> PageBuilder,
+    ) -> Self {
+        let mut region = PageBuilder::new().area(area).direction(direction);
+        region.units = self.units;
+        region.profile = self.profile.clone();
+        let mut region = f(region);
+
+        for page in core::mem::take(&mut region.previous) {
+            self = self.merge_region(area, direction, page).page_break();
+        }
+        self.merge_region(area, direction, region)
+    }
+
+    /// Queue one page of a [`region`](Self::region) on the current page.
+    fn merge_region(mut self, area: PrintArea, direction: PrintDirection, region: Self) -> Self {
+        self = self
+            .command(SetPrintArea(region.area.unwrap_or(area)))
+            .command(SetPrintDirection(region.direction.unwrap_or(direction)));
+        if self.region_overflow.is_none() {
+            self.region_overflow = region.overflow();
+        }
+        if self.error.is_none() {
+            self.error = region.error;
+        }
+        self.commands.extend(region.commands);
+        self.barcode_settings |= region.barcode_settings;
+        // Setting the area moves the print position back to its start
+        self.cursor = None;
+
+        let parent_area = self.area.unwrap_or(PrintArea::default_80mm());
+        let parent_direction = self.direction.unwrap_or_default();
+        self.command(SetPrintArea(parent_area)).command(SetPrintDirection(parent_direction))
+    }
+
+    /// Add text wrapped to fit inside `area`.
+    ///
+    /// The text is word wrapped to the width of the box along the page's
+    /// print direction and laid out line by line from its top, using the
+    /// printer profile's character sizes and the default 1/6 inch line
+    /// spacing (more for taller text). Lines that would extend past the
+    /// bottom of the box are dropped. Justification is relative to the box.
+    ///
+    /// The box is printed as a [`region`](Self::region) in the page's
+    /// current direction, so the page's print area is restored afterwards.
+    ///
+    /// ```
+    /// use bixolon::command::page_mode::PrintArea;
+    /// use bixolon::page::PageBuilder;
+    ///
+    /// let notes = PrintArea { x: 256, y: 0, width: 256, height: 200 };
+    /// let page = PageBuilder::new()
+    ///     .text_box(notes, "Allergies: peanuts, shellfish. Deliver to back door.")
+    ///     .build()?;
+    /// # Ok::<(), bixolon::error::ValidationError>(())
+    /// ```
+    pub fn text_box(self, area: PrintArea, node: impl Into<StyledNode>) -> Self {
+        let (line_length, depth) = self.extent_dots(&area);
+
+        let profile = self.profile.clone();
+        let lines = node.into().wrap(&profile, line_length);
+        let minimum_height = u32::from(profile.char_height_dots(Default::default()));
+        let direction = self.direction.unwrap_or_default();
+        self.region(area, direction, |mut region| {
+            let mut top = 0;
+            for line in lines {
+                let height = line.height_dots(&profile).max(minimum_height);
+                if top + height > depth {
+                    break;
+                }
+                let baseline = Dots((top + height).min(u32::from(u16::MAX)) as u16);
+                region = region.position(0, baseline);
+                region.place(height);
+                region.commands.push(QueuedCommand::StyledText(line));
+                top += height.max(LINE_SPACING);
+            }
+            region
+        })
+    }
+
+    /// Draw a horizontal line across the page's print area.
+    ///
+    /// The line's top edge is `y` below the top of the area and it is
+    /// `thickness` dots thick. "Horizontal" follows the print direction, so
+    /// in a rotated page the line runs along the paper.
+    pub fn hline(self, y: impl Length, thickness: u16) -> Self {
+        let (length, _) = self.extent_dots(&self.page_area());
+        let y = self.across_dots(y);
+        self.bar(0, y, length, u32::from(thickness))
+    }
+
+    /// Draw a vertical line down the full depth of the page's print area.
+    ///
+    /// The line's left edge is `x` from the start of the area and it is
+    /// `thickness` dots thick.
+    pub fn vline(self, x: impl Length, thickness: u16) -> Self {
+        let (_, depth) = self.extent_dots(&self.page_area());
+        let x = self.along_dots(x);
+        self.bar(x, 0, u32::from(thickness), depth)
+    }
+
+    /// Draw a frame just inside the edges of `area`.
+    ///
+    /// Drawn as a [`region`](Self::region) in the page's current direction,
+    /// so `area` is given in print area coordinates like any other region.
+    ///
+    /// ```
+    /// use bixolon::command::page_mode::PrintArea;
+    /// use bixolon::page::PageBuilder;
+    ///
+    /// let ticket = PrintArea { x: 0, y: 0, width: 512, height: 600 };
+    /// let page = PageBuilder::new()
+    ///     .rect(ticket, 3)
+    ///     .hline(120u16, 1)
+    ///     .build()?;
+    /// # Ok::<(), bixolon::error::ValidationError>(())
+    /// ```
+    pub fn rect(self, area: PrintArea, thickness: u16) -> Self {
+        let (length, depth) = self.extent_dots(&area);
+        let thickness = u32::from(thickness);
+        let direction = self.direction.unwrap_or_default();
+        self.region(area, direction, |region| {
+            region
+                .bar(0, 0, length, thickness)
+                .bar(0, depth.saturating_sub(thickness), length, thickness)
+                .bar(0, 0, thickness, depth)
+                .bar(length.saturating_sub(thickness), 0, thickness, depth)
+        })
+    }
+
+    /// Queue a solid raster block with its top-left corner at (`x`, `y`).
+    ///
+    /// All values are dots along and across the print direction.
+    fn bar(self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let width = width.min(u32::from(u16::MAX) * 8);
+        let height = height.min(u32::from(u16::MAX));
+        if width == 0 || height == 0 {
+            return self;
+        }
+
+        let width_bytes = width.div_ceil(8) as usize;
+        let mut row = vec![0xFF; width_bytes];
+        if !width.is_multiple_of(8) {
+            row[width_bytes - 1] = 0xFF << (8 - width % 8);
+        }
+        let data = row.repeat(height as usize);
+
+        let clamp = |dots: u32| Dots(dots.min(u32::from(u16::MAX)) as u16);
+        self.position(clamp(x), clamp(y + height)).image(PrintRasterImage::new(
+            width_bytes as u16,
+            height as u16,
+            data,
+        ))
+    }
+
+    /// The page's print area, or the printer default.
+    fn page_area(&self) -> PrintArea {
+        self.area.unwrap_or(PrintArea::default_80mm())
+    }
+
+    /// Size of `area` in dots, along and across the print direction.
+    fn extent_dots(&self, area: &PrintArea) -> (u32, u32) {
+        let units = self.units.unwrap_or_default();
+        let width = units_to_dots(area.width, units.horizontal_per_inch());
+        let height = units_to_dots(area.height, units.vertical_per_inch());
+        if self.is_rotated() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// A distance along the print direction, in dots.
+    fn along_dots(&self, length: impl Length) -> u32 {
+        let units = self.units.unwrap_or_default();
+        if self.is_rotated() {
+            units_to_dots(length.vertical_units(&units), units.vertical_per_inch())
+        } else {
+            units_to_dots(length.horizontal_units(&units), units.horizontal_per_inch())
+        }
+    }
+
+    /// A distance across the print direction, in dots.
+    fn across_dots(&self, length: impl Length) -> u32 {
+        let units = self.units.unwrap_or_default();
+        if self.is_rotated() {
+            units_to_dots(length.horizontal_units(&units), units.horizontal_per_inch())
+        } else {
+            units_to_dots(length.vertical_units(&units), units.vertical_per_inch())
+        }
+    }
+
     /// Add a command to the queue.
     pub fn command(mut self, cmd: impl Command) -> Self {
         self.commands.push(QueuedCommand::Raw(cmd.encode()));
@@ -93,81 +432,509 @@ impl PageBuilder {
 
     /// Add styled text.
     pub fn text(mut self, node: impl Into<StyledNode>) -> Self {
-        self.commands.push(QueuedCommand::StyledText(node.into()));
+        let node = node.into();
+        self.track_text(&node, false);
+        self.commands.push(QueuedCommand::StyledText(node));
         self
     }
 
     /// Add styled text followed by a line feed.
     pub fn text_line(mut self, node: impl Into<StyledNode>) -> Self {
         let node = node.into();
-        self.commands.push(QueuedCommand::Raw(node.render_line()));
+        self.track_text(&node, true);
+        self.commands.push(QueuedCommand::StyledLine(node));
         self
     }
 
+    /// Advance the cursor over text, as the printer would lay it out.
+    fn track_text(&mut self, node: &StyledNode, line_feed: bool) {
+        let (line_length, _) = self.extent_dots(&self.page_area());
+        let minimum_height = u32::from(self.profile.char_height_dots(Default::default()));
+        for (index, line) in node.wrap(&self.profile, line_length).iter().enumerate() {
+            let height = line.height_dots(&self.profile).max(minimum_height);
+            let baseline = match self.cursor {
+                None => height,
+                Some(cursor) if index == 0 => cursor,
+                Some(cursor) => cursor + height.max(LINE_SPACING),
+            };
+            self.cursor = Some(baseline);
+            self.extent = self.extent.max(baseline);
+        }
+        if line_feed || ends_with_newline(node) {
+            self.cursor = Some(self.cursor.unwrap_or(minimum_height) + LINE_SPACING);
+        }
+    }
+
+    /// Record content `height` dots tall sitting on the current baseline.
+    fn place(&mut self, height: u32) {
+        let baseline = self.cursor.unwrap_or(height);
+        self.cursor = Some(baseline);
+        self.extent = self.extent.max(baseline);
+    }
+
+    /// Add a barcode at the current position.
+    ///
+    /// The barcode's bottom edge sits on the current baseline, so set the
+    /// vertical position to the barcode height (plus any HRI text) below
+    /// where its top should be.
+    pub fn barcode(mut self, barcode: PrintBarcode) -> Self {
+        self.place(0);
+        self.commands.push(QueuedCommand::Raw(barcode.encode()));
+        self
+    }
+
+    /// Add a QR code at the current position.
+    ///
+    /// Like barcodes, the symbol is placed with its bottom edge on the
+    /// current baseline and is rotated with the page's print direction, so
+    /// a code in a fixed corner only needs a [`position`](Self::position).
+    ///
+    /// If the builder's [`profile`](Self::profile) lacks native QR support
+    /// and the `qrcode` feature is enabled, the symbol is added as a raster
+    /// [`image`](Self::image) instead. If it can't be, [`build`](Self::build)
+    /// fails with [`ValidationError::Unsupported`] or
+    /// [`ValidationError::QrCode`].
+    pub fn qr_code(mut self, qr: PrintQrCode) -> Self {
+        match qr_fallback(&qr, &self.profile) {
+            Ok(Some(image)) => return self.image(image),
+            Ok(None) => {}
+            Err(error) => {
+                let error = match error {
+                    PrinterError::Validation(error) => error,
+                    PrinterError::QrCode(error) => ValidationError::QrCode(error),
+                    error => unreachable!("unexpected QR fallback error: {error}"),
+                };
+                self.error.get_or_insert(error);
+                return self;
+            }
+        }
+        self.place(0);
+        self.commands.push(QueuedCommand::Raw(qr.encode()));
+        self
+    }
+
+    /// Add a PDF417 symbol at the current position.
+    ///
+    /// Placed like [`qr_code`](Self::qr_code).
+    pub fn pdf417(mut self, pdf417: PrintPdf417) -> Self {
+        self.place(0);
+        self.commands.push(QueuedCommand::Raw(pdf417.encode()));
+        self
+    }
+
+    /// Add a raster image, such as a logo, at the current position.
+    ///
+    /// The image's bottom edge sits on the current baseline, as with
+    /// [`barcode`](Self::barcode).
+    pub fn image(mut self, image: impl Into<PrintRasterImage>) -> Self {
+        let image = image.into();
+        self.place(u32::from(image.height_dots));
+        self.commands.push(QueuedCommand::Raw(image.encode()));
+        self
+    }
+
+    /// Add an `image` crate image at its own size, as [`image`](Self::image)
+    /// does.
+    ///
+    /// If the image is too large to print, [`build`](Self::build) fails
+    /// with [`ValidationError::ImageTooLarge`].
+    #[cfg(feature = "image")]
+    pub fn dynamic_image(mut self, image: &::image::DynamicImage) -> Self {
+        match PrintRasterImage::try_from(image) {
+            Ok(image) => self.image(image),
+            Err(error) => {
+                self.error.get_or_insert(error);
+                self
+            }
+        }
+    }
+
+    /// Set the height of following barcodes.
+    ///
+    /// Barcode settings are scoped to the page: the printer defaults are
+    /// restored after the page's commands.
+    pub fn barcode_height(mut self, height: SetBarcodeHeight) -> Self {
+        self.barcode_settings = true;
+        self.command(height)
+    }
+
+    /// Set the module width of following barcodes, scoped to the page.
+    pub fn barcode_width(mut self, width: BarcodeWidth) -> Self {
+        self.barcode_settings = true;
+        self.command(SetBarcodeWidth(width))
+    }
+
+    /// Set where following barcodes print their HRI text, scoped to the
+    /// page.
+    pub fn hri_position(mut self, position: HriPosition) -> Self {
+        self.barcode_settings = true;
+        self.command(SetHriPosition(position))
+    }
+
+    /// Set the HRI font of following barcodes, scoped to the page.
+    pub fn hri_font(mut self, font: HriFont) -> Self {
+        self.barcode_settings = true;
+        self.command(SetHriFont(font))
+    }
+
     /// Add raw bytes to the queue.
     pub fn raw(mut self, bytes: impl Into<Vec<u8>>) -> Self {
         self.commands.push(QueuedCommand::Raw(bytes.into()));
         self
     }
 
+    /// End the current page and start a new one.
+    ///
+    /// The new page keeps the print area, direction, motion units and
+    /// profile, and starts with an empty buffer at the top of the area.
+    /// [`build`](Self::build) emits every page in order, each entering page
+    /// mode and printing with its own FormFeed.
+    ///
+    /// ```
+    /// use bixolon::page::PageBuilder;
+    ///
+    /// let labels = ["A-101", "A-102", "A-103"];
+    /// let mut page = PageBuilder::new();
+    /// for (index, label) in labels.iter().enumerate() {
+    ///     if index > 0 {
+    ///         page = page.page_break();
+    ///     }
+    ///     page = page.text_line(*label);
+    /// }
+    /// assert_eq!(page.page_count(), 3);
+    /// ```
+    pub fn page_break(mut self) -> Self {
+        let mut previous = core::mem::take(&mut self.previous);
+        let next = PageBuilder {
+            area: self.area,
+            direction: self.direction,
+            units: self.units,
+            profile: self.profile.clone(),
+            ..Default::default()
+        };
+        previous.push(self);
+        PageBuilder {
+            previous,
+            ..next
+        }
+    }
+
+    /// Number of pages, including the current one.
+    pub fn page_count(&self) -> usize {
+        self.previous.len() + 1
+    }
+
+    /// Iterate over the pages, building each on its own.
+    ///
+    /// Useful for sending a label run one label at a time, e.g. to check
+    /// status between them. Concatenating every page gives the output of
+    /// [`build`](Self::build).
+    pub fn pages(&self) -> Pages<'_> {
+        Pages {
+            previous: self.previous.iter(),
+            current: Some(self),
+        }
+    }
+
+    /// Every page, in order.
+    fn each_page(&self) -> impl Iterator<Item = &PageBuilder> {
+        self.previous.iter().chain(core::iter::once(self))
+    }
+
     /// Build the complete page mode byte sequence.
     ///
-    /// Returns bytes ready to send to the printer, including:
+    /// Returns bytes ready to send to the printer. Each page includes:
     /// 1. EnterPageMode command
-    /// 2. Print area (if set)
-    /// 3. Print direction (if set)
-    /// 4. All queued commands
-    /// 5. FormFeed to print the page
-    pub fn build(&self) -> Vec<u8> {
+    /// 2. Motion units (if set)
+    /// 3. Print area (if set)
+    /// 4. Print direction (if set)
+    /// 5. All queued commands
+    /// 6. Default barcode and motion unit settings, if the page changed them
+    /// 7. FormFeed to print the page
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::PageOverflow`] if content reaches past the
+    /// bottom of the print area, or of a region's area, where the printer
+    /// would silently drop it. See [`overflow`](Self::overflow) for what is
+    /// measured.
+    ///
+    /// Returns [`ValidationError::Unsupported`] or [`ValidationError::QrCode`]
+    /// if a [`qr_code`](Self::qr_code) can't be printed on the profile.
+    pub fn build(&self) -> Result<Vec<u8>, ValidationError> {
+        let mut output = Vec::new();
+        for page in self.each_page() {
+            page.build_page_into(&mut output)?;
+        }
+        Ok(output)
+    }
+
+    /// Build the current page alone.
+    fn build_page(&self) -> Result<Vec<u8>, ValidationError> {
+        let mut output = Vec::new();
+        self.build_page_into(&mut output)?;
+        Ok(output)
+    }
+
+    /// Append the current page alone to `output`.
+    fn build_page_into(&self, output: &mut Vec<u8>) -> Result<(), ValidationError> {
+        self.build_body_into(output)?;
+
+        // FormFeed to print the page
+        FormFeed.encode_into(output);
+
+        Ok(())
+    }
+
+    /// Check the content's depth against the print area.
+    ///
+    /// Returns the depth reached and the depth available, in dots, if the
+    /// content doesn't fit. Text is measured by wrapping it at the area's
+    /// width with the builder's [`profile`](Self::profile) and the default
+    /// line spacing; images, lines and positioned barcodes and 2D symbols
+    /// count down to their baseline. Raw commands are not measured.
+    ///
+    /// With several pages, reports the first page that overflows.
+    pub fn overflow(&self) -> Option<(u32, u32)> {
+        self.each_page().find_map(PageBuilder::page_overflow)
+    }
+
+    /// Overflow of the current page alone.
+    fn page_overflow(&self) -> Option<(u32, u32)> {
+        if self.region_overflow.is_some() {
+            return self.region_overflow;
+        }
+        let (_, depth) = self.extent_dots(&self.page_area());
+        (self.extent > depth).then_some((self.extent, depth))
+    }
+
+    /// Write every page to `writer` as it is encoded.
+    ///
+    /// Produces the same bytes as [`build`](Self::build) without collecting
+    /// them into one buffer, saving the second copy of the document that
+    /// `build` returns. The builder itself already holds each queued
+    /// command, images included, in encoded form. All pages are checked
+    /// for overflow before anything is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ValidationError`]s [`build`](Self::build) does, or the
+    /// first I/O error from `writer`.
+    #[cfg(feature = "std")]
+    pub fn build_into(&self, writer: &mut impl Write) -> Result<(), PrinterError> {
+        if let Some(error) = self.each_page().find_map(|page| page.error.clone()) {
+            return Err(error.into());
+        }
+        if let Some((needed, available)) = self.overflow() {
+            return Err(ValidationError::PageOverflow {
+                needed,
+                available,
+            }
+            .into());
+        }
+        for page in self.each_page() {
+            page.write_body(&mut |bytes, _| writer.write_all(bytes).map_err(PrinterError::from))?;
+            writer.write_all(FormFeed::BYTES)?;
+        }
+        Ok(())
+    }
+
+    /// Stream every page to `printer`, as [`Printer::print_page`] does
+    /// without taking ownership of the builder.
+    ///
+    /// # Errors
+    ///
+    /// As for [`build_into`](Self::build_into).
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write, R>(&self, printer: &mut Printer<W, R>) -> Result<(), PrinterError> {
+        printer.write_page(self)?;
+        Ok(())
+    }
+
+    /// Build a page that is printed `copies` times.
+    ///
+    /// The page buffer is printed with [`PrintPageModeData`] for every copy
+    /// but the last, which uses FormFeed to print and clear the buffer.
+    /// A `copies` value of 0 is treated as 1. With several pages, each page
+    /// is printed `copies` times before the next.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ValidationError`]s [`build`](Self::build) does.
+    pub fn build_copies(&self, copies: u8) -> Result<Vec<u8>, ValidationError> {
         let mut output = Vec::new();
+        for page in self.each_page() {
+            page.build_body_into(&mut output)?;
+
+            for _ in 1..copies.max(1) {
+                PrintPageModeData.encode_into(&mut output);
+            }
+            FormFeed.encode_into(&mut output);
+        }
+
+        Ok(output)
+    }
+
+    /// Append everything up to, but not including, the final print command
+    /// to `output`.
+    fn build_body_into(&self, output: &mut Vec<u8>) -> Result<(), ValidationError> {
+        self.write_body(&mut |bytes, _| {
+            output.extend_from_slice(bytes);
+            Ok::<_, ValidationError>(())
+        })
+    }
+
+    /// Emit everything up to, but not including, the final print command.
+    ///
+    /// `emit` receives the output in order, with `true` for rendered text.
+    /// Commands and text are encoded into one scratch buffer, so `emit`'s
+    /// slices are only valid for the call.
+    fn write_body<E: From<ValidationError>>(
+        &self,
+        emit: &mut impl FnMut(&[u8], bool) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if let Some(error) = &self.error {
+            return Err(error.clone().into());
+        }
+        if let Some((needed, available)) = self.page_overflow() {
+            return Err(ValidationError::PageOverflow {
+                needed,
+                available,
+            }
+            .into());
+        }
+
+        /// Encode `cmd` into `scratch` and emit it.
+        fn emit_command<E>(
+            emit: &mut impl FnMut(&[u8], bool) -> Result<(), E>,
+            scratch: &mut Vec<u8>,
+            cmd: &dyn Command,
+        ) -> Result<(), E> {
+            scratch.clear();
+            cmd.encode_into(scratch);
+            emit(scratch, false)
+        }
+        let mut scratch = Vec::new();
 
         // Enter page mode
-        output.extend(EnterPageMode.encode());
+        emit_command(emit, &mut scratch, &EnterPageMode)?;
+
+        // Set motion units first, as the print area is measured in them
+        if let Some(units) = &self.units {
+            emit_command(emit, &mut scratch, units)?;
+        }
 
         // Set print area if configured
         if let Some(area) = &self.area {
-            output.extend(SetPrintArea(*area).encode());
+            emit_command(emit, &mut scratch, &SetPrintArea(*area))?;
         }
 
         // Set print direction if configured
         if let Some(direction) = &self.direction {
-            output.extend(SetPrintDirection(*direction).encode());
+            emit_command(emit, &mut scratch, &SetPrintDirection(*direction))?;
         }
 
         // Render all queued commands
         for cmd in &self.commands {
+            scratch.clear();
             match cmd {
-                QueuedCommand::Raw(bytes) => output.extend(bytes),
-                QueuedCommand::StyledText(node) => output.extend(node.render()),
+                QueuedCommand::Raw(bytes) => emit(bytes, false)?,
+                QueuedCommand::StyledText(node) => {
+                    node.render_into_for(&self.profile, &mut scratch);
+                    emit(&scratch, true)?
+                }
+                QueuedCommand::StyledLine(node) => {
+                    node.render_line_into_for(&self.profile, &mut scratch);
+                    emit(&scratch, true)?
+                }
             }
         }
 
-        // FormFeed to print the page
-        output.extend(FormFeed.encode());
+        // Restore barcode settings changed by this page
+        if self.barcode_settings {
+            emit_command(emit, &mut scratch, &SetBarcodeHeight::default())?;
+            emit_command(emit, &mut scratch, &SetBarcodeWidth::default())?;
+            emit_command(emit, &mut scratch, &SetHriPosition::default())?;
+            emit_command(emit, &mut scratch, &SetHriFont::default())?;
+        }
+
+        // Restore motion units changed by this page
+        if self.units.is_some() {
+            emit_command(emit, &mut scratch, &SetMotionUnits::default())?;
+        }
 
-        output
+        Ok(())
     }
 
     /// Build and include ExitPageMode at the end.
     ///
     /// Use this when you want to return to standard mode after printing.
-    pub fn build_and_exit(&self) -> Vec<u8> {
-        let mut output = self.build();
-        output.extend(ExitPageMode.encode());
-        output
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ValidationError`]s [`build`](Self::build) does.
+    pub fn build_and_exit(&self) -> Result<Vec<u8>, ValidationError> {
+        let mut output = self.build()?;
+        output.extend_from_slice(ExitPageMode::BYTES);
+        Ok(output)
     }
 
-    /// Check if the builder is empty (no commands queued).
+    /// Check if the builder is empty (no commands queued on any page).
     pub fn is_empty(&self) -> bool {
-        self.commands.is_empty()
+        self.each_page().all(|page| page.commands.is_empty())
     }
 
-    /// Get the number of queued commands.
+    /// Get the number of queued commands, across all pages.
     pub fn len(&self) -> usize {
-        self.commands.len()
+        self.each_page().map(|page| page.commands.len()).sum()
     }
 }
 
+impl FromIterator<PageBuilder> for PageBuilder {
+    /// Join pages into one document, as if separated by
+    /// [`page_break`](PageBuilder::page_break).
+    fn from_iter<I: IntoIterator<Item = PageBuilder>>(iter: I) -> Self {
+        let mut pages = Vec::new();
+        for mut page in iter {
+            pages.append(&mut page.previous);
+            pages.push(page);
+        }
+        match pages.pop() {
+            Some(mut last) => {
+                last.previous = pages;
+                last
+            }
+            None => PageBuilder::new(),
+        }
+    }
+}
+
+/// Iterator over the built pages of a [`PageBuilder`].
+///
+/// Created by [`PageBuilder::pages`]. Each item is one page's complete byte
+/// sequence, or the overflow error for that page.
+#[derive(Debug, Clone)]
+pub struct Pages<'a> {
+    previous: core::slice::Iter<'a, PageBuilder>,
+    current: Option<&'a PageBuilder>,
+}
+
+impl Iterator for Pages<'_> {
+    type Item = Result<Vec<u8>, ValidationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let page = self.previous.next().or_else(|| self.current.take())?;
+        Some(page.build_page())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.previous.len() + usize::from(self.current.is_some());
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Pages<'_> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,7 +942,7 @@ mod tests {
 
     #[test]
     fn empty_page_has_enter_and_ff() {
-        let page = PageBuilder::new().build();
+        let page = PageBuilder::new().build().unwrap();
 
         // Should start with ESC L (enter page mode)
         assert_eq!(&page[0..2], &[ESC, b'L']);
@@ -186,7 +953,7 @@ mod tests {
 
     #[test]
     fn page_with_area() {
-        let page = PageBuilder::new().area(PrintArea::default_80mm()).build();
+        let page = PageBuilder::new().area(PrintArea::default_80mm()).build().unwrap();
 
         // Should contain ESC W (set print area)
         assert!(page.windows(2).any(|w| w == [ESC, b'W']));
@@ -194,7 +961,7 @@ mod tests {
 
     #[test]
     fn page_with_direction() {
-        let page = PageBuilder::new().direction(PrintDirection::BottomToTop).build();
+        let page = PageBuilder::new().direction(PrintDirection::BottomToTop).build().unwrap();
 
         // Should contain ESC T 1 (set direction)
         assert!(page.windows(3).any(|w| w == [ESC, b'T', 1]));
@@ -202,7 +969,7 @@ mod tests {
 
     #[test]
     fn page_with_vertical_position() {
-        let page = PageBuilder::new().vertical_position(256).build();
+        let page = PageBuilder::new().vertical_position(256).build().unwrap();
 
         // Should contain GS $ 0 1 (position 256 = 0x0100)
         assert!(page.windows(4).any(|w| w == [GS, b'$', 0, 1]));
@@ -210,7 +977,7 @@ mod tests {
 
     #[test]
     fn page_with_text() {
-        let page = PageBuilder::new().text("Hello").build();
+        let page = PageBuilder::new().text("Hello").build().unwrap();
 
         assert!(page.windows(5).any(|w| w == b"Hello"));
     }
@@ -219,21 +986,549 @@ mod tests {
     fn page_with_raw_command() {
         use crate::command::paper::CutPaper;
 
-        let page = PageBuilder::new().command(CutPaper::full()).build();
+        let page = PageBuilder::new().command(CutPaper::full()).build().unwrap();
 
         // Should contain GS V 0 (full cut)
         assert!(page.windows(3).any(|w| w == [GS, b'V', 0]));
     }
 
+    #[test]
+    fn page_with_barcode_restores_settings() {
+        use crate::command::barcode::BarcodeSystem;
+
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "A1").unwrap();
+        let page = PageBuilder::new()
+            .position(40, 120)
+            .barcode_height(SetBarcodeHeight::new(80).unwrap())
+            .hri_position(HriPosition::Below)
+            .barcode(barcode.clone())
+            .build()
+            .unwrap();
+
+        let body = [
+            &[GS, b'h', 80][..],
+            &[GS, b'H', 2],
+            &barcode.encode(),
+            &[GS, b'h', 162, GS, b'w', 3, GS, b'H', 0, GS, b'f', 0, 0x0C],
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn page_with_2d_symbols() {
+        let qr = PrintQrCode::new("https://example.com").unwrap();
+        let pdf417 = PrintPdf417::new("BOARDING PASS");
+        let page = PageBuilder::new()
+            .direction(PrintDirection::BottomToTop)
+            .position(400, 200)
+            .qr_code(qr.clone())
+            .position(0, 200)
+            .pdf417(pdf417.clone())
+            .build()
+            .unwrap();
+
+        let body = [
+            &[ESC, b'$', 0x90, 1, GS, b'$', 200, 0][..],
+            &qr.encode(),
+            &[ESC, b'$', 0, 0, GS, b'$', 200, 0],
+            &pdf417.encode(),
+            &[0x0C],
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn unprintable_qr_code_fails_the_build() {
+        use crate::command::symbol::QrErrorCorrection;
+
+        let profile = PrinterProfile::default().with_qr_code(false);
+        let qr =
+            PrintQrCode::new(vec![b'x'; 2000]).unwrap().with_error_correction(QrErrorCorrection::H);
+        let page = PageBuilder::new().profile(profile).qr_code(qr).text("after");
+        #[cfg(feature = "qrcode")]
+        assert!(matches!(page.build(), Err(ValidationError::QrCode(_))));
+        #[cfg(not(feature = "qrcode"))]
+        assert!(matches!(page.build(), Err(ValidationError::Unsupported { .. })));
+        assert!(page.build_copies(2).is_err());
+        #[cfg(feature = "std")]
+        assert!(page.build_into(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn page_with_image() {
+        let logo = PrintRasterImage::new(1, 2, vec![0xFF, 0x81]);
+        let page = PageBuilder::new().position(100, 64).image(logo.clone()).build().unwrap();
+
+        let body = [&[ESC, b'$', 100, 0, GS, b'$', 64, 0][..], &logo.encode(), &[0x0C]].concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn page_with_dynamic_image() {
+        use ::image::{DynamicImage, GrayImage, Luma};
+
+        let logo = DynamicImage::ImageLuma8(GrayImage::from_pixel(8, 2, Luma([0])));
+        let page = PageBuilder::new().dynamic_image(&logo).build().unwrap();
+        assert!(page.ends_with(
+            &[&PrintRasterImage::new(1, 2, vec![0xFF; 2]).encode()[..], &[0x0C]].concat()
+        ));
+
+        let tall = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 70_000, Luma([0])));
+        let page = PageBuilder::new().dynamic_image(&tall);
+        assert!(matches!(page.build(), Err(ValidationError::ImageTooLarge { .. })));
+    }
+
+    #[test]
+    fn region_sets_and_restores_area_and_direction() {
+        let outer = PrintArea {
+            x: 0,
+            y: 0,
+            width: 400,
+            height: 300,
+        };
+        let inner = PrintArea {
+            x: 400,
+            y: 0,
+            width: 112,
+            height: 300,
+        };
+        let page = PageBuilder::new()
+            .area(outer)
+            .region(inner, PrintDirection::BottomToTop, |r| r.text("Stub"))
+            .text("Main")
+            .build()
+            .unwrap();
+
+        let body = [
+            &SetPrintArea(inner).encode()[..],
+            &[ESC, b'T', 1],
+            b"Stub",
+            &SetPrintArea(outer).encode(),
+            &[ESC, b'T', 0],
+            b"Main\x0C",
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn nested_regions_restore_enclosing_region() {
+        let left = PrintArea {
+            x: 0,
+            y: 0,
+            width: 256,
+            height: 200,
+        };
+        let corner = PrintArea {
+            x: 0,
+            y: 0,
+            width: 128,
+            height: 100,
+        };
+        let page = PageBuilder::new()
+            .region(left, PrintDirection::LeftToRight, |r| {
+                r.region(corner, PrintDirection::TopToBottom, |r| r.text("A")).text("B")
+            })
+            .build()
+            .unwrap();
+
+        let body = [
+            &SetPrintArea(left).encode()[..],
+            &[ESC, b'T', 0],
+            &SetPrintArea(corner).encode(),
+            &[ESC, b'T', 3],
+            b"A",
+            &SetPrintArea(left).encode(),
+            &[ESC, b'T', 0],
+            b"B",
+            &SetPrintArea(PrintArea::default_80mm()).encode(),
+            &[ESC, b'T', 0, 0x0C],
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn region_merges_barcode_settings() {
+        let page = PageBuilder::new()
+            .region(PrintArea::default_58mm(), PrintDirection::LeftToRight, |r| {
+                r.hri_font(HriFont::B)
+            })
+            .build()
+            .unwrap();
+        assert!(page.ends_with(&[GS, b'f', 0, 0x0C]));
+    }
+
+    #[test]
+    fn positions_convert_through_motion_units() {
+        use crate::command::spacing::{Dots, Mm};
+
+        let units = SetMotionUnits::new(180, 180);
+        let page = PageBuilder::new()
+            .motion_units(units)
+            .area(PrintArea::from_lengths(Mm(0.0), Mm(0.0), Mm(72.0), Mm(100.0), &units))
+            .position(Mm(25.4), Dots(90))
+            .build()
+            .unwrap();
+
+        let expected = [
+            &[ESC, b'L', GS, b'P', 180, 180][..],
+            &SetPrintArea(PrintArea {
+                x: 0,
+                y: 0,
+                width: 510,
+                height: 709,
+            })
+            .encode(),
+            &[ESC, b'$', 180, 0, GS, b'$', 90, 0],
+            &[GS, b'P', 0, 0, 0x0C],
+        ]
+        .concat();
+        assert_eq!(page, expected);
+    }
+
+    #[test]
+    fn default_units_keep_bare_positions() {
+        use crate::command::spacing::Dots;
+
+        let page =
+            PageBuilder::new().vertical_position(Dots(10)).vertical_position(10).build().unwrap();
+        assert!(page.ends_with(&[GS, b'$', 20, 0, GS, b'$', 10, 0, 0x0C]));
+    }
+
+    #[test]
+    fn text_box_wraps_and_clips() {
+        use crate::style::text::Styleable;
+
+        // 120 dots wide fits 10 Font A characters; 70 dots tall fits two
+        // lines at 30 dot spacing (24 dot glyphs)
+        let area = PrintArea {
+            x: 100,
+            y: 50,
+            width: 120,
+            height: 140,
+        };
+        let page = PageBuilder::new()
+            .text_box(area, StyledNode::text("Grilled ") + "cheese".bold() + " sandwich")
+            .build()
+            .unwrap();
+
+        let body = [
+            &SetPrintArea(area).encode()[..],
+            &[ESC, b'T', 0],
+            &[ESC, b'$', 0, 0, GS, b'$', 48, 0],
+            b"Grilled",
+            &[ESC, b'$', 0, 0, GS, b'$', 108, 0],
+            &"cheese".bold().render(),
+            &SetPrintArea(PrintArea::default_80mm()).encode(),
+            &[ESC, b'T', 0, 0x0C],
+        ]
+        .concat();
+        assert_eq!(&page[2..], &body[..]);
+    }
+
+    #[test]
+    fn text_box_in_rotated_page_wraps_along_height() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 512,
+            height: 240,
+        };
+        let page = PageBuilder::new()
+            .direction(PrintDirection::BottomToTop)
+            .text_box(area, "abcdefghijkl")
+            .build()
+            .unwrap();
+
+        // 240 vertical units are 120 dots: 10 characters per line, with
+        // lines advancing in horizontal units
+        let body = [
+            &[ESC, b'$', 0, 0, GS, b'$', 24, 0][..],
+            b"abcdefghij",
+            &[ESC, b'$', 0, 0, GS, b'$', 54, 0],
+            b"kl",
+        ]
+        .concat();
+        assert!(page.windows(body.len()).any(|window| window == body));
+    }
+
+    #[test]
+    fn hline_spans_the_page_area() {
+        let page = PageBuilder::new()
+            .area(PrintArea {
+                x: 0,
+                y: 0,
+                width: 20,
+                height: 200,
+            })
+            .hline(10u16, 2)
+            .build()
+            .unwrap();
+
+        // Top edge at 10 units (5 dots), 2 dots thick: baseline at 7 dots
+        let body = [
+            &[ESC, b'$', 0, 0, GS, b'$', 14, 0][..],
+            &PrintRasterImage::new(3, 2, vec![0xFF, 0xFF, 0xF0, 0xFF, 0xFF, 0xF0]).encode(),
+            &[0x0C],
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn vline_spans_the_page_depth() {
+        let page = PageBuilder::new()
+            .area(PrintArea {
+                x: 0,
+                y: 0,
+                width: 512,
+                height: 8,
+            })
+            .vline(Dots(100), 3)
+            .build()
+            .unwrap();
+
+        let body = [
+            &[ESC, b'$', 100, 0, GS, b'$', 8, 0][..],
+            &PrintRasterImage::new(1, 4, vec![0xE0; 4]).encode(),
+            &[0x0C],
+        ]
+        .concat();
+        assert!(page.ends_with(&body));
+    }
+
+    #[test]
+    fn rect_draws_four_sides_in_a_region() {
+        let area = PrintArea {
+            x: 16,
+            y: 0,
+            width: 16,
+            height: 16,
+        };
+        let page = PageBuilder::new().rect(area, 1).build().unwrap();
+
+        let top = PrintRasterImage::new(2, 1, vec![0xFF, 0xFF]).encode();
+        let side = PrintRasterImage::new(1, 8, vec![0x80; 8]).encode();
+        let body = [
+            &SetPrintArea(area).encode()[..],
+            &[ESC, b'T', 0],
+            &[ESC, b'$', 0, 0, GS, b'$', 2, 0],
+            &top,
+            &[ESC, b'$', 0, 0, GS, b'$', 16, 0],
+            &top,
+            &[ESC, b'$', 0, 0, GS, b'$', 16, 0],
+            &side,
+            &[ESC, b'$', 15, 0, GS, b'$', 16, 0],
+            &side,
+            &SetPrintArea(PrintArea::default_80mm()).encode(),
+        ]
+        .concat();
+        assert!(page.windows(body.len()).any(|window| window == body));
+    }
+
+    #[test]
+    fn text_past_the_area_overflows() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 512,
+            height: 120,
+        };
+        // Three lines: baselines at 24, 54 and 84 dots; the area is 60 deep
+        let page = PageBuilder::new().area(area).text("one\ntwo\nthree");
+        assert_eq!(page.overflow(), Some((84, 60)));
+        assert!(matches!(
+            page.build(),
+            Err(ValidationError::PageOverflow {
+                needed: 84,
+                available: 60,
+            })
+        ));
+
+        let fits = PageBuilder::new().area(area).text_line("one").text_line("two");
+        assert_eq!(fits.overflow(), None);
+    }
+
+    #[test]
+    fn long_text_overflows_after_wrapping() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 120,
+            height: 100,
+        };
+        assert!(PageBuilder::new().area(area).text("x".repeat(10)).build().is_ok());
+        assert!(PageBuilder::new().area(area).text("x".repeat(30)).build().is_err());
+    }
+
+    #[test]
+    fn positioned_content_past_the_area_overflows() {
+        let page = PageBuilder::new().vertical_position(Dots(900)).image(PrintRasterImage::new(
+            1,
+            1,
+            vec![0xFF],
+        ));
+        assert_eq!(page.overflow(), Some((900, 831)));
+
+        // Positions alone are not content
+        assert_eq!(PageBuilder::new().vertical_position(Dots(900)).overflow(), None);
+    }
+
+    #[test]
+    fn region_overflow_is_reported() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 256,
+            height: 40,
+        };
+        let page = PageBuilder::new().region(area, PrintDirection::LeftToRight, |r| r.text("a\nb"));
+        assert_eq!(page.overflow(), Some((54, 20)));
+    }
+
+    #[test]
+    fn page_break_in_region_continues_on_the_next_page() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 256,
+            height: 200,
+        };
+        let page = PageBuilder::new()
+            .text("a")
+            .region(area, PrintDirection::TopToBottom, |r| r.text("b").page_break().text("c"));
+        assert_eq!(page.page_count(), 2);
+
+        let pages: Vec<_> = page.pages().map(Result::unwrap).collect();
+        let region = |text: &[u8]| {
+            [
+                &SetPrintArea(area).encode()[..],
+                &[ESC, b'T', 3],
+                text,
+                &SetPrintArea(PrintArea::default_80mm()).encode(),
+                &[ESC, b'T', 0],
+            ]
+            .concat()
+        };
+        assert!(pages[0].ends_with(&[&b"a"[..], &region(b"b"), &[0x0C]].concat()));
+        assert!(pages[1].ends_with(&[region(b"c"), vec![0x0C]].concat()));
+    }
+
+    #[test]
+    fn page_break_builds_separate_pages() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 256,
+            height: 200,
+        };
+        let page = PageBuilder::new().area(area).text("one").page_break().text("two");
+        assert_eq!(page.page_count(), 2);
+        assert_eq!(page.len(), 2);
+
+        let first = [&[ESC, b'L'][..], &SetPrintArea(area).encode(), b"one\x0C"].concat();
+        let second = [&[ESC, b'L'][..], &SetPrintArea(area).encode(), b"two\x0C"].concat();
+        assert_eq!(page.build().unwrap(), [first.clone(), second.clone()].concat());
+
+        let pages: Vec<_> = page.pages().map(Result::unwrap).collect();
+        assert_eq!(pages, [first, second]);
+    }
+
+    #[test]
+    fn page_break_resets_cursor_and_overflow_is_per_page() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 512,
+            height: 120,
+        };
+        let page = PageBuilder::new().area(area).text_line("a").text_line("b");
+        assert!(page.clone().text("c").build().is_err());
+        assert!(page.clone().page_break().text("c").build().is_ok());
+
+        let overflowing = PageBuilder::new().area(area).page_break().text("a\nb\nc");
+        let results: Vec<_> = overflowing.pages().map(|page| page.is_ok()).collect();
+        assert_eq!(results, [true, false]);
+    }
+
+    #[test]
+    fn pages_collect_into_one_document() {
+        let page: PageBuilder =
+            ["A", "B", "C"].into_iter().map(|label| PageBuilder::new().text(label)).collect();
+        assert_eq!(page.page_count(), 3);
+        assert_eq!(page.build().unwrap(), b"\x1bLA\x0C\x1bLB\x0C\x1bLC\x0C");
+
+        let copies = page.build_copies(2).unwrap();
+        assert_eq!(copies.windows(2).filter(|w| *w == [ESC, 0x0C]).count(), 3);
+    }
+
+    #[test]
+    fn build_into_matches_build() {
+        let page = PageBuilder::new()
+            .area(PrintArea::default_58mm())
+            .text_line("one")
+            .image(PrintRasterImage::new(2, 2, vec![0xAA; 4]))
+            .page_break()
+            .text("two");
+
+        let mut streamed = Vec::new();
+        page.build_into(&mut streamed).unwrap();
+        assert_eq!(streamed, page.build().unwrap());
+    }
+
+    #[test]
+    fn build_into_checks_every_page_before_writing() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 512,
+            height: 60,
+        };
+        let page = PageBuilder::new().area(area).text("fits").page_break().text("a\nb\nc");
+
+        let mut streamed = Vec::new();
+        let result = page.build_into(&mut streamed);
+        assert!(matches!(
+            result,
+            Err(PrinterError::Validation(ValidationError::PageOverflow { .. }))
+        ));
+        assert!(streamed.is_empty());
+    }
+
+    #[test]
+    fn page_without_barcode_settings_does_not_restore() {
+        let page = PageBuilder::new().text("x").build().unwrap();
+        assert!(!page.windows(2).any(|w| w == [GS, b'h']));
+    }
+
     #[test]
     fn build_and_exit_adds_exit_command() {
-        let page = PageBuilder::new().build_and_exit();
+        let page = PageBuilder::new().build_and_exit().unwrap();
 
         // Should end with ESC S (exit page mode) after FF
         let len = page.len();
         assert_eq!(&page[len - 2..], &[ESC, b'S']);
     }
 
+    #[test]
+    fn build_copies_repeats_print() {
+        let page = PageBuilder::new().text("Hi").build_copies(3).unwrap();
+
+        // Two ESC FF prints, then the final FF
+        assert_eq!(page.windows(2).filter(|w| *w == [ESC, 0x0C]).count(), 2);
+        assert!(page.ends_with(&[ESC, 0x0C, ESC, 0x0C, 0x0C]));
+    }
+
+    #[test]
+    fn build_copies_zero_is_single() {
+        let page = PageBuilder::new().text("Hi");
+        assert_eq!(page.build_copies(0).unwrap(), page.build().unwrap());
+    }
+
     #[test]
     fn is_empty_and_len() {
         let empty = PageBuilder::new();
@@ -255,7 +1550,8 @@ mod tests {
             .text_line("Header".bold())
             .vertical_position(100)
             .text("Body")
-            .build();
+            .build()
+            .unwrap();
 
         // Verify structure
         let bytes = page;
@@ -0,0 +1,967 @@
+//! PNG previews of page mode output.
+//!
+//! Simulates the printer's page buffer for a page mode job, so layouts can
+//! be checked without printing. Positions, print areas, motion units, the
+//! four print directions, character fonts and sizes, emphasis, underline,
+//! reverse printing and raster images are drawn as the printer would place
+//! them. Glyphs come from the X11 fixed fonts bundled with
+//! `embedded-graphics`, so the shapes differ from the printer's own fonts
+//! while the cell sizes match.
+//!
+//! Barcodes and 2D symbols are drawn as outlined boxes of roughly their
+//! printed size. Text outside page mode is ignored.
+//!
+//! ```
+//! use bixolon::page::PageBuilder;
+//! use bixolon::style::text::Styleable;
+//!
+//! let page = PageBuilder::new().position(100, 60).text("Table 12".double_size());
+//! let previews = page.preview()?;
+//! let png = previews[0].to_png()?;
+//! assert!(png.starts_with(b"\x89PNG"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::{self, Write};
+
+use embedded_graphics::Drawable;
+use embedded_graphics::mono_font::iso_8859_1::{FONT_9X15, FONT_10X20};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Pixel, Point, Size};
+use embedded_graphics::text::{Baseline, Text};
+
+use super::PageBuilder;
+use crate::command::character::Font;
+use crate::command::codepage::CodePage;
+use crate::command::page_mode::{PrintArea, PrintDirection};
+use crate::command::spacing::SetMotionUnits;
+use crate::command::{CAN, CR, DLE, ESC, FF, FS, GS, HT, LF};
+use crate::error::ValidationError;
+use crate::profile::PrinterProfile;
+
+/// One printed page, one pixel per dot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PagePreview {
+    width: u32,
+    height: u32,
+    dots: Vec<bool>,
+}
+
+impl PagePreview {
+    /// Width in dots.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in dots.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Whether the dot at (`x`, `y`) is printed. Out of range dots are not.
+    pub fn is_black(&self, x: u32, y: u32) -> bool {
+        x < self.width
+            && y < self.height
+            && self.dots[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Encode as a 1-bit grayscale PNG.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `writer` fails.
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+
+        let row_bytes = self.width.div_ceil(8) as usize;
+        let mut data = vec![0xFF; row_bytes * self.height as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_black(x, y) {
+                    data[y as usize * row_bytes + x as usize / 8] &= !(0x80 >> (x % 8));
+                }
+            }
+        }
+        writer.write_image_data(&data).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    /// Encode as a 1-bit grayscale PNG in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if PNG encoding fails.
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut png = Vec::new();
+        self.write_png(&mut png)?;
+        Ok(png)
+    }
+}
+
+impl PageBuilder {
+    /// Render every page to a [`PagePreview`].
+    ///
+    /// Text is measured and drawn with the builder's
+    /// [`profile`](Self::profile).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::PageOverflow`] as for [`build`](Self::build).
+    pub fn preview(&self) -> Result<Vec<PagePreview>, ValidationError> {
+        Ok(render_with_profile(&self.build()?, &self.profile))
+    }
+}
+
+/// Render the pages printed by a raw ESC/POS job.
+///
+/// A page is produced for each `FF` or `ESC FF` issued in page mode.
+pub fn render(job: &[u8]) -> Vec<PagePreview> {
+    render_with_profile(job, &PrinterProfile::default())
+}
+
+/// Render the pages printed by a raw ESC/POS job on a specific printer.
+pub fn render_with_profile(job: &[u8], profile: &PrinterProfile) -> Vec<PagePreview> {
+    let mut simulator = Simulator::new(profile.clone());
+    simulator.feed(job);
+    simulator.pages
+}
+
+/// Default line spacing of 1/6 inch, in dots.
+pub(crate) const LINE_SPACING: u32 = 30;
+
+/// Longest page mode area drawn, in dots.
+///
+/// Printers accept far shorter areas; the limit keeps a corrupt `ESC W`
+/// from allocating gigabytes of paper.
+const MAX_PAGE_LENGTH: u32 = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Style {
+    bold: bool,
+    underline: u8,
+    reverse: bool,
+    font: Font,
+    width: u32,
+    height: u32,
+}
+
+struct Simulator {
+    profile: PrinterProfile,
+    pages: Vec<PagePreview>,
+    in_page_mode: bool,
+    canvas: Canvas,
+    units: SetMotionUnits,
+    area: PrintArea,
+    direction: PrintDirection,
+    /// Position along the print direction, in dots from the area's start.
+    x: u32,
+    /// Baseline of the current line, in dots from the area's start.
+    baseline: Option<u32>,
+    /// Tallest character on the current line, in dots.
+    line_height: u32,
+    line_spacing: u32,
+    style: Style,
+    code_page: Option<CodePage>,
+    barcode_height: u32,
+    barcode_module: u32,
+    symbol_module: u32,
+    glyphs: HashMap<(char, Font), Vec<bool>>,
+}
+
+impl Simulator {
+    fn new(profile: PrinterProfile) -> Self {
+        let mut simulator = Self {
+            profile,
+            pages: Vec::new(),
+            in_page_mode: false,
+            canvas: Canvas::default(),
+            units: SetMotionUnits::default(),
+            area: PrintArea::default_80mm(),
+            direction: PrintDirection::LeftToRight,
+            x: 0,
+            baseline: None,
+            line_height: 0,
+            line_spacing: LINE_SPACING,
+            style: Style::default(),
+            code_page: None,
+            barcode_height: 162,
+            barcode_module: 3,
+            symbol_module: 3,
+            glyphs: HashMap::new(),
+        };
+        simulator.reset();
+        simulator
+    }
+
+    fn reset(&mut self) {
+        self.in_page_mode = false;
+        self.units = SetMotionUnits::default();
+        self.area = PrintArea::default_80mm();
+        self.direction = PrintDirection::LeftToRight;
+        self.line_spacing = LINE_SPACING;
+        self.style = Style {
+            width: 1,
+            height: 1,
+            ..Style::default()
+        };
+        self.code_page = None;
+        self.barcode_height = 162;
+        self.barcode_module = 3;
+        self.symbol_module = 3;
+        self.clear();
+    }
+
+    /// Empty the page buffer and return to the start of the area.
+    fn clear(&mut self) {
+        self.canvas = Canvas::default();
+        self.canvas.grow(
+            u32::from(self.profile.paper_width_dots),
+            self.area_dots().1 + self.area_origin().1,
+        );
+        self.home();
+    }
+
+    fn home(&mut self) {
+        self.x = 0;
+        self.baseline = None;
+        self.line_height = 0;
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            i += 1;
+            match byte {
+                ESC => i += self.escape(&bytes[i..]),
+                GS => i += self.group_separator(&bytes[i..]),
+                FS => i += self.file_separator(&bytes[i..]),
+                DLE => {
+                    let consumed = match bytes.get(i) {
+                        Some(0x14) => 4,
+                        _ => 2,
+                    };
+                    i += consumed.min(bytes.len() - i);
+                }
+                LF => self.line_feed(),
+                FF => {
+                    if self.in_page_mode {
+                        self.print();
+                        self.reset();
+                    }
+                }
+                CAN => {
+                    if self.in_page_mode {
+                        self.clear();
+                    }
+                }
+                HT => {
+                    let cell = self.cell_width();
+                    self.x = (self.x / (cell * 8) + 1) * cell * 8;
+                }
+                CR => {}
+                _ if byte < 0x20 || byte == 0x7F => {}
+                _ => {
+                    let end = bytes[i..]
+                        .iter()
+                        .position(|&b| b < 0x20 || b == 0x7F)
+                        .map_or(bytes.len(), |n| i + n);
+                    self.text(&bytes[i - 1..end]);
+                    i = end;
+                }
+            }
+        }
+    }
+
+    /// Handle `ESC x ...`, returning the number of bytes consumed.
+    fn escape(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let arg = rest.get(1).copied().unwrap_or(0);
+        let consumed = match command {
+            b'@' => {
+                self.reset();
+                1
+            }
+            b'L' => {
+                self.in_page_mode = true;
+                self.clear();
+                1
+            }
+            b'S' => {
+                self.in_page_mode = false;
+                1
+            }
+            FF => {
+                if self.in_page_mode {
+                    self.print();
+                }
+                1
+            }
+            b'W' => {
+                self.area = PrintArea {
+                    x: arg_u16(rest, 1),
+                    y: arg_u16(rest, 3),
+                    width: arg_u16(rest, 5),
+                    height: arg_u16(rest, 7),
+                };
+                let (x, y) = self.area_origin();
+                let (width, height) = self.area_dots();
+                self.canvas.grow(x + width, y + height);
+                self.home();
+                9
+            }
+            b'T' => {
+                self.direction = match arg & 0x03 {
+                    1 => PrintDirection::BottomToTop,
+                    2 => PrintDirection::RightToLeft,
+                    3 => PrintDirection::TopToBottom,
+                    _ => PrintDirection::LeftToRight,
+                };
+                self.home();
+                2
+            }
+            b'$' => {
+                self.x = self.along_dots(arg_u16(rest, 1));
+                3
+            }
+            b'\\' => {
+                let offset = arg_u16(rest, 1) as i16;
+                let dots = self.along_dots(offset.unsigned_abs());
+                self.x = if offset < 0 {
+                    self.x.saturating_sub(dots)
+                } else {
+                    self.x + dots
+                };
+                3
+            }
+            b'J' => {
+                let dots = self.across_dots(u16::from(arg));
+                self.baseline = Some(self.baseline.unwrap_or(0) + dots);
+                self.x = 0;
+                2
+            }
+            b'd' => {
+                for _ in 0..arg {
+                    self.line_feed();
+                }
+                2
+            }
+            b'2' => {
+                self.line_spacing = LINE_SPACING;
+                1
+            }
+            b'3' => {
+                self.line_spacing = self.across_dots(u16::from(arg));
+                2
+            }
+            b'!' => {
+                self.style.font = if arg & 0x01 != 0 {
+                    Font::B
+                } else {
+                    Font::A
+                };
+                self.style.bold = arg & 0x08 != 0;
+                self.style.height = if arg & 0x10 != 0 {
+                    2
+                } else {
+                    1
+                };
+                self.style.width = if arg & 0x20 != 0 {
+                    2
+                } else {
+                    1
+                };
+                self.style.underline = u8::from(arg & 0x80 != 0);
+                2
+            }
+            b'E' | b'G' => {
+                self.style.bold = arg & 1 == 1;
+                2
+            }
+            b'-' => {
+                self.style.underline = match arg {
+                    1 | b'1' => 1,
+                    2 | b'2' => 2,
+                    _ => 0,
+                };
+                2
+            }
+            b'M' => {
+                self.style.font = if matches!(arg, 1 | b'1') {
+                    Font::B
+                } else {
+                    Font::A
+                };
+                2
+            }
+            b't' => {
+                self.code_page = Some(CodePage::from_byte(arg).unwrap_or_default());
+                2
+            }
+            b'*' => {
+                let count = usize::from(arg_u16(rest, 2));
+                let bytes_per_column = if arg >= 32 {
+                    3
+                } else {
+                    1
+                };
+                4 + count * bytes_per_column
+            }
+            b'D' => rest.iter().position(|&b| b == 0).map_or(rest.len(), |n| n + 1),
+            b'p' => 4,
+            b'c' => 3,
+            b'i' | b'm' | b'<' => 1,
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    /// Handle `GS x ...`, returning the number of bytes consumed.
+    fn group_separator(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let arg = rest.get(1).copied().unwrap_or(0);
+        let consumed = match command {
+            b'$' => {
+                self.baseline = Some(self.across_dots(arg_u16(rest, 1)));
+                3
+            }
+            b'!' => {
+                self.style.width = u32::from(arg >> 4) + 1;
+                self.style.height = u32::from(arg & 0x0F) + 1;
+                2
+            }
+            b'B' => {
+                self.style.reverse = arg & 1 == 1;
+                2
+            }
+            b'P' => {
+                self.units = SetMotionUnits::new(arg, rest.get(2).copied().unwrap_or(0));
+                3
+            }
+            b'h' => {
+                self.barcode_height = u32::from(arg);
+                2
+            }
+            b'w' => {
+                self.barcode_module = u32::from(arg);
+                2
+            }
+            b'v' => {
+                let width_bytes = usize::from(arg_u16(rest, 3));
+                let height = usize::from(arg_u16(rest, 5));
+                let start = 7.min(rest.len());
+                let end = (7 + width_bytes * height).min(rest.len());
+                self.raster(arg_mode(rest.get(2).copied()), width_bytes, &rest[start..end]);
+                7 + width_bytes * height
+            }
+            b'k' => {
+                let (data_len, consumed) = if arg <= 6 {
+                    let data = &rest[2.min(rest.len())..];
+                    let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                    (len, 2 + len + 1)
+                } else {
+                    let len = usize::from(rest.get(2).copied().unwrap_or(0));
+                    (len, 3 + len)
+                };
+                // About 11 modules per character plus quiet zones and guards
+                let width = (data_len as u32 * 11 + 35) * self.barcode_module;
+                self.placeholder(width, self.barcode_height);
+                consumed
+            }
+            b'(' => {
+                let len = usize::from(arg_u16(rest, 1));
+                if arg == b'k' {
+                    let function = rest.get(5).copied();
+                    match function {
+                        Some(b'C') => {
+                            self.symbol_module = u32::from(rest.get(6).copied().unwrap_or(3))
+                        }
+                        Some(b'Q') => {
+                            // Roughly a version 2 QR code
+                            let side = 25 * self.symbol_module;
+                            self.placeholder(side, side);
+                        }
+                        _ => {}
+                    }
+                }
+                3 + len
+            }
+            b'V' => {
+                if arg >= 65 {
+                    3
+                } else {
+                    2
+                }
+            }
+            b'*' => {
+                let size = usize::from(arg) * usize::from(rest.get(2).copied().unwrap_or(0)) * 8;
+                3 + size
+            }
+            b'^' => 4,
+            b'L' | b'W' | b'\\' => 3,
+            b':' => 1,
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    /// Handle `FS x ...`, returning the number of bytes consumed.
+    fn file_separator(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let consumed = match command {
+            b'&' | b'.' => 1,
+            b'p' => 3,
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    fn print(&mut self) {
+        self.pages.push(self.canvas.to_preview());
+    }
+
+    /// Motion units per inch (along, across) the print direction.
+    fn units_per_inch(&self) -> (u16, u16) {
+        let horizontal = self.units.horizontal_per_inch();
+        let vertical = self.units.vertical_per_inch();
+        match self.direction {
+            PrintDirection::BottomToTop | PrintDirection::TopToBottom => (vertical, horizontal),
+            _ => (horizontal, vertical),
+        }
+    }
+
+    fn along_dots(&self, units: u16) -> u32 {
+        units_to_dots(units, self.units_per_inch().0)
+    }
+
+    fn across_dots(&self, units: u16) -> u32 {
+        units_to_dots(units, self.units_per_inch().1)
+    }
+
+    /// Top-left corner of the print area on the paper, in dots.
+    ///
+    /// Clamped to the paper width and [`MAX_PAGE_LENGTH`].
+    fn area_origin(&self) -> (u32, u32) {
+        (
+            units_to_dots(self.area.x, self.units.horizontal_per_inch())
+                .min(u32::from(self.profile.paper_width_dots)),
+            units_to_dots(self.area.y, self.units.vertical_per_inch()).min(MAX_PAGE_LENGTH),
+        )
+    }
+
+    /// Width and height of the print area on the paper, in dots.
+    ///
+    /// Clamped so the area ends within the paper width and
+    /// [`MAX_PAGE_LENGTH`].
+    fn area_dots(&self) -> (u32, u32) {
+        let (x, y) = self.area_origin();
+        (
+            units_to_dots(self.area.width, self.units.horizontal_per_inch())
+                .min(u32::from(self.profile.paper_width_dots) - x),
+            units_to_dots(self.area.height, self.units.vertical_per_inch())
+                .min(MAX_PAGE_LENGTH - y),
+        )
+    }
+
+    /// Length of a line and depth of the area in the print direction.
+    fn line_extent(&self) -> (u32, u32) {
+        let (width, height) = self.area_dots();
+        match self.direction {
+            PrintDirection::BottomToTop | PrintDirection::TopToBottom => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// Set the dot at `along`, `across` in print direction coordinates.
+    fn set(&mut self, along: u32, across: u32, black: bool) {
+        let (line, depth) = self.line_extent();
+        if along >= line || across >= depth {
+            return;
+        }
+        let (width, height) = self.area_dots();
+        let (x, y) = match self.direction {
+            PrintDirection::LeftToRight => (along, across),
+            PrintDirection::BottomToTop => (across, height - 1 - along),
+            PrintDirection::RightToLeft => (width - 1 - along, height - 1 - across),
+            PrintDirection::TopToBottom => (width - 1 - across, along),
+        };
+        let (origin_x, origin_y) = self.area_origin();
+        self.canvas.set(origin_x + x, origin_y + y, black);
+    }
+
+    fn cell_width(&self) -> u32 {
+        u32::from(self.profile.char_width_dots(self.style.font)) * self.style.width
+    }
+
+    fn cell_height(&self) -> u32 {
+        u32::from(self.profile.char_height_dots(self.style.font)) * self.style.height
+    }
+
+    /// Baseline for content `height` dots tall, starting a line if needed.
+    fn baseline_for(&mut self, height: u32) -> u32 {
+        let baseline = *self.baseline.get_or_insert(height);
+        self.line_height = self.line_height.max(height);
+        baseline
+    }
+
+    fn line_feed(&mut self) {
+        let advance = self.line_spacing.max(self.line_height);
+        let baseline = self.baseline.unwrap_or(self.cell_height());
+        self.baseline = Some(baseline + advance);
+        self.x = 0;
+        self.line_height = 0;
+    }
+
+    fn text(&mut self, bytes: &[u8]) {
+        let decoded: Vec<char> = match (self.code_page, std::str::from_utf8(bytes)) {
+            (None, Ok(text)) => text.chars().collect(),
+            (page, _) => {
+                let page = page.unwrap_or_default();
+                bytes.iter().map(|&b| page.decode_byte(b).unwrap_or('?')).collect()
+            }
+        };
+        if !self.in_page_mode {
+            return;
+        }
+        for c in decoded {
+            self.character(c);
+        }
+    }
+
+    fn character(&mut self, c: char) {
+        let (cell_width, cell_height) = (self.cell_width(), self.cell_height());
+        if self.x > 0 && self.x + cell_width > self.line_extent().0 {
+            self.line_feed();
+        }
+        let baseline = self.baseline_for(cell_height);
+        let top = baseline.saturating_sub(cell_height);
+        let style = self.style;
+
+        let font = font(style.font);
+        let glyph = self.glyph(c, style.font);
+        let glyph_width = font.character_size.width;
+        let glyph_height = font.character_size.height;
+        let base_width = u32::from(self.profile.char_width_dots(style.font));
+        let base_height = u32::from(self.profile.char_height_dots(style.font));
+        let offset_x = base_width.saturating_sub(glyph_width) / 2;
+        let offset_y = base_height.saturating_sub(glyph_height) / 2;
+
+        for row in 0..base_height {
+            for column in 0..base_width {
+                let (gx, gy) = (column.wrapping_sub(offset_x), row.wrapping_sub(offset_y));
+                let lit = |gx: u32| {
+                    gx < glyph_width && gy < glyph_height && glyph[(gy * glyph_width + gx) as usize]
+                };
+                let mut black = lit(gx) || (style.bold && gx > 0 && lit(gx - 1));
+                if style.underline > 0 && row >= base_height - u32::from(style.underline) {
+                    black = true;
+                }
+                if style.reverse {
+                    black = !black;
+                }
+                for dy in 0..style.height {
+                    for dx in 0..style.width {
+                        self.set(
+                            self.x + column * style.width + dx,
+                            top + row * style.height + dy,
+                            black,
+                        );
+                    }
+                }
+            }
+        }
+        self.x += cell_width;
+    }
+
+    fn glyph(&mut self, c: char, font_choice: Font) -> Vec<bool> {
+        self.glyphs.entry((c, font_choice)).or_insert_with(|| draw_glyph(c, font_choice)).clone()
+    }
+
+    fn raster(&mut self, (scale_x, scale_y): (u32, u32), width_bytes: usize, data: &[u8]) {
+        if width_bytes == 0 {
+            return;
+        }
+        let rows = data.len() / width_bytes;
+        let height = rows as u32 * scale_y;
+        let baseline = self.baseline_for(height);
+        let top = baseline.saturating_sub(height);
+        for (row, bytes) in data.chunks(width_bytes).enumerate() {
+            for (byte_index, &byte) in bytes.iter().enumerate() {
+                for bit in 0..8 {
+                    if byte & (0x80 >> bit) == 0 {
+                        continue;
+                    }
+                    let column = (byte_index * 8 + bit) as u32;
+                    for dy in 0..scale_y {
+                        for dx in 0..scale_x {
+                            self.set(
+                                self.x + column * scale_x + dx,
+                                top + row as u32 * scale_y + dy,
+                                true,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Outline a box standing on the baseline at the current position.
+    fn placeholder(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let baseline = self.baseline_for(height);
+        let top = baseline.saturating_sub(height);
+        for along in 0..width {
+            self.set(self.x + along, top, true);
+            self.set(self.x + along, baseline - 1, true);
+        }
+        for across in top..baseline {
+            self.set(self.x, across, true);
+            self.set(self.x + width - 1, across, true);
+        }
+    }
+}
+
+/// Scale factors for a `GS v 0` mode byte.
+fn arg_mode(mode: Option<u8>) -> (u32, u32) {
+    match mode.unwrap_or(0) & 0x03 {
+        1 => (2, 1),
+        2 => (1, 2),
+        3 => (2, 2),
+        _ => (1, 1),
+    }
+}
+
+/// Dots of `c` in `font_choice`'s bitmap font, row by row.
+pub(crate) fn draw_glyph(c: char, font_choice: Font) -> Vec<bool> {
+    let font = font(font_choice);
+    let mut glyph = Glyph {
+        size: font.character_size,
+        dots: vec![false; (font.character_size.width * font.character_size.height) as usize],
+    };
+    let mut buffer = [0u8; 4];
+    let text = c.encode_utf8(&mut buffer);
+    let style = MonoTextStyle::new(font, BinaryColor::On);
+    let _ = Text::with_baseline(text, Point::zero(), style, Baseline::Top).draw(&mut glyph);
+    glyph.dots
+}
+
+pub(crate) fn font(font: Font) -> &'static MonoFont<'static> {
+    match font {
+        Font::A => &FONT_10X20,
+        Font::B => &FONT_9X15,
+    }
+}
+
+pub(crate) fn units_to_dots(units: u16, per_inch: u16) -> u32 {
+    u32::from(units) * 180 / u32::from(per_inch)
+}
+
+/// Little-endian `u16` at `offset`, or 0 if the stream is truncated.
+fn arg_u16(rest: &[u8], offset: usize) -> u16 {
+    match (rest.get(offset), rest.get(offset + 1)) {
+        (Some(&low), Some(&high)) => u16::from_le_bytes([low, high]),
+        _ => 0,
+    }
+}
+
+/// The paper, growing as print areas reach further.
+#[derive(Default)]
+pub(crate) struct Canvas {
+    width: u32,
+    height: u32,
+    dots: Vec<bool>,
+}
+
+impl Canvas {
+    /// Grow to at least `width` by `height` dots.
+    ///
+    /// Sizes too large to address are ignored, leaving the canvas as it was.
+    pub(crate) fn grow(&mut self, width: u32, height: u32) {
+        if width <= self.width && height <= self.height {
+            return;
+        }
+        let (new_width, new_height) = (width.max(self.width), height.max(self.height));
+        let Some(len) = (new_width as usize).checked_mul(new_height as usize) else {
+            return;
+        };
+        let mut dots = vec![false; len];
+        let old_width = self.width as usize;
+        for y in 0..self.height as usize {
+            let old = y * old_width;
+            let new = y * new_width as usize;
+            dots[new..new + old_width].copy_from_slice(&self.dots[old..old + old_width]);
+        }
+        self.width = new_width;
+        self.height = new_height;
+        self.dots = dots;
+    }
+
+    pub(crate) fn set(&mut self, x: u32, y: u32, black: bool) {
+        if x < self.width && y < self.height {
+            self.dots[y as usize * self.width as usize + x as usize] = black;
+        }
+    }
+
+    pub(crate) fn to_preview(&self) -> PagePreview {
+        PagePreview {
+            width: self.width,
+            height: self.height,
+            dots: self.dots.clone(),
+        }
+    }
+}
+
+/// Target for drawing one glyph with `embedded-graphics`.
+struct Glyph {
+    size: Size,
+    dots: Vec<bool>,
+}
+
+impl OriginDimensions for Glyph {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Glyph {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y))
+                && x < self.size.width
+                && y < self.size.height
+            {
+                self.dots[(y * self.size.width + x) as usize] = color.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::image::PrintRasterImage;
+    use crate::style::text::Styleable;
+
+    /// Bounding box of printed dots as (left, top, right, bottom), inclusive.
+    fn bounds(page: &PagePreview) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for y in 0..page.height() {
+            for x in 0..page.width() {
+                if page.is_black(x, y) {
+                    let (l, t, r, b) = bounds.unwrap_or((x, y, x, y));
+                    bounds = Some((l.min(x), t.min(y), r.max(x), b.max(y)));
+                }
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn empty_page_is_blank_and_area_sized() {
+        let pages = PageBuilder::new().preview().unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!((pages[0].width(), pages[0].height()), (512, 831));
+        assert_eq!(bounds(&pages[0]), None);
+    }
+
+    #[test]
+    fn text_sits_above_its_baseline() {
+        let page = PageBuilder::new().position(120, 200).text("H").preview().unwrap();
+        let (left, top, right, bottom) = bounds(&page[0]).unwrap();
+        // Baseline at 100 dots; a 24 dot tall cell starting 120 dots in
+        assert!(left >= 120 && right < 132, "{left}..{right}");
+        assert!(top >= 76 && bottom < 100, "{top}..{bottom}");
+    }
+
+    #[test]
+    fn double_size_text_covers_twice_the_cell() {
+        let single = PageBuilder::new().position(0, 200).text("M".reversed()).preview().unwrap();
+        let double = PageBuilder::new()
+            .position(0, 200)
+            .text("M".reversed().double_size())
+            .preview()
+            .unwrap();
+        assert_eq!(bounds(&single[0]), Some((0, 76, 11, 99)));
+        assert_eq!(bounds(&double[0]), Some((0, 52, 23, 99)));
+    }
+
+    #[test]
+    fn rotated_direction_runs_up_the_page() {
+        let area = PrintArea {
+            x: 0,
+            y: 0,
+            width: 200,
+            height: 400,
+        };
+        let page = PageBuilder::new()
+            .area(area)
+            .direction(PrintDirection::BottomToTop)
+            .text("MM".reversed())
+            .preview()
+            .unwrap();
+        // Two cells, 24 dots along the paper feed from the bottom, 24 dots
+        // from the left edge
+        assert_eq!(bounds(&page[0]), Some((0, 176, 23, 199)));
+    }
+
+    #[test]
+    fn raster_image_and_page_breaks() {
+        let logo = PrintRasterImage::new(1, 2, vec![0xFF, 0x81]);
+        let pages = PageBuilder::new()
+            .position(8, 20)
+            .image(logo)
+            .page_break()
+            .text("x")
+            .preview()
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(bounds(&pages[0]), Some((8, 8, 15, 9)));
+        assert!(pages[0].is_black(8, 9) && !pages[0].is_black(9, 9));
+        assert!(bounds(&pages[1]).is_some());
+    }
+
+    #[test]
+    fn oversized_print_area_is_clamped() {
+        let job = b"\x1bL\x1dP\x01\x01\x1bW\x00\x00\x00\x00\xff\xff\xff\xffHi\x1b\x0c";
+        let pages = render(job);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].width(), u32::from(PrinterProfile::default().paper_width_dots));
+        assert_eq!(pages[0].height(), MAX_PAGE_LENGTH);
+        assert!(bounds(&pages[0]).is_some());
+
+        // Default motion units reach the same limits
+        let pages = render(b"\x1bL\x1bW\x00\x00\x00\x00\xff\xff\xff\xffHi\x1b\x0c");
+        assert_eq!(pages[0].height(), MAX_PAGE_LENGTH);
+    }
+
+    #[test]
+    fn png_has_page_dimensions() {
+        let page = &PageBuilder::new().text("Hi").preview().unwrap()[0];
+        let png = page.to_png().unwrap();
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (512, 831));
+    }
+}
@@ -0,0 +1,216 @@
+//! Reusable page layouts with placeholders.
+//!
+//! A [`PageTemplate`] is built once from a [`PageBuilder`] whose text
+//! contains placeholders such as `{guest}`, then filled in for each print.
+//! Everything except the placeholder values is encoded when the template is
+//! compiled, so repeated labels cost little more than copying bytes.
+//!
+//! ```
+//! use bixolon::page::PageBuilder;
+//! use bixolon::page::template::PageTemplate;
+//! use bixolon::style::text::Styleable;
+//!
+//! let page = PageBuilder::new()
+//!     .position(0, 60)
+//!     .text_line("{guest}".bold())
+//!     .text_line("Table {table}");
+//! let template = PageTemplate::new(&page)?;
+//!
+//! let label = template.render(&[("guest", "Ada Lovelace"), ("table", "12")])?;
+//! assert!(label.windows(8).any(|w| w == b"Table 12"));
+//! # Ok::<(), bixolon::error::ValidationError>(())
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::PageBuilder;
+use crate::command::StaticCommand;
+use crate::command::basic::FormFeed;
+use crate::error::ValidationError;
+
+/// A compiled page layout with named placeholders.
+///
+/// Placeholders are `{name}` in text added with [`PageBuilder::text`],
+/// [`text_line`](PageBuilder::text_line) or
+/// [`text_box`](PageBuilder::text_box), where `name` is ASCII letters,
+/// digits and underscores. Other braces print as written. Raw commands,
+/// barcodes and images are never searched, so data such as Code128's `{A`
+/// is left alone.
+///
+/// Values are inserted as UTF-8 in the style active at the placeholder.
+/// Layout is measured with the placeholder text itself, so overflow checks
+/// and text box wrapping don't account for longer values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(Vec<u8>),
+    Placeholder(String),
+}
+
+impl PageTemplate {
+    /// Compile every page of `page` into a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::PageOverflow`] as for
+    /// [`PageBuilder::build`].
+    pub fn new(page: &PageBuilder) -> Result<Self, ValidationError> {
+        let mut template = Self {
+            segments: Vec::new(),
+        };
+        for page in page.each_page() {
+            page.write_body(&mut |bytes, text| {
+                if text {
+                    template.push_text(bytes);
+                } else {
+                    template.push_literal(bytes);
+                }
+                Ok::<_, ValidationError>(())
+            })?;
+            template.push_literal(FormFeed::BYTES);
+        }
+        Ok(template)
+    }
+
+    /// Names of the placeholders, in order of appearance.
+    ///
+    /// A placeholder used more than once is listed each time.
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Fill in the placeholders and return the page bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::MissingPlaceholder`] if a placeholder has
+    /// no value. Values for unknown names are ignored.
+    pub fn render(&self, values: &[(&str, &str)]) -> Result<Vec<u8>, ValidationError> {
+        let mut output = Vec::new();
+        self.render_into(values, &mut output)?;
+        Ok(output)
+    }
+
+    /// Fill in the placeholders, appending the page bytes to `output`.
+    ///
+    /// Lets a run of labels reuse one buffer. On error, `output` may hold a
+    /// partial page.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::MissingPlaceholder`] as for
+    /// [`render`](Self::render).
+    pub fn render_into(
+        &self,
+        values: &[(&str, &str)],
+        output: &mut Vec<u8>,
+    ) -> Result<(), ValidationError> {
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(bytes) => output.extend_from_slice(bytes),
+                Segment::Placeholder(name) => {
+                    let (_, value) = values
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .ok_or_else(|| ValidationError::MissingPlaceholder(name.clone()))?;
+                    output.extend_from_slice(value.as_bytes());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_literal(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        match self.segments.last_mut() {
+            Some(Segment::Literal(literal)) => literal.extend_from_slice(bytes),
+            _ => self.segments.push(Segment::Literal(bytes.to_vec())),
+        }
+    }
+
+    /// Split rendered text at its placeholders.
+    fn push_text(&mut self, mut bytes: &[u8]) {
+        while let Some((start, len)) = find_placeholder(bytes) {
+            self.push_literal(&bytes[..start]);
+            let name = String::from_utf8_lossy(&bytes[start + 1..start + len - 1]).into_owned();
+            self.segments.push(Segment::Placeholder(name));
+            bytes = &bytes[start + len..];
+        }
+        self.push_literal(bytes);
+    }
+}
+
+/// Start and length of the first `{name}` in `bytes`.
+pub(crate) fn find_placeholder(bytes: &[u8]) -> Option<(usize, usize)> {
+    let is_name = |b: &u8| b.is_ascii_alphanumeric() || *b == b'_';
+    let mut from = 0;
+    while let Some(offset) = bytes[from..].iter().position(|&b| b == b'{') {
+        let start = from + offset;
+        let name_len = bytes[start + 1..].iter().take_while(|b| is_name(b)).count();
+        if name_len > 0 && bytes.get(start + 1 + name_len) == Some(&b'}') {
+            return Some((start, name_len + 2));
+        }
+        from = start + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::barcode::Code128Builder;
+    use crate::style::text::Styleable;
+
+    #[test]
+    fn placeholders_are_filled_in_style() {
+        let page = PageBuilder::new().text("Hi {name}!".bold());
+        let template = PageTemplate::new(&page).unwrap();
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), ["name"]);
+
+        let filled = PageBuilder::new().text("Hi Ada!".bold()).build().unwrap();
+        assert_eq!(template.render(&[("name", "Ada")]).unwrap(), filled);
+    }
+
+    #[test]
+    fn repeated_and_multi_page_placeholders() {
+        let page = PageBuilder::new().text("{a}{a}").page_break().text_line("{b}");
+        let template = PageTemplate::new(&page).unwrap();
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), ["a", "a", "b"]);
+        assert_eq!(template.render(&[("b", "2"), ("a", "1")]).unwrap(), b"\x1bL11\x0C\x1bL2\n\x0C");
+    }
+
+    #[test]
+    fn non_placeholder_braces_are_literal() {
+        let page = PageBuilder::new().text("{} {not valid} {{x}");
+        let template = PageTemplate::new(&page).unwrap();
+        assert_eq!(template.render(&[("x", "!")]).unwrap(), b"\x1bL{} {not valid} {!\x0C");
+    }
+
+    #[test]
+    fn barcode_data_is_not_searched() {
+        let barcode = Code128Builder::new().text("{abc}").build().unwrap();
+        let page = PageBuilder::new().barcode(barcode);
+        let template = PageTemplate::new(&page).unwrap();
+        assert_eq!(template.placeholders().count(), 0);
+        assert_eq!(template.render(&[]).unwrap(), page.build().unwrap());
+    }
+
+    #[test]
+    fn missing_value_is_an_error() {
+        let template = PageTemplate::new(&PageBuilder::new().text("{table}")).unwrap();
+        assert!(matches!(
+            template.render(&[("guest", "x")]),
+            Err(ValidationError::MissingPlaceholder(name)) if name == "table"
+        ));
+    }
+}
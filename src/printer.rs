@@ -2,8 +2,16 @@
 //!
 //! Provides [`Printer`] for synchronous printing.
 
+mod flush;
+mod history;
+mod monitor;
+mod nv_graphics;
 mod sync;
 
+pub use flush::FlushPolicy;
+pub use history::JobHistory;
+pub use monitor::{StatusEvent, StatusMonitor};
+pub use nv_graphics::NvGraphics;
 pub use sync::Printer;
 
 #[cfg(feature = "async")]
@@ -11,3 +19,61 @@ mod r#async;
 
 #[cfg(feature = "async")]
 pub use r#async::AsyncPrinter;
+
+use core::time::Duration;
+
+use crate::command::QueryCommand;
+use crate::error::StatusParseError;
+
+/// How long a status query waits for the printer's answer by default.
+///
+/// Printers answer real-time status requests within milliseconds, even
+/// while busy, so a longer wait means the printer is off or disconnected.
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pause between status requests while waiting for the printer to become
+/// ready.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Drop the complete ASB packets at the start of `buf[..*filled]`,
+/// recording each in `monitor`.
+///
+/// Returns whether the bytes left are the query response. They aren't yet
+/// if there are none, or if they're the start of another ASB packet.
+fn strip_asb_packets(buf: &mut [u8], filled: &mut usize, monitor: &mut StatusMonitor) -> bool {
+    use crate::command::status::{ASB_PACKET_LEN, PrinterStatus, is_asb_header};
+
+    loop {
+        match buf[..*filled] {
+            [] => return false,
+            [first, ..] if !is_asb_header(first) => return true,
+            _ if *filled < ASB_PACKET_LEN => return false,
+            _ => {}
+        }
+        let Some(status) = PrinterStatus::from_asb(&buf[..*filled]) else {
+            return true;
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?status, "ASB packet read while awaiting query response");
+        monitor.update(status);
+        buf.copy_within(ASB_PACKET_LEN..*filled, 0);
+        *filled -= ASB_PACKET_LEN;
+    }
+}
+
+/// Drop the ASB packets ahead of the response to `cmd`, as
+/// [`strip_asb_packets`] does, and return whether the whole response is in.
+///
+/// Variable-length responses can arrive in pieces, so one that `cmd` finds
+/// [`Incomplete`](StatusParseError::Incomplete) is still being read, unless
+/// `buf` is full.
+fn response_complete<Q: QueryCommand>(
+    cmd: &Q,
+    buf: &mut [u8],
+    filled: &mut usize,
+    monitor: &mut StatusMonitor,
+) -> bool {
+    strip_asb_packets(buf, filled, monitor)
+        && (*filled == buf.len()
+            || !matches!(cmd.parse_response(&buf[..*filled]), Err(StatusParseError::Incomplete)))
+}
@@ -2,13 +2,33 @@
 //!
 //! Requires the `async` feature.
 
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter};
+use std::time::Duration;
 
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf};
+use tokio::time::Instant;
+
+use crate::command::barcode::{PrintBarcode, barcode_fallback};
+use crate::command::basic::Cancel;
+use crate::command::character::{Justification, SetJustification};
+use crate::command::codepage::CodePage;
+use crate::command::image::{ColumnImage, Darkness, PrintRasterImage};
+use crate::command::page_mode::ExitPageMode;
 use crate::command::printer_control::Initialize;
-use crate::command::{Command, QueryCommand};
-use crate::error::PrinterError;
+use crate::command::rule::{RuleStyle, TextRule};
+use crate::command::status::{PrinterStatus, StatusResponse, StatusType, TransmitStatus};
+use crate::command::symbol::{PrintQrCode, qr_fallback};
+use crate::command::{Command, QueryCommand, StaticCommand};
+use crate::encoding::Fallback;
+use crate::error::{PrinterError, StatusError};
+use crate::footer::QrFooter;
 use crate::page::PageBuilder;
-use crate::style::text::StyledNode;
+use crate::printer::{
+    DEFAULT_QUERY_TIMEOUT, FlushPolicy, JobHistory, READY_POLL_INTERVAL, StatusEvent,
+    StatusMonitor, response_complete,
+};
+use crate::profile::{PrinterProfile, StylePolicy};
+use crate::style::StyleSet;
+use crate::style::text::{StyleRenderer, StyledNode};
 
 /// Asynchronous printer interface.
 ///
@@ -31,6 +51,39 @@ use crate::style::text::StyledNode;
 pub struct AsyncPrinter<W: AsyncWrite + Unpin, R = ()> {
     writer: BufWriter<W>,
     reader: R,
+    profile: PrinterProfile,
+    style_policy: StylePolicy,
+    encoding_fallback: Option<Fallback>,
+    /// Code page the printer is in outside `code_page` styles, if not CP437.
+    code_page: Option<CodePage>,
+    renderer: Option<StyleRenderer>,
+    scopes: Vec<StyleSet>,
+    monitor: StatusMonitor,
+    history: Option<JobHistory>,
+    /// Scratch buffer commands and text are encoded into before writing.
+    buffer: Vec<u8>,
+    flush_policy: FlushPolicy,
+    /// Bytes written since the last flush.
+    unflushed: usize,
+    query_timeout: Duration,
+    /// Bytes of a query whose write was cancelled, sent before anything else.
+    unsent_query: Vec<u8>,
+    /// A query was abandoned before its response was read.
+    stale_response: bool,
+}
+
+/// Closes a [`with_style`](AsyncPrinter::with_style) scope when dropped, so
+/// a cancelled closure doesn't leave its style applied.
+struct ScopeGuard<'a, W: AsyncWrite + Unpin, R> {
+    printer: &'a mut AsyncPrinter<W, R>,
+    /// Number of scopes open before this one.
+    depth: usize,
+}
+
+impl<W: AsyncWrite + Unpin, R> Drop for ScopeGuard<'_, W, R> {
+    fn drop(&mut self) {
+        self.printer.scopes.truncate(self.depth);
+    }
 }
 
 impl<W: AsyncWrite + Unpin> AsyncPrinter<W, ()> {
@@ -39,6 +92,20 @@ impl<W: AsyncWrite + Unpin> AsyncPrinter<W, ()> {
         Self {
             writer: BufWriter::new(writer),
             reader: (),
+            profile: PrinterProfile::default(),
+            style_policy: StylePolicy::default(),
+            encoding_fallback: None,
+            code_page: None,
+            renderer: None,
+            scopes: Vec::new(),
+            monitor: StatusMonitor::new(),
+            history: None,
+            buffer: Vec::new(),
+            flush_policy: FlushPolicy::default(),
+            unflushed: 0,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
+            unsent_query: Vec::new(),
+            stale_response: false,
         }
     }
 }
@@ -49,22 +116,296 @@ impl<W: AsyncWrite + Unpin, R> AsyncPrinter<W, R> {
         Self {
             writer: BufWriter::new(writer),
             reader,
+            profile: PrinterProfile::default(),
+            style_policy: StylePolicy::default(),
+            encoding_fallback: None,
+            code_page: None,
+            renderer: None,
+            scopes: Vec::new(),
+            monitor: StatusMonitor::new(),
+            history: None,
+            buffer: Vec::new(),
+            flush_policy: FlushPolicy::default(),
+            unflushed: 0,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
+            unsent_query: Vec::new(),
+            stale_response: false,
+        }
+    }
+
+    /// Use a capability profile other than the SRP-350plus default.
+    ///
+    /// The profile is checked when printing styled text, so features the
+    /// model lacks (such as red text) are rejected instead of misprinting.
+    pub fn with_profile(mut self, profile: PrinterProfile) -> Self {
+        self.profile = profile;
+        if self.renderer.is_some() {
+            self.renderer =
+                Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        }
+        self
+    }
+
+    /// Choose how styles the profile doesn't support are handled.
+    ///
+    /// The default, [`StylePolicy::Permissive`], only rejects color on
+    /// single-color models. [`StylePolicy::Strict`] also rejects reverse
+    /// printing and oversized character scales the profile lacks, and
+    /// [`StylePolicy::Downgrade`] quietly drops or clamps them instead.
+    pub fn with_style_policy(mut self, policy: StylePolicy) -> Self {
+        self.style_policy = policy;
+        self
+    }
+
+    /// Handle characters missing from the code page when printing text.
+    ///
+    /// By default text is sent as rendered, with unencodable characters
+    /// becoming `?`. With a fallback set, [`Fallback::Error`] rejects such
+    /// text before anything is sent, and [`Fallback::Transliterate`] prints
+    /// ASCII lookalikes instead. Text without an explicit code page is
+    /// checked against the printer's [code page](Self::with_code_page).
+    pub fn with_encoding_fallback(mut self, fallback: Fallback) -> Self {
+        self.encoding_fallback = Some(fallback);
+        self
+    }
+
+    /// Assume the printer is in `code_page` outside `code_page` styles,
+    /// e.g. one chosen by a memory switch or a
+    /// [`SelectCodePage`](crate::command::codepage::SelectCodePage) sent
+    /// earlier.
+    ///
+    /// Text without a code page of its own is encoded in it, and styled
+    /// text that switches to another page returns to it afterwards rather
+    /// than to CP437. See [`StyleRenderer::with_code_page`].
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = Some(code_page);
+        if self.renderer.is_some() {
+            self.renderer =
+                Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        }
+        self
+    }
+
+    /// Start `renderer` in the printer's [code page](Self::with_code_page).
+    fn in_code_page(&self, renderer: StyleRenderer) -> StyleRenderer {
+        match self.code_page {
+            Some(code_page) => renderer.with_code_page(code_page),
+            None => renderer,
+        }
+    }
+
+    /// Keep recent jobs in `history` for [`reprint_last`](Self::reprint_last).
+    ///
+    /// Every byte sent is recorded except status queries, and each
+    /// [`flush`](Self::flush) ends a job, so flush once per receipt.
+    pub fn with_job_history(mut self, history: JobHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Buffer up to `capacity` bytes before writing to the printer, instead
+    /// of 8 KiB.
+    ///
+    /// A small buffer suits a slow serial link, where bytes should start
+    /// moving early; a large one lets an image-heavy job reach the
+    /// transport in fewer, bigger writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if anything has been written but not flushed, as resizing
+    /// the buffer would lose it. Set the capacity when constructing the
+    /// printer.
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        assert!(self.writer.buffer().is_empty(), "printer buffer resized with unflushed bytes");
+        self.writer = BufWriter::with_capacity(capacity, self.writer.into_inner());
+        self
+    }
+
+    /// Flush automatically according to `policy`.
+    pub fn with_flush_policy(mut self, policy: FlushPolicy) -> Self {
+        self.flush_policy = policy;
+        self
+    }
+
+    /// Wait up to `timeout` for the answer to a [`query`](Self::query),
+    /// instead of [`DEFAULT_QUERY_TIMEOUT`].
+    ///
+    /// The deadline uses Tokio's timer, so the runtime needs its time
+    /// driver enabled.
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
+    /// Carry text style across `print`/`println` calls.
+    ///
+    /// By default every call returns the printer to its default style.
+    /// With tracking enabled the printer remembers the active style and
+    /// only sends the commands needed to reach the next node's style, so
+    /// consecutive bold lines send `ESC E 1` once. Call
+    /// [`reset_style`](Self::reset_style) before sending raw commands that
+    /// depend on the default style.
+    pub fn with_style_tracking(mut self) -> Self {
+        self.renderer = Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        self
+    }
+
+    /// Return the printer to its default text style.
+    ///
+    /// Only sends commands when style tracking is enabled and a non-default
+    /// style is active.
+    pub async fn reset_style(&mut self) -> Result<&Self, PrinterError> {
+        if let Some(renderer) = &mut self.renderer {
+            let bytes = renderer.reset();
+            self.write_bytes(&bytes).await?;
         }
+        Ok(self)
+    }
+
+    /// Run `f` with `style` applied to everything it prints.
+    ///
+    /// Styles nest: text printed inside the closure inherits the scope's
+    /// style unless it overrides an attribute itself, and the previous
+    /// effective style applies again once the closure returns (even on
+    /// error, or if the future is dropped before it completes).
+    ///
+    /// ```ignore
+    /// printer
+    ///     .with_style(StyleSet::new().with_bold(true), async |p| {
+    ///         p.println("TOTAL").await?;
+    ///         p.println("25.00").await?;
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn with_style<T>(
+        &mut self,
+        style: StyleSet,
+        f: impl AsyncFnOnce(&mut Self) -> Result<T, PrinterError>,
+    ) -> Result<T, PrinterError> {
+        let depth = self.scopes.len();
+        self.scopes.push(style);
+        let guard = ScopeGuard {
+            printer: self,
+            depth,
+        };
+        f(&mut *guard.printer).await
+    }
+
+    /// The printer's capability profile.
+    pub fn profile(&self) -> &PrinterProfile {
+        &self.profile
+    }
+
+    /// The status changes seen so far.
+    ///
+    /// Fed by every printer status reading, such as
+    /// [`drawer_is_open`](Self::drawer_is_open).
+    pub fn status_monitor(&mut self) -> &mut StatusMonitor {
+        &mut self.monitor
+    }
+
+    /// The retained jobs, if [`with_job_history`](Self::with_job_history)
+    /// enabled them.
+    pub fn job_history(&self) -> Option<&JobHistory> {
+        self.history.as_ref()
     }
 
     /// Send a command to the printer.
     ///
+    /// The command is encoded for the profile's
+    /// [`Dialect`](crate::command::Dialect).
+    ///
     /// Does not flush - call `flush()` to ensure data is sent.
     pub async fn send(&mut self, cmd: impl Command) -> Result<&Self, PrinterError> {
-        self.writer.write_all(&cmd.encode()).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(command = std::any::type_name_of_val(&cmd), "sending command");
+        self.write_encoded(|printer, buffer| cmd.encode_into_for(printer.profile.dialect, buffer))
+            .await?;
         Ok(self)
     }
 
+    /// Send several commands in one write.
+    ///
+    /// The commands are encoded back to back into one buffer, each for the
+    /// profile's [`Dialect`](crate::command::Dialect), and written at once,
+    /// saving the per-write overhead of a serial or USB transfer when
+    /// sending many small configuration commands.
+    ///
+    /// Does not flush - call `flush()` to ensure data is sent.
+    pub async fn send_all<'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a dyn Command>,
+    ) -> Result<&Self, PrinterError> {
+        self.write_encoded(|printer, buffer| {
+            for cmd in commands {
+                cmd.encode_into_for(printer.profile.dialect, buffer);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(bytes = buffer.len(), "sending batched commands");
+        })
+        .await?;
+        Ok(self)
+    }
+
+    /// Encode into the reusable buffer with `encode`, then write it.
+    async fn write_encoded(
+        &mut self,
+        encode: impl FnOnce(&mut Self, &mut Vec<u8>),
+    ) -> std::io::Result<()> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        encode(self, &mut buffer);
+        let result = self.write_bytes(&buffer).await;
+        self.buffer = buffer;
+        result
+    }
+
+    /// Write `bytes` to the printer's buffer.
+    async fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if let Some(history) = &mut self.history {
+            history.record(bytes);
+        }
+        self.write_unrecorded(bytes).await
+    }
+
+    /// Write without recording the bytes in the job history.
+    async fn write_unrecorded(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(bytes = bytes.len(), "writing to printer");
+        self.finish_query_write().await?;
+        self.writer.write_all(bytes).await?;
+        self.unflushed += bytes.len();
+        if self.flush_policy.should_flush(bytes, self.unflushed, self.profile.dialect) {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(bytes = self.unflushed, "flushing printer by policy");
+            self.writer.flush().await?;
+            self.unflushed = 0;
+        }
+        Ok(())
+    }
+
+    /// Write the rest of [`unsent_query`](Self::unsent_query).
+    ///
+    /// Each `write` either completes or writes nothing when cancelled, so
+    /// the remainder is always accurate and a query is never cut short by
+    /// the bytes sent after it.
+    async fn finish_query_write(&mut self) -> std::io::Result<()> {
+        while !self.unsent_query.is_empty() {
+            let written = self.writer.write(&self.unsent_query).await?;
+            if written == 0 {
+                return Err(std::io::ErrorKind::WriteZero.into());
+            }
+            self.unsent_query.drain(..written);
+        }
+        Ok(())
+    }
+
     /// Send raw bytes to the printer.
     ///
     /// Does not flush - call `flush()` to ensure data is sent.
     pub async fn send_raw(&mut self, bytes: &[u8]) -> Result<&Self, PrinterError> {
-        self.writer.write_all(bytes).await?;
+        self.write_bytes(bytes).await?;
         Ok(self)
     }
 
@@ -72,32 +413,277 @@ impl<W: AsyncWrite + Unpin, R> AsyncPrinter<W, R> {
     ///
     /// Does not add a line feed. Use `println` for that.
     pub async fn print(&mut self, text: impl Into<StyledNode>) -> Result<&Self, PrinterError> {
-        let node = text.into();
-        self.writer.write_all(&node.render()).await?;
+        let node = self.prepare(text.into())?;
+        self.write_encoded(|printer, buffer| match &mut printer.renderer {
+            Some(renderer) => renderer.render_into(&node, buffer),
+            None => {
+                let mut renderer =
+                    printer.in_code_page(StyleRenderer::eager(printer.profile.clone()));
+                renderer.render_into(&node, buffer);
+                renderer.reset_into(buffer);
+            }
+        })
+        .await?;
         Ok(self)
     }
 
     /// Print styled text followed by a line feed.
     pub async fn println(&mut self, text: impl Into<StyledNode>) -> Result<&Self, PrinterError> {
-        let node = text.into();
-        self.writer.write_all(&node.render_line()).await?;
+        let node = self.prepare(text.into())?;
+        self.write_encoded(|printer, buffer| match &mut printer.renderer {
+            Some(renderer) => renderer.render_line_into(&node, buffer),
+            None => {
+                let mut renderer =
+                    printer.in_code_page(StyleRenderer::eager(printer.profile.clone()));
+                renderer.render_into(&node, buffer);
+                renderer.finish_line(buffer);
+            }
+        })
+        .await?;
+        Ok(self)
+    }
+
+    /// Apply scopes, the style policy and the encoding fallback to `node`.
+    fn prepare(&self, node: StyledNode) -> Result<StyledNode, PrinterError> {
+        let node = self.profile.apply_policy(self.style_policy, self.scoped(node))?;
+        match self.encoding_fallback {
+            Some(fallback) => {
+                Ok(node.apply_fallback_in(self.code_page.unwrap_or_default(), fallback)?)
+            }
+            None => Ok(node),
+        }
+    }
+
+    /// Wrap `node` in the styles of the active `with_style` scopes.
+    fn scoped(&self, node: StyledNode) -> StyledNode {
+        if self.scopes.is_empty() {
+            node
+        } else {
+            node.with_style(StyleSet::from_stack(&self.scopes))
+        }
+    }
+
+    /// Print a full-width horizontal rule sized from the printer profile.
+    ///
+    /// Uses CP437 box-drawing characters; see [`TextRule`].
+    pub async fn rule(&mut self, style: RuleStyle) -> Result<&Self, PrinterError> {
+        self.write_bytes(&TextRule::full_width(style, &self.profile).encode()).await?;
         Ok(self)
     }
 
     /// Print a page mode document.
     pub async fn print_page(&mut self, page: PageBuilder) -> Result<&Self, PrinterError> {
-        self.writer.write_all(&page.build()).await?;
+        self.write_bytes(&page.build()?).await?;
+        Ok(self)
+    }
+
+    /// Print a page mode document `copies` times.
+    ///
+    /// The page is buffered once and reprinted without being re-sent.
+    pub async fn print_page_copies(
+        &mut self,
+        page: PageBuilder,
+        copies: u8,
+    ) -> Result<&Self, PrinterError> {
+        self.write_bytes(&page.build_copies(copies)?).await?;
         Ok(self)
     }
 
     /// Print a page mode document and return to standard mode.
     pub async fn print_page_and_exit(&mut self, page: PageBuilder) -> Result<&Self, PrinterError> {
-        self.writer.write_all(&page.build_and_exit()).await?;
+        self.write_bytes(&page.build_and_exit()?).await?;
+        Ok(self)
+    }
+
+    /// Print a raster image in bands.
+    ///
+    /// Tall images are split with [`PrintRasterImage::bands`] so no single
+    /// command overflows the printer's receive buffer. Use
+    /// [`print_image_checked`](Self::print_image_checked) to stop early if
+    /// the printer goes offline part way through. Images failing
+    /// [`PrintRasterImage::validate`] are rejected before anything is sent.
+    pub async fn print_image(&mut self, image: &PrintRasterImage) -> Result<&Self, PrinterError> {
+        image.validate()?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            width_bytes = image.width_bytes,
+            height_dots = image.height_dots,
+            "printing raster image"
+        );
+        for band in image.band_slices(image.band_height()) {
+            self.write_encoded(|printer, buffer| {
+                band.encode_into_for(printer.profile.dialect, buffer)
+            })
+            .await?;
+        }
+        Ok(self)
+    }
+
+    /// Print a raster image with extra passes to darken it.
+    ///
+    /// [`Darkness::Normal`] prints like [`print_image`](Self::print_image).
+    /// Darker settings send the image as [`ColumnImage`] bands, striking
+    /// each band repeatedly before feeding the paper.
+    pub async fn print_image_with_darkness(
+        &mut self,
+        image: &PrintRasterImage,
+        darkness: Darkness,
+    ) -> Result<&Self, PrinterError> {
+        if darkness == Darkness::Normal {
+            return self.print_image(image).await;
+        }
+        image.validate()?;
+        let columns = ColumnImage::try_from(image)?.with_darkness(darkness);
+        self.write_bytes(&columns.encode()).await?;
+        Ok(self)
+    }
+
+    /// Print a QR code, natively or as a raster image.
+    ///
+    /// Uses `GS ( k` when the profile reports native QR support. Otherwise,
+    /// with the `qrcode` feature, the symbol is rendered with
+    /// [`PrintQrCode::to_raster`] and printed as an image.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`](crate::error::ValidationError::Unsupported)
+    /// if the profile lacks QR support and the `qrcode` feature is
+    /// disabled, or [`PrinterError::QrCode`] if rendering fails.
+    pub async fn print_qr_code(&mut self, qr: &PrintQrCode) -> Result<&Self, PrinterError> {
+        match qr_fallback(qr, &self.profile)? {
+            Some(image) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("rendering QR code as a raster image");
+                self.print_image(&image).await
+            }
+            None => {
+                self.write_bytes(&qr.encode()).await?;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Print a [`QrFooter`]: its QR code centered, then its captions.
+    ///
+    /// The symbol falls back to a raster image like
+    /// [`print_qr_code`](Self::print_qr_code).
+    ///
+    /// # Errors
+    ///
+    /// As for [`print_qr_code`](Self::print_qr_code) and
+    /// [`println`](Self::println).
+    pub async fn print_qr_footer(&mut self, footer: &QrFooter) -> Result<&Self, PrinterError> {
+        self.send(SetJustification(Justification::Center)).await?;
+        self.print_qr_code(&footer.qr_code(&self.profile)).await?;
+        self.send(SetJustification(Justification::Left)).await?;
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        for line in footer.captions() {
+            self.println(line.clone().with_style(centered.clone())).await?;
+        }
+        Ok(self)
+    }
+
+    /// Print a barcode, rendering it host-side if the profile requires.
+    ///
+    /// Barcodes with more data than [`PrinterProfile::max_barcode_data`]
+    /// are printed as raster images at the default module width and
+    /// height; others are sent as `GS k` using the current barcode settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Barcode`] if a barcode needing the fallback
+    /// can't be rendered.
+    pub async fn print_barcode(&mut self, barcode: &PrintBarcode) -> Result<&Self, PrinterError> {
+        match barcode_fallback(barcode, &self.profile)? {
+            Some(image) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("rendering barcode as a raster image");
+                self.print_image(&image).await
+            }
+            None => {
+                self.write_bytes(&barcode.encode()).await?;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Print Japanese or Chinese text using the profile's Kanji mode.
+    ///
+    /// Two-byte characters are sent in Kanji mode, encoded with
+    /// [`PrinterProfile::cjk`]; other characters are sent in the code page
+    /// of the enclosing [`with_style`](Self::with_style) scopes, or the
+    /// printer's [code page](Self::with_code_page) if they set none. See [`encode_mixed`](crate::encoding::cjk::encode_mixed).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`](crate::error::ValidationError::Unsupported)
+    /// if the profile has no Kanji mode, or [`PrinterError::Encoding`] if
+    /// a character can't be encoded.
+    #[cfg(feature = "cjk")]
+    pub async fn print_cjk(&mut self, text: &str) -> Result<&Self, PrinterError> {
+        use crate::command::codepage::SelectCodePage;
+
+        let Some(encoding) = self.profile.cjk else {
+            return Err(crate::error::ValidationError::Unsupported {
+                feature: "Kanji mode",
+                profile: self.profile.name.to_string(),
+            }
+            .into());
+        };
+        let base = self.code_page.unwrap_or_default();
+        let code_page = StyleSet::from_stack(&self.scopes).code_page.unwrap_or(base);
+        let mut bytes = Vec::new();
+        if code_page != base {
+            bytes.extend(SelectCodePage(code_page).to_bytes());
+        }
+        bytes.extend(crate::encoding::cjk::encode_mixed(text, encoding, code_page)?);
+        if code_page != base {
+            bytes.extend(SelectCodePage(base).to_bytes());
+        }
+        self.write_bytes(&bytes).await?;
+        Ok(self)
+    }
+
+    /// Print text, downloading glyphs for characters the code page lacks.
+    ///
+    /// Text is encoded in the default code page for Font A; missing
+    /// characters are drawn from built-in bitmap fonts and sent as
+    /// user-defined characters, up to
+    /// [`PrinterProfile::user_defined_characters`] distinct ones. See
+    /// [`encode_with_glyphs`](crate::encoding::glyph::encode_with_glyphs).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Encoding`] if a character has no glyph or
+    /// the text needs more user-defined characters than the printer holds.
+    #[cfg(feature = "glyphs")]
+    pub async fn print_with_glyphs(&mut self, text: &str) -> Result<&Self, PrinterError> {
+        let bytes = crate::encoding::glyph::encode_with_glyphs(
+            text,
+            Default::default(),
+            crate::command::character::Font::A,
+            self.profile.user_defined_characters,
+        )?;
+        self.write_bytes(&bytes).await?;
+        Ok(self)
+    }
+
+    /// Abandon a partially sent page mode document.
+    ///
+    /// Cancels the buffered page data and returns to standard mode, e.g.
+    /// after an application error left a page half-built. Harmless when the
+    /// printer is already in standard mode.
+    pub async fn abort_page(&mut self) -> Result<&Self, PrinterError> {
+        self.write_bytes(&Cancel.encode()).await?;
+        self.write_bytes(ExitPageMode::BYTES).await?;
         Ok(self)
     }
 
     /// Initialize the printer (reset to defaults).
     pub async fn initialize(&mut self) -> Result<&Self, PrinterError> {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.assume_default();
+        }
         self.send(Initialize).await?;
         Ok(self)
     }
@@ -106,7 +692,37 @@ impl<W: AsyncWrite + Unpin, R> AsyncPrinter<W, R> {
     ///
     /// Call this to ensure all pending data is sent.
     pub async fn flush(&mut self) -> Result<&Self, PrinterError> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("flushing printer");
+        self.finish_query_write().await?;
         self.writer.flush().await?;
+        self.unflushed = 0;
+        if let Some(history) = &mut self.history {
+            history.end_job();
+        }
+        Ok(self)
+    }
+
+    /// Send the most recent completed job again, e.g. for a customer copy.
+    ///
+    /// The job is sent exactly as recorded, so any drawer pulse in it is
+    /// sent again too. The reprint is not recorded as a new job. Does not
+    /// flush - call `flush()` to ensure data is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::NoJobToReprint`] if job history is disabled
+    /// or no job has been flushed yet.
+    pub async fn reprint_last(&mut self) -> Result<&Self, PrinterError> {
+        let job = self
+            .history
+            .as_ref()
+            .and_then(JobHistory::last)
+            .ok_or(PrinterError::NoJobToReprint)?
+            .to_vec();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bytes = job.len(), "reprinting last job");
+        self.write_unrecorded(&job).await?;
         Ok(self)
     }
 
@@ -137,29 +753,247 @@ impl<W: AsyncWrite + Unpin, R> AsyncPrinter<W, R> {
 }
 
 impl<W: AsyncWrite + Unpin, R: AsyncRead + Unpin> AsyncPrinter<W, R> {
+    /// Print a raster image in bands, checking status between them.
+    ///
+    /// Like [`print_image`](Self::print_image), but before each band after
+    /// the first the printer is asked whether it is still online. Printing
+    /// stops with [`PrinterError::Status`] if it isn't, instead of streaming
+    /// the rest of the image into a printer that can't print it.
+    ///
+    /// Images failing [`PrintRasterImage::validate`] are rejected before
+    /// anything is sent.
+    pub async fn print_image_checked(
+        &mut self,
+        image: &PrintRasterImage,
+    ) -> Result<&Self, PrinterError> {
+        image.validate()?;
+        for (index, band) in image.band_slices(image.band_height()).enumerate() {
+            if index > 0 {
+                self.ensure_online().await?;
+            }
+            self.write_encoded(|printer, buffer| {
+                band.encode_into_for(printer.profile.dialect, buffer)
+            })
+            .await?;
+        }
+        Ok(self)
+    }
+
+    /// Read the printer status with `DLE EOT 1`, recording it in the
+    /// [status monitor](Self::status_monitor).
+    pub async fn printer_status(&mut self) -> Result<PrinterStatus, PrinterError> {
+        match self.query(TransmitStatus(StatusType::Printer)).await? {
+            StatusResponse::Printer(status) => {
+                self.monitor.update(status);
+                Ok(status)
+            }
+            _ => unreachable!("printer status query answered with another status"),
+        }
+    }
+
+    /// Whether the cash drawer is open.
+    ///
+    /// Reads pin 3 of the drawer kick-out connector. Most drawers close the
+    /// switch on that pin when open; for one wired the other way round,
+    /// negate the result.
+    pub async fn drawer_is_open(&mut self) -> Result<bool, PrinterError> {
+        Ok(self.printer_status().await?.drawer_open)
+    }
+
+    /// Read the printer status and return what changed since the last
+    /// reading, such as [`StatusEvent::DrawerOpened`].
+    ///
+    /// Call this periodically to alarm on a drawer left open or a printer
+    /// going offline. The first call only establishes the baseline.
+    pub async fn poll_status(&mut self) -> Result<Vec<StatusEvent>, PrinterError> {
+        self.printer_status().await?;
+        Ok(self.monitor.take_events())
+    }
+
+    /// Return an error describing why the printer is offline, if it is.
+    ///
+    /// Sends real-time status requests, so this works while the printer is
+    /// busy.
+    pub async fn ensure_online(&mut self) -> Result<(), PrinterError> {
+        if self.printer_status().await?.online {
+            return Ok(());
+        }
+        if let StatusResponse::PaperRoll(status) =
+            self.query(TransmitStatus(StatusType::PaperRoll)).await?
+            && status.paper_end
+        {
+            return Err(StatusError::PaperEnd.into());
+        }
+        if let StatusResponse::Offline(status) =
+            self.query(TransmitStatus(StatusType::Offline)).await?
+        {
+            if status.cover_open {
+                return Err(StatusError::CoverOpen.into());
+            }
+            if status.cutter_error {
+                return Err(StatusError::CutterError.into());
+            }
+        }
+        Err(StatusError::Offline.into())
+    }
+
+    /// Wait up to `timeout` for the printer to come online, e.g. after
+    /// power-on or while the cover is open.
+    ///
+    /// Asks for the printer status every 100 ms. Status requests that go
+    /// unanswered count as not ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Timeout`] if the printer is still not online
+    /// after `timeout`.
+    ///
+    /// # Panics
+    ///
+    /// As for [`query`](Self::query).
+    pub async fn wait_until_ready(&mut self, timeout: Duration) -> Result<&Self, PrinterError> {
+        let started = Instant::now();
+        loop {
+            match self.printer_status().await {
+                Ok(status) if status.online => return Ok(self),
+                Ok(_)
+                | Err(PrinterError::Timeout {
+                    ..
+                }) => {}
+                Err(err) => return Err(err),
+            }
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(PrinterError::Timeout {
+                    operation: "wait_until_ready",
+                    elapsed,
+                });
+            }
+            tokio::time::sleep(READY_POLL_INTERVAL.min(timeout - elapsed)).await;
+        }
+    }
+
     /// Execute a query command and parse the response.
     ///
-    /// Flushes the write buffer before reading the response.
+    /// Flushes the write buffer before reading the response. Automatic
+    /// Status Back packets read ahead of the response are recorded in the
+    /// [status monitor](Self::status_monitor) rather than parsed as it.
+    ///
+    /// # Cancellation safety
+    ///
+    /// Dropping the future, e.g. in `tokio::select!` or after an outer
+    /// timeout, never leaves part of the query in the buffer: the rest is
+    /// written before the next command. Bytes of an abandoned query's
+    /// response that have already arrived when the next query starts are
+    /// discarded, but a response arriving later still can be taken as the
+    /// next query's answer. After abandoning a query, wait for the printer
+    /// to finish answering before sending another.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Timeout`] if no answer arrives within the
+    /// [query timeout](Self::with_query_timeout), and
+    /// [`PrinterError::NoResponse`] if the reader is at end of file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime with the time driver
+    /// enabled, which the query timeout needs.
     pub async fn query<Q: QueryCommand>(&mut self, cmd: Q) -> Result<Q::Response, PrinterError> {
+        let started = Instant::now();
+
+        if self.stale_response {
+            self.discard_stale_response()?;
+        }
+
         // Send the query command
-        self.writer.write_all(&cmd.encode()).await?;
+        self.finish_query_write().await?;
+        cmd.encode_into_for(self.profile.dialect, &mut self.unsent_query);
+        self.finish_query_write().await?;
         self.writer.flush().await?;
 
         // Read response
+        self.stale_response = true;
         let mut buf = [0u8; 64];
-        let n = self.reader.read(&mut buf).await?;
+        let reader = &mut self.reader;
+        let monitor = &mut self.monitor;
+        let read = async {
+            // Skip any ASB packets the printer sent first
+            let mut n = 0;
+            while !response_complete(&cmd, &mut buf, &mut n, monitor) {
+                match reader.read(&mut buf[n..]).await? {
+                    // A truncated ASB packet or response, left for the parser to reject
+                    0 => break,
+                    read => n += read,
+                }
+            }
+            Ok::<_, std::io::Error>(n)
+        };
+        let n = match tokio::time::timeout(self.query_timeout, read).await {
+            Ok(read) => read?,
+            Err(_) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    command = std::any::type_name_of_val(&cmd),
+                    timeout = ?self.query_timeout,
+                    "query timed out"
+                );
+                return Err(PrinterError::Timeout {
+                    operation: "query",
+                    elapsed: started.elapsed(),
+                });
+            }
+        };
+        self.stale_response = false;
 
         if n == 0 {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(command = std::any::type_name_of_val(&cmd), "no response to query");
             return Err(PrinterError::NoResponse);
         }
 
-        cmd.parse_response(&buf[..n]).map_err(PrinterError::StatusParse)
+        let response = cmd.parse_response(&buf[..n]).map_err(PrinterError::StatusParse);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            command = std::any::type_name_of_val(&cmd),
+            bytes = n,
+            elapsed = ?started.elapsed(),
+            ok = response.is_ok(),
+            "query answered"
+        );
+        response
+    }
+
+    /// Drop bytes already received in answer to an abandoned query.
+    ///
+    /// Reads only what is available now, without waiting.
+    fn discard_stale_response(&mut self) -> Result<(), PrinterError> {
+        let waker = std::task::Waker::noop();
+        let mut cx = std::task::Context::from_waker(waker);
+        let mut buf = [0u8; 64];
+        loop {
+            let mut read = ReadBuf::new(&mut buf);
+            match std::pin::Pin::new(&mut self.reader).poll_read(&mut cx, &mut read) {
+                std::task::Poll::Ready(Ok(())) if !read.filled().is_empty() => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(bytes = read.filled().len(), "discarding stale response");
+                }
+                std::task::Poll::Ready(result) => {
+                    result?;
+                    break;
+                }
+                std::task::Poll::Pending => break,
+            }
+        }
+        self.stale_response = false;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::{DLE, EOT, ESC};
     use std::io::Cursor;
 
     // Helper to create async cursor
@@ -198,6 +1032,48 @@ mod tests {
         assert!(bytes.ends_with(&[0x0A]));
     }
 
+    #[tokio::test]
+    async fn with_style_applies_to_closure_prints() {
+        let mut printer = AsyncPrinter::new(async_cursor(Vec::new()));
+        printer
+            .with_style(StyleSet::new().with_bold(true), async |p| {
+                p.print("A").await?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        printer.print("B").await.unwrap();
+        printer.flush().await.unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(
+            inner.into_inner(),
+            [&[0x1B, b'E', 1][..], b"A", &[0x1B, b'E', 0], b"B"].concat()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_with_style_closes_its_scope() {
+        let mut printer = AsyncPrinter::new(async_cursor(Vec::new()));
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            printer.with_style(StyleSet::new().with_bold(true), async |p| {
+                p.print("A").await?;
+                std::future::pending::<Result<(), PrinterError>>().await
+            }),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        printer.print("B").await.unwrap();
+        printer.flush().await.unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(
+            inner.into_inner(),
+            [&[0x1B, b'E', 1][..], b"A", &[0x1B, b'E', 0], b"B"].concat()
+        );
+    }
+
     #[tokio::test]
     async fn print_page_sends_page_mode() {
         let buf = async_cursor(Vec::new());
@@ -227,4 +1103,77 @@ mod tests {
         let result = printer.query(TransmitStatus(StatusType::Printer)).await;
         assert!(result.is_ok());
     }
+    #[tokio::test]
+    async fn query_skips_asb_packets() {
+        let reader = async_cursor(vec![0x14, 0x00, 0x00, 0x00, 0x12]);
+        let mut printer = AsyncPrinter::with_reader(async_cursor(Vec::new()), reader);
+        assert_eq!(printer.poll_status().await.unwrap(), [StatusEvent::DrawerClosed]);
+    }
+
+    #[tokio::test]
+    async fn query_waits_for_the_rest_of_a_split_response() {
+        use crate::command::symbol::QuerySymbolStorage;
+
+        let reader =
+            async_cursor(b"\x37\x76120\x1F".to_vec()).chain(async_cursor(b"96\x1F1\x00".to_vec()));
+        let mut printer = AsyncPrinter::with_reader(async_cursor(Vec::new()), reader);
+        let info = printer.query(QuerySymbolStorage).await.unwrap();
+        assert_eq!((info.width_dots, info.height_dots), (120, 96));
+        assert!(!info.printable);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn query_times_out() {
+        let (reader, _printer_side) = tokio::io::duplex(64);
+        let mut printer = AsyncPrinter::with_reader(async_cursor(Vec::new()), reader)
+            .with_query_timeout(Duration::from_millis(500));
+
+        let result = printer.query(TransmitStatus(StatusType::Printer)).await;
+        assert!(matches!(
+            result,
+            Err(PrinterError::Timeout { operation: "query", elapsed })
+                if elapsed >= Duration::from_millis(500)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_query_response_is_discarded() {
+        let (reader, mut printer_side) = tokio::io::duplex(64);
+        let mut printer = AsyncPrinter::with_reader(async_cursor(Vec::new()), reader);
+
+        // Abandon a query, then let its answer arrive late
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(10),
+            printer.query(TransmitStatus(StatusType::Printer)),
+        )
+        .await;
+        assert!(abandoned.is_err());
+        printer_side.write_all(&[0x1E]).await.unwrap();
+
+        let answer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            printer_side.write_all(&[0x12]).await.unwrap();
+            printer_side
+        });
+        let status = printer.printer_status().await.unwrap();
+        assert!(status.online);
+        assert!(!status.drawer_open);
+        answer.await.unwrap();
+
+        let (writer, _) = printer.into_inner();
+        assert_eq!(writer.into_inner(), TransmitStatus(StatusType::Printer).encode().repeat(2));
+    }
+
+    #[tokio::test]
+    async fn cancelled_query_write_is_completed() {
+        let reader = async_cursor(vec![0x12]);
+        let mut printer = AsyncPrinter::with_reader(async_cursor(Vec::new()), reader);
+        printer.unsent_query.extend_from_slice(&[DLE, EOT]);
+
+        printer.send(Initialize).await.unwrap();
+        printer.flush().await.unwrap();
+
+        let (writer, _) = printer.into_inner();
+        assert_eq!(writer.into_inner(), [DLE, EOT, ESC, b'@']);
+    }
 }
@@ -0,0 +1,100 @@
+//! When the printer flushes its write buffer on its own.
+
+use crate::command::{Dialect, ESC, GS};
+
+/// When a [`Printer`](super::Printer) flushes without being asked.
+///
+/// By default nothing is sent until [`flush`](super::Printer::flush) is
+/// called or the write buffer fills. A policy can also flush once a number
+/// of bytes has built up, so a slow serial link starts printing early, or
+/// after each cut, so a receipt comes out as soon as it is complete.
+///
+/// Automatic flushes only push bytes to the printer: unlike an explicit
+/// [`flush`](super::Printer::flush), they don't end a job in the
+/// [`JobHistory`](super::JobHistory).
+///
+/// # Example
+///
+/// ```
+/// use bixolon::printer::{FlushPolicy, Printer};
+///
+/// let printer = Printer::new(Vec::new())
+///     .with_buffer_capacity(512)
+///     .with_flush_policy(FlushPolicy::manual().after_bytes(256).after_cut());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FlushPolicy {
+    after_bytes: Option<usize>,
+    after_cut: bool,
+}
+
+impl FlushPolicy {
+    /// Only flush when asked or when the buffer fills (the default).
+    pub const fn manual() -> Self {
+        Self {
+            after_bytes: None,
+            after_cut: false,
+        }
+    }
+
+    /// Also flush once `bytes` bytes have been written since the last
+    /// flush.
+    pub const fn after_bytes(mut self, bytes: usize) -> Self {
+        self.after_bytes = Some(bytes);
+        self
+    }
+
+    /// Also flush after each write that ends with a paper cut.
+    pub const fn after_cut(mut self) -> Self {
+        self.after_cut = true;
+        self
+    }
+
+    /// Whether to flush after writing `bytes`, with `unflushed` bytes
+    /// written since the last flush, `bytes` included.
+    pub(crate) fn should_flush(&self, bytes: &[u8], unflushed: usize, dialect: Dialect) -> bool {
+        self.after_bytes.is_some_and(|limit| unflushed >= limit)
+            || (self.after_cut && ends_with_cut(bytes, dialect))
+    }
+}
+
+/// Whether `bytes` end with a cut command in `dialect`.
+fn ends_with_cut(bytes: &[u8], dialect: Dialect) -> bool {
+    match dialect {
+        Dialect::Bixolon | Dialect::Epson => {
+            matches!(bytes, [.., GS, b'V', 0 | 1 | b'0' | b'1'] | [.., GS, b'V', 65 | 66, _])
+        }
+        Dialect::Star => matches!(bytes, [.., ESC, b'd', 0..=3]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::command::paper::CutPaper;
+
+    #[test]
+    fn cuts_are_detected_per_dialect() {
+        let policy = FlushPolicy::manual().after_cut();
+        for cut in [CutPaper::full(), CutPaper::feed_and_partial(3)] {
+            for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+                let bytes = [b"Total\n".as_slice(), &cut.encode_for(dialect)].concat();
+                assert!(policy.should_flush(&bytes, 0, dialect));
+            }
+        }
+        assert!(!policy.should_flush(b"Total\n", 0, Dialect::Bixolon));
+        assert!(!FlushPolicy::manual().should_flush(
+            &CutPaper::full().encode(),
+            0,
+            Dialect::Bixolon
+        ));
+    }
+
+    #[test]
+    fn byte_limit_counts_unflushed_bytes() {
+        let policy = FlushPolicy::manual().after_bytes(100);
+        assert!(!policy.should_flush(b"x", 99, Dialect::Bixolon));
+        assert!(policy.should_flush(b"x", 100, Dialect::Bixolon));
+    }
+}
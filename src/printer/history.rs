@@ -0,0 +1,161 @@
+//! Retained print jobs for reprinting.
+
+use std::collections::VecDeque;
+
+/// The byte streams of the most recent print jobs.
+///
+/// A [`Printer`](super::Printer) given a history with
+/// [`with_job_history`](super::Printer::with_job_history) records every
+/// byte it sends except status queries, and ends the current job each time
+/// it is flushed. [`reprint_last`](super::Printer::reprint_last) then sends
+/// the newest job again, byte for byte: a job that opened the cash drawer
+/// opens it again when reprinted, so flush drawer pulses as a job of their
+/// own.
+///
+/// Memory is bounded by the number of jobs kept and by their total size,
+/// [`DEFAULT_MAX_BYTES`](Self::DEFAULT_MAX_BYTES) unless changed with
+/// [`with_max_bytes`](Self::with_max_bytes): once either limit is passed
+/// the oldest jobs are dropped, and a job larger than the byte limit on its
+/// own is not kept at all.
+///
+/// # Example
+///
+/// ```
+/// use bixolon::printer::{JobHistory, Printer};
+///
+/// let mut printer = Printer::new(Vec::new())
+///     .with_job_history(JobHistory::new(3).with_max_bytes(64 * 1024));
+/// printer.println("Order #42")?.flush()?;
+/// printer.reprint_last()?.flush()?;
+/// assert_eq!(printer.job_history().unwrap().len(), 1);
+/// # Ok::<(), bixolon::PrinterError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JobHistory {
+    max_jobs: usize,
+    max_bytes: Option<usize>,
+    jobs: VecDeque<Vec<u8>>,
+    current: Vec<u8>,
+    overflowed: bool,
+}
+
+impl JobHistory {
+    /// Byte limit of a history made with [`new`](Self::new): 1 MiB.
+    pub const DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+
+    /// A history keeping up to `jobs` jobs, of at most
+    /// [`DEFAULT_MAX_BYTES`](Self::DEFAULT_MAX_BYTES) in total.
+    pub fn new(jobs: usize) -> Self {
+        Self {
+            max_jobs: jobs,
+            max_bytes: Some(Self::DEFAULT_MAX_BYTES),
+            ..Self::default()
+        }
+    }
+
+    /// Keep at most `bytes` bytes across all retained jobs.
+    pub fn with_max_bytes(mut self, bytes: usize) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// The number of completed jobs retained.
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Whether no completed jobs are retained.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// The bytes of the most recent completed job.
+    pub fn last(&self) -> Option<&[u8]> {
+        self.get(0)
+    }
+
+    /// The bytes of a completed job, counting back from the most recent
+    /// at 0.
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        self.jobs.iter().rev().nth(index).map(Vec::as_slice)
+    }
+
+    /// Forget every job, including the one in progress.
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+        self.current.clear();
+        self.overflowed = false;
+    }
+
+    /// Append bytes to the job in progress.
+    pub(crate) fn record(&mut self, bytes: &[u8]) {
+        if self.overflowed {
+            return;
+        }
+        if self.max_bytes.is_some_and(|max| self.current.len() + bytes.len() > max) {
+            self.current = Vec::new();
+            self.overflowed = true;
+            return;
+        }
+        self.current.extend_from_slice(bytes);
+    }
+
+    /// Finish the job in progress, if anything was written to it.
+    pub(crate) fn end_job(&mut self) {
+        let job = std::mem::take(&mut self.current);
+        if std::mem::take(&mut self.overflowed) || job.is_empty() || self.max_jobs == 0 {
+            return;
+        }
+        self.jobs.push_back(job);
+        let mut total: usize = self.jobs.iter().map(Vec::len).sum();
+        while self.jobs.len() > self.max_jobs || self.max_bytes.is_some_and(|max| total > max) {
+            let dropped = self.jobs.pop_front().map_or(0, |job| job.len());
+            total -= dropped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(history: &mut JobHistory, bytes: &[u8]) {
+        history.record(bytes);
+        history.end_job();
+    }
+
+    #[test]
+    fn keeps_the_newest_jobs() {
+        let mut history = JobHistory::new(2);
+        for job in [b"one", b"two", b"six"] {
+            finish(&mut history, job);
+        }
+        history.end_job();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.last(), Some(&b"six"[..]));
+        assert_eq!(history.get(1), Some(&b"two"[..]));
+        assert_eq!(history.get(2), None);
+    }
+
+    #[test]
+    fn byte_limit_drops_old_and_oversized_jobs() {
+        let mut history = JobHistory::new(10).with_max_bytes(8);
+        finish(&mut history, b"12345");
+        finish(&mut history, b"678");
+        finish(&mut history, b"9");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(1), Some(&b"678"[..]));
+
+        history.record(b"12345");
+        history.record(b"6789");
+        history.end_job();
+        assert_eq!(history.last(), Some(&b"9"[..]));
+    }
+
+    #[test]
+    fn new_histories_have_a_byte_limit() {
+        let mut history = JobHistory::new(2);
+        finish(&mut history, &vec![0; JobHistory::DEFAULT_MAX_BYTES + 1]);
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Printer status change tracking.
+
+use crate::command::status::PrinterStatus;
+
+/// A change in printer status between two readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatusEvent {
+    /// The drawer kick-out connector's sensor pin went high.
+    DrawerOpened,
+    /// The drawer kick-out connector's sensor pin went low.
+    DrawerClosed,
+    /// The printer came back online.
+    Online,
+    /// The printer went offline, e.g. because the cover was opened.
+    Offline,
+    /// The paper ran out.
+    PaperOut,
+    /// Paper was loaded after running out.
+    PaperLoaded,
+}
+
+/// Tracks printer status readings and reports what changed.
+///
+/// Each [`Printer`](super::Printer) keeps one, fed by every printer status
+/// reading it takes, such as [`drawer_is_open`](super::Printer::drawer_is_open).
+/// The first reading only sets the baseline; later readings queue an event
+/// for each changed field until [`take_events`](Self::take_events) collects
+/// them.
+///
+/// # Example
+///
+/// ```
+/// use bixolon::command::status::PrinterStatus;
+/// use bixolon::printer::{StatusEvent, StatusMonitor};
+///
+/// let closed = PrinterStatus {
+///     drawer_open: false,
+///     online: true,
+///     feed_button_pressed: false,
+///     paper_present: true,
+/// };
+/// let mut monitor = StatusMonitor::new();
+/// monitor.update(closed);
+/// monitor.update(PrinterStatus {
+///     drawer_open: true,
+///     ..closed
+/// });
+/// assert_eq!(monitor.take_events(), [StatusEvent::DrawerOpened]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatusMonitor {
+    last: Option<PrinterStatus>,
+    events: Vec<StatusEvent>,
+}
+
+impl StatusMonitor {
+    /// A monitor with no readings yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a status reading, queuing events for whatever changed since
+    /// the previous one.
+    pub fn update(&mut self, status: PrinterStatus) {
+        if let Some(last) = self.last.replace(status) {
+            let changes = [
+                (
+                    last.drawer_open,
+                    status.drawer_open,
+                    StatusEvent::DrawerOpened,
+                    StatusEvent::DrawerClosed,
+                ),
+                (last.online, status.online, StatusEvent::Online, StatusEvent::Offline),
+                (
+                    last.paper_present,
+                    status.paper_present,
+                    StatusEvent::PaperLoaded,
+                    StatusEvent::PaperOut,
+                ),
+            ];
+            for (was, is, rising, falling) in changes {
+                if was != is {
+                    self.events.push(if is {
+                        rising
+                    } else {
+                        falling
+                    });
+                }
+            }
+        }
+    }
+
+    /// The most recent reading, if any.
+    pub fn last(&self) -> Option<PrinterStatus> {
+        self.last
+    }
+
+    /// Whether the drawer was open at the most recent reading.
+    pub fn drawer_open(&self) -> Option<bool> {
+        self.last.map(|status| status.drawer_open)
+    }
+
+    /// Take the events queued since the last call, oldest first.
+    pub fn take_events(&mut self) -> Vec<StatusEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(drawer_open: bool, online: bool) -> PrinterStatus {
+        PrinterStatus {
+            drawer_open,
+            online,
+            feed_button_pressed: false,
+            paper_present: true,
+        }
+    }
+
+    #[test]
+    fn first_reading_is_the_baseline() {
+        let mut monitor = StatusMonitor::new();
+        assert_eq!(monitor.drawer_open(), None);
+        monitor.update(status(true, true));
+        assert!(monitor.take_events().is_empty());
+        assert_eq!(monitor.drawer_open(), Some(true));
+    }
+
+    #[test]
+    fn queues_changes_until_taken() {
+        let mut monitor = StatusMonitor::new();
+        monitor.update(status(false, true));
+        monitor.update(status(true, true));
+        monitor.update(status(true, true));
+        monitor.update(status(false, false));
+        assert_eq!(
+            monitor.take_events(),
+            [StatusEvent::DrawerOpened, StatusEvent::DrawerClosed, StatusEvent::Offline]
+        );
+        assert!(monitor.take_events().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! NV graphics memory manager.
+
+use std::io::{Read, Write};
+
+use super::Printer;
+use crate::command::graphics::{
+    DefineNvGraphics, DeleteAllNvGraphics, DeleteNvGraphics, KeyCode, PrintNvGraphics,
+    QueryNvCapacity, QueryNvKeyCodes, QueryNvRemaining,
+};
+use crate::command::image::PrintRasterImage;
+use crate::command::{CAN, QueryCommand};
+use crate::error::{PrinterError, StatusParseError};
+
+/// Acknowledges a key code block.
+const ACK: u8 = 0x06;
+
+/// Manage graphics stored in the printer's NV graphics memory.
+///
+/// Obtained from [`Printer::nv_graphics`]. Storing, deleting and printing
+/// work on any printer; listing keys and reading capacity need a reader.
+///
+/// # Example
+///
+/// ```ignore
+/// use bixolon::command::graphics::KeyCode;
+///
+/// let logo_key = KeyCode::new(b'L', b'1')?;
+/// let mut nv = printer.nv_graphics();
+/// if !nv.keys()?.contains(&logo_key) {
+///     nv.define(logo_key, &logo)?;
+/// }
+/// nv.print(logo_key)?;
+/// ```
+pub struct NvGraphics<'a, W: Write, R = ()> {
+    printer: &'a mut Printer<W, R>,
+}
+
+impl<'a, W: Write, R> NvGraphics<'a, W, R> {
+    pub(super) fn new(printer: &'a mut Printer<W, R>) -> Self {
+        Self {
+            printer,
+        }
+    }
+
+    /// Store `image` under `key`, replacing any graphic already there.
+    ///
+    /// The data is flushed immediately, since the printer stops receiving
+    /// while it writes NV memory. Avoid more than about 10 writes a day.
+    pub fn define(&mut self, key: KeyCode, image: &PrintRasterImage) -> Result<(), PrinterError> {
+        self.printer.send(DefineNvGraphics {
+            key,
+            image: image.clone(),
+        })?;
+        self.printer.flush()?;
+        Ok(())
+    }
+
+    /// Print the graphic stored under `key` at normal size.
+    pub fn print(&mut self, key: KeyCode) -> Result<(), PrinterError> {
+        self.printer.send(PrintNvGraphics::new(key))?;
+        Ok(())
+    }
+
+    /// Delete the graphic stored under `key`.
+    pub fn delete(&mut self, key: KeyCode) -> Result<(), PrinterError> {
+        self.printer.send(DeleteNvGraphics(key))?;
+        Ok(())
+    }
+
+    /// Delete every stored graphic.
+    pub fn delete_all(&mut self) -> Result<(), PrinterError> {
+        self.printer.send(DeleteAllNvGraphics)?;
+        Ok(())
+    }
+}
+
+impl<W: Write, R: Read> NvGraphics<'_, W, R> {
+    /// Total NV graphics memory in bytes.
+    pub fn capacity(&mut self) -> Result<u32, PrinterError> {
+        self.query(QueryNvCapacity)
+    }
+
+    /// Unused NV graphics memory in bytes.
+    pub fn remaining(&mut self) -> Result<u32, PrinterError> {
+        self.query(QueryNvRemaining)
+    }
+
+    /// Key codes of every stored graphic.
+    ///
+    /// Reads each block of the list and acknowledges it. If a block can't
+    /// be parsed, the transfer is cancelled so the printer doesn't wait.
+    pub fn keys(&mut self) -> Result<Vec<KeyCode>, PrinterError> {
+        self.printer.send(QueryNvKeyCodes)?;
+        let mut keys = Vec::new();
+        loop {
+            let block = match self.read_response().and_then(|bytes| {
+                QueryNvKeyCodes.parse_response(&bytes).map_err(PrinterError::StatusParse)
+            }) {
+                Ok(block) => block,
+                Err(err) => {
+                    self.printer.send_raw(&[CAN])?;
+                    self.printer.flush()?;
+                    return Err(err);
+                }
+            };
+            keys.extend(block.keys);
+            self.printer.send_raw(&[ACK])?;
+            if !block.more {
+                self.printer.flush()?;
+                return Ok(keys);
+            }
+        }
+    }
+
+    fn query<Q: QueryCommand>(&mut self, cmd: Q) -> Result<Q::Response, PrinterError> {
+        self.printer.send_raw(&cmd.encode())?;
+        let bytes = self.read_response()?;
+        cmd.parse_response(&bytes).map_err(PrinterError::StatusParse)
+    }
+
+    /// Flush, then read one NUL-terminated response.
+    ///
+    /// Reads a byte at a time so a following key code block stays unread.
+    fn read_response(&mut self) -> Result<Vec<u8>, PrinterError> {
+        self.printer.flush()?;
+        let mut response = Vec::new();
+        let mut buf = [0u8; 1];
+        while response.last() != Some(&0x00) {
+            let n = self.printer.reader_mut().read(&mut buf)?;
+            if n == 0 {
+                return Err(if response.is_empty() {
+                    PrinterError::NoResponse
+                } else {
+                    PrinterError::StatusParse(StatusParseError::Truncated)
+                });
+            }
+            response.extend_from_slice(&buf[..n]);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use std::io::Cursor;
+
+    fn key(kc2: u8) -> KeyCode {
+        KeyCode::new(b'L', kc2).unwrap()
+    }
+
+    #[test]
+    fn define_print_and_delete_send_commands() {
+        let image = PrintRasterImage::new(1, 1, vec![0x80]);
+        let mut printer = Printer::new(Vec::new());
+        let mut nv = printer.nv_graphics();
+        nv.define(key(b'1'), &image).unwrap();
+        nv.print(key(b'1')).unwrap();
+        nv.delete(key(b'1')).unwrap();
+        nv.delete_all().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        let expected = [
+            DefineNvGraphics {
+                key: key(b'1'),
+                image,
+            }
+            .encode(),
+            PrintNvGraphics::new(key(b'1')).encode(),
+            DeleteNvGraphics(key(b'1')).encode(),
+            DeleteAllNvGraphics.encode(),
+        ]
+        .concat();
+        assert_eq!(inner, expected);
+    }
+
+    #[test]
+    fn keys_reads_and_acknowledges_every_block() {
+        let response = b"\x37\x72\x41L1L2\x00\x37\x72\x40L3\x00".to_vec();
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(response));
+        let keys = printer.nv_graphics().keys().unwrap();
+        assert_eq!(keys, [key(b'1'), key(b'2'), key(b'3')]);
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner, [QueryNvKeyCodes.encode(), vec![ACK, ACK]].concat());
+    }
+
+    #[test]
+    fn keys_cancels_on_malformed_block() {
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(b"\x37\x99\x00".to_vec()));
+        assert!(printer.nv_graphics().keys().is_err());
+
+        let (inner, _) = printer.into_inner();
+        assert!(inner.ends_with(&[CAN]));
+    }
+
+    #[test]
+    fn capacity_and_remaining() {
+        let response = b"\x370262144\x00".to_vec();
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(response));
+        assert_eq!(printer.nv_graphics().capacity().unwrap(), 262_144);
+
+        let response = b"\x371100000\x00".to_vec();
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(response));
+        assert_eq!(printer.nv_graphics().remaining().unwrap(), 100_000);
+    }
+}
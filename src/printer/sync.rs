@@ -1,12 +1,30 @@
 //! Synchronous printer interface.
 
-use std::io::{BufWriter, Read, Write};
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
 
+use crate::command::barcode::{PrintBarcode, barcode_fallback};
+use crate::command::basic::Cancel;
+use crate::command::character::{Justification, SetJustification};
+use crate::command::codepage::CodePage;
+use crate::command::image::{ColumnImage, Darkness, PrintRasterImage};
+use crate::command::page_mode::ExitPageMode;
 use crate::command::printer_control::Initialize;
-use crate::command::{Command, QueryCommand};
-use crate::error::PrinterError;
+use crate::command::rule::{RuleStyle, TextRule};
+use crate::command::status::{PrinterStatus, StatusResponse, StatusType, TransmitStatus};
+use crate::command::symbol::{PrintQrCode, qr_fallback};
+use crate::command::{Command, QueryCommand, StaticCommand};
+use crate::encoding::Fallback;
+use crate::error::{PrinterError, StatusError};
+use crate::footer::QrFooter;
 use crate::page::PageBuilder;
-use crate::style::text::StyledNode;
+use crate::printer::{
+    DEFAULT_QUERY_TIMEOUT, FlushPolicy, JobHistory, NvGraphics, READY_POLL_INTERVAL, StatusEvent,
+    StatusMonitor, response_complete,
+};
+use crate::profile::{PrinterProfile, StylePolicy};
+use crate::style::StyleSet;
+use crate::style::text::{StyleRenderer, StyledNode};
 
 /// Synchronous printer interface.
 ///
@@ -30,6 +48,21 @@ use crate::style::text::StyledNode;
 pub struct Printer<W: Write, R = ()> {
     writer: BufWriter<W>,
     reader: R,
+    profile: PrinterProfile,
+    style_policy: StylePolicy,
+    encoding_fallback: Option<Fallback>,
+    /// Code page the printer is in outside `code_page` styles, if not CP437.
+    code_page: Option<CodePage>,
+    renderer: Option<StyleRenderer>,
+    scopes: Vec<StyleSet>,
+    monitor: StatusMonitor,
+    history: Option<JobHistory>,
+    /// Scratch buffer commands and text are encoded into before writing.
+    buffer: Vec<u8>,
+    flush_policy: FlushPolicy,
+    /// Bytes written since the last flush.
+    unflushed: usize,
+    query_timeout: Duration,
 }
 
 impl<W: Write> Printer<W, ()> {
@@ -38,6 +71,18 @@ impl<W: Write> Printer<W, ()> {
         Self {
             writer: BufWriter::new(writer),
             reader: (),
+            profile: PrinterProfile::default(),
+            style_policy: StylePolicy::default(),
+            encoding_fallback: None,
+            code_page: None,
+            renderer: None,
+            scopes: Vec::new(),
+            monitor: StatusMonitor::new(),
+            history: None,
+            buffer: Vec::new(),
+            flush_policy: FlushPolicy::default(),
+            unflushed: 0,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
         }
     }
 }
@@ -48,22 +93,278 @@ impl<W: Write, R> Printer<W, R> {
         Self {
             writer: BufWriter::new(writer),
             reader,
+            profile: PrinterProfile::default(),
+            style_policy: StylePolicy::default(),
+            encoding_fallback: None,
+            code_page: None,
+            renderer: None,
+            scopes: Vec::new(),
+            monitor: StatusMonitor::new(),
+            history: None,
+            buffer: Vec::new(),
+            flush_policy: FlushPolicy::default(),
+            unflushed: 0,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
         }
     }
 
+    /// Use a capability profile other than the SRP-350plus default.
+    ///
+    /// The profile is checked when printing styled text, so features the
+    /// model lacks (such as red text) are rejected instead of misprinting.
+    pub fn with_profile(mut self, profile: PrinterProfile) -> Self {
+        self.profile = profile;
+        if self.renderer.is_some() {
+            self.renderer =
+                Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        }
+        self
+    }
+
+    /// Choose how styles the profile doesn't support are handled.
+    ///
+    /// The default, [`StylePolicy::Permissive`], only rejects color on
+    /// single-color models. [`StylePolicy::Strict`] also rejects reverse
+    /// printing and oversized character scales the profile lacks, and
+    /// [`StylePolicy::Downgrade`] quietly drops or clamps them instead.
+    pub fn with_style_policy(mut self, policy: StylePolicy) -> Self {
+        self.style_policy = policy;
+        self
+    }
+
+    /// Handle characters missing from the code page when printing text.
+    ///
+    /// By default text is sent as rendered, with unencodable characters
+    /// becoming `?`. With a fallback set, [`Fallback::Error`] rejects such
+    /// text before anything is sent, and [`Fallback::Transliterate`] prints
+    /// ASCII lookalikes instead. Text without an explicit code page is
+    /// checked against the printer's [code page](Self::with_code_page).
+    pub fn with_encoding_fallback(mut self, fallback: Fallback) -> Self {
+        self.encoding_fallback = Some(fallback);
+        self
+    }
+
+    /// Assume the printer is in `code_page` outside `code_page` styles,
+    /// e.g. one chosen by a memory switch or a
+    /// [`SelectCodePage`](crate::command::codepage::SelectCodePage) sent
+    /// earlier.
+    ///
+    /// Text without a code page of its own is encoded in it, and styled
+    /// text that switches to another page returns to it afterwards rather
+    /// than to CP437. See [`StyleRenderer::with_code_page`].
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = Some(code_page);
+        if self.renderer.is_some() {
+            self.renderer =
+                Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        }
+        self
+    }
+
+    /// Start `renderer` in the printer's [code page](Self::with_code_page).
+    fn in_code_page(&self, renderer: StyleRenderer) -> StyleRenderer {
+        match self.code_page {
+            Some(code_page) => renderer.with_code_page(code_page),
+            None => renderer,
+        }
+    }
+
+    /// Keep recent jobs in `history` for [`reprint_last`](Self::reprint_last).
+    ///
+    /// Every byte sent is recorded except status queries, and each
+    /// [`flush`](Self::flush) ends a job, so flush once per receipt.
+    pub fn with_job_history(mut self, history: JobHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Buffer up to `capacity` bytes before writing to the printer, instead
+    /// of 8 KiB.
+    ///
+    /// A small buffer suits a slow serial link, where bytes should start
+    /// moving early; a large one lets an image-heavy job reach the
+    /// transport in fewer, bigger writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if anything has been written but not flushed, as resizing
+    /// the buffer would lose it. Set the capacity when constructing the
+    /// printer.
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        assert!(self.writer.buffer().is_empty(), "printer buffer resized with unflushed bytes");
+        let (writer, _) = self.writer.into_parts();
+        self.writer = BufWriter::with_capacity(capacity, writer);
+        self
+    }
+
+    /// Flush automatically according to `policy`.
+    pub fn with_flush_policy(mut self, policy: FlushPolicy) -> Self {
+        self.flush_policy = policy;
+        self
+    }
+
+    /// Give up on a [`query`](Self::query) after `timeout`, instead of
+    /// [`DEFAULT_QUERY_TIMEOUT`].
+    ///
+    /// A blocking read can't be interrupted, so the deadline is checked
+    /// each time the reader gives up with [`ErrorKind::TimedOut`] or
+    /// [`ErrorKind::WouldBlock`], as serial ports and sockets with a read
+    /// timeout do. Set the transport's own timeout shorter than this.
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
+    /// Carry text style across `print`/`println` calls.
+    ///
+    /// By default every call returns the printer to its default style.
+    /// With tracking enabled the printer remembers the active style and
+    /// only sends the commands needed to reach the next node's style, so
+    /// consecutive bold lines send `ESC E 1` once. Call
+    /// [`reset_style`](Self::reset_style) before sending raw commands that
+    /// depend on the default style.
+    pub fn with_style_tracking(mut self) -> Self {
+        self.renderer = Some(self.in_code_page(StyleRenderer::for_profile(self.profile.clone())));
+        self
+    }
+
+    /// Return the printer to its default text style.
+    ///
+    /// Only sends commands when style tracking is enabled and a non-default
+    /// style is active.
+    pub fn reset_style(&mut self) -> Result<&mut Self, PrinterError> {
+        if let Some(renderer) = &mut self.renderer {
+            let bytes = renderer.reset();
+            self.write_bytes(&bytes)?;
+        }
+        Ok(self)
+    }
+
+    /// Run `f` with `style` applied to everything it prints.
+    ///
+    /// Styles nest: text printed inside the closure inherits the scope's
+    /// style unless it overrides an attribute itself, and the previous
+    /// effective style applies again once the closure returns (even on
+    /// error).
+    ///
+    /// ```
+    /// use bixolon::printer::Printer;
+    /// use bixolon::style::StyleSet;
+    ///
+    /// let mut printer = Printer::new(Vec::new());
+    /// printer.with_style(StyleSet::new().with_bold(true), |p| {
+    ///     p.println("TOTAL")?;
+    ///     p.println("25.00")?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), bixolon::PrinterError>(())
+    /// ```
+    pub fn with_style<T>(
+        &mut self,
+        style: StyleSet,
+        f: impl FnOnce(&mut Self) -> Result<T, PrinterError>,
+    ) -> Result<T, PrinterError> {
+        self.scopes.push(style);
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    /// The printer's capability profile.
+    pub fn profile(&self) -> &PrinterProfile {
+        &self.profile
+    }
+
+    /// The status changes seen so far.
+    ///
+    /// Fed by every printer status reading, such as
+    /// [`drawer_is_open`](Self::drawer_is_open).
+    pub fn status_monitor(&mut self) -> &mut StatusMonitor {
+        &mut self.monitor
+    }
+
+    /// The retained jobs, if [`with_job_history`](Self::with_job_history)
+    /// enabled them.
+    pub fn job_history(&self) -> Option<&JobHistory> {
+        self.history.as_ref()
+    }
+
     /// Send a command to the printer.
     ///
+    /// The command is encoded for the profile's
+    /// [`Dialect`](crate::command::Dialect).
+    ///
     /// Does not flush - call `flush()` to ensure data is sent.
     pub fn send(&mut self, cmd: impl Command) -> Result<&mut Self, PrinterError> {
-        self.writer.write_all(&cmd.encode())?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(command = std::any::type_name_of_val(&cmd), "sending command");
+        self.write_encoded(|printer, buffer| cmd.encode_into_for(printer.profile.dialect, buffer))?;
+        Ok(self)
+    }
+
+    /// Send several commands in one write.
+    ///
+    /// The commands are encoded back to back into one buffer, each for the
+    /// profile's [`Dialect`](crate::command::Dialect), and written at once,
+    /// saving the per-write overhead of a serial or USB transfer when
+    /// sending many small configuration commands.
+    ///
+    /// Does not flush - call `flush()` to ensure data is sent.
+    pub fn send_all<'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a dyn Command>,
+    ) -> Result<&mut Self, PrinterError> {
+        self.write_encoded(|printer, buffer| {
+            for cmd in commands {
+                cmd.encode_into_for(printer.profile.dialect, buffer);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(bytes = buffer.len(), "sending batched commands");
+        })?;
         Ok(self)
     }
 
+    /// Encode into the reusable buffer with `encode`, then write it.
+    fn write_encoded(
+        &mut self,
+        encode: impl FnOnce(&mut Self, &mut Vec<u8>),
+    ) -> std::io::Result<()> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        encode(self, &mut buffer);
+        let result = self.write_bytes(&buffer);
+        self.buffer = buffer;
+        result
+    }
+
+    /// Write `bytes` to the printer's buffer.
+    fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if let Some(history) = &mut self.history {
+            history.record(bytes);
+        }
+        self.write_unrecorded(bytes)
+    }
+
+    /// Write without recording the bytes in the job history.
+    fn write_unrecorded(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(bytes = bytes.len(), "writing to printer");
+        self.writer.write_all(bytes)?;
+        self.unflushed += bytes.len();
+        if self.flush_policy.should_flush(bytes, self.unflushed, self.profile.dialect) {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(bytes = self.unflushed, "flushing printer by policy");
+            self.writer.flush()?;
+            self.unflushed = 0;
+        }
+        Ok(())
+    }
+
     /// Send raw bytes to the printer.
     ///
     /// Does not flush - call `flush()` to ensure data is sent.
     pub fn send_raw(&mut self, bytes: &[u8]) -> Result<&mut Self, PrinterError> {
-        self.writer.write_all(bytes)?;
+        self.write_bytes(bytes)?;
         Ok(self)
     }
 
@@ -71,32 +372,284 @@ impl<W: Write, R> Printer<W, R> {
     ///
     /// Does not add a line feed. Use `println` for that.
     pub fn print(&mut self, text: impl Into<StyledNode>) -> Result<&mut Self, PrinterError> {
-        let node = text.into();
-        self.writer.write_all(&node.render())?;
+        let node = self.prepare(text.into())?;
+        self.write_encoded(|printer, buffer| match &mut printer.renderer {
+            Some(renderer) => renderer.render_into(&node, buffer),
+            None => {
+                let mut renderer =
+                    printer.in_code_page(StyleRenderer::eager(printer.profile.clone()));
+                renderer.render_into(&node, buffer);
+                renderer.reset_into(buffer);
+            }
+        })?;
         Ok(self)
     }
 
     /// Print styled text followed by a line feed.
     pub fn println(&mut self, text: impl Into<StyledNode>) -> Result<&mut Self, PrinterError> {
-        let node = text.into();
-        self.writer.write_all(&node.render_line())?;
+        let node = self.prepare(text.into())?;
+        self.write_encoded(|printer, buffer| match &mut printer.renderer {
+            Some(renderer) => renderer.render_line_into(&node, buffer),
+            None => {
+                let mut renderer =
+                    printer.in_code_page(StyleRenderer::eager(printer.profile.clone()));
+                renderer.render_into(&node, buffer);
+                renderer.finish_line(buffer);
+            }
+        })?;
+        Ok(self)
+    }
+
+    /// Apply scopes, the style policy and the encoding fallback to `node`.
+    fn prepare(&self, node: StyledNode) -> Result<StyledNode, PrinterError> {
+        let node = self.profile.apply_policy(self.style_policy, self.scoped(node))?;
+        match self.encoding_fallback {
+            Some(fallback) => {
+                Ok(node.apply_fallback_in(self.code_page.unwrap_or_default(), fallback)?)
+            }
+            None => Ok(node),
+        }
+    }
+
+    /// Wrap `node` in the styles of the active `with_style` scopes.
+    fn scoped(&self, node: StyledNode) -> StyledNode {
+        if self.scopes.is_empty() {
+            node
+        } else {
+            node.with_style(StyleSet::from_stack(&self.scopes))
+        }
+    }
+
+    /// Print a full-width horizontal rule sized from the printer profile.
+    ///
+    /// Uses CP437 box-drawing characters; see [`TextRule`].
+    pub fn rule(&mut self, style: RuleStyle) -> Result<&mut Self, PrinterError> {
+        self.write_bytes(&TextRule::full_width(style, &self.profile).encode())?;
         Ok(self)
     }
 
     /// Print a page mode document.
+    ///
+    /// The page is streamed to the printer as it is encoded; see
+    /// [`PageBuilder::build_into`].
     pub fn print_page(&mut self, page: PageBuilder) -> Result<&mut Self, PrinterError> {
-        self.writer.write_all(&page.build())?;
+        self.write_page(&page)
+    }
+
+    /// Stream a borrowed page mode document, for [`PageBuilder::write_to`].
+    pub(crate) fn write_page(&mut self, page: &PageBuilder) -> Result<&mut Self, PrinterError> {
+        page.build_into(&mut PrinterWriter(self))?;
+        Ok(self)
+    }
+
+    /// Print a page mode document `copies` times.
+    ///
+    /// The page is buffered once and reprinted without being re-sent.
+    pub fn print_page_copies(
+        &mut self,
+        page: PageBuilder,
+        copies: u8,
+    ) -> Result<&mut Self, PrinterError> {
+        self.write_bytes(&page.build_copies(copies)?)?;
         Ok(self)
     }
 
     /// Print a page mode document and return to standard mode.
     pub fn print_page_and_exit(&mut self, page: PageBuilder) -> Result<&mut Self, PrinterError> {
-        self.writer.write_all(&page.build_and_exit())?;
+        self.write_bytes(&page.build_and_exit()?)?;
+        Ok(self)
+    }
+
+    /// Print a raster image in bands.
+    ///
+    /// Tall images are split with [`PrintRasterImage::bands`] so no single
+    /// command overflows the printer's receive buffer. Use
+    /// [`print_image_checked`](Self::print_image_checked) to stop early if
+    /// the printer goes offline part way through. Images failing
+    /// [`PrintRasterImage::validate`] are rejected before anything is sent.
+    pub fn print_image(&mut self, image: &PrintRasterImage) -> Result<&mut Self, PrinterError> {
+        image.validate()?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            width_bytes = image.width_bytes,
+            height_dots = image.height_dots,
+            "printing raster image"
+        );
+        let dialect = self.profile.dialect;
+        image.write_bands_to(image.band_height(), dialect, &mut PrinterWriter(self))?;
+        Ok(self)
+    }
+
+    /// Print a raster image with extra passes to darken it.
+    ///
+    /// [`Darkness::Normal`] prints like [`print_image`](Self::print_image).
+    /// Darker settings send the image as [`ColumnImage`] bands, striking
+    /// each band repeatedly before feeding the paper.
+    pub fn print_image_with_darkness(
+        &mut self,
+        image: &PrintRasterImage,
+        darkness: Darkness,
+    ) -> Result<&mut Self, PrinterError> {
+        if darkness == Darkness::Normal {
+            return self.print_image(image);
+        }
+        image.validate()?;
+        let columns = ColumnImage::try_from(image)?.with_darkness(darkness);
+        self.write_bytes(&columns.encode())?;
+        Ok(self)
+    }
+
+    /// Manage graphics stored in NV graphics memory.
+    pub fn nv_graphics(&mut self) -> NvGraphics<'_, W, R> {
+        NvGraphics::new(self)
+    }
+
+    /// Print a QR code, natively or as a raster image.
+    ///
+    /// Uses `GS ( k` when the profile reports native QR support. Otherwise,
+    /// with the `qrcode` feature, the symbol is rendered with
+    /// [`PrintQrCode::to_raster`] and printed as an image.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`](crate::error::ValidationError::Unsupported)
+    /// if the profile lacks QR support and the `qrcode` feature is
+    /// disabled, or [`PrinterError::QrCode`] if rendering fails.
+    pub fn print_qr_code(&mut self, qr: &PrintQrCode) -> Result<&mut Self, PrinterError> {
+        match qr_fallback(qr, &self.profile)? {
+            Some(image) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("rendering QR code as a raster image");
+                self.print_image(&image)
+            }
+            None => {
+                self.write_bytes(&qr.encode())?;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Print a [`QrFooter`]: its QR code centered, then its captions.
+    ///
+    /// The symbol falls back to a raster image like
+    /// [`print_qr_code`](Self::print_qr_code).
+    ///
+    /// # Errors
+    ///
+    /// As for [`print_qr_code`](Self::print_qr_code) and
+    /// [`println`](Self::println).
+    pub fn print_qr_footer(&mut self, footer: &QrFooter) -> Result<&mut Self, PrinterError> {
+        self.send(SetJustification(Justification::Center))?;
+        self.print_qr_code(&footer.qr_code(&self.profile))?;
+        self.send(SetJustification(Justification::Left))?;
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        for line in footer.captions() {
+            self.println(line.clone().with_style(centered.clone()))?;
+        }
+        Ok(self)
+    }
+
+    /// Print a barcode, rendering it host-side if the profile requires.
+    ///
+    /// Barcodes with more data than [`PrinterProfile::max_barcode_data`]
+    /// are printed as raster images at the default module width and
+    /// height; others are sent as `GS k` using the current barcode settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Barcode`] if a barcode needing the fallback
+    /// can't be rendered.
+    pub fn print_barcode(&mut self, barcode: &PrintBarcode) -> Result<&mut Self, PrinterError> {
+        match barcode_fallback(barcode, &self.profile)? {
+            Some(image) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("rendering barcode as a raster image");
+                self.print_image(&image)
+            }
+            None => {
+                self.write_bytes(&barcode.encode())?;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Print Japanese or Chinese text using the profile's Kanji mode.
+    ///
+    /// Two-byte characters are sent in Kanji mode, encoded with
+    /// [`PrinterProfile::cjk`]; other characters are sent in the code page
+    /// of the enclosing [`with_style`](Self::with_style) scopes, or the
+    /// printer's [code page](Self::with_code_page) if they set none. See [`encode_mixed`](crate::encoding::cjk::encode_mixed).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`](crate::error::ValidationError::Unsupported)
+    /// if the profile has no Kanji mode, or [`PrinterError::Encoding`] if
+    /// a character can't be encoded.
+    #[cfg(feature = "cjk")]
+    pub fn print_cjk(&mut self, text: &str) -> Result<&mut Self, PrinterError> {
+        use crate::command::codepage::SelectCodePage;
+
+        let Some(encoding) = self.profile.cjk else {
+            return Err(crate::error::ValidationError::Unsupported {
+                feature: "Kanji mode",
+                profile: self.profile.name.to_string(),
+            }
+            .into());
+        };
+        let base = self.code_page.unwrap_or_default();
+        let code_page = StyleSet::from_stack(&self.scopes).code_page.unwrap_or(base);
+        let mut bytes = Vec::new();
+        if code_page != base {
+            bytes.extend(SelectCodePage(code_page).to_bytes());
+        }
+        bytes.extend(crate::encoding::cjk::encode_mixed(text, encoding, code_page)?);
+        if code_page != base {
+            bytes.extend(SelectCodePage(base).to_bytes());
+        }
+        self.write_bytes(&bytes)?;
+        Ok(self)
+    }
+
+    /// Print text, downloading glyphs for characters the code page lacks.
+    ///
+    /// Text is encoded in the default code page for Font A; missing
+    /// characters are drawn from built-in bitmap fonts and sent as
+    /// user-defined characters, up to
+    /// [`PrinterProfile::user_defined_characters`] distinct ones. See
+    /// [`encode_with_glyphs`](crate::encoding::glyph::encode_with_glyphs).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Encoding`] if a character has no glyph or
+    /// the text needs more user-defined characters than the printer holds.
+    #[cfg(feature = "glyphs")]
+    pub fn print_with_glyphs(&mut self, text: &str) -> Result<&mut Self, PrinterError> {
+        let bytes = crate::encoding::glyph::encode_with_glyphs(
+            text,
+            Default::default(),
+            crate::command::character::Font::A,
+            self.profile.user_defined_characters,
+        )?;
+        self.write_bytes(&bytes)?;
+        Ok(self)
+    }
+
+    /// Abandon a partially sent page mode document.
+    ///
+    /// Cancels the buffered page data and returns to standard mode, e.g.
+    /// after an application error left a page half-built. Harmless when the
+    /// printer is already in standard mode.
+    pub fn abort_page(&mut self) -> Result<&mut Self, PrinterError> {
+        self.write_bytes(&Cancel.encode())?;
+        self.write_bytes(ExitPageMode::BYTES)?;
         Ok(self)
     }
 
     /// Initialize the printer (reset to defaults).
     pub fn initialize(&mut self) -> Result<&mut Self, PrinterError> {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.assume_default();
+        }
         self.send(Initialize)
     }
 
@@ -104,7 +657,36 @@ impl<W: Write, R> Printer<W, R> {
     ///
     /// Call this to ensure all pending data is sent.
     pub fn flush(&mut self) -> Result<&mut Self, PrinterError> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!("flushing printer");
         self.writer.flush()?;
+        self.unflushed = 0;
+        if let Some(history) = &mut self.history {
+            history.end_job();
+        }
+        Ok(self)
+    }
+
+    /// Send the most recent completed job again, e.g. for a customer copy.
+    ///
+    /// The job is sent exactly as recorded, so any drawer pulse in it is
+    /// sent again too. The reprint is not recorded as a new job. Does not
+    /// flush - call `flush()` to ensure data is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::NoJobToReprint`] if job history is disabled
+    /// or no job has been flushed yet.
+    pub fn reprint_last(&mut self) -> Result<&mut Self, PrinterError> {
+        let job = self
+            .history
+            .as_ref()
+            .and_then(JobHistory::last)
+            .ok_or(PrinterError::NoJobToReprint)?
+            .to_vec();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bytes = job.len(), "reprinting last job");
+        self.write_unrecorded(&job)?;
         Ok(self)
     }
 
@@ -149,30 +731,213 @@ impl<W: Write, R> Printer<W, R> {
     }
 }
 
+/// Writes through a printer, recording history and applying its flush
+/// policy, for streaming encoders.
+struct PrinterWriter<'a, W: Write, R>(&'a mut Printer<W, R>);
+
+impl<W: Write, R> Write for PrinterWriter<'_, W, R> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write_bytes(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 impl<W: Write, R: Read> Printer<W, R> {
+    /// Print a raster image in bands, checking status between them.
+    ///
+    /// Like [`print_image`](Self::print_image), but before each band after
+    /// the first the printer is asked whether it is still online. Printing
+    /// stops with [`PrinterError::Status`] if it isn't, instead of streaming
+    /// the rest of the image into a printer that can't print it.
+    ///
+    /// Images failing [`PrintRasterImage::validate`] are rejected before
+    /// anything is sent.
+    pub fn print_image_checked(
+        &mut self,
+        image: &PrintRasterImage,
+    ) -> Result<&mut Self, PrinterError> {
+        image.validate()?;
+        for (index, band) in image.band_slices(image.band_height()).enumerate() {
+            if index > 0 {
+                self.ensure_online()?;
+            }
+            band.emit(self.profile.dialect, &mut |bytes| self.write_bytes(bytes))?;
+        }
+        Ok(self)
+    }
+
+    /// Read the printer status with `DLE EOT 1`, recording it in the
+    /// [status monitor](Self::status_monitor).
+    pub fn printer_status(&mut self) -> Result<PrinterStatus, PrinterError> {
+        match self.query(TransmitStatus(StatusType::Printer))? {
+            StatusResponse::Printer(status) => {
+                self.monitor.update(status);
+                Ok(status)
+            }
+            _ => unreachable!("printer status query answered with another status"),
+        }
+    }
+
+    /// Whether the cash drawer is open.
+    ///
+    /// Reads pin 3 of the drawer kick-out connector. Most drawers close the
+    /// switch on that pin when open; for one wired the other way round,
+    /// negate the result.
+    pub fn drawer_is_open(&mut self) -> Result<bool, PrinterError> {
+        Ok(self.printer_status()?.drawer_open)
+    }
+
+    /// Read the printer status and return what changed since the last
+    /// reading, such as [`StatusEvent::DrawerOpened`].
+    ///
+    /// Call this periodically to alarm on a drawer left open or a printer
+    /// going offline. The first call only establishes the baseline.
+    pub fn poll_status(&mut self) -> Result<Vec<StatusEvent>, PrinterError> {
+        self.printer_status()?;
+        Ok(self.monitor.take_events())
+    }
+
+    /// Return an error describing why the printer is offline, if it is.
+    ///
+    /// Sends real-time status requests, so this works while the printer is
+    /// busy.
+    pub fn ensure_online(&mut self) -> Result<(), PrinterError> {
+        if self.printer_status()?.online {
+            return Ok(());
+        }
+        if let StatusResponse::PaperRoll(status) =
+            self.query(TransmitStatus(StatusType::PaperRoll))?
+            && status.paper_end
+        {
+            return Err(StatusError::PaperEnd.into());
+        }
+        if let StatusResponse::Offline(status) = self.query(TransmitStatus(StatusType::Offline))? {
+            if status.cover_open {
+                return Err(StatusError::CoverOpen.into());
+            }
+            if status.cutter_error {
+                return Err(StatusError::CutterError.into());
+            }
+        }
+        Err(StatusError::Offline.into())
+    }
+
+    /// Wait up to `timeout` for the printer to come online, e.g. after
+    /// power-on or while the cover is open.
+    ///
+    /// Asks for the printer status every 100 ms. Status requests that go
+    /// unanswered count as not ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Timeout`] if the printer is still not online
+    /// after `timeout`.
+    pub fn wait_until_ready(&mut self, timeout: Duration) -> Result<&mut Self, PrinterError> {
+        let started = Instant::now();
+        loop {
+            match self.printer_status() {
+                Ok(status) if status.online => return Ok(self),
+                Ok(_)
+                | Err(PrinterError::Timeout {
+                    ..
+                }) => {}
+                Err(err) => return Err(err),
+            }
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(PrinterError::Timeout {
+                    operation: "wait_until_ready",
+                    elapsed,
+                });
+            }
+            std::thread::sleep(READY_POLL_INTERVAL.min(timeout - elapsed));
+        }
+    }
+
     /// Execute a query command and parse the response.
     ///
-    /// Flushes the write buffer before reading the response.
+    /// Flushes the write buffer before reading the response. Automatic
+    /// Status Back packets read ahead of the response are recorded in the
+    /// [status monitor](Self::status_monitor) rather than parsed as it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrinterError::Timeout`] if the reader keeps timing out
+    /// past the [query timeout](Self::with_query_timeout), and
+    /// [`PrinterError::NoResponse`] if it is at end of file.
     pub fn query<Q: QueryCommand>(&mut self, cmd: Q) -> Result<Q::Response, PrinterError> {
+        let started = Instant::now();
+
         // Send the query command
-        self.writer.write_all(&cmd.encode())?;
+        self.write_unrecorded(&cmd.encode_for(self.profile.dialect))?;
         self.writer.flush()?;
 
-        // Read response
+        // Read response, skipping any ASB packets the printer sent first
         let mut buf = [0u8; 64];
-        let n = self.reader.read(&mut buf)?;
-
-        if n == 0 {
-            return Err(PrinterError::NoResponse);
+        let mut n = 0;
+        while !response_complete(&cmd, &mut buf, &mut n, &mut self.monitor) {
+            match self.reader.read(&mut buf[n..]) {
+                Ok(0) if n == 0 => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        command = std::any::type_name_of_val(&cmd),
+                        "no response to query"
+                    );
+                    return Err(PrinterError::NoResponse);
+                }
+                // A truncated ASB packet or response, left for the parser to reject
+                Ok(0) => break,
+                Ok(read) => n += read,
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::TimedOut | ErrorKind::WouldBlock | ErrorKind::Interrupted
+                    ) =>
+                {
+                    let elapsed = started.elapsed();
+                    if elapsed >= self.query_timeout {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(
+                            command = std::any::type_name_of_val(&cmd),
+                            ?elapsed,
+                            "query timed out"
+                        );
+                        return Err(PrinterError::Timeout {
+                            operation: "query",
+                            elapsed,
+                        });
+                    }
+                    if err.kind() == ErrorKind::WouldBlock {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
         }
 
-        cmd.parse_response(&buf[..n]).map_err(PrinterError::StatusParse)
+        let response = cmd.parse_response(&buf[..n]).map_err(PrinterError::StatusParse);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            command = std::any::type_name_of_val(&cmd),
+            bytes = n,
+            elapsed = ?started.elapsed(),
+            ok = response.is_ok(),
+            "query answered"
+        );
+        response
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::Dialect;
+    use crate::command::barcode::BarcodeSystem;
+    use crate::command::paper::CutPaper;
     use std::io::Cursor;
 
     #[test]
@@ -200,6 +965,294 @@ mod tests {
         assert_eq!(inner, vec![0x1B, b'@']);
     }
 
+    #[test]
+    fn send_encodes_for_profile_dialect() {
+        let profile = PrinterProfile::SRP_350PLUS.with_dialect(Dialect::Star);
+        let mut printer = Printer::new(Vec::new()).with_profile(profile);
+
+        printer.send(CutPaper::partial()).unwrap();
+
+        assert_eq!(printer.into_inner().0, [0x1B, b'd', 1]);
+    }
+
+    #[test]
+    fn send_all_encodes_every_command_for_profile_dialect() {
+        let profile = PrinterProfile::SRP_350PLUS.with_dialect(Dialect::Star);
+        let mut printer = Printer::new(Vec::new()).with_profile(profile);
+
+        let commands: [&dyn Command; 3] =
+            [&Initialize, &SetJustification(Justification::Center), &CutPaper::partial()];
+        printer.send_all(commands).unwrap();
+
+        assert_eq!(printer.into_inner().0, [0x1B, b'@', 0x1B, b'a', 1, 0x1B, b'd', 1]);
+    }
+
+    #[test]
+    fn flush_policy_flushes_after_cuts_and_byte_limit() {
+        let policy = FlushPolicy::manual().after_cut();
+        let mut printer = Printer::new(Vec::new()).with_flush_policy(policy);
+        printer.println("Total").unwrap();
+        assert!(printer.writer().is_empty());
+        printer.send(CutPaper::partial()).unwrap();
+        assert_eq!(printer.writer(), b"Total\n\x1dV\x01");
+
+        let policy = FlushPolicy::manual().after_bytes(4);
+        let mut printer =
+            Printer::new(Vec::new()).with_buffer_capacity(64).with_flush_policy(policy);
+        printer.print("abc").unwrap();
+        assert!(printer.writer().is_empty());
+        printer.print("d").unwrap();
+        assert_eq!(printer.writer(), b"abcd");
+    }
+
+    #[test]
+    fn page_write_to_streams_page() {
+        let page = PageBuilder::new().text_line("Stub").page_break().text_line("Ticket");
+        let mut printer = Printer::new(Vec::new());
+
+        page.write_to(&mut printer).unwrap();
+        printer.print_page(page.clone()).unwrap();
+
+        let (inner, _) = printer.into_inner();
+        let built = page.build().unwrap();
+        assert_eq!(inner, [built.clone(), built].concat());
+    }
+
+    /// Answers each status query with the next byte.
+    struct StatusBytes(std::collections::VecDeque<u8>);
+
+    impl Read for StatusBytes {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.pop_front() {
+                Some(byte) => {
+                    buf[0] = byte;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    /// Never answers, failing every read with `kind` as a reader with a
+    /// read timeout does.
+    struct Silent(ErrorKind);
+
+    impl Read for Silent {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(self.0.into())
+        }
+    }
+
+    #[test]
+    fn query_times_out() {
+        for kind in [ErrorKind::TimedOut, ErrorKind::WouldBlock] {
+            let mut printer = Printer::with_reader(Vec::new(), Silent(kind))
+                .with_query_timeout(Duration::from_millis(5));
+            let result = printer.query(TransmitStatus(StatusType::Printer));
+            assert!(matches!(
+                result,
+                Err(PrinterError::Timeout { operation: "query", elapsed })
+                    if elapsed >= Duration::from_millis(5)
+            ));
+        }
+    }
+
+    #[test]
+    fn wait_until_ready_polls_until_online() {
+        let status = StatusBytes([0x1A, 0x12].into());
+        let mut printer = Printer::with_reader(Vec::new(), status);
+        printer.wait_until_ready(Duration::from_secs(1)).unwrap();
+        assert_eq!(printer.into_inner().0, b"\x10\x04\x01".repeat(2));
+
+        let mut printer = Printer::with_reader(Vec::new(), Silent(ErrorKind::TimedOut))
+            .with_query_timeout(Duration::from_millis(5));
+        assert!(matches!(
+            printer.wait_until_ready(Duration::from_millis(20)).map(|_| ()),
+            Err(PrinterError::Timeout {
+                operation: "wait_until_ready",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn print_image_sends_bands() {
+        let image = PrintRasterImage::new(64, 130, vec![0xAA; 64 * 130]);
+        let mut printer = Printer::new(Vec::new());
+        printer.print_image(&image).unwrap();
+
+        let (inner, _) = printer.into_inner();
+        let expected: Vec<u8> = image.bands(64).flat_map(|band| band.encode()).collect();
+        assert_eq!(inner, expected);
+        assert_eq!(inner.windows(3).filter(|w| w == b"\x1dv0").count(), 3);
+    }
+
+    #[test]
+    fn print_image_with_darkness_strikes_columns() {
+        let image = PrintRasterImage::new(2, 30, vec![0xF0; 60]);
+        let mut printer = Printer::new(Vec::new());
+        printer.print_image_with_darkness(&image, Darkness::Normal).unwrap();
+        printer.print_image_with_darkness(&image, Darkness::Double).unwrap();
+
+        let (inner, _) = printer.into_inner();
+        let columns = ColumnImage::try_from(&image).unwrap().with_darkness(Darkness::Double);
+        assert_eq!(inner, [image.encode(), columns.encode()].concat());
+    }
+
+    #[cfg(feature = "cjk")]
+    #[test]
+    fn print_cjk_requires_kanji_mode() {
+        use crate::encoding::CjkEncoding;
+
+        let mut printer = Printer::new(Vec::new());
+        assert!(matches!(
+            printer.print_cjk("日本").map(|_| ()),
+            Err(PrinterError::Validation(crate::error::ValidationError::Unsupported { .. }))
+        ));
+
+        let profile = PrinterProfile::SRP_350PLUS.with_cjk(Some(CjkEncoding::Gb18030));
+        let mut printer = Printer::new(Vec::new()).with_profile(profile);
+        printer.print_cjk("中").unwrap();
+        assert_eq!(printer.into_inner().0, b"\x1c&\xd6\xd0\x1c.");
+    }
+
+    #[cfg(feature = "cjk")]
+    #[test]
+    fn print_cjk_uses_the_scope_code_page() {
+        use crate::command::codepage::CodePage;
+        use crate::encoding::CjkEncoding;
+
+        let profile = PrinterProfile::SRP_350PLUS.with_cjk(Some(CjkEncoding::ShiftJis));
+        let mut printer = Printer::new(Vec::new()).with_profile(profile);
+        let style = StyleSet::new().with_code_page(CodePage::Cp858Euro);
+        printer.with_style(style, |p| p.print_cjk("5€").map(|_| ())).unwrap();
+        let bytes = printer.into_inner().0;
+        assert!(bytes.starts_with(b"\x1bt\x13"));
+        assert!(bytes.windows(2).any(|w| w == b"5\xd5"));
+        assert!(bytes.ends_with(b"\x1bt\x00"));
+    }
+
+    #[test]
+    fn code_page_styles_return_to_the_printer_code_page() {
+        use crate::style::text::Styleable;
+
+        let node = "5€ ".append("Чай".code_page(CodePage::Windows1251Cyrillic));
+        let expected = b"5\xd5 \x1bt\x1c\xd7\xe0\xe9\x1bt\x13";
+
+        let mut printer = Printer::new(Vec::new()).with_code_page(CodePage::Cp858Euro);
+        printer.print(node.clone()).unwrap();
+        assert_eq!(printer.into_inner().0, expected);
+
+        let mut printer =
+            Printer::new(Vec::new()).with_style_tracking().with_code_page(CodePage::Cp858Euro);
+        printer.print(node).unwrap().reset_style().unwrap();
+        assert_eq!(printer.into_inner().0, expected);
+    }
+
+    #[test]
+    fn encoding_fallback_applies_to_printed_text() {
+        let mut printer = Printer::new(Vec::new()).with_encoding_fallback(Fallback::Transliterate);
+        printer.print("“Tea™”").unwrap();
+        assert_eq!(printer.into_inner().0, b"\"TeaTM\"");
+
+        let mut printer = Printer::new(Vec::new()).with_encoding_fallback(Fallback::Error);
+        assert!(matches!(printer.println("Чай").map(|_| ()), Err(PrinterError::Encoding(_))));
+        assert!(printer.into_inner().0.is_empty());
+    }
+
+    #[test]
+    fn print_barcode_falls_back_past_profile_limit() {
+        let barcode = PrintBarcode::new(BarcodeSystem::Jan13, "590123412345").unwrap();
+        let mut printer = Printer::new(Vec::new());
+        printer.print_barcode(&barcode).unwrap();
+        assert_eq!(printer.into_inner().0, barcode.encode());
+
+        let profile = PrinterProfile::SRP_350PLUS.with_max_barcode_data(0);
+        let mut printer = Printer::new(Vec::new()).with_profile(profile);
+        printer.print_barcode(&barcode).unwrap();
+        let image = barcode.to_raster(Default::default(), Default::default()).unwrap();
+        let expected: Vec<u8> =
+            image.bands(image.band_height()).flat_map(|band| band.encode()).collect();
+        assert_eq!(printer.into_inner().0, expected);
+    }
+
+    #[test]
+    fn print_image_checked_stops_when_paper_runs_out() {
+        let image = PrintRasterImage::new(64, 130, vec![0xAA; 64 * 130]);
+        // Online before band 2, then offline with the paper end sensor set
+        let status = StatusBytes([0x12, 0x1A, 0x72].into());
+        let mut printer = Printer::with_reader(Vec::new(), status);
+
+        let result = printer.print_image_checked(&image).map(|_| ());
+        assert!(matches!(result, Err(PrinterError::Status(StatusError::PaperEnd))));
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner.windows(3).filter(|w| w == b"\x1dv0").count(), 2);
+    }
+
+    #[test]
+    fn poll_status_reports_drawer_changes() {
+        let status = StatusBytes([0x12, 0x16, 0x16, 0x12].into());
+        let mut printer = Printer::with_reader(Vec::new(), status);
+
+        assert!(!printer.drawer_is_open().unwrap());
+        assert_eq!(printer.poll_status().unwrap(), [StatusEvent::DrawerOpened]);
+        assert!(printer.drawer_is_open().unwrap());
+        assert_eq!(printer.poll_status().unwrap(), [StatusEvent::DrawerClosed]);
+        assert_eq!(printer.into_inner().0, b"\x10\x04\x01".repeat(4));
+    }
+
+    #[test]
+    fn query_skips_asb_packets() {
+        // The drawer opens and closes again before the answer arrives
+        let status = StatusBytes([0x14, 0x00, 0x00, 0x00, 0x12].into());
+        let mut printer = Printer::with_reader(Vec::new(), status);
+        assert_eq!(printer.poll_status().unwrap(), [StatusEvent::DrawerClosed]);
+
+        // Several packets in one read, with the answer
+        let packets = [0x10, 0x00, 0x00, 0x00, 0x18, 0x00, 0x0C, 0x00, 0x1A];
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(packets));
+        let status = printer.printer_status().unwrap();
+        assert!(!status.online);
+        assert_eq!(
+            printer.status_monitor().take_events(),
+            [StatusEvent::Offline, StatusEvent::PaperOut, StatusEvent::PaperLoaded]
+        );
+    }
+
+    #[test]
+    fn query_waits_for_the_rest_of_a_split_response() {
+        use crate::command::symbol::QuerySymbolStorage;
+
+        let reader = Cursor::new(b"\x37\x76120\x1F".to_vec()).chain(Cursor::new(b"96\x1F1\x00"));
+        let mut printer = Printer::with_reader(Vec::new(), reader);
+        let info = printer.query(QuerySymbolStorage).unwrap();
+        assert_eq!((info.width_dots, info.height_dots), (120, 96));
+        assert!(!info.printable);
+
+        let mut printer = Printer::with_reader(Vec::new(), Cursor::new(b"\x37\x76120".to_vec()));
+        assert!(matches!(
+            printer.query(QuerySymbolStorage),
+            Err(PrinterError::StatusParse(crate::error::StatusParseError::Incomplete))
+        ));
+    }
+
+    #[test]
+    fn reprint_last_resends_the_flushed_job() {
+        let status = StatusBytes([0x12].into());
+        let mut printer =
+            Printer::with_reader(Vec::new(), status).with_job_history(JobHistory::new(2));
+        assert!(matches!(printer.reprint_last(), Err(PrinterError::NoJobToReprint)));
+
+        printer.send_raw(b"receipt").unwrap();
+        printer.drawer_is_open().unwrap();
+        printer.send_raw(b"\n").unwrap().flush().unwrap();
+        printer.reprint_last().unwrap().flush().unwrap();
+
+        assert_eq!(printer.job_history().unwrap().len(), 1);
+        assert_eq!(printer.into_inner().0, b"receipt\x10\x04\x01\nreceipt\n");
+    }
+
     #[test]
     fn send_raw_writes_bytes() {
         let buf = Vec::new();
@@ -242,6 +1295,118 @@ mod tests {
         assert!(inner.ends_with(&[0x0A]));
     }
 
+    #[test]
+    fn println_rejects_color_on_single_color_profile() {
+        use crate::style::text::Styleable;
+
+        let mut printer = Printer::new(Vec::new());
+        let result = printer.println("VOID".red());
+        assert!(matches!(result, Err(PrinterError::Validation(_))));
+
+        let mut printer =
+            Printer::new(Vec::new()).with_profile(PrinterProfile::SRP_350PLUS.with_two_color(true));
+        printer.println("VOID".red()).unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert!(inner.starts_with(&[0x1B, b'r', 1]));
+    }
+
+    #[test]
+    fn strict_style_policy_rejects_unsupported_reverse() {
+        use crate::error::ValidationError;
+        use crate::style::text::Styleable;
+
+        let profile = PrinterProfile::SRP_350PLUS.with_reverse(false);
+        let mut printer =
+            Printer::new(Vec::new()).with_profile(profile).with_style_policy(StylePolicy::Strict);
+        let result = printer.println("VOID".reversed());
+        assert!(matches!(
+            result,
+            Err(PrinterError::Validation(ValidationError::Unsupported { .. }))
+        ));
+    }
+
+    #[test]
+    fn rule_spans_profile_width() {
+        let mut printer = Printer::new(Vec::new());
+        printer.rule(RuleStyle::Double).unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner.len(), 43);
+        assert_eq!(inner[0], 0xCD);
+    }
+
+    #[test]
+    fn style_tracking_avoids_redundant_toggles() {
+        use crate::style::text::Styleable;
+
+        let mut printer = Printer::new(Vec::new()).with_style_tracking();
+        printer.println("A".bold()).unwrap();
+        printer.println("B".bold()).unwrap();
+        printer.reset_style().unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner, [&[0x1B, b'E', 1][..], b"A\nB\n", &[0x1B, b'E', 0]].concat());
+    }
+
+    #[test]
+    fn with_style_applies_to_closure_prints() {
+        use crate::style::text::Styleable;
+
+        let mut printer = Printer::new(Vec::new()).with_style_tracking();
+        printer
+            .with_style(StyleSet::new().with_bold(true), |p| {
+                p.println("A")?;
+                p.println("B".underlined())?;
+                Ok(())
+            })
+            .unwrap();
+        printer.println("C").unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        let expected = [
+            &[0x1B, b'E', 1][..],
+            b"A\n",
+            &[0x1B, b'-', 1],
+            b"B\n",
+            &[0x1B, b'E', 0, 0x1B, b'-', 0],
+            b"C\n",
+        ]
+        .concat();
+        assert_eq!(inner, expected);
+    }
+
+    #[test]
+    fn with_style_pops_scope_on_error() {
+        let mut printer = Printer::new(Vec::new());
+        let result: Result<(), _> =
+            printer.with_style(StyleSet::new().with_bold(true), |_| Err(PrinterError::NoResponse));
+        assert!(result.is_err());
+        printer.print("plain").unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner, b"plain");
+    }
+
+    #[test]
+    fn initialize_forgets_tracked_style() {
+        use crate::style::text::Styleable;
+
+        let mut printer = Printer::new(Vec::new()).with_style_tracking();
+        printer.println("A".bold()).unwrap();
+        printer.initialize().unwrap();
+        printer.println("B".bold()).unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        assert_eq!(inner.windows(3).filter(|w| *w == [0x1B, b'E', 1]).count(), 2);
+    }
+
     #[test]
     fn print_page_sends_page_mode() {
         let buf = Vec::new();
@@ -259,6 +1424,21 @@ mod tests {
         assert!(inner.ends_with(&[0x0C]));
     }
 
+    #[test]
+    fn abort_page_cancels_and_exits() {
+        let buf = Vec::new();
+        let mut printer = Printer::new(buf);
+
+        printer.send_raw(&[0x1B, b'L']).unwrap();
+        printer.print("Half").unwrap();
+        printer.abort_page().unwrap();
+        printer.flush().unwrap();
+
+        let (inner, _) = printer.into_inner();
+        // CAN, then ESC S
+        assert!(inner.ends_with(&[0x18, 0x1B, b'S']));
+    }
+
     #[test]
     fn initialize_sends_esc_at() {
         let buf = Vec::new();
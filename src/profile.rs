@@ -0,0 +1,567 @@
+//! Printer capability profiles.
+//!
+//! A [`PrinterProfile`] describes what a particular model can do, so
+//! higher-level APIs can reject output the printer would silently ignore
+//! or misprint.
+//!
+//! ```
+//! use bixolon::profile::PrinterProfile;
+//!
+//! let profile = PrinterProfile::SRP_350PLUS;
+//! assert!(!profile.two_color);
+//! ```
+//!
+//! Models without a built-in profile, such as ESC/POS clones, can be
+//! described in TOML or JSON at runtime. Fields left out take their
+//! SRP-350plus values:
+//!
+//! ```
+//! # #[cfg(feature = "toml")]
+//! # {
+//! use bixolon::profile::PrinterProfile;
+//!
+//! let profile = PrinterProfile::from_toml(
+//!     r#"
+//!     name = "Generic 58mm"
+//!     paper_width_dots = 384
+//!     font_a_columns = 32
+//!     font_b_columns = 42
+//!     qr_code = false
+//!     "#,
+//! )?;
+//! assert_eq!(profile.paper_width_dots, 384);
+//! assert!(profile.reverse);
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+
+use crate::command::Dialect;
+use crate::command::character::{Font, PrintColor, ScaleFactor};
+use crate::encoding::CjkEncoding;
+#[cfg(any(feature = "json", feature = "toml"))]
+use crate::error::ProfileError;
+use crate::error::ValidationError;
+use crate::style::StyleSet;
+use crate::style::text::StyledNode;
+
+/// Capabilities of a printer model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct PrinterProfile {
+    /// Model name, used in error messages.
+    pub name: Cow<'static, str>,
+    /// Printable width in dots.
+    pub paper_width_dots: u16,
+    /// Characters per line in Font A.
+    pub font_a_columns: u8,
+    /// Characters per line in Font B.
+    pub font_b_columns: u8,
+    /// Maximum barcode height in dots (`GS h`).
+    pub max_barcode_height: u8,
+    /// Smallest barcode module width in dots (`GS w`).
+    pub min_barcode_module_width: u8,
+    /// Largest barcode module width in dots (`GS w`).
+    pub max_barcode_module_width: u8,
+    /// Supports two-color (black/red) printing via `ESC r`.
+    pub two_color: bool,
+    /// Supports reverse (white on black) printing via `GS B`.
+    pub reverse: bool,
+    /// Largest character width/height scale (`GS !`).
+    pub max_character_scale: ScaleFactor,
+    /// Supports QR codes natively via `GS ( k`.
+    ///
+    /// Without it, QR codes are rendered as raster images when the `qrcode`
+    /// feature is enabled.
+    pub qr_code: bool,
+    /// Longest `GS k` barcode data the firmware accepts, in bytes.
+    ///
+    /// Longer CODE128 and EAN/UPC barcodes are rendered as raster images.
+    pub max_barcode_data: u8,
+    /// Two-byte encoding of the model's Kanji mode, if it has one.
+    pub cjk: Option<CjkEncoding>,
+    /// Character codes that can hold user-defined glyphs at once (`ESC &`).
+    pub user_defined_characters: u8,
+    /// Command set used for cuts, drawer pulses and raster images.
+    pub dialect: Dialect,
+}
+
+/// How the printer treats text styles the profile doesn't support.
+///
+/// Covers attributes such as reverse printing and large character scales,
+/// which some firmwares ignore or print as garbage. Two-color text on a
+/// single-color model is rejected unless the policy is
+/// [`Downgrade`](Self::Downgrade), which prints it in black.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StylePolicy {
+    /// Send styles as requested.
+    #[default]
+    Permissive,
+    /// Drop unsupported attributes and clamp scales to the profile's
+    /// maximum.
+    Downgrade,
+    /// Reject unsupported attributes with a [`ValidationError`].
+    Strict,
+}
+
+impl PrinterProfile {
+    /// Bixolon SRP-350plus: 80mm paper, 72mm (512 dot) print width,
+    /// single color.
+    pub const SRP_350PLUS: Self = Self {
+        name: Cow::Borrowed("SRP-350plus"),
+        paper_width_dots: 512,
+        font_a_columns: 42,
+        font_b_columns: 56,
+        max_barcode_height: 255,
+        min_barcode_module_width: 2,
+        max_barcode_module_width: 6,
+        two_color: false,
+        reverse: true,
+        max_character_scale: ScaleFactor::X8,
+        qr_code: true,
+        max_barcode_data: u8::MAX,
+        cjk: None,
+        user_defined_characters: 95,
+        dialect: Dialect::Bixolon,
+    };
+
+    /// Load a profile from TOML, filling missing fields from the
+    /// SRP-350plus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::Toml`] for malformed TOML or unknown fields,
+    /// and [`ProfileError::Invalid`] if the profile fails
+    /// [`validate`](Self::validate).
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, ProfileError> {
+        let profile: Self = toml::from_str(toml)?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Load a profile from JSON, filling missing fields from the
+    /// SRP-350plus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProfileError::Json`] for malformed JSON or unknown fields,
+    /// and [`ProfileError::Invalid`] if the profile fails
+    /// [`validate`](Self::validate).
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ProfileError> {
+        let profile: Self = serde_json::from_str(json)?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Check that the profile's values are usable.
+    ///
+    /// Built-in profiles always pass; this catches mistakes in profiles
+    /// loaded at runtime before they cause divisions by zero or impossible
+    /// barcode widths.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::OutOfRange`] for a zero print width or
+    /// column count, a minimum barcode module width of zero or above the
+    /// maximum, or more than 95 user-defined characters.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let check = |name, value: u16, min: u16, max: u16| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(ValidationError::OutOfRange {
+                    name,
+                    value,
+                    min,
+                    max,
+                })
+            }
+        };
+        check("paper_width_dots", self.paper_width_dots, 1, u16::MAX)?;
+        check("font_a_columns", self.font_a_columns.into(), 1, self.paper_width_dots.min(255))?;
+        check("font_b_columns", self.font_b_columns.into(), 1, self.paper_width_dots.min(255))?;
+        check(
+            "min_barcode_module_width",
+            self.min_barcode_module_width.into(),
+            1,
+            self.max_barcode_module_width.into(),
+        )?;
+        check("user_defined_characters", self.user_defined_characters.into(), 0, 95)
+    }
+
+    /// Set two-color support.
+    pub const fn with_two_color(mut self, enabled: bool) -> Self {
+        self.two_color = enabled;
+        self
+    }
+
+    /// Set reverse printing support.
+    pub const fn with_reverse(mut self, enabled: bool) -> Self {
+        self.reverse = enabled;
+        self
+    }
+
+    /// Set native QR code support.
+    pub const fn with_qr_code(mut self, enabled: bool) -> Self {
+        self.qr_code = enabled;
+        self
+    }
+
+    /// Set the longest barcode data sent natively.
+    pub const fn with_max_barcode_data(mut self, bytes: u8) -> Self {
+        self.max_barcode_data = bytes;
+        self
+    }
+
+    /// Set the Kanji mode encoding.
+    pub const fn with_cjk(mut self, encoding: Option<CjkEncoding>) -> Self {
+        self.cjk = encoding;
+        self
+    }
+
+    /// Set how many user-defined characters can be defined at once.
+    pub const fn with_user_defined_characters(mut self, count: u8) -> Self {
+        self.user_defined_characters = count;
+        self
+    }
+
+    /// Set the command set used where manufacturers differ.
+    pub const fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Set the largest supported character scale.
+    pub const fn with_max_character_scale(mut self, scale: ScaleFactor) -> Self {
+        self.max_character_scale = scale;
+        self
+    }
+
+    /// Characters per line in `font` at 1x width.
+    pub const fn columns(&self, font: Font) -> u8 {
+        match font {
+            Font::A => self.font_a_columns,
+            Font::B => self.font_b_columns,
+        }
+    }
+
+    /// Width of one 1x character cell in `font`, in dots.
+    ///
+    /// At least one dot, even for a profile that fails
+    /// [`validate`](Self::validate) with zero columns or paper width.
+    pub const fn char_width_dots(&self, font: Font) -> u16 {
+        let width = match self.paper_width_dots.checked_div(self.columns(font) as u16) {
+            Some(width) => width,
+            None => self.paper_width_dots,
+        };
+        if width == 0 {
+            1
+        } else {
+            width
+        }
+    }
+
+    /// Height of one 1x character cell in `font`, in dots.
+    ///
+    /// Font A is 24 dots and Font B 17 dots tall on Bixolon's built-in
+    /// fonts.
+    pub const fn char_height_dots(&self, font: Font) -> u16 {
+        match font {
+            Font::A => 24,
+            Font::B => 17,
+        }
+    }
+
+    /// Check that styled text only uses features this model supports.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`] if the text selects a print
+    /// color on a single-color model.
+    pub fn check_node(&self, node: &StyledNode) -> Result<(), ValidationError> {
+        if !self.two_color && node.uses_color() {
+            return Err(ValidationError::Unsupported {
+                feature: "two-color printing",
+                profile: self.name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check every style attribute in the node against this profile.
+    ///
+    /// Stricter than [`check_node`](Self::check_node): also rejects reverse
+    /// printing and character scales the model lacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::Unsupported`] for unsupported features
+    /// and [`ValidationError::OutOfRange`] for oversized character scales.
+    pub fn check_styles(&self, node: &StyledNode) -> Result<(), ValidationError> {
+        self.check_node(node)?;
+        self.check_tree(node)
+    }
+
+    /// Check the style of `node` and every node under it.
+    fn check_tree(&self, node: &StyledNode) -> Result<(), ValidationError> {
+        match node {
+            StyledNode::Text(_) => Ok(()),
+            StyledNode::Styled {
+                style,
+                children,
+            } => {
+                self.check_style(style)?;
+                children.iter().try_for_each(|child| self.check_tree(child))
+            }
+        }
+    }
+
+    fn check_style(&self, style: &StyleSet) -> Result<(), ValidationError> {
+        if !self.reverse && style.reverse == Some(true) {
+            return Err(ValidationError::Unsupported {
+                feature: "reverse printing",
+                profile: self.name.to_string(),
+            });
+        }
+        let (width_scale, height_scale) = style.scales();
+        for scale in [width_scale, height_scale].into_iter().flatten() {
+            if scale as u8 > self.max_character_scale as u8 {
+                return Err(ValidationError::OutOfRange {
+                    name: "character scale",
+                    value: u16::from(scale.multiplier()),
+                    min: 1,
+                    max: u16::from(self.max_character_scale.multiplier()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove or clamp style attributes this profile doesn't support.
+    ///
+    /// Reverse printing and color are dropped when unsupported, and
+    /// character scales are clamped to the profile's maximum.
+    pub fn downgrade(&self, node: StyledNode) -> StyledNode {
+        match node {
+            StyledNode::Text(_) => node,
+            StyledNode::Styled {
+                mut style,
+                children,
+            } => {
+                if !self.reverse {
+                    style.reverse = None;
+                }
+                if !self.two_color {
+                    style.color = style.color.filter(|&color| color == PrintColor::Black);
+                }
+                let clamp = |scale: ScaleFactor| {
+                    if scale as u8 > self.max_character_scale as u8 {
+                        self.max_character_scale
+                    } else {
+                        scale
+                    }
+                };
+                let (width_scale, height_scale) = style.scales();
+                style.width_scale = width_scale.map(clamp);
+                style.height_scale = height_scale.map(clamp);
+                #[allow(deprecated)]
+                {
+                    style.size = None;
+                }
+                StyledNode::Styled {
+                    style,
+                    children: children.into_iter().map(|child| self.downgrade(child)).collect(),
+                }
+            }
+        }
+    }
+
+    /// Apply a [`StylePolicy`] to a node about to be printed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ValidationError`] for color on a single-color model under
+    /// [`StylePolicy::Permissive`], or for any unsupported attribute under
+    /// [`StylePolicy::Strict`]. [`StylePolicy::Downgrade`] never fails.
+    pub fn apply_policy(
+        &self,
+        policy: StylePolicy,
+        node: StyledNode,
+    ) -> Result<StyledNode, ValidationError> {
+        match policy {
+            StylePolicy::Permissive => self.check_node(&node)?,
+            StylePolicy::Downgrade => return Ok(self.downgrade(node)),
+            StylePolicy::Strict => self.check_styles(&node)?,
+        }
+        Ok(node)
+    }
+}
+
+impl Default for PrinterProfile {
+    fn default() -> Self {
+        Self::SRP_350PLUS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::character::CharacterSize;
+    use crate::style::text::Styleable;
+
+    #[test]
+    fn default_is_srp_350plus() {
+        assert_eq!(PrinterProfile::default(), PrinterProfile::SRP_350PLUS);
+    }
+
+    #[test]
+    fn built_in_profiles_are_valid() {
+        assert!(PrinterProfile::SRP_350PLUS.validate().is_ok());
+    }
+
+    #[test]
+    fn validation_rejects_inconsistent_values() {
+        let profile = PrinterProfile {
+            font_b_columns: 0,
+            ..PrinterProfile::SRP_350PLUS
+        };
+        assert!(matches!(
+            profile.validate(),
+            Err(ValidationError::OutOfRange {
+                name: "font_b_columns",
+                ..
+            })
+        ));
+
+        let profile = PrinterProfile {
+            min_barcode_module_width: 7,
+            ..PrinterProfile::SRP_350PLUS
+        };
+        assert!(profile.validate().is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn loads_partial_toml_profiles() {
+        let profile = PrinterProfile::from_toml(
+            r#"
+            name = "Clone 58"
+            paper_width_dots = 384
+            font_a_columns = 32
+            font_b_columns = 42
+            max_character_scale = "X4"
+            cjk = "ShiftJis"
+            dialect = "Star"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(profile.name, "Clone 58");
+        assert_eq!(profile.char_width_dots(Font::A), 12);
+        assert_eq!(profile.max_character_scale, ScaleFactor::X4);
+        assert_eq!(profile.cjk, Some(CjkEncoding::ShiftJis));
+        assert_eq!(profile.dialect, Dialect::Star);
+        assert_eq!(profile.max_barcode_module_width, 6);
+
+        assert!(matches!(
+            PrinterProfile::from_toml("paper_width_dots = 0"),
+            Err(ProfileError::Invalid(_))
+        ));
+        assert!(matches!(
+            PrinterProfile::from_toml("paper_width = 384"),
+            Err(ProfileError::Toml(_))
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn profiles_round_trip_through_json() {
+        let profile = PrinterProfile::SRP_350PLUS.with_two_color(true).with_qr_code(false);
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(PrinterProfile::from_json(&json).unwrap(), profile);
+        assert_eq!(PrinterProfile::from_json("{}").unwrap(), PrinterProfile::SRP_350PLUS);
+    }
+
+    #[test]
+    fn srp_350plus_cell_widths() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        assert_eq!(profile.char_width_dots(Font::A), 12);
+        assert_eq!(profile.char_width_dots(Font::B), 9);
+        assert_eq!(profile.char_height_dots(Font::A), 24);
+        assert_eq!(profile.char_height_dots(Font::B), 17);
+    }
+
+    #[test]
+    fn invalid_profiles_have_nonzero_cell_widths() {
+        let profile = PrinterProfile {
+            font_a_columns: 0,
+            font_b_columns: 0,
+            ..PrinterProfile::SRP_350PLUS
+        };
+        assert_eq!(profile.char_width_dots(Font::A), 512);
+        let profile = PrinterProfile {
+            paper_width_dots: 0,
+            ..profile
+        };
+        assert_eq!(profile.char_width_dots(Font::B), 1);
+    }
+
+    #[test]
+    fn single_color_rejects_red() {
+        let result = PrinterProfile::SRP_350PLUS.check_node(&"VOID".red());
+        assert!(matches!(result, Err(ValidationError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn strict_rejects_reverse_without_support() {
+        let profile = PrinterProfile::SRP_350PLUS.with_reverse(false);
+        assert!(profile.check_node(&"X".reversed()).is_ok());
+        let result = profile.check_styles(&"X".bold().append("Y".reversed()));
+        assert!(matches!(
+            result,
+            Err(ValidationError::Unsupported {
+                feature: "reverse printing",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn strict_rejects_oversized_scale() {
+        let profile = PrinterProfile::SRP_350PLUS.with_max_character_scale(ScaleFactor::X4);
+        let big = "X".sized(CharacterSize::new(ScaleFactor::X8, ScaleFactor::X1));
+        let result = profile.check_styles(&big);
+        assert!(matches!(
+            result,
+            Err(ValidationError::OutOfRange {
+                value: 8,
+                max: 4,
+                ..
+            })
+        ));
+        assert!(profile.check_styles(&"X".double_size()).is_ok());
+    }
+
+    #[test]
+    fn downgrade_drops_and_clamps() {
+        let profile = PrinterProfile::SRP_350PLUS
+            .with_reverse(false)
+            .with_max_character_scale(ScaleFactor::X2);
+        let node = "X".reversed().red().sized(CharacterSize::new(ScaleFactor::X8, ScaleFactor::X3));
+        let downgraded = profile.apply_policy(StylePolicy::Downgrade, node).unwrap();
+        assert_eq!(downgraded.render(), "X".double_size().render());
+    }
+
+    #[test]
+    fn two_color_accepts_red() {
+        let profile = PrinterProfile::SRP_350PLUS.with_two_color(true);
+        assert!(profile.check_node(&"VOID".red()).is_ok());
+    }
+}
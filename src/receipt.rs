@@ -0,0 +1,1228 @@
+//! Declarative JSON receipts.
+//!
+//! A [`Receipt`] describes a whole print job as data, so backends written
+//! in other languages can drive a printer through this crate by sending
+//! JSON. [`Receipt::from_json`] parses a document and
+//! [`Receipt::render`] turns it into printer bytes:
+//!
+//! ```
+//! use bixolon::profile::PrinterProfile;
+//! use bixolon::receipt::Receipt;
+//!
+//! let receipt = Receipt::from_json(
+//!     r#"{
+//!         "sections": [
+//!             {"type": "Text", "align": "Center", "spans": [
+//!                 {"text": "ACME Coffee", "bold": true, "width_scale": "X2"}
+//!             ]},
+//!             {"type": "Columns", "left": ["Flat white"], "right": ["4.50"], "fill": "."},
+//!             {"type": "Rule"},
+//!             {"type": "Barcode", "system": "Code39", "data": "1042", "hri": "Below"},
+//!             {"type": "Cut", "partial": true}
+//!         ]
+//!     }"#,
+//! )?;
+//! let bytes = receipt.render(&PrinterProfile::default())?;
+//! assert!(bytes.starts_with(b"\x1b@"));
+//! assert!(bytes.windows(10).any(|w| w == b"Flat white"));
+//! # Ok::<(), bixolon::error::ReceiptError>(())
+//! ```
+//!
+//! # Format
+//!
+//! The document is an object with a `sections` array, printed top to
+//! bottom. Each section is an object whose `type` is one of:
+//!
+//! | `type` | Fields |
+//! |--------|--------|
+//! | `Text` | `spans`, optional `align` |
+//! | `Columns` | optional `left`, `center` and `right` spans, optional `amount`, optional `fill` character |
+//! | `Item` | `qty`, `description` spans, `unit_price`, optional `total` |
+//! | `Totals` | `subtotal`, optional `lines` of `label` spans and `amount`, optional `total` and `double_width` |
+//! | `Signature` | optional `amount`, `tip` and `total` (default `false`) and `caption` spans |
+//! | `Rule` | optional `style`: `Light` (default), `Heavy` or `Double` |
+//! | `Barcode` | `system`, `data`, optional `height` in dots, `width`, `hri` and `align` |
+//! | `QrCode` | `data`, optional `module_size`, `error_correction` and `align` |
+//! | `Image` | `source`, optional `width` in dots, `dither` and `align` |
+//! | `Feed` | `lines` |
+//! | `Cut` | optional `partial` (default `false`), optional `feed` lines (default 3) |
+//!
+//! Spans are either plain strings or objects with a `text` field and any
+//! [`StyleSet`] fields, such as `"bold": true`, `"font": "B"` or
+//! `"height_scale": "X2"`. Enumerated values use the names of the
+//! corresponding Rust types: `align` is a [`Justification`], `system` a
+//! [`BarcodeSystem`], `width` a [`BarcodeWidth`], `hri` an
+//! [`HriPosition`], `module_size` a [`QrModuleSize`], `error_correction` a
+//! [`QrErrorCorrection`] and `dither` a [`Dither`].
+//!
+//! An image `source` is either `{"base64": "..."}` or `{"url": "..."}`,
+//! holding a PNG. `data:` URLs are decoded directly; other URLs are passed
+//! to the loader given to [`Receipt::print_with_loader`], so the caller
+//! decides how, and whether, to fetch them. Images without a `width` are
+//! printed at their own size, shrunk to fit the paper if necessary.
+//!
+//! Columns `amount`s, item prices and totals are integers in minor units, such as cents, formatted
+//! with the document's optional `currency` (default US dollars) and
+//! `locale` (default US English), e.g.
+//! `"currency": {"code": "EUR", "symbol": "EUR", "decimals": 2}` and
+//! `"locale": {"decimal": ",", "group": ".", "symbol_after": true,
+//! "symbol_space": true}`. Amounts line up on the decimal point.
+//!
+//! The job starts with `ESC @`, so the printer begins from its default
+//! state whatever was printed before.
+//!
+//! Text is encoded in the optional `code_page` (a [`CodePage`], default
+//! CP437), selected with `ESC t` before it prints. Characters the code page
+//! lacks print as `?`, or as the printer's
+//! [encoding fallback](Printer::with_encoding_fallback) handles them.
+//!
+//! An optional `copies` count (default 1) prints the receipt several
+//! times, each copy after the first starting with `ESC @` and a centered
+//! `copy_banner` (spans, default `["COPY"]`). Copies are separated by a
+//! partial cut unless the last section is already a `Cut`.
+//!
+//! An optional `pagination` object splits long receipts, such as pick
+//! lists and end-of-day reports, into pages; see [`Pagination`]. Its
+//! fields are an optional `lines` per page, `header` sections repeated at
+//! the top of every page, a `continued` marker (spans, default
+//! `["continued..."]`) and `cut` (default `false`) to cut between pages.
+
+pub mod template;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::command::barcode::{
+    BarcodeSystem, BarcodeWidth, HriPosition, PrintBarcode, SetBarcodeHeight, SetBarcodeWidth,
+    SetHriPosition,
+};
+use crate::command::character::{Justification, ScaleFactor, SetJustification};
+use crate::command::codepage::CodePage;
+use crate::command::image::raster::{self, Dither, FilterType};
+use crate::command::paper::{CutPaper, FeedLines};
+use crate::command::rule::RuleStyle;
+use crate::command::symbol::{PrintQrCode, QrErrorCorrection, QrModuleSize};
+use crate::error::{PrinterError, ReceiptError};
+use crate::money::{Currency, Locale, Money};
+use crate::printer::Printer;
+use crate::profile::PrinterProfile;
+use crate::style::StyleSet;
+use crate::style::layout::{ItemLine, LineBuilder, SignatureBlock, TotalsBlock};
+use crate::style::text::StyledNode;
+
+/// A receipt described as data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Receipt {
+    /// Sections printed top to bottom.
+    pub sections: Vec<Section>,
+    /// Currency of amounts and prices.
+    #[serde(default)]
+    pub currency: Currency,
+    /// How amounts and prices are written.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Copies to print, one after another.
+    #[serde(default = "default_copies")]
+    pub copies: u8,
+    /// Banner printed at the top of every copy after the first.
+    #[serde(default = "default_copy_banner")]
+    pub copy_banner: Vec<Span>,
+    /// Split the receipt into pages with repeated headers.
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
+    /// Code page text is encoded in.
+    #[serde(default)]
+    pub code_page: CodePage,
+}
+
+impl Default for Receipt {
+    fn default() -> Self {
+        Self {
+            sections: Vec::new(),
+            currency: Currency::default(),
+            locale: Locale::default(),
+            copies: default_copies(),
+            copy_banner: default_copy_banner(),
+            pagination: None,
+            code_page: CodePage::default(),
+        }
+    }
+}
+
+fn default_copies() -> u8 {
+    1
+}
+
+fn default_copy_banner() -> Vec<Span> {
+    vec![Span::Plain("COPY".into())]
+}
+
+/// How a long [`Receipt`] is split into pages.
+///
+/// A page ends after every `Cut` section and, if [`lines`](Self::lines) is
+/// set, once that many lines have printed. Pages that continue on the next
+/// get the [`continued`](Self::continued) marker at the bottom, and every
+/// page starts with the [`header`](Self::header) sections, such as column
+/// headings. Pages only break between sections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pagination {
+    /// Lines per page, not counting the header; pages only end at cuts if
+    /// unset. Text counts the lines it wraps to at the paper width, and
+    /// barcodes, QR codes and images count as no lines.
+    #[serde(default)]
+    pub lines: Option<usize>,
+    /// Sections printed at the top of every page.
+    #[serde(default)]
+    pub header: Vec<Section>,
+    /// Marker printed centered at the bottom of pages that continue.
+    #[serde(default = "default_continued")]
+    pub continued: Vec<Span>,
+    /// Cut the paper at page breaks after [`lines`](Self::lines).
+    #[serde(default)]
+    pub cut: bool,
+}
+
+impl Pagination {
+    /// Pages of `lines` lines, without header or cut.
+    pub fn every(lines: usize) -> Self {
+        Self {
+            lines: Some(lines),
+            ..Self::default()
+        }
+    }
+
+    /// Pages ending only at `Cut` sections.
+    pub fn per_cut() -> Self {
+        Self::default()
+    }
+
+    /// Add a section to the header printed at the top of every page.
+    pub fn header(mut self, section: Section) -> Self {
+        self.header.push(section);
+        self
+    }
+
+    /// Use `marker` instead of "continued..." at the bottom of pages.
+    pub fn continued(mut self, marker: Vec<Span>) -> Self {
+        self.continued = marker;
+        self
+    }
+
+    /// Cut the paper between pages of [`lines`](Self::lines) lines.
+    pub fn cut(mut self, cut: bool) -> Self {
+        self.cut = cut;
+        self
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            lines: None,
+            header: Vec::new(),
+            continued: default_continued(),
+            cut: false,
+        }
+    }
+}
+
+fn default_continued() -> Vec<Span> {
+    vec![Span::Plain("continued...".into())]
+}
+
+/// One block of a [`Receipt`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", deny_unknown_fields)]
+pub enum Section {
+    /// A line of styled text, wrapped by the printer if too long.
+    Text {
+        /// Text of the line.
+        spans: Vec<Span>,
+        /// Line justification.
+        #[serde(default)]
+        align: Option<Justification>,
+    },
+    /// A line with left, centered and right aligned segments.
+    ///
+    /// See [`LineBuilder`].
+    Columns {
+        /// Segment starting at the left margin.
+        #[serde(default)]
+        left: Option<Vec<Span>>,
+        /// Segment centered on the line.
+        #[serde(default)]
+        center: Option<Vec<Span>>,
+        /// Segment ending at the right margin.
+        #[serde(default)]
+        right: Option<Vec<Span>>,
+        /// Amount in minor units, printed in place of `right` and aligned
+        /// on the decimal point.
+        #[serde(default)]
+        amount: Option<i64>,
+        /// Character filling the gaps, a space if unset.
+        #[serde(default)]
+        fill: Option<char>,
+    },
+    /// A sold item with its quantity and price.
+    ///
+    /// See [`ItemLine`].
+    Item {
+        /// Units sold.
+        qty: u32,
+        /// What was sold.
+        description: Vec<Span>,
+        /// Price of one unit, in minor units.
+        unit_price: i64,
+        /// Price of the line in minor units, `qty` times `unit_price` if
+        /// unset.
+        #[serde(default)]
+        total: Option<i64>,
+    },
+    /// Subtotal, tax and discount lines and the grand total.
+    ///
+    /// See [`TotalsBlock`].
+    Totals {
+        /// Subtotal in minor units.
+        subtotal: i64,
+        /// Tax lines, and discount lines with negative amounts.
+        #[serde(default)]
+        lines: Vec<TotalsLine>,
+        /// Grand total in minor units, the subtotal plus every line if
+        /// unset.
+        #[serde(default)]
+        total: Option<i64>,
+        /// Print the grand total in double-width characters.
+        #[serde(default)]
+        double_width: bool,
+    },
+    /// Tip and total fill-ins and a signature line for card payments.
+    ///
+    /// See [`SignatureBlock`].
+    Signature {
+        /// Amount authorized in minor units, printed above the fill-ins.
+        #[serde(default)]
+        amount: Option<i64>,
+        /// Add a tip fill-in line.
+        #[serde(default)]
+        tip: bool,
+        /// Add a total fill-in line.
+        #[serde(default)]
+        total: bool,
+        /// Text centered under the signature line.
+        #[serde(default)]
+        caption: Option<Vec<Span>>,
+    },
+    /// A full-width horizontal rule.
+    Rule {
+        /// Line weight.
+        #[serde(default)]
+        style: RuleStyle,
+    },
+    /// A one-dimensional barcode.
+    Barcode {
+        /// Barcode symbology.
+        system: BarcodeSystem,
+        /// Data to encode.
+        data: String,
+        /// Bar height in dots.
+        #[serde(default)]
+        height: Option<u8>,
+        /// Module width.
+        #[serde(default)]
+        width: Option<BarcodeWidth>,
+        /// Where the human readable text is printed.
+        #[serde(default)]
+        hri: Option<HriPosition>,
+        /// Horizontal alignment.
+        #[serde(default)]
+        align: Option<Justification>,
+    },
+    /// A QR code.
+    QrCode {
+        /// Data to encode.
+        data: String,
+        /// Module size.
+        #[serde(default)]
+        module_size: Option<QrModuleSize>,
+        /// Error correction level.
+        #[serde(default)]
+        error_correction: Option<QrErrorCorrection>,
+        /// Horizontal alignment.
+        #[serde(default)]
+        align: Option<Justification>,
+    },
+    /// A PNG image.
+    Image {
+        /// Where the image data comes from.
+        source: ImageSource,
+        /// Width in dots to scale the image to.
+        #[serde(default)]
+        width: Option<u16>,
+        /// How gray levels become dots.
+        #[serde(default)]
+        dither: Dither,
+        /// Horizontal alignment.
+        #[serde(default)]
+        align: Option<Justification>,
+    },
+    /// Blank lines.
+    Feed {
+        /// Number of lines to feed.
+        lines: u8,
+    },
+    /// A paper cut.
+    Cut {
+        /// Leave one point uncut.
+        #[serde(default)]
+        partial: bool,
+        /// Lines fed first, so the last line clears the cutter.
+        #[serde(default = "default_cut_feed")]
+        feed: u8,
+    },
+}
+
+fn default_cut_feed() -> u8 {
+    3
+}
+
+/// A run of text within a section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Span {
+    /// Unstyled text.
+    Plain(String),
+    /// Text with a style.
+    Styled {
+        /// The text.
+        text: String,
+        /// Style applied to the text.
+        #[serde(flatten)]
+        style: StyleSet,
+    },
+}
+
+impl From<&Span> for StyledNode {
+    fn from(span: &Span) -> Self {
+        match span {
+            Span::Plain(text) => StyledNode::text(text.clone()),
+            Span::Styled {
+                text,
+                style,
+            } => StyledNode::styled(style.clone(), text.clone()),
+        }
+    }
+}
+
+/// A tax or discount line in [`Section::Totals`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TotalsLine {
+    /// What the line is for.
+    pub label: Vec<Span>,
+    /// Amount in minor units, negative for discounts.
+    pub amount: i64,
+}
+
+/// Where an image's PNG data comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    /// A `data:` URL, or a URL for the caller's loader.
+    Url(String),
+    /// Base64 encoded data.
+    Base64(String),
+}
+
+impl Receipt {
+    /// Parse a receipt from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError::Json`] if the document doesn't match the
+    /// format, naming the offending line and column.
+    pub fn from_json(json: &str) -> Result<Self, ReceiptError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Print `copies` copies of the receipt, e.g. a merchant and a
+    /// customer copy.
+    ///
+    /// Copies after the first start with the [copy banner](Self::with_copy_banner),
+    /// and are separated by a partial cut unless the receipt already ends
+    /// with a `Cut` section.
+    pub fn print_copies(mut self, copies: u8) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    /// Split the receipt into pages with repeated headers, e.g. for long
+    /// pick lists and reports.
+    pub fn paginate(mut self, pagination: Pagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+
+    /// Use `banner` instead of "COPY" to mark copies after the first.
+    ///
+    /// The banner is printed centered in bold at double size; styles on
+    /// its spans take precedence.
+    pub fn with_copy_banner(mut self, banner: Vec<Span>) -> Self {
+        self.copy_banner = banner;
+        self
+    }
+
+    /// Encode text in `code_page` instead of CP437.
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Render the receipt to printer bytes for `profile`.
+    ///
+    /// Only `data:` image URLs are loaded; use
+    /// [`print_with_loader`](Self::print_with_loader) for others.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError`] for the first section that can't be
+    /// printed, such as invalid barcode data or an undecodable image.
+    pub fn render(&self, profile: &PrinterProfile) -> Result<Vec<u8>, ReceiptError> {
+        let mut printer = Printer::new(Vec::new()).with_profile(profile.clone());
+        self.print(&mut printer)?;
+        printer.flush()?;
+        Ok(printer.into_inner().0)
+    }
+
+    /// Print the receipt, loading only `data:` image URLs.
+    ///
+    /// # Errors
+    ///
+    /// As for [`render`](Self::render), plus I/O errors from the printer.
+    pub fn print<W: Write, R>(&self, printer: &mut Printer<W, R>) -> Result<(), ReceiptError> {
+        self.print_with_loader(printer, |_| {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "only data: URLs are loaded without a loader",
+            ))
+        })
+    }
+
+    /// Print the receipt, fetching image URLs other than `data:` with `load`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`print`](Self::print), plus [`ReceiptError::Load`] for
+    /// errors returned by `load`.
+    pub fn print_with_loader<W: Write, R>(
+        &self,
+        printer: &mut Printer<W, R>,
+        mut load: impl FnMut(&str) -> io::Result<Vec<u8>>,
+    ) -> Result<(), ReceiptError> {
+        // Copies reuse loaded images rather than fetching them again
+        let mut loaded = HashMap::new();
+        let mut load = |url: &str| match loaded.get(url) {
+            Some(data) => Ok(Vec::clone(data)),
+            None => {
+                let data = load(url)?;
+                loaded.insert(url.to_owned(), data.clone());
+                Ok(data)
+            }
+        };
+        // Encode text in the receipt's code page unless a span picks another
+        let code_page = StyleSet::new().with_code_page(self.code_page);
+        printer.with_style(code_page, |printer| Ok(self.print_all(printer, &mut load)))?
+    }
+
+    fn print_all<W: Write, R>(
+        &self,
+        printer: &mut Printer<W, R>,
+        load: &mut impl FnMut(&str) -> io::Result<Vec<u8>>,
+    ) -> Result<(), ReceiptError> {
+        for copy in 0..self.copies.max(1) {
+            if copy > 0 {
+                if !matches!(self.sections.last(), Some(Section::Cut { .. })) {
+                    printer.send(CutPaper::feed_and_partial(default_cut_feed()))?;
+                }
+                printer.initialize()?;
+                let banner = StyleSet::new()
+                    .with_justification(Justification::Center)
+                    .with_bold(true)
+                    .with_width_scale(ScaleFactor::X2)
+                    .with_height_scale(ScaleFactor::X2);
+                printer.println(line(&self.copy_banner).with_style(banner))?;
+            } else {
+                printer.initialize()?;
+            }
+            self.print_sections(printer, load)?;
+        }
+        Ok(())
+    }
+
+    fn print_sections<W: Write, R>(
+        &self,
+        printer: &mut Printer<W, R>,
+        load: &mut impl FnMut(&str) -> io::Result<Vec<u8>>,
+    ) -> Result<(), ReceiptError> {
+        let Some(pages) = &self.pagination else {
+            for section in &self.sections {
+                section.print(self, printer, load)?;
+            }
+            return Ok(());
+        };
+        for section in &pages.header {
+            section.print(self, printer, load)?;
+        }
+        let mut printed = 0;
+        for (index, section) in self.sections.iter().enumerate() {
+            let is_cut = matches!(section, Section::Cut { .. });
+            if !is_cut && printed > 0 && pages.lines.is_some_and(|lines| printed >= lines) {
+                let cut = pages.cut.then(|| Section::Cut {
+                    partial: true,
+                    feed: default_cut_feed(),
+                });
+                self.break_page(pages, cut.as_ref(), printer, load)?;
+                printed = section.print(self, printer, load)?;
+            } else if is_cut && index + 1 < self.sections.len() {
+                self.break_page(pages, Some(section), printer, load)?;
+                printed = 0;
+            } else {
+                printed += section.print(self, printer, load)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// End a page with the continued marker and `cut`, then start the next
+    /// with the header.
+    fn break_page<W: Write, R>(
+        &self,
+        pages: &Pagination,
+        cut: Option<&Section>,
+        printer: &mut Printer<W, R>,
+        load: &mut impl FnMut(&str) -> io::Result<Vec<u8>>,
+    ) -> Result<(), ReceiptError> {
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        printer.println(line(&pages.continued).with_style(centered))?;
+        if let Some(cut) = cut {
+            cut.print(self, printer, load)?;
+        }
+        for section in &pages.header {
+            section.print(self, printer, load)?;
+        }
+        Ok(())
+    }
+}
+
+impl Section {
+    fn print<W: Write, R>(
+        &self,
+        receipt: &Receipt,
+        printer: &mut Printer<W, R>,
+        load: &mut impl FnMut(&str) -> io::Result<Vec<u8>>,
+    ) -> Result<usize, ReceiptError> {
+        let mut printed = 0;
+        match self {
+            Section::Text {
+                spans,
+                align,
+            } => {
+                let line = line(spans);
+                let width = u32::from(printer.profile().paper_width_dots);
+                printed += line.wrap(printer.profile(), width).len().max(1);
+                match align {
+                    Some(align) => printer.println(line.with_style(StyleSet {
+                        justification: Some(*align),
+                        ..StyleSet::new()
+                    }))?,
+                    None => printer.println(line)?,
+                };
+            }
+            Section::Columns {
+                left,
+                center,
+                right,
+                amount,
+                fill,
+            } => {
+                let mut builder = LineBuilder::new().fill(fill.unwrap_or(' '));
+                if let Some(spans) = left {
+                    builder = builder.left(line(spans));
+                }
+                if let Some(spans) = center {
+                    builder = builder.center(line(spans));
+                }
+                if let Some(spans) = right {
+                    builder = builder.right(line(spans));
+                }
+                if let Some(minor) = amount {
+                    let amount = Money::new(*minor, receipt.currency.clone());
+                    builder = builder.amount(&amount, &receipt.locale);
+                }
+                let line = builder.build(printer.profile());
+                printer.println(line)?;
+                printed += 1;
+            }
+            Section::Item {
+                qty,
+                description,
+                unit_price,
+                total,
+            } => {
+                let money = |minor| Money::new(minor, receipt.currency.clone());
+                let mut item = ItemLine::checked_new(*qty, line(description), money(*unit_price))
+                    .ok_or(ReceiptError::AmountOverflow)?;
+                if let Some(total) = total {
+                    item = item.with_total(money(*total));
+                }
+                for line in item.build(printer.profile(), &receipt.locale) {
+                    printer.println(line)?;
+                    printed += 1;
+                }
+            }
+            Section::Totals {
+                subtotal,
+                lines,
+                total,
+                double_width,
+            } => {
+                let money = |minor| Money::new(minor, receipt.currency.clone());
+                let mut totals =
+                    TotalsBlock::new(money(*subtotal)).double_width_total(*double_width);
+                for TotalsLine {
+                    label,
+                    amount,
+                } in lines
+                {
+                    totals = totals.line(line(label), money(*amount));
+                }
+                if let Some(total) = total {
+                    totals = totals.total(money(*total));
+                }
+                totals.checked_grand_total().ok_or(ReceiptError::AmountOverflow)?;
+                for line in totals.build(printer.profile(), &receipt.locale) {
+                    printer.println(line)?;
+                    printed += 1;
+                }
+            }
+            Section::Signature {
+                amount,
+                tip,
+                total,
+                caption,
+            } => {
+                let mut block = SignatureBlock::new();
+                if let Some(minor) = amount {
+                    block = block.amount(Money::new(*minor, receipt.currency.clone()));
+                }
+                if *tip {
+                    block = block.tip_line();
+                }
+                if *total {
+                    block = block.total_line();
+                }
+                if let Some(spans) = caption {
+                    block = block.caption(line(spans));
+                }
+                for line in block.build(printer.profile(), &receipt.locale) {
+                    printer.println(line)?;
+                    printed += 1;
+                }
+            }
+            Section::Rule {
+                style,
+            } => {
+                printer.rule(*style)?;
+                printed += 1;
+            }
+            Section::Barcode {
+                system,
+                data,
+                height,
+                width,
+                hri,
+                align,
+            } => {
+                let barcode =
+                    PrintBarcode::new(*system, data.as_bytes()).map_err(PrinterError::from)?;
+                if let Some(height) = height {
+                    let height = SetBarcodeHeight::for_profile(*height, printer.profile())
+                        .map_err(PrinterError::from)?;
+                    printer.send(height)?;
+                }
+                if let Some(width) = width {
+                    printer.send(SetBarcodeWidth(*width))?;
+                }
+                if let Some(hri) = hri {
+                    printer.send(SetHriPosition(*hri))?;
+                }
+                aligned(printer, *align, |printer| printer.print_barcode(&barcode))?;
+            }
+            Section::QrCode {
+                data,
+                module_size,
+                error_correction,
+                align,
+            } => {
+                let mut qr = PrintQrCode::new(data.as_bytes()).map_err(PrinterError::from)?;
+                if let Some(size) = module_size {
+                    qr = qr.with_module_size(*size);
+                }
+                if let Some(level) = error_correction {
+                    qr = qr.with_error_correction(*level);
+                }
+                aligned(printer, *align, |printer| printer.print_qr_code(&qr))?;
+            }
+            Section::Image {
+                source,
+                width,
+                dither,
+                align,
+            } => {
+                let data = match source {
+                    ImageSource::Base64(text) => decode_base64(text).ok_or(ReceiptError::Base64)?,
+                    ImageSource::Url(url) => match data_url(url) {
+                        Some(text) => decode_base64(text).ok_or(ReceiptError::Base64)?,
+                        None => load(url).map_err(|source| ReceiptError::Load {
+                            url: url.clone(),
+                            source,
+                        })?,
+                    },
+                };
+                let image = ::image::load_from_memory(&data)?;
+                let raster = match width {
+                    Some(width) => raster::from_image(&image, *width, *dither),
+                    None => raster::fit_to_profile(
+                        &image,
+                        printer.profile(),
+                        FilterType::Triangle,
+                        *dither,
+                    ),
+                }
+                .map_err(PrinterError::from)?;
+                aligned(printer, *align, |printer| printer.print_image(&raster))?;
+            }
+            Section::Feed {
+                lines,
+            } => {
+                printer.send(FeedLines(*lines))?;
+                printed += usize::from(*lines);
+            }
+            Section::Cut {
+                partial,
+                feed,
+            } => {
+                let cut = if *partial {
+                    CutPaper::feed_and_partial(*feed)
+                } else {
+                    CutPaper::feed_and_full(*feed)
+                };
+                printer.send(cut)?;
+            }
+        }
+        Ok(printed)
+    }
+}
+
+/// Spans joined into one node.
+fn line(spans: &[Span]) -> StyledNode {
+    spans
+        .iter()
+        .map(StyledNode::from)
+        .reduce(StyledNode::append)
+        .unwrap_or_else(|| StyledNode::text(""))
+}
+
+/// Run `print` with `align` selected, returning to left alignment after.
+fn aligned<W: Write, R>(
+    printer: &mut Printer<W, R>,
+    align: Option<Justification>,
+    print: impl FnOnce(&mut Printer<W, R>) -> Result<&mut Printer<W, R>, PrinterError>,
+) -> Result<(), PrinterError> {
+    let Some(align) = align else {
+        print(printer)?;
+        return Ok(());
+    };
+    printer.send(SetJustification(align))?;
+    print(printer)?;
+    printer.send(SetJustification(Justification::Left))?;
+    Ok(())
+}
+
+/// Base64 payload of a `data:` URL.
+fn data_url(url: &str) -> Option<&str> {
+    let (media_type, data) = url.strip_prefix("data:")?.split_once(',')?;
+    media_type.ends_with(";base64").then_some(data)
+}
+
+/// Decode standard base64, ignoring whitespace and padding.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace());
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3 + 2);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    // A lone trailing character can't complete a byte
+    (bits < 6).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use ::image::{DynamicImage, GrayImage, ImageFormat, Luma};
+
+    use super::*;
+    use crate::command::Command;
+    use crate::command::image::PrintRasterImage;
+    use crate::style::text::Styleable;
+
+    fn render(json: &str) -> Vec<u8> {
+        Receipt::from_json(json).unwrap().render(&PrinterProfile::default()).unwrap()
+    }
+
+    fn sections(json: &str) -> Vec<u8> {
+        render(&format!(r#"{{"sections": {json}}}"#))[2..].to_vec()
+    }
+
+    fn png_base64(width: u32, height: u32) -> String {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([0])));
+        let mut png = Vec::new();
+        image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut text = String::new();
+        for chunk in png.chunks(3) {
+            let n =
+                chunk.iter().fold(0u32, |n, &b| n << 8 | u32::from(b)) << (8 * (3 - chunk.len()));
+            for i in 0..=chunk.len() {
+                text.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 63]));
+            }
+        }
+        while !text.len().is_multiple_of(4) {
+            text.push('=');
+        }
+        text
+    }
+
+    #[test]
+    fn renders_styled_spans() {
+        let bytes = sections(
+            r#"[{"type": "Text", "align": "Center", "spans": ["Total ", {"text": "25.00", "bold": true}]}]"#,
+        );
+        let node = (StyledNode::text("Total ") + "25.00".bold()).with_style(StyleSet {
+            justification: Some(Justification::Center),
+            ..StyleSet::new()
+        });
+        assert_eq!(bytes, node.render_line());
+    }
+
+    #[test]
+    fn renders_columns_and_rules() {
+        let bytes = sections(
+            r#"[{"type": "Columns", "left": ["Tea"], "right": ["2.00"], "fill": "."}, {"type": "Rule", "style": "Heavy"}]"#,
+        );
+        let line = format!("Tea{}2.00\n", ".".repeat(35));
+        assert!(bytes.starts_with(line.as_bytes()));
+        assert!(bytes.ends_with(&[0xDC, b'\n']));
+    }
+
+    #[test]
+    fn formats_amounts_for_the_locale() {
+        let bytes = render(
+            r#"{
+                "currency": {"code": "EUR", "symbol": "EUR", "decimals": 2},
+                "locale": {"decimal": ",", "group": ".", "symbol_after": true, "symbol_space": true},
+                "sections": [{"type": "Columns", "left": ["Total"], "amount": 123456}]
+            }"#,
+        );
+        let line = format!("Total{}1.234,56 EUR\n", " ".repeat(25));
+        assert_eq!(&bytes[2..], line.as_bytes());
+    }
+
+    #[test]
+    fn renders_totals() {
+        let bytes = sections(
+            r#"[{"type": "Totals", "subtotal": 1000, "lines": [
+                {"label": ["Coupon"], "amount": -100},
+                {"label": ["Tax"], "amount": 72}
+            ]}]"#,
+        );
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], format!("Subtotal{}$10.00", " ".repeat(28)));
+        assert_eq!(lines[1], format!("Coupon{}-$1.00", " ".repeat(30)));
+        assert!(lines[3].contains("TOTAL") && lines[3].contains("$9.72"));
+    }
+
+    #[test]
+    fn renders_signature_lines() {
+        let bytes = sections(r#"[{"type": "Signature", "amount": 2450, "tip": true}]"#);
+        let text = String::from_utf8(bytes).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], format!("Amount{}$24.50", " ".repeat(30)));
+        assert_eq!(lines[1], format!("Tip{}{}", " ".repeat(29), "_".repeat(10)));
+        assert_eq!(lines[4], format!("X{}", "_".repeat(41)));
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn renders_items() {
+        let bytes =
+            sections(r#"[{"type": "Item", "qty": 3, "description": ["Tea"], "unit_price": 250}]"#);
+        let expected = format!("3  Tea{}$7.50\n   @ $2.50\n", " ".repeat(31));
+        assert_eq!(bytes, expected.as_bytes());
+    }
+
+    #[test]
+    fn renders_barcodes_and_qr_codes() {
+        let bytes = sections(
+            r#"[
+                {"type": "Barcode", "system": "Code39", "data": "AB", "height": 80, "hri": "Below", "align": "Center"},
+                {"type": "QrCode", "data": "hi", "module_size": "Size4"}
+            ]"#,
+        );
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "AB").unwrap();
+        let qr = PrintQrCode::new("hi").unwrap().with_module_size(QrModuleSize::Size4);
+        let expected =
+            [&b"\x1dh\x50\x1dH\x02\x1ba\x01"[..], &barcode.encode(), b"\x1ba\x00", &qr.encode()]
+                .concat();
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn loads_base64_images_and_data_urls() {
+        let png = png_base64(16, 2);
+        let expected = PrintRasterImage::new(2, 2, vec![0xFF; 4]).encode();
+        let bytes = sections(&format!(
+            r#"[{{"type": "Image", "source": {{"base64": "{png}"}}}},
+               {{"type": "Image", "source": {{"url": "data:image/png;base64,{png}"}}}}]"#
+        ));
+        assert_eq!(bytes, [&expected[..], &expected].concat());
+
+        let bytes = sections(&format!(
+            r#"[{{"type": "Image", "source": {{"base64": "{png}"}}, "width": 8}}]"#
+        ));
+        assert_eq!(bytes, PrintRasterImage::new(1, 1, vec![0xFF]).encode());
+    }
+
+    #[test]
+    fn other_urls_go_to_the_loader() {
+        let receipt = Receipt::from_json(
+            r#"{"sections": [{"type": "Image", "source": {"url": "https://example.com/logo.png"}}]}"#,
+        )
+        .unwrap();
+        let err = receipt.render(&PrinterProfile::default()).unwrap_err();
+        assert!(
+            matches!(err, ReceiptError::Load { ref url, .. } if url == "https://example.com/logo.png")
+        );
+
+        let png = decode_base64(&png_base64(8, 1)).unwrap();
+        let mut printer = Printer::new(Vec::new());
+        let mut requested = Vec::new();
+        receipt
+            .print_with_loader(&mut printer, |url| {
+                requested.push(url.to_string());
+                Ok(png.clone())
+            })
+            .unwrap();
+        assert_eq!(requested, ["https://example.com/logo.png"]);
+        assert!(
+            printer.into_inner().0.ends_with(&PrintRasterImage::new(1, 1, vec![0xFF]).encode())
+        );
+    }
+
+    #[test]
+    fn feeds_and_cuts() {
+        let bytes = sections(
+            r#"[{"type": "Feed", "lines": 2}, {"type": "Cut"}, {"type": "Cut", "partial": true, "feed": 0}]"#,
+        );
+        let expected = [
+            FeedLines(2).encode(),
+            CutPaper::feed_and_full(3).encode(),
+            CutPaper::feed_and_partial(0).encode(),
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn prints_copies_with_a_banner() {
+        let receipt =
+            Receipt::from_json(r#"{"sections": [{"type": "Text", "spans": ["Thanks"]}]}"#)
+                .unwrap()
+                .print_copies(3);
+        let bytes = receipt.render(&PrinterProfile::default()).unwrap();
+        let banner = StyledNode::text("COPY")
+            .with_style(
+                StyleSet::new()
+                    .with_justification(Justification::Center)
+                    .with_bold(true)
+                    .with_width_scale(ScaleFactor::X2)
+                    .with_height_scale(ScaleFactor::X2),
+            )
+            .render_line();
+        let copy = [
+            CutPaper::feed_and_partial(3).encode(),
+            b"\x1b@".to_vec(),
+            banner,
+            b"Thanks\n".to_vec(),
+        ]
+        .concat();
+        assert_eq!(bytes, [b"\x1b@Thanks\n".to_vec(), copy.clone(), copy].concat());
+
+        let cut = r#"{"sections": [{"type": "Cut"}], "copies": 2, "copy_banner": ["MERCHANT"]}"#;
+        let bytes = render(cut);
+        let count =
+            |command: Vec<u8>| bytes.windows(command.len()).filter(|w| *w == command).count();
+        assert_eq!(count(CutPaper::feed_and_full(3).encode()), 2);
+        assert_eq!(count(CutPaper::feed_and_partial(3).encode()), 0);
+        assert_eq!(count(b"MERCHANT".to_vec()), 1);
+    }
+
+    #[test]
+    fn paginates_with_repeated_headers() {
+        let text = |text: &str| format!(r#"{{"type": "Text", "spans": ["{text}"]}}"#);
+        let more = r#"{"type": "Text", "spans": ["continued..."], "align": "Center"}"#;
+        let paged = format!(
+            r#"{{"sections": [{}, {}, {}, {}, {}],
+                "pagination": {{"lines": 2, "header": [{}]}}}}"#,
+            text("a"),
+            text("b"),
+            text("c"),
+            text("d"),
+            text("e"),
+            text("Item")
+        );
+        let expected = format!(
+            r#"{{"sections": [{h}, {}, {}, {more}, {h}, {}, {}, {more}, {h}, {}]}}"#,
+            text("a"),
+            text("b"),
+            text("c"),
+            text("d"),
+            text("e"),
+            h = text("Item")
+        );
+        assert_eq!(render(&paged), render(&expected));
+
+        let cut = r#"{"type": "Cut"}"#;
+        let paged = format!(
+            r#"{{"sections": [{}, {cut}, {}, {cut}], "pagination": {{"header": [{}]}}}}"#,
+            text("a"),
+            text("b"),
+            text("Item")
+        );
+        let expected = format!(
+            r#"{{"sections": [{h}, {}, {more}, {cut}, {h}, {}, {cut}]}}"#,
+            text("a"),
+            text("b"),
+            h = text("Item")
+        );
+        assert_eq!(render(&paged), render(&expected));
+    }
+
+    #[test]
+    fn pagination_counts_wrapped_text_lines() {
+        let text = |text: &str| format!(r#"{{"type": "Text", "spans": ["{text}"]}}"#);
+        let more = r#"{"type": "Text", "spans": ["continued..."], "align": "Center"}"#;
+        let long = "x".repeat(50);
+        let paged = format!(
+            r#"{{"sections": [{}, {}], "pagination": {{"lines": 2}}}}"#,
+            text(&long),
+            text("b"),
+        );
+        let expected = format!(r#"{{"sections": [{}, {more}, {}]}}"#, text(&long), text("b"));
+        assert_eq!(render(&paged), render(&expected));
+    }
+
+    #[test]
+    fn text_is_encoded_in_the_code_page() {
+        let bytes = render(r#"{"sections": [{"type": "Text", "spans": ["Café"]}]}"#);
+        assert!(bytes.windows(4).any(|w| w == b"Caf\x82"));
+        assert!(!bytes.windows(2).any(|w| w == [0xC3, 0xA9]));
+
+        let euro =
+            r#"{"code_page": "Cp858Euro", "sections": [{"type": "Text", "spans": ["5 €"]}]}"#;
+        let bytes = render(euro);
+        assert!(bytes.windows(3).any(|w| w == [0x1B, b't', 19]));
+        assert!(bytes.windows(3).any(|w| w == b"5 \xd5"));
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        let typo = r#"{"sections": [{"type": "Text", "spans": [], "algin": "Center"}]}"#;
+        assert!(matches!(Receipt::from_json(typo), Err(ReceiptError::Json(_))));
+
+        let barcode = r#"{"sections": [{"type": "Barcode", "system": "UpcA", "data": "12"}]}"#;
+        let err = Receipt::from_json(barcode).unwrap().render(&PrinterProfile::default());
+        assert!(matches!(err, Err(ReceiptError::Printer(PrinterError::Barcode(_)))));
+
+        let image = r#"{"sections": [{"type": "Image", "source": {"base64": "a"}}]}"#;
+        let err = Receipt::from_json(image).unwrap().render(&PrinterProfile::default());
+        assert!(matches!(err, Err(ReceiptError::Base64)));
+
+        let item = r#"{"sections": [
+            {"type": "Item", "qty": 4000000000, "description": ["x"], "unit_price": 9000000000000}
+        ]}"#;
+        let err = Receipt::from_json(item).unwrap().render(&PrinterProfile::default());
+        assert!(matches!(err, Err(ReceiptError::AmountOverflow)));
+
+        let totals = r#"{"sections": [{"type": "Totals", "subtotal": 9223372036854775807,
+            "lines": [{"label": ["Tax"], "amount": 1}]}]}"#;
+        let err = Receipt::from_json(totals).unwrap().render(&PrinterProfile::default());
+        assert!(matches!(err, Err(ReceiptError::AmountOverflow)));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let receipt = Receipt {
+            sections: vec![
+                Section::Text {
+                    spans: vec![Span::Plain("Thanks".into())],
+                    align: None,
+                },
+                Section::Cut {
+                    partial: true,
+                    feed: 3,
+                },
+            ],
+            currency: Currency::EUR,
+            locale: Locale::DE_DE,
+            copies: 2,
+            copy_banner: vec![Span::Plain("DUPLICATE".into())],
+            pagination: Some(Pagination::every(40).cut(true).header(Section::Rule {
+                style: RuleStyle::Double,
+            })),
+            code_page: CodePage::Cp858Euro,
+        };
+        let json = serde_json::to_string(&receipt).unwrap();
+        assert_eq!(Receipt::from_json(&json).unwrap(), receipt);
+    }
+
+    #[test]
+    fn decodes_base64() {
+        assert_eq!(decode_base64("aGk=").unwrap(), b"hi");
+        assert_eq!(decode_base64("aGk\n").unwrap(), b"hi");
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("a"), None);
+        assert_eq!(decode_base64("a!=="), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Receipts compiled once and printed many times.
+//!
+//! A [`CompiledTemplate`] is built from a [`Receipt`] whose text contains
+//! placeholders such as `{order}`. The whole receipt, images and barcodes
+//! included, is encoded when the template is compiled; each print only
+//! encodes the placeholder values and copies the bytes around them. Kitchen
+//! and ticket printers turning out hundreds of receipts an hour skip the
+//! layout, styling and image conversion of every print after the first.
+//!
+//! ```
+//! use bixolon::profile::PrinterProfile;
+//! use bixolon::receipt::Receipt;
+//! use bixolon::receipt::template::CompiledTemplate;
+//!
+//! let receipt = Receipt::from_json(
+//!     r#"{
+//!         "sections": [
+//!             {"type": "Text", "align": "Center", "spans": [
+//!                 {"text": "Order {order}", "bold": true}
+//!             ]},
+//!             {"type": "Text", "spans": ["Pick up at {counter}"]},
+//!             {"type": "Cut", "partial": true}
+//!         ]
+//!     }"#,
+//! )?;
+//! let template = CompiledTemplate::new(&receipt, &PrinterProfile::default())?;
+//!
+//! let ticket = template.render(&[("order", "1042"), ("counter", "B")])?;
+//! assert!(ticket.windows(10).any(|w| w == b"Order 1042"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::command::codepage::CodePage;
+use crate::encoding;
+use crate::error::{ReceiptError, ValidationError};
+use crate::page::template::find_placeholder;
+use crate::profile::PrinterProfile;
+
+use super::{Receipt, Section, Span, TotalsLine};
+
+/// A receipt encoded ahead of time, with named placeholders.
+///
+/// Placeholders are `{name}` in the text of spans, where `name` is ASCII
+/// letters, digits and underscores. Other braces print as written.
+/// Barcode, QR code and image data are never searched, so data such as
+/// Code128's `{A` is left alone.
+///
+/// Values are encoded in the receipt's [code page](Receipt::code_page),
+/// with characters it lacks printed as `?`, in the style active at the
+/// placeholder. Control characters in values, such as newlines or `ESC`,
+/// also print as `?`, so a value can't inject printer commands. Layout is
+/// measured with the placeholder text itself, so column alignment and
+/// wrapping don't account for longer values; placeholders suit short fields
+/// such as order numbers and names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledTemplate {
+    segments: Vec<Segment>,
+    code_page: CodePage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(Vec<u8>),
+    Placeholder(String),
+}
+
+impl CompiledTemplate {
+    /// Compile `receipt` for `profile`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError`] for the first section that can't be
+    /// printed, as for [`Receipt::render`].
+    pub fn new(receipt: &Receipt, profile: &PrinterProfile) -> Result<Self, ReceiptError> {
+        let bytes = receipt.render(profile)?;
+
+        // Placeholders are found where the receipt changes when their braces
+        // are swapped. Same-length text keeps the layout identical, so any
+        // other `{name}` in the bytes, such as barcode data, is unaffected.
+        let mut masked = receipt.clone();
+        for_each_span(&mut masked, &mut |text| *text = mask(text));
+        let masked = masked.render(profile)?;
+
+        let mut template = Self {
+            segments: Vec::new(),
+            code_page: receipt.code_page,
+        };
+        let mut literal = 0;
+        let mut from = 0;
+        while let Some((offset, len)) = find_placeholder(&bytes[from..]) {
+            let start = from + offset;
+            if masked.get(start) == Some(&b'{') {
+                from = start + 1;
+                continue;
+            }
+            template.push_literal(&bytes[literal..start]);
+            let name = String::from_utf8_lossy(&bytes[start + 1..start + len - 1]).into_owned();
+            template.segments.push(Segment::Placeholder(name));
+            from = start + len;
+            literal = from;
+        }
+        template.push_literal(&bytes[literal..]);
+        Ok(template)
+    }
+
+    /// Names of the placeholders, in order of appearance.
+    ///
+    /// A placeholder used more than once, or printed on every copy or page,
+    /// is listed each time.
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Fill in the placeholders and return the receipt bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::MissingPlaceholder`] if a placeholder has
+    /// no value. Values for unknown names are ignored.
+    pub fn render(&self, values: &[(&str, &str)]) -> Result<Vec<u8>, ValidationError> {
+        let mut output = Vec::new();
+        self.render_into(values, &mut output)?;
+        Ok(output)
+    }
+
+    /// Fill in the placeholders, appending the receipt bytes to `output`.
+    ///
+    /// Lets a run of tickets reuse one buffer. On error, `output` may hold a
+    /// partial receipt.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::MissingPlaceholder`] as for
+    /// [`render`](Self::render).
+    pub fn render_into(
+        &self,
+        values: &[(&str, &str)],
+        output: &mut Vec<u8>,
+    ) -> Result<(), ValidationError> {
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(bytes) => output.extend_from_slice(bytes),
+                Segment::Placeholder(name) => {
+                    let (_, value) = values
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .ok_or_else(|| ValidationError::MissingPlaceholder(name.clone()))?;
+                    if value.contains(char::is_control) {
+                        let value: String = value
+                            .chars()
+                            .map(|c| {
+                                if c.is_control() {
+                                    '?'
+                                } else {
+                                    c
+                                }
+                            })
+                            .collect();
+                        output.extend(encoding::encode_lossy(&value, self.code_page));
+                    } else {
+                        output.extend(encoding::encode_lossy(value, self.code_page));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_literal(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        match self.segments.last_mut() {
+            Some(Segment::Literal(literal)) => literal.extend_from_slice(bytes),
+            _ => self.segments.push(Segment::Literal(bytes.to_vec())),
+        }
+    }
+}
+
+/// `text` with the braces of each placeholder swapped, `{a}` becoming `}a{`.
+fn mask(text: &str) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+    let mut from = 0;
+    while let Some((offset, len)) = find_placeholder(&bytes[from..]) {
+        let start = from + offset;
+        bytes[start] = b'}';
+        bytes[start + len - 1] = b'{';
+        from = start + len;
+    }
+    // Only ASCII braces were swapped
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_owned())
+}
+
+/// Call `f` with the text of every span in `receipt`.
+fn for_each_span(receipt: &mut Receipt, f: &mut impl FnMut(&mut String)) {
+    let mut spans = |spans: &mut Vec<Span>| {
+        for span in spans {
+            match span {
+                Span::Plain(text)
+                | Span::Styled {
+                    text,
+                    ..
+                } => f(text),
+            }
+        }
+    };
+    section_spans(&mut receipt.sections, &mut spans);
+    spans(&mut receipt.copy_banner);
+    if let Some(pagination) = &mut receipt.pagination {
+        section_spans(&mut pagination.header, &mut spans);
+        spans(&mut pagination.continued);
+    }
+}
+
+/// Call `spans` with every run of spans in `sections`.
+fn section_spans(sections: &mut [Section], spans: &mut impl FnMut(&mut Vec<Span>)) {
+    for section in sections {
+        match section {
+            Section::Text {
+                spans: text,
+                ..
+            } => spans(text),
+            Section::Columns {
+                left,
+                center,
+                right,
+                ..
+            } => {
+                for segment in [left, center, right].into_iter().flatten() {
+                    spans(segment);
+                }
+            }
+            Section::Item {
+                description,
+                ..
+            } => spans(description),
+            Section::Totals {
+                lines,
+                ..
+            } => {
+                for TotalsLine {
+                    label,
+                    ..
+                } in lines
+                {
+                    spans(label);
+                }
+            }
+            Section::Signature {
+                caption: Some(caption),
+                ..
+            } => spans(caption),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(json: &str) -> CompiledTemplate {
+        let receipt = Receipt::from_json(json).unwrap();
+        CompiledTemplate::new(&receipt, &PrinterProfile::default()).unwrap()
+    }
+
+    fn render(json: &str) -> Vec<u8> {
+        Receipt::from_json(json).unwrap().render(&PrinterProfile::default()).unwrap()
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let template = compile(
+            r#"{"sections": [
+                {"type": "Text", "spans": [{"text": "Hi {name}!", "bold": true}]},
+                {"type": "Item", "qty": 1, "description": ["{dish}"], "unit_price": 450}
+            ]}"#,
+        );
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), ["name", "dish"]);
+
+        let filled = render(
+            r#"{"sections": [
+                {"type": "Text", "spans": [{"text": "Hi Ada!", "bold": true}]},
+                {"type": "Item", "qty": 1, "description": ["Salads"], "unit_price": 450}
+            ]}"#,
+        );
+        assert_eq!(template.render(&[("name", "Ada"), ("dish", "Salads")]).unwrap(), filled);
+    }
+
+    #[test]
+    fn copies_repeat_placeholders() {
+        let template =
+            compile(r#"{"copies": 2, "sections": [{"type": "Text", "spans": ["No. {order}"]}]}"#);
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), ["order", "order"]);
+    }
+
+    #[test]
+    fn barcode_data_is_not_searched() {
+        let json = r#"{"sections": [
+            {"type": "Barcode", "system": "Code128", "data": "{Bab}"},
+            {"type": "QrCode", "data": "{url}"}
+        ]}"#;
+        let template = compile(json);
+        assert_eq!(template.placeholders().count(), 0);
+        assert_eq!(template.render(&[]).unwrap(), render(json));
+    }
+
+    #[test]
+    fn values_are_encoded_in_the_default_code_page() {
+        let template = compile(r#"{"sections": [{"type": "Text", "spans": ["{name}"]}]}"#);
+        let ticket = template.render(&[("name", "Zoë")]).unwrap();
+        let name = encoding::encode_lossy("Zoë", CodePage::default());
+        assert!(ticket.windows(name.len()).any(|w| w == name));
+    }
+
+    #[test]
+    fn values_are_encoded_in_the_receipts_code_page() {
+        let template = compile(
+            r#"{"code_page": "Cp858Euro", "sections": [{"type": "Text", "spans": ["{price}"]}]}"#,
+        );
+        let ticket = template.render(&[("price", "5 €")]).unwrap();
+        assert!(ticket.windows(3).any(|w| w == b"5 \xd5"));
+    }
+
+    #[test]
+    fn control_characters_in_values_are_replaced() {
+        let template = compile(r#"{"sections": [{"type": "Text", "spans": ["{name}"]}]}"#);
+        let ticket = template.render(&[("name", "A\x1bp\x00\n\tB")]).unwrap();
+        assert!(ticket.windows(7).any(|w| w == b"A?p???B"));
+    }
+
+    #[test]
+    fn missing_value_is_an_error() {
+        let template = compile(r#"{"sections": [{"type": "Text", "spans": ["{table}"]}]}"#);
+        assert!(matches!(
+            template.render(&[("guest", "x")]),
+            Err(ValidationError::MissingPlaceholder(name)) if name == "table"
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+//! End-of-day reports and shift summaries.
+//!
+//! A [`Report`] lays out a Z-report or shift summary: a title, sections of
+//! key-value rows joined by dotted leaders, amounts grouped under
+//! subtotals, and a boxed block of grand totals at the end.
+//!
+//! ```
+//! use bixolon::money::{Currency, Money};
+//! use bixolon::profile::PrinterProfile;
+//! use bixolon::report::{Report, ReportSection};
+//!
+//! let usd = |minor| Money::new(minor, Currency::USD);
+//! let report = Report::new("Z-REPORT")
+//!     .header("Register 2 - 2024-05-17")
+//!     .section(
+//!         ReportSection::new("Tenders")
+//!             .amount("Cash", usd(81_250))
+//!             .amount("Card", usd(142_075)),
+//!     )
+//!     .section(
+//!         ReportSection::new("Sales")
+//!             .amount("Coffee", usd(120_000))
+//!             .amount("Pastries", usd(60_325))
+//!             .subtotal("Food and drink")
+//!             .amount("Merchandise", usd(43_000))
+//!             .subtotal("Retail")
+//!             .row("Transactions", "412"),
+//!     )
+//!     .total("Net sales", usd(223_325));
+//! let bytes = report.build(&PrinterProfile::SRP_350PLUS);
+//! assert!(bytes.windows(8).any(|w| w == b"Z-REPORT"));
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::command::Command;
+use crate::command::character::{Justification, ScaleFactor};
+use crate::command::codepage::CodePage;
+use crate::command::paper::CutPaper;
+use crate::command::printer_control::Initialize;
+use crate::error::ValidationError;
+use crate::money::{Locale, Money};
+use crate::profile::PrinterProfile;
+use crate::style::StyleSet;
+use crate::style::layout::LineBuilder;
+use crate::style::text::StyledNode;
+
+/// Text of CP437 box-drawing characters, which UTF-8 lacks on the printer.
+fn box_drawing(text: String) -> StyledNode {
+    StyledNode::styled(StyleSet::new().with_code_page(CodePage::Cp437UsaStandardEurope), text)
+}
+
+/// One row of a [`ReportSection`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReportRow {
+    Value(StyledNode, StyledNode),
+    Amount(StyledNode, Money),
+    Subtotal(StyledNode),
+}
+
+/// A titled group of rows in a [`Report`].
+///
+/// Rows print in the order added. A [subtotal](Self::subtotal) adds up the
+/// amounts since the previous subtotal, so a section can hold several
+/// groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportSection {
+    title: StyledNode,
+    rows: Vec<ReportRow>,
+}
+
+impl ReportSection {
+    /// An empty section headed `title`.
+    pub fn new(title: impl Into<StyledNode>) -> Self {
+        Self {
+            title: title.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row with `value` at the right margin, joined to `key` by a
+    /// dotted leader.
+    pub fn row(mut self, key: impl Into<StyledNode>, value: impl Into<StyledNode>) -> Self {
+        self.rows.push(ReportRow::Value(key.into(), value.into()));
+        self
+    }
+
+    /// Add a row with an amount, aligned on the decimal point.
+    pub fn amount(mut self, key: impl Into<StyledNode>, amount: Money) -> Self {
+        self.rows.push(ReportRow::Amount(key.into(), amount));
+        self
+    }
+
+    /// Add a bold subtotal of the amounts since the previous subtotal.
+    pub fn subtotal(mut self, label: impl Into<StyledNode>) -> Self {
+        self.rows.push(ReportRow::Subtotal(label.into()));
+        self
+    }
+
+    fn build(
+        &self,
+        profile: &PrinterProfile,
+        locale: &Locale,
+        columns: usize,
+    ) -> Result<Vec<StyledNode>, ValidationError> {
+        let mut lines = vec![
+            self.title.clone().with_style(StyleSet::new().with_bold(true)),
+            box_drawing("─".repeat(columns)),
+        ];
+        let mut group: Option<Money> = None;
+        for row in &self.rows {
+            let line = match row {
+                ReportRow::Value(key, value) => {
+                    LineBuilder::new().left(key.clone()).right(value.clone()).fill('.')
+                }
+                ReportRow::Amount(key, amount) => {
+                    group = Some(match group.take() {
+                        Some(sum) => add(&sum, amount)?,
+                        None => amount.clone(),
+                    });
+                    LineBuilder::new().left(key.clone()).amount(amount, locale).fill('.')
+                }
+                ReportRow::Subtotal(label) => {
+                    let Some(sum) = group.take() else {
+                        continue;
+                    };
+                    let line = LineBuilder::new().left(label.clone()).amount(&sum, locale);
+                    let bold = StyleSet::new().with_bold(true);
+                    lines.push(line.build_with_width(profile, columns).with_style(bold));
+                    continue;
+                }
+            };
+            lines.push(line.build_with_width(profile, columns));
+        }
+        Ok(lines)
+    }
+}
+
+/// `sum + amount` for a subtotal.
+fn add(sum: &Money, amount: &Money) -> Result<Money, ValidationError> {
+    if sum.currency != amount.currency {
+        return Err(ValidationError::MixedCurrencies {
+            expected: sum.currency.code.to_string(),
+            found: amount.currency.code.to_string(),
+        });
+    }
+    sum.checked_add(amount).ok_or(ValidationError::AmountOverflow)
+}
+
+/// Builder for an end-of-day or shift report.
+///
+/// Prints, top to bottom: the title, centered in bold at double size; the
+/// header lines, centered; each section with its title underlined, separated
+/// by blank lines; then the totals in a double-line box; and cuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    title: StyledNode,
+    header: Vec<StyledNode>,
+    sections: Vec<ReportSection>,
+    totals: Vec<ReportRow>,
+    locale: Locale,
+    cut: bool,
+    code_page: CodePage,
+}
+
+impl Report {
+    /// A report titled `title`, such as "Z-REPORT".
+    pub fn new(title: impl Into<StyledNode>) -> Self {
+        Self {
+            title: title.into(),
+            header: Vec::new(),
+            sections: Vec::new(),
+            totals: Vec::new(),
+            locale: Locale::default(),
+            cut: true,
+            code_page: CodePage::default(),
+        }
+    }
+
+    /// Add a line under the title, such as the register and date.
+    pub fn header(mut self, line: impl Into<StyledNode>) -> Self {
+        self.header.push(line.into());
+        self
+    }
+
+    /// Add a section.
+    pub fn section(mut self, section: ReportSection) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    /// Add an amount to the totals box.
+    pub fn total(mut self, label: impl Into<StyledNode>, amount: Money) -> Self {
+        self.totals.push(ReportRow::Amount(label.into(), amount));
+        self
+    }
+
+    /// Add a value, such as a count, to the totals box.
+    pub fn total_row(mut self, label: impl Into<StyledNode>, value: impl Into<StyledNode>) -> Self {
+        self.totals.push(ReportRow::Value(label.into(), value.into()));
+        self
+    }
+
+    /// Write amounts with `locale` instead of US English.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Whether to cut the paper at the end (the default).
+    pub fn cut(mut self, cut: bool) -> Self {
+        self.cut = cut;
+        self
+    }
+
+    /// Encode text in `code_page` instead of CP437.
+    ///
+    /// Box borders always print in CP437. Characters the code page lacks,
+    /// such as `€` in CP437, print as `?`.
+    pub fn code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// The report's lines for the profile's Font A line width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a subtotal can't be added up; see
+    /// [`checked_build`](Self::checked_build).
+    pub fn lines(&self, profile: &PrinterProfile) -> Vec<StyledNode> {
+        self.lines_with_width(profile, usize::from(profile.font_a_columns))
+    }
+
+    /// The report's lines for a width of `columns` Font A cells.
+    ///
+    /// Each line is a separate node without a trailing newline; blank lines
+    /// are empty nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a subtotal can't be added up; see
+    /// [`checked_build`](Self::checked_build).
+    pub fn lines_with_width(&self, profile: &PrinterProfile, columns: usize) -> Vec<StyledNode> {
+        self.try_lines_with_width(profile, columns).expect("cannot add up subtotal")
+    }
+
+    fn try_lines_with_width(
+        &self,
+        profile: &PrinterProfile,
+        columns: usize,
+    ) -> Result<Vec<StyledNode>, ValidationError> {
+        let centered = StyleSet::new().with_justification(Justification::Center);
+        let title = centered
+            .clone()
+            .with_bold(true)
+            .with_width_scale(ScaleFactor::X2)
+            .with_height_scale(ScaleFactor::X2);
+        let mut lines = vec![self.title.clone().with_style(title)];
+        lines.extend(self.header.iter().map(|line| line.clone().with_style(centered.clone())));
+
+        for section in &self.sections {
+            lines.push(StyledNode::text(""));
+            lines.extend(section.build(profile, &self.locale, columns)?);
+        }
+
+        if !self.totals.is_empty() {
+            let inner = columns.saturating_sub(4);
+            lines.push(StyledNode::text(""));
+            lines.push(box_drawing(format!("╔{}╗", "═".repeat(columns.saturating_sub(2)))));
+            for row in &self.totals {
+                let line = match row {
+                    ReportRow::Amount(label, amount) => {
+                        LineBuilder::new().left(label.clone()).amount(amount, &self.locale)
+                    }
+                    ReportRow::Value(label, value) => {
+                        LineBuilder::new().left(label.clone()).right(value.clone())
+                    }
+                    ReportRow::Subtotal(_) => continue,
+                };
+                let line = line.build_with_width(profile, inner);
+                // Pad short lines so the right border lines up
+                let pad = inner.saturating_sub(line.columns(profile));
+                lines.push(
+                    box_drawing("║ ".into())
+                        .append(line.with_style(StyleSet::new().with_bold(true)))
+                        .append(box_drawing(format!("{} ║", " ".repeat(pad)))),
+                );
+            }
+            lines.push(box_drawing(format!("╚{}╝", "═".repeat(columns.saturating_sub(2)))));
+        }
+        Ok(lines)
+    }
+
+    /// Build the report as a complete print job for `profile`.
+    ///
+    /// The job starts with `ESC @`, and the cut uses the profile's dialect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a subtotal can't be added up; see
+    /// [`checked_build`](Self::checked_build).
+    pub fn build(&self, profile: &PrinterProfile) -> Vec<u8> {
+        self.checked_build(profile).expect("cannot add up subtotal")
+    }
+
+    /// Build the report, checking the subtotals.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::MixedCurrencies`] if a subtotal's amounts
+    /// are in different currencies, or [`ValidationError::AmountOverflow`]
+    /// if their sum is past the range of `i64` minor units.
+    pub fn checked_build(&self, profile: &PrinterProfile) -> Result<Vec<u8>, ValidationError> {
+        let mut output = Initialize.encode();
+        let encoded = StyleSet::new().with_code_page(self.code_page);
+        let columns = usize::from(profile.font_a_columns);
+        for line in self.try_lines_with_width(profile, columns)? {
+            line.with_style(encoded.clone()).render_line_into_for(profile, &mut output);
+        }
+        if self.cut {
+            output.extend(CutPaper::feed_and_partial(3).encode_for(profile.dialect));
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::encode_lossy;
+    use crate::money::Currency;
+
+    /// Whether `line` prints `text`, ignoring style commands around it.
+    fn shows(profile: &PrinterProfile, line: &StyledNode, text: &str) -> bool {
+        let expected = encode_lossy(text, CodePage::default());
+        line.render_for(profile).windows(expected.len()).any(|w| w == expected)
+    }
+
+    #[test]
+    fn rows_use_dotted_leaders_and_subtotals_sum_groups() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let usd = |minor| Money::new(minor, Currency::USD);
+        let section = ReportSection::new("Sales")
+            .amount("Coffee", usd(1_000))
+            .amount("Tea", usd(250))
+            .subtotal("Drinks")
+            .amount("Mugs", usd(1_200))
+            .subtotal("Retail")
+            .row("Voids", "3");
+        let lines = section.build(&profile, &Locale::EN_US, 20).unwrap();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[2].render_for(&profile), b"Coffee........$10.00");
+        assert_eq!(lines[7].render_for(&profile), b"Voids..............3");
+        assert!(shows(&profile, &lines[4], "Drinks        $12.50"));
+        assert!(shows(&profile, &lines[6], "Retail        $12.00"));
+    }
+
+    #[test]
+    fn unsummable_subtotals_are_errors() {
+        let profile = PrinterProfile::default();
+        let usd = |minor| Money::new(minor, Currency::USD);
+        let overflow = ReportSection::new("Sales")
+            .amount("A", usd(i64::MAX))
+            .amount("B", usd(1))
+            .subtotal("Total");
+        let report = Report::new("X").section(overflow);
+        assert!(matches!(report.checked_build(&profile), Err(ValidationError::AmountOverflow)));
+
+        let mixed = ReportSection::new("Sales")
+            .amount("A", usd(100))
+            .amount("B", Money::new(100, Currency::EUR));
+        let report = Report::new("X").section(mixed);
+        assert!(matches!(
+            report.checked_build(&profile),
+            Err(ValidationError::MixedCurrencies { expected, found })
+                if expected == "USD" && found == "EUR"
+        ));
+    }
+
+    #[test]
+    fn totals_are_boxed() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let report = Report::new("X")
+            .total("Net", Money::new(500, Currency::USD))
+            .total_row("Customers", "12");
+        let lines = report.lines_with_width(&profile, 20);
+        let widths: Vec<_> = lines[2..].iter().map(|line| line.columns(&profile)).collect();
+        assert_eq!(widths, [20, 20, 20, 20]);
+        assert!(shows(&profile, &lines[2], &format!("╔{}╗", "═".repeat(18))));
+        assert!(shows(&profile, &lines[4], "Customers     12"));
+        assert!(shows(&profile, &lines[4], " ║"));
+    }
+
+    #[test]
+    fn build_encodes_text_in_the_code_page() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let bytes = Report::new("X").header("Café").cut(false).build(&profile);
+        assert!(bytes.windows(4).any(|w| w == b"Caf\x82"));
+
+        let report = Report::new("X")
+            .locale(Locale::DE_DE)
+            .total("Netto", Money::new(500, Currency::EUR))
+            .cut(false);
+        let bytes = report.clone().build(&profile);
+        assert!(bytes.windows(6).any(|w| w == b"5,00 ?"));
+        let bytes = report.code_page(CodePage::Cp858Euro).build(&profile);
+        assert!(bytes.windows(6).any(|w| w == b"5,00 \xd5"));
+        // The box still prints in CP437
+        assert!(bytes.windows(2).any(|w| w == [0xC9, 0xCD]));
+    }
+}
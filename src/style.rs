@@ -3,10 +3,19 @@
 //! Provides [`StyleSet`] for defining text formatting and stack-based
 //! resolution for handling nested styles correctly.
 
+#[cfg(feature = "bidi")]
+pub mod bidi;
+pub mod html;
+pub mod layout;
+pub mod preview;
 pub mod text;
 
-use crate::command::Command;
-use crate::command::character::{CharacterSize, Justification, UnderlineThickness};
+use alloc::vec::Vec;
+
+use crate::command::character::{
+    CharacterSize, Font, Justification, PrintColor, ScaleFactor, UnderlineThickness,
+};
+use crate::command::codepage::CodePage;
 
 /// A set of optional style properties.
 ///
@@ -30,6 +39,7 @@ use crate::command::character::{CharacterSize, Justification, UnderlineThickness
 /// assert_eq!(effective.underline, Some(true)); // from inner
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StyleSet {
     /// Bold/emphasized text.
     pub bold: Option<bool>,
@@ -39,7 +49,15 @@ pub struct StyleSet {
     pub double_underline: Option<bool>,
     /// Double-strike mode.
     pub double_strike: Option<bool>,
+    /// Horizontal character scale.
+    pub width_scale: Option<ScaleFactor>,
+    /// Vertical character scale.
+    pub height_scale: Option<ScaleFactor>,
     /// Character size multiplier.
+    ///
+    /// Sets both scales at once; `width_scale` and `height_scale` take
+    /// precedence over it.
+    #[deprecated(note = "use `width_scale` and `height_scale`, or `with_size`")]
     pub size: Option<CharacterSize>,
     /// Reverse (white on black) mode.
     pub reverse: Option<bool>,
@@ -49,6 +67,16 @@ pub struct StyleSet {
     pub rotated: Option<bool>,
     /// Text justification.
     pub justification: Option<Justification>,
+    /// Print color (two-color models only).
+    pub color: Option<PrintColor>,
+    /// Character font.
+    pub font: Option<Font>,
+    /// Strikethrough, emulated by overprinting dashes.
+    pub strikethrough: Option<bool>,
+    /// Code page the text is transcoded into.
+    ///
+    /// Unset text is sent as-is with the printer's default code page.
+    pub code_page: Option<CodePage>,
 }
 
 impl StyleSet {
@@ -59,11 +87,18 @@ impl StyleSet {
             underline: None,
             double_underline: None,
             double_strike: None,
+            width_scale: None,
+            height_scale: None,
+            #[allow(deprecated)]
             size: None,
             reverse: None,
             upside_down: None,
             rotated: None,
             justification: None,
+            color: None,
+            font: None,
+            strikethrough: None,
+            code_page: None,
         }
     }
 
@@ -85,8 +120,12 @@ impl StyleSet {
             if style.double_strike.is_some() {
                 merged.double_strike = style.double_strike;
             }
-            if style.size.is_some() {
-                merged.size = style.size;
+            let (width_scale, height_scale) = style.scales();
+            if width_scale.is_some() {
+                merged.width_scale = width_scale;
+            }
+            if height_scale.is_some() {
+                merged.height_scale = height_scale;
             }
             if style.reverse.is_some() {
                 merged.reverse = style.reverse;
@@ -100,6 +139,18 @@ impl StyleSet {
             if style.justification.is_some() {
                 merged.justification = style.justification;
             }
+            if style.color.is_some() {
+                merged.color = style.color;
+            }
+            if style.font.is_some() {
+                merged.font = style.font;
+            }
+            if style.strikethrough.is_some() {
+                merged.strikethrough = style.strikethrough;
+            }
+            if style.code_page.is_some() {
+                merged.code_page = style.code_page;
+            }
         }
         merged
     }
@@ -128,12 +179,41 @@ impl StyleSet {
         self
     }
 
-    /// Set character size.
+    /// Set character size (both width and height scale).
     pub const fn with_size(mut self, size: CharacterSize) -> Self {
-        self.size = Some(size);
+        self.width_scale = Some(size.width);
+        self.height_scale = Some(size.height);
+        self
+    }
+
+    /// Set horizontal character scale, inheriting the vertical scale.
+    pub const fn with_width_scale(mut self, scale: ScaleFactor) -> Self {
+        self.width_scale = Some(scale);
+        self
+    }
+
+    /// Set vertical character scale, inheriting the horizontal scale.
+    pub const fn with_height_scale(mut self, scale: ScaleFactor) -> Self {
+        self.height_scale = Some(scale);
         self
     }
 
+    /// Effective character size, treating unset scales as 1x.
+    pub fn size(&self) -> CharacterSize {
+        let (width_scale, height_scale) = self.scales();
+        CharacterSize::new(width_scale.unwrap_or_default(), height_scale.unwrap_or_default())
+    }
+
+    /// Width and height scales, falling back to the deprecated `size`.
+    pub(crate) fn scales(&self) -> (Option<ScaleFactor>, Option<ScaleFactor>) {
+        #[allow(deprecated)]
+        let size = self.size;
+        (
+            self.width_scale.or(size.map(|size| size.width)),
+            self.height_scale.or(size.map(|size| size.height)),
+        )
+    }
+
     /// Set reverse (white on black) mode.
     pub const fn with_reverse(mut self, enabled: bool) -> Self {
         self.reverse = Some(enabled);
@@ -158,17 +238,48 @@ impl StyleSet {
         self
     }
 
+    /// Set print color.
+    pub const fn with_color(mut self, color: PrintColor) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Set character font.
+    pub const fn with_font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Set strikethrough.
+    ///
+    /// The printer has no native strikethrough; the renderer overprints
+    /// the text with dashes using relative positioning.
+    pub const fn with_strikethrough(mut self, enabled: bool) -> Self {
+        self.strikethrough = Some(enabled);
+        self
+    }
+
+    /// Set the code page text is transcoded into.
+    pub const fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = Some(code_page);
+        self
+    }
+
     /// Check if any property is set.
     pub fn is_empty(&self) -> bool {
         self.bold.is_none()
             && self.underline.is_none()
             && self.double_underline.is_none()
             && self.double_strike.is_none()
-            && self.size.is_none()
+            && self.scales() == (None, None)
             && self.reverse.is_none()
             && self.upside_down.is_none()
             && self.rotated.is_none()
             && self.justification.is_none()
+            && self.color.is_none()
+            && self.font.is_none()
+            && self.strikethrough.is_none()
+            && self.code_page.is_none()
     }
 }
 
@@ -177,18 +288,31 @@ impl StyleSet {
 /// This is used when rendering styled text to generate the minimal
 /// set of ESC/POS commands needed between text segments.
 pub fn style_transition_commands(from: &StyleSet, to: &StyleSet) -> Vec<Vec<u8>> {
+    let mut commands = Vec::new();
+    transition(from, to, |bytes| commands.push(bytes.to_vec()));
+    commands
+}
+
+/// Append the commands needed to transition from one style to another.
+///
+/// Same commands as [`style_transition_commands`], written straight into
+/// `output` without allocating a buffer per command.
+pub fn write_style_transition(from: &StyleSet, to: &StyleSet, output: &mut Vec<u8>) {
+    transition(from, to, |bytes| output.extend_from_slice(bytes));
+}
+
+fn transition(from: &StyleSet, to: &StyleSet, mut emit: impl FnMut(&[u8])) {
     use crate::command::character::{
-        RotationMode, SetCharacterSize, SetDoubleStrike, SetEmphasized, SetJustification,
-        SetReverse, SetRotation, SetUnderline, SetUpsideDown,
+        RotationMode, SelectFont, SelectPrintColor, SetCharacterSize, SetDoubleStrike,
+        SetEmphasized, SetJustification, SetReverse, SetRotation, SetUnderline, SetUpsideDown,
     };
-
-    let mut commands: Vec<Vec<u8>> = Vec::new();
+    use crate::command::codepage::SelectCodePage;
 
     // Bold
     let from_bold = from.bold.unwrap_or(false);
     let to_bold = to.bold.unwrap_or(false);
     if from_bold != to_bold {
-        commands.push(SetEmphasized(to_bold).encode());
+        emit(&SetEmphasized(to_bold).to_bytes());
     }
 
     // Underline
@@ -205,35 +329,42 @@ pub fn style_transition_commands(from: &StyleSet, to: &StyleSet) -> Vec<Vec<u8>>
         } else {
             UnderlineThickness::Off
         };
-        commands.push(SetUnderline(thickness).encode());
+        emit(&SetUnderline(thickness).to_bytes());
     }
 
     // Double-strike
     let from_ds = from.double_strike.unwrap_or(false);
     let to_ds = to.double_strike.unwrap_or(false);
     if from_ds != to_ds {
-        commands.push(SetDoubleStrike(to_ds).encode());
+        emit(&SetDoubleStrike(to_ds).to_bytes());
+    }
+
+    // Font
+    let from_font = from.font.unwrap_or_default();
+    let to_font = to.font.unwrap_or_default();
+    if from_font != to_font {
+        emit(&SelectFont(to_font).to_bytes());
     }
 
     // Size
-    let from_size = from.size.unwrap_or_default();
-    let to_size = to.size.unwrap_or_default();
+    let from_size = from.size();
+    let to_size = to.size();
     if from_size != to_size {
-        commands.push(SetCharacterSize(to_size).encode());
+        emit(&SetCharacterSize(to_size).to_bytes());
     }
 
     // Reverse
     let from_reverse = from.reverse.unwrap_or(false);
     let to_reverse = to.reverse.unwrap_or(false);
     if from_reverse != to_reverse {
-        commands.push(SetReverse(to_reverse).encode());
+        emit(&SetReverse(to_reverse).to_bytes());
     }
 
     // Upside-down
     let from_upside = from.upside_down.unwrap_or(false);
     let to_upside = to.upside_down.unwrap_or(false);
     if from_upside != to_upside {
-        commands.push(SetUpsideDown(to_upside).encode());
+        emit(&SetUpsideDown(to_upside).to_bytes());
     }
 
     // Rotated
@@ -245,17 +376,29 @@ pub fn style_transition_commands(from: &StyleSet, to: &StyleSet) -> Vec<Vec<u8>>
         } else {
             RotationMode::Off
         };
-        commands.push(SetRotation(mode).encode());
+        emit(&SetRotation(mode).to_bytes());
     }
 
     // Justification
     let from_just = from.justification.unwrap_or(Justification::Left);
     let to_just = to.justification.unwrap_or(Justification::Left);
     if from_just != to_just {
-        commands.push(SetJustification(to_just).encode());
+        emit(&SetJustification(to_just).to_bytes());
     }
 
-    commands
+    // Color
+    let from_color = from.color.unwrap_or_default();
+    let to_color = to.color.unwrap_or_default();
+    if from_color != to_color {
+        emit(&SelectPrintColor(to_color).to_bytes());
+    }
+
+    // Code page
+    let from_code_page = from.code_page.unwrap_or_default();
+    let to_code_page = to.code_page.unwrap_or_default();
+    if from_code_page != to_code_page {
+        emit(&SelectCodePage(to_code_page).to_bytes());
+    }
 }
 
 #[cfg(test)]
@@ -339,4 +482,60 @@ mod tests {
         let commands = style_transition_commands(&from, &to);
         assert_eq!(commands.len(), 3);
     }
+
+    #[test]
+    fn style_transition_color() {
+        let from = StyleSet::default();
+        let to = StyleSet::default().with_color(PrintColor::Red);
+        assert_eq!(style_transition_commands(&from, &to), vec![vec![0x1B, b'r', 1]]);
+        assert_eq!(style_transition_commands(&to, &from), vec![vec![0x1B, b'r', 0]]);
+    }
+
+    #[test]
+    fn from_stack_combines_width_and_height_scale() {
+        let outer = StyleSet::default().with_width_scale(ScaleFactor::X2);
+        let inner = StyleSet::default().with_height_scale(ScaleFactor::X3);
+        let result = StyleSet::from_stack(&[outer, inner]);
+        assert_eq!(result.size(), CharacterSize::new(ScaleFactor::X2, ScaleFactor::X3));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_size_sets_both_scales() {
+        let outer = StyleSet {
+            size: Some(CharacterSize::double()),
+            ..StyleSet::default()
+        };
+        let inner = StyleSet {
+            size: Some(CharacterSize::double()),
+            ..StyleSet::default().with_height_scale(ScaleFactor::X3)
+        };
+        assert!(!outer.is_empty());
+        assert_eq!(outer.size(), CharacterSize::double());
+        let result = StyleSet::from_stack(&[outer, inner]);
+        assert_eq!(result.size(), CharacterSize::new(ScaleFactor::X2, ScaleFactor::X3));
+    }
+
+    #[test]
+    fn style_transition_size() {
+        let from = StyleSet::default();
+        let to = StyleSet::default().with_size(CharacterSize::double());
+        assert_eq!(style_transition_commands(&from, &to), vec![vec![0x1D, b'!', 0x11]]);
+    }
+
+    #[test]
+    fn style_transition_font() {
+        let from = StyleSet::default();
+        let to = StyleSet::default().with_font(Font::B);
+        assert_eq!(style_transition_commands(&from, &to), vec![vec![0x1B, b'M', 1]]);
+        assert_eq!(style_transition_commands(&to, &from), vec![vec![0x1B, b'M', 0]]);
+    }
+
+    #[test]
+    fn style_transition_code_page() {
+        let from = StyleSet::default();
+        let to = StyleSet::default().with_code_page(CodePage::Windows1251Cyrillic);
+        assert_eq!(style_transition_commands(&from, &to), vec![vec![0x1B, b't', 28]]);
+        assert_eq!(style_transition_commands(&to, &from), vec![vec![0x1B, b't', 0]]);
+    }
 }
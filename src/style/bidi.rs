@@ -0,0 +1,122 @@
+//! Right-to-left text reordering.
+//!
+//! The printer draws every line left to right in the order the bytes
+//! arrive, so Hebrew and Arabic text sent in logical (reading) order comes
+//! out reversed. [`to_visual`] applies the Unicode Bidirectional Algorithm
+//! to each line, reversing right-to-left runs while leaving numbers and
+//! embedded left-to-right text readable:
+//!
+//! ```
+//! use bixolon::style::bidi::to_visual;
+//!
+//! assert_eq!(to_visual("שלום 123"), "123 םולש");
+//! ```
+//!
+//! Reordering works per printed line, so wrap text before reordering it.
+//! Arabic letters also change shape with their neighbours, which the
+//! printer can't do; [`check_shaping`] reports such text.
+
+use std::borrow::Cow;
+
+use unicode_bidi::ParagraphBidiInfo;
+
+use crate::error::ShapingError;
+
+/// Reorder each line of `text` from logical to visual order.
+///
+/// Lines containing no right-to-left characters are returned unchanged.
+pub fn to_visual(text: &str) -> Cow<'_, str> {
+    if !text.lines().any(has_rtl) {
+        return Cow::Borrowed(text);
+    }
+    let lines: Vec<Cow<str>> = text
+        .split('\n')
+        .map(|line| {
+            if has_rtl(line) {
+                ParagraphBidiInfo::new(line, None).reorder_line(0..line.len())
+            } else {
+                Cow::Borrowed(line)
+            }
+        })
+        .collect();
+    Cow::Owned(lines.join("\n"))
+}
+
+/// Check that `text` needs no contextual shaping.
+///
+/// Arabic-script letters take different forms at the start, middle and end
+/// of a word. Code pages such as CP864 hold the individual presentation
+/// forms (U+FB50-U+FEFF), which pass this check; base letters don't.
+///
+/// # Errors
+///
+/// Returns [`ShapingError`] pointing at the first joining letter.
+pub fn check_shaping(text: &str) -> Result<(), ShapingError> {
+    match text.char_indices().find(|&(_, c)| needs_shaping(c)) {
+        Some((offset, c)) => Err(ShapingError {
+            src: text.to_string(),
+            span: (offset, c.len_utf8()).into(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// [`check_shaping`], then [`to_visual`].
+///
+/// # Errors
+///
+/// Returns [`ShapingError`] if the text needs contextual shaping.
+pub fn prepare_rtl(text: &str) -> Result<Cow<'_, str>, ShapingError> {
+    check_shaping(text)?;
+    Ok(to_visual(text))
+}
+
+fn has_rtl(line: &str) -> bool {
+    line.chars().any(|c| {
+        matches!(c, '\u{0590}'..='\u{08ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}')
+    })
+}
+
+/// Whether `c` is an Arabic or Syriac letter that joins its neighbours.
+fn needs_shaping(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0620}'
+            | '\u{0622}'..='\u{064a}'
+            | '\u{066e}'..='\u{066f}'
+            | '\u{0671}'..='\u{06d3}'
+            | '\u{06d5}'
+            | '\u{06ee}'..='\u{06ef}'
+            | '\u{06fa}'..='\u{06fc}'
+            | '\u{06ff}'
+            | '\u{0710}'..='\u{072f}'
+            | '\u{074d}'..='\u{077f}'
+            | '\u{08a0}'..='\u{08c9}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverses_rtl_runs_per_line() {
+        assert_eq!(to_visual("abc שלום def"), "abc םולש def");
+        assert_eq!(to_visual("סה\"כ: 12.50\nTotal"), "12.50 :כ\"הס\nTotal");
+    }
+
+    #[test]
+    fn ltr_text_is_borrowed() {
+        assert!(matches!(to_visual("Total 12.50"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn flags_arabic_base_letters() {
+        let err = check_shaping("Tea شاي").unwrap_err();
+        assert_eq!(err.span, (4, 2).into());
+
+        // Isolated presentation forms are already shaped
+        assert!(prepare_rtl("\u{feb1}\u{fe8e}").is_ok());
+        assert!(check_shaping("שלום").is_ok());
+    }
+}
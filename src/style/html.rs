@@ -0,0 +1,388 @@
+//! Convert a restricted HTML fragment into a [`StyledNode`].
+//!
+//! For systems whose receipt content already arrives as HTML. Only a small
+//! subset is understood:
+//!
+//! | Markup | Effect |
+//! |--------|--------|
+//! | `<b>`, `<strong>` | Bold |
+//! | `<u>` | Underline |
+//! | `<center>` | Centered lines, starting and ending on a line of its own |
+//! | `<br>` | Line break |
+//! | `<font size="N">` | Character size: 1-3 normal, 4 double height, 5 double, 6 triple, 7 quadruple; `+N`/`-N` are relative to 3 |
+//!
+//! Whitespace is collapsed as in HTML, and the entities `&amp;`, `&lt;`,
+//! `&gt;`, `&quot;`, `&apos;`, `&nbsp;` and numeric references are decoded.
+//! Any other tag is an error rather than being silently dropped.
+//!
+//! ```
+//! use bixolon::style::html;
+//! use bixolon::style::text::Styleable;
+//!
+//! let node = html::parse("<b>Total</b> 25.00").unwrap();
+//! assert_eq!(node.render(), ("Total".bold() + " 25.00").render());
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::StyleSet;
+use super::text::StyledNode;
+use crate::command::character::{CharacterSize, Justification, ScaleFactor};
+use crate::error::HtmlError;
+
+/// Parse an HTML fragment into a styled node tree.
+///
+/// # Errors
+///
+/// Returns an [`HtmlError`] for tags outside the supported subset,
+/// mismatched or unclosed tags, malformed markup and invalid font sizes.
+pub fn parse(html: &str) -> Result<StyledNode, HtmlError> {
+    Parser::new(html).parse()
+}
+
+struct Frame {
+    tag: String,
+    style: StyleSet,
+    children: Vec<StyledNode>,
+    start: usize,
+    len: usize,
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    stack: Vec<Frame>,
+    text: String,
+    /// Last character emitted, for whitespace collapsing and block breaks.
+    last: Option<char>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            stack: vec![Frame {
+                tag: String::new(),
+                style: StyleSet::new(),
+                children: Vec::new(),
+                start: 0,
+                len: 0,
+            }],
+            text: String::new(),
+            last: None,
+        }
+    }
+
+    fn parse(mut self) -> Result<StyledNode, HtmlError> {
+        let mut i = 0;
+        while let Some(c) = self.src[i..].chars().next() {
+            match c {
+                '<' => i = self.tag(i)?,
+                '&' => i = self.entity(i)?,
+                c if c.is_whitespace() => {
+                    if self.last.is_some_and(|last| !last.is_whitespace()) {
+                        self.push(' ');
+                    }
+                    i += c.len_utf8();
+                }
+                c => {
+                    self.push(c);
+                    i += c.len_utf8();
+                }
+            }
+        }
+
+        self.flush_text();
+        if self.stack.len() > 1 {
+            let frame = self.stack.pop().expect("stack has an open tag");
+            return Err(HtmlError::UnclosedTag {
+                src: self.src.to_string(),
+                span: (frame.start, frame.len).into(),
+                tag: frame.tag,
+            });
+        }
+        let root = self.stack.pop().expect("stack has a root frame");
+        Ok(StyledNode::Styled {
+            style: root.style,
+            children: root.children,
+        })
+    }
+
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.last = Some(c);
+    }
+
+    /// Start a new line unless already at the start of one.
+    fn line_break(&mut self) {
+        if self.last.is_some_and(|last| last != '\n') {
+            self.push('\n');
+        }
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            let text = core::mem::take(&mut self.text);
+            self.stack.last_mut().expect("stack has a root frame").children.push(text.into());
+        }
+    }
+
+    fn malformed(&self, start: usize, len: usize) -> HtmlError {
+        HtmlError::Malformed {
+            src: self.src.to_string(),
+            span: (start, len).into(),
+        }
+    }
+
+    /// Handle the tag starting at `start`, returning the index after it.
+    fn tag(&mut self, start: usize) -> Result<usize, HtmlError> {
+        let rest = &self.src[start..];
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").ok_or_else(|| self.malformed(start, rest.len()))?;
+            return Ok(start + end + 3);
+        }
+        let len = rest.find('>').ok_or_else(|| self.malformed(start, rest.len()))? + 1;
+        let inner = rest[1..len - 1].trim();
+        let (closing, inner) = match inner.strip_prefix('/') {
+            Some(inner) => (true, inner.trim_start()),
+            None => (false, inner.strip_suffix('/').unwrap_or(inner).trim_end()),
+        };
+        let name_len = inner.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(inner.len());
+        if name_len == 0 {
+            return Err(self.malformed(start, len));
+        }
+        let name = inner[..name_len].to_ascii_lowercase();
+        let attributes = &inner[name_len..];
+
+        if closing {
+            self.close(name, start, len)?;
+        } else {
+            self.open(name, attributes, start, len)?;
+        }
+        Ok(start + len)
+    }
+
+    fn open(
+        &mut self,
+        name: String,
+        attributes: &str,
+        start: usize,
+        len: usize,
+    ) -> Result<(), HtmlError> {
+        let style = match name.as_str() {
+            "br" => {
+                self.push('\n');
+                return Ok(());
+            }
+            "b" | "strong" => StyleSet::new().with_bold(true),
+            "u" => StyleSet::new().with_underline(true),
+            "center" => {
+                self.line_break();
+                StyleSet::new().with_justification(Justification::Center)
+            }
+            "font" => match attribute(attributes, "size") {
+                Some(size) => {
+                    let size = font_size(size).ok_or_else(|| HtmlError::InvalidFontSize {
+                        src: self.src.to_string(),
+                        span: (start, len).into(),
+                        size: size.to_string(),
+                    })?;
+                    StyleSet::new().with_size(size)
+                }
+                None => StyleSet::new(),
+            },
+            _ => {
+                return Err(HtmlError::UnsupportedTag {
+                    src: self.src.to_string(),
+                    span: (start, len).into(),
+                    tag: name,
+                });
+            }
+        };
+        self.flush_text();
+        self.stack.push(Frame {
+            tag: name,
+            style,
+            children: Vec::new(),
+            start,
+            len,
+        });
+        Ok(())
+    }
+
+    fn close(&mut self, name: String, start: usize, len: usize) -> Result<(), HtmlError> {
+        if self.stack.len() == 1 || self.stack.last().is_some_and(|frame| frame.tag != name) {
+            return Err(HtmlError::UnexpectedClosingTag {
+                src: self.src.to_string(),
+                span: (start, len).into(),
+                tag: name,
+            });
+        }
+        self.flush_text();
+        let frame = self.stack.pop().expect("stack has an open tag");
+        self.stack.last_mut().expect("stack has a root frame").children.push(StyledNode::Styled {
+            style: frame.style,
+            children: frame.children,
+        });
+        if frame.tag == "center" {
+            self.line_break();
+        }
+        Ok(())
+    }
+
+    /// Handle the entity starting at `start`, returning the index after it.
+    fn entity(&mut self, start: usize) -> Result<usize, HtmlError> {
+        let rest = &self.src[start..];
+        // A bare `&`, as in "Fish & Chips", is literal text.
+        let Some(end) = rest.find(|c: char| c == ';' || c.is_whitespace() || c == '<') else {
+            self.push('&');
+            return Ok(start + 1);
+        };
+        if !rest[end..].starts_with(';') {
+            self.push('&');
+            return Ok(start + 1);
+        }
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            name => name
+                .strip_prefix("#x")
+                .or_else(|| name.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| name.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        let c = c.ok_or_else(|| self.malformed(start, end + 1))?;
+        // A non-breaking space is kept as a plain space that never collapses.
+        self.push(if c == '\u{a0}' {
+            ' '
+        } else {
+            c
+        });
+        Ok(start + end + 1)
+    }
+}
+
+/// Value of attribute `name` in a tag's attribute list.
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let key_len = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        rest = rest[key_len..].trim_start();
+        let mut value = "";
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (parsed, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let end = body.find(quote).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = parsed;
+            rest = remaining.trim_start();
+        }
+        if key.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Character size for an HTML `<font size>` value.
+fn font_size(value: &str) -> Option<CharacterSize> {
+    let value = value.trim();
+    let size = if let Some(delta) = value.strip_prefix('+') {
+        3i16.saturating_add(delta.parse().ok()?).clamp(1, 7)
+    } else if value.starts_with('-') {
+        3i16.saturating_add(value.parse().ok()?).clamp(1, 7)
+    } else {
+        value.parse::<i16>().ok().filter(|size| (1..=7).contains(size))?
+    };
+    Some(match size {
+        4 => CharacterSize::double_height(),
+        5 => CharacterSize::double(),
+        6 => CharacterSize::new(ScaleFactor::X3, ScaleFactor::X3),
+        7 => CharacterSize::new(ScaleFactor::X4, ScaleFactor::X4),
+        _ => CharacterSize::standard(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::text::Styleable;
+
+    fn rendered(html: &str) -> Vec<u8> {
+        parse(html).unwrap().render()
+    }
+
+    #[test]
+    fn nested_bold_and_underline() {
+        let expected = "a".bold().append("b".underlined().bold()).render_merged();
+        assert_eq!(parse("<b>a<u>b</u></b>").unwrap().render_merged(), expected);
+    }
+
+    #[test]
+    fn whitespace_collapses_and_br_breaks() {
+        assert_eq!(rendered("  Line   one<br/>Line\n two "), b"Line one\nLine two ");
+    }
+
+    #[test]
+    fn center_is_a_block() {
+        let expected = (StyledNode::text("Shop\n") + "Receipt".centered() + "\nThanks").render();
+        assert_eq!(rendered("Shop<center>Receipt</center>Thanks"), expected);
+    }
+
+    #[test]
+    fn font_sizes() {
+        assert_eq!(rendered("<font size=5>X</font>"), "X".double_size().render());
+        assert_eq!(
+            rendered("<font size=\"+1\">X</font>"),
+            "X".sized(CharacterSize::double_height()).render()
+        );
+        assert_eq!(rendered("<FONT SIZE='2'>X</FONT>"), b"X");
+
+        // Relative sizes clamp to 1..=7 however far out they reach
+        let largest = "X".sized(CharacterSize::new(ScaleFactor::X4, ScaleFactor::X4)).render();
+        assert_eq!(rendered("<font size=\"+125\">X</font>"), largest);
+        assert_eq!(rendered("<font size=\"-128\">X</font>"), b"X");
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(
+            rendered("Fish &amp; Chips &lt;3 &#36;5 &#x41; & more"),
+            b"Fish & Chips <3 $5 A & more"
+        );
+    }
+
+    #[test]
+    fn unsupported_tag_is_rejected() {
+        let err = parse("<i>x</i>").unwrap_err();
+        assert!(matches!(err, HtmlError::UnsupportedTag { ref tag, .. } if tag == "i"));
+    }
+
+    #[test]
+    fn mismatched_and_unclosed_tags() {
+        assert!(matches!(parse("<b>x</u>"), Err(HtmlError::UnexpectedClosingTag { .. })));
+        assert!(matches!(parse("<b>x"), Err(HtmlError::UnclosedTag { .. })));
+        assert!(matches!(parse("a < b"), Err(HtmlError::Malformed { .. })));
+    }
+
+    #[test]
+    fn invalid_font_size() {
+        assert!(matches!(parse("<font size=9>x</font>"), Err(HtmlError::InvalidFontSize { .. })));
+    }
+}
@@ -0,0 +1,778 @@
+//! Partial-line alignment by padding.
+//!
+//! `ESC a` justifies whole lines only. [`LineBuilder`] places left, center
+//! and right segments within a single line by inserting fill characters,
+//! sized from the printer profile.
+//!
+//! ```
+//! use bixolon::profile::PrinterProfile;
+//! use bixolon::style::layout::LineBuilder;
+//! use bixolon::style::text::Styleable;
+//!
+//! let profile = PrinterProfile::SRP_350PLUS;
+//! let line = LineBuilder::new()
+//!     .left("Coffee")
+//!     .right("$9.99".bold())
+//!     .fill('.')
+//!     .build(&profile);
+//! assert_eq!(line.columns(&profile), 42);
+//! ```
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::StyleSet;
+use super::text::StyledNode;
+use crate::command::character::{Font, ScaleFactor};
+use crate::money::{Locale, Money};
+use crate::profile::PrinterProfile;
+
+/// Builder for a line with left, center and right aligned segments.
+///
+/// Widths are measured in Font A cells (see [`StyledNode::columns`]) and
+/// the gaps are filled with 1x Font A characters. Segments using other
+/// fonts may leave the right edge up to one cell short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineBuilder {
+    left: Option<StyledNode>,
+    center: Option<StyledNode>,
+    right: Option<StyledNode>,
+    fill: char,
+    /// Decimal separator and the cells after it, for aligning `right`.
+    decimal_tab: Option<(char, usize)>,
+}
+
+impl Default for LineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineBuilder {
+    /// Create an empty line filled with spaces.
+    pub fn new() -> Self {
+        Self {
+            left: None,
+            center: None,
+            right: None,
+            fill: ' ',
+            decimal_tab: None,
+        }
+    }
+
+    /// Set the segment starting at the left margin.
+    pub fn left(mut self, node: impl Into<StyledNode>) -> Self {
+        self.left = Some(node.into());
+        self
+    }
+
+    /// Set the segment centered on the line.
+    pub fn center(mut self, node: impl Into<StyledNode>) -> Self {
+        self.center = Some(node.into());
+        self
+    }
+
+    /// Set the segment ending at the right margin.
+    pub fn right(mut self, node: impl Into<StyledNode>) -> Self {
+        self.right = Some(node.into());
+        self
+    }
+
+    /// Set the character used to fill gaps, e.g. `'.'` for dot leaders.
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Align the right segment on a decimal tab instead of the margin.
+    ///
+    /// The segment is placed so its last `separator` has `after` cells to
+    /// its right, lining numbers with different tails up on the decimal
+    /// point. A segment without `separator` ends just before the tab.
+    ///
+    /// ```
+    /// use bixolon::profile::PrinterProfile;
+    /// use bixolon::style::layout::LineBuilder;
+    ///
+    /// let profile = PrinterProfile::SRP_350PLUS;
+    /// let line = |right| LineBuilder::new().right(right).align_decimal('.', 4);
+    /// let qty = line("1.5 kg").build_with_width(&profile, 10).render();
+    /// assert_eq!(qty, b"    1.5 kg");
+    /// assert_eq!(line("12").build_with_width(&profile, 10).render(), b"   12");
+    /// ```
+    pub fn align_decimal(mut self, separator: char, after: usize) -> Self {
+        self.decimal_tab = Some((separator, after));
+        self
+    }
+
+    /// Set the right segment to `amount`, aligned on its decimal point.
+    ///
+    /// Amounts in one currency and locale then line up on the decimal
+    /// point whatever their sign or size, and alongside other right
+    /// segments using [`align_decimal`](Self::align_decimal) with the same
+    /// tab.
+    ///
+    /// ```
+    /// use bixolon::money::{Currency, Locale, Money};
+    /// use bixolon::profile::PrinterProfile;
+    /// use bixolon::style::layout::LineBuilder;
+    ///
+    /// let profile = PrinterProfile::SRP_350PLUS;
+    /// let line = LineBuilder::new()
+    ///     .left("Total")
+    ///     .amount(&Money::new(123_456, Currency::EUR), &Locale::DE_DE)
+    ///     .build_with_width(&profile, 20);
+    /// assert_eq!(line.render(), "Total     1.234,56 €".as_bytes());
+    /// ```
+    pub fn amount(mut self, amount: &Money, locale: &Locale) -> Self {
+        let decimals = usize::from(amount.currency.decimals);
+        if decimals > 0 {
+            let suffix = if locale.symbol_after {
+                usize::from(locale.symbol_space) + amount.currency.symbol.chars().count()
+            } else {
+                0
+            };
+            self.decimal_tab = Some((locale.decimal, decimals + suffix));
+        }
+        self.right = Some(StyledNode::Text(amount.format(locale)));
+        self
+    }
+
+    /// Build the padded line for the profile's Font A line width.
+    ///
+    /// Segments that don't fit are separated by a single fill character and
+    /// the printer wraps the overflow. No line feed is appended.
+    pub fn build(&self, profile: &PrinterProfile) -> StyledNode {
+        self.build_with_width(profile, usize::from(profile.font_a_columns))
+    }
+
+    /// Build the padded line for a width of `columns` Font A cells.
+    pub fn build_with_width(&self, profile: &PrinterProfile, columns: usize) -> StyledNode {
+        let mut children = Vec::new();
+        let mut position = 0;
+
+        let segments = [
+            (&self.left, Placement::Left),
+            (&self.center, Placement::Center),
+            (&self.right, Placement::Right),
+        ];
+        for (segment, placement) in segments {
+            let Some(node) = segment else {
+                continue;
+            };
+            let width = node.columns(profile);
+            let start = match placement {
+                Placement::Left => 0,
+                Placement::Center => columns.saturating_sub(width) / 2,
+                Placement::Right => match self.decimal_tab {
+                    Some((separator, after)) => {
+                        let tail =
+                            node.columns_after(profile, separator).map_or(0, |tail| tail + 1);
+                        (columns + tail).saturating_sub(after + 1 + width)
+                    }
+                    None => columns.saturating_sub(width),
+                },
+            };
+            let min_start = if children.is_empty() {
+                position
+            } else {
+                position + 1
+            };
+            let start = start.max(min_start);
+            if start > position {
+                children.push(StyledNode::Text(self.fill.to_string().repeat(start - position)));
+            }
+            children.push(node.clone());
+            position = start + width;
+        }
+
+        StyledNode::Styled {
+            style: Default::default(),
+            children,
+        }
+    }
+}
+
+/// A receipt body row: quantity, description and line total.
+///
+/// Descriptions too long for the space left of the price wrap onto
+/// continuation lines indented under the description, keeping the total's
+/// column clear. When more than one unit was sold, a last continuation line
+/// gives the unit price.
+///
+/// ```
+/// use bixolon::money::{Currency, Locale, Money};
+/// use bixolon::profile::PrinterProfile;
+/// use bixolon::style::layout::ItemLine;
+///
+/// let profile = PrinterProfile::SRP_350PLUS;
+/// let item = ItemLine::new(2, "Grilled cheese sandwich", Money::new(650, Currency::USD));
+/// let lines: Vec<_> = item
+///     .build_with_width(&profile, &Locale::EN_US, 24)
+///     .iter()
+///     .map(|line| String::from_utf8(line.render()).unwrap())
+///     .collect();
+/// assert_eq!(lines, ["2  Grilled cheese $13.00", "   sandwich", "   @ $6.50"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemLine {
+    /// Units sold.
+    pub qty: u32,
+    /// What was sold.
+    pub description: StyledNode,
+    /// Price of one unit.
+    pub unit_price: Money,
+    /// Price of the line, usually `qty` times `unit_price`.
+    pub total: Money,
+}
+
+impl ItemLine {
+    /// `qty` units of `description` at `unit_price` each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the line total overflows; see
+    /// [`checked_new`](Self::checked_new).
+    pub fn new(qty: u32, description: impl Into<StyledNode>, unit_price: Money) -> Self {
+        Self::checked_new(qty, description, unit_price).expect("amount overflowed")
+    }
+
+    /// `qty` units of `description` at `unit_price` each, or `None` if the
+    /// line total overflows.
+    pub fn checked_new(
+        qty: u32,
+        description: impl Into<StyledNode>,
+        unit_price: Money,
+    ) -> Option<Self> {
+        let total = unit_price.checked_mul(i64::from(qty))?;
+        Some(Self {
+            qty,
+            description: description.into(),
+            unit_price,
+            total,
+        })
+    }
+
+    /// Override the line total, e.g. for a line discount.
+    pub fn with_total(mut self, total: Money) -> Self {
+        self.total = total;
+        self
+    }
+
+    /// Build the lines for the profile's Font A line width.
+    pub fn build(&self, profile: &PrinterProfile, locale: &Locale) -> Vec<StyledNode> {
+        self.build_with_width(profile, locale, usize::from(profile.font_a_columns))
+    }
+
+    /// Build the lines for a width of `columns` Font A cells.
+    ///
+    /// Each line is a separate node without a trailing newline.
+    pub fn build_with_width(
+        &self,
+        profile: &PrinterProfile,
+        locale: &Locale,
+        columns: usize,
+    ) -> Vec<StyledNode> {
+        let qty = self.qty.to_string();
+        let indent = (qty.len() + 1).max(3);
+        let total = self.total.format(locale);
+        let description_columns = columns.saturating_sub(indent + total.chars().count() + 1).max(1);
+        let cell = u32::from(profile.char_width_dots(Font::A));
+        let mut description =
+            self.description.wrap(profile, (description_columns as u32) * cell).into_iter();
+
+        let padding = " ".repeat(indent);
+        let first = StyledNode::text(format!("{qty:<indent$}"))
+            .append(description.next().unwrap_or_else(|| StyledNode::text("")));
+        let mut lines = vec![
+            LineBuilder::new()
+                .left(first)
+                .amount(&self.total, locale)
+                .build_with_width(profile, columns),
+        ];
+        lines.extend(description.map(|line| StyledNode::text(padding.clone()).append(line)));
+        if self.qty != 1 {
+            lines.push(StyledNode::text(format!("{padding}@ {}", self.unit_price.format(locale))));
+        }
+        lines
+    }
+}
+
+/// The totals at the foot of a receipt.
+///
+/// Lists the subtotal, then tax and discount lines in the order added, then
+/// the grand total in bold. Amounts line up on the decimal point at the
+/// right margin whatever the paper width. The grand total is the subtotal
+/// plus every line unless set explicitly.
+///
+/// ```
+/// use bixolon::money::{Currency, Locale, Money};
+/// use bixolon::profile::PrinterProfile;
+/// use bixolon::style::layout::TotalsBlock;
+///
+/// let usd = |minor| Money::new(minor, Currency::USD);
+/// let totals = TotalsBlock::new(usd(2_000))
+///     .discount("Member discount", usd(200))
+///     .tax("Sales tax 8%", usd(144));
+/// let lines: Vec<_> = totals
+///     .build_with_width(&PrinterProfile::SRP_350PLUS, &Locale::EN_US, 24)
+///     .iter()
+///     .map(|line| line.columns(&PrinterProfile::SRP_350PLUS))
+///     .collect();
+/// assert_eq!(lines, [24, 24, 24, 24]);
+/// assert_eq!(totals.grand_total(), usd(1_944));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotalsBlock {
+    subtotal: Money,
+    subtotal_label: StyledNode,
+    lines: Vec<(StyledNode, Money)>,
+    total: Option<Money>,
+    total_label: StyledNode,
+    double_width_total: bool,
+}
+
+impl TotalsBlock {
+    /// Totals for a sale of `subtotal` before tax and discounts.
+    pub fn new(subtotal: Money) -> Self {
+        Self {
+            subtotal,
+            subtotal_label: StyledNode::text("Subtotal"),
+            lines: Vec::new(),
+            total: None,
+            total_label: StyledNode::text("TOTAL"),
+            double_width_total: false,
+        }
+    }
+
+    /// Label the subtotal line with something other than "Subtotal".
+    pub fn subtotal_label(mut self, label: impl Into<StyledNode>) -> Self {
+        self.subtotal_label = label.into();
+        self
+    }
+
+    /// Add a line adding `amount` to the total, or subtracting it if
+    /// negative.
+    pub fn line(mut self, label: impl Into<StyledNode>, amount: Money) -> Self {
+        self.lines.push((label.into(), amount));
+        self
+    }
+
+    /// Add a tax line.
+    pub fn tax(self, label: impl Into<StyledNode>, amount: Money) -> Self {
+        self.line(label, amount)
+    }
+
+    /// Add a discount line, printed as a negative amount.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` can't be negated; see
+    /// [`checked_discount`](Self::checked_discount).
+    pub fn discount(self, label: impl Into<StyledNode>, amount: Money) -> Self {
+        self.checked_discount(label, amount).expect("amount overflowed")
+    }
+
+    /// Add a discount line, or return `None` if `amount` can't be negated
+    /// because it is the most negative amount representable.
+    pub fn checked_discount(self, label: impl Into<StyledNode>, amount: Money) -> Option<Self> {
+        Some(self.line(label, amount.checked_neg()?))
+    }
+
+    /// Print `total` as the grand total instead of adding up the lines,
+    /// e.g. after cash rounding.
+    pub fn total(mut self, total: Money) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Label the grand total with something other than "TOTAL".
+    pub fn total_label(mut self, label: impl Into<StyledNode>) -> Self {
+        self.total_label = label.into();
+        self
+    }
+
+    /// Print the grand total line in double-width characters.
+    pub fn double_width_total(mut self, double: bool) -> Self {
+        self.double_width_total = double;
+        self
+    }
+
+    /// The grand total: the explicit total if set, otherwise the subtotal
+    /// plus every line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lines' currencies differ from the subtotal's, or if
+    /// the sum overflows; see [`checked_grand_total`](Self::checked_grand_total).
+    pub fn grand_total(&self) -> Money {
+        self.checked_grand_total().expect("amounts overflowed or mixed currencies")
+    }
+
+    /// The grand total, or `None` if the lines' currencies differ from the
+    /// subtotal's or adding them up overflows.
+    pub fn checked_grand_total(&self) -> Option<Money> {
+        match &self.total {
+            Some(total) => Some(total.clone()),
+            None => self
+                .lines
+                .iter()
+                .try_fold(self.subtotal.clone(), |sum, (_, amount)| sum.checked_add(amount)),
+        }
+    }
+
+    /// Build the lines for the profile's Font A line width.
+    pub fn build(&self, profile: &PrinterProfile, locale: &Locale) -> Vec<StyledNode> {
+        self.build_with_width(profile, locale, usize::from(profile.font_a_columns))
+    }
+
+    /// Build the lines for a width of `columns` Font A cells.
+    ///
+    /// Each line is a separate node without a trailing newline.
+    pub fn build_with_width(
+        &self,
+        profile: &PrinterProfile,
+        locale: &Locale,
+        columns: usize,
+    ) -> Vec<StyledNode> {
+        let line = |label: &StyledNode, amount: &Money, columns| {
+            LineBuilder::new()
+                .left(label.clone())
+                .amount(amount, locale)
+                .build_with_width(profile, columns)
+        };
+        let mut lines = vec![line(&self.subtotal_label, &self.subtotal, columns)];
+        lines.extend(self.lines.iter().map(|(label, amount)| line(label, amount, columns)));
+
+        // Lay a double-width total out in half the cells, then widen it,
+        // padding an odd width so the amount still ends at the margin
+        let style = StyleSet::new().with_bold(true);
+        let total = if self.double_width_total {
+            let total = line(&self.total_label, &self.grand_total(), columns / 2)
+                .with_style(style.with_width_scale(ScaleFactor::X2));
+            match columns % 2 {
+                0 => total,
+                _ => StyledNode::text(" ").append(total),
+            }
+        } else {
+            line(&self.total_label, &self.grand_total(), columns).with_style(style)
+        };
+        lines.push(total);
+        lines
+    }
+}
+
+/// Fill-in lines for a card payment receipt: tip and total boxes and a
+/// signature line.
+///
+/// The tip and total lines end in a blank at least as wide as the printed
+/// amount, for the customer to write in. The signature line is an `X`
+/// followed by underscores across the full width, below enough blank lines
+/// to sign in.
+///
+/// ```
+/// use bixolon::money::{Currency, Locale, Money};
+/// use bixolon::profile::PrinterProfile;
+/// use bixolon::style::layout::SignatureBlock;
+///
+/// let profile = PrinterProfile::SRP_350PLUS;
+/// let block = SignatureBlock::new()
+///     .amount(Money::new(2_450, Currency::USD))
+///     .tip_line()
+///     .total_line()
+///     .caption("Cardholder signature");
+/// let lines: Vec<_> = block
+///     .build_with_width(&profile, &Locale::EN_US, 24)
+///     .iter()
+///     .map(|line| String::from_utf8(line.render()).unwrap())
+///     .collect();
+/// assert_eq!(
+///     lines,
+///     [
+///         "Amount            $24.50",
+///         "Tip           __________",
+///         "Total         __________",
+///         "",
+///         "",
+///         "X_______________________",
+///         "  Cardholder signature",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureBlock {
+    amount: Option<(StyledNode, Money)>,
+    tip: Option<StyledNode>,
+    total: Option<StyledNode>,
+    signing_lines: usize,
+    caption: Option<StyledNode>,
+}
+
+impl Default for SignatureBlock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignatureBlock {
+    /// Blank fill-ins are at least this many cells wide.
+    const MIN_BLANK: usize = 10;
+
+    /// A signature line with two blank lines above it to sign in.
+    pub fn new() -> Self {
+        Self {
+            amount: None,
+            tip: None,
+            total: None,
+            signing_lines: 2,
+            caption: None,
+        }
+    }
+
+    /// Print the amount authorized above the fill-ins, labelled "Amount".
+    pub fn amount(self, amount: Money) -> Self {
+        self.labelled_amount("Amount", amount)
+    }
+
+    /// Print the amount authorized with a label other than "Amount".
+    pub fn labelled_amount(mut self, label: impl Into<StyledNode>, amount: Money) -> Self {
+        self.amount = Some((label.into(), amount));
+        self
+    }
+
+    /// Add a "Tip" fill-in line.
+    pub fn tip_line(self) -> Self {
+        self.labelled_tip_line("Tip")
+    }
+
+    /// Add a tip fill-in line with a label other than "Tip".
+    pub fn labelled_tip_line(mut self, label: impl Into<StyledNode>) -> Self {
+        self.tip = Some(label.into());
+        self
+    }
+
+    /// Add a "Total" fill-in line.
+    pub fn total_line(self) -> Self {
+        self.labelled_total_line("Total")
+    }
+
+    /// Add a total fill-in line with a label other than "Total".
+    pub fn labelled_total_line(mut self, label: impl Into<StyledNode>) -> Self {
+        self.total = Some(label.into());
+        self
+    }
+
+    /// Leave `lines` blank lines above the signature line instead of 2.
+    pub fn signing_lines(mut self, lines: usize) -> Self {
+        self.signing_lines = lines;
+        self
+    }
+
+    /// Print `caption` centered under the signature line, e.g.
+    /// "Cardholder signature".
+    pub fn caption(mut self, caption: impl Into<StyledNode>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Build the lines for the profile's Font A line width.
+    pub fn build(&self, profile: &PrinterProfile, locale: &Locale) -> Vec<StyledNode> {
+        self.build_with_width(profile, locale, usize::from(profile.font_a_columns))
+    }
+
+    /// Build the lines for a width of `columns` Font A cells.
+    ///
+    /// Each line is a separate node without a trailing newline; blank lines
+    /// are empty nodes.
+    pub fn build_with_width(
+        &self,
+        profile: &PrinterProfile,
+        locale: &Locale,
+        columns: usize,
+    ) -> Vec<StyledNode> {
+        let mut lines = Vec::new();
+        let mut blank = Self::MIN_BLANK;
+        if let Some((label, amount)) = &self.amount {
+            let line = LineBuilder::new().left(label.clone()).amount(amount, locale);
+            blank = blank.max(amount.format(locale).chars().count());
+            lines.push(line.build_with_width(profile, columns));
+        }
+        let blank = StyledNode::Text("_".repeat(blank.min(columns / 2)));
+        for label in [&self.tip, &self.total].into_iter().flatten() {
+            let line = LineBuilder::new().left(label.clone()).right(blank.clone());
+            lines.push(line.build_with_width(profile, columns));
+        }
+
+        lines.extend((0..self.signing_lines).map(|_| StyledNode::text("")));
+        lines.push(StyledNode::Text(format!("X{}", "_".repeat(columns.saturating_sub(1)))));
+        if let Some(caption) = &self.caption {
+            lines.push(
+                LineBuilder::new().center(caption.clone()).build_with_width(profile, columns),
+            );
+        }
+        lines
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Placement {
+    Left,
+    Center,
+    Right,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+    use crate::style::text::Styleable;
+
+    fn plain(line: &StyledNode) -> String {
+        String::from_utf8(line.render()).unwrap()
+    }
+
+    #[test]
+    fn left_and_right_fill_the_line() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line =
+            LineBuilder::new().left("Item").right("$9.99").fill('.').build_with_width(&profile, 16);
+        assert_eq!(plain(&line), "Item.......$9.99");
+    }
+
+    #[test]
+    fn center_only_is_padded_on_the_left() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line = LineBuilder::new().center("Menu").build_with_width(&profile, 10);
+        assert_eq!(plain(&line), "   Menu");
+    }
+
+    #[test]
+    fn three_segments() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line =
+            LineBuilder::new().left("A").center("B").right("C").build_with_width(&profile, 9);
+        assert_eq!(plain(&line), "A   B   C");
+    }
+
+    #[test]
+    fn overlong_segments_keep_one_separator() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line =
+            LineBuilder::new().left("Long item name").right("$1").build_with_width(&profile, 10);
+        assert_eq!(plain(&line), "Long item name $1");
+    }
+
+    #[test]
+    fn accented_and_wide_text_pad_by_cells() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line =
+            LineBuilder::new().left("Crème brûlée").right("$7").build_with_width(&profile, 16);
+        assert_eq!(plain(&line), "Crème brûlée  $7");
+        let line = LineBuilder::new().left("寿司").right("$9").build_with_width(&profile, 8);
+        assert_eq!(plain(&line), "寿司  $9");
+    }
+
+    #[test]
+    fn amounts_align_on_the_decimal_point() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line = |builder: LineBuilder| plain(&builder.build_with_width(&profile, 16));
+        let usd = |minor| Money::new(minor, Currency::USD);
+        assert_eq!(
+            line(LineBuilder::new().left("Tea").amount(&usd(350), &Locale::EN_US)),
+            "Tea        $3.50"
+        );
+        assert_eq!(
+            line(LineBuilder::new().left("Off").amount(&usd(-50), &Locale::EN_US)),
+            "Off       -$0.50"
+        );
+        // A tax flag after the amount hangs past the column
+        let flagged = LineBuilder::new().left("Cake").right("4.00 T").align_decimal('.', 2);
+        assert_eq!(line(flagged), "Cake        4.00 T");
+        let yen = LineBuilder::new().amount(&Money::new(500, Currency::JPY), &Locale::EN_US);
+        assert_eq!(line(yen), "            ¥500");
+    }
+
+    #[test]
+    fn item_lines_keep_the_price_column_clear() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let lines = |item: ItemLine, locale| -> Vec<String> {
+            item.build_with_width(&profile, &locale, 20).iter().map(plain).collect()
+        };
+        let eur = |minor| Money::new(minor, Currency::EUR);
+
+        let item = ItemLine::new(1, "Espresso", eur(250));
+        assert_eq!(lines(item, Locale::DE_DE), ["1  Espresso   2,50 €"]);
+
+        let item = ItemLine::new(12, "Cinnamon raisin bagel", eur(1_75)).with_total(eur(1_800));
+        assert_eq!(
+            lines(item, Locale::DE_DE),
+            ["12 Cinnamon  18,00 €", "   raisin", "   bagel", "   @ 1,75 €"]
+        );
+    }
+
+    #[test]
+    fn item_line_wraps_long_words() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let item = ItemLine::new(1, "Supercalifragilistic", Money::new(100, Currency::USD));
+        let lines: Vec<_> =
+            item.build_with_width(&profile, &Locale::EN_US, 16).iter().map(plain).collect();
+        assert_eq!(lines, ["1  Superca $1.00", "   lifragi", "   listic"]);
+    }
+
+    #[test]
+    fn totals_right_align_at_any_width() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let usd = |minor| Money::new(minor, Currency::USD);
+        let totals = TotalsBlock::new(usd(1_000)).tax("Tax", usd(80)).double_width_total(true);
+        for columns in [32, 41, 42, 48] {
+            let lines = totals.build_with_width(&profile, &Locale::EN_US, columns);
+            assert_eq!(plain(&lines[1]), format!("Tax{}$0.80", " ".repeat(columns - 8)));
+            assert_eq!(lines[2].columns(&profile), columns);
+        }
+
+        let total = totals.build_with_width(&profile, &Locale::EN_US, 24).pop().unwrap();
+        assert_eq!(total.render(), b"\x1bE\x01\x1d!\x10TOTAL $10.80\x1bE\x00\x1d!\x00");
+    }
+
+    #[test]
+    fn unnegatable_discount_is_rejected() {
+        let usd = |minor| Money::new(minor, Currency::USD);
+        let totals = TotalsBlock::new(usd(1_000));
+        assert!(totals.clone().checked_discount("Bad", usd(i64::MIN)).is_none());
+        let totals = totals.checked_discount("Coupon", usd(100)).unwrap();
+        assert_eq!(totals.grand_total(), usd(900));
+    }
+
+    #[test]
+    fn explicit_total_overrides_the_sum() {
+        let chf = |minor| Money::new(minor, Currency::CHF);
+        let totals = TotalsBlock::new(chf(1_003)).total(chf(1_005)).total_label("Rounded");
+        assert_eq!(totals.grand_total(), chf(1_005));
+        let lines = totals.build_with_width(&PrinterProfile::SRP_350PLUS, &Locale::DE_CH, 20);
+        assert!(plain(&lines[1]).contains("Rounded    CHF 10.05"));
+    }
+
+    #[test]
+    fn signature_blanks_fit_the_amount_and_paper() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let lines =
+            SignatureBlock::new().signing_lines(1).build_with_width(&profile, &Locale::EN_US, 8);
+        assert_eq!(lines.iter().map(plain).collect::<Vec<_>>(), ["", "X_______"]);
+
+        let large = SignatureBlock::new().amount(Money::new(123_456_789, Currency::USD)).tip_line();
+        let lines = large.build(&profile, &Locale::EN_US);
+        assert_eq!(plain(&lines[1]), format!("Tip{}{}", " ".repeat(26), "_".repeat(13)));
+    }
+
+    #[test]
+    fn scaled_segments_use_printed_width() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let line = LineBuilder::new()
+            .left("Total".double_width())
+            .right("$5".font(Font::B))
+            .build(&profile);
+        assert_eq!(line.columns(&profile), 42);
+    }
+}
@@ -0,0 +1,491 @@
+//! ANSI terminal preview of printer output.
+//!
+//! Approximates a receipt in a terminal so layouts can be checked without
+//! printing. Bold, double-strike and double-height text show as bold,
+//! underline and reverse map to their ANSI equivalents, red prints red,
+//! wider characters are padded with spaces, and dashes overprinted with
+//! `ESC \\` (as emitted for struck text) show as strikethrough. Justification and line
+//! wrapping use the profile's Font A line width.
+//!
+//! Barcodes, 2D symbols and images are shown as bracketed placeholders.
+//!
+//! ```
+//! use bixolon::profile::PrinterProfile;
+//! use bixolon::style::preview;
+//! use bixolon::style::text::Styleable;
+//!
+//! let profile = PrinterProfile::SRP_350PLUS;
+//! let text = preview::ansi(&"Total".bold(), &profile);
+//! assert_eq!(text, "\x1b[0;1mTotal\x1b[0m\n");
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::text::StyledNode;
+use crate::command::character::{Font, Justification};
+use crate::command::codepage::CodePage;
+use crate::command::{CR, DLE, ESC, FS, GS, HT, LF};
+use crate::profile::PrinterProfile;
+
+/// Preview a styled node.
+pub fn ansi(node: &StyledNode, profile: &PrinterProfile) -> String {
+    ansi_job(&node.render(), profile)
+}
+
+/// Preview a raw ESC/POS byte stream, such as a captured print job.
+///
+/// Text without an explicit `ESC t` code page is read as UTF-8, which is
+/// how [`StyledNode`] sends it, falling back to CP437 for invalid
+/// sequences. Unrecognized commands are skipped.
+pub fn ansi_job(bytes: &[u8], profile: &PrinterProfile) -> String {
+    let mut preview =
+        Preview::new(usize::from(profile.font_a_columns), profile.char_width_dots(Font::A));
+    preview.feed(bytes);
+    preview.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Attributes {
+    bold: bool,
+    double_strike: bool,
+    underline: bool,
+    reverse: bool,
+    red: bool,
+    struck: bool,
+    width: u8,
+    height: u8,
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            double_strike: false,
+            underline: false,
+            reverse: false,
+            red: false,
+            struck: false,
+            width: 1,
+            height: 1,
+        }
+    }
+}
+
+impl Attributes {
+    fn sgr(&self) -> String {
+        let mut codes = String::from("\x1b[0");
+        if self.bold || self.double_strike || self.height > 1 {
+            codes.push_str(";1");
+        }
+        if self.underline {
+            codes.push_str(";4");
+        }
+        if self.reverse {
+            codes.push_str(";7");
+        }
+        if self.struck {
+            codes.push_str(";9");
+        }
+        if self.red {
+            codes.push_str(";31");
+        }
+        codes.push('m');
+        codes
+    }
+}
+
+struct Preview {
+    columns: usize,
+    cell_dots: u16,
+    /// Index into `line` being overprinted after a backwards `ESC \\`.
+    overprint: Option<usize>,
+    attributes: Attributes,
+    justification: Justification,
+    code_page: Option<CodePage>,
+    line: Vec<(Attributes, char)>,
+    line_width: usize,
+    line_justification: Justification,
+    output: String,
+}
+
+impl Preview {
+    fn new(columns: usize, cell_dots: u16) -> Self {
+        Self {
+            columns,
+            cell_dots,
+            overprint: None,
+            attributes: Attributes::default(),
+            justification: Justification::Left,
+            code_page: None,
+            line: Vec::new(),
+            line_width: 0,
+            line_justification: Justification::Left,
+            output: String::new(),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.line.is_empty() {
+            self.flush_line();
+        }
+        self.output
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            i += 1;
+            match byte {
+                ESC => i += self.escape(&bytes[i..]),
+                GS => i += self.group_separator(&bytes[i..]),
+                FS => i += self.file_separator(&bytes[i..]),
+                DLE => i += 2.min(bytes.len() - i),
+                LF => self.flush_line(),
+                HT => {
+                    let stop = (self.line_width / 8 + 1) * 8;
+                    while self.line_width < stop.min(self.columns) {
+                        self.push_char(' ');
+                    }
+                }
+                CR => {}
+                _ if byte < 0x20 || byte == 0x7F => {}
+                _ => {
+                    let end = bytes[i..]
+                        .iter()
+                        .position(|&b| b < 0x20 || b == 0x7F)
+                        .map_or(bytes.len(), |n| i + n);
+                    self.text(&bytes[i - 1..end]);
+                    i = end;
+                }
+            }
+        }
+    }
+
+    /// Handle `ESC x ...`, returning the number of bytes consumed.
+    fn escape(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let arg = rest.get(1).copied().unwrap_or(0);
+        let consumed = match command {
+            b'@' => {
+                self.attributes = Attributes::default();
+                self.justification = Justification::Left;
+                self.code_page = None;
+                1
+            }
+            b'E' => {
+                self.attributes.bold = arg & 1 == 1;
+                2
+            }
+            b'G' => {
+                self.attributes.double_strike = arg & 1 == 1;
+                2
+            }
+            b'-' => {
+                self.attributes.underline = !matches!(arg, 0 | b'0');
+                2
+            }
+            b'!' => {
+                self.attributes.bold = arg & 0x08 != 0;
+                self.attributes.height = if arg & 0x10 != 0 {
+                    2
+                } else {
+                    1
+                };
+                self.attributes.width = if arg & 0x20 != 0 {
+                    2
+                } else {
+                    1
+                };
+                self.attributes.underline = arg & 0x80 != 0;
+                2
+            }
+            b'a' => {
+                self.justification = match arg {
+                    1 | b'1' => Justification::Center,
+                    2 | b'2' => Justification::Right,
+                    _ => Justification::Left,
+                };
+                2
+            }
+            b'r' => {
+                self.attributes.red = arg & 1 == 1;
+                2
+            }
+            b't' => {
+                self.code_page = Some(CodePage::from_byte(arg).unwrap_or_default());
+                2
+            }
+            b'd' => {
+                for _ in 0..arg {
+                    self.flush_line();
+                }
+                2
+            }
+            b'*' => {
+                let count = usize::from(arg_u16(rest, 2));
+                let bytes_per_column = if arg >= 32 {
+                    3
+                } else {
+                    1
+                };
+                self.placeholder("image");
+                4 + count * bytes_per_column
+            }
+            b'D' => rest.iter().position(|&b| b == 0).map_or(rest.len(), |n| n + 1),
+            b'\\' => {
+                let offset = arg_u16(rest, 1) as i16;
+                if offset < 0 {
+                    self.move_back(offset.unsigned_abs());
+                }
+                3
+            }
+            b'p' => 4,
+            b'$' | b'c' => 3,
+            b'W' => 9,
+            b'2' | b'<' | b'L' | b'S' | b'i' | b'm' => 1,
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    /// Handle `GS x ...`, returning the number of bytes consumed.
+    fn group_separator(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let arg = rest.get(1).copied().unwrap_or(0);
+        let consumed = match command {
+            b'!' => {
+                self.attributes.width = (arg >> 4) + 1;
+                self.attributes.height = (arg & 0x0F) + 1;
+                2
+            }
+            b'B' => {
+                self.attributes.reverse = arg & 1 == 1;
+                2
+            }
+            b'V' => {
+                self.placeholder("cut");
+                if arg >= 65 {
+                    3
+                } else {
+                    2
+                }
+            }
+            b'k' => {
+                let (data, consumed) = if arg <= 6 {
+                    let data = &rest[2.min(rest.len())..];
+                    let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                    (&data[..len], 2 + len + 1)
+                } else {
+                    let len = usize::from(rest.get(2).copied().unwrap_or(0));
+                    let data = &rest[3.min(rest.len())..(3 + len).min(rest.len())];
+                    (data, 3 + len)
+                };
+                self.placeholder(&format!("barcode {}", String::from_utf8_lossy(data)));
+                consumed
+            }
+            b'(' => {
+                let len = usize::from(arg_u16(rest, 2));
+                if arg == b'k' && rest.get(5) == Some(&b'Q') {
+                    self.placeholder("2D symbol");
+                }
+                4 + len
+            }
+            b'v' => {
+                let width = usize::from(arg_u16(rest, 3));
+                let height = usize::from(arg_u16(rest, 5));
+                self.placeholder("image");
+                7 + width * height
+            }
+            b'*' => {
+                let size = usize::from(arg) * usize::from(rest.get(2).copied().unwrap_or(0)) * 8;
+                3 + size
+            }
+            b'/' => {
+                self.placeholder("image");
+                2
+            }
+            b'^' => 4,
+            b'L' | b'W' | b'$' | b'\\' | b'P' => 3,
+            b':' => 1,
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    /// Handle `FS x ...`, returning the number of bytes consumed.
+    fn file_separator(&mut self, rest: &[u8]) -> usize {
+        let Some(&command) = rest.first() else {
+            return 0;
+        };
+        let consumed = match command {
+            b'&' | b'.' => 1,
+            b'S' => 3,
+            b'p' => {
+                self.placeholder("NV image");
+                3
+            }
+            _ => 2,
+        };
+        consumed.min(rest.len())
+    }
+
+    fn text(&mut self, bytes: &[u8]) {
+        let decoded: Vec<char> = match (self.code_page, core::str::from_utf8(bytes)) {
+            (None, Ok(text)) => text.chars().collect(),
+            (page, _) => {
+                let page = page.unwrap_or_default();
+                bytes.iter().map(|&b| page.decode_byte(b).unwrap_or('?')).collect()
+            }
+        };
+        for c in decoded {
+            self.push_char(c);
+        }
+    }
+
+    fn move_back(&mut self, dots: u16) {
+        let mut cells = usize::from(dots / self.cell_dots.max(1));
+        let mut index = self.overprint.unwrap_or(self.line.len());
+        while cells > 0 && index > 0 {
+            index -= 1;
+            cells = cells.saturating_sub(usize::from(self.line[index].0.width));
+        }
+        self.overprint = Some(index);
+    }
+
+    fn push_char(&mut self, c: char) {
+        if let Some(index) = self.overprint {
+            if index < self.line.len() {
+                let (attributes, existing) = &mut self.line[index];
+                if c == '-' {
+                    attributes.struck = true;
+                } else if c != ' ' {
+                    *existing = c;
+                }
+                self.overprint = Some(index + 1);
+                return;
+            }
+            self.overprint = None;
+        }
+        let width = usize::from(self.attributes.width);
+        if self.line_width > 0 && self.line_width + width > self.columns {
+            self.flush_line();
+        }
+        if self.line.is_empty() {
+            self.line_justification = self.justification;
+        }
+        self.line.push((self.attributes, c));
+        self.line_width += width;
+    }
+
+    fn placeholder(&mut self, label: &str) {
+        if !self.line.is_empty() {
+            self.flush_line();
+        }
+        self.output.push('[');
+        self.output.push_str(label);
+        self.output.push_str("]\n");
+    }
+
+    fn flush_line(&mut self) {
+        self.overprint = None;
+        let padding = match self.line_justification {
+            Justification::Left => 0,
+            Justification::Center => self.columns.saturating_sub(self.line_width) / 2,
+            Justification::Right => self.columns.saturating_sub(self.line_width),
+        };
+        self.output.extend(core::iter::repeat_n(' ', padding));
+
+        let mut current = Attributes::default();
+        for (attributes, c) in self.line.drain(..) {
+            if attributes != current {
+                self.output.push_str(&attributes.sgr());
+                current = attributes;
+            }
+            self.output.push(c);
+            for _ in 1..attributes.width {
+                self.output.push(' ');
+            }
+        }
+        if current != Attributes::default() {
+            self.output.push_str("\x1b[0m");
+        }
+        self.output.push('\n');
+        self.line_width = 0;
+    }
+}
+
+/// Little-endian `u16` at `offset`, or 0 if the stream is truncated.
+fn arg_u16(rest: &[u8], offset: usize) -> u16 {
+    match (rest.get(offset), rest.get(offset + 1)) {
+        (Some(&low), Some(&high)) => u16::from_le_bytes([low, high]),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::text::Styleable;
+
+    fn preview(node: &StyledNode) -> String {
+        ansi(node, &PrinterProfile::SRP_350PLUS)
+    }
+
+    #[test]
+    fn plain_text_has_no_escapes() {
+        assert_eq!(preview(&StyledNode::text("Hello\nWorld")), "Hello\nWorld\n");
+    }
+
+    #[test]
+    fn styles_map_to_sgr() {
+        let node = "a".underlined() + "b".reversed();
+        assert_eq!(preview(&node), "\x1b[0;4ma\x1b[0;7mb\x1b[0m\n");
+    }
+
+    #[test]
+    fn double_width_pads_characters() {
+        assert_eq!(preview(&"ab".double_size()), "\x1b[0;1ma b \x1b[0m\n");
+    }
+
+    #[test]
+    fn centered_line_is_padded() {
+        let line = preview(&"Hi".centered());
+        assert_eq!(line, format!("{}Hi\n", " ".repeat(20)));
+    }
+
+    #[test]
+    fn long_lines_wrap_at_profile_width() {
+        let text = "x".repeat(50);
+        let output = preview(&StyledNode::text(text));
+        assert_eq!(output, format!("{}\n{}\n", "x".repeat(42), "x".repeat(8)));
+    }
+
+    #[test]
+    fn code_page_text_is_decoded() {
+        let node = "Чай".code_page(CodePage::Windows1251Cyrillic);
+        assert_eq!(preview(&node), "Чай\n");
+    }
+
+    #[test]
+    fn barcode_placeholder() {
+        use crate::command::Command;
+        use crate::command::barcode::{BarcodeSystem, PrintBarcode};
+
+        let barcode = PrintBarcode::new(BarcodeSystem::Code39, "ABC").unwrap();
+        let output = ansi_job(&barcode.encode(), &PrinterProfile::SRP_350PLUS);
+        assert_eq!(output, "[barcode ABC]\n");
+    }
+
+    #[test]
+    fn struck_text_uses_strikethrough() {
+        let node = "Fries".struck() + " 2.50";
+        assert_eq!(preview(&node), "\x1b[0;9mFries\x1b[0m 2.50\n");
+    }
+}
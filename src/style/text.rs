@@ -14,15 +14,37 @@
 //! // Nested styling
 //! let node = "Bold and ".bold().append("underlined".underlined());
 //!
+//! // Concatenation
+//! let node = "Total: ".bold() + "25.00" + "\n";
+//!
 //! // Complex nesting preserves all styles
 //! let node = "outer".bold().append(
 //!     "inner".underlined().append("deep".reversed())
 //! );
 //! ```
 
-use super::{StyleSet, style_transition_commands};
-use crate::command::Command;
-use crate::command::basic::LineFeed;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Add, AddAssign};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{StyleSet, write_style_transition};
+use crate::command::LF;
+use crate::command::character::{
+    CharacterSize, Font, Justification, PrintColor, ScaleFactor, SetJustification,
+};
+use crate::command::codepage::CodePage;
+use crate::command::spacing::SetRelativePosition;
+use crate::encoding::{self, Fallback};
+use crate::error::EncodingError;
+use crate::profile::PrinterProfile;
 
 /// A node in the styled text AST.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -71,83 +93,711 @@ impl StyledNode {
         }
     }
 
+    /// Rewrite characters missing from each text's code page.
+    ///
+    /// Text without an explicit code page is checked against the printer's
+    /// default, CP437. See [`Fallback`] for the policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EncodingError`] only for [`Fallback::Error`].
+    pub fn apply_fallback(self, fallback: Fallback) -> Result<Self, EncodingError> {
+        self.apply_fallback_in(CodePage::default(), fallback)
+    }
+
+    pub(crate) fn apply_fallback_in(
+        self,
+        code_page: CodePage,
+        fallback: Fallback,
+    ) -> Result<Self, EncodingError> {
+        match self {
+            StyledNode::Text(text) => match encoding::apply_fallback(&text, code_page, fallback)? {
+                Cow::Owned(replaced) => Ok(StyledNode::Text(replaced)),
+                Cow::Borrowed(_) => Ok(StyledNode::Text(text)),
+            },
+            StyledNode::Styled {
+                style,
+                children,
+            } => {
+                let code_page = style.code_page.unwrap_or(code_page);
+                let children = children
+                    .into_iter()
+                    .map(|child| child.apply_fallback_in(code_page, fallback))
+                    .collect::<Result<_, _>>()?;
+                Ok(StyledNode::Styled {
+                    style,
+                    children,
+                })
+            }
+        }
+    }
+
+    /// Whether any part of this node selects a non-default print color.
+    pub fn uses_color(&self) -> bool {
+        match self {
+            StyledNode::Text(_) => false,
+            StyledNode::Styled {
+                style,
+                children,
+            } => {
+                style.color.is_some_and(|c| c != PrintColor::Black)
+                    || children.iter().any(StyledNode::uses_color)
+            }
+        }
+    }
+
+    /// Printed width of the widest line, in dots.
+    ///
+    /// Each character occupies one cell of its font, scaled by its width
+    /// scale. Wide (CJK) characters take two cells, and combining marks
+    /// none.
+    pub fn width_dots(&self, profile: &PrinterProfile) -> u32 {
+        let mut line = 0;
+        let mut widest = 0;
+        self.measure(profile, &mut vec![StyleSet::default()], &mut line, &mut widest);
+        widest.max(line)
+    }
+
+    /// Printed width of the widest line, in Font A character cells.
+    ///
+    /// Text in other fonts or sizes is converted to the Font A cells it
+    /// covers, rounding up, so the result can be compared directly against
+    /// [`PrinterProfile::font_a_columns`] when padding or right-aligning.
+    ///
+    /// ```
+    /// use bixolon::profile::PrinterProfile;
+    /// use bixolon::style::text::Styleable;
+    ///
+    /// let profile = PrinterProfile::SRP_350PLUS;
+    /// assert_eq!("Total".double_width().columns(&profile), 10);
+    /// ```
+    pub fn columns(&self, profile: &PrinterProfile) -> usize {
+        let cell = u32::from(profile.char_width_dots(Font::A));
+        self.width_dots(profile).div_ceil(cell) as usize
+    }
+
+    /// Printed width of the text after the last `separator`, in Font A
+    /// cells rounded up, or `None` if the text has no `separator`.
+    pub(crate) fn columns_after(&self, profile: &PrinterProfile, separator: char) -> Option<usize> {
+        let mut runs = Vec::new();
+        self.flatten(&mut vec![StyleSet::default()], &mut runs);
+        let (start, offset) =
+            runs.iter().enumerate().rev().find_map(|(index, (_, text))| {
+                text.rfind(separator).map(|offset| (index, offset))
+            })?;
+
+        let mut dots = 0;
+        for (index, (style, text)) in runs.iter().enumerate().skip(start) {
+            let tail = if index == start {
+                &text[offset + separator.len_utf8()..]
+            } else {
+                text
+            };
+            let width = u32::from(profile.char_width_dots(style.font.unwrap_or_default()))
+                * u32::from(style.size().width.multiplier());
+            dots += graphemes(tail).map(|(_, count)| count * width).sum::<u32>();
+        }
+        let cell = u32::from(profile.char_width_dots(Font::A));
+        Some(dots.div_ceil(cell) as usize)
+    }
+
+    /// Printed height of the tallest character, in dots.
+    ///
+    /// Each character is one cell of its font tall, scaled by its height
+    /// scale. Empty text measures 0.
+    pub fn height_dots(&self, profile: &PrinterProfile) -> u32 {
+        let mut runs = Vec::new();
+        self.flatten(&mut vec![StyleSet::default()], &mut runs);
+        runs.iter()
+            .filter(|(_, text)| text.chars().any(|c| !c.is_control()))
+            .map(|(style, _)| {
+                u32::from(profile.char_height_dots(style.font.unwrap_or_default()))
+                    * u32::from(style.size().height.multiplier())
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Split into lines no wider than `width_dots`, breaking at spaces.
+    ///
+    /// Newlines always break. Words wider than a whole line are split
+    /// between grapheme clusters, so accents stay with their letters, and
+    /// spaces at a break are dropped. Each line is a separate node without a
+    /// trailing newline, keeping the styles of its text.
+    ///
+    /// ```
+    /// use bixolon::profile::PrinterProfile;
+    /// use bixolon::style::text::StyledNode;
+    ///
+    /// let profile = PrinterProfile::SRP_350PLUS;
+    /// // Font A cells are 12 dots wide, so 120 dots fit 10 characters
+    /// let lines = StyledNode::text("Grilled cheese sandwich").wrap(&profile, 120);
+    /// let lines: Vec<_> = lines.iter().map(|line| line.render()).collect();
+    /// assert_eq!(lines, [&b"Grilled"[..], b"cheese", b"sandwich"]);
+    /// ```
+    pub fn wrap(&self, profile: &PrinterProfile, width_dots: u32) -> Vec<StyledNode> {
+        let mut runs = Vec::new();
+        self.flatten(&mut vec![StyleSet::default()], &mut runs);
+
+        let mut cells = Vec::new();
+        for (index, (style, text)) in runs.iter().enumerate() {
+            let width = u32::from(profile.char_width_dots(style.font.unwrap_or_default()))
+                * u32::from(style.size().width.multiplier());
+            cells.extend(graphemes(text).map(|(g, count)| (index, g, count * width)));
+        }
+
+        let mut lines = Vec::new();
+        let mut line: Vec<(usize, &str)> = Vec::new();
+        let mut line_width = 0;
+        let mut spaces: Vec<(usize, &str)> = Vec::new();
+        let mut spaces_width = 0;
+        let mut i = 0;
+        while i < cells.len() {
+            let (_, g, _) = cells[i];
+            let end = match g {
+                "\n" | "\r\n" => i + 1,
+                " " => i + cells[i..].iter().take_while(|(_, g, _)| *g == " ").count(),
+                _ => {
+                    i + cells[i..]
+                        .iter()
+                        .take_while(|(_, g, _)| !matches!(*g, " " | "\n" | "\r\n"))
+                        .count()
+                }
+            };
+            let token = &cells[i..end];
+            i = end;
+
+            match g {
+                "\n" | "\r\n" => {
+                    lines.push(core::mem::take(&mut line));
+                    line_width = 0;
+                    spaces.clear();
+                    spaces_width = 0;
+                }
+                " " => {
+                    if !line.is_empty() {
+                        spaces.extend(token.iter().map(|&(index, g, _)| (index, g)));
+                        spaces_width += token.iter().map(|(_, _, width)| width).sum::<u32>();
+                    }
+                }
+                _ => {
+                    let word_width: u32 = token.iter().map(|(_, _, width)| width).sum();
+                    if !line.is_empty() && line_width + spaces_width + word_width > width_dots {
+                        lines.push(core::mem::take(&mut line));
+                        line_width = 0;
+                    } else {
+                        line.append(&mut spaces);
+                        line_width += spaces_width;
+                    }
+                    spaces.clear();
+                    spaces_width = 0;
+
+                    for &(index, g, width) in token {
+                        if !line.is_empty() && line_width + width > width_dots {
+                            lines.push(core::mem::take(&mut line));
+                            line_width = 0;
+                        }
+                        line.push((index, g));
+                        line_width += width;
+                    }
+                }
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+
+        lines
+            .into_iter()
+            .map(|line| {
+                let mut children: Vec<StyledNode> = Vec::new();
+                let mut current = None;
+                for (index, g) in line {
+                    if current == Some(index) {
+                        if let Some(StyledNode::Styled {
+                            children: text,
+                            ..
+                        }) = children.last_mut()
+                            && let Some(StyledNode::Text(text)) = text.last_mut()
+                        {
+                            text.push_str(g);
+                        }
+                    } else {
+                        current = Some(index);
+                        children.push(StyledNode::styled(runs[index].0.clone(), g));
+                    }
+                }
+                StyledNode::Styled {
+                    style: StyleSet::new(),
+                    children,
+                }
+            })
+            .collect()
+    }
+
+    /// Collect text with its fully resolved style, in order.
+    fn flatten(&self, stack: &mut Vec<StyleSet>, runs: &mut Vec<(StyleSet, String)>) {
+        match self {
+            StyledNode::Text(text) => runs.push((StyleSet::from_stack(stack), text.clone())),
+            StyledNode::Styled {
+                style,
+                children,
+            } => {
+                stack.push(style.clone());
+                for child in children {
+                    child.flatten(stack, runs);
+                }
+                stack.pop();
+            }
+        }
+    }
+
+    fn measure(
+        &self,
+        profile: &PrinterProfile,
+        stack: &mut Vec<StyleSet>,
+        line: &mut u32,
+        widest: &mut u32,
+    ) {
+        match self {
+            StyledNode::Text(text) => {
+                let style = StyleSet::from_stack(stack);
+                let cell = u32::from(profile.char_width_dots(style.font.unwrap_or_default()))
+                    * u32::from(style.size().width.multiplier());
+                for (g, count) in graphemes(text) {
+                    if g.ends_with('\n') {
+                        *widest = (*widest).max(*line);
+                        *line = 0;
+                    } else {
+                        *line += count * cell;
+                    }
+                }
+            }
+            StyledNode::Styled {
+                style,
+                children,
+            } => {
+                stack.push(style.clone());
+                for child in children {
+                    child.measure(profile, stack, line, widest);
+                }
+                stack.pop();
+            }
+        }
+    }
+
     /// Render to bytes, including style commands.
     ///
-    /// Returns the byte sequence ready to send to the printer.
+    /// Returns the byte sequence ready to send to the printer, ending with
+    /// the printer back in its default style. Each styled node switches its
+    /// style on where it starts and off where it ends, even between
+    /// siblings sharing a style; [`render_merged`](Self::render_merged)
+    /// emits fewer commands.
+    ///
+    /// Justification only takes effect at the start of a line, so a change
+    /// is emitted before the next line begins rather than where the style
+    /// changes. If the text does not end with a newline, a non-default
+    /// justification stays active until the next line; prefer
+    /// [`render_line`](Self::render_line) for aligned text.
     pub fn render(&self) -> Vec<u8> {
+        self.render_for(&PrinterProfile::default())
+    }
+
+    /// Render to bytes for a specific printer profile.
+    ///
+    /// The profile supplies character cell widths for emulated styles such
+    /// as strikethrough; otherwise identical to [`render`](Self::render).
+    pub fn render_for(&self, profile: &PrinterProfile) -> Vec<u8> {
         let mut output = Vec::new();
-        let mut style_stack: Vec<StyleSet> = vec![StyleSet::default()];
-        let mut current_effective = StyleSet::default();
+        self.render_into_for(profile, &mut output);
+        output
+    }
 
-        self.render_recursive(&mut output, &mut style_stack, &mut current_effective);
+    /// Render into an existing buffer, appending to its contents.
+    ///
+    /// Lets large receipts reuse one buffer instead of allocating a `Vec`
+    /// per node.
+    pub fn render_into(&self, output: &mut Vec<u8>) {
+        self.render_into_for(&PrinterProfile::default(), output);
+    }
 
-        // Reset to default style at end
-        let default_style = StyleSet::default();
-        let reset_commands = style_transition_commands(&current_effective, &default_style);
-        for cmd in reset_commands {
-            output.extend(cmd);
-        }
+    /// Render into an existing buffer for a specific printer profile.
+    pub fn render_into_for(&self, profile: &PrinterProfile, output: &mut Vec<u8>) {
+        let mut renderer = StyleRenderer::eager(profile.clone());
+        renderer.render_into(self, output);
+        renderer.reset_into(output);
+    }
 
+    /// Render to bytes, emitting style commands only where the style of
+    /// the text changes.
+    ///
+    /// Adjacent runs with the same effective style share one set of style
+    /// commands, so `"a".bold() + "b".bold()` sends `ESC E 1` once. The
+    /// printer ends in its default style, as with [`render`](Self::render).
+    /// Use a [`StyleRenderer`] to carry the style across several nodes.
+    pub fn render_merged(&self) -> Vec<u8> {
+        let mut renderer = StyleRenderer::new();
+        let mut output = renderer.render(self);
+        renderer.reset_into(&mut output);
         output
     }
 
+    /// Render to bytes with merged style runs and append a line feed.
+    ///
+    /// See [`render_merged`](Self::render_merged) and
+    /// [`render_line`](Self::render_line).
+    pub fn render_line_merged(&self) -> Vec<u8> {
+        let mut renderer = StyleRenderer::new();
+        let mut output = Vec::new();
+        renderer.render_into(self, &mut output);
+        renderer.finish_line(&mut output);
+        output
+    }
+
+    /// Render straight to a writer.
+    ///
+    /// Produces the bytes of [`render`](Self::render), writing them after
+    /// each run of text rather than collecting the whole node first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from the writer; nothing more is written
+    /// after it.
+    #[cfg(feature = "std")]
+    pub fn render_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut renderer = StyleRenderer::eager(PrinterProfile::default());
+        let mut output = Vec::new();
+        let mut result = Ok(());
+        renderer.render_draining(self, &mut output, &mut |output| {
+            if result.is_ok() {
+                result = writer.write_all(output);
+            }
+            output.clear();
+        });
+        result?;
+        renderer.reset_into(&mut output);
+        writer.write_all(&output)
+    }
+
     /// Render to bytes and append a line feed.
+    ///
+    /// Any justification used by the node is restored to left after the
+    /// line feed.
     pub fn render_line(&self) -> Vec<u8> {
-        let mut output = self.render();
-        output.extend(LineFeed.encode());
+        self.render_line_for(&PrinterProfile::default())
+    }
+
+    /// Render to bytes for a specific printer profile and append a line
+    /// feed.
+    pub fn render_line_for(&self, profile: &PrinterProfile) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.render_line_into_for(profile, &mut output);
         output
     }
 
+    /// Render into an existing buffer and append a line feed.
+    pub fn render_line_into(&self, output: &mut Vec<u8>) {
+        self.render_line_into_for(&PrinterProfile::default(), output);
+    }
+
+    /// Render into an existing buffer for a specific printer profile and
+    /// append a line feed.
+    pub fn render_line_into_for(&self, profile: &PrinterProfile, output: &mut Vec<u8>) {
+        let mut renderer = StyleRenderer::eager(profile.clone());
+        renderer.render_into(self, output);
+        renderer.finish_line(output);
+    }
+
     fn render_recursive(
         &self,
+        renderer: &mut StyleRenderer,
+        stack: &mut Vec<StyleSet>,
         output: &mut Vec<u8>,
-        style_stack: &mut Vec<StyleSet>,
-        current_effective: &mut StyleSet,
+        drain: &mut dyn FnMut(&mut Vec<u8>),
     ) {
         match self {
             StyledNode::Text(text) => {
-                output.extend(text.as_bytes());
+                renderer.write_text(StyleSet::from_stack(stack), text, output);
+                drain(output);
             }
             StyledNode::Styled {
                 style,
                 children,
             } => {
-                // Push style onto stack
-                style_stack.push(style.clone());
-
-                // Compute new effective style
-                let new_effective = StyleSet::from_stack(style_stack);
-
-                // Generate transition commands
-                let transition = style_transition_commands(current_effective, &new_effective);
-                for cmd in transition {
-                    output.extend(cmd);
+                stack.push(style.clone());
+                if renderer.eager {
+                    renderer.apply_style(StyleSet::from_stack(stack), output);
                 }
-                *current_effective = new_effective;
-
-                // Render children
                 for child in children {
-                    child.render_recursive(output, style_stack, current_effective);
+                    child.render_recursive(renderer, stack, output, drain);
                 }
+                stack.pop();
+                if renderer.eager {
+                    renderer.apply_style(StyleSet::from_stack(stack), output);
+                }
+            }
+        }
+    }
+}
+
+/// Incremental renderer that tracks the printer's active style.
+///
+/// Style commands are emitted lazily, just before text that needs them,
+/// and only as a diff against what the printer already has. Unlike
+/// [`StyledNode::render`], which returns the printer to its default style
+/// after every node, a `StyleRenderer` carries the effective style from
+/// one call to the next, so consecutive bold lines send `ESC E 1` once.
+///
+/// ```
+/// use bixolon::style::text::{StyleRenderer, Styleable};
+///
+/// let mut renderer = StyleRenderer::new();
+/// let first = renderer.render_line(&"Total".bold());
+/// let second = renderer.render_line(&"Paid".bold());
+/// assert_eq!(first, b"\x1bE\x01Total\n");
+/// assert_eq!(second, b"Paid\n");
+///
+/// // Return the printer to its default style when done
+/// assert_eq!(renderer.reset(), b"\x1bE\x00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleRenderer {
+    /// Style currently active on the printer, excluding justification.
+    printed: StyleSet,
+    /// Justification currently active on the printer.
+    printed_justification: Justification,
+    /// Justification wanted for the next line.
+    justification: Justification,
+    at_line_start: bool,
+    profile: PrinterProfile,
+    /// Style stack reused across renders, empty between them.
+    stack: Vec<StyleSet>,
+    /// Switch styles where nodes start and end rather than before text.
+    eager: bool,
+    /// Style the printer returns to, carrying the code page it is in
+    /// outside `code_page` styles.
+    base: StyleSet,
+}
+
+impl Default for StyleRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StyleRenderer {
+    /// Create a renderer assuming the printer is in its default style at
+    /// the start of a line.
+    pub fn new() -> Self {
+        Self::for_profile(PrinterProfile::default())
+    }
+
+    /// Create a renderer for a specific printer profile.
+    pub fn for_profile(profile: PrinterProfile) -> Self {
+        Self {
+            printed: StyleSet::new(),
+            printed_justification: Justification::Left,
+            justification: Justification::Left,
+            at_line_start: true,
+            profile,
+            stack: Vec::new(),
+            eager: false,
+            base: StyleSet::new(),
+        }
+    }
+
+    /// Assume the printer is in `code_page` outside `code_page` styles,
+    /// e.g. one chosen by a memory switch or a [`SelectCodePage`] sent
+    /// earlier.
+    ///
+    /// Text without a code page of its own is encoded in it, and nodes
+    /// that switch to another page return to it afterwards rather than to
+    /// CP437.
+    ///
+    /// ```
+    /// use bixolon::command::codepage::CodePage;
+    /// use bixolon::style::text::{StyleRenderer, Styleable};
+    ///
+    /// let mut renderer = StyleRenderer::new().with_code_page(CodePage::Cp858Euro);
+    /// let node = "Чай".code_page(CodePage::Windows1251Cyrillic) + " 5€";
+    /// assert_eq!(renderer.render(&node), b"\x1bt\x1c\xd7\xe0\xe9\x1bt\x13 5\xd5");
+    /// assert_eq!(renderer.reset(), b"");
+    /// ```
+    ///
+    /// [`SelectCodePage`]: crate::command::codepage::SelectCodePage
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.base = StyleSet::new().with_code_page(code_page);
+        self.printed = self.base.clone();
+        self
+    }
+
+    /// A renderer emitting style commands at node boundaries, as
+    /// [`StyledNode::render`] does.
+    pub(crate) fn eager(profile: PrinterProfile) -> Self {
+        Self {
+            eager: true,
+            ..Self::for_profile(profile)
+        }
+    }
+
+    /// Render a node, leaving its final style active.
+    pub fn render(&mut self, node: &StyledNode) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.render_into(node, &mut output);
+        output
+    }
+
+    /// Render a node into an existing buffer, leaving its final style
+    /// active.
+    pub fn render_into(&mut self, node: &StyledNode, output: &mut Vec<u8>) {
+        self.render_draining(node, output, &mut |_| {});
+    }
+
+    /// Render a node into `output`, passing it to `drain` after each run
+    /// of text.
+    fn render_draining(
+        &mut self,
+        node: &StyledNode,
+        output: &mut Vec<u8>,
+        drain: &mut dyn FnMut(&mut Vec<u8>),
+    ) {
+        let mut stack = core::mem::take(&mut self.stack);
+        stack.push(self.base.clone());
+        node.render_recursive(self, &mut stack, output, drain);
+        stack.clear();
+        self.stack = stack;
+    }
+
+    /// Render a node followed by a line feed, leaving its final style active.
+    pub fn render_line(&mut self, node: &StyledNode) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.render_line_into(node, &mut output);
+        output
+    }
 
-                // Pop style from stack
-                style_stack.pop();
+    /// Render a node and a line feed into an existing buffer, leaving its
+    /// final style active.
+    pub fn render_line_into(&mut self, node: &StyledNode, output: &mut Vec<u8>) {
+        self.render_into(node, output);
+        output.push(LF);
+        self.at_line_start = true;
+    }
 
-                // Compute style after popping
-                let popped_effective = StyleSet::from_stack(style_stack);
+    /// Commands returning the printer to its default style.
+    ///
+    /// Justification is only reset when at the start of a line.
+    pub fn reset(&mut self) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.reset_into(&mut output);
+        output
+    }
+
+    /// Append the commands returning the printer to its default style.
+    pub fn reset_into(&mut self, output: &mut Vec<u8>) {
+        self.reset_style(output);
+        self.justification = Justification::Left;
+        if self.at_line_start {
+            self.sync_justification(output);
+        }
+    }
+
+    /// Forget tracked state after the printer was reset externally, e.g.
+    /// by `ESC @`. The [code page](Self::with_code_page) is kept.
+    pub fn assume_default(&mut self) {
+        let base = core::mem::take(&mut self.base);
+        *self = Self {
+            eager: self.eager,
+            printed: base.clone(),
+            base,
+            ..Self::for_profile(core::mem::take(&mut self.profile))
+        };
+    }
+
+    /// End the line in the default style, restoring justification after
+    /// the line feed.
+    pub(crate) fn finish_line(&mut self, output: &mut Vec<u8>) {
+        self.reset_style(output);
+        output.push(LF);
+        self.at_line_start = true;
+        self.reset_into(output);
+    }
 
-                // Generate transition back
-                let transition_back =
-                    style_transition_commands(current_effective, &popped_effective);
-                for cmd in transition_back {
-                    output.extend(cmd);
+    /// Switch the printer to `target`, deferring justification to the next
+    /// line.
+    fn apply_style(&mut self, mut target: StyleSet, output: &mut Vec<u8>) {
+        self.justification = target.justification.take().unwrap_or_default();
+        write_style_transition(&self.printed, &target, output);
+        self.printed = target;
+    }
+
+    fn reset_style(&mut self, output: &mut Vec<u8>) {
+        write_style_transition(&self.printed, &self.base, output);
+        self.printed = self.base.clone();
+    }
+
+    fn write_text(&mut self, target: StyleSet, text: &str, output: &mut Vec<u8>) {
+        if text.is_empty() {
+            return;
+        }
+        let code_page = target.code_page;
+        let strike_cell = target.strikethrough.unwrap_or(false).then(|| {
+            let font = target.font.unwrap_or_default();
+            self.profile.char_width_dots(font) * u16::from(target.size().width.multiplier())
+        });
+        self.apply_style(target, output);
+
+        for line in text.split_inclusive('\n') {
+            if self.at_line_start {
+                self.sync_justification(output);
+            }
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line, ""),
+            };
+            match code_page {
+                Some(code_page) => output.extend(
+                    encoding::compose(content)
+                        .chars()
+                        .map(|c| code_page.encode_char(c).unwrap_or(b'?')),
+                ),
+                None => output.extend(content.as_bytes()),
+            }
+            if let Some(cell) = strike_cell {
+                let count = graphemes(content).map(|(_, count)| count as usize).sum::<usize>();
+                let width = (count * usize::from(cell)).min(i16::MAX as usize) as i16;
+                if count > 0 {
+                    output.extend_from_slice(&SetRelativePosition(-width).to_bytes());
+                    output.extend(core::iter::repeat_n(b'-', count));
                 }
-                *current_effective = popped_effective;
             }
+            output.extend(newline.as_bytes());
+            self.at_line_start = !newline.is_empty();
+        }
+    }
+
+    fn sync_justification(&mut self, output: &mut Vec<u8>) {
+        if self.justification != self.printed_justification {
+            output.extend_from_slice(&SetJustification(self.justification).to_bytes());
+            self.printed_justification = self.justification;
         }
     }
 }
 
+/// Grapheme clusters of `text` with the character cells each one covers.
+///
+/// Combining marks share their base character's cell, wide (CJK)
+/// characters cover two cells, and control characters none.
+fn graphemes(text: &str) -> impl Iterator<Item = (&str, u32)> {
+    text.graphemes(true).map(|g| {
+        let cells = if g.starts_with(char::is_control) {
+            0
+        } else {
+            g.width() as u32
+        };
+        (g, cells)
+    })
+}
+
 impl From<&str> for StyledNode {
     fn from(s: &str) -> Self {
         StyledNode::Text(s.to_string())
@@ -160,9 +810,110 @@ impl From<String> for StyledNode {
     }
 }
 
+impl<T: Into<StyledNode>> Add<T> for StyledNode {
+    type Output = StyledNode;
+
+    /// Concatenate two nodes as siblings.
+    ///
+    /// Unlike [`append`](StyledNode::append), adding to a neutral container
+    /// extends it rather than nesting another wrapper, so `a + b + c`
+    /// produces one container with three children.
+    fn add(mut self, rhs: T) -> StyledNode {
+        self += rhs;
+        self
+    }
+}
+
+impl<T: Into<StyledNode>> AddAssign<T> for StyledNode {
+    fn add_assign(&mut self, rhs: T) {
+        let rhs = rhs.into();
+        match self {
+            StyledNode::Styled {
+                style,
+                children,
+            } if style.is_empty() => children.push(rhs),
+            _ => {
+                let lhs = core::mem::replace(self, StyledNode::Text(String::new()));
+                *self = StyledNode::Styled {
+                    style: StyleSet::default(),
+                    children: vec![lhs, rhs],
+                };
+            }
+        }
+    }
+}
+
+impl<T: Into<StyledNode>> Extend<T> for StyledNode {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for node in iter {
+            *self += node;
+        }
+    }
+}
+
+impl<T: Into<StyledNode>> FromIterator<T> for StyledNode {
+    /// Collect nodes as siblings of a single neutral container.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        StyledNode::Styled {
+            style: StyleSet::default(),
+            children: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Wrapper that makes any [`Display`](fmt::Display) value styleable.
+///
+/// Numbers and `char` are styleable directly; wrap other types, such as
+/// money or date types, to style their formatted output.
+///
+/// ```
+/// use bixolon::style::text::{Displayed, Styleable};
+///
+/// let ip = std::net::Ipv4Addr::new(192, 168, 0, 10);
+/// let node = Displayed(ip).bold();
+/// assert!(node.render().windows(12).any(|w| w == b"192.168.0.10"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Displayed<T>(pub T);
+
+impl<T: fmt::Display> From<Displayed<T>> for StyledNode {
+    fn from(value: Displayed<T>) -> Self {
+        StyledNode::Text(value.0.to_string())
+    }
+}
+
+impl<T: fmt::Display> Styleable for Displayed<T> {
+    fn into_node(self) -> StyledNode {
+        self.into()
+    }
+}
+
+macro_rules! display_styleable {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for StyledNode {
+                fn from(value: $ty) -> Self {
+                    StyledNode::Text(value.to_string())
+                }
+            }
+
+            impl Styleable for $ty {
+                fn into_node(self) -> StyledNode {
+                    self.into()
+                }
+            }
+        )*
+    };
+}
+
+display_styleable!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char,);
+
 /// Trait for ergonomic text styling.
 ///
-/// Implemented for `&str` and `String` to allow fluent style application.
+/// Implemented for `&str`, `String`, numbers and `char` to allow fluent
+/// style application. Wrap other [`Display`](fmt::Display) types in
+/// [`Displayed`], or build formatted text with
+/// [`format_styled!`](crate::format_styled).
 pub trait Styleable: Sized {
     /// Convert to a StyledNode.
     fn into_node(self) -> StyledNode;
@@ -202,6 +953,76 @@ pub trait Styleable: Sized {
         self.into_node().with_style(StyleSet::default().with_rotated(true))
     }
 
+    /// Apply strikethrough, emulated by overprinting dashes.
+    ///
+    /// Useful for voided items. The dashes take the text's other styles,
+    /// so struck underlined text is underlined twice.
+    fn struck(self) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_strikethrough(true))
+    }
+
+    /// Use the given character font.
+    fn font(self, font: Font) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_font(font))
+    }
+
+    /// Apply a character size (width and height scale).
+    fn sized(self, size: CharacterSize) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_size(size))
+    }
+
+    /// Apply double width and height.
+    fn double_size(self) -> StyledNode {
+        self.sized(CharacterSize::double())
+    }
+
+    /// Apply double width, inheriting the height scale.
+    fn double_width(self) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_width_scale(ScaleFactor::X2))
+    }
+
+    /// Apply double height, inheriting the width scale.
+    fn double_height(self) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_height_scale(ScaleFactor::X2))
+    }
+
+    /// Align the line(s) containing this text.
+    ///
+    /// Justification applies to whole lines: it is emitted before the line
+    /// starts and the previous alignment is restored on the following line.
+    fn aligned(self, justification: Justification) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_justification(justification))
+    }
+
+    /// Center the line(s) containing this text.
+    fn centered(self) -> StyledNode {
+        self.aligned(Justification::Center)
+    }
+
+    /// Right-align the line(s) containing this text.
+    fn right_aligned(self) -> StyledNode {
+        self.aligned(Justification::Right)
+    }
+
+    /// Print in the given color (two-color models only).
+    fn color(self, color: PrintColor) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_color(color))
+    }
+
+    /// Print in red (two-color models only).
+    fn red(self) -> StyledNode {
+        self.color(PrintColor::Red)
+    }
+
+    /// Transcode this text into the given code page.
+    ///
+    /// The renderer selects the code page with `ESC t` before the text and
+    /// switches back afterwards, so one document can mix scripts, e.g.
+    /// Latin and Cyrillic. Characters the code page lacks print as `?`.
+    fn code_page(self, code_page: CodePage) -> StyledNode {
+        self.into_node().with_style(StyleSet::default().with_code_page(code_page))
+    }
+
     /// Append another styled node.
     fn append(self, other: impl Styleable) -> StyledNode {
         self.into_node().append(other.into_node())
@@ -226,11 +1047,174 @@ impl Styleable for StyledNode {
     }
 }
 
+/// Build a [`StyledNode`] tree declaratively.
+///
+/// The body is a sequence of items:
+///
+/// - a string literal, e.g. `"Total"`
+/// - a parenthesized expression convertible with [`Styleable::into_node`],
+///   e.g. `(format!("{qty} x"))` or `(other_node)`
+/// - a style block `name { ... }`, or `name(arg) { ... }` for styles that
+///   take a parameter
+///
+/// Style names: `bold`, `underline`, `double_underline`, `double_strike`, `struck`,
+/// `reverse`, `upside_down`, `rotated`, `double_width`, `double_height`,
+/// `double_size`, `centered`, `right`, `red`, `font(Font)`,
+/// `size(CharacterSize)`, `align(Justification)`, `color(PrintColor)`,
+/// `code_page(CodePage)` and `style(StyleSet)`.
+///
+/// ```
+/// use bixolon::styled;
+/// use bixolon::command::character::Font;
+///
+/// let total = "25.00";
+/// let node = styled! {
+///     bold { "Total" }
+///     "  $" (total)
+///     font(Font::B) { " incl. tax" }
+/// };
+/// assert!(node.render().windows(5).any(|w| w == b"Total"));
+/// ```
+#[macro_export]
+macro_rules! styled {
+    (@items [$($out:expr,)*]) => {
+        $crate::__private::vec![$($out,)*]
+    };
+    (@items [$($out:expr,)*] $text:literal $($rest:tt)*) => {
+        $crate::styled!(@items [$($out,)* $crate::style::text::StyledNode::from($text),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] ($value:expr) $($rest:tt)*) => {
+        $crate::styled!(@items [$($out,)* $crate::style::text::Styleable::into_node($value),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] $name:ident ($($arg:tt)*) { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::styled!(@items [$($out,)* $crate::style::text::StyledNode::Styled {
+            style: $crate::styled!(@style $name ($($arg)*)),
+            children: $crate::styled!(@items [] $($inner)*),
+        },] $($rest)*)
+    };
+    (@items [$($out:expr,)*] $name:ident { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::styled!(@items [$($out,)* $crate::style::text::StyledNode::Styled {
+            style: $crate::styled!(@style $name),
+            children: $crate::styled!(@items [] $($inner)*),
+        },] $($rest)*)
+    };
+
+    (@style bold) => { $crate::style::StyleSet::new().with_bold(true) };
+    (@style underline) => { $crate::style::StyleSet::new().with_underline(true) };
+    (@style double_underline) => { $crate::style::StyleSet::new().with_double_underline(true) };
+    (@style double_strike) => { $crate::style::StyleSet::new().with_double_strike(true) };
+    (@style struck) => { $crate::style::StyleSet::new().with_strikethrough(true) };
+    (@style reverse) => { $crate::style::StyleSet::new().with_reverse(true) };
+    (@style upside_down) => { $crate::style::StyleSet::new().with_upside_down(true) };
+    (@style rotated) => { $crate::style::StyleSet::new().with_rotated(true) };
+    (@style double_width) => {
+        $crate::style::StyleSet::new()
+            .with_width_scale($crate::command::character::ScaleFactor::X2)
+    };
+    (@style double_height) => {
+        $crate::style::StyleSet::new()
+            .with_height_scale($crate::command::character::ScaleFactor::X2)
+    };
+    (@style double_size) => {
+        $crate::style::StyleSet::new()
+            .with_size($crate::command::character::CharacterSize::double())
+    };
+    (@style centered) => {
+        $crate::style::StyleSet::new()
+            .with_justification($crate::command::character::Justification::Center)
+    };
+    (@style right) => {
+        $crate::style::StyleSet::new()
+            .with_justification($crate::command::character::Justification::Right)
+    };
+    (@style red) => {
+        $crate::style::StyleSet::new().with_color($crate::command::character::PrintColor::Red)
+    };
+    (@style font ($font:expr)) => { $crate::style::StyleSet::new().with_font($font) };
+    (@style size ($size:expr)) => { $crate::style::StyleSet::new().with_size($size) };
+    (@style align ($justification:expr)) => {
+        $crate::style::StyleSet::new().with_justification($justification)
+    };
+    (@style color ($color:expr)) => { $crate::style::StyleSet::new().with_color($color) };
+    (@style code_page ($code_page:expr)) => {
+        $crate::style::StyleSet::new().with_code_page($code_page)
+    };
+    (@style style ($style:expr)) => { $style };
+
+    ($($body:tt)*) => {
+        $crate::style::text::StyledNode::Styled {
+            style: $crate::style::StyleSet::new(),
+            children: $crate::styled!(@items [] $($body)*),
+        }
+    };
+}
+
+/// Format text into a [`StyledNode`], optionally applying styles.
+///
+/// Takes the same arguments as [`format!`], optionally preceded by a
+/// comma-separated list of [`styled!`] style names and a `;`.
+///
+/// ```
+/// use bixolon::format_styled;
+/// use bixolon::style::text::{StyledNode, Styleable};
+///
+/// let total = 25.5;
+/// let plain = format_styled!("Total: {total:.2}");
+/// assert_eq!(plain, StyledNode::text("Total: 25.50"));
+///
+/// let styled = format_styled!(bold, double_height; "Total: {total:.2}");
+/// assert_eq!(styled.render_merged(), "Total: 25.50".bold().double_height().render_merged());
+/// ```
+#[macro_export]
+macro_rules! format_styled {
+    ($($name:ident $(($($arg:tt)*))?),+ ; $($fmt:tt)+) => {
+        $crate::style::text::StyledNode::Styled {
+            style: $crate::style::StyleSet::from_stack(&[
+                $($crate::styled!(@style $name $(($($arg)*))?)),+
+            ]),
+            children: $crate::__private::vec![$crate::style::text::StyledNode::Text($crate::__private::format!($($fmt)+))],
+        }
+    };
+    ($($fmt:tt)+) => {
+        $crate::style::text::StyledNode::Text($crate::__private::format!($($fmt)+))
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::command::ESC;
 
+    #[test]
+    fn wrap_breaks_at_spaces_and_keeps_styles() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let node = StyledNode::text("one ") + "two three".bold() + "\n\nfour";
+        let lines: Vec<Vec<u8>> =
+            node.wrap(&profile, 9 * 12).iter().map(StyledNode::render).collect();
+        assert_eq!(
+            lines,
+            [
+                (StyledNode::text("one ") + "two".bold()).render(),
+                "three".bold().render(),
+                Vec::new(),
+                b"four".to_vec(),
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_splits_long_words_and_measures_scaled_text() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let lines = StyledNode::text("abcdefg").wrap(&profile, 36);
+        let lines: Vec<Vec<u8>> = lines.iter().map(StyledNode::render).collect();
+        assert_eq!(lines, [b"abc".to_vec(), b"def".to_vec(), b"g".to_vec()]);
+
+        let lines = "ab cd".double_width().wrap(&profile, 48);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].height_dots(&profile), 24);
+        assert_eq!("x".double_height().height_dots(&profile), 48);
+    }
+
     #[test]
     fn text_node_renders_plain() {
         let node = StyledNode::text("Hello");
@@ -354,4 +1338,313 @@ mod tests {
         assert_eq!(underline_on_count, 1);
         assert_eq!(underline_off_count, 1);
     }
+
+    #[test]
+    fn red_renders_color_commands() {
+        let node = "VOID".red();
+        assert!(node.uses_color());
+
+        let output = node.render();
+        assert!(output.starts_with(&[ESC, b'r', 1]));
+        assert!(output.ends_with(&[ESC, b'r', 0]));
+    }
+
+    #[test]
+    fn uses_color_ignores_black() {
+        assert!(!"plain".bold().uses_color());
+        assert!(!"black".color(PrintColor::Black).uses_color());
+        assert!("a".bold().append("b".red()).uses_color());
+    }
+
+    #[test]
+    fn double_height_renders_size_commands() {
+        let output = "TOTAL".double_height().render();
+        assert!(output.starts_with(&[0x1D, b'!', 0x01]));
+        assert!(output.ends_with(&[0x1D, b'!', 0x00]));
+    }
+
+    #[test]
+    fn nested_size_restores_outer() {
+        let node = "A ".double_width().append("B".double_height().double_width()).double_width();
+        let output = node.render();
+
+        // Outer 2x1, inner 2x2, back to 2x1, then reset
+        let sizes: Vec<u8> =
+            output.windows(3).filter(|w| w[..2] == [0x1D, b'!']).map(|w| w[2]).collect();
+        assert_eq!(sizes, vec![0x10, 0x11, 0x10, 0x00]);
+    }
+
+    #[test]
+    fn merged_rendering_shares_style_runs() {
+        let node = "Total".bold() + " $25.00".bold();
+        assert_eq!(node.render(), b"\x1bE\x01Total\x1bE\x00\x1bE\x01 $25.00\x1bE\x00");
+        assert_eq!(node.render_merged(), b"\x1bE\x01Total $25.00\x1bE\x00");
+        assert_eq!(node.render_line_merged(), b"\x1bE\x01Total $25.00\x1bE\x00\n");
+    }
+
+    #[test]
+    fn centered_line_emits_justification_before_text_and_restores() {
+        let output = "Thanks!".centered().render_line();
+        let mut expected = vec![ESC, b'a', 1];
+        expected.extend_from_slice(b"Thanks!\n");
+        expected.extend_from_slice(&[ESC, b'a', 0]);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn justification_change_mid_line_is_deferred() {
+        let node = "Total: ".append("$5\nnext".right_aligned());
+        let output = node.render_line();
+
+        // Right alignment can only apply from the line after "$5"
+        let just_pos = output.windows(3).position(|w| w == [ESC, b'a', 2]).unwrap();
+        let next_pos = output.windows(4).position(|w| w == b"next").unwrap();
+        let dollar_pos = output.iter().position(|&b| b == b'$').unwrap();
+        assert!(dollar_pos < just_pos && just_pos < next_pos);
+        assert!(output.ends_with(&[0x0A, ESC, b'a', 0]));
+    }
+
+    #[test]
+    fn nested_justification_restores_outer_on_next_line() {
+        let node =
+            "left\n".append("center\n".centered()).append("right".into_node()).right_aligned();
+        let output = node.render_line();
+        let codes: Vec<u8> =
+            output.windows(3).filter(|w| w[..2] == [ESC, b'a']).map(|w| w[2]).collect();
+        assert_eq!(codes, vec![2, 1, 2, 0]);
+    }
+
+    #[test]
+    fn nested_font_restores_outer() {
+        let node =
+            "outer ".append("inner".font(Font::A)).append(" outer".into_node()).font(Font::B);
+        let output = node.render();
+        let fonts: Vec<u8> =
+            output.windows(3).filter(|w| w[..2] == [ESC, b'M']).map(|w| w[2]).collect();
+        assert_eq!(fonts, vec![1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn styled_macro_matches_builder() {
+        let from_macro = crate::styled! { bold { "Total" } "  $25.00" };
+        let from_builder = "Total".bold().append("  $25.00".into());
+        assert_eq!(from_macro.render(), from_builder.render());
+    }
+
+    #[test]
+    fn styled_macro_nests_and_takes_arguments() {
+        let qty = 3;
+        let node = crate::styled! {
+            centered {
+                bold { underline { "A" } "B" }
+                font(Font::B) { (format!("{qty}x")) }
+            }
+        };
+        let output = node.render_line();
+
+        let centered = output.windows(3).position(|w| w == [ESC, b'a', 1]).unwrap();
+        assert!(centered < output.iter().position(|&b| b == b'A').unwrap());
+        assert!(output.windows(3).any(|w| w == [ESC, b'M', 1]));
+        assert!(output.windows(2).any(|w| w == b"3x"));
+        assert!(output.ends_with(&[0x0A, ESC, b'a', 0]));
+    }
+
+    #[test]
+    fn styled_macro_empty() {
+        assert!(crate::styled! {}.render().is_empty());
+    }
+
+    #[test]
+    fn style_renderer_carries_style_across_calls() {
+        let mut renderer = StyleRenderer::new();
+        let first = renderer.render_line(&"A".bold());
+        let second = renderer.render_line(&"B".bold().underlined());
+        let third = renderer.render_line(&StyledNode::text("C"));
+
+        assert_eq!(first, [&[ESC, b'E', 1][..], b"A\n"].concat());
+        assert_eq!(second, [&[ESC, b'-', 1][..], b"B\n"].concat());
+        assert_eq!(third, [&[ESC, b'E', 0, ESC, b'-', 0][..], b"C\n"].concat());
+        assert!(renderer.reset().is_empty());
+    }
+
+    #[test]
+    fn style_renderer_defers_justification_restore() {
+        let mut renderer = StyleRenderer::new();
+        let first = renderer.render_line(&"Title".centered());
+        let second = renderer.render_line(&"Subtitle".centered());
+        let third = renderer.render_line(&StyledNode::text("Body"));
+
+        assert!(first.starts_with(&[ESC, b'a', 1]));
+        assert_eq!(second, b"Subtitle\n");
+        assert!(third.starts_with(&[ESC, b'a', 0]));
+    }
+
+    #[test]
+    fn apply_fallback_uses_each_nodes_code_page() {
+        let node = "Чай ".append("Чай".code_page(CodePage::Windows1251Cyrillic));
+        let replaced = node.clone().apply_fallback(Fallback::Replace).unwrap();
+        assert_eq!(replaced, "??? ".append("Чай".code_page(CodePage::Windows1251Cyrillic)));
+        assert!(node.apply_fallback(Fallback::Error).is_err());
+    }
+
+    #[test]
+    fn code_page_transcodes_and_restores() {
+        let node =
+            "Tea ".append("Чай".code_page(CodePage::Windows1251Cyrillic)).append(" ok".into());
+        let expected =
+            [&b"Tea "[..], &[ESC, b't', 28], &[0xD7, 0xE0, 0xE9], &[ESC, b't', 0], b" ok"].concat();
+        assert_eq!(node.render(), expected);
+    }
+
+    #[test]
+    fn decomposed_text_is_composed_before_encoding() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let node = "Cafe\u{301}".code_page(CodePage::Cp437UsaStandardEurope);
+        assert_eq!(node.columns(&profile), 4);
+        assert_eq!(node.render(), b"Caf\x82");
+    }
+
+    #[test]
+    fn numbers_are_styleable() {
+        assert_eq!(42u32.bold(), "42".bold());
+        assert_eq!((-1.5f64).into_node(), StyledNode::text("-1.5"));
+        assert_eq!(StyledNode::from('x'), StyledNode::text("x"));
+    }
+
+    #[test]
+    fn format_styled_applies_styles() {
+        let qty = 3;
+        let node = crate::format_styled!(bold, font(Font::B); "{qty} x");
+        let expected =
+            StyledNode::styled(StyleSet::new().with_bold(true).with_font(Font::B), "3 x");
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn columns_accounts_for_font_and_size() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        assert_eq!(StyledNode::text("Hello").columns(&profile), 5);
+        assert_eq!("Hi".double_size().columns(&profile), 4);
+        // 4 Font B cells are 36 dots, covering 3 Font A cells
+        assert_eq!("abcd".font(Font::B).columns(&profile), 3);
+    }
+
+    #[test]
+    fn columns_measures_widest_line() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let node = "Receipt\n".bold().append("Thank you!".into());
+        assert_eq!(node.columns(&profile), 10);
+    }
+
+    #[test]
+    fn columns_count_graphemes_and_wide_characters() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        // Precomposed and decomposed forms print one cell each
+        assert_eq!(StyledNode::text("Café").columns(&profile), 4);
+        assert_eq!(StyledNode::text("Cafe\u{301}").columns(&profile), 4);
+        assert_eq!(StyledNode::text("寿司 x2").columns(&profile), 7);
+        assert_eq!(StyledNode::text("ｶﾂ").columns(&profile), 2);
+    }
+
+    #[test]
+    fn wrap_keeps_clusters_whole() {
+        let profile = PrinterProfile::SRP_350PLUS;
+        let lines = StyledNode::text("e\u{301}e\u{301}e\u{301}").wrap(&profile, 24);
+        let lines: Vec<Vec<u8>> = lines.iter().map(StyledNode::render).collect();
+        assert_eq!(lines, ["e\u{301}e\u{301}".as_bytes(), "e\u{301}".as_bytes()]);
+
+        // Each wide character needs two Font A cells
+        let lines = StyledNode::text("日本語").wrap(&profile, 48);
+        let lines: Vec<Vec<u8>> = lines.iter().map(StyledNode::render).collect();
+        assert_eq!(lines, ["日本".as_bytes(), "語".as_bytes()]);
+    }
+
+    #[test]
+    fn add_flattens_into_one_container() {
+        let node = "A".bold() + "B" + "C".underlined();
+        let StyledNode::Styled {
+            style,
+            children,
+        } = &node
+        else {
+            panic!("expected a container");
+        };
+        assert!(style.is_empty());
+        assert_eq!(children.len(), 3);
+        assert_eq!(node.render(), "A".bold().append("B".into()).append("C".underlined()).render());
+    }
+
+    #[test]
+    fn add_assign_does_not_extend_styled_nodes() {
+        let mut node = "A".bold();
+        node += "B";
+        assert_eq!(node.render(), [&[ESC, b'E', 1][..], b"A", &[ESC, b'E', 0], b"B"].concat());
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut node: StyledNode = ["a", "b"].into_iter().collect();
+        node.extend(vec!["c".bold()]);
+        assert_eq!(node.render(), [&b"ab"[..], &[ESC, b'E', 1], b"c", &[ESC, b'E', 0]].concat());
+    }
+
+    #[test]
+    fn struck_overprints_dashes() {
+        let expected = [&b"Fries"[..], &[ESC, b'\\', 0xC4, 0xFF], b"-----"].concat();
+        assert_eq!("Fries".struck().render(), expected);
+    }
+
+    #[test]
+    fn struck_scales_with_width_and_keeps_newlines() {
+        let expected =
+            [&[0x1D, b'!', 0x10][..], b"ab", &[ESC, b'\\', 0xD0, 0xFF], b"--\n", &[0x1D, b'!', 0]]
+                .concat();
+        assert_eq!("ab\n".struck().double_width().render(), expected);
+    }
+
+    #[test]
+    fn struck_counts_cells_not_bytes() {
+        let expected = [&b"Cr\x88pe"[..], &[ESC, b'\\', 0xC4, 0xFF], b"-----"].concat();
+        let node = "Crêpe".struck().code_page(CodePage::Cp437UsaStandardEurope);
+        assert!(node.render().ends_with(&expected));
+    }
+
+    #[test]
+    fn render_into_appends_to_buffer() {
+        let mut output = b"head:".to_vec();
+        "A".bold().render_into(&mut output);
+        StyledNode::text("B").render_line_into(&mut output);
+        let expected =
+            [&b"head:"[..], &"A".bold().render(), &StyledNode::text("B").render_line()].concat();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn render_to_writes_bytes() {
+        let mut writer = std::io::Cursor::new(Vec::new());
+        "Hi".underlined().render_to(&mut writer).unwrap();
+        assert_eq!(writer.into_inner(), "Hi".underlined().render());
+    }
+
+    #[test]
+    fn render_to_writes_each_text_run() {
+        struct Writes(Vec<Vec<u8>>);
+
+        impl Write for Writes {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.push(buf.to_vec());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let node = "a".bold() + "b" + "c".underlined();
+        let mut writer = Writes(Vec::new());
+        node.render_to(&mut writer).unwrap();
+        assert_eq!(writer.0.len(), 4);
+        assert_eq!(writer.0.concat(), node.render());
+    }
 }
@@ -2,3 +2,11 @@
 
 #[cfg(feature = "rusb")]
 pub mod usb;
+#[cfg(feature = "web")]
+pub mod web;
+
+/// Bixolon USB Vendor ID.
+pub const BIXOLON_VENDOR_ID: u16 = 0x1504;
+
+/// SRP-350plus USB Product ID.
+pub const SRP350PLUS_PRODUCT_ID: u16 = 0x0006;
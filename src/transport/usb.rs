@@ -20,13 +20,9 @@ use std::time::Duration;
 
 use rusb::{Context, Device, DeviceHandle, Direction, TransferType, UsbContext};
 
-use crate::error::UsbError;
+use crate::error::{TimeoutError, UsbError};
 
-/// Bixolon USB Vendor ID.
-pub const BIXOLON_VENDOR_ID: u16 = 0x1504;
-
-/// SRP-350plus USB Product ID.
-pub const SRP350PLUS_PRODUCT_ID: u16 = 0x0006;
+pub use super::{BIXOLON_VENDOR_ID, SRP350PLUS_PRODUCT_ID};
 
 /// Default USB timeout.
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -83,6 +79,14 @@ impl UsbPrinterDevice {
 
         let write_endpoint = write_endpoint.ok_or(UsbError::NoWriteEndpoint)?;
         let read_endpoint = read_endpoint.ok_or(UsbError::NoReadEndpoint)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            vendor_id = self.vendor_id,
+            product_id = self.product_id,
+            write_endpoint,
+            read_endpoint,
+            "opened USB printer"
+        );
 
         Ok(UsbPrinter {
             handle: Some(handle),
@@ -185,6 +189,8 @@ impl UsbPrinter {
         // Detach kernel driver if attached (Linux)
         #[cfg(target_os = "linux")]
         if handle.kernel_driver_active(self.interface_number)? {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(interface = self.interface_number, "detaching kernel driver");
             handle.detach_kernel_driver(self.interface_number)?;
         }
 
@@ -246,7 +252,11 @@ pub struct UsbReader {
 
 impl Read for UsbReader {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.handle.read_bulk(self.endpoint, buf, self.timeout).map_err(std::io::Error::other)
+        self.handle.read_bulk(self.endpoint, buf, self.timeout).map_err(|err| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %err, endpoint = self.endpoint, "USB bulk read failed");
+            transfer_error(err, "USB bulk read", self.timeout)
+        })
     }
 }
 
@@ -259,7 +269,11 @@ pub struct UsbWriter {
 
 impl Write for UsbWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.handle.write_bulk(self.endpoint, buf, self.timeout).map_err(std::io::Error::other)
+        self.handle.write_bulk(self.endpoint, buf, self.timeout).map_err(|err| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %err, endpoint = self.endpoint, "USB bulk write failed");
+            transfer_error(err, "USB bulk write", self.timeout)
+        })
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
@@ -267,3 +281,16 @@ impl Write for UsbWriter {
         Ok(())
     }
 }
+
+/// Convert a failed transfer to an I/O error, keeping timeouts typed so
+/// the printer reports [`PrinterError::Timeout`](crate::error::PrinterError::Timeout).
+fn transfer_error(err: rusb::Error, operation: &'static str, timeout: Duration) -> std::io::Error {
+    match err {
+        rusb::Error::Timeout => TimeoutError {
+            operation,
+            elapsed: timeout,
+        }
+        .into(),
+        err => std::io::Error::other(err),
+    }
+}
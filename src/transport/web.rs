@@ -0,0 +1,248 @@
+//! WebUSB transport for browser apps.
+//!
+//! Lets kiosk and point-of-sale apps compiled to `wasm32-unknown-unknown`
+//! print over USB from Chromium-based browsers. Commands are encoded with
+//! the rest of the crate as usual and written with [`WebUsbPrinter::write`].
+//!
+//! Browsers only open devices the user has granted the page access to.
+//! [`WebUsbPrinter::request_bixolon`] shows the browser's device picker and
+//! must be called from a user gesture such as a click handler; after that,
+//! [`WebUsbPrinter::paired`] reconnects without asking.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use bixolon::command::paper::CutPaper;
+//! use bixolon::style::text::Styleable;
+//! use bixolon::transport::web::WebUsbPrinter;
+//!
+//! let printer = WebUsbPrinter::request_bixolon().await?;
+//! printer.write(&"Order 42".bold().render_line()).await?;
+//! printer.send(CutPaper::partial()).await?;
+//! ```
+
+use js_sys::{Array, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use super::BIXOLON_VENDOR_ID;
+use crate::command::Command;
+use crate::error::WebUsbError;
+
+#[wasm_bindgen]
+extern "C" {
+    /// `navigator.usb`.
+    type Usb;
+
+    #[wasm_bindgen(method, js_name = requestDevice)]
+    fn request_device(this: &Usb, options: &Object) -> Promise;
+
+    #[wasm_bindgen(method, js_name = getDevices)]
+    fn get_devices(this: &Usb) -> Promise;
+
+    /// A `USBDevice`.
+    type UsbDevice;
+
+    #[wasm_bindgen(method, getter, js_name = vendorId)]
+    fn vendor_id(this: &UsbDevice) -> u16;
+
+    #[wasm_bindgen(method, getter, js_name = productId)]
+    fn product_id(this: &UsbDevice) -> u16;
+
+    #[wasm_bindgen(method, getter)]
+    fn configuration(this: &UsbDevice) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    fn open(this: &UsbDevice) -> Promise;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &UsbDevice) -> Promise;
+
+    #[wasm_bindgen(method, js_name = selectConfiguration)]
+    fn select_configuration(this: &UsbDevice, value: u8) -> Promise;
+
+    #[wasm_bindgen(method, js_name = claimInterface)]
+    fn claim_interface(this: &UsbDevice, number: u8) -> Promise;
+
+    #[wasm_bindgen(method, js_name = releaseInterface)]
+    fn release_interface(this: &UsbDevice, number: u8) -> Promise;
+
+    #[wasm_bindgen(method, js_name = transferOut)]
+    fn transfer_out(this: &UsbDevice, endpoint: u8, data: &Uint8Array) -> Promise;
+
+    #[wasm_bindgen(method, js_name = transferIn)]
+    fn transfer_in(this: &UsbDevice, endpoint: u8, length: u32) -> Promise;
+}
+
+/// An opened WebUSB printer.
+pub struct WebUsbPrinter {
+    device: UsbDevice,
+    interface_number: u8,
+    write_endpoint: u8,
+    read_endpoint: Option<u8>,
+}
+
+impl WebUsbPrinter {
+    /// Ask the user to pick a Bixolon printer, then open it.
+    pub async fn request_bixolon() -> Result<Self, WebUsbError> {
+        Self::request(&[BIXOLON_VENDOR_ID]).await
+    }
+
+    /// Ask the user to pick a printer from any of `vendor_ids`, then open it.
+    pub async fn request(vendor_ids: &[u16]) -> Result<Self, WebUsbError> {
+        let filters: Array = vendor_ids
+            .iter()
+            .map(|&vendor_id| {
+                let filter = Object::new();
+                Reflect::set(&filter, &"vendorId".into(), &vendor_id.into()).map(|_| filter)
+            })
+            .collect::<Result<_, _>>()?;
+        let options = Object::new();
+        Reflect::set(&options, &"filters".into(), &filters)?;
+        let device = JsFuture::from(usb()?.request_device(&options)).await?;
+        Self::open_device(device.unchecked_into()).await
+    }
+
+    /// Open every printer the page was granted access to before.
+    ///
+    /// Unlike [`request`](Self::request), this needs no user gesture, so a
+    /// kiosk can reconnect on load. Devices that fail to open, e.g. because
+    /// another tab has claimed them, are skipped.
+    pub async fn paired() -> Result<Vec<Self>, WebUsbError> {
+        let devices: Array = JsFuture::from(usb()?.get_devices()).await?.unchecked_into();
+        let mut printers = Vec::new();
+        for device in devices.iter() {
+            match Self::open_device(device.unchecked_into()).await {
+                Ok(printer) => printers.push(printer),
+                #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %err, "skipping paired device that failed to open");
+                }
+            }
+        }
+        Ok(printers)
+    }
+
+    async fn open_device(device: UsbDevice) -> Result<Self, WebUsbError> {
+        JsFuture::from(device.open()).await?;
+        if device.configuration().is_null() {
+            JsFuture::from(device.select_configuration(1)).await?;
+        }
+
+        // Find the first interface with a bulk OUT endpoint, and its bulk IN
+        // endpoint if it has one; both must belong to the claimed interface
+        let mut found = None;
+        let interfaces: Array = get(&device.configuration(), "interfaces")?.unchecked_into();
+        for interface in interfaces.iter() {
+            let mut write_endpoint = None;
+            let mut read_endpoint = None;
+            let endpoints: Array =
+                get(&get(&interface, "alternate")?, "endpoints")?.unchecked_into();
+            for endpoint in endpoints.iter() {
+                if get(&endpoint, "type")?.as_string().as_deref() != Some("bulk") {
+                    continue;
+                }
+                let number = get(&endpoint, "endpointNumber")?.as_f64().unwrap_or(0.0) as u8;
+                match get(&endpoint, "direction")?.as_string().as_deref() {
+                    Some("out") if write_endpoint.is_none() => write_endpoint = Some(number),
+                    Some("in") if read_endpoint.is_none() => read_endpoint = Some(number),
+                    _ => {}
+                }
+            }
+            if let Some(write_endpoint) = write_endpoint {
+                let number = get(&interface, "interfaceNumber")?.as_f64().unwrap_or(0.0) as u8;
+                found = Some((number, write_endpoint, read_endpoint));
+                break;
+            }
+        }
+        let (interface_number, write_endpoint, read_endpoint) =
+            found.ok_or(WebUsbError::NoWriteEndpoint)?;
+        JsFuture::from(device.claim_interface(interface_number)).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            vendor_id = device.vendor_id(),
+            product_id = device.product_id(),
+            write_endpoint,
+            read_endpoint,
+            "opened WebUSB printer"
+        );
+
+        Ok(Self {
+            device,
+            interface_number,
+            write_endpoint,
+            read_endpoint,
+        })
+    }
+
+    /// Get the vendor ID.
+    pub fn vendor_id(&self) -> u16 {
+        self.device.vendor_id()
+    }
+
+    /// Get the product ID.
+    pub fn product_id(&self) -> u16 {
+        self.device.product_id()
+    }
+
+    /// Write raw bytes to the printer.
+    pub async fn write(&self, bytes: &[u8]) -> Result<(), WebUsbError> {
+        let data = Uint8Array::from(bytes);
+        let result = JsFuture::from(self.device.transfer_out(self.write_endpoint, &data)).await?;
+        check_status(&result)
+    }
+
+    /// Encode and write a command.
+    pub async fn send(&self, cmd: impl Command) -> Result<(), WebUsbError> {
+        self.write(&cmd.encode()).await
+    }
+
+    /// Read up to `max_len` bytes, such as a status response.
+    pub async fn read(&self, max_len: u32) -> Result<Vec<u8>, WebUsbError> {
+        let endpoint = self.read_endpoint.ok_or(WebUsbError::NoReadEndpoint)?;
+        let result = JsFuture::from(self.device.transfer_in(endpoint, max_len)).await?;
+        check_status(&result)?;
+        let data = get(&result, "data")?;
+        if data.is_undefined() || data.is_null() {
+            return Ok(Vec::new());
+        }
+        let buffer = get(&data, "buffer")?;
+        let offset = get(&data, "byteOffset")?.as_f64().unwrap_or(0.0) as u32;
+        let length = get(&data, "byteLength")?.as_f64().unwrap_or(0.0) as u32;
+        Ok(Uint8Array::new_with_byte_offset_and_length(&buffer, offset, length).to_vec())
+    }
+
+    /// Release the interface and close the device.
+    pub async fn close(self) -> Result<(), WebUsbError> {
+        JsFuture::from(self.device.release_interface(self.interface_number)).await?;
+        JsFuture::from(self.device.close()).await?;
+        Ok(())
+    }
+}
+
+/// `navigator.usb`, if the browser has it.
+fn usb() -> Result<Usb, WebUsbError> {
+    let navigator = get(&js_sys::global(), "navigator")?;
+    let usb = get(&navigator, "usb")?;
+    if usb.is_undefined() {
+        return Err(WebUsbError::Unsupported);
+    }
+    Ok(usb.unchecked_into())
+}
+
+fn get(target: &JsValue, key: &str) -> Result<JsValue, WebUsbError> {
+    if target.is_undefined() || target.is_null() {
+        return Err(WebUsbError::Unsupported);
+    }
+    Ok(Reflect::get(target, &key.into())?)
+}
+
+/// Fail unless a transfer result's status is `ok`.
+fn check_status(result: &JsValue) -> Result<(), WebUsbError> {
+    match get(result, "status")?.as_string() {
+        Some(status) if status == "ok" => Ok(()),
+        Some(status) => Err(WebUsbError::Transfer(status)),
+        None => Err(WebUsbError::Transfer("unknown".into())),
+    }
+}
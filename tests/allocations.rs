@@ -0,0 +1,97 @@
+//! Allocation regression tests.
+//!
+//! Encoding paths meant for hot loops must not allocate once the output
+//! buffer has room. A counting allocator catches changes that quietly add
+//! a copy; the benchmarks in `benches/` measure the rest.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use bixolon::command::barcode::{BarcodeSystem, PrintBarcodeRef};
+use bixolon::command::basic::LineFeed;
+use bixolon::command::image::{PrintRasterImage, PrintRasterImageRef};
+use bixolon::command::printer_control::Initialize;
+use bixolon::command::symbol::PrintQrCodeRef;
+use bixolon::command::{Command, Dialect, StaticCommand};
+
+/// Counts allocations made by the current thread, so tests running in
+/// parallel don't see each other's.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocations made while running `f`.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn static_commands_encode_without_allocating() {
+    let mut output = Vec::with_capacity(64);
+    let count = allocations(|| {
+        Initialize.encode_into(&mut output);
+        for _ in 0..8 {
+            output.extend_from_slice(LineFeed::BYTES);
+            LineFeed.encode_into(&mut output);
+        }
+    });
+    assert_eq!(count, 0);
+    assert_eq!(output.len(), 2 + 16);
+}
+
+#[test]
+fn borrowed_commands_encode_without_allocating() {
+    let data = vec![0xAA; 64 * 32];
+    let barcode = PrintBarcodeRef::new(BarcodeSystem::Code39, b"1042").unwrap();
+    let qr = PrintQrCodeRef::new(b"https://example.com/r/1042").unwrap();
+    let image = PrintRasterImageRef::try_new(64, 32, &data).unwrap();
+
+    let mut output = Vec::with_capacity(5 * data.len());
+    let count = allocations(|| {
+        barcode.encode_into(&mut output);
+        image.encode_into(&mut output);
+        for dialect in [Dialect::Bixolon, Dialect::Epson, Dialect::Star] {
+            image.encode_into_for(dialect, &mut output);
+        }
+    });
+    assert_eq!(count, 0);
+
+    // The QR settings commands still allocate; the data must not be copied
+    let mut output = Vec::with_capacity(256);
+    let count = allocations(|| qr.encode_into(&mut output));
+    assert!(count <= 3, "{count} allocations");
+}
+
+#[test]
+fn raster_images_stream_without_copying() {
+    let image = PrintRasterImage::new(64, 512, vec![0x55; 64 * 512]);
+    let mut output = Vec::with_capacity(3 * image.data.len());
+    let count = allocations(|| {
+        image.write_bands_to(image.band_height(), Dialect::Epson, &mut output).unwrap();
+        image.write_to(&mut output).unwrap();
+    });
+    assert_eq!(count, 0);
+}
@@ -67,6 +67,30 @@ fn test_horizontal_tab() {
     assert_eq!(output, vec![0x09]);
 }
 
+#[test]
+fn test_encode_into_for_uses_encode_for() {
+    use bixolon::command::{Command, Dialect};
+
+    struct DialectAware;
+    impl Command for DialectAware {
+        fn encode(&self) -> Vec<u8> {
+            vec![0x01]
+        }
+
+        fn encode_for(&self, dialect: Dialect) -> Vec<u8> {
+            match dialect {
+                Dialect::Star => vec![0x02],
+                _ => self.encode(),
+            }
+        }
+    }
+
+    let mut output = Vec::new();
+    DialectAware.encode_into_for(Dialect::Star, &mut output);
+    DialectAware.encode_into_for(Dialect::Bixolon, &mut output);
+    assert_eq!(output, vec![0x02, 0x01]);
+}
+
 // ============================================================================
 // Character Formatting
 // ============================================================================
@@ -269,7 +293,7 @@ fn test_qr_code_hello() {
 
 #[test]
 fn test_page_builder_empty() {
-    let page = PageBuilder::new().build();
+    let page = PageBuilder::new().build().unwrap();
 
     // Exact: ESC L, FF
     let expected = vec![0x1B, b'L', 0x0C];
@@ -278,7 +302,7 @@ fn test_page_builder_empty() {
 
 #[test]
 fn test_page_builder_with_text() {
-    let page = PageBuilder::new().text("Test").build();
+    let page = PageBuilder::new().text("Test").build().unwrap();
 
     // Exact: ESC L, "Test", FF
     let expected = vec![0x1B, b'L', b'T', b'e', b's', b't', 0x0C];
@@ -294,7 +318,8 @@ fn test_page_builder_with_area() {
             width: 512,
             height: 400,
         })
-        .build();
+        .build()
+        .unwrap();
 
     // Exact: ESC L, ESC W (area: x=0, y=0, w=512, h=400), FF
     // Area params are 8 bytes: xL xH yL yH wL wH hL hH
@@ -313,7 +338,7 @@ fn test_page_builder_with_area() {
 
 #[test]
 fn test_page_builder_with_direction() {
-    let page = PageBuilder::new().direction(PrintDirection::BottomToTop).build();
+    let page = PageBuilder::new().direction(PrintDirection::BottomToTop).build().unwrap();
 
     // Exact: ESC L, ESC T 1, FF
     let expected = vec![0x1B, b'L', 0x1B, b'T', 0x01, 0x0C];
@@ -327,7 +352,8 @@ fn test_page_builder_complete() {
         .direction(PrintDirection::LeftToRight)
         .vertical_position(100)
         .text_line("Header".bold())
-        .build();
+        .build()
+        .unwrap();
 
     // Exact expected output
     // PrintArea::default_80mm() = { x: 0, y: 0, width: 512, height: 1662 }
@@ -350,6 +376,15 @@ fn test_page_builder_complete() {
     assert_eq!(page, expected);
 }
 
+#[test]
+fn test_page_builder_copies() {
+    let page = PageBuilder::new().text("Test").build_copies(2).unwrap();
+
+    // Exact: ESC L, "Test", ESC FF (print, keep buffer), FF (print and clear)
+    let expected = vec![0x1B, b'L', b'T', b'e', b's', b't', 0x1B, 0x0C, 0x0C];
+    assert_eq!(page, expected);
+}
+
 // ============================================================================
 // Full Receipt Test
 // ============================================================================